@@ -3,8 +3,52 @@ pragma solidity ^0.8.20;
 
 import {ISP1Verifier} from "@sp1-contracts/ISP1Verifier.sol";
 
+/// @notice Public values committed by the zkPDF program, in the order it ABI-encodes them.
 struct PublicValuesStruct {
-    bool result;
+    /// @dev Layout version of this struct, `PUBLIC_VALUES_VERSION` in zkpdf_lib. It is the first
+    /// word after the struct's offset, so it can be checked before decoding the rest.
+    uint16 version;
+    /// @dev `ClaimType` of the proven claim: 1 substring, 2 regex, 3 absence, 4 date, 5 template,
+    /// 6 GST registration, 7 PAN card, 8 balance threshold, 9 XML, 10 Aadhaar; 0 for a failure.
+    uint8 claimType;
+    bool substringMatches;
+    bytes32 messageDigestHash;
+    bytes32 signerKeyHash;
+    bytes32 substringHash;
+    bytes32 nullifier;
+    bool negated;
+    bytes32 dateClaim;
+    uint32 pageCount;
+    /// @dev Merkle root of the keccak hashes of the pages' text, for `verifyPageInclusion`.
+    bytes32 pagesRoot;
+    /// @dev keccak256 of the signer certificate's subject DN, and its validity window in Unix
+    /// seconds; zero when the certificate is unknown. The window is also zero when the
+    /// nullifier is bound to a user secret.
+    bytes32 signerSubjectHash;
+    uint64 notBefore;
+    uint64 notAfter;
+    /// @dev Unix time a TSA attests the signature existed at; zero without a valid timestamp.
+    uint64 timestampedAt;
+    /// @dev Commitment to the template id and field name of a template claim; zero otherwise.
+    bytes32 templateClaim;
+    /// @dev keccak256 of the name of whoever the document is about, such as the legal name of
+    /// a GST registration; zero for claims that identify no one.
+    bytes32 holderNameHash;
+    /// @dev How `nullifier` is scoped: 0 per claim, 1 per document, 2 per signer and document.
+    uint8 nullifierScope;
+    /// @dev The application's domain separator `nullifier` is bound to; zero if none was given.
+    bytes32 externalNullifier;
+    /// @dev Commitment to the prover's secret for this external nullifier, when the nullifier is
+    /// bound to one; zero otherwise. The message digest, pages root and timestamp are then zero.
+    bytes32 secretCommitment;
+    /// @dev Hash function of the claim hashes and nullifier: 0 keccak256, 1 Poseidon over BN254.
+    uint8 hashSuite;
+    /// @dev keccak256 of the signer certificate's DER; pins an issued certificate rather than a
+    /// key, which several certificates can share. Zero if the certificate is unknown.
+    bytes32 signerCertHash;
+    /// @dev The claimed substring or captured value, for claims that asked to reveal it;
+    /// empty otherwise.
+    bytes revealed;
 }
 
 /// @title PdfVerifier
@@ -16,27 +60,96 @@ contract PdfVerifier {
     /// @notice Verification key for the zkPDF program.
     bytes32 public programVKey;
 
+    /// @notice Layout version of `PublicValuesStruct` this contract decodes.
+    uint16 public constant PUBLIC_VALUES_VERSION = 1;
+
+    /// @notice `claimType` of a GST registration proof.
+    uint8 public constant GST_CLAIM_TYPE = 6;
+
+    /// @notice `templateClaim` of a GST registration proof: `template_claim_hash` of the
+    /// `gst-certificate` template's `gst_number` field.
+    bytes32 public constant GST_CLAIM = keccak256(
+        abi.encodePacked("zkpdf-template-claim-v0", "gst-certificate", bytes1(0), "gst_number")
+    );
+
     constructor(address _verifier, bytes32 _programVKey) {
         verifier = _verifier;
         programVKey = _programVKey;
     }
 
-    /// @notice Verifies a zkPDF proof and returns the attested result flag.
+    /// @notice Verifies a zkPDF proof and returns its decoded public values, including any
+    /// revealed value (e.g. a GSTIN) the contract can read directly. Reverts for public values
+    /// of another layout version, which would otherwise be misread.
     /// @param _publicValues ABI-encoded public values emitted by the zkPDF program.
     /// @param _proofBytes Encoded SP1 proof bytes.
     function verifyPdfProof(
         bytes calldata _publicValues,
         bytes calldata _proofBytes
-    ) public view returns (bool) {
+    ) public view returns (PublicValuesStruct memory) {
         ISP1Verifier(verifier).verifyProof(
             programVKey,
             _publicValues,
             _proofBytes
         );
+        require(
+            publicValuesVersion(_publicValues) == PUBLIC_VALUES_VERSION,
+            "unsupported public values version"
+        );
         PublicValuesStruct memory publicValues = abi.decode(
             _publicValues,
             (PublicValuesStruct)
         );
-        return publicValues.result;
+        return publicValues;
+    }
+
+    /// @notice Verifies a proof that a signed GST registration certificate has a valid GSTIN,
+    /// for checking a business's registration on-chain. A proof bound to a user secret blinds
+    /// its hashes, `templateClaim` included, so it is not accepted here.
+    /// @return gstinHash keccak256 of the GSTIN.
+    /// @return legalNameHash keccak256 of the registered legal name.
+    /// @return notAfter End of the signer certificate's validity window, in Unix seconds.
+    function verifyGstProof(
+        bytes calldata _publicValues,
+        bytes calldata _proofBytes
+    ) public view returns (bytes32 gstinHash, bytes32 legalNameHash, uint64 notAfter) {
+        PublicValuesStruct memory publicValues = verifyPdfProof(_publicValues, _proofBytes);
+        require(
+            publicValues.substringMatches && publicValues.claimType == GST_CLAIM_TYPE
+                && publicValues.templateClaim == GST_CLAIM,
+            "not a GST registration proof"
+        );
+        return (publicValues.substringHash, publicValues.holderNameHash, publicValues.notAfter);
+    }
+
+    /// @notice Reads the layout version of ABI-encoded public values without decoding them,
+    /// e.g. to route proofs of older layouts to a contract that decodes those.
+    /// @return The `version` field, or 0 if the values are too short to have one.
+    function publicValuesVersion(bytes calldata _publicValues) public pure returns (uint256) {
+        if (_publicValues.length < 64) {
+            return 0;
+        }
+        return uint256(bytes32(_publicValues[32:64]));
+    }
+
+    /// @notice Checks that `pageHash` is the hash of page `index` of the document committed
+    /// to by `pagesRoot`, given the sibling hashes from the leaf up.
+    /// @dev Mirrors `merkle::verify` in pdf_core: nodes are keccak256(left, right) and the
+    /// index bits tell on which side each sibling is.
+    function verifyPageInclusion(
+        bytes32 pagesRoot,
+        bytes32 pageHash,
+        uint256 index,
+        bytes32[] calldata siblings
+    ) public pure returns (bool) {
+        if (index >> siblings.length != 0) {
+            return false;
+        }
+        bytes32 hash = pageHash;
+        for (uint256 level = 0; level < siblings.length; level++) {
+            hash = (index >> level) & 1 == 0
+                ? keccak256(abi.encodePacked(hash, siblings[level]))
+                : keccak256(abi.encodePacked(siblings[level], hash));
+        }
+        return hash == pagesRoot;
     }
 }
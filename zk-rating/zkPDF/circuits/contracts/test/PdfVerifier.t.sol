@@ -3,7 +3,7 @@ pragma solidity ^0.8.20;
 
 import {Test} from "forge-std/Test.sol";
 import {stdJson} from "forge-std/StdJson.sol";
-import {PdfVerifier} from "../src/PdfVerifier.sol";
+import {PdfVerifier, PublicValuesStruct} from "../src/PdfVerifier.sol";
 import {SP1VerifierGateway} from "@sp1-contracts/SP1VerifierGateway.sol";
 
 struct SP1ProofFixtureJson {
@@ -57,6 +57,16 @@ contract PdfVerifierGroth16Test is Test {
 
         pdfVerifier.verifyPdfProof(fixture.publicValues, fakeProof);
     }
+
+    function testRevert_UnsupportedPublicValuesVersion() public {
+        vm.mockCall(verifier, abi.encodeWithSelector(SP1VerifierGateway.verifyProof.selector), abi.encode(true));
+
+        // The struct's offset, then the version of a layout this contract does not decode
+        bytes memory publicValues = abi.encode(uint256(32), uint256(pdfVerifier.PUBLIC_VALUES_VERSION()) + 1);
+
+        vm.expectRevert("unsupported public values version");
+        pdfVerifier.verifyPdfProof(publicValues, "");
+    }
 }
 
 contract PdfVerifierPlonkTest is Test {
@@ -0,0 +1,74 @@
+//! Claims about UIDAI offline e-KYC downloads, for holders who have Aadhaar XML rather than a
+//! signed PDF. The share-code protected ZIP is decrypted and its signed XML verified inside
+//! the circuit, and the claim is committed like a template claim, under the
+//! `aadhaar-offline-ekyc` id.
+
+use pdf_core::{
+    aadhaar::{selector, verify_offline_kyc},
+    nullifier::keccak256,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::types::PDFCircuitOutput;
+
+/// The template id an offline e-KYC claim's `template_claim` is computed with.
+pub const AADHAAR_TEMPLATE_ID: &str = "aadhaar-offline-ekyc";
+
+pub const AADHAAR_NULLIFIER_DOMAIN: &[u8] = b"zkpdf-aadhaar-v0";
+
+/// A claim that the field `field` of an offline e-KYC download, one of
+/// `pdf_core::aadhaar::FIELDS` such as `pincode` or `mobile`, has the value `value`. With
+/// `reveal` the value is disclosed in `revealed`, besides being hashed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AadhaarCircuitInput {
+    /// The ZIP as UIDAI serves it, or the XML extracted from it.
+    pub data: Vec<u8>,
+    /// Decrypts the ZIP, and salts the mobile and email hashes.
+    pub share_code: String,
+    /// UIDAI's signing certificate, DER. Its key is committed in `signer_key_hash`.
+    pub uidai_certificate: Vec<u8>,
+    pub field: String,
+    pub value: String,
+    pub reveal: bool,
+}
+
+/// Verifies an offline e-KYC claim. `substring_matches` is whether the field has the value,
+/// by its hash for `mobile` and `email`; `substring_hash` is the hash of the value,
+/// `holder_name_hash` of the holder's name, and `template_claim` is
+/// `template_claim_hash(AADHAAR_TEMPLATE_ID, field)`. The nullifier comes from
+/// `aadhaar_nullifier`. A field that is not one of `FIELDS`, or is missing, is
+/// `Error::FieldNotFound`.
+pub fn verify_aadhaar_claim(
+    input: AadhaarCircuitInput,
+) -> Result<PDFCircuitOutput, pdf_core::Error> {
+    // Step 1: decrypt and verify the download from verify_offline_kyc
+    let selector = selector(&input.field)
+        .ok_or_else(|| pdf_core::Error::FieldNotFound(input.field.clone()))?;
+    let kyc = verify_offline_kyc(&input.data, &input.share_code, &input.uidai_certificate)?;
+    if kyc.field(&input.field).is_none() {
+        return Err(pdf_core::Error::FieldNotFound(selector.to_string()));
+    }
+
+    // Step 2: check the claimed value and construct output
+    let output =
+        PDFCircuitOutput::from_aadhaar(&input.field, &input.value, &input.share_code, &kyc);
+    Ok(if input.reveal {
+        output.reveal(&input.value)
+    } else {
+        output
+    })
+}
+
+/// Nullifier of an offline e-KYC claim: keccak256 over the Aadhaar domain, the signer hash and
+/// the hash of the holder's photograph. Every download is signed afresh, with a new reference
+/// id, so unlike other nullifiers it leaves out the signed digest: the photograph is the same
+/// in each, and one holder gives one nullifier however often they download.
+pub fn aadhaar_nullifier(signer_key_hash: &[u8], photo_hash: &[u8]) -> [u8; 32] {
+    let mut preimage = Vec::with_capacity(AADHAAR_NULLIFIER_DOMAIN.len() + 32 * 2);
+
+    preimage.extend_from_slice(AADHAAR_NULLIFIER_DOMAIN);
+    preimage.extend_from_slice(signer_key_hash);
+    preimage.extend_from_slice(photo_hash);
+
+    keccak256(&preimage)
+}
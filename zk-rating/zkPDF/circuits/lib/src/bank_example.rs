@@ -0,0 +1,91 @@
+use pdf_core::{
+    claims::parse_number, extract_pages, nullifier::keccak256, table::column,
+    verify_signature_with_policy, ExtractOptions, Page, PdfSignatureResult, Template,
+    VerificationPolicy,
+};
+
+use crate::templates::{BankStatement, DocumentTemplate};
+
+pub const BALANCE_CLAIM_DOMAIN: &[u8] = b"zkpdf-balance-claim-v0";
+
+/// Headers the balance column of a statement's transaction table goes by, most specific first.
+const BALANCE_HEADERS: [&str; 2] = ["Closing Balance", "Balance"];
+
+/// The closing balance read from a signed bank statement.
+pub struct BalanceVerification {
+    /// In minor units (paise), negative for an overdrawn account; `None` if no closing balance
+    /// could be read.
+    pub balance: Option<i64>,
+    pub signature: PdfSignatureResult,
+}
+
+/// Verifies the statement's signature, checks the signer against the `bank-statement`
+/// template's pins and reads the closing balance with `closing_balance`.
+pub fn verify_closing_balance(pdf_bytes: Vec<u8>) -> Result<BalanceVerification, pdf_core::Error> {
+    // Step 1: verify signature and signer
+    let signature = verify_signature_with_policy(&pdf_bytes, &VerificationPolicy::default())?;
+    BankStatement.check_signer(&signature)?;
+
+    // Step 2: read the balance from the text and the word boxes
+    let pages = extract_pages(&pdf_bytes, &ExtractOptions::default())?;
+    Ok(BalanceVerification {
+        balance: closing_balance(&pages),
+        signature,
+    })
+}
+
+/// The closing balance of a statement, in paise: the amount after a `Closing Balance` label,
+/// as the `bank-statement` template's `closing_balance` field reads it, or else the last
+/// amount in the balance column of the transaction table, which is the balance after the
+/// last transaction.
+pub fn closing_balance(pages: &[Page]) -> Option<i64> {
+    let texts: Vec<String> = pages.iter().map(|page| page.text.clone()).collect();
+    let labelled = BankStatement.field("closing_balance").and_then(|field| {
+        Template {
+            fields: vec![field.pattern],
+        }
+        .match_pages(&texts)
+        .ok()?
+        .pop()
+    });
+    if let Some(field) = labelled {
+        return parse_amount(&field.value);
+    }
+
+    BALANCE_HEADERS.iter().find_map(|header| {
+        column(pages, header)
+            .iter()
+            .rev()
+            .find_map(|cell| parse_amount(&cell.text))
+    })
+}
+
+/// An amount such as `1,20,000.50`, `INR 500.00 Cr` or `75.25 Dr`, in paise. A `Dr` suffix
+/// makes it negative.
+pub fn parse_amount(text: &str) -> Option<i64> {
+    let text = text.trim().to_ascii_uppercase();
+    let (amount, sign) = match text.strip_suffix("DR") {
+        Some(amount) => (amount, -1),
+        None => (text.strip_suffix("CR").unwrap_or(&text), 1),
+    };
+    let amount = amount.trim();
+    let amount = amount
+        .strip_prefix("INR")
+        .or_else(|| amount.strip_prefix("RS."))
+        .unwrap_or(amount);
+    let number = parse_number(amount).filter(|number| number.abs() < 1e15)?;
+    Some(sign * (number * 100.0).round() as i64)
+}
+
+/// Commitment to a balance claim: keccak256 over the domain, the big-endian threshold in paise
+/// and the result. A verifier recomputes it from the threshold it asked for and a result of
+/// `true`.
+pub fn balance_claim_hash(threshold: u64, holds: bool) -> [u8; 32] {
+    let mut preimage = Vec::with_capacity(BALANCE_CLAIM_DOMAIN.len() + 8 + 1);
+
+    preimage.extend_from_slice(BALANCE_CLAIM_DOMAIN);
+    preimage.extend_from_slice(&threshold.to_be_bytes());
+    preimage.push(u8::from(holds));
+
+    keccak256(&preimage)
+}
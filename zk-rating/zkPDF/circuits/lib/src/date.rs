@@ -0,0 +1,120 @@
+//! Date claims, such as "issued after 2023-01-01" or "not expired": where the date is read
+//! from, and the hashes a proof of a comparison commits to. Dates are parsed by
+//! `pdf_core::date`.
+
+use pdf_core::{
+    document_info, nullifier::keccak256, verify_regex, verify_signature_with_policy,
+    PdfSignatureResult, VerificationPolicy,
+};
+use serde::{Deserialize, Serialize};
+
+pub use pdf_core::date::{unix_timestamp, Date};
+
+pub const DATE_CLAIM_DOMAIN: &[u8] = b"zkpdf-date-claim-v0";
+
+/// How the claimed date relates to the threshold. Both are strict.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DateComparison {
+    /// Later than the threshold: issued after it, or, for an expiry date and today's date,
+    /// not expired.
+    After,
+    /// Earlier than the threshold.
+    Before,
+}
+
+impl DateComparison {
+    pub fn holds(self, date: Date, threshold: Date) -> bool {
+        match self {
+            DateComparison::After => date > threshold,
+            DateComparison::Before => date < threshold,
+        }
+    }
+
+    fn code(self) -> u8 {
+        match self {
+            DateComparison::After => 0,
+            DateComparison::Before => 1,
+        }
+    }
+}
+
+/// Where the claimed date is read from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum DateSource {
+    /// `/CreationDate` of the document information dictionary.
+    CreationDate,
+    /// The value `pattern` captures on `page_number`, as `verify_regex` captures it.
+    Field { page_number: u8, pattern: String },
+}
+
+/// The date text read from a signed document, and the date it parses to.
+pub struct DateVerification {
+    /// Empty if the source has no date.
+    pub text: String,
+    /// `None` if the text is in neither known format.
+    pub date: Option<Date>,
+    pub signature: PdfSignatureResult,
+}
+
+/// Verifies the PDF's signature and reads the date `source` points to.
+pub fn verify_date(
+    pdf_bytes: Vec<u8>,
+    source: &DateSource,
+) -> Result<DateVerification, pdf_core::Error> {
+    let (text, signature) = match source {
+        DateSource::CreationDate => {
+            let signature =
+                verify_signature_with_policy(&pdf_bytes, &VerificationPolicy::default())?;
+            let info = document_info(&pdf_bytes).map_err(pdf_core::Error::Extraction)?;
+            let text = info.metadata.get("CreationDate").cloned();
+            (text.unwrap_or_default(), signature)
+        }
+        DateSource::Field {
+            page_number,
+            pattern,
+        } => {
+            let result = verify_regex(pdf_bytes, *page_number, pattern)?;
+            let text = result.value().map(|capture| capture.text.clone());
+            (text.unwrap_or_default(), result.signature)
+        }
+    };
+
+    Ok(DateVerification {
+        date: Date::parse(&text),
+        text,
+        signature,
+    })
+}
+
+/// Commitment to a date claim: keccak256 over the domain, the comparison (0 for after, 1 for
+/// before), the big-endian YYYYMMDD threshold and the result. A verifier recomputes it from
+/// the threshold it expects and a result of `true`.
+pub fn date_claim_hash(comparison: DateComparison, threshold: Date, holds: bool) -> [u8; 32] {
+    let mut preimage = Vec::with_capacity(DATE_CLAIM_DOMAIN.len() + 1 + 4 + 1);
+
+    preimage.extend_from_slice(DATE_CLAIM_DOMAIN);
+    preimage.push(comparison.code());
+    preimage.extend_from_slice(&threshold.to_yyyymmdd().to_be_bytes());
+    preimage.push(u8::from(holds));
+
+    keccak256(&preimage)
+}
+
+/// Nullifier of a date claim: keccak256 over the date domain, the document, signer and date
+/// text hashes and the date claim hash, so it never equals the nullifier of a substring claim.
+pub fn date_nullifier(
+    message_digest_hash: &[u8],
+    signer_key_hash: &[u8],
+    date_hash: &[u8],
+    date_claim_hash: &[u8],
+) -> [u8; 32] {
+    let mut preimage = Vec::with_capacity(DATE_CLAIM_DOMAIN.len() + 32 * 4);
+
+    preimage.extend_from_slice(DATE_CLAIM_DOMAIN);
+    preimage.extend_from_slice(message_digest_hash);
+    preimage.extend_from_slice(signer_key_hash);
+    preimage.extend_from_slice(date_hash);
+    preimage.extend_from_slice(date_claim_hash);
+
+    keccak256(&preimage)
+}
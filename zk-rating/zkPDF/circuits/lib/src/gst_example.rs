@@ -1,38 +1,171 @@
-use pdf_core::PdfSignatureResult;
+use core::fmt;
+
+use pdf_core::{
+    nullifier::keccak256, verify_and_extract_strict, Date, FieldPattern, PdfSignatureResult,
+    Template,
+};
+
+use crate::templates::{DocumentTemplate, GstCertificate};
+
+/// Trade name, which REG-06 prints under "Trade Name, if any".
+const TRADE_NAME: &str =
+    r"Trade Name,? (?:if any)?\s*([A-Za-z0-9\s&.,'-]+?)\s*(?:\n|Additional|Constitution|$)";
+/// Date of liability, from which the registration is effective.
+const REGISTRATION_DATE: &str =
+    r"Date of (?:Liability|Registration)\s*:?\s*([0-9]{2}/[0-9]{2}/[0-9]{4})";
+
+pub const GST_NULLIFIER_DOMAIN: &[u8] = b"zkpdf-gst-v0";
+
+/// GST state codes: 01 to 38, 97 (other territory) and 99 (centre jurisdiction).
+const STATE_CODES: [core::ops::RangeInclusive<u8>; 3] = [1..=38, 97..=97, 99..=99];
 
 pub struct GSTCertificate {
     pub gst_number: String,
     pub legal_name: String,
+    /// `None` if the certificate has no trade name, or it could not be read.
+    pub trade_name: Option<String>,
+    /// `None` if no date of liability in `dd/mm/yyyy` form could be read.
+    pub registration_date: Option<Date>,
+    /// The state of the principal place of business, the first two digits of the GSTIN.
+    pub state_code: u8,
     pub signature: PdfSignatureResult,
 }
-/// GST Certificate verification function that extracts legal name and GST number
-pub fn verify_gst_certificate(pdf_bytes: Vec<u8>) -> GSTCertificate {
-    let verified_content = pdf_core::verify_and_extract(pdf_bytes).unwrap();
 
-    let full_text = verified_content.pages.join(" ");
+/// Why a document is not a verified GST certificate.
+#[derive(Debug)]
+pub enum GstError {
+    /// The signature does not verify, the text could not be extracted, or the GST number or
+    /// legal name is missing.
+    Verification(pdf_core::Error),
+    /// The GSTIN's fifteenth character is not the check character of the first fourteen.
+    InvalidChecksum(String),
+    /// The GSTIN does not start with a state code.
+    InvalidStateCode(String),
+}
 
-    let gst_pattern =
-        regex::Regex::new(r"([0-9]{2}[A-Z]{5}[0-9]{4}[A-Z]{1}[1-9A-Z]{1}[Z]{1}[0-9A-Z]{1})")
-            .unwrap();
+impl fmt::Display for GstError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GstError::Verification(e) => write!(f, "{}", e),
+            GstError::InvalidChecksum(gstin) => {
+                write!(f, "GSTIN `{}` has a bad check character", gstin)
+            }
+            GstError::InvalidStateCode(gstin) => {
+                write!(f, "GSTIN `{}` has no valid state code", gstin)
+            }
+        }
+    }
+}
 
-    let gst_number = gst_pattern
-        .captures(&full_text)
-        .and_then(|cap| cap.get(1))
-        .map(|m| m.as_str().to_string())
-        .unwrap();
+impl std::error::Error for GstError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            GstError::Verification(e) => Some(e),
+            _ => None,
+        }
+    }
+}
 
-    let legal_name_pattern =
-        regex::Regex::new(r"Legal Name\s*([A-Za-z\s&.,]+?)(?:\n|Trade Name|Additional|$)").unwrap();
+impl From<pdf_core::Error> for GstError {
+    fn from(e: pdf_core::Error) -> Self {
+        GstError::Verification(e)
+    }
+}
 
-    let legal_name = legal_name_pattern
-        .captures(&full_text)
-        .and_then(|cap| cap.get(1))
-        .map(|m| m.as_str().trim().to_string())
-        .unwrap();
+/// GST Certificate verification function that extracts legal name and GST number, and the
+/// trade name and date of liability when present. The GSTIN's state code and check character
+/// are checked.
+pub fn verify_gst_certificate(pdf_bytes: Vec<u8>) -> Result<GSTCertificate, GstError> {
+    // Step 1: verify signature and signer
+    let content = verify_and_extract_strict(pdf_bytes)?;
+    GstCertificate.check_signer(&content.signature)?;
 
-    GSTCertificate {
+    // Step 2: locate the required fields, then the optional ones
+    let mut fields = GstCertificate
+        .template()
+        .match_pages(&content.pages)?
+        .into_iter()
+        .map(|field| field.value);
+    let (Some(gst_number), Some(legal_name)) = (fields.next(), fields.next()) else {
+        return Err(pdf_core::Error::FieldNotFound("gst_number".to_string()).into());
+    };
+    let optional = |name: &str, pattern: &str| {
+        Template {
+            fields: vec![FieldPattern {
+                name: name.to_string(),
+                pattern: pattern.to_string(),
+                group: 1,
+            }],
+        }
+        .match_pages(&content.pages)
+        .ok()
+        .and_then(|mut found| found.pop())
+        .map(|field| field.value)
+    };
+    let trade_name = optional("trade_name", TRADE_NAME);
+    let registration_date =
+        optional("registration_date", REGISTRATION_DATE).and_then(|date| Date::parse_dmy(&date));
+
+    // Step 3: check the GSTIN itself
+    let state_code = gstin_state_code(&gst_number)
+        .ok_or_else(|| GstError::InvalidStateCode(gst_number.clone()))?;
+    if gstin_check_character(&gst_number) != gst_number.chars().nth(14) {
+        return Err(GstError::InvalidChecksum(gst_number));
+    }
+
+    Ok(GSTCertificate {
         gst_number,
         legal_name,
-        signature: verified_content.signature,
+        trade_name,
+        registration_date,
+        state_code,
+        signature: content.signature,
+    })
+}
+
+/// The state code a GSTIN starts with, if it is one.
+pub fn gstin_state_code(gstin: &str) -> Option<u8> {
+    let code: u8 = gstin.get(..2)?.parse().ok()?;
+    STATE_CODES
+        .iter()
+        .any(|codes| codes.contains(&code))
+        .then_some(code)
+}
+
+/// The check character of a GSTIN: each of its first fourteen characters, read as a base-36
+/// digit, is weighted 1 and 2 alternately; the base-36 digits of every product are summed, and
+/// the check character is what brings the sum to a multiple of 36. `None` if the GSTIN is
+/// shorter than fourteen characters or has one outside `0-9A-Z`.
+pub fn gstin_check_character(gstin: &str) -> Option<char> {
+    let mut sum = 0;
+    let mut chars = gstin.chars();
+    for position in 0..14u32 {
+        let c = chars
+            .next()
+            .filter(|c| c.is_ascii_digit() || c.is_ascii_uppercase())?;
+        let value = c.to_digit(36)?;
+        let product = value * if position.is_multiple_of(2) { 1 } else { 2 };
+        sum += product / 36 + product % 36;
     }
+    char::from_digit((36 - sum % 36) % 36, 36).map(|c| c.to_ascii_uppercase())
+}
+
+/// Nullifier of a GST registration claim: keccak256 over the GST domain and the document,
+/// signer, GSTIN and legal name hashes, so one certificate gives one nullifier however often
+/// it is proven.
+pub fn gst_nullifier(
+    message_digest_hash: &[u8],
+    signer_key_hash: &[u8],
+    gstin_hash: &[u8],
+    legal_name_hash: &[u8],
+) -> [u8; 32] {
+    let mut preimage = Vec::with_capacity(GST_NULLIFIER_DOMAIN.len() + 32 * 4);
+
+    preimage.extend_from_slice(GST_NULLIFIER_DOMAIN);
+    preimage.extend_from_slice(message_digest_hash);
+    preimage.extend_from_slice(signer_key_hash);
+    preimage.extend_from_slice(gstin_hash);
+    preimage.extend_from_slice(legal_name_hash);
+
+    keccak256(&preimage)
 }
@@ -1,41 +1,197 @@
 // Public modules
+pub mod aadhaar; // Aadhaar offline e-KYC claims
+pub mod bank_example; // Bank statement balance claims
+pub mod date; // Date parsing and comparison claims
 pub mod gst_example; // GST certificate verification logic
 pub mod nullifier; // Nullifier utilities for ZK circuits
+pub mod pan_example; // PAN card verification logic
+pub mod templates; // Document template registry
 pub mod types; // Shared data structures
+pub mod xml; // Signed XML attribute claims
 
 // Re-exports for main API surface
+pub use aadhaar::verify_aadhaar_claim; // Aadhaar offline e-KYC check
 pub use extractor::extract_text; // PDF text extraction
-pub use gst_example::verify_gst_certificate; // GST certificate check
+pub use gst_example::{verify_gst_certificate, GstError}; // GST certificate check
+pub use pan_example::{verify_pan_card, PanError}; // PAN card check
+pub use pdf_core::nullifier::{HashSuite, NullifierScope}; // Claim hash suites and nullifier scopes
 pub use pdf_core::{
-    verify_and_extract, // Verify + extract in one call
-    verify_text,        // Verify substring at offset
+    find_substring,            // Locate a substring's page offsets
+    page_commitments,          // Page count + Merkle root of page hashes
+    verify_absent,             // Verify + check a substring is on no page
+    verify_and_extract,        // Verify + extract in one call, invalid signatures included
+    verify_and_extract_strict, // Verify + extract, valid signatures only
+    verify_regex,              // Verify + match a pattern on one page
+    verify_text,               // Verify substring at offset
+    Capture,
+    PdfAbsenceResult,
+    PdfRegexResult,
     PdfSignatureResult,
     PdfVerificationResult,
     PdfVerifiedContent,
 };
 pub use signature_validator::verify_pdf_signature; // Signature-only verification
-pub use types::PublicValuesStruct; // Public circuit values
+pub use templates::DocumentTemplate; // Registered document kinds
+pub use types::{ClaimType, PublicValuesStruct, PUBLIC_VALUES_VERSION}; // Public circuit values
+pub use xml::verify_xml_claim; // Signed XML attribute check
+pub use zkpdf_errors::{ErrorCode, ZkpdfError}; // Stable error codes
 
 // Internal circuit types (not re-exported)
 use crate::types::{PDFCircuitInput, PDFCircuitOutput};
 
-/// Generic PDF verification function for basic text extraction and signature verification
-pub fn verify_pdf_claim(input: PDFCircuitInput) -> Result<PDFCircuitOutput, String> {
-    let PDFCircuitInput {
-        pdf_bytes,
-        page_number,
-        offset,
-        substring,
-    } = input;
-
-    // Step 1: verify signature and offset from verify_text function
-    let result = verify_text(pdf_bytes, page_number, substring.as_str(), offset as usize)?;
-
-    // Step 2: construct output
-    Ok(PDFCircuitOutput::from_verification(
-        &substring,
-        page_number,
-        offset,
-        result,
-    ))
+/// Generic PDF verification function for basic text extraction and signature verification.
+/// Besides the claim, the output commits to the document's page count and the Merkle root of
+/// its page hashes, for which every page is extracted; balance claims, whose page hashes
+/// would leak the balance, are the exception. A `Scoped` claim is verified like the claim it
+/// wraps, then its nullifier is rebound.
+pub fn verify_pdf_claim(input: PDFCircuitInput) -> Result<PDFCircuitOutput, pdf_core::Error> {
+    if let PDFCircuitInput::Scoped {
+        scope,
+        external_nullifier,
+        user_secret,
+        claim,
+    } = input
+    {
+        return Ok(match *claim {
+            PDFCircuitInput::Scoped { .. } => PDFCircuitOutput::failure(),
+            claim => {
+                verify_pdf_claim(claim)?.scoped(scope, external_nullifier, user_secret.as_ref())
+            }
+        });
+    }
+    if let PDFCircuitInput::BalanceAtLeast { .. } = input {
+        return verify_claim(input);
+    }
+    let pdf_bytes = input.pdf_bytes().to_vec();
+    let output = verify_claim(input)?;
+
+    // Step 3: commit to the document structure
+    let (page_count, pages_root) = page_commitments(&pdf_bytes)?;
+    Ok(output.with_pages(page_count, pages_root))
+}
+
+fn verify_claim(input: PDFCircuitInput) -> Result<PDFCircuitOutput, pdf_core::Error> {
+    match input {
+        PDFCircuitInput::Substring {
+            pdf_bytes,
+            page_number,
+            offset,
+            substring,
+            reveal,
+            hash_suite,
+        } => {
+            // Step 1: verify signature and offset from verify_text function
+            let result = verify_text(pdf_bytes, page_number, substring.as_str(), offset as usize)?;
+
+            // Step 2: construct output, hashed with the requested suite
+            let output = PDFCircuitOutput::from_verification(
+                &substring,
+                page_number,
+                offset,
+                hash_suite,
+                result,
+            );
+            Ok(if reveal {
+                output.reveal(&substring)
+            } else {
+                output
+            })
+        }
+        PDFCircuitInput::Regex {
+            pdf_bytes,
+            page_number,
+            pattern,
+            reveal,
+        } => {
+            // Step 1: verify signature and capture the value from verify_regex function
+            let result = verify_regex(pdf_bytes, page_number, &pattern)?;
+            let value = result.value().map(|capture| capture.text.clone());
+
+            // Step 2: commit to the captured value and where it was found
+            let output = PDFCircuitOutput::from_regex(page_number, result);
+            Ok(match value {
+                Some(value) if reveal => output.reveal(&value),
+                _ => output,
+            })
+        }
+        PDFCircuitInput::Date {
+            pdf_bytes,
+            source,
+            comparison,
+            threshold,
+        } => {
+            // Step 1: verify signature and read the date from verify_date function
+            let result = date::verify_date(pdf_bytes, &source)?;
+
+            // Step 2: compare it and commit to the threshold and the result
+            Ok(PDFCircuitOutput::from_date(comparison, threshold, result))
+        }
+        PDFCircuitInput::Absent {
+            pdf_bytes,
+            substring,
+        } => {
+            // Step 1: verify signature and scan every page from verify_absent function
+            let result = verify_absent(pdf_bytes, substring.as_str())?;
+
+            // Step 2: construct output with the negated flag set
+            Ok(PDFCircuitOutput::from_absence(&substring, result))
+        }
+        PDFCircuitInput::Template {
+            pdf_bytes,
+            template_id,
+            field,
+            reveal,
+        } => {
+            // Step 1: look up the template, verify signature and signer, and locate the field
+            let template = templates::lookup(&template_id)?;
+            let result = templates::verify_field(pdf_bytes, template, &field)?;
+            let value = result.field.value.clone();
+
+            // Step 2: commit to the value, where it was found and which field it is
+            let output = PDFCircuitOutput::from_template(template.id(), result);
+            Ok(if reveal {
+                output.reveal(&value)
+            } else {
+                output
+            })
+        }
+        PDFCircuitInput::Gst { pdf_bytes } => {
+            // Step 1: verify signature and read the certificate from verify_gst_certificate;
+            // a GSTIN that fails its checks gives the failure output
+            let certificate = match verify_gst_certificate(pdf_bytes) {
+                Ok(certificate) => certificate,
+                Err(GstError::Verification(e)) => return Err(e),
+                Err(GstError::InvalidChecksum(_) | GstError::InvalidStateCode(_)) => {
+                    return Ok(PDFCircuitOutput::failure())
+                }
+            };
+
+            // Step 2: commit to the GSTIN and legal name hashes
+            Ok(PDFCircuitOutput::from_gst(&certificate))
+        }
+        PDFCircuitInput::Pan { pdf_bytes } => {
+            // Step 1: verify signature and read the card from verify_pan_card; a PAN that
+            // fails its check gives the failure output
+            let card = match verify_pan_card(pdf_bytes) {
+                Ok(card) => card,
+                Err(PanError::Verification(e)) => return Err(e),
+                Err(PanError::InvalidPan(_)) => return Ok(PDFCircuitOutput::failure()),
+            };
+
+            // Step 2: commit to the PAN and holder name hashes, disclosing the PAN's end
+            Ok(PDFCircuitOutput::from_pan(&card))
+        }
+        PDFCircuitInput::BalanceAtLeast {
+            pdf_bytes,
+            threshold,
+        } => {
+            // Step 1: verify signature and read the balance from verify_closing_balance
+            let result = bank_example::verify_closing_balance(pdf_bytes)?;
+
+            // Step 2: compare it and commit to the threshold and the result only
+            Ok(PDFCircuitOutput::from_balance(threshold, result))
+        }
+        // Unwrapped by verify_pdf_claim; a scoped claim inside another fails
+        PDFCircuitInput::Scoped { .. } => Ok(PDFCircuitOutput::failure()),
+    }
 }
@@ -1,7 +1,3 @@
-use alloy_primitives::keccak256;
-
-use crate::types::NULLIFIER_DOMAIN;
-
 pub fn compute_nullifier(
     message_digest_hash: &[u8],
     signer_key_hash: &[u8],
@@ -9,15 +5,12 @@ pub fn compute_nullifier(
     page_number: u8,
     offset: u32,
 ) -> alloy_primitives::B256 {
-    const HASH_LEN: usize = 32;
-    let mut preimage = Vec::with_capacity(NULLIFIER_DOMAIN.len() + HASH_LEN * 3 + 1 + 4);
-
-    preimage.extend_from_slice(NULLIFIER_DOMAIN);
-    preimage.extend_from_slice(message_digest_hash);
-    preimage.extend_from_slice(signer_key_hash);
-    preimage.extend_from_slice(substring_hash);
-    preimage.push(page_number);
-    preimage.extend_from_slice(&offset.to_be_bytes());
-
-    keccak256(&preimage)
+    pdf_core::nullifier::compute_nullifier(
+        message_digest_hash,
+        signer_key_hash,
+        substring_hash,
+        page_number,
+        offset,
+    )
+    .into()
 }
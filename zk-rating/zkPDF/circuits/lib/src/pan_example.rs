@@ -0,0 +1,130 @@
+use core::fmt;
+
+use pdf_core::{
+    nullifier::keccak256, verify_and_extract_strict, Date, PdfSignatureResult, Template,
+};
+
+use crate::templates::{DocumentTemplate, PanCard};
+
+pub const PAN_NULLIFIER_DOMAIN: &[u8] = b"zkpdf-pan-v0";
+
+/// Characters of the PAN a proof discloses, as KYC flows display it (`XXXXXX234F`).
+pub const PAN_UNMASKED_LEN: usize = 4;
+
+/// The fourth character of a PAN: the kind of holder, `P` for an individual.
+const HOLDER_TYPES: &str = "ABCFGHJLPT";
+
+pub struct PANCard {
+    pub pan_number: String,
+    pub holder_name: String,
+    /// `None` if no date of birth in `dd/mm/yyyy` form could be read.
+    pub date_of_birth: Option<Date>,
+    pub signature: PdfSignatureResult,
+}
+
+impl PANCard {
+    /// The last `PAN_UNMASKED_LEN` characters of the PAN, which a proof discloses.
+    pub fn unmasked(&self) -> &str {
+        &self.pan_number[self.pan_number.len() - PAN_UNMASKED_LEN..]
+    }
+
+    /// The PAN with all but its last `PAN_UNMASKED_LEN` characters replaced by `X`.
+    pub fn masked(&self) -> String {
+        let hidden = self.pan_number.len() - PAN_UNMASKED_LEN;
+        format!("{}{}", "X".repeat(hidden), self.unmasked())
+    }
+}
+
+/// Why a document is not a verified PAN card.
+#[derive(Debug)]
+pub enum PanError {
+    /// The signature does not verify, the text could not be extracted, or the PAN or holder
+    /// name is missing.
+    Verification(pdf_core::Error),
+    /// The PAN's fourth character is not a holder type.
+    InvalidPan(String),
+}
+
+impl fmt::Display for PanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PanError::Verification(e) => write!(f, "{}", e),
+            PanError::InvalidPan(pan) => write!(f, "PAN `{}` has no valid holder type", pan),
+        }
+    }
+}
+
+impl std::error::Error for PanError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PanError::Verification(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<pdf_core::Error> for PanError {
+    fn from(e: pdf_core::Error) -> Self {
+        PanError::Verification(e)
+    }
+}
+
+/// PAN card verification function that extracts the PAN and holder name from a DigiLocker
+/// e-PAN, and the date of birth when present. The PAN's holder type is checked.
+pub fn verify_pan_card(pdf_bytes: Vec<u8>) -> Result<PANCard, PanError> {
+    // Step 1: verify signature and signer
+    let content = verify_and_extract_strict(pdf_bytes)?;
+    PanCard.check_signer(&content.signature)?;
+
+    // Step 2: locate the PAN and name, then the date of birth
+    let find = |name: &str| -> Result<String, pdf_core::Error> {
+        let field = PanCard
+            .field(name)
+            .ok_or_else(|| pdf_core::Error::FieldNotFound(name.to_string()))?;
+        let mut found = Template {
+            fields: vec![field.pattern],
+        }
+        .match_pages(&content.pages)?;
+        Ok(found.remove(0).value)
+    };
+    let pan_number = find("pan_number")?;
+    let holder_name = find("name")?;
+    let date_of_birth = find("date_of_birth")
+        .ok()
+        .and_then(|date| Date::parse_dmy(&date));
+
+    // Step 3: check the PAN itself
+    if !pan_number
+        .chars()
+        .nth(3)
+        .is_some_and(|c| HOLDER_TYPES.contains(c))
+    {
+        return Err(PanError::InvalidPan(pan_number));
+    }
+
+    Ok(PANCard {
+        pan_number,
+        holder_name,
+        date_of_birth,
+        signature: content.signature,
+    })
+}
+
+/// Nullifier of a PAN claim: keccak256 over the PAN domain and the document, signer, PAN and
+/// holder name hashes, so one card gives one nullifier however often it is proven.
+pub fn pan_nullifier(
+    message_digest_hash: &[u8],
+    signer_key_hash: &[u8],
+    pan_hash: &[u8],
+    holder_name_hash: &[u8],
+) -> [u8; 32] {
+    let mut preimage = Vec::with_capacity(PAN_NULLIFIER_DOMAIN.len() + 32 * 4);
+
+    preimage.extend_from_slice(PAN_NULLIFIER_DOMAIN);
+    preimage.extend_from_slice(message_digest_hash);
+    preimage.extend_from_slice(signer_key_hash);
+    preimage.extend_from_slice(pan_hash);
+    preimage.extend_from_slice(holder_name_hash);
+
+    keccak256(&preimage)
+}
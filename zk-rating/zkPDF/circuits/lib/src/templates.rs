@@ -0,0 +1,297 @@
+//! Document templates: the fields a kind of signed document carries, how to find them in its
+//! text, and which keys may sign it. Templates are registered by id, and a
+//! `PDFCircuitInput::Template` claim names one, so supporting a new kind of document means
+//! adding a template here rather than a new circuit program.
+
+use pdf_core::{
+    claims::parse_number, nullifier::keccak256, verify_and_extract_strict, Date, Error,
+    ExtractedField, FieldPattern, PdfSignatureResult, Template, TemplateResult,
+};
+
+pub const TEMPLATE_CLAIM_DOMAIN: &[u8] = b"zkpdf-template-claim-v0";
+
+/// What a field's value must look like for a claim about it to hold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldKind {
+    /// Any text.
+    Text,
+    /// A decimal number, as `pdf_core::claims::parse_number` reads it.
+    Number,
+    /// A date, as `Date::parse` reads it.
+    Date,
+}
+
+impl FieldKind {
+    pub fn accepts(self, value: &str) -> bool {
+        match self {
+            FieldKind::Text => true,
+            FieldKind::Number => parse_number(value).is_some(),
+            FieldKind::Date => Date::parse(value).is_some(),
+        }
+    }
+}
+
+/// One field of a template: where its value is found, and what kind of value it is.
+#[derive(Debug, Clone)]
+pub struct TemplateField {
+    pub pattern: FieldPattern,
+    pub kind: FieldKind,
+}
+
+impl TemplateField {
+    pub fn new(name: &str, pattern: &str, group: usize, kind: FieldKind) -> Self {
+        Self {
+            pattern: FieldPattern {
+                name: name.to_string(),
+                pattern: pattern.to_string(),
+                group,
+            },
+            kind,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.pattern.name
+    }
+}
+
+/// A kind of signed document the circuit can prove claims about.
+pub trait DocumentTemplate: Sync {
+    /// The id claims name the template by. Part of every template claim hash, so it must not
+    /// change once proofs about it exist.
+    fn id(&self) -> &'static str;
+
+    /// SHA-256 fingerprints of the SubjectPublicKeyInfo of the keys allowed to sign the
+    /// document, as `SignerDetails::key_fingerprint_sha256` computes them. Empty accepts any
+    /// signer whose signature verifies.
+    fn signer_pins(&self) -> &'static [[u8; 32]] {
+        &[]
+    }
+
+    /// The fields, in the order `verify_document` returns them.
+    fn fields(&self) -> Vec<TemplateField>;
+
+    /// The field named `name`.
+    fn field(&self, name: &str) -> Option<TemplateField> {
+        self.fields().into_iter().find(|field| field.name() == name)
+    }
+
+    /// The fields as a `pdf_core::Template`, for `apply_template`.
+    fn template(&self) -> Template {
+        Template {
+            fields: self
+                .fields()
+                .into_iter()
+                .map(|field| field.pattern)
+                .collect(),
+        }
+    }
+
+    /// Fails with `Error::UntrustedSigner` unless the template pins no keys or pins the
+    /// signer's.
+    fn check_signer(&self, signature: &PdfSignatureResult) -> Result<(), Error> {
+        let pins = self.signer_pins();
+        let pinned = signature
+            .signer
+            .as_ref()
+            .is_some_and(|signer| pins.contains(&signer.key_fingerprint_sha256()));
+        if pins.is_empty() || pinned {
+            Ok(())
+        } else {
+            Err(Error::UntrustedSigner)
+        }
+    }
+}
+
+/// GST registration certificate (Form GST REG-06), with the fields of
+/// `Template::gst_certificate`.
+pub struct GstCertificate;
+
+impl DocumentTemplate for GstCertificate {
+    fn id(&self) -> &'static str {
+        "gst-certificate"
+    }
+
+    fn fields(&self) -> Vec<TemplateField> {
+        Template::gst_certificate()
+            .fields
+            .into_iter()
+            .map(|pattern| TemplateField {
+                pattern,
+                kind: FieldKind::Text,
+            })
+            .collect()
+    }
+}
+
+/// e-PAN card issued by the Income Tax Department.
+pub struct PanCard;
+
+impl DocumentTemplate for PanCard {
+    fn id(&self) -> &'static str {
+        "pan-card"
+    }
+
+    fn fields(&self) -> Vec<TemplateField> {
+        vec![
+            TemplateField::new(
+                "pan_number",
+                r"\b([A-Z]{5}[0-9]{4}[A-Z])\b",
+                1,
+                FieldKind::Text,
+            ),
+            TemplateField::new(
+                "name",
+                r"Name\s*:?\s*([A-Z][A-Z .]+?)\s*(?:\n|Father|$)",
+                1,
+                FieldKind::Text,
+            ),
+            TemplateField::new(
+                "date_of_birth",
+                r"Date of Birth\s*:?\s*([0-9]{2}/[0-9]{2}/[0-9]{4})",
+                1,
+                FieldKind::Date,
+            ),
+        ]
+    }
+}
+
+/// School or university marksheet.
+pub struct Marksheet;
+
+impl DocumentTemplate for Marksheet {
+    fn id(&self) -> &'static str {
+        "marksheet"
+    }
+
+    fn fields(&self) -> Vec<TemplateField> {
+        vec![
+            TemplateField::new(
+                "roll_number",
+                r"Roll No\.?\s*:?\s*([0-9A-Z/-]+)",
+                1,
+                FieldKind::Text,
+            ),
+            TemplateField::new(
+                "candidate_name",
+                r"Name of (?:the )?Candidate\s*:?\s*([A-Za-z .]+?)\s*(?:\n|$)",
+                1,
+                FieldKind::Text,
+            ),
+            TemplateField::new(
+                "total_marks",
+                r"(?:Grand )?Total\s*(?:Marks)?\s*:?\s*([0-9]+(?:\.[0-9]+)?)",
+                1,
+                FieldKind::Number,
+            ),
+            TemplateField::new(
+                "result",
+                r"Result\s*:?\s*(PASS|FAIL|Pass|Fail)",
+                1,
+                FieldKind::Text,
+            ),
+        ]
+    }
+}
+
+/// Bank account statement.
+pub struct BankStatement;
+
+impl DocumentTemplate for BankStatement {
+    fn id(&self) -> &'static str {
+        "bank-statement"
+    }
+
+    fn fields(&self) -> Vec<TemplateField> {
+        vec![
+            TemplateField::new(
+                "account_number",
+                r"Account (?:No|Number)\.?\s*:?\s*([0-9]{9,18})",
+                1,
+                FieldKind::Text,
+            ),
+            TemplateField::new("ifsc", r"\b([A-Z]{4}0[A-Z0-9]{6})\b", 1, FieldKind::Text),
+            TemplateField::new(
+                "closing_balance",
+                r"Closing Balance[ \t]*:?[ \t]*(?:INR|Rs\.?)?[ \t]*([0-9][0-9,]*\.[0-9]{2})",
+                1,
+                FieldKind::Number,
+            ),
+        ]
+    }
+}
+
+/// Every template a claim can name.
+pub static REGISTRY: &[&dyn DocumentTemplate] =
+    &[&GstCertificate, &PanCard, &Marksheet, &BankStatement];
+
+/// The registered template with id `id`.
+pub fn lookup(id: &str) -> Result<&'static dyn DocumentTemplate, Error> {
+    REGISTRY
+        .iter()
+        .copied()
+        .find(|template| template.id() == id)
+        .ok_or_else(|| Error::UnknownTemplate(id.to_string()))
+}
+
+/// A template field located in a signed document.
+pub struct TemplateFieldVerification {
+    pub field: ExtractedField,
+    /// Whether the value is of the field's kind.
+    pub conforms: bool,
+    pub signature: PdfSignatureResult,
+}
+
+/// Verifies the PDF's signature, checks the signer against the template's pins and locates
+/// `field`. A field the template does not have, or that matches on no page, is
+/// `Error::FieldNotFound`.
+pub fn verify_field(
+    pdf_bytes: Vec<u8>,
+    template: &dyn DocumentTemplate,
+    field: &str,
+) -> Result<TemplateFieldVerification, Error> {
+    let schema = template
+        .field(field)
+        .ok_or_else(|| Error::FieldNotFound(field.to_string()))?;
+    let content = verify_and_extract_strict(pdf_bytes)?;
+    template.check_signer(&content.signature)?;
+
+    let located = Template {
+        fields: vec![schema.pattern],
+    }
+    .match_pages(&content.pages)?
+    .remove(0);
+    Ok(TemplateFieldVerification {
+        conforms: schema.kind.accepts(&located.value),
+        field: located,
+        signature: content.signature,
+    })
+}
+
+/// Verifies the PDF's signature, checks the signer against the pins of the template `id`
+/// and locates every one of its fields.
+pub fn verify_document(pdf_bytes: Vec<u8>, id: &str) -> Result<TemplateResult, Error> {
+    let template = lookup(id)?;
+    let content = verify_and_extract_strict(pdf_bytes)?;
+    template.check_signer(&content.signature)?;
+
+    Ok(TemplateResult {
+        fields: template.template().match_pages(&content.pages)?,
+        signature: content.signature,
+    })
+}
+
+/// Commitment to which field of which template a claim is about: keccak256 over the domain,
+/// the template id, a zero byte and the field name. A verifier recomputes it for the field it
+/// expects.
+pub fn template_claim_hash(template_id: &str, field: &str) -> [u8; 32] {
+    let mut preimage =
+        Vec::with_capacity(TEMPLATE_CLAIM_DOMAIN.len() + template_id.len() + 1 + field.len());
+
+    preimage.extend_from_slice(TEMPLATE_CLAIM_DOMAIN);
+    preimage.extend_from_slice(template_id.as_bytes());
+    preimage.push(0);
+    preimage.extend_from_slice(field.as_bytes());
+
+    keccak256(&preimage)
+}
@@ -1,47 +1,271 @@
-use pdf_core::PdfVerificationResult;
+use pdf_core::{
+    aadhaar::{selector, OfflineKyc},
+    nullifier::{
+        blinded_hash, keccak256, scoped_nullifier, secret_bound_nullifier, secret_commitment,
+        ClaimHashes, HashSuite, NullifierScope,
+    },
+    PdfAbsenceResult, PdfRegexResult, PdfSignatureResult, PdfVerificationResult,
+    SignatureTimestamp, SignerDetails, XmlAttributeResult,
+};
 
-use alloy_primitives::{keccak256, B256};
+use alloy_primitives::{Bytes, B256};
 use alloy_sol_types::sol;
 use serde::{Deserialize, Serialize};
 
-pub const NULLIFIER_DOMAIN: &[u8] = b"zkpdf-nullifier-v0";
+use crate::aadhaar::{aadhaar_nullifier, AADHAAR_TEMPLATE_ID};
+use crate::bank_example::{balance_claim_hash, BalanceVerification};
+use crate::date::{
+    date_claim_hash, date_nullifier, unix_timestamp, Date, DateComparison, DateSource,
+    DateVerification,
+};
+use crate::gst_example::{gst_nullifier, GSTCertificate};
+use crate::pan_example::{pan_nullifier, PANCard};
+use crate::templates::{
+    template_claim_hash, BankStatement, DocumentTemplate, GstCertificate, PanCard,
+    TemplateFieldVerification,
+};
+
+pub use pdf_core::nullifier::NULLIFIER_DOMAIN;
+
+/// Longest value, in bytes, a proof may reveal in its public values.
+pub const MAX_REVEALED_LEN: usize = 256;
+
+/// Layout version of `PublicValuesStruct`, committed as its first field. Bump it whenever a
+/// field is added, removed, retyped or moved, so that a verifier built for one layout rejects
+/// proofs of another instead of misreading them.
+pub const PUBLIC_VALUES_VERSION: u16 = 1;
+
+/// The kind of claim a proof is about, committed as `claimType`, so a verifier can tell, say,
+/// a GST registration proof from a template claim on the same field. `Xml` and `Aadhaar` are
+/// the `verify_xml_claim` and `verify_aadhaar_claim` entrypoints; the failure output commits
+/// `Failure`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum ClaimType {
+    Failure = 0,
+    Substring = 1,
+    Regex = 2,
+    Absent = 3,
+    Date = 4,
+    Template = 5,
+    Gst = 6,
+    Pan = 7,
+    BalanceAtLeast = 8,
+    Xml = 9,
+    Aadhaar = 10,
+}
 
 sol! {
     /// The public values encoded as a struct that can be easily deserialized inside Solidity.
     struct PublicValuesStruct {
+        // `PUBLIC_VALUES_VERSION` of this layout. It is the first word of the head, so it can
+        // be read before decoding the rest.
+        uint16 version;
+        // `ClaimType` of the claim the proof is about.
+        uint8 claimType;
         bool substringMatches;
         bytes32 messageDigestHash;
         bytes32 signerKeyHash;
         bytes32 substringHash;
         bytes32 nullifier;
+        // Set for absence claims, whose `substringMatches` means the substring appears
+        // nowhere in the document.
+        bool negated;
+        // `date_claim_hash` of the comparison, threshold and result for date claims, zero
+        // otherwise.
+        bytes32 dateClaim;
+        // Number of pages of the document, and `merkle::root` of their `hash_pages` hashes,
+        // so a page can later be proven part of the document off-circuit. Both zero when the
+        // nullifier is bound to a user secret.
+        uint32 pageCount;
+        bytes32 pagesRoot;
+        // keccak256 of the signer certificate's subject distinguished name, and its validity
+        // window in Unix seconds; all zero if the signer certificate is unknown. The window is
+        // also zero when the nullifier is bound to a user secret.
+        bytes32 signerSubjectHash;
+        uint64 notBefore;
+        uint64 notAfter;
+        // When a TSA attests the signature existed, in Unix seconds: the `genTime` of a valid
+        // timeStampToken over the signature value; zero if there is none.
+        uint64 timestampedAt;
+        // `template_claim_hash` of the template id and field name for template claims, zero
+        // otherwise.
+        bytes32 templateClaim;
+        // keccak256 of the name of whoever the document is about, for claims that identify
+        // one, such as the legal name of a GST registration; zero otherwise.
+        bytes32 holderNameHash;
+        // `NullifierScope` the nullifier was computed under, and the application's external
+        // nullifier it is bound to; zero for claims that were not scoped.
+        uint8 nullifierScope;
+        bytes32 externalNullifier;
+        // `secret_commitment` of the prover's secret and the external nullifier for claims
+        // whose nullifier is bound to one; zero otherwise.
+        bytes32 secretCommitment;
+        // `HashSuite` the claim hashes and nullifier were computed with: 0 for keccak256, 1 for
+        // Poseidon over BN254.
+        uint8 hashSuite;
+        // keccak256 of the DER of the signer certificate, which unlike `signerKeyHash` tells
+        // apart certificates issued for one key; zero if the signer certificate is unknown.
+        bytes32 signerCertHash;
+        // The claimed substring or captured value itself when the claim asked to reveal it,
+        // empty otherwise.
+        bytes revealed;
     }
 }
 
+/// The claim a proof is about.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PDFCircuitInput {
-    pub pdf_bytes: Vec<u8>,
-    pub page_number: u8,
-    pub offset: u32,
-    pub substring: String,
+pub enum PDFCircuitInput {
+    /// `substring` appears at `offset` on `page_number`. With `reveal` the substring is
+    /// disclosed in `revealed`, besides being hashed. The digest, key and substring hashes and
+    /// the nullifier are computed with `hash_suite`, keccak256 unless given.
+    Substring {
+        pdf_bytes: Vec<u8>,
+        page_number: u8,
+        offset: u32,
+        substring: String,
+        reveal: bool,
+        #[serde(default)]
+        hash_suite: HashSuite,
+    },
+    /// `pattern` matches on `page_number`. The proof commits to the hash of the captured value
+    /// (the first group, or the whole match) and binds its offset into the nullifier, so the
+    /// prover needs no offset and reveals only the hash, unless `reveal` discloses the value
+    /// itself in `revealed`.
+    Regex {
+        pdf_bytes: Vec<u8>,
+        page_number: u8,
+        pattern: String,
+        reveal: bool,
+    },
+    /// `substring` appears on no page of the document. Every page is extracted, and the
+    /// output has `negated` set.
+    Absent {
+        pdf_bytes: Vec<u8>,
+        substring: String,
+    },
+    /// The date read from `source` compares with `threshold` as `comparison` says, e.g. the
+    /// document was issued after it. The output commits to the hash of the date text and to
+    /// the comparison in `date_claim`.
+    Date {
+        pdf_bytes: Vec<u8>,
+        source: DateSource,
+        comparison: DateComparison,
+        threshold: Date,
+    },
+    /// The field `field` of the registered template `template_id` is present in a document
+    /// signed by a key the template pins, and its value is of the field's kind. The proof
+    /// commits to the hash of the value like a regex claim, and to the template and field in
+    /// `template_claim`. With `reveal` the value is disclosed in `revealed`.
+    Template {
+        pdf_bytes: Vec<u8>,
+        template_id: String,
+        field: String,
+        reveal: bool,
+    },
+    /// The document is a GST registration certificate, as `verify_gst_certificate` checks it,
+    /// with a valid GSTIN. The proof commits to the hash of the GSTIN in `substring_hash`, of
+    /// the legal name in `holder_name_hash`, and marks itself with the `template_claim` of the
+    /// `gst-certificate` template's `gst_number` field.
+    Gst { pdf_bytes: Vec<u8> },
+    /// The document is a PAN card, as `verify_pan_card` checks it. The proof commits to the
+    /// hash of the PAN in `substring_hash` and of the holder name in `holder_name_hash`, and
+    /// discloses the PAN's last four characters in `revealed`.
+    Pan { pdf_bytes: Vec<u8> },
+    /// The closing balance of a signed bank statement is at least `threshold` paise. Only the
+    /// result and the signer are committed: `substring_hash` is `balance_claim_hash` of the
+    /// threshold and the result, and the digest, nullifier and page commitments are left out,
+    /// since each is a hash of text that includes the balance.
+    BalanceAtLeast { pdf_bytes: Vec<u8>, threshold: u64 },
+    /// `claim`, with its nullifier recomputed by `pdf_core::nullifier::scoped_nullifier` under
+    /// `scope` and bound to `external_nullifier`, an application's domain separator, so that,
+    /// say, one document can back one claim per application whatever is claimed. Both are
+    /// committed. A claim that is itself scoped, or that commits no document digest such as a
+    /// balance claim, gives the failure output.
+    ///
+    /// With `user_secret`, the nullifier is further mixed with the prover's secret by
+    /// `secret_bound_nullifier`, and `secret_commitment` commits to the secret. The digest,
+    /// page count and root, timestamp and certificate validity window, which would identify
+    /// the document to every application, are then left out, and the other hashes are blinded
+    /// with the bound nullifier (see [`PDFCircuitOutput::scoped`]).
+    Scoped {
+        scope: NullifierScope,
+        external_nullifier: [u8; 32],
+        #[serde(default)]
+        user_secret: Option<[u8; 32]>,
+        claim: Box<PDFCircuitInput>,
+    },
 }
 
 #[derive(Debug, Clone)]
 pub struct PDFCircuitOutput {
+    pub claim_type: u8,
     pub substring_matches: bool,
     pub message_digest_hash: B256,
     pub signer_key_hash: B256,
     pub substring_hash: B256,
     pub nullifier: B256,
+    pub negated: bool,
+    pub date_claim: B256,
+    pub page_count: u32,
+    pub pages_root: B256,
+    pub signer_subject_hash: B256,
+    pub not_before: u64,
+    pub not_after: u64,
+    pub timestamped_at: u64,
+    pub template_claim: B256,
+    pub holder_name_hash: B256,
+    pub nullifier_scope: u8,
+    pub external_nullifier: B256,
+    pub secret_commitment: B256,
+    pub hash_suite: u8,
+    pub signer_cert_hash: B256,
+    pub revealed: Bytes,
+}
+
+impl PDFCircuitInput {
+    /// The document the claim is about.
+    pub fn pdf_bytes(&self) -> &[u8] {
+        match self {
+            PDFCircuitInput::Substring { pdf_bytes, .. }
+            | PDFCircuitInput::Regex { pdf_bytes, .. }
+            | PDFCircuitInput::Absent { pdf_bytes, .. }
+            | PDFCircuitInput::Date { pdf_bytes, .. }
+            | PDFCircuitInput::Template { pdf_bytes, .. }
+            | PDFCircuitInput::Gst { pdf_bytes }
+            | PDFCircuitInput::Pan { pdf_bytes }
+            | PDFCircuitInput::BalanceAtLeast { pdf_bytes, .. } => pdf_bytes,
+            PDFCircuitInput::Scoped { claim, .. } => claim.pdf_bytes(),
+        }
+    }
 }
 
 impl From<PDFCircuitOutput> for PublicValuesStruct {
     fn from(value: PDFCircuitOutput) -> Self {
         PublicValuesStruct {
+            version: PUBLIC_VALUES_VERSION,
+            claimType: value.claim_type,
             substringMatches: value.substring_matches,
             messageDigestHash: value.message_digest_hash,
             signerKeyHash: value.signer_key_hash,
             substringHash: value.substring_hash,
             nullifier: value.nullifier,
+            negated: value.negated,
+            dateClaim: value.date_claim,
+            pageCount: value.page_count,
+            pagesRoot: value.pages_root,
+            signerSubjectHash: value.signer_subject_hash,
+            notBefore: value.not_before,
+            notAfter: value.not_after,
+            timestampedAt: value.timestamped_at,
+            templateClaim: value.template_claim,
+            holderNameHash: value.holder_name_hash,
+            nullifierScope: value.nullifier_scope,
+            externalNullifier: value.external_nullifier,
+            secretCommitment: value.secret_commitment,
+            hashSuite: value.hash_suite,
+            signerCertHash: value.signer_cert_hash,
+            revealed: value.revealed,
         }
     }
 }
@@ -50,11 +274,137 @@ impl PDFCircuitOutput {
     /// Construct a failure output (all zeros).
     pub fn failure() -> Self {
         Self {
+            claim_type: ClaimType::Failure as u8,
             substring_matches: false,
             message_digest_hash: B256::ZERO,
             signer_key_hash: B256::ZERO,
             substring_hash: B256::ZERO,
             nullifier: B256::ZERO,
+            negated: false,
+            date_claim: B256::ZERO,
+            page_count: 0,
+            pages_root: B256::ZERO,
+            signer_subject_hash: B256::ZERO,
+            not_before: 0,
+            not_after: 0,
+            timestamped_at: 0,
+            template_claim: B256::ZERO,
+            holder_name_hash: B256::ZERO,
+            nullifier_scope: 0,
+            external_nullifier: B256::ZERO,
+            secret_commitment: B256::ZERO,
+            hash_suite: 0,
+            signer_cert_hash: B256::ZERO,
+            revealed: Bytes::new(),
+        }
+    }
+
+    /// Disclose `value` in the public values. A value longer than `MAX_REVEALED_LEN` bytes
+    /// gives the failure output rather than a truncated value.
+    pub fn reveal(self, value: &str) -> Self {
+        if value.len() > MAX_REVEALED_LEN {
+            return Self::failure();
+        }
+        Self {
+            revealed: Bytes::copy_from_slice(value.as_bytes()),
+            ..self
+        }
+    }
+
+    /// Replace the nullifier with one under `scope`, bound to `external_nullifier`, and commit
+    /// to both. An output without a document digest, a failure or a balance claim, gives the
+    /// failure output, since its nullifier would bind to nothing. With `user_secret` the
+    /// nullifier is also bound to the secret, whose commitment replaces the digest and page
+    /// root; the page count, timestamp and the certificate's `not_before` and `not_after` are
+    /// zeroed, and every other hash is blinded with the bound nullifier by `blinded_hash`. Only
+    /// the claim kind and any `revealed` value, which the verifier asked for, are then the same
+    /// in two applications' proofs.
+    pub fn scoped(
+        self,
+        scope: NullifierScope,
+        external_nullifier: [u8; 32],
+        user_secret: Option<&[u8; 32]>,
+    ) -> Self {
+        if self.message_digest_hash == B256::ZERO {
+            return Self::failure();
+        }
+        let nullifier = scoped_nullifier(
+            scope,
+            &external_nullifier,
+            self.message_digest_hash.as_slice(),
+            self.signer_key_hash.as_slice(),
+            &self.nullifier.0,
+        );
+        let output = Self {
+            nullifier: nullifier.into(),
+            nullifier_scope: scope as u8,
+            external_nullifier: external_nullifier.into(),
+            ..self
+        };
+        match user_secret {
+            Some(user_secret) => {
+                let nullifier = secret_bound_nullifier(user_secret, &nullifier);
+                let blind = |hash: B256| B256::from(blinded_hash(&hash.0, &nullifier));
+                Self {
+                    nullifier: nullifier.into(),
+                    secret_commitment: secret_commitment(user_secret, &external_nullifier).into(),
+                    message_digest_hash: B256::ZERO,
+                    page_count: 0,
+                    pages_root: B256::ZERO,
+                    not_before: 0,
+                    not_after: 0,
+                    timestamped_at: 0,
+                    signer_key_hash: blind(output.signer_key_hash),
+                    substring_hash: blind(output.substring_hash),
+                    date_claim: blind(output.date_claim),
+                    signer_subject_hash: blind(output.signer_subject_hash),
+                    template_claim: blind(output.template_claim),
+                    holder_name_hash: blind(output.holder_name_hash),
+                    signer_cert_hash: blind(output.signer_cert_hash),
+                    ..output
+                }
+            }
+            None => output,
+        }
+    }
+
+    /// Commit to the document's structure: its page count and the Merkle root of its page
+    /// hashes, as `pdf_core::page_commitments` computes them.
+    pub fn with_pages(self, page_count: usize, pages_root: [u8; 32]) -> Self {
+        Self {
+            page_count: u32::try_from(page_count).unwrap_or(u32::MAX),
+            pages_root: pages_root.into(),
+            ..self
+        }
+    }
+
+    /// Commit to the certificate that signed the document, as `with_certificate` does, along
+    /// with the time a valid signature timestamp attests. A time that is not after 1970 is
+    /// committed as zero.
+    pub fn with_signer(self, signature: &PdfSignatureResult) -> Self {
+        let Some(signer) = &signature.signer else {
+            return self;
+        };
+        let timestamped_at = signature
+            .timestamp
+            .as_ref()
+            .and_then(SignatureTimestamp::verified)
+            .and_then(|timestamp| unix_timestamp(&timestamp.gen_time))
+            .unwrap_or(0);
+        Self {
+            timestamped_at,
+            ..self.with_certificate(signer)
+        }
+    }
+
+    /// Commit to the hash of the signer certificate, of its subject DN and its validity window.
+    fn with_certificate(self, signer: &SignerDetails) -> Self {
+        Self {
+            signer_subject_hash: keccak256(signer.subject.as_bytes()).into(),
+            signer_cert_hash: keccak256(&signer.certificate).into(),
+            not_before: unix_timestamp(&signer.not_before).unwrap_or(0),
+            not_after: unix_timestamp(&signer.not_after).unwrap_or(0),
+            ..self
         }
     }
 
@@ -63,26 +413,333 @@ impl PDFCircuitOutput {
         sub_string: &str,
         page_number: u8,
         offset: u32,
+        hash_suite: HashSuite,
         verification_result: PdfVerificationResult,
     ) -> Self {
-        let message_digest_hash = keccak256(&verification_result.signature.message_digest);
-        let pub_key_hash = keccak256(verification_result.signature.public_key);
-        let sub_string_hash = keccak256(sub_string.as_bytes());
-
-        let nullifier = crate::nullifier::compute_nullifier(
-            message_digest_hash.as_slice(),
-            pub_key_hash.as_slice(),
-            sub_string_hash.as_slice(),
+        let hashes = ClaimHashes::with_suite(
+            hash_suite,
+            &verification_result.signature,
+            sub_string,
             page_number,
             offset,
         );
 
         Self {
+            claim_type: ClaimType::Substring as u8,
             substring_matches: verification_result.substring_matches,
-            message_digest_hash,
-            signer_key_hash: pub_key_hash,
-            substring_hash: sub_string_hash,
-            nullifier,
+            message_digest_hash: hashes.message_digest_hash.into(),
+            signer_key_hash: hashes.signer_key_hash.into(),
+            substring_hash: hashes.substring_hash.into(),
+            nullifier: hashes.nullifier.into(),
+            hash_suite: hash_suite as u8,
+            ..Self::failure()
+        }
+        .with_signer(&verification_result.signature)
+    }
+
+    /// Build a circuit output from a regex verification result. A pattern that does not match
+    /// gives the failure output, with the signature hashes left out.
+    pub fn from_regex(page_number: u8, verification_result: PdfRegexResult) -> Self {
+        let Some(value) = verification_result.value() else {
+            return Self::failure();
+        };
+        let Ok(offset) = u32::try_from(value.offset) else {
+            return Self::failure();
+        };
+        let hashes = ClaimHashes::new(
+            &verification_result.signature,
+            &value.text,
+            page_number,
+            offset,
+        );
+
+        Self {
+            claim_type: ClaimType::Regex as u8,
+            substring_matches: true,
+            message_digest_hash: hashes.message_digest_hash.into(),
+            signer_key_hash: hashes.signer_key_hash.into(),
+            substring_hash: hashes.substring_hash.into(),
+            nullifier: hashes.nullifier.into(),
+            ..Self::failure()
+        }
+        .with_signer(&verification_result.signature)
+    }
+
+    /// Build a circuit output from an absence verification result. `substring_matches` is
+    /// true when the substring is absent, and the nullifier is bound to the document and the
+    /// substring only.
+    pub fn from_absence(sub_string: &str, verification_result: PdfAbsenceResult) -> Self {
+        let hashes = ClaimHashes::absent(&verification_result.signature, sub_string);
+
+        Self {
+            claim_type: ClaimType::Absent as u8,
+            substring_matches: verification_result.absent,
+            message_digest_hash: hashes.message_digest_hash.into(),
+            signer_key_hash: hashes.signer_key_hash.into(),
+            substring_hash: hashes.substring_hash.into(),
+            nullifier: hashes.nullifier.into(),
+            negated: true,
+            ..Self::failure()
+        }
+        .with_signer(&verification_result.signature)
+    }
+
+    /// Build a circuit output from a date verification result. A source without a date in a
+    /// known format gives the failure output.
+    pub fn from_date(
+        comparison: DateComparison,
+        threshold: Date,
+        verification_result: DateVerification,
+    ) -> Self {
+        let Some(date) = verification_result.date else {
+            return Self::failure();
+        };
+        let holds = comparison.holds(date, threshold);
+        let signature = &verification_result.signature;
+        let message_digest_hash = keccak256(&signature.message_digest);
+        let signer_key_hash = keccak256(&signature.public_key);
+        let date_hash = keccak256(verification_result.text.as_bytes());
+        let date_claim = date_claim_hash(comparison, threshold, holds);
+        let nullifier = date_nullifier(
+            &message_digest_hash,
+            &signer_key_hash,
+            &date_hash,
+            &date_claim,
+        );
+
+        Self {
+            claim_type: ClaimType::Date as u8,
+            substring_matches: holds,
+            message_digest_hash: message_digest_hash.into(),
+            signer_key_hash: signer_key_hash.into(),
+            substring_hash: date_hash.into(),
+            nullifier: nullifier.into(),
+            date_claim: date_claim.into(),
+            ..Self::failure()
+        }
+        .with_signer(signature)
+    }
+
+    /// Build a circuit output from an XML attribute verification result. `substring_matches`
+    /// is true when the attribute has the claimed value; the signer fields come from the
+    /// KeyInfo certificate, if the signature carries one.
+    pub fn from_xml(selector: &str, value: &str, verification_result: XmlAttributeResult) -> Self {
+        let signature = &verification_result.signature;
+        let hashes = ClaimHashes::xml(signature, selector, value);
+
+        let output = Self {
+            claim_type: ClaimType::Xml as u8,
+            substring_matches: verification_result.value == value,
+            message_digest_hash: hashes.message_digest_hash.into(),
+            signer_key_hash: hashes.signer_key_hash.into(),
+            substring_hash: hashes.substring_hash.into(),
+            nullifier: hashes.nullifier.into(),
+            ..Self::failure()
+        };
+        match &signature.signer {
+            Some(signer) => output.with_certificate(signer),
+            None => output,
+        }
+    }
+
+    /// Build a circuit output from a template field verification result. `substring_matches`
+    /// is true when the value is of the field's kind; a field found beyond page 255 gives the
+    /// failure output.
+    pub fn from_template(
+        template_id: &str,
+        verification_result: TemplateFieldVerification,
+    ) -> Self {
+        let field = &verification_result.field;
+        let (Ok(page_number), Ok(offset)) = (u8::try_from(field.page), u32::try_from(field.offset))
+        else {
+            return Self::failure();
+        };
+        let signature = &verification_result.signature;
+        let hashes = ClaimHashes::new(signature, &field.value, page_number, offset);
+
+        Self {
+            claim_type: ClaimType::Template as u8,
+            substring_matches: verification_result.conforms,
+            message_digest_hash: hashes.message_digest_hash.into(),
+            signer_key_hash: hashes.signer_key_hash.into(),
+            substring_hash: hashes.substring_hash.into(),
+            nullifier: hashes.nullifier.into(),
+            template_claim: template_claim_hash(template_id, &field.name).into(),
+            ..Self::failure()
+        }
+        .with_signer(signature)
+    }
+
+    /// Build a circuit output from a verified GST certificate.
+    pub fn from_gst(certificate: &GSTCertificate) -> Self {
+        let signature = &certificate.signature;
+        let message_digest_hash = keccak256(&signature.message_digest);
+        let signer_key_hash = keccak256(&signature.public_key);
+        let gstin_hash = keccak256(certificate.gst_number.as_bytes());
+        let legal_name_hash = keccak256(certificate.legal_name.as_bytes());
+        let nullifier = gst_nullifier(
+            &message_digest_hash,
+            &signer_key_hash,
+            &gstin_hash,
+            &legal_name_hash,
+        );
+
+        Self {
+            claim_type: ClaimType::Gst as u8,
+            substring_matches: true,
+            message_digest_hash: message_digest_hash.into(),
+            signer_key_hash: signer_key_hash.into(),
+            substring_hash: gstin_hash.into(),
+            nullifier: nullifier.into(),
+            template_claim: template_claim_hash(GstCertificate.id(), "gst_number").into(),
+            holder_name_hash: legal_name_hash.into(),
+            ..Self::failure()
+        }
+        .with_signer(signature)
+    }
+
+    /// Build a circuit output from a verified offline e-KYC download. `substring_matches` is
+    /// true when the field `field` has `value`; a field that is not one of
+    /// `pdf_core::aadhaar::FIELDS`, or a document without a photograph to derive the nullifier
+    /// from, gives the failure output.
+    pub fn from_aadhaar(field: &str, value: &str, share_code: &str, kyc: &OfflineKyc) -> Self {
+        let (Some(selector), Some(photo)) = (selector(field), kyc.photo()) else {
+            return Self::failure();
+        };
+        let signature = kyc.signature();
+        let hashes = ClaimHashes::xml(signature, selector, value);
+        let nullifier = aadhaar_nullifier(&hashes.signer_key_hash, &keccak256(photo.as_bytes()));
+        let holder_name_hash = kyc
+            .field("name")
+            .map_or(B256::ZERO, |name| keccak256(name.as_bytes()).into());
+
+        let output = Self {
+            claim_type: ClaimType::Aadhaar as u8,
+            substring_matches: kyc.matches(field, value, share_code),
+            message_digest_hash: hashes.message_digest_hash.into(),
+            signer_key_hash: hashes.signer_key_hash.into(),
+            substring_hash: hashes.substring_hash.into(),
+            nullifier: nullifier.into(),
+            template_claim: template_claim_hash(AADHAAR_TEMPLATE_ID, field).into(),
+            holder_name_hash,
+            ..Self::failure()
+        };
+        match &signature.signer {
+            Some(signer) => output.with_certificate(signer),
+            None => output,
+        }
+    }
+
+    /// Build a circuit output from a verified PAN card, disclosing the unmasked end of the
+    /// PAN.
+    pub fn from_pan(card: &PANCard) -> Self {
+        let signature = &card.signature;
+        let message_digest_hash = keccak256(&signature.message_digest);
+        let signer_key_hash = keccak256(&signature.public_key);
+        let pan_hash = keccak256(card.pan_number.as_bytes());
+        let holder_name_hash = keccak256(card.holder_name.as_bytes());
+        let nullifier = pan_nullifier(
+            &message_digest_hash,
+            &signer_key_hash,
+            &pan_hash,
+            &holder_name_hash,
+        );
+
+        Self {
+            claim_type: ClaimType::Pan as u8,
+            substring_matches: true,
+            message_digest_hash: message_digest_hash.into(),
+            signer_key_hash: signer_key_hash.into(),
+            substring_hash: pan_hash.into(),
+            nullifier: nullifier.into(),
+            template_claim: template_claim_hash(PanCard.id(), "pan_number").into(),
+            holder_name_hash: holder_name_hash.into(),
+            ..Self::failure()
+        }
+        .with_signer(signature)
+        .reveal(card.unmasked())
+    }
+
+    /// Build a circuit output from a closing balance. A statement whose balance could not be
+    /// read gives the failure output.
+    pub fn from_balance(threshold: u64, verification_result: BalanceVerification) -> Self {
+        let Some(balance) = verification_result.balance else {
+            return Self::failure();
+        };
+        let holds = i64::try_from(threshold).is_ok_and(|threshold| balance >= threshold);
+        let signature = &verification_result.signature;
+
+        Self {
+            claim_type: ClaimType::BalanceAtLeast as u8,
+            substring_matches: holds,
+            signer_key_hash: keccak256(&signature.public_key).into(),
+            substring_hash: balance_claim_hash(threshold, holds).into(),
+            template_claim: template_claim_hash(BankStatement.id(), "closing_balance").into(),
+            ..Self::failure()
+        }
+        .with_signer(signature)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_secret_scoped_outputs_share_no_hash() {
+        let output = PDFCircuitOutput {
+            claim_type: ClaimType::Substring as u8,
+            substring_matches: true,
+            message_digest_hash: keccak256(b"digest").into(),
+            signer_key_hash: keccak256(b"signer key").into(),
+            substring_hash: keccak256(b"substring").into(),
+            nullifier: keccak256(b"nullifier").into(),
+            page_count: 2,
+            pages_root: keccak256(b"pages").into(),
+            signer_subject_hash: keccak256(b"subject").into(),
+            not_before: 1_600_000_000,
+            not_after: 1_900_000_000,
+            timestamped_at: 1_700_000_000,
+            template_claim: keccak256(b"template").into(),
+            holder_name_hash: keccak256(b"holder").into(),
+            signer_cert_hash: keccak256(b"certificate").into(),
+            ..PDFCircuitOutput::failure()
+        };
+        let secret = [1u8; 32];
+        let scope = NullifierScope::PerDocument;
+        let first = output.clone().scoped(scope, [7; 32], Some(&secret));
+        let second = output.scoped(scope, [8; 32], Some(&secret));
+        // Nor the page count, timestamp or validity window
+        for output in [&first, &second] {
+            assert_eq!(
+                (output.page_count, output.not_before, output.not_after),
+                (0, 0, 0)
+            );
+            assert_eq!(output.timestamped_at, 0);
+        }
+
+        let hashes = |output: &PDFCircuitOutput| {
+            [
+                output.message_digest_hash,
+                output.signer_key_hash,
+                output.substring_hash,
+                output.nullifier,
+                output.date_claim,
+                output.pages_root,
+                output.signer_subject_hash,
+                output.template_claim,
+                output.holder_name_hash,
+                output.external_nullifier,
+                output.secret_commitment,
+                output.signer_cert_hash,
+            ]
+        };
+        let (first, second) = (hashes(&first), hashes(&second));
+        for hash in first.iter().filter(|hash| **hash != B256::ZERO) {
+            assert!(!second.contains(hash), "{hash} is committed by both");
         }
+        // The digest and page root are left out rather than blinded
+        assert_eq!(first[0], B256::ZERO);
+        assert_eq!(first[5], B256::ZERO);
     }
 }
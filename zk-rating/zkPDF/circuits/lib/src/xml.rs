@@ -0,0 +1,35 @@
+//! Claims about signed XML documents, such as DigiLocker certificates: the signature is an
+//! enveloped XML-DSig signature rather than a PDF's, and the claimed value is an attribute of
+//! the signed content rather than page text.
+
+use pdf_core::verify_xml_attribute;
+use serde::{Deserialize, Serialize};
+
+use crate::types::PDFCircuitOutput;
+
+/// A claim that the attribute `selector` names, such as `Certificate/IssuedTo/Person@name`,
+/// has the value `value` in the signed content of `xml_bytes`. With `reveal` the value is
+/// disclosed in `revealed`, besides being hashed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct XMLCircuitInput {
+    pub xml_bytes: Vec<u8>,
+    pub selector: String,
+    pub value: String,
+    pub reveal: bool,
+}
+
+/// Verifies an XML claim. The output commits to the signed SignedInfo digest and the signer's
+/// key like a PDF claim does, with `substring_hash` the hash of the claimed value and the
+/// nullifier bound to the selector; there are no page commitments.
+pub fn verify_xml_claim(input: XMLCircuitInput) -> Result<PDFCircuitOutput, pdf_core::Error> {
+    // Step 1: verify the signature and read the attribute from verify_xml_attribute
+    let result = verify_xml_attribute(&input.xml_bytes, &input.selector)?;
+
+    // Step 2: compare it with the claimed value and construct output
+    let output = PDFCircuitOutput::from_xml(&input.selector, &input.value, result);
+    Ok(if input.reveal {
+        output.reveal(&input.value)
+    } else {
+        output
+    })
+}
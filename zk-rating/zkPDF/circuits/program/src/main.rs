@@ -4,12 +4,16 @@ sp1_zkvm::entrypoint!(main);
 use alloy_sol_types::SolType;
 use zkpdf_lib::{
     types::{PDFCircuitInput, PDFCircuitOutput},
-    verify_pdf_claim, PublicValuesStruct,
+    verify_pdf_claim, PublicValuesStruct, ZkpdfError,
 };
 
 pub fn main() {
     let input = sp1_zkvm::io::read::<PDFCircuitInput>();
-    let output = verify_pdf_claim(input).unwrap_or_else(|_| PDFCircuitOutput::failure());
+    let output = verify_pdf_claim(input).unwrap_or_else(|e| {
+        // The public values only say that verification failed; the code tells the host why.
+        println!("{}", ZkpdfError::from(e));
+        PDFCircuitOutput::failure()
+    });
     let public_values: PublicValuesStruct = output.into();
     let bytes = PublicValuesStruct::abi_encode(&public_values);
 
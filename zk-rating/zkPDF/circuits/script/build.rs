@@ -1,5 +1,9 @@
-use sp1_build::build_program_with_args;
+use sp1_build::{build_program_with_args, BuildArgs};
 
 fn main() {
-    build_program_with_args("../program", Default::default())
+    let mut args = BuildArgs::default();
+    if cfg!(feature = "poseidon") {
+        args.features.push("poseidon".to_string());
+    }
+    build_program_with_args("../program", args)
 }
@@ -10,14 +10,11 @@
 //! RUST_LOG=info cargo run --release --bin evm -- --system plonk
 //! ```
 
-use alloy_sol_types::SolType;
 use clap::{Parser, ValueEnum};
-use serde::{Deserialize, Serialize};
-use sp1_sdk::{
-    include_elf, HashableKey, ProverClient, SP1ProofWithPublicValues, SP1Stdin, SP1VerifyingKey,
-};
+use sp1_sdk::{include_elf, ProverClient, SP1ProofWithPublicValues, SP1Stdin, SP1VerifyingKey};
 use std::path::PathBuf;
-use zkpdf_lib::{types::PDFCircuitInput, PublicValuesStruct};
+use zkpdf_lib::{types::PDFCircuitInput, HashSuite};
+use zkpdf_script::{claim::first_offset, fixture::SP1ZkPdfProofFixture};
 
 /// The ELF (executable and linkable format) file for the Succinct RISC-V zkVM.
 pub const ZKPDF_ELF: &[u8] = include_elf!("zkpdf-program");
@@ -41,8 +38,13 @@ struct EVMArgs {
     #[arg(long, default_value = "Sample Signed PDF Document")]
     substring: String,
 
-    #[arg(long, default_value_t = 0)]
-    offset: usize,
+    /// Byte offset of the substring in the page text; its first occurrence on the page when
+    /// omitted.
+    #[arg(long)]
+    offset: Option<usize>,
+    /// Disclose the substring in the proof's public values instead of only its hash.
+    #[arg(long)]
+    reveal: bool,
 }
 
 /// Enum representing the available proof systems
@@ -52,20 +54,6 @@ enum ProofSystem {
     Groth16,
 }
 
-/// A fixture that can be used to test the verification of SP1 zkVM proofs inside Solidity.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-struct SP1ZkPdfProofFixture {
-    substring_matches: bool,
-    message_digest_hash: String,
-    signer_key_hash: String,
-    substring_hash: String,
-    nullifier: String,
-    vkey: String,
-    public_values: String,
-    proof: String,
-}
-
 fn main() {
     // Setup the logger.
     sp1_sdk::utils::setup_logger();
@@ -77,6 +65,7 @@ fn main() {
         page,
         substring,
         offset,
+        reveal,
     } = EVMArgs::parse();
 
     // Setup the prover client.
@@ -92,6 +81,11 @@ fn main() {
     // Setup the inputs.
     let page_number: u8 = page;
     let sub_string = substring;
+    let offset = offset.unwrap_or_else(|| {
+        first_offset(&pdf_bytes, page_number, &sub_string)
+            .expect("failed to extract text")
+            .unwrap_or_else(|| panic!("{:?} not found on page {}", sub_string, page_number))
+    });
 
     println!("pdf_path: {}", pdf_path);
     println!("page: {}", page_number);
@@ -100,11 +94,13 @@ fn main() {
     println!("Proof System: {:?}", system);
 
     let offset_u32 = u32::try_from(offset).expect("offset does not fit in u32");
-    let proof_input = PDFCircuitInput {
+    let proof_input = PDFCircuitInput::Substring {
         pdf_bytes,
         page_number,
         offset: offset_u32,
         substring: sub_string,
+        reveal,
+        hash_suite: HashSuite::Keccak256,
     };
 
     let mut stdin = SP1Stdin::new();
@@ -126,21 +122,8 @@ fn create_proof_fixture(
     vk: &SP1VerifyingKey,
     system: ProofSystem,
 ) {
-    // Deserialize the public values.
-    let bytes = proof.public_values.as_slice();
-    let decoded = PublicValuesStruct::abi_decode(bytes, false).unwrap();
-
     // Create the testing fixture so we can test things end-to-end.
-    let fixture = SP1ZkPdfProofFixture {
-        substring_matches: decoded.substringMatches,
-        message_digest_hash: format!("0x{}", hex::encode(decoded.messageDigestHash.as_slice())),
-        signer_key_hash: format!("0x{}", hex::encode(decoded.signerKeyHash.as_slice())),
-        substring_hash: format!("0x{}", hex::encode(decoded.substringHash.as_slice())),
-        nullifier: format!("0x{}", hex::encode(decoded.nullifier.as_slice())),
-        vkey: vk.bytes32().to_string(),
-        public_values: format!("0x{}", hex::encode(bytes)),
-        proof: format!("0x{}", hex::encode(proof.bytes())),
-    };
+    let fixture = SP1ZkPdfProofFixture::new(proof, vk).expect("failed to decode public values");
 
     // The verification key is used to verify that the proof corresponds to the execution of the
     // program on the given input.
@@ -9,11 +9,21 @@
 //! ```shell
 //! RUST_LOG=info cargo run --release -- --prove
 //! ```
+//! or, to produce a compressed proof suitable for recursive aggregation
+//! ```shell
+//! RUST_LOG=info cargo run --release -- --prove --proof-type compressed --proof-path proof.bin
+//! ```
+//! or, to print the extracted text of a page with the byte offset of each row (`-` reads the PDF from stdin)
+//! ```shell
+//! cat document.pdf | cargo run --release -- --show-text --pdf-path - --page 0
+//! ```
 
 use alloy_sol_types::SolType;
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use sp1_sdk::{include_elf, ProverClient, SP1Stdin};
-use zkpdf_lib::{types::PDFCircuitInput, PublicValuesStruct};
+use std::{io::Read, path::PathBuf};
+use zkpdf_lib::{extract_text, types::PDFCircuitInput, HashSuite, PublicValuesStruct};
+use zkpdf_script::claim::first_offset;
 
 /// The ELF (executable and linkable format) file for the Succinct RISC-V zkVM.
 pub const ZKPDF_ELF: &[u8] = include_elf!("zkpdf-program");
@@ -28,6 +38,12 @@ struct Args {
     #[arg(long)]
     prove: bool,
 
+    /// Print the extracted text of `--page` with a column ruler and the byte offset of each row,
+    /// and exit.
+    #[arg(long)]
+    show_text: bool,
+
+    /// Path to the PDF, or `-` to read it from stdin.
     #[arg(
         long,
         default_value = "../../pdf-utils/sample-pdfs/digitally_signed.pdf"
@@ -40,8 +56,30 @@ struct Args {
     #[arg(long, default_value = "Sample Signed PDF Document")]
     substring: String,
 
-    #[arg(long, default_value_t = 0)]
-    offset: usize,
+    /// Byte offset of the substring in the page text; its first occurrence on the page when
+    /// omitted.
+    #[arg(long)]
+    offset: Option<usize>,
+
+    /// Disclose the substring in the proof's public values instead of only its hash.
+    #[arg(long)]
+    reveal: bool,
+
+    #[arg(long, value_enum, default_value = "core")]
+    proof_type: ProofType,
+
+    #[arg(long)]
+    proof_path: Option<PathBuf>,
+}
+
+/// Number of characters of page text printed per row by `--show-text`.
+const RULER_WIDTH: usize = 64;
+
+/// Enum representing the non-EVM proof types available with `--prove`
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Debug)]
+enum ProofType {
+    Core,
+    Compressed,
 }
 
 fn main() {
@@ -53,26 +91,48 @@ fn main() {
     let Args {
         execute,
         prove,
+        show_text,
         pdf_path,
         page,
         substring,
         offset,
+        reveal,
+        proof_type,
+        proof_path,
     } = Args::parse();
 
-    if execute == prove {
-        eprintln!("Error: You must specify either --execute or --prove");
+    if usize::from(execute) + usize::from(prove) + usize::from(show_text) != 1 {
+        eprintln!("Error: You must specify one of --execute, --prove or --show-text");
         std::process::exit(1);
     }
 
+    // Load the PDF bytes from the provided path, or stdin for `-`
+    let pdf_bytes = read_pdf(&pdf_path)
+        .unwrap_or_else(|e| panic!("Failed to read PDF file at {}: {}", pdf_path, e));
+
+    if show_text {
+        let pages = extract_text(pdf_bytes).expect("failed to extract text");
+        let text = pages.get(page as usize).unwrap_or_else(|| {
+            panic!(
+                "page {} out of range, document has {} pages",
+                page,
+                pages.len()
+            )
+        });
+        print_text_with_ruler(text);
+        return;
+    }
+
     // Setup the prover client.
     let client = ProverClient::from_env();
 
-    // Load the PDF bytes from the provided path
-    let pdf_bytes = std::fs::read(&pdf_path)
-        .unwrap_or_else(|_| panic!("Failed to read PDF file at {}", pdf_path));
-
     let page_number: u8 = page;
     let sub_string = substring;
+    let offset = offset.unwrap_or_else(|| {
+        first_offset(&pdf_bytes, page_number, &sub_string)
+            .expect("failed to extract text")
+            .unwrap_or_else(|| panic!("{:?} not found on page {}", sub_string, page_number))
+    });
 
     println!("pdf_path: {}", pdf_path);
     println!("page: {}", page_number);
@@ -80,11 +140,13 @@ fn main() {
     println!("offset: {}", offset);
 
     let offset_u32 = u32::try_from(offset).expect("offset does not fit in u32");
-    let proof_input = PDFCircuitInput {
+    let proof_input = PDFCircuitInput::Substring {
         pdf_bytes,
         page_number,
         offset: offset_u32,
         substring: sub_string,
+        reveal,
+        hash_suite: HashSuite::Keccak256,
     };
 
     // Setup the inputs.
@@ -98,6 +160,8 @@ fn main() {
 
         // Read the output.
         let decoded = PublicValuesStruct::abi_decode(output.as_slice(), true).unwrap();
+        println!("Public values version: {}", decoded.version);
+        println!("Claim type: {}", decoded.claimType);
         println!("Substring matches: {}", decoded.substringMatches);
         println!(
             "Message digest hash: 0x{}",
@@ -112,21 +176,126 @@ fn main() {
             hex::encode(decoded.substringHash.as_slice())
         );
         println!("Nullifier: 0x{}", hex::encode(decoded.nullifier.as_slice()));
+        println!("Negated: {}", decoded.negated);
+        println!(
+            "Date claim: 0x{}",
+            hex::encode(decoded.dateClaim.as_slice())
+        );
+        println!("Page count: {}", decoded.pageCount);
+        println!(
+            "Pages root: 0x{}",
+            hex::encode(decoded.pagesRoot.as_slice())
+        );
+        println!(
+            "Signer subject hash: 0x{}",
+            hex::encode(decoded.signerSubjectHash.as_slice())
+        );
+        println!(
+            "Signer certificate validity: {} to {}",
+            decoded.notBefore, decoded.notAfter
+        );
+        println!("Timestamped at: {}", decoded.timestampedAt);
+        println!(
+            "Template claim: 0x{}",
+            hex::encode(decoded.templateClaim.as_slice())
+        );
+        println!(
+            "Holder name hash: 0x{}",
+            hex::encode(decoded.holderNameHash.as_slice())
+        );
+        println!("Nullifier scope: {}", decoded.nullifierScope);
+        println!(
+            "External nullifier: 0x{}",
+            hex::encode(decoded.externalNullifier.as_slice())
+        );
+        println!(
+            "Secret commitment: 0x{}",
+            hex::encode(decoded.secretCommitment.as_slice())
+        );
+        println!("Hash suite: {}", decoded.hashSuite);
+        println!(
+            "Signer certificate hash: 0x{}",
+            hex::encode(decoded.signerCertHash.as_slice())
+        );
+        println!("Revealed: {}", String::from_utf8_lossy(&decoded.revealed));
         println!("Number of cycles: {}", report.total_instruction_count());
     } else {
         // Setup the program for proving.
         let (pk, vk) = client.setup(ZKPDF_ELF);
 
         // Generate the proof
-        let proof = client
-            .prove(&pk, &stdin)
-            .run()
-            .expect("failed to generate proof");
+        let proof = match proof_type {
+            ProofType::Core => client.prove(&pk, &stdin).core().run(),
+            ProofType::Compressed => client.prove(&pk, &stdin).compressed().run(),
+        }
+        .expect("failed to generate proof");
 
-        println!("Successfully generated proof!");
+        println!("Successfully generated {:?} proof!", proof_type);
 
         // Verify the proof.
         client.verify(&proof, &vk).expect("failed to verify proof");
         println!("Successfully verified proof!");
+
+        if let Some(path) = proof_path {
+            proof.save(&path).expect("failed to save proof");
+            println!("Proof saved to {}", path.display());
+        }
+    }
+}
+
+fn read_pdf(path: &str) -> std::io::Result<Vec<u8>> {
+    if path == "-" {
+        let mut bytes = Vec::new();
+        std::io::stdin().read_to_end(&mut bytes)?;
+        Ok(bytes)
+    } else {
+        std::fs::read(path)
+    }
+}
+
+/// Prints page text in rows of `RULER_WIDTH` characters, each prefixed by the byte offset of its
+/// first character, as `--offset` takes it. The ruler counts characters, one per column, so on
+/// a row of ASCII text the offset of a character is the row's plus its column; a character
+/// after non-ASCII ones is further on by their extra UTF-8 bytes, which the row's trailing
+/// `[+n bytes]` totals. Control characters are shown as `.`.
+fn print_text_with_ruler(text: &str) {
+    let tens: String = (0..RULER_WIDTH)
+        .map(|col| {
+            if col % 10 == 0 {
+                char::from(b'0' + (col / 10 % 10) as u8)
+            } else {
+                ' '
+            }
+        })
+        .collect();
+    let units: String = (0..RULER_WIDTH)
+        .map(|col| char::from(b'0' + (col % 10) as u8))
+        .collect();
+    println!("{:>8}  {}", "", tens);
+    println!("{:>8}  {}", "offset", units);
+
+    let print_row = |row_start: usize, row: &str, row_bytes: usize| {
+        let extra = row_bytes - row.chars().count();
+        if extra == 0 {
+            println!("{:>8}  {}", row_start, row);
+        } else {
+            println!("{:>8}  {}  [+{} bytes]", row_start, row, extra);
+        }
+    };
+    let mut row_start = 0;
+    let mut row = String::new();
+    for (index, ch) in text.char_indices() {
+        if row.chars().count() == RULER_WIDTH {
+            print_row(row_start, &row, index - row_start);
+            row_start = index;
+            row.clear();
+        }
+        row.push(if ch.is_control() { '.' } else { ch });
     }
+    print_row(row_start, &row, text.len() - row_start);
+    println!(
+        "({} bytes, {} characters)",
+        text.len(),
+        text.chars().count()
+    );
 }
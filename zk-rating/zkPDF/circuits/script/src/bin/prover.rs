@@ -1,10 +1,22 @@
-use axum::{routing::post, serve, Json, Router};
+use axum::{
+    extract::State,
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    routing::post,
+    serve, Json, Router,
+};
 use serde::{Deserialize, Serialize};
-use sp1_sdk::{include_elf, ProverClient, SP1ProofWithPublicValues, SP1Stdin};
-use std::net::SocketAddr;
-use tokio::net::TcpListener;
+use sp1_sdk::{
+    include_elf, network, ProverClient, SP1ProofWithPublicValues, SP1Stdin, SP1VerifyingKey,
+};
+use std::{io::ErrorKind, net::SocketAddr, sync::Arc, time::Duration};
+use tokio::{net::TcpListener, sync::Semaphore};
+use tonic::{Code, Status};
 use tower_http::cors::{Any, CorsLayer};
-use zkpdf_lib::types::PDFCircuitInput;
+use zkpdf_lib::{
+    types::PDFCircuitInput, verify_pdf_claim, ErrorCode, HashSuite, NullifierScope, ZkpdfError,
+};
+use zkpdf_script::{claim::first_offset, fixture::SP1ZkPdfProofFixture};
 
 pub const ZKPDF_ELF: &[u8] = include_elf!("zkpdf-program");
 
@@ -14,6 +26,36 @@ struct ProofRequest {
     page_number: u8,
     sub_string: String,
     offset: Option<usize>,
+    /// Disclose the substring in the public values instead of only its hash.
+    #[serde(default)]
+    reveal: bool,
+    #[serde(default)]
+    proof_type: ProofType,
+    /// What the nullifier is bound to; `per_claim` unless given.
+    #[serde(default)]
+    nullifier_scope: NullifierScope,
+    /// The application's domain separator, as 32 bytes, which the nullifier is bound to.
+    #[serde(default)]
+    external_nullifier: Option<[u8; 32]>,
+    /// The holder's secret, as 32 bytes, to bind the nullifier to so applications cannot
+    /// correlate the document across proofs.
+    #[serde(default)]
+    user_secret: Option<[u8; 32]>,
+    /// `keccak256` unless given; `poseidon` needs the server built with the `poseidon` feature.
+    #[serde(default)]
+    hash_suite: HashSuite,
+}
+
+/// Proof type requested by the client. `compressed` and `core` proofs are intended for
+/// recursive aggregation; `groth16` and `plonk` are wrapped for on-chain verification.
+#[derive(Deserialize, Default, Clone, Copy, Debug)]
+#[serde(rename_all = "lowercase")]
+enum ProofType {
+    Core,
+    Compressed,
+    Plonk,
+    #[default]
+    Groth16,
 }
 
 #[derive(Serialize)]
@@ -22,37 +64,405 @@ struct VerifyResponse {
     error: Option<String>,
 }
 
-async fn prove(Json(body): Json<ProofRequest>) -> Json<SP1ProofWithPublicValues> {
-    let client = ProverClient::from_env();
-    let (pk, _vk) = client.setup(ZKPDF_ELF);
+#[derive(Serialize)]
+struct ErrorResponse {
+    /// Stable `zkpdf-errors` code; the HTTP status is derived from it.
+    code: u16,
+    error: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    kind: Option<FailureKind>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    attempts: Option<u32>,
+}
+
+#[derive(Clone)]
+struct AppState {
+    limits: ProvingLimits,
+    retry: RetryPolicy,
+}
+
+/// Whether a failed request may succeed if retried later.
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+enum FailureKind {
+    /// Network or capacity problems on the prover side, e.g. a dropped connection.
+    Transient,
+    /// Failures that will repeat for the same input, e.g. an unexecutable program.
+    Permanent,
+}
+
+impl FailureKind {
+    /// Classifies a prover error by the types in its source chain: a network proof request
+    /// that timed out, an RPC status saying the service is unavailable or overloaded, or an I/O
+    /// error on the connection is transient. Anything else, such as an unexecutable program or
+    /// an unfulfillable request, is permanent.
+    fn classify(error: &(dyn std::error::Error + 'static)) -> Self {
+        let transient = std::iter::successors(Some(error), |error| error.source()).any(|error| {
+            if let Some(error) = error.downcast_ref::<network::Error>() {
+                return matches!(error, network::Error::RequestTimedOut { .. });
+            }
+            if let Some(status) = error.downcast_ref::<Status>() {
+                return matches!(
+                    status.code(),
+                    Code::Unavailable
+                        | Code::DeadlineExceeded
+                        | Code::ResourceExhausted
+                        | Code::Aborted
+                );
+            }
+            error.downcast_ref::<std::io::Error>().is_some_and(|error| {
+                matches!(
+                    error.kind(),
+                    ErrorKind::TimedOut
+                        | ErrorKind::ConnectionRefused
+                        | ErrorKind::ConnectionReset
+                        | ErrorKind::ConnectionAborted
+                        | ErrorKind::BrokenPipe
+                        | ErrorKind::NotConnected
+                )
+            })
+        });
+        if transient {
+            FailureKind::Transient
+        } else {
+            FailureKind::Permanent
+        }
+    }
+}
+
+/// A failed proving attempt, classified when it failed while its error types are known.
+struct ProvingError {
+    kind: FailureKind,
+    message: String,
+}
+
+/// Retry policy for proving calls that fail with a transient error.
+#[derive(Clone, Copy)]
+struct RetryPolicy {
+    max_retries: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+}
+
+impl RetryPolicy {
+    fn from_env() -> Self {
+        let millis = |name, default| Duration::from_millis(env_or(name, default) as u64);
+        Self {
+            max_retries: env_or("PROOF_MAX_RETRIES", 3) as u32,
+            initial_backoff: millis("PROOF_RETRY_BACKOFF_MS", 2_000),
+            max_backoff: millis("PROOF_RETRY_MAX_BACKOFF_MS", 30_000),
+        }
+    }
+
+    /// Exponential backoff before the retry following the given (1-based) attempt.
+    fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 1u32 << (attempt - 1).min(16);
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+/// Limits on the number of proofs generated at once and the number of requests allowed to
+/// wait for a proving slot.
+#[derive(Clone)]
+struct ProvingLimits {
+    /// Permits for requests that are either proving or waiting to prove.
+    admission: Arc<Semaphore>,
+    /// Permits for requests that are actively proving.
+    proving: Arc<Semaphore>,
+    retry_after_secs: u64,
+}
+
+impl ProvingLimits {
+    fn from_env() -> Self {
+        let max_concurrent = env_or("MAX_CONCURRENT_PROOFS", 2).max(1);
+        let max_queued = env_or("MAX_QUEUED_PROOFS", 8);
+        let retry_after_secs = env_or("PROOF_RETRY_AFTER_SECS", 30) as u64;
+
+        tracing::info!(
+            "proving limits: {} concurrent, {} queued, retry after {}s",
+            max_concurrent,
+            max_queued,
+            retry_after_secs
+        );
+
+        Self {
+            admission: Arc::new(Semaphore::new(max_concurrent + max_queued)),
+            proving: Arc::new(Semaphore::new(max_concurrent)),
+            retry_after_secs,
+        }
+    }
+
+    fn saturated(&self) -> Response {
+        let (status, body) = error_response(
+            ErrorCode::ProverBusy,
+            "Prover is at capacity, retry later".to_string(),
+            FailureKind::Transient,
+            None,
+        );
+        (
+            status,
+            [(header::RETRY_AFTER, self.retry_after_secs.to_string())],
+            body,
+        )
+            .into_response()
+    }
+}
+
+fn error_response(
+    code: ErrorCode,
+    error: String,
+    kind: FailureKind,
+    attempts: Option<u32>,
+) -> (StatusCode, Json<ErrorResponse>) {
+    let status =
+        StatusCode::from_u16(code.http_status()).expect("error codes map to valid HTTP statuses");
+    let body = Json(ErrorResponse {
+        code: code.code(),
+        error,
+        kind: Some(kind),
+        attempts,
+    });
+    (status, body)
+}
+
+fn bad_request(error: &str) -> Response {
+    error_response(
+        ErrorCode::InvalidInput,
+        error.to_string(),
+        FailureKind::Permanent,
+        None,
+    )
+    .into_response()
+}
+
+/// The document or claim itself is at fault, e.g. the PDF is unsigned or was modified.
+fn rejected(error: ZkpdfError) -> Response {
+    error_response(error.code, error.message, FailureKind::Permanent, None).into_response()
+}
+
+fn proving_failed(
+    kind: FailureKind,
+    error: String,
+    attempts: u32,
+    limits: &ProvingLimits,
+) -> Response {
+    let code = match kind {
+        FailureKind::Transient => ErrorCode::ProverUnavailable,
+        FailureKind::Permanent => ErrorCode::ProvingFailed,
+    };
+    let (status, body) = error_response(
+        code,
+        format!("Proof generation failed: {}", error),
+        kind,
+        Some(attempts),
+    );
+    match kind {
+        FailureKind::Transient => (
+            status,
+            [(header::RETRY_AFTER, limits.retry_after_secs.to_string())],
+            body,
+        )
+            .into_response(),
+        FailureKind::Permanent => (status, body).into_response(),
+    }
+}
+
+fn env_or(name: &str, default: usize) -> usize {
+    std::env::var(name)
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(default)
+}
+
+async fn prove(
+    State(state): State<AppState>,
+    Json(body): Json<ProofRequest>,
+) -> Result<Json<SP1ProofWithPublicValues>, Response> {
+    let (proof, _vk) = run_proving(state, body).await?;
+    Ok(Json(proof))
+}
+
+/// Generates an on-chain proof and returns it in the same Solidity fixture format as the
+/// `evm` binary.
+async fn fixture(
+    State(state): State<AppState>,
+    Json(body): Json<ProofRequest>,
+) -> Result<Json<SP1ZkPdfProofFixture>, Response> {
+    if !matches!(body.proof_type, ProofType::Groth16 | ProofType::Plonk) {
+        return Err(bad_request(
+            "Fixtures require a groth16 or plonk proof_type",
+        ));
+    }
+
+    let (proof, vk) = run_proving(state.clone(), body).await?;
+    let fixture = SP1ZkPdfProofFixture::new(&proof, &vk).map_err(|e| {
+        proving_failed(
+            FailureKind::Permanent,
+            format!("invalid public values: {}", e),
+            1,
+            &state.limits,
+        )
+    })?;
+    Ok(Json(fixture))
+}
+
+async fn run_proving(
+    state: AppState,
+    body: ProofRequest,
+) -> Result<(SP1ProofWithPublicValues, SP1VerifyingKey), Response> {
+    let AppState { limits, retry } = state;
 
     let ProofRequest {
         pdf_bytes,
         page_number,
         sub_string,
         offset,
+        reveal,
+        proof_type,
+        nullifier_scope,
+        external_nullifier,
+        user_secret,
+        hash_suite,
     } = body;
 
-    let offset = offset.expect("Offset must be provided in the request");
-    let offset_u32 = u32::try_from(offset).expect("offset does not fit in u32");
+    // Reject immediately when both the proving slots and the queue are full. Locating
+    // the offset and checking the claim parse the PDF, so they wait until a request is admitted.
+    let Ok(admission) = limits.admission.clone().try_acquire_owned() else {
+        return Err(limits.saturated());
+    };
+
+    // Without an offset, the claim is about the first occurrence of the substring on the page
+    let offset = match offset {
+        Some(offset) => offset,
+        None => first_offset(&pdf_bytes, page_number, &sub_string)
+            .map_err(rejected)?
+            .ok_or_else(|| bad_request("Substring not found on the page; pass an offset"))?,
+    };
+    let offset_u32 =
+        u32::try_from(offset).map_err(|_| bad_request("offset does not fit in u32"))?;
 
-    let proof_input = PDFCircuitInput {
+    let proof_input = PDFCircuitInput::Substring {
         pdf_bytes,
         page_number,
         offset: offset_u32,
         substring: sub_string,
+        reveal,
+        hash_suite,
     };
+    // An unscoped claim keeps the nullifier it always had
+    let proof_input = match (nullifier_scope, external_nullifier, user_secret) {
+        (NullifierScope::PerClaim, None, None) => proof_input,
+        (scope, external_nullifier, user_secret) => PDFCircuitInput::Scoped {
+            scope,
+            external_nullifier: external_nullifier.unwrap_or_default(),
+            user_secret,
+            claim: Box::new(proof_input),
+        },
+    };
+
+    // Once admitted, check the claim natively before taking a proving slot. An unsigned or
+    // modified PDF would otherwise be proven, minutes later, as the all-zero failure output.
+    // Holding the admission permit bounds how many of these checks run at once.
+    let claim = proof_input.clone();
+    match tokio::task::spawn_blocking(move || verify_pdf_claim(claim)).await {
+        Ok(Ok(_)) => {}
+        Ok(Err(e)) => return Err(rejected(e.into())),
+        Err(e) => {
+            return Err(rejected(ZkpdfError::new(
+                ErrorCode::Internal,
+                format!("verification task panicked: {}", e),
+            )))
+        }
+    }
+    let proving = limits
+        .proving
+        .clone()
+        .acquire_owned()
+        .await
+        .expect("proving semaphore closed");
+    // The permits go with each proving task and come back when it ends, so a proof whose
+    // client disconnected keeps its slot until it finishes, not until the handler is dropped
+    let mut permits = (admission, proving);
+
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        let input = proof_input.clone();
+        let task =
+            tokio::task::spawn_blocking(move || (generate_proof(input, proof_type), permits));
+        let result = match task.await {
+            Ok((result, returned)) => {
+                permits = returned;
+                result
+            }
+            // The permits were dropped with the task, which is no longer running
+            Err(e) => {
+                let error = format!("proving task panicked: {}", e);
+                tracing::error!("{}", error);
+                return Err(proving_failed(
+                    FailureKind::Permanent,
+                    error,
+                    attempts,
+                    &limits,
+                ));
+            }
+        };
+
+        let ProvingError {
+            kind,
+            message: error,
+        } = match result {
+            Ok(proved) => return Ok(proved),
+            Err(error) => error,
+        };
+
+        if kind == FailureKind::Transient && attempts <= retry.max_retries {
+            let delay = retry.backoff(attempts);
+            tracing::warn!(
+                "transient proving failure (attempt {}), retrying in {:?}: {}",
+                attempts,
+                delay,
+                error
+            );
+            tokio::time::sleep(delay).await;
+            continue;
+        }
+
+        tracing::error!(
+            "{:?} proving failure after {} attempt(s): {}",
+            kind,
+            attempts,
+            error
+        );
+        return Err(proving_failed(kind, error, attempts, &limits));
+    }
+}
+
+fn generate_proof(
+    proof_input: PDFCircuitInput,
+    proof_type: ProofType,
+) -> Result<(SP1ProofWithPublicValues, SP1VerifyingKey), ProvingError> {
+    let client = ProverClient::from_env();
+    let (pk, vk) = client.setup(ZKPDF_ELF);
 
     let mut stdin = SP1Stdin::new();
     stdin.write(&proof_input);
 
-    let proof = client
-        .prove(&pk, &stdin)
-        .groth16()
-        .run()
-        .expect("failed to generate proof");
+    let builder = client.prove(&pk, &stdin);
+    let proof = match proof_type {
+        ProofType::Core => builder.core().run(),
+        ProofType::Compressed => builder.compressed().run(),
+        ProofType::Plonk => builder.plonk().run(),
+        ProofType::Groth16 => builder.groth16().run(),
+    }
+    .map_err(|e| ProvingError {
+        kind: FailureKind::classify(&*e),
+        message: format!("{:#}", e),
+    })?;
 
-    Json(proof)
+    Ok((proof, vk))
 }
 
 async fn verify(Json(proof): Json<SP1ProofWithPublicValues>) -> Json<VerifyResponse> {
@@ -93,6 +503,11 @@ async fn main() {
     let app = Router::new()
         .route("/prove", post(prove))
         .route("/verify", post(verify))
+        .route("/fixture", post(fixture))
+        .with_state(AppState {
+            limits: ProvingLimits::from_env(),
+            retry: RetryPolicy::from_env(),
+        })
         .layer(cors);
 
     let port: u16 = std::env::var("PORT")
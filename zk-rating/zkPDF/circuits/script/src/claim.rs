@@ -0,0 +1,17 @@
+//! Locating the substring a claim is about in the page text the circuit extracts.
+
+use zkpdf_lib::{find_substring, ZkpdfError};
+
+/// Byte offset of the first occurrence of `substring` on page `page`, or `None` if the page
+/// does not contain it.
+pub fn first_offset(
+    pdf_bytes: &[u8],
+    page: u8,
+    substring: &str,
+) -> Result<Option<usize>, ZkpdfError> {
+    let matches = find_substring(pdf_bytes, substring, &Default::default())?;
+    Ok(matches
+        .iter()
+        .find(|found| found.page == usize::from(page))
+        .map(|found| found.offset))
+}
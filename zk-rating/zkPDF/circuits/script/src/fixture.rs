@@ -0,0 +1,85 @@
+use alloy_sol_types::SolType;
+use serde::{Deserialize, Serialize};
+use sp1_sdk::{HashableKey, SP1ProofWithPublicValues, SP1VerifyingKey};
+use zkpdf_lib::{PublicValuesStruct, PUBLIC_VALUES_VERSION};
+
+/// A fixture that can be used to test the verification of SP1 zkVM proofs inside Solidity.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SP1ZkPdfProofFixture {
+    pub version: u16,
+    pub claim_type: u8,
+    pub substring_matches: bool,
+    pub message_digest_hash: String,
+    pub signer_key_hash: String,
+    pub substring_hash: String,
+    pub nullifier: String,
+    pub negated: bool,
+    pub date_claim: String,
+    pub page_count: u32,
+    pub pages_root: String,
+    pub signer_subject_hash: String,
+    pub not_before: u64,
+    pub not_after: u64,
+    pub timestamped_at: u64,
+    pub template_claim: String,
+    pub holder_name_hash: String,
+    pub nullifier_scope: u8,
+    pub external_nullifier: String,
+    pub secret_commitment: String,
+    pub hash_suite: u8,
+    pub signer_cert_hash: String,
+    pub revealed: String,
+    pub vkey: String,
+    pub public_values: String,
+    pub proof: String,
+}
+
+impl SP1ZkPdfProofFixture {
+    /// Build a fixture from a Groth16 or PLONK proof and the program verification key. Public
+    /// values of a layout other than `PUBLIC_VALUES_VERSION` are rejected, since a contract
+    /// built from this tree could not decode them.
+    pub fn new(
+        proof: &SP1ProofWithPublicValues,
+        vk: &SP1VerifyingKey,
+    ) -> Result<Self, alloy_sol_types::Error> {
+        // Deserialize the public values.
+        let bytes = proof.public_values.as_slice();
+        let decoded = PublicValuesStruct::abi_decode(bytes, false)?;
+        if decoded.version != PUBLIC_VALUES_VERSION {
+            return Err(alloy_sol_types::Error::custom(format!(
+                "public values version {} is not {}",
+                decoded.version, PUBLIC_VALUES_VERSION
+            )));
+        }
+
+        Ok(Self {
+            version: decoded.version,
+            claim_type: decoded.claimType,
+            substring_matches: decoded.substringMatches,
+            message_digest_hash: format!("0x{}", hex::encode(decoded.messageDigestHash.as_slice())),
+            signer_key_hash: format!("0x{}", hex::encode(decoded.signerKeyHash.as_slice())),
+            substring_hash: format!("0x{}", hex::encode(decoded.substringHash.as_slice())),
+            nullifier: format!("0x{}", hex::encode(decoded.nullifier.as_slice())),
+            negated: decoded.negated,
+            date_claim: format!("0x{}", hex::encode(decoded.dateClaim.as_slice())),
+            page_count: decoded.pageCount,
+            pages_root: format!("0x{}", hex::encode(decoded.pagesRoot.as_slice())),
+            signer_subject_hash: format!("0x{}", hex::encode(decoded.signerSubjectHash.as_slice())),
+            not_before: decoded.notBefore,
+            not_after: decoded.notAfter,
+            timestamped_at: decoded.timestampedAt,
+            template_claim: format!("0x{}", hex::encode(decoded.templateClaim.as_slice())),
+            holder_name_hash: format!("0x{}", hex::encode(decoded.holderNameHash.as_slice())),
+            nullifier_scope: decoded.nullifierScope,
+            external_nullifier: format!("0x{}", hex::encode(decoded.externalNullifier.as_slice())),
+            secret_commitment: format!("0x{}", hex::encode(decoded.secretCommitment.as_slice())),
+            hash_suite: decoded.hashSuite,
+            signer_cert_hash: format!("0x{}", hex::encode(decoded.signerCertHash.as_slice())),
+            revealed: format!("0x{}", hex::encode(&decoded.revealed)),
+            vkey: vk.bytes32().to_string(),
+            public_values: format!("0x{}", hex::encode(bytes)),
+            proof: format!("0x{}", hex::encode(proof.bytes())),
+        })
+    }
+}
@@ -0,0 +1,4 @@
+//! Shared helpers for the zkPDF host binaries.
+
+pub mod claim; // Offsets of claimed substrings
+pub mod fixture; // Solidity test fixtures built from proofs
@@ -0,0 +1,368 @@
+//! `zkpdf`: trusted-verifier command line over `pdf_core`, without the proving toolchain.
+//! Every subcommand prints a JSON document to stdout. Errors go to stderr as `error[E<code>]: ...`
+//! with the exit status of that `zkpdf_errors::ErrorCode`.
+
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::{Args, Parser, Subcommand};
+use pdf_core::{
+    apply_template, extract_form_fields, extract_page_text, extract_text_with_options, find_text,
+    verify_pdf_timestamps, verify_signature_with_policy, ExtractOptions, FieldPattern,
+    Normalization, Template, VerificationPolicy,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use zkpdf_errors::{ErrorCode, ZkpdfError};
+
+#[derive(Parser)]
+#[command(
+    name = "zkpdf",
+    version,
+    about = "Extract text from and verify signed PDFs"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Extract the text of every page, or of a single page
+    Extract {
+        file: PathBuf,
+        /// Only extract this page (0-based)
+        #[arg(long)]
+        page: Option<usize>,
+        #[command(flatten)]
+        options: ExtractArgs,
+    },
+    /// Verify the embedded signature
+    VerifySignature {
+        file: PathBuf,
+        /// Report an invalid signature instead of failing
+        #[arg(long)]
+        allow_invalid_signature: bool,
+        /// Accept incremental updates after the signed range instead of failing
+        #[arg(long)]
+        allow_unsigned_updates: bool,
+    },
+    /// Find occurrences of a string, with the offsets to prove them at
+    Find {
+        file: PathBuf,
+        needle: String,
+        /// Only search this page (0-based)
+        #[arg(long)]
+        page: Option<usize>,
+        #[command(flatten)]
+        options: ExtractArgs,
+    },
+    /// List AcroForm fields with their types and values
+    Fields { file: PathBuf },
+    /// Verify the signature and extract the fields of a template
+    Template {
+        file: PathBuf,
+        /// JSON template `{"fields": [{"name", "pattern", "group"?}]}`; defaults to the GST
+        /// registration certificate template
+        #[arg(long)]
+        template: Option<PathBuf>,
+        /// Report an invalid signature instead of failing
+        #[arg(long)]
+        allow_invalid_signature: bool,
+        /// Accept incremental updates after the signed range instead of failing
+        #[arg(long)]
+        allow_unsigned_updates: bool,
+        #[command(flatten)]
+        options: ExtractArgs,
+    },
+}
+
+#[derive(Args)]
+struct ExtractArgs {
+    /// Keep the text as emitted by the content stream instead of collapsing whitespace.
+    /// Offsets from raw text cannot be proven
+    #[arg(long)]
+    raw: bool,
+    /// Rebuild lines from where the text is drawn on the page, so table rows and columns read
+    /// in order. Offsets from layout text cannot be proven
+    #[arg(long, conflicts_with = "raw")]
+    layout: bool,
+    /// Return pages that fail to load as empty strings, and skip objects that fail to parse
+    #[arg(long)]
+    lenient: bool,
+    /// Only extract the first N pages
+    #[arg(long, value_name = "N")]
+    max_pages: Option<usize>,
+    /// Read the document as of revision N (0 is the original) instead of the latest
+    /// incremental update
+    #[arg(long, value_name = "N")]
+    revision: Option<usize>,
+    /// Also extract the text drawn by annotation appearance streams, such as FreeText stamps
+    /// and filled-in form widgets
+    #[arg(long)]
+    annotations: bool,
+    /// Normalize the text to NFC, folding Arabic and Hebrew presentation forms to letters
+    #[arg(long)]
+    unicode_normalization: bool,
+    /// Reorder right-to-left runs from the order they were drawn in to logical order
+    #[arg(long)]
+    bidi: bool,
+}
+
+impl ExtractArgs {
+    fn extract_options(&self) -> ExtractOptions {
+        ExtractOptions {
+            normalization: if self.raw {
+                Normalization::Raw
+            } else if self.layout {
+                Normalization::Layout
+            } else {
+                Normalization::Canonical
+            },
+            lenient: self.lenient,
+            max_pages: self.max_pages,
+            revision: self.revision,
+            annotations: self.annotations,
+            unicode_normalization: self.unicode_normalization,
+            bidi: self.bidi,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TemplateFile {
+    fields: Vec<FieldFile>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FieldFile {
+    name: String,
+    pattern: String,
+    #[serde(default)]
+    group: usize,
+}
+
+fn read_file(path: &Path) -> Result<Vec<u8>, ZkpdfError> {
+    std::fs::read(path).map_err(|e| {
+        ZkpdfError::new(
+            ErrorCode::Io,
+            format!("failed to read {}: {}", path.display(), e),
+        )
+    })
+}
+
+fn load_template(path: Option<&Path>) -> Result<Template, ZkpdfError> {
+    let Some(path) = path else {
+        return Ok(Template::gst_certificate());
+    };
+    let template: TemplateFile = serde_json::from_slice(&read_file(path)?).map_err(|e| {
+        ZkpdfError::new(
+            ErrorCode::InvalidInput,
+            format!("invalid template {}: {}", path.display(), e),
+        )
+    })?;
+    Ok(Template {
+        fields: template
+            .fields
+            .into_iter()
+            .map(|field| FieldPattern {
+                name: field.name,
+                pattern: field.pattern,
+                group: field.group,
+            })
+            .collect(),
+    })
+}
+
+fn run(command: Command) -> Result<Value, ZkpdfError> {
+    match command {
+        Command::Extract {
+            file,
+            page: Some(page),
+            options,
+        } => {
+            let page_text = extract_page_text(&read_file(&file)?, page, &options.extract_options())
+                .map_err(pdf_core::Error::Extraction)?;
+            Ok(json!({
+                "page": page,
+                "page_count": page_text.page_count,
+                "text": page_text.text,
+            }))
+        }
+        Command::Extract {
+            file,
+            page: None,
+            options,
+        } => {
+            let pages = extract_text_with_options(read_file(&file)?, &options.extract_options())
+                .map_err(pdf_core::Error::Extraction)?;
+            Ok(json!({ "pages": pages }))
+        }
+        Command::VerifySignature {
+            file,
+            allow_invalid_signature,
+            allow_unsigned_updates,
+        } => {
+            let policy = VerificationPolicy {
+                allow_invalid_signature,
+                allow_unsigned_updates,
+                ..VerificationPolicy::default()
+            };
+            let pdf = read_file(&file)?;
+            let signature = verify_signature_with_policy(&pdf, &policy)?;
+            let timestamps: Vec<Value> = verify_pdf_timestamps(&pdf)
+                .map_err(pdf_core::Error::Signature)?
+                .iter()
+                .map(|t| {
+                    json!({
+                        "is_valid": t.is_valid,
+                        "gen_time": t.gen_time,
+                        "tsa": t.tsa.subject,
+                    })
+                })
+                .collect();
+            Ok(json!({
+                "is_valid": signature.is_valid,
+                "message_digest": hex::encode(&signature.message_digest),
+                "public_key": hex::encode(&signature.public_key),
+                "timestamps": timestamps,
+            }))
+        }
+        Command::Find {
+            file,
+            needle,
+            page,
+            options,
+        } => {
+            let pages = extract_text_with_options(read_file(&file)?, &options.extract_options())
+                .map_err(pdf_core::Error::Extraction)?;
+            let matches: Vec<Value> = find_text(&pages, &needle, page)
+                .into_iter()
+                .map(|m| json!({ "page": m.page, "offset": m.offset, "length": m.len }))
+                .collect();
+            Ok(json!({ "matches": matches }))
+        }
+        Command::Fields { file } => {
+            let fields: Vec<Value> = extract_form_fields(&read_file(&file)?)
+                .map_err(|e| {
+                    ZkpdfError::new((&e).into(), format!("failed to read form fields: {}", e))
+                })?
+                .into_iter()
+                .map(|field| {
+                    json!({
+                        "name": field.name,
+                        "type": field.field_type.to_string(),
+                        "value": field.value,
+                    })
+                })
+                .collect();
+            Ok(json!({ "fields": fields }))
+        }
+        Command::Template {
+            file,
+            template,
+            allow_invalid_signature,
+            allow_unsigned_updates,
+            options,
+        } => {
+            let template = load_template(template.as_deref())?;
+            let policy = VerificationPolicy {
+                allow_invalid_signature,
+                allow_unsigned_updates,
+                ..VerificationPolicy::default()
+            };
+            let result = apply_template(
+                read_file(&file)?,
+                &template,
+                &options.extract_options(),
+                &policy,
+            )?;
+            let fields: Vec<Value> = result
+                .fields
+                .into_iter()
+                .map(|field| {
+                    json!({
+                        "name": field.name,
+                        "value": field.value,
+                        "page": field.page,
+                        "offset": field.offset,
+                    })
+                })
+                .collect();
+            Ok(json!({
+                "fields": fields,
+                "signature": {
+                    "is_valid": result.signature.is_valid,
+                    "message_digest": hex::encode(&result.signature.message_digest),
+                    "public_key": hex::encode(&result.signature.public_key),
+                },
+            }))
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(cli.command) {
+        Ok(output) => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&output).expect("JSON values always serialize")
+            );
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::from(e.code.exit_code())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_args(args: &[&str]) -> Result<Value, ZkpdfError> {
+        let cli = Cli::try_parse_from(std::iter::once("zkpdf").chain(args.iter().copied()))
+            .expect("invalid arguments");
+        run(cli.command)
+    }
+
+    fn sample(name: &str) -> String {
+        format!("{}/../sample-pdfs/{}", env!("CARGO_MANIFEST_DIR"), name)
+    }
+
+    #[test]
+    fn extract_and_find_agree() {
+        let pdf = sample("digitally_signed.pdf");
+
+        let extracted = run_args(&["extract", &pdf]).unwrap();
+        assert_eq!(extracted["pages"][0], "Sample Signed PDF Document");
+
+        let found = run_args(&["find", &pdf, "PDF"]).unwrap();
+        assert_eq!(
+            found["matches"],
+            json!([{ "page": 0, "offset": 14, "length": 3 }])
+        );
+    }
+
+    #[test]
+    fn verify_signature_and_template() {
+        let signed = run_args(&["verify-signature", &sample("digitally_signed.pdf")]).unwrap();
+        assert_eq!(signed["is_valid"], true);
+        assert_eq!(signed["timestamps"], json!([]));
+
+        let gst = run_args(&["template", &sample("GST-certificate.pdf")]).unwrap();
+        assert_eq!(gst["fields"][0]["value"], "07AAATC0869P1ZB");
+        assert_eq!(gst["signature"]["is_valid"], true);
+    }
+
+    #[test]
+    fn missing_file_is_an_error() {
+        let err = run_args(&["fields", "does-not-exist.pdf"]).unwrap_err();
+        assert_eq!(err.code, ErrorCode::Io);
+        assert_eq!(err.code.exit_code(), 2);
+    }
+}
@@ -0,0 +1,113 @@
+//! Stage-by-stage timings over the corpus in `support`: object parsing, stream decompression,
+//! text decoding, signature verification and the end-to-end `verify_and_extract`. Throughput is
+//! reported in bytes of input PDF.
+//!
+//! `cargo bench -p core`
+
+mod support;
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+// The library is named `core`; cargo's `--extern` makes that path resolve to it, not libcore
+use core::verify_and_extract;
+use extractor::{extract_text, extract_text_from_document, parse_pdf, types::PdfObj};
+use miniz_oxide::inflate::decompress_to_vec_zlib;
+use signature_validator::verify_pdf_signature;
+
+use support::{corpus, Document};
+
+fn flate_streams(document: &Document) -> Vec<Vec<u8>> {
+    let (_, objects) = parse_pdf(&document.bytes).expect("corpus document must parse");
+    objects
+        .into_values()
+        .filter_map(|obj| match obj {
+            PdfObj::Stream(stream) => match stream.dict.get("Filter") {
+                Some(PdfObj::Name(filter)) if filter == "FlateDecode" => {
+                    Some(stream.data.into_owned())
+                }
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect()
+}
+
+fn bench_stages(c: &mut Criterion) {
+    let corpus = corpus();
+    for document in &corpus {
+        let pages = extract_text(document.bytes.clone()).expect("corpus document must extract");
+        assert!(
+            pages.iter().any(|page| !page.is_empty()),
+            "{} extracted no text",
+            document.name
+        );
+    }
+
+    let mut group = c.benchmark_group("parse");
+    for document in &corpus {
+        group.throughput(Throughput::Bytes(document.bytes.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(document.name),
+            &document.bytes,
+            |b, bytes| b.iter(|| parse_pdf(black_box(bytes)).unwrap()),
+        );
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("decompress");
+    for document in &corpus {
+        let streams = flate_streams(document);
+        let compressed: usize = streams.iter().map(Vec::len).sum();
+        group.throughput(Throughput::Bytes(compressed as u64));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(document.name),
+            &streams,
+            |b, streams| {
+                b.iter(|| {
+                    for stream in streams {
+                        black_box(decompress_to_vec_zlib(stream).ok());
+                    }
+                })
+            },
+        );
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("decode");
+    for document in &corpus {
+        let parsed = parse_pdf(&document.bytes).unwrap();
+        group.throughput(Throughput::Bytes(document.bytes.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(document.name),
+            &parsed,
+            |b, (pages, objects)| {
+                b.iter(|| extract_text_from_document(black_box(pages), objects).unwrap())
+            },
+        );
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("verify_signature");
+    for document in corpus.iter().filter(|document| document.signed) {
+        group.throughput(Throughput::Bytes(document.bytes.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(document.name),
+            &document.bytes,
+            |b, bytes| b.iter(|| verify_pdf_signature(black_box(bytes)).unwrap()),
+        );
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("verify_and_extract");
+    for document in corpus.iter().filter(|document| document.signed) {
+        group.throughput(Throughput::Bytes(document.bytes.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(document.name),
+            &document.bytes,
+            |b, bytes| b.iter(|| verify_and_extract(black_box(bytes.clone())).unwrap()),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_stages);
+criterion_main!(benches);
@@ -0,0 +1,182 @@
+//! Benchmark corpus: the signed samples plus generated documents for the shapes we have no
+//! shareable sample of (long statements, CID-keyed fonts).
+
+use miniz_oxide::deflate::compress_to_vec_zlib;
+
+pub struct Document {
+    pub name: &'static str,
+    pub bytes: Vec<u8>,
+    /// Whether the document carries a signature that verifies.
+    pub signed: bool,
+}
+
+pub fn corpus() -> Vec<Document> {
+    vec![
+        Document {
+            name: "gst_certificate",
+            bytes: include_bytes!("../../../sample-pdfs/GST-certificate.pdf").to_vec(),
+            signed: true,
+        },
+        Document {
+            name: "digitally_signed",
+            bytes: include_bytes!("../../../sample-pdfs/digitally_signed.pdf").to_vec(),
+            signed: true,
+        },
+        Document {
+            name: "statement_50_pages",
+            bytes: statement(50, 48),
+            signed: false,
+        },
+        Document {
+            name: "cid_font",
+            bytes: cid_font_document(10, 40),
+            signed: false,
+        },
+    ]
+}
+
+/// Writes `objects` (numbered from 1) as a PDF with a classic cross-reference table. Object 1
+/// must be the catalog.
+fn build_pdf(objects: &[Vec<u8>]) -> Vec<u8> {
+    let mut pdf = b"%PDF-1.7\n%\xE2\xE3\xCF\xD3\n".to_vec();
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (index, body) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.extend_from_slice(format!("{} 0 obj\n", index + 1).as_bytes());
+        pdf.extend_from_slice(body);
+        pdf.extend_from_slice(b"\nendobj\n");
+    }
+
+    let xref = pdf.len();
+    pdf.extend_from_slice(
+        format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).as_bytes(),
+    );
+    for offset in offsets {
+        pdf.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+    }
+    pdf.extend_from_slice(
+        format!(
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            xref
+        )
+        .as_bytes(),
+    );
+    pdf
+}
+
+fn flate_stream(data: &[u8], extra: &str) -> Vec<u8> {
+    let compressed = compress_to_vec_zlib(data, 6);
+    let mut body = format!(
+        "<< /Length {} /Filter /FlateDecode{} >>\nstream\n",
+        compressed.len(),
+        extra
+    )
+    .into_bytes();
+    body.extend_from_slice(&compressed);
+    body.extend_from_slice(b"\nendstream");
+    body
+}
+
+/// Catalog, page tree, one font object per entry of `fonts` and one page plus content stream
+/// per entry of `contents`. Pages reference the first font as `/F1`.
+fn paged_document(fonts: Vec<Vec<u8>>, contents: Vec<Vec<u8>>) -> Vec<u8> {
+    let font_id = 3;
+    let first_page = font_id + fonts.len();
+    let kids: Vec<String> = (0..contents.len())
+        .map(|i| format!("{} 0 R", first_page + 2 * i))
+        .collect();
+
+    let mut objects = vec![
+        b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            kids.join(" "),
+            contents.len()
+        )
+        .into_bytes(),
+    ];
+    objects.extend(fonts);
+    for (i, content) in contents.iter().enumerate() {
+        let page_id = first_page + 2 * i;
+        objects.push(
+            format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 612 792] \
+                 /Resources << /Font << /F1 {} 0 R >> >> /Contents {} 0 R >>",
+                font_id,
+                page_id + 1
+            )
+            .into_bytes(),
+        );
+        objects.push(flate_stream(content, ""));
+    }
+    build_pdf(&objects)
+}
+
+/// A bank-statement-like document: `pages` pages of `lines` transaction rows in Helvetica.
+pub fn statement(pages: usize, lines: usize) -> Vec<u8> {
+    let font = b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>"
+        .to_vec();
+    let contents = (0..pages)
+        .map(|page| {
+            let mut content = format!(
+                "BT /F1 12 Tf 50 760 Td (Account statement, page {} of {}) Tj ET\n",
+                page + 1,
+                pages
+            );
+            content.push_str("BT /F1 9 Tf 50 730 Td 12 TL\n");
+            for line in 0..lines {
+                let n = page * lines + line;
+                content.push_str(&format!(
+                    "(2024-{:02}-{:02}  UPI/{:010}/PAYMENT TO MERCHANT {:04}  {:>9}.{:02} DR) '\n",
+                    n % 12 + 1,
+                    n % 28 + 1,
+                    n * 7919,
+                    n % 9973,
+                    n * 37 % 100_000,
+                    n % 100
+                ));
+            }
+            content.push_str("ET\n");
+            content.into_bytes()
+        })
+        .collect();
+    paged_document(vec![font], contents)
+}
+
+/// A document using a Type0 font with Identity-H encoding and a ToUnicode CMap, as produced for
+/// CJK and Indic scripts: every glyph is a two-byte CID mapped to a CJK ideograph.
+pub fn cid_font_document(pages: usize, lines: usize) -> Vec<u8> {
+    const GLYPHS: u16 = 200;
+    let cmap = format!(
+        "/CIDInit /ProcSet findresource begin\n12 dict begin\nbegincmap\n\
+         /CMapName /Adobe-Identity-UCS def\n/CMapType 2 def\n\
+         1 begincodespacerange\n<0000> <FFFF>\nendcodespacerange\n\
+         1 beginbfrange\n<0001> <{:04X}> <4E00>\nendbfrange\n\
+         endcmap\nCMapName currentdict /CMap defineresource pop\nend\nend\n",
+        GLYPHS
+    );
+    let fonts = vec![
+        b"<< /Type /Font /Subtype /Type0 /BaseFont /NotoSansCJK-Regular /Encoding /Identity-H \
+          /DescendantFonts [4 0 R] /ToUnicode 5 0 R >>"
+            .to_vec(),
+        b"<< /Type /Font /Subtype /CIDFontType2 /BaseFont /NotoSansCJK-Regular \
+          /CIDSystemInfo << /Registry (Adobe) /Ordering (Identity) /Supplement 0 >> /DW 1000 >>"
+            .to_vec(),
+        flate_stream(cmap.as_bytes(), ""),
+    ];
+    let contents = (0..pages)
+        .map(|page| {
+            let mut content = String::from("BT /F1 10 Tf 50 760 Td 14 TL\n");
+            for line in 0..lines {
+                let hex: String = (0..30)
+                    .map(|i| format!("{:04X}", (page * 131 + line * 17 + i) as u16 % GLYPHS + 1))
+                    .collect();
+                content.push_str(&format!("<{}> '\n", hex));
+            }
+            content.push_str("ET\n");
+            content.into_bytes()
+        })
+        .collect();
+    paged_document(fonts, contents)
+}
@@ -0,0 +1,24 @@
+//! Encrypts a fixture for the golden corpus, or decrypts one for inspection.
+//!
+//! `ZKPDF_GOLDEN_KEY=... cargo run -p core --example golden_crypt -- input.pdf golden-corpus/name.pdf.enc`
+
+#[path = "../tests/golden_support/mod.rs"]
+mod golden_support;
+
+use golden_support::{apply_keystream, KEY_VAR};
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let [input, output] = args.as_slice() else {
+        eprintln!("usage: golden_crypt <input> <output>");
+        std::process::exit(2);
+    };
+    let Ok(passphrase) = std::env::var(KEY_VAR) else {
+        eprintln!("{} is not set", KEY_VAR);
+        std::process::exit(2);
+    };
+
+    let mut data = std::fs::read(input).expect("failed to read input");
+    apply_keystream(&passphrase, &mut data);
+    std::fs::write(output, data).expect("failed to write output");
+}
@@ -0,0 +1,221 @@
+//! UIDAI offline e-KYC: an `OfflinePaperlessKyc` XML document signed by UIDAI, which the
+//! holder downloads as a ZIP encrypted with a share code of their choosing. The signature
+//! carries no KeyInfo, so it is checked against UIDAI's published certificate. The mobile
+//! number and email address are not in the document, only salted hashes of them, which
+//! `OfflineKyc::matches` checks a claimed value against.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use sha2::{Digest, Sha256};
+use zkpdf_xml_validator::{verify_xml_with_certificate, Element, VerifiedXml, XmlSignatureResult};
+
+use crate::zip::{self, ZipError};
+use crate::Error;
+
+const REFERENCE_ID: &str = "OfflinePaperlessKyc@referenceId";
+
+/// The fields claims can name, with the attribute each is read from. `mobile` and `email`
+/// hold hashes, see `demographic_hash`.
+pub const FIELDS: [(&str, &str); 9] = [
+    ("name", "OfflinePaperlessKyc/UidData/Poi@name"),
+    ("date_of_birth", "OfflinePaperlessKyc/UidData/Poi@dob"),
+    ("gender", "OfflinePaperlessKyc/UidData/Poi@gender"),
+    ("mobile", "OfflinePaperlessKyc/UidData/Poi@m"),
+    ("email", "OfflinePaperlessKyc/UidData/Poi@e"),
+    ("care_of", "OfflinePaperlessKyc/UidData/Poa@careof"),
+    ("district", "OfflinePaperlessKyc/UidData/Poa@dist"),
+    ("state", "OfflinePaperlessKyc/UidData/Poa@state"),
+    ("pincode", "OfflinePaperlessKyc/UidData/Poa@pc"),
+];
+
+/// The attribute selector of the field `name`.
+pub fn selector(name: &str) -> Option<&'static str> {
+    FIELDS
+        .iter()
+        .find(|(field, _)| *field == name)
+        .map(|(_, selector)| *selector)
+}
+
+/// A verified offline e-KYC document.
+#[derive(Debug, Clone)]
+pub struct OfflineKyc {
+    pub xml: VerifiedXml,
+}
+
+impl OfflineKyc {
+    pub fn signature(&self) -> &XmlSignatureResult {
+        &self.xml.signature
+    }
+
+    /// The reference id: the last four digits of the Aadhaar number, then the download time
+    /// as `yyyyMMddHHmmssSSS`.
+    pub fn reference_id(&self) -> &str {
+        // Checked by `verify_offline_kyc`
+        self.xml.attribute(REFERENCE_ID).unwrap_or_default()
+    }
+
+    /// The value of the field `name`, as the document holds it.
+    pub fn field(&self, name: &str) -> Option<&str> {
+        self.xml.attribute(selector(name)?)
+    }
+
+    /// The holder's photograph, a base64 JPEG.
+    pub fn photo(&self) -> Option<String> {
+        let uid_data = child(&self.xml.document.root, "UidData")?;
+        Some(child(uid_data, "Pht")?.text())
+    }
+
+    /// Whether the field `name` has the value `value`. For `mobile` and `email` that is whether
+    /// `value` hashes, with `share_code`, to the hash the document holds; for the other fields
+    /// `share_code` is unused.
+    pub fn matches(&self, name: &str, value: &str, share_code: &str) -> bool {
+        let Some(held) = self.field(name) else {
+            return false;
+        };
+        match name {
+            "mobile" | "email" => {
+                let Some(digit) = self
+                    .reference_id()
+                    .chars()
+                    .nth(3)
+                    .and_then(|c| c.to_digit(10))
+                else {
+                    return false;
+                };
+                demographic_hash(value, share_code, digit as u8).eq_ignore_ascii_case(held)
+            }
+            _ => held == value,
+        }
+    }
+}
+
+fn child<'a>(element: &'a Element, local_name: &str) -> Option<&'a Element> {
+    element
+        .elements()
+        .find(|child| child.local_name() == local_name)
+}
+
+/// The hash offline e-KYC holds for a mobile number or email address: SHA-256 of the value
+/// followed by the share code, then SHA-256 of the hex digest, as many times in all as
+/// `last_digit`, the last digit of the Aadhaar number, says; once for 0 or 1. Lowercase hex.
+pub fn demographic_hash(value: &str, share_code: &str, last_digit: u8) -> String {
+    let mut hash = String::with_capacity(64);
+    hash.push_str(value);
+    hash.push_str(share_code);
+    for _ in 0..last_digit.max(1) {
+        let digest = Sha256::digest(hash.as_bytes());
+        hash.clear();
+        for byte in digest {
+            let _ = write!(hash, "{:02x}", byte);
+        }
+    }
+    hash
+}
+
+/// The XML of an offline e-KYC download: the `.xml` entry of the ZIP, decrypted with
+/// `share_code`, or `data` itself if it is not a ZIP, for holders who extracted it already.
+pub fn open_offline_kyc(data: &[u8], share_code: &str) -> Result<Vec<u8>, Error> {
+    if !data.starts_with(b"PK\x03\x04") {
+        return Ok(data.to_vec());
+    }
+    let entry = zip::entries(data)?
+        .into_iter()
+        .find(|entry| entry.name.to_ascii_lowercase().ends_with(".xml"))
+        .ok_or_else(|| ZipError::NotFound("*.xml".to_string()))?;
+    Ok(zip::read(data, &entry, share_code.as_bytes())?)
+}
+
+/// Opens an offline e-KYC download with `open_offline_kyc` and verifies its signature with the
+/// key of `certificate`, UIDAI's DER certificate. A signature that does not verify is
+/// `Error::InvalidSignature`; a document that is not signed as a whole, or has no reference
+/// id starting with four digits, is `Error::FieldNotFound`.
+pub fn verify_offline_kyc(
+    data: &[u8],
+    share_code: &str,
+    certificate: &[u8],
+) -> Result<OfflineKyc, Error> {
+    let xml = open_offline_kyc(data, share_code)?;
+    let verified = verify_xml_with_certificate(&xml, certificate)?;
+    if !verified.signature.is_valid {
+        return Err(Error::InvalidSignature);
+    }
+    // The photo is read outside `VerifiedXml::attribute`, so the whole document must be signed
+    let whole = verified
+        .signature
+        .references
+        .iter()
+        .any(|uri| uri.is_empty());
+    let reference_id = verified.attribute(REFERENCE_ID).unwrap_or_default();
+    let digits = reference_id
+        .bytes()
+        .take(4)
+        .filter(u8::is_ascii_digit)
+        .count();
+    if !whole || digits < 4 {
+        return Err(Error::FieldNotFound(REFERENCE_ID.to_string()));
+    }
+    Ok(OfflineKyc { xml: verified })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static ARCHIVE: &[u8] = include_bytes!("../../xml-validator/tests/data/offline_ekyc.zip");
+    static XML: &[u8] = include_bytes!("../../xml-validator/tests/data/offline_ekyc.xml");
+    static CERTIFICATE: &[u8] =
+        include_bytes!("../../xml-validator/tests/data/uidai_test_certificate.der");
+
+    #[test]
+    fn test_verify_offline_kyc() {
+        let kyc = verify_offline_kyc(ARCHIVE, "Ab12", CERTIFICATE).unwrap();
+        assert!(kyc.signature().is_valid);
+        assert_eq!(kyc.reference_id(), "567820190308112233444");
+        assert_eq!(kyc.field("name"), Some("Asha Kumari"));
+        assert_eq!(kyc.field("pincode"), Some("411005"));
+        assert_eq!(kyc.field("aadhaar"), None);
+        assert_eq!(kyc.photo().as_deref(), Some("/9j/4CB0ZXN0IHBob3RvIP/Z"));
+
+        assert!(kyc.matches("gender", "F", ""));
+        assert!(!kyc.matches("gender", "M", ""));
+        // The Aadhaar number ends in 8, so the hashes are chained eight times
+        assert!(kyc.matches("mobile", "9876543210", "Ab12"));
+        assert!(kyc.matches("email", "asha@example.com", "Ab12"));
+        assert!(!kyc.matches("mobile", "9876543211", "Ab12"));
+        assert!(!kyc.matches("mobile", "9876543210", "Ab13"));
+        assert!(!kyc.matches("email", "9876543210", "Ab12"));
+
+        // Extracted XML verifies too, and the share code is then only the hash salt
+        let extracted = verify_offline_kyc(XML, "", CERTIFICATE).unwrap();
+        assert_eq!(extracted.reference_id(), kyc.reference_id());
+
+        assert!(matches!(
+            verify_offline_kyc(ARCHIVE, "0000", CERTIFICATE),
+            Err(Error::Zip(ZipError::WrongPassword))
+        ));
+        let tampered = core::str::from_utf8(XML)
+            .unwrap()
+            .replacen("411005", "411006", 1);
+        assert!(matches!(
+            verify_offline_kyc(tampered.as_bytes(), "", CERTIFICATE),
+            Err(Error::Xml(_))
+        ));
+    }
+
+    #[test]
+    fn test_demographic_hash() {
+        let once = demographic_hash("9876543210", "Ab12", 1);
+        assert_eq!(
+            once,
+            "fc01fae42add31992ebe7ad5b4c54939485dcaaa096c7679bb819122dd731f62"
+        );
+        assert_eq!(demographic_hash("9876543210", "Ab12", 0), once);
+        let mut twice = String::new();
+        for byte in Sha256::digest(once.as_bytes()) {
+            write!(twice, "{:02x}", byte).unwrap();
+        }
+        assert_eq!(demographic_hash("9876543210", "Ab12", 2), twice);
+    }
+}
@@ -0,0 +1,276 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use regex::Regex;
+
+#[cfg(feature = "std")]
+use crate::date::Date;
+use crate::{
+    extract_form_fields, extract_text_with_options, verify_signature_with_policy, Error,
+    ExtractOptions, FormField, MatchMode, PdfSignatureResult, VerificationPolicy,
+};
+
+/// A statement about a signed document, checked by `verify_claims`. Text claims are about the
+/// extracted page text, form field claims about the AcroForm fields.
+#[derive(Debug, Clone)]
+pub enum Claim {
+    /// `substring` appears at `offset` on `page`, as `verify_text` checks it with the same
+    /// `MatchMode`.
+    Substring {
+        page: usize,
+        substring: String,
+        offset: usize,
+    },
+    /// `substring` appears on `page`, or on any page when `None`. The match is the first
+    /// occurrence in page order; its offset indexes the page text as the `MatchMode`
+    /// canonicalizes it.
+    SubstringAnywhere {
+        substring: String,
+        page: Option<usize>,
+    },
+    /// The form field whose fully qualified name is `field` has the value `value`, compared in
+    /// the `MatchMode`. A field that does not exist is an error; one without a value does not
+    /// hold.
+    FieldEquals { field: String, value: String },
+    /// `pattern` matches on `page`, or on any page when `None`. Needs the `std` feature.
+    #[cfg(feature = "std")]
+    Regex {
+        pattern: String,
+        page: Option<usize>,
+    },
+    /// The number `pattern` captures on `page`, or on the first page it matches when `None`,
+    /// lies within `min..=max`; a missing bound is open. The captured value is the first group
+    /// if the pattern has one, as `verify_regex` reads it, and its `,` and space separators
+    /// are ignored, so `1,20,000.50` is 120000.5. Needs the `std` feature.
+    #[cfg(feature = "std")]
+    NumericRange {
+        pattern: String,
+        page: Option<usize>,
+        min: Option<f64>,
+        max: Option<f64>,
+    },
+    /// The date `pattern` captures, read the way `verify_regex` reads it, is strictly earlier
+    /// than `before`. The date may be a PDF date or `dd/mm/yyyy` text, as `Date::parse` reads
+    /// it. Needs the `std` feature.
+    #[cfg(feature = "std")]
+    DateBefore {
+        pattern: String,
+        page: Option<usize>,
+        before: Date,
+    },
+}
+
+/// Where a claim matched. For regex claims `value` is the first match, in page order, and for
+/// numeric and date claims the captured value. `page` and `offset` are `None` for form field
+/// claims, whose `value` is the field's value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClaimMatch {
+    pub page: Option<usize>,
+    pub offset: Option<usize>,
+    pub value: String,
+}
+
+impl ClaimMatch {
+    fn at(page: usize, offset: usize, value: &str) -> Self {
+        Self {
+            page: Some(page),
+            offset: Some(offset),
+            value: value.into(),
+        }
+    }
+}
+
+/// Result returned by `verify_claims`, with one entry per claim in input order.
+#[derive(Debug)]
+pub struct ClaimsVerification {
+    /// `Ok(None)` if the claim does not hold, `Err` if it could not be evaluated (invalid
+    /// pattern, page out of bounds, unknown form field).
+    pub results: Vec<Result<Option<ClaimMatch>, Error>>,
+    pub signature: PdfSignatureResult,
+}
+
+impl Claim {
+    /// Evaluates the claim against the text of every page.
+    pub fn check(&self, pages: &[String]) -> Result<Option<ClaimMatch>, Error> {
+        self.check_with_mode(pages, MatchMode::Exact)
+    }
+
+    /// `check`, comparing substring claims in `mode`. Regex claims are unaffected. Form field
+    /// claims are checked against no fields; use `check_document` for those.
+    pub fn check_with_mode(
+        &self,
+        pages: &[String],
+        mode: MatchMode,
+    ) -> Result<Option<ClaimMatch>, Error> {
+        self.check_document(pages, &[], mode)
+    }
+
+    /// Evaluates the claim against the text of every page and the document's form fields,
+    /// as `extract_form_fields` returns them.
+    pub fn check_document(
+        &self,
+        pages: &[String],
+        fields: &[FormField],
+        mode: MatchMode,
+    ) -> Result<Option<ClaimMatch>, Error> {
+        match self {
+            Claim::Substring {
+                page,
+                substring,
+                offset,
+            } => {
+                let text = page_text(pages, *page)?;
+                let matches = mode.matches_at(text, substring, *offset);
+                Ok(matches.then(|| ClaimMatch::at(*page, *offset, substring)))
+            }
+            Claim::SubstringAnywhere { substring, page } => {
+                let needle = mode.canonicalize(substring);
+                Ok(candidates(pages, *page)?
+                    .into_iter()
+                    .find_map(|(page, text)| {
+                        let offset = mode.canonicalize(text).find(needle.as_ref())?;
+                        Some(ClaimMatch::at(page, offset, substring))
+                    }))
+            }
+            Claim::FieldEquals { field, value } => {
+                let found = fields
+                    .iter()
+                    .find(|candidate| candidate.name == *field)
+                    .ok_or_else(|| Error::FieldNotFound(field.clone()))?;
+                Ok(found
+                    .value
+                    .as_ref()
+                    .filter(|actual| mode.canonicalize(actual) == mode.canonicalize(value))
+                    .map(|actual| ClaimMatch {
+                        page: None,
+                        offset: None,
+                        value: actual.clone(),
+                    }))
+            }
+            #[cfg(feature = "std")]
+            Claim::Regex { pattern, page } => {
+                let regex = compile(pattern)?;
+                Ok(candidates(pages, *page)?
+                    .into_iter()
+                    .find_map(|(page, text)| {
+                        regex
+                            .find(text)
+                            .map(|m| ClaimMatch::at(page, m.start(), m.as_str()))
+                    }))
+            }
+            #[cfg(feature = "std")]
+            Claim::NumericRange {
+                pattern,
+                page,
+                min,
+                max,
+            } => {
+                let found = capture(&compile(pattern)?, pages, *page)?;
+                Ok(found.filter(|found| {
+                    parse_number(&found.value).is_some_and(|number| {
+                        min.is_none_or(|min| number >= min) && max.is_none_or(|max| number <= max)
+                    })
+                }))
+            }
+            #[cfg(feature = "std")]
+            Claim::DateBefore {
+                pattern,
+                page,
+                before,
+            } => {
+                let found = capture(&compile(pattern)?, pages, *page)?;
+                Ok(found
+                    .filter(|found| Date::parse(&found.value).is_some_and(|date| date < *before)))
+            }
+        }
+    }
+
+    fn needs_fields(&self) -> bool {
+        matches!(self, Claim::FieldEquals { .. })
+    }
+}
+
+fn page_text(pages: &[String], page: usize) -> Result<&String, Error> {
+    pages.get(page).ok_or(Error::PageOutOfBounds {
+        page,
+        total: pages.len(),
+    })
+}
+
+/// `page` with its text, or every page when `None`.
+fn candidates(pages: &[String], page: Option<usize>) -> Result<Vec<(usize, &String)>, Error> {
+    match page {
+        Some(page) => Ok(alloc::vec![(page, page_text(pages, page)?)]),
+        None => Ok(pages.iter().enumerate().collect()),
+    }
+}
+
+#[cfg(feature = "std")]
+fn compile(pattern: &str) -> Result<Regex, Error> {
+    Regex::new(pattern).map_err(|e| Error::InvalidPattern {
+        field: None,
+        message: e.to_string(),
+    })
+}
+
+/// The first match of `regex` in page order, as the value `verify_regex` commits to: the first
+/// group if the pattern has one and it matched, the whole match otherwise.
+#[cfg(feature = "std")]
+fn capture(
+    regex: &Regex,
+    pages: &[String],
+    page: Option<usize>,
+) -> Result<Option<ClaimMatch>, Error> {
+    Ok(candidates(pages, page)?
+        .into_iter()
+        .find_map(|(page, text)| {
+            let captures = regex.captures(text)?;
+            let value = captures.get(1).or_else(|| captures.get(0))?;
+            Some(ClaimMatch::at(page, value.start(), value.as_str()))
+        }))
+}
+
+/// A decimal number with optional sign, ignoring `,` and space digit-group separators.
+#[cfg(feature = "std")]
+pub fn parse_number(text: &str) -> Option<f64> {
+    let digits: String = text
+        .trim()
+        .chars()
+        .filter(|c| *c != ',' && *c != ' ')
+        .collect();
+    let valid = !digits.is_empty()
+        && digits
+            .trim_start_matches(['-', '+'])
+            .chars()
+            .all(|c| c.is_ascii_digit() || c == '.');
+    valid.then(|| digits.parse().ok()).flatten()
+}
+
+/// Verifies the PDF's signature once, extracts its text once, reads its form fields if a claim
+/// is about one, and evaluates every claim against them. Fails only if the signature or
+/// extraction fails; claim errors are reported per claim.
+pub fn verify_claims(
+    pdf_bytes: Vec<u8>,
+    claims: &[Claim],
+    options: &ExtractOptions,
+    policy: &VerificationPolicy,
+) -> Result<ClaimsVerification, Error> {
+    // Step 1: verify signature
+    let signature = verify_signature_with_policy(&pdf_bytes, policy)?;
+
+    // Step 2: extract the form fields if needed, and the text
+    let fields = if claims.iter().any(Claim::needs_fields) {
+        extract_form_fields(&pdf_bytes)?
+    } else {
+        Vec::new()
+    };
+    let pages = extract_text_with_options(pdf_bytes, options)?;
+
+    // Step 3: evaluate every claim
+    let results = claims
+        .iter()
+        .map(|claim| claim.check_document(&pages, &fields, policy.match_mode))
+        .collect();
+
+    Ok(ClaimsVerification { results, signature })
+}
@@ -0,0 +1,125 @@
+//! Calendar dates as signed documents write them: PDF dates (`D:YYYYMMDD...`) and
+//! `dd/mm/yyyy` text, and the ISO 8601 timestamps certificate validity is reported in.
+
+/// A calendar date. Dates order chronologically.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Date {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+}
+
+impl Date {
+    /// `None` unless `day` exists in `month` of `year`.
+    pub fn new(year: u16, month: u8, day: u8) -> Option<Self> {
+        let valid = (1..=12).contains(&month) && day >= 1 && day <= days_in_month(year, month);
+        valid.then_some(Self { year, month, day })
+    }
+
+    /// A PDF date string, `D:YYYYMMDDHHmmSSOHH'mm'`. Month and day may be left out and default
+    /// to 01; the time and time zone are ignored. Many writers omit the `D:` prefix.
+    pub fn parse_pdf(text: &str) -> Option<Self> {
+        let text = text.trim();
+        let text = text.strip_prefix("D:").unwrap_or(text);
+        let digits = text.bytes().take_while(u8::is_ascii_digit).count();
+        // Only a time zone may follow the digits, and only after a full time
+        let rest = &text[digits..];
+        if !(rest.is_empty() || digits >= 14 && rest.starts_with(['Z', '+', '-'])) {
+            return None;
+        }
+        let field = |range: core::ops::Range<usize>| text[range].parse::<u16>().ok();
+        let (year, month, day) = match digits {
+            4 => (field(0..4)?, 1, 1),
+            6 => (field(0..4)?, field(4..6)?, 1),
+            8.. => (field(0..4)?, field(4..6)?, field(6..8)?),
+            _ => return None,
+        };
+        Self::new(year, u8::try_from(month).ok()?, u8::try_from(day).ok()?)
+    }
+
+    /// `dd/mm/yyyy`, with one-digit days and months allowed and `-` or `.` also accepted as
+    /// the separator.
+    pub fn parse_dmy(text: &str) -> Option<Self> {
+        let mut parts = text.trim().split(['/', '-', '.']);
+        let (day, month, year) = (parts.next()?, parts.next()?, parts.next()?);
+        let digits = |part: &str, lengths: core::ops::RangeInclusive<usize>| {
+            lengths.contains(&part.len()) && part.bytes().all(|b| b.is_ascii_digit())
+        };
+        if parts.next().is_some() || !digits(day, 1..=2) || !digits(month, 1..=2) {
+            return None;
+        }
+        if !digits(year, 4..=4) {
+            return None;
+        }
+        Self::new(year.parse().ok()?, month.parse().ok()?, day.parse().ok()?)
+    }
+
+    /// `parse_pdf` or `parse_dmy`, whichever format `text` is in.
+    pub fn parse(text: &str) -> Option<Self> {
+        Self::parse_pdf(text).or_else(|| Self::parse_dmy(text))
+    }
+
+    /// The date as the number YYYYMMDD, the form public values commit to.
+    pub fn to_yyyymmdd(self) -> u32 {
+        u32::from(self.year) * 10_000 + u32::from(self.month) * 100 + u32::from(self.day)
+    }
+}
+
+/// Seconds since the Unix epoch of an ISO 8601 UTC timestamp, `YYYY-MM-DDTHH:MM:SSZ`, the
+/// form certificate validity is reported in. `None` for other forms and for times before 1970.
+pub fn unix_timestamp(text: &str) -> Option<u64> {
+    let bytes = text.as_bytes();
+    let separators = [
+        (4, b'-'),
+        (7, b'-'),
+        (10, b'T'),
+        (13, b':'),
+        (16, b':'),
+        (19, b'Z'),
+    ];
+    if bytes.len() != 20 || separators.iter().any(|&(i, b)| bytes[i] != b) {
+        return None;
+    }
+    let field = |range: core::ops::Range<usize>| {
+        let part = &text[range];
+        part.bytes()
+            .all(|b| b.is_ascii_digit())
+            .then(|| part.parse::<u16>().ok())
+            .flatten()
+    };
+    let date = Date::new(
+        field(0..4)?,
+        u8::try_from(field(5..7)?).ok()?,
+        u8::try_from(field(8..10)?).ok()?,
+    )?;
+    let (hour, minute, second) = (field(11..13)?, field(14..16)?, field(17..19)?);
+    if date.year < 1970 || hour > 23 || minute > 59 || second > 59 {
+        return None;
+    }
+    let days = (1970..date.year)
+        .map(|year| {
+            if days_in_month(year, 2) == 29 {
+                366
+            } else {
+                365
+            }
+        })
+        .sum::<u64>()
+        + (1..date.month)
+            .map(|month| u64::from(days_in_month(date.year, month)))
+            .sum::<u64>()
+        + u64::from(date.day - 1);
+    Some(days * 86_400 + u64::from(hour) * 3_600 + u64::from(minute) * 60 + u64::from(second))
+}
+
+fn days_in_month(year: u16, month: u8) -> u8 {
+    match month {
+        2 if year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400)) => {
+            29
+        }
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
@@ -0,0 +1,109 @@
+use alloc::string::String;
+use core::fmt;
+
+use extractor::types::PdfError;
+use signature_validator::types::SignatureValidationError;
+use zkpdf_xml_validator::XmlError;
+
+use crate::zip::ZipError;
+
+/// Errors returned by the verify-and-extract functions of this crate.
+#[derive(Debug)]
+pub enum Error {
+    /// The signature could not be checked: the PDF is unsigned, its PKCS#7 blob is malformed or
+    /// uses an unsupported algorithm, or the signed bytes were modified.
+    Signature(SignatureValidationError),
+    /// The signature was checked and does not verify, and the policy does not allow that.
+    InvalidSignature,
+    /// The signature verifies, but `appended_bytes` were added to the file after signing, and
+    /// the policy does not allow that: text read from the latest revision may not be signed.
+    UnsignedUpdates { appended_bytes: usize },
+    /// Text extraction failed.
+    Extraction(PdfError),
+    /// `page` is not a page of the document.
+    PageOutOfBounds { page: usize, total: usize },
+    /// A claim or template pattern does not compile. `field` names the template field.
+    InvalidPattern {
+        field: Option<String>,
+        message: String,
+    },
+    /// A template field did not match on any page, or an XML attribute is not in the signed
+    /// content.
+    FieldNotFound(String),
+    /// An XML document could not be parsed, or its signature could not be checked.
+    Xml(XmlError),
+    /// No document template is registered under this id.
+    UnknownTemplate(String),
+    /// The signature verifies, but with a key the document template does not pin.
+    UntrustedSigner,
+    /// A ZIP archive, such as an offline e-KYC download, could not be read or decrypted.
+    Zip(ZipError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Signature(e) => write!(f, "signature verification error: {}", e),
+            Error::InvalidSignature => write!(f, "signature verification failed"),
+            Error::UnsignedUpdates { appended_bytes } => write!(
+                f,
+                "{} bytes were appended after the signed range",
+                appended_bytes
+            ),
+            Error::Extraction(e) => write!(f, "text extraction error: {}", e),
+            Error::PageOutOfBounds { page, total } => {
+                write!(f, "page {} out of bounds (total pages: {})", page, total)
+            }
+            Error::InvalidPattern {
+                field: Some(field),
+                message,
+            } => write!(f, "invalid pattern for field `{}`: {}", field, message),
+            Error::InvalidPattern {
+                field: None,
+                message,
+            } => write!(f, "invalid pattern: {}", message),
+            Error::FieldNotFound(field) => write!(f, "field `{}` not found", field),
+            Error::Xml(e) => write!(f, "XML signature verification error: {}", e),
+            Error::UnknownTemplate(id) => write!(f, "unknown document template `{}`", id),
+            Error::UntrustedSigner => write!(f, "signer key is not pinned by the template"),
+            Error::Zip(e) => write!(f, "archive error: {}", e),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Signature(e) => Some(e),
+            Error::Extraction(e) => Some(e),
+            Error::Xml(e) => Some(e),
+            Error::Zip(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<SignatureValidationError> for Error {
+    fn from(e: SignatureValidationError) -> Self {
+        Error::Signature(e)
+    }
+}
+
+impl From<PdfError> for Error {
+    fn from(e: PdfError) -> Self {
+        Error::Extraction(e)
+    }
+}
+
+impl From<XmlError> for Error {
+    fn from(e: XmlError) -> Self {
+        Error::Xml(e)
+    }
+}
+
+impl From<ZipError> for Error {
+    fn from(e: ZipError) -> Self {
+        Error::Zip(e)
+    }
+}
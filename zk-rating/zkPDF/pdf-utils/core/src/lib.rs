@@ -1,5 +1,49 @@
-pub use extractor::extract_text;
-pub use signature_validator::{types::PdfSignatureResult, verify_pdf_signature};
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+pub mod aadhaar;
+pub mod claims;
+pub mod date;
+pub mod error;
+pub mod merkle;
+pub mod nullifier;
+#[cfg(feature = "poseidon")]
+pub mod poseidon;
+pub mod table;
+#[cfg(feature = "std")]
+pub mod template;
+pub mod xml;
+pub mod zip;
+
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+pub use claims::{verify_claims, Claim, ClaimMatch, ClaimsVerification};
+pub use date::Date;
+pub use error::Error;
+pub use extractor::{
+    document_info, extract_form_fields, extract_page_text, extract_pages, extract_structured,
+    extract_text, extract_text_at_revision, extract_text_page, extract_text_positions,
+    extract_text_with_options, find_substring, find_text, revision_count,
+    types::{
+        DocumentInfo, ExtractOptions, FormField, FormFieldType, Normalization, Page, PageText,
+        PageTokens, PdfFont, TextMatch, TextRun, Token, Word,
+    },
+    PdfDocument,
+};
+pub use signature_validator::{
+    get_signer_details, has_signature, signer_key_fingerprint_sha256,
+    types::{
+        DetachedSignatureResult, DocumentTimestampResult, PdfSignatureResult, SignatureCoverage,
+        SignatureTimestamp, SignatureValidationError, SignerDetails,
+    },
+    verify_pdf_signature, verify_pdf_timestamps, verify_pkcs7_detached,
+};
+#[cfg(feature = "std")]
+pub use template::{apply_template, ExtractedField, FieldPattern, Template, TemplateResult};
+pub use xml::{verify_xml_attribute, XmlAttributeResult, XmlSignatureResult};
 
 /// Result returned by `verify_text`, providing both the substring match and signature metadata.
 pub struct PdfVerificationResult {
@@ -7,6 +51,65 @@ pub struct PdfVerificationResult {
     pub signature: PdfSignatureResult,
 }
 
+/// Controls which signature outcomes are accepted by the `*_with_options` functions, and how
+/// substring claims are compared with the page text.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct VerificationPolicy {
+    /// Return content for documents whose signature does not verify, with
+    /// `signature.is_valid == false`, instead of an error. If the signed bytes were modified
+    /// `signature.public_key` is empty. Documents without a parsable signature are still rejected.
+    pub allow_invalid_signature: bool,
+    /// Accept documents with incremental updates after the signed range
+    /// (`SignatureCoverage::PartialWithUpdates`) instead of failing with
+    /// `Error::UnsignedUpdates`. Text is read from the latest revision, so text added after
+    /// signing is then returned alongside the signed text.
+    pub allow_unsigned_updates: bool,
+    pub match_mode: MatchMode,
+}
+
+/// How a claimed substring is compared with the page text. Outside `Exact`, the page text and
+/// the substring are both canonicalized with `MatchMode::canonicalize` first, and the offset
+/// indexes the canonical page text, so a claim survives extractor versions that space text
+/// differently.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MatchMode {
+    /// Byte for byte at the offset.
+    #[default]
+    Exact,
+    /// Every run of whitespace becomes one space, and leading and trailing whitespace is
+    /// dropped.
+    NormalizedWhitespace,
+    /// Letters are lowercased.
+    CaseInsensitive,
+}
+
+impl MatchMode {
+    /// The text as this mode compares it; offsets of non-exact claims index this text.
+    pub fn canonicalize<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        match self {
+            MatchMode::Exact => Cow::Borrowed(text),
+            MatchMode::NormalizedWhitespace => {
+                let mut canonical = String::with_capacity(text.len());
+                for word in text.split_whitespace() {
+                    if !canonical.is_empty() {
+                        canonical.push(' ');
+                    }
+                    canonical.push_str(word);
+                }
+                Cow::Owned(canonical)
+            }
+            MatchMode::CaseInsensitive => Cow::Owned(text.to_lowercase()),
+        }
+    }
+
+    /// Whether `needle` occurs at `offset` of `text`, both canonicalized.
+    pub fn matches_at(&self, text: &str, needle: &str, offset: usize) -> bool {
+        self.canonicalize(text)
+            .get(offset..)
+            .is_some_and(|slice| slice.starts_with(self.canonicalize(needle).as_ref()))
+    }
+}
+
 /// Verifies a PDF's digital signature and checks that `sub_string` appears at `offset` on
 /// `page_number`. Returns signature metadata and a substring match flag on success, or an error for
 /// signature/extraction failures.
@@ -15,25 +118,33 @@ pub fn verify_text(
     page_number: u8,
     sub_string: &str,
     offset: usize,
-) -> Result<PdfVerificationResult, String> {
-    // Step 1: verify signature and extract text
-    let PdfVerifiedContent { pages, signature } = verify_and_extract(pdf_bytes)?;
+) -> Result<PdfVerificationResult, Error> {
+    verify_text_with_options(
+        pdf_bytes,
+        page_number,
+        sub_string,
+        offset,
+        &ExtractOptions::default(),
+        &VerificationPolicy::default(),
+    )
+}
 
-    let index = page_number as usize;
-    if index >= pages.len() {
-        return Err(format!(
-            "page {} out of bounds (total pages: {})",
-            page_number,
-            pages.len()
-        ));
-    }
+/// `verify_text` with configurable extraction and signature policy. Offsets refer to the text
+/// produced by `options`.
+pub fn verify_text_with_options(
+    pdf_bytes: Vec<u8>,
+    page_number: u8,
+    sub_string: &str,
+    offset: usize,
+    options: &ExtractOptions,
+    policy: &VerificationPolicy,
+) -> Result<PdfVerificationResult, Error> {
+    // Step 1: verify signature and extract the text of the claimed page
+    let signature = verify_signature_with_policy(&pdf_bytes, policy)?;
+    let page_text = claimed_page_text(pdf_bytes, page_number as usize, options)?;
 
-    // Step 2: check if substring matches exactly at the requested offset
-    let page_text = &pages[index];
-    let result = page_text
-        .get(offset..)
-        .map(|slice| slice.starts_with(sub_string))
-        .unwrap_or(false);
+    // Step 2: check if substring matches at the requested offset
+    let result = policy.match_mode.matches_at(&page_text, sub_string, offset);
 
     Ok(PdfVerificationResult {
         substring_matches: result,
@@ -41,26 +152,268 @@ pub fn verify_text(
     })
 }
 
+/// Result returned by `verify_absent`.
+pub struct PdfAbsenceResult {
+    /// The substring appears on no page of the document.
+    pub absent: bool,
+    pub signature: PdfSignatureResult,
+}
+
+/// Verifies a PDF's digital signature and checks that `sub_string` appears on none of its
+/// pages, for claims such as "this certificate carries no CANCELLED watermark". Every page is
+/// extracted, so this costs as much as `verify_and_extract`.
+pub fn verify_absent(pdf_bytes: Vec<u8>, sub_string: &str) -> Result<PdfAbsenceResult, Error> {
+    verify_absent_with_options(
+        pdf_bytes,
+        sub_string,
+        &ExtractOptions::default(),
+        &VerificationPolicy::default(),
+    )
+}
+
+/// `verify_absent` with configurable extraction and signature policy. Pages and substring are
+/// compared in the policy's match mode. `max_pages` is ignored: an absence claim is about the
+/// whole document.
+pub fn verify_absent_with_options(
+    pdf_bytes: Vec<u8>,
+    sub_string: &str,
+    options: &ExtractOptions,
+    policy: &VerificationPolicy,
+) -> Result<PdfAbsenceResult, Error> {
+    let options = ExtractOptions {
+        max_pages: None,
+        ..options.clone()
+    };
+    let PdfVerifiedContent { pages, signature } =
+        verify_and_extract_with_options(pdf_bytes, &options, policy)?;
+
+    let mode = policy.match_mode;
+    let needle = mode.canonicalize(sub_string);
+    let absent = !pages
+        .iter()
+        .any(|page| mode.canonicalize(page).contains(needle.as_ref()));
+
+    Ok(PdfAbsenceResult { absent, signature })
+}
+
+/// Part of the page text matched by a regex group.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Capture {
+    pub offset: usize,
+    pub text: String,
+}
+
+/// Result returned by `verify_regex`.
+#[cfg(feature = "std")]
+pub struct PdfRegexResult {
+    /// Every group of the first match on the page, the whole match first; a group that did not
+    /// take part in the match is `None`. Empty if the pattern does not match.
+    pub captures: Vec<Option<Capture>>,
+    pub signature: PdfSignatureResult,
+}
+
+#[cfg(feature = "std")]
+impl PdfRegexResult {
+    /// The value a claim about the match commits to: the first group if the pattern has one
+    /// and it matched, the whole match otherwise. `None` if the pattern does not match.
+    pub fn value(&self) -> Option<&Capture> {
+        self.captures
+            .get(1)
+            .and_then(Option::as_ref)
+            .or_else(|| self.captures.first()?.as_ref())
+    }
+}
+
+/// Verifies a PDF's digital signature and matches `pattern` against the text of `page_number`,
+/// so a claim can be about a value of a known shape without knowing where it is. Offsets of
+/// the captures index the page text as `verify_text` offsets do. Needs the `std` feature.
+#[cfg(feature = "std")]
+pub fn verify_regex(
+    pdf_bytes: Vec<u8>,
+    page_number: u8,
+    pattern: &str,
+) -> Result<PdfRegexResult, Error> {
+    verify_regex_with_options(
+        pdf_bytes,
+        page_number,
+        pattern,
+        &ExtractOptions::default(),
+        &VerificationPolicy::default(),
+    )
+}
+
+/// `verify_regex` with configurable extraction and signature policy. The policy's match mode
+/// does not apply to patterns.
+#[cfg(feature = "std")]
+pub fn verify_regex_with_options(
+    pdf_bytes: Vec<u8>,
+    page_number: u8,
+    pattern: &str,
+    options: &ExtractOptions,
+    policy: &VerificationPolicy,
+) -> Result<PdfRegexResult, Error> {
+    // An invalid pattern is reported before any verification work
+    let regex = regex::Regex::new(pattern).map_err(|e| Error::InvalidPattern {
+        field: None,
+        message: e.to_string(),
+    })?;
+    let signature = verify_signature_with_policy(&pdf_bytes, policy)?;
+    let page_text = claimed_page_text(pdf_bytes, page_number as usize, options)?;
+
+    let captures = regex
+        .captures(&page_text)
+        .map(|groups| {
+            groups
+                .iter()
+                .map(|group| {
+                    group.map(|m| Capture {
+                        offset: m.start(),
+                        text: m.as_str().to_string(),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(PdfRegexResult {
+        captures,
+        signature,
+    })
+}
+
+// Only the claimed page is parsed and decoded, which keeps proofs about one page of a long
+// document cheap. Anything that keeps it from being extracted, a page past the end included, is
+// reported as extracting the whole document reports it.
+fn claimed_page_text(
+    pdf_bytes: Vec<u8>,
+    index: usize,
+    options: &ExtractOptions,
+) -> Result<String, Error> {
+    if options.max_pages.is_none_or(|max| index < max) {
+        if let Ok(page) = extract_page_text(&pdf_bytes, index, options) {
+            return Ok(page.text);
+        }
+    }
+    let mut pages = extract_text_with_options(pdf_bytes, options)?;
+    if index >= pages.len() {
+        return Err(Error::PageOutOfBounds {
+            page: index,
+            total: pages.len(),
+        });
+    }
+    Ok(pages.swap_remove(index))
+}
+
 #[derive(Debug, Clone)]
 pub struct PdfVerifiedContent {
     pub pages: Vec<String>,
     pub signature: PdfSignatureResult,
 }
 
-pub fn verify_and_extract(pdf_bytes: Vec<u8>) -> Result<PdfVerifiedContent, String> {
+/// Verifies the PDF's signature and extracts the text of every page. The content is returned
+/// even if the signature does not verify, with `signature.is_valid == false`, so callers can
+/// show what the document says alongside the failure and decide for themselves; see
+/// `VerificationPolicy::allow_invalid_signature`. Likewise for updates appended after signing,
+/// which `signature.coverage` reports. Documents without a parsable signature are still
+/// rejected.
+pub fn verify_and_extract(pdf_bytes: Vec<u8>) -> Result<PdfVerifiedContent, Error> {
+    let policy = VerificationPolicy {
+        allow_invalid_signature: true,
+        allow_unsigned_updates: true,
+        ..VerificationPolicy::default()
+    };
+    verify_and_extract_with_options(pdf_bytes, &ExtractOptions::default(), &policy)
+}
+
+/// `verify_and_extract` that fails unless the signature verifies and covers the whole file, as
+/// the circuit does: with `Error::InvalidSignature`, `Error::Signature` if the signed bytes were
+/// modified, or `Error::UnsignedUpdates` if bytes were appended after signing.
+pub fn verify_and_extract_strict(pdf_bytes: Vec<u8>) -> Result<PdfVerifiedContent, Error> {
+    verify_and_extract_with_options(
+        pdf_bytes,
+        &ExtractOptions::default(),
+        &VerificationPolicy::default(),
+    )
+}
+
+/// `verify_and_extract` with configurable extraction and signature policy.
+pub fn verify_and_extract_with_options(
+    pdf_bytes: Vec<u8>,
+    options: &ExtractOptions,
+    policy: &VerificationPolicy,
+) -> Result<PdfVerifiedContent, Error> {
     // Step 1: verify signature
-    let signature = verify_pdf_signature(&pdf_bytes)
-        .map_err(|e| format!("signature verification error: {}", e))?;
-    if !signature.is_valid {
-        return Err("signature verification failed".to_string());
-    }
+    let signature = verify_signature_with_policy(&pdf_bytes, policy)?;
 
     // Step 2: extract text
-    let pages = extract_text(pdf_bytes).map_err(|e| format!("text extraction error: {:?}", e))?;
+    let pages = extract_text_with_options(pdf_bytes, options)?;
 
     Ok(PdfVerifiedContent { pages, signature })
 }
 
+/// Hashes the text of every page with `nullifier::page_text_hash`, the text being exactly what
+/// `extract_text` returns and `verify_text` offsets index. Indexers and the circuit can agree on
+/// page commitments this way without exchanging the text. The signature is not verified.
+pub fn hash_pages(pdf_bytes: &[u8]) -> Result<Vec<[u8; 32]>, Error> {
+    hash_pages_with_options(pdf_bytes, &ExtractOptions::default())
+}
+
+/// Number of pages and `merkle::root` of their `hash_pages` hashes: the document structure a
+/// proof commits to. The signature is not verified.
+pub fn page_commitments(pdf_bytes: &[u8]) -> Result<(usize, [u8; 32]), Error> {
+    let hashes = hash_pages(pdf_bytes)?;
+    Ok((hashes.len(), merkle::root(&hashes)))
+}
+
+/// `hash_pages` over the text produced by `options`, for claims verified with the same options.
+pub fn hash_pages_with_options(
+    pdf_bytes: &[u8],
+    options: &ExtractOptions,
+) -> Result<Vec<[u8; 32]>, Error> {
+    let pages = extract_text_with_options(pdf_bytes.to_vec(), options)?;
+    Ok(pages
+        .iter()
+        .map(|text| nullifier::page_text_hash(text))
+        .collect())
+}
+
+/// Verifies the PDF's signature and applies `policy`, without extracting any text.
+pub fn verify_signature_with_policy(
+    pdf_bytes: &[u8],
+    policy: &VerificationPolicy,
+) -> Result<PdfSignatureResult, Error> {
+    let signature = match verify_pdf_signature(pdf_bytes) {
+        Ok(signature) => signature,
+        // The signed bytes were modified; the signer's key is not recovered in this case
+        Err(SignatureValidationError::MessageDigestMismatch { expected, .. })
+            if policy.allow_invalid_signature =>
+        {
+            PdfSignatureResult {
+                is_valid: false,
+                message_digest: expected,
+                public_key: Vec::new(),
+                signer: None,
+                field_name: None,
+                coverage: None,
+                sub_filter: None,
+                signing_time: None,
+                timestamp: None,
+            }
+        }
+        Err(e) => return Err(Error::Signature(e)),
+    };
+    if !signature.is_valid && !policy.allow_invalid_signature {
+        return Err(Error::InvalidSignature);
+    }
+    if let Some(SignatureCoverage::PartialWithUpdates { appended_bytes }) = signature.coverage {
+        if !policy.allow_unsigned_updates {
+            return Err(Error::UnsignedUpdates { appended_bytes });
+        }
+    }
+    Ok(signature)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -85,6 +438,590 @@ mod tests {
             "Text match failed at given offset"
         );
     }
+
+    #[test]
+    fn test_verify_absent_scans_every_page() {
+        let pdf_bytes = include_bytes!("../../sample-pdfs/digitally_signed.pdf").to_vec();
+
+        let result = verify_absent(pdf_bytes.clone(), "CANCELLED").unwrap();
+        assert!(result.signature.is_valid);
+        assert!(result.absent);
+        assert!(!verify_absent(pdf_bytes.clone(), "PDF Doc").unwrap().absent);
+        assert!(verify_absent(pdf_bytes.clone(), "pdf doc").unwrap().absent);
+
+        let policy = VerificationPolicy {
+            match_mode: MatchMode::CaseInsensitive,
+            ..VerificationPolicy::default()
+        };
+        let options = ExtractOptions {
+            max_pages: Some(0),
+            ..ExtractOptions::default()
+        };
+        let result = verify_absent_with_options(pdf_bytes, "pdf doc", &options, &policy).unwrap();
+        assert!(!result.absent);
+
+        let hashes = nullifier::ClaimHashes::absent(&result.signature, "pdf doc");
+        let positive = nullifier::ClaimHashes::new(&result.signature, "pdf doc", 0, 0);
+        assert_eq!(hashes.substring_hash, positive.substring_hash);
+        assert_ne!(hashes.nullifier, positive.nullifier);
+    }
+
+    #[test]
+    fn test_scoped_nullifiers() {
+        use nullifier::{scoped_nullifier, NullifierScope};
+
+        let (digest, signer) = (
+            nullifier::keccak256(b"document"),
+            nullifier::keccak256(b"key"),
+        );
+        let name = nullifier::compute_nullifier(&digest, &signer, b"name", 0, 10);
+        let dob = nullifier::compute_nullifier(&digest, &signer, b"dob", 1, 20);
+        let app = [7u8; 32];
+        let scoped =
+            |scope, external, claim| scoped_nullifier(scope, external, &digest, &signer, claim);
+
+        // Without an external nullifier, per-claim nullifiers are the claims' own
+        assert_eq!(scoped(NullifierScope::PerClaim, &[0; 32], &name), name);
+        assert_ne!(scoped(NullifierScope::PerClaim, &app, &name), name);
+        assert_ne!(
+            scoped(NullifierScope::PerClaim, &app, &name),
+            scoped(NullifierScope::PerClaim, &app, &dob)
+        );
+
+        // Broader scopes give every claim about the document one nullifier per application
+        for scope in [NullifierScope::PerDocument, NullifierScope::PerSignerAndDoc] {
+            assert_eq!(scoped(scope, &app, &name), scoped(scope, &app, &dob));
+            assert_ne!(scoped(scope, &app, &name), scoped(scope, &[8; 32], &name));
+        }
+        assert_ne!(
+            scoped(NullifierScope::PerDocument, &app, &name),
+            scoped(NullifierScope::PerSignerAndDoc, &app, &name)
+        );
+        let preimage = [nullifier::SCOPED_NULLIFIER_DOMAIN, &[1], &app, &digest].concat();
+        assert_eq!(
+            scoped(NullifierScope::PerDocument, &app, &name),
+            nullifier::keccak256(&preimage)
+        );
+        let other_signer = nullifier::keccak256(b"other key");
+        assert_eq!(
+            scoped(NullifierScope::PerDocument, &app, &name),
+            scoped_nullifier(
+                NullifierScope::PerDocument,
+                &app,
+                &digest,
+                &other_signer,
+                &dob
+            )
+        );
+    }
+
+    #[test]
+    fn test_hash_suites() {
+        use nullifier::{ClaimHashes, HashSuite};
+
+        let pdf_bytes = include_bytes!("../../sample-pdfs/digitally_signed.pdf").to_vec();
+        let result = verify_text(pdf_bytes, 0, "Sample", 0).unwrap();
+        let keccak = ClaimHashes::new(&result.signature, "Sample", 0, 0);
+        assert_eq!(
+            ClaimHashes::with_suite(HashSuite::Keccak256, &result.signature, "Sample", 0, 0),
+            keccak
+        );
+
+        #[cfg(feature = "poseidon")]
+        {
+            let hashes =
+                ClaimHashes::with_suite(HashSuite::Poseidon, &result.signature, "Sample", 0, 7);
+            assert_eq!(hashes.substring_hash, poseidon::hash_bytes(b"Sample"));
+            assert_eq!(
+                hashes.signer_key_hash,
+                poseidon::hash_bytes(&result.signature.public_key)
+            );
+            assert_eq!(
+                hashes.nullifier,
+                poseidon::hash_fields(&[
+                    nullifier::NULLIFIER_DOMAIN,
+                    &hashes.message_digest_hash,
+                    &hashes.signer_key_hash,
+                    &hashes.substring_hash,
+                    &[0],
+                    &[0, 0, 0, 7],
+                ])
+            );
+            assert_ne!(hashes.substring_hash, keccak.substring_hash);
+        }
+    }
+
+    #[test]
+    fn test_secret_bound_nullifiers() {
+        use nullifier::{secret_bound_nullifier, secret_commitment};
+
+        let nullifier = nullifier::keccak256(b"scoped nullifier");
+        let (secret, app, other_app) = ([1u8; 32], [7u8; 32], [8u8; 32]);
+
+        let bound = secret_bound_nullifier(&secret, &nullifier);
+        assert_ne!(bound, nullifier);
+        assert_eq!(bound, secret_bound_nullifier(&secret, &nullifier));
+        assert_ne!(bound, secret_bound_nullifier(&[2; 32], &nullifier));
+        assert_eq!(
+            bound,
+            nullifier::keccak256(
+                &[nullifier::SECRET_NULLIFIER_DOMAIN, &secret, &nullifier].concat()
+            )
+        );
+
+        // One secret commits differently for each application
+        assert_ne!(
+            secret_commitment(&secret, &app),
+            secret_commitment(&secret, &other_app)
+        );
+        assert_ne!(
+            secret_commitment(&secret, &app),
+            secret_commitment(&[2; 32], &app)
+        );
+
+        // Blinded hashes follow the nullifier, and zero stays zero
+        let other = secret_bound_nullifier(&secret, &nullifier::keccak256(b"other"));
+        let hash = nullifier::keccak256(b"substring");
+        assert_ne!(
+            nullifier::blinded_hash(&hash, &bound),
+            nullifier::blinded_hash(&hash, &other)
+        );
+        assert_eq!(
+            nullifier::blinded_hash(&hash, &bound),
+            nullifier::keccak256(&[nullifier::BLINDED_HASH_DOMAIN, &hash, &bound].concat())
+        );
+        assert_eq!(nullifier::blinded_hash(&[0; 32], &bound), [0; 32]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_verify_regex_captures_the_value() {
+        let pdf_bytes = include_bytes!("../../sample-pdfs/digitally_signed.pdf").to_vec();
+
+        let result = verify_regex(pdf_bytes.clone(), 0, r"Signed (\w+) (\w+)").unwrap();
+        assert!(result.signature.is_valid);
+        let texts: Vec<_> = result
+            .captures
+            .iter()
+            .map(|c| c.as_ref().map(|c| (c.offset, c.text.as_str())))
+            .collect();
+        assert_eq!(
+            texts,
+            [
+                Some((7, "Signed PDF Document")),
+                Some((14, "PDF")),
+                Some((18, "Document")),
+            ]
+        );
+        assert_eq!(result.value().unwrap().text, "PDF");
+
+        let whole = verify_regex(pdf_bytes.clone(), 0, r"S\w+ S").unwrap();
+        assert_eq!(whole.value().unwrap().text, "Sample S");
+
+        let missing = verify_regex(pdf_bytes.clone(), 0, r"\d{15}").unwrap();
+        assert!(missing.captures.is_empty());
+        assert!(missing.value().is_none());
+
+        assert!(matches!(
+            verify_regex(pdf_bytes, 0, "("),
+            Err(Error::InvalidPattern { field: None, .. })
+        ));
+    }
+
+    #[test]
+    fn test_verify_text_page_out_of_bounds() {
+        let pdf_bytes = include_bytes!("../../sample-pdfs/digitally_signed.pdf").to_vec();
+
+        assert!(matches!(
+            verify_text(pdf_bytes, 3, "Sample", 0),
+            Err(Error::PageOutOfBounds { page: 3, total: 1 })
+        ));
+    }
+
+    #[test]
+    fn test_match_modes_canonicalize_text_and_needle() {
+        let pdf_bytes = include_bytes!("../../sample-pdfs/digitally_signed.pdf").to_vec();
+        let verify = |needle: &str, offset: usize, match_mode: MatchMode| {
+            let policy = VerificationPolicy {
+                match_mode,
+                ..VerificationPolicy::default()
+            };
+            let options = ExtractOptions::default();
+            verify_text_with_options(pdf_bytes.clone(), 0, needle, offset, &options, &policy)
+                .unwrap()
+                .substring_matches
+        };
+
+        assert!(verify("Signed PDF", 7, MatchMode::Exact));
+        assert!(!verify("Signed \n PDF", 7, MatchMode::Exact));
+        assert!(verify("Signed \n PDF", 7, MatchMode::NormalizedWhitespace));
+        assert!(!verify("SIGNED pdf", 7, MatchMode::Exact));
+        assert!(verify("SIGNED pdf", 7, MatchMode::CaseInsensitive));
+        assert!(!verify("SIGNED pdf", 8, MatchMode::CaseInsensitive));
+
+        assert_eq!(
+            MatchMode::NormalizedWhitespace.canonicalize(" a\t\u{a0}b \n"),
+            "a b"
+        );
+        assert_eq!(MatchMode::CaseInsensitive.canonicalize("ÉTAT"), "état");
+    }
+
+    #[test]
+    fn test_policy_allows_tampered_signature() {
+        let mut pdf_bytes = include_bytes!("../../sample-pdfs/digitally_signed.pdf").to_vec();
+        // Flip a byte of the binary header comment, which is covered by the signature
+        pdf_bytes[10] ^= 0xFF;
+
+        assert!(matches!(
+            verify_and_extract_strict(pdf_bytes.clone()),
+            Err(Error::Signature(
+                SignatureValidationError::MessageDigestMismatch { .. }
+            ))
+        ));
+        assert!(matches!(
+            verify_and_extract_with_options(
+                pdf_bytes.clone(),
+                &ExtractOptions::default(),
+                &VerificationPolicy::default()
+            ),
+            Err(Error::Signature(_))
+        ));
+
+        let content = verify_and_extract(pdf_bytes).expect("content of an invalid signature");
+        assert!(!content.signature.is_valid);
+        assert!(content.signature.public_key.is_empty());
+        assert_eq!(content.pages[0], "Sample Signed PDF Document");
+
+        // An unsigned document has no signature result to return
+        assert!(matches!(
+            verify_and_extract(b"%PDF-1.7\n%%EOF".to_vec()),
+            Err(Error::Signature(_))
+        ));
+    }
+
+    #[test]
+    fn test_policy_rejects_updates_after_signing() {
+        let mut pdf_bytes = include_bytes!("../../sample-pdfs/GST-certificate.pdf").to_vec();
+        let update = b"\n99 0 obj\n<< >>\nendobj\n%%EOF\n";
+        pdf_bytes.extend_from_slice(update);
+
+        assert!(matches!(
+            verify_and_extract_strict(pdf_bytes.clone()),
+            Err(Error::UnsignedUpdates { appended_bytes }) if appended_bytes == update.len()
+        ));
+        let policy = VerificationPolicy {
+            allow_unsigned_updates: true,
+            ..VerificationPolicy::default()
+        };
+        let signature = verify_signature_with_policy(&pdf_bytes, &policy).unwrap();
+        assert!(signature.is_valid);
+
+        let content = verify_and_extract(pdf_bytes).expect("content of an updated document");
+        assert_eq!(
+            content.signature.coverage,
+            Some(SignatureCoverage::PartialWithUpdates {
+                appended_bytes: update.len()
+            })
+        );
+    }
+
+    #[test]
+    fn test_merkle_proofs_of_every_page() {
+        let pages: Vec<[u8; 32]> = (0..5u8).map(|i| nullifier::keccak256(&[i])).collect();
+
+        assert_eq!(merkle::root(&[]), [0; 32]);
+        assert_eq!(merkle::root(&pages[..1]), pages[0]);
+        assert_eq!(
+            merkle::root(&pages[..2]),
+            nullifier::keccak256(&[pages[0], pages[1]].concat())
+        );
+        for count in 1..=pages.len() {
+            let leaves = &pages[..count];
+            let root = merkle::root(leaves);
+            for (index, leaf) in leaves.iter().enumerate() {
+                let proof = merkle::proof(leaves, index).unwrap();
+                assert_eq!(
+                    proof.len(),
+                    count.next_power_of_two().trailing_zeros() as usize
+                );
+                assert!(merkle::verify(&root, leaf, index, &proof));
+                assert!(!merkle::verify(&root, leaf, index ^ 1, &proof));
+                assert!(!merkle::verify(
+                    &root,
+                    leaf,
+                    index + (1 << proof.len()),
+                    &proof
+                ));
+            }
+            assert!(merkle::proof(leaves, count).is_none());
+        }
+
+        let pdf_bytes = include_bytes!("../../sample-pdfs/digitally_signed.pdf");
+        let (count, root) = page_commitments(pdf_bytes).unwrap();
+        assert_eq!(count, 1);
+        assert_eq!(root, hash_pages(pdf_bytes).unwrap()[0]);
+    }
+
+    #[test]
+    fn test_keccak_matches_ethereum() {
+        assert_eq!(
+            nullifier::keccak256(b""),
+            [
+                0xc5, 0xd2, 0x46, 0x01, 0x86, 0xf7, 0x23, 0x3c, 0x92, 0x7e, 0x7d, 0xb2, 0xdc, 0xc7,
+                0x03, 0xc0, 0xe5, 0x00, 0xb6, 0x53, 0xca, 0x82, 0x27, 0x3b, 0x7b, 0xfa, 0xd8, 0x04,
+                0x5d, 0x85, 0xa4, 0x70,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_hash_pages_commits_to_page_text() {
+        let pdf_bytes = include_bytes!("../../sample-pdfs/digitally_signed.pdf");
+
+        let hashes = hash_pages(pdf_bytes).unwrap();
+        assert_eq!(
+            hashes,
+            [nullifier::page_text_hash("Sample Signed PDF Document")]
+        );
+        // Domain separated from a plain hash of the text, such as a claim's substring hash
+        assert_ne!(
+            hashes[0],
+            nullifier::keccak256(b"Sample Signed PDF Document")
+        );
+
+        let mut preimage = nullifier::PAGE_HASH_DOMAIN.to_vec();
+        preimage.extend_from_slice(b"Sample Signed PDF Document");
+        assert_eq!(hashes[0], nullifier::keccak256(&preimage));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_verify_claims_batch() {
+        let pdf_bytes = include_bytes!("../../sample-pdfs/digitally_signed.pdf").to_vec();
+        let claims = [
+            Claim::Substring {
+                page: 0,
+                substring: "Signed PDF".to_string(),
+                offset: 7,
+            },
+            Claim::Substring {
+                page: 0,
+                substring: "Signed PDF".to_string(),
+                offset: 0,
+            },
+            Claim::Regex {
+                pattern: r"PDF \w+".to_string(),
+                page: None,
+            },
+            Claim::Regex {
+                pattern: "(".to_string(),
+                page: Some(0),
+            },
+            Claim::Substring {
+                page: 9,
+                substring: "Sample".to_string(),
+                offset: 0,
+            },
+        ];
+
+        let result = verify_claims(
+            pdf_bytes,
+            &claims,
+            &ExtractOptions::default(),
+            &VerificationPolicy::default(),
+        )
+        .expect("batch verification failed");
+
+        assert!(result.signature.is_valid);
+        assert_eq!(result.results.len(), claims.len());
+        assert_eq!(
+            result.results[0].as_ref().unwrap().as_ref().unwrap().offset,
+            Some(7)
+        );
+        assert!(matches!(result.results[1], Ok(None)));
+        assert_eq!(
+            result.results[2].as_ref().unwrap(),
+            &Some(ClaimMatch {
+                page: Some(0),
+                offset: Some(14),
+                value: "PDF Document".to_string(),
+            })
+        );
+        assert!(matches!(
+            result.results[3],
+            Err(Error::InvalidPattern { field: None, .. })
+        ));
+        assert!(matches!(
+            result.results[4],
+            Err(Error::PageOutOfBounds { page: 9, total: 1 })
+        ));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_claim_dsl_variants() {
+        let pdf_bytes = include_bytes!("../../sample-pdfs/digitally_signed.pdf").to_vec();
+        let fields = extract_form_fields(&pdf_bytes).unwrap();
+        let signature_field = fields[0].name.clone();
+        let claims = [
+            Claim::SubstringAnywhere {
+                substring: "signed pdf".to_string(),
+                page: None,
+            },
+            Claim::SubstringAnywhere {
+                substring: "Unsigned".to_string(),
+                page: Some(0),
+            },
+            Claim::FieldEquals {
+                field: signature_field,
+                value: String::new(),
+            },
+            Claim::FieldEquals {
+                field: "Applicant.Name".to_string(),
+                value: "Asha".to_string(),
+            },
+            Claim::NumericRange {
+                pattern: r"\d+".to_string(),
+                page: None,
+                min: None,
+                max: Some(0.0),
+            },
+        ];
+        let policy = VerificationPolicy {
+            match_mode: MatchMode::CaseInsensitive,
+            ..VerificationPolicy::default()
+        };
+        let result =
+            verify_claims(pdf_bytes, &claims, &ExtractOptions::default(), &policy).unwrap();
+
+        assert_eq!(
+            result.results[0].as_ref().unwrap(),
+            &Some(ClaimMatch {
+                page: Some(0),
+                offset: Some(7),
+                value: "signed pdf".to_string(),
+            })
+        );
+        assert!(matches!(result.results[1], Ok(None)));
+        // A signature field has no value to compare
+        assert!(matches!(result.results[2], Ok(None)));
+        assert!(matches!(
+            &result.results[3],
+            Err(Error::FieldNotFound(field)) if field == "Applicant.Name"
+        ));
+        // The page has no digits to capture
+        assert!(matches!(result.results[4], Ok(None)));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_claim_values_and_dates() {
+        let pages = [
+            "Invoice 7 dated 15/03/2024".to_string(),
+            "Total: Rs. 1,20,000.50 (issued D:20240301)".to_string(),
+        ];
+        let range = |min, max| Claim::NumericRange {
+            pattern: r"Rs\. ([\d,.]+)".to_string(),
+            page: None,
+            min,
+            max,
+        };
+        let found = range(Some(100_000.0), Some(120_000.5))
+            .check(&pages)
+            .unwrap()
+            .unwrap();
+        assert_eq!((found.page, found.offset), (Some(1), Some(11)));
+        assert_eq!(found.value, "1,20,000.50");
+        assert!(range(None, Some(120_000.0))
+            .check(&pages)
+            .unwrap()
+            .is_none());
+        assert!(range(Some(120_001.0), None)
+            .check(&pages)
+            .unwrap()
+            .is_none());
+        assert!(range(None, None).check(&pages).unwrap().is_some());
+
+        let before = |pattern: &str, day| Claim::DateBefore {
+            pattern: pattern.to_string(),
+            page: None,
+            before: Date::new(2024, 3, day).unwrap(),
+        };
+        let dated = r"dated (\S+)";
+        assert!(before(dated, 16).check(&pages).unwrap().is_some());
+        assert!(before(dated, 15).check(&pages).unwrap().is_none());
+        assert!(before(r"issued (D:\d+)", 2)
+            .check(&pages)
+            .unwrap()
+            .is_some());
+        assert!(before(r"Invoice (\d+)", 31)
+            .check(&pages)
+            .unwrap()
+            .is_none());
+        assert!(matches!(
+            before(dated, 1).check_with_mode(&pages[..0], MatchMode::Exact),
+            Ok(None)
+        ));
+
+        let field = |value: Option<&str>| FormField {
+            name: "Applicant.Name".to_string(),
+            field_type: FormFieldType::Text,
+            value: value.map(str::to_string),
+        };
+        let claim = Claim::FieldEquals {
+            field: "Applicant.Name".to_string(),
+            value: "asha  kumari".to_string(),
+        };
+        let check = |fields: &[FormField], mode| claim.check_document(&pages, fields, mode);
+        let named = [field(Some("Asha Kumari"))];
+        assert!(check(&named, MatchMode::Exact).unwrap().is_none());
+        assert!(check(&named, MatchMode::NormalizedWhitespace)
+            .unwrap()
+            .is_none());
+        let matched = check(&named, MatchMode::CaseInsensitive).unwrap();
+        assert!(matched.is_none(), "whitespace differs too");
+        let spaced = [field(Some("Asha  Kumari"))];
+        let matched = check(&spaced, MatchMode::CaseInsensitive).unwrap().unwrap();
+        assert_eq!(
+            (matched.page, matched.value.as_str()),
+            (None, "Asha  Kumari")
+        );
+        assert!(check(&[field(None)], MatchMode::Exact).unwrap().is_none());
+        assert!(matches!(claim.check(&pages), Err(Error::FieldNotFound(_))));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_gst_template() {
+        let pdf_bytes = include_bytes!("../../sample-pdfs/GST-certificate.pdf").to_vec();
+
+        let result = apply_template(
+            pdf_bytes.clone(),
+            &Template::gst_certificate(),
+            &ExtractOptions::default(),
+            &VerificationPolicy::default(),
+        )
+        .expect("GST template failed");
+
+        assert!(result.signature.is_valid);
+        let values: Vec<_> = result.fields.iter().map(|f| f.value.as_str()).collect();
+        assert_eq!(
+            values,
+            ["07AAATC0869P1ZB", "CONSUMER UNITY AND TRUST SOCIETY"]
+        );
+
+        // Every field must be provable with verify_text at the reported location
+        for field in &result.fields {
+            let verified = verify_text(
+                pdf_bytes.clone(),
+                field.page as u8,
+                &field.value,
+                field.offset,
+            )
+            .unwrap();
+            assert!(verified.substring_matches, "{} not at offset", field.name);
+        }
+    }
 }
 
 #[cfg(feature = "private_tests")]
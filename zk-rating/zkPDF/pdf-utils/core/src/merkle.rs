@@ -0,0 +1,80 @@
+//! Merkle tree over the page hashes of `hash_pages`. The circuit commits to its root, so a
+//! claim about one page can later be checked against a proof of the whole document without
+//! proving again.
+//!
+//! Leaves are the page hashes in page order, padded with zero hashes to a power of two, and
+//! each node is keccak256 over its left and right children. A proof lists the siblings from
+//! the leaf up; the page index tells on which side each one is.
+
+use alloc::vec::Vec;
+
+use crate::nullifier::keccak256;
+
+const HASH_LEN: usize = 32;
+
+/// Root of the tree over `leaves`; the leaf itself for one page, and zero for none.
+pub fn root(leaves: &[[u8; HASH_LEN]]) -> [u8; HASH_LEN] {
+    let mut level = padded(leaves);
+    while level.len() > 1 {
+        level = level
+            .chunks_exact(2)
+            .map(|pair| node(&pair[0], &pair[1]))
+            .collect();
+    }
+    level.first().copied().unwrap_or([0; HASH_LEN])
+}
+
+/// Siblings of leaf `index` from the bottom of the tree up, or `None` if there is no such leaf.
+pub fn proof(leaves: &[[u8; HASH_LEN]], index: usize) -> Option<Vec<[u8; HASH_LEN]>> {
+    if index >= leaves.len() {
+        return None;
+    }
+    let mut level = padded(leaves);
+    let mut index = index;
+    let mut siblings = Vec::new();
+    while level.len() > 1 {
+        siblings.push(level[index ^ 1]);
+        level = level
+            .chunks_exact(2)
+            .map(|pair| node(&pair[0], &pair[1]))
+            .collect();
+        index /= 2;
+    }
+    Some(siblings)
+}
+
+/// Whether `proof` shows that `leaf` is leaf `index` of the tree with `root`.
+pub fn verify(
+    root: &[u8; HASH_LEN],
+    leaf: &[u8; HASH_LEN],
+    index: usize,
+    proof: &[[u8; HASH_LEN]],
+) -> bool {
+    if proof.len() < usize::BITS as usize && index >> proof.len() != 0 {
+        return false;
+    }
+    let mut hash = *leaf;
+    for (level, sibling) in proof.iter().enumerate() {
+        hash = if (index >> level) & 1 == 0 {
+            node(&hash, sibling)
+        } else {
+            node(sibling, &hash)
+        };
+    }
+    hash == *root
+}
+
+fn padded(leaves: &[[u8; HASH_LEN]]) -> Vec<[u8; HASH_LEN]> {
+    let mut level = leaves.to_vec();
+    if !level.is_empty() {
+        level.resize(level.len().next_power_of_two(), [0; HASH_LEN]);
+    }
+    level
+}
+
+fn node(left: &[u8; HASH_LEN], right: &[u8; HASH_LEN]) -> [u8; HASH_LEN] {
+    let mut preimage = [0u8; HASH_LEN * 2];
+    preimage[..HASH_LEN].copy_from_slice(left);
+    preimage[HASH_LEN..].copy_from_slice(right);
+    keccak256(&preimage)
+}
@@ -0,0 +1,356 @@
+use alloc::vec::Vec;
+use tiny_keccak::{Hasher, Keccak};
+
+use crate::xml::XmlSignatureResult;
+use crate::PdfSignatureResult;
+
+pub const NULLIFIER_DOMAIN: &[u8] = b"zkpdf-nullifier-v0";
+
+pub const ABSENCE_NULLIFIER_DOMAIN: &[u8] = b"zkpdf-absence-nullifier-v0";
+
+pub const XML_NULLIFIER_DOMAIN: &[u8] = b"zkpdf-xml-nullifier-v0";
+
+pub const PAGE_HASH_DOMAIN: &[u8] = b"zkpdf-page-v0";
+
+pub const SCOPED_NULLIFIER_DOMAIN: &[u8] = b"zkpdf-scoped-nullifier-v0";
+
+pub const SECRET_NULLIFIER_DOMAIN: &[u8] = b"zkpdf-secret-nullifier-v0";
+
+pub const SECRET_COMMITMENT_DOMAIN: &[u8] = b"zkpdf-secret-commitment-v0";
+
+pub const BLINDED_HASH_DOMAIN: &[u8] = b"zkpdf-blinded-hash-v0";
+
+const HASH_LEN: usize = 32;
+
+pub fn keccak256(data: &[u8]) -> [u8; HASH_LEN] {
+    let mut hasher = Keccak::v256();
+    let mut output = [0u8; HASH_LEN];
+    hasher.update(data);
+    hasher.finalize(&mut output);
+    output
+}
+
+/// Hash function a claim's hashes and nullifier are computed with. The discriminant is the
+/// `hashSuite` public value.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+#[repr(u8)]
+pub enum HashSuite {
+    #[default]
+    Keccak256 = 0,
+    /// Poseidon over BN254 with circom's parameters (`crate::poseidon`), which a SNARK
+    /// recomputes far more cheaply than keccak256. Needs the `poseidon` feature.
+    #[cfg(feature = "poseidon")]
+    Poseidon = 1,
+}
+
+impl HashSuite {
+    /// The hash of `data`: keccak256, or `poseidon::hash_bytes`.
+    pub fn hash(self, data: &[u8]) -> [u8; HASH_LEN] {
+        match self {
+            HashSuite::Keccak256 => keccak256(data),
+            #[cfg(feature = "poseidon")]
+            HashSuite::Poseidon => crate::poseidon::hash_bytes(data),
+        }
+    }
+}
+
+/// Commitment to the text of one page: keccak256 over the domain and the text's UTF-8 bytes.
+pub fn page_text_hash(text: &str) -> [u8; HASH_LEN] {
+    let mut hasher = Keccak::v256();
+    let mut output = [0u8; HASH_LEN];
+    hasher.update(PAGE_HASH_DOMAIN);
+    hasher.update(text.as_bytes());
+    hasher.finalize(&mut output);
+    output
+}
+
+/// What a nullifier is bound to, and so which proofs share one. The discriminant is the
+/// `nullifierScope` public value.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+#[repr(u8)]
+pub enum NullifierScope {
+    /// The document, the signer and the claim itself, so different claims about one document
+    /// have different nullifiers. The nullifier each claim computes, unchanged.
+    #[default]
+    PerClaim = 0,
+    /// The signed document only: every claim about it shares one nullifier, so it can be used
+    /// once whatever is claimed.
+    PerDocument = 1,
+    /// The signed document and the signer's key.
+    PerSignerAndDoc = 2,
+}
+
+/// The nullifier a claim commits to under `scope`, with `external_nullifier` an application's
+/// domain separator, so each application spends a document independently. A claim with the
+/// `PerClaim` scope and a zero external nullifier keeps `claim_nullifier`; otherwise it is
+/// keccak256 over the domain, the scope, the external nullifier, the document hash and, by
+/// scope, the signer hash and the claim nullifier.
+pub fn scoped_nullifier(
+    scope: NullifierScope,
+    external_nullifier: &[u8; HASH_LEN],
+    message_digest_hash: &[u8],
+    signer_key_hash: &[u8],
+    claim_nullifier: &[u8; HASH_LEN],
+) -> [u8; HASH_LEN] {
+    if scope == NullifierScope::PerClaim && *external_nullifier == [0; HASH_LEN] {
+        return *claim_nullifier;
+    }
+    let mut preimage = Vec::with_capacity(SCOPED_NULLIFIER_DOMAIN.len() + 1 + HASH_LEN * 4);
+
+    preimage.extend_from_slice(SCOPED_NULLIFIER_DOMAIN);
+    preimage.push(scope as u8);
+    preimage.extend_from_slice(external_nullifier);
+    preimage.extend_from_slice(message_digest_hash);
+    match scope {
+        NullifierScope::PerDocument => {}
+        NullifierScope::PerSignerAndDoc => preimage.extend_from_slice(signer_key_hash),
+        NullifierScope::PerClaim => {
+            preimage.extend_from_slice(signer_key_hash);
+            preimage.extend_from_slice(claim_nullifier);
+        }
+    }
+
+    keccak256(&preimage)
+}
+
+/// `nullifier` mixed with `user_secret`, a secret only the prover holds, as a Semaphore identity
+/// nullifier is: keccak256 over the domain, the secret and the nullifier. Without the secret
+/// nobody can compute it from the document, so the nullifiers one document gives under
+/// different external nullifiers cannot be matched up.
+pub fn secret_bound_nullifier(
+    user_secret: &[u8; HASH_LEN],
+    nullifier: &[u8; HASH_LEN],
+) -> [u8; HASH_LEN] {
+    let mut preimage = Vec::with_capacity(SECRET_NULLIFIER_DOMAIN.len() + HASH_LEN * 2);
+
+    preimage.extend_from_slice(SECRET_NULLIFIER_DOMAIN);
+    preimage.extend_from_slice(user_secret);
+    preimage.extend_from_slice(nullifier);
+
+    keccak256(&preimage)
+}
+
+/// Public commitment to `user_secret` for the application `external_nullifier`: keccak256 over
+/// the domain, the secret and the external nullifier. An application can require proofs to
+/// come from a commitment it registered; being per application, commitments do not link a
+/// holder's proofs across applications either.
+pub fn secret_commitment(
+    user_secret: &[u8; HASH_LEN],
+    external_nullifier: &[u8; HASH_LEN],
+) -> [u8; HASH_LEN] {
+    let mut preimage = Vec::with_capacity(SECRET_COMMITMENT_DOMAIN.len() + HASH_LEN * 2);
+
+    preimage.extend_from_slice(SECRET_COMMITMENT_DOMAIN);
+    preimage.extend_from_slice(user_secret);
+    preimage.extend_from_slice(external_nullifier);
+
+    keccak256(&preimage)
+}
+
+/// `hash` as committed by a proof bound to a user secret: keccak256 over the domain, the hash
+/// and the proof's secret-bound `nullifier`. Hashes of one document or signer then differ
+/// between applications, while a verifier that knows the value can still check it against
+/// the proof's own nullifier. A zero hash, a field the claim does not commit, stays zero.
+pub fn blinded_hash(hash: &[u8; HASH_LEN], nullifier: &[u8; HASH_LEN]) -> [u8; HASH_LEN] {
+    if *hash == [0; HASH_LEN] {
+        return *hash;
+    }
+    let mut preimage = Vec::with_capacity(BLINDED_HASH_DOMAIN.len() + HASH_LEN * 2);
+
+    preimage.extend_from_slice(BLINDED_HASH_DOMAIN);
+    preimage.extend_from_slice(hash);
+    preimage.extend_from_slice(nullifier);
+
+    keccak256(&preimage)
+}
+
+/// Nullifier committed by the circuit: keccak256 over the domain, the three claim hashes, the
+/// page number and the big-endian offset.
+pub fn compute_nullifier(
+    message_digest_hash: &[u8],
+    signer_key_hash: &[u8],
+    substring_hash: &[u8],
+    page_number: u8,
+    offset: u32,
+) -> [u8; HASH_LEN] {
+    let mut preimage = Vec::with_capacity(NULLIFIER_DOMAIN.len() + HASH_LEN * 3 + 1 + 4);
+
+    preimage.extend_from_slice(NULLIFIER_DOMAIN);
+    preimage.extend_from_slice(message_digest_hash);
+    preimage.extend_from_slice(signer_key_hash);
+    preimage.extend_from_slice(substring_hash);
+    preimage.push(page_number);
+    preimage.extend_from_slice(&offset.to_be_bytes());
+
+    keccak256(&preimage)
+}
+
+/// `compute_nullifier` under `suite`. Under Poseidon it is the Poseidon of six field elements,
+/// the domain, the three claim hashes, the page number and the offset, rather than of their
+/// concatenation.
+pub fn compute_nullifier_with(
+    suite: HashSuite,
+    message_digest_hash: &[u8],
+    signer_key_hash: &[u8],
+    substring_hash: &[u8],
+    page_number: u8,
+    offset: u32,
+) -> [u8; HASH_LEN] {
+    match suite {
+        HashSuite::Keccak256 => compute_nullifier(
+            message_digest_hash,
+            signer_key_hash,
+            substring_hash,
+            page_number,
+            offset,
+        ),
+        #[cfg(feature = "poseidon")]
+        HashSuite::Poseidon => crate::poseidon::hash_fields(&[
+            NULLIFIER_DOMAIN,
+            message_digest_hash,
+            signer_key_hash,
+            substring_hash,
+            &[page_number],
+            &offset.to_be_bytes(),
+        ]),
+    }
+}
+
+/// Nullifier of a claim that a substring appears nowhere in the document: keccak256 over its own
+/// domain and the three claim hashes, so it never equals the nullifier of a claim about where
+/// the substring is.
+pub fn compute_absence_nullifier(
+    message_digest_hash: &[u8],
+    signer_key_hash: &[u8],
+    substring_hash: &[u8],
+) -> [u8; HASH_LEN] {
+    let mut preimage = Vec::with_capacity(ABSENCE_NULLIFIER_DOMAIN.len() + HASH_LEN * 3);
+
+    preimage.extend_from_slice(ABSENCE_NULLIFIER_DOMAIN);
+    preimage.extend_from_slice(message_digest_hash);
+    preimage.extend_from_slice(signer_key_hash);
+    preimage.extend_from_slice(substring_hash);
+
+    keccak256(&preimage)
+}
+
+/// Nullifier of a claim about an XML attribute: keccak256 over its own domain, the three claim
+/// hashes and the hash of the selector, so claims about different attributes of one document
+/// never share a nullifier.
+pub fn compute_xml_nullifier(
+    message_digest_hash: &[u8],
+    signer_key_hash: &[u8],
+    value_hash: &[u8],
+    selector_hash: &[u8],
+) -> [u8; HASH_LEN] {
+    let mut preimage = Vec::with_capacity(XML_NULLIFIER_DOMAIN.len() + HASH_LEN * 4);
+
+    preimage.extend_from_slice(XML_NULLIFIER_DOMAIN);
+    preimage.extend_from_slice(message_digest_hash);
+    preimage.extend_from_slice(signer_key_hash);
+    preimage.extend_from_slice(value_hash);
+    preimage.extend_from_slice(selector_hash);
+
+    keccak256(&preimage)
+}
+
+/// The hashes a proof of `substring` at `offset` on `page_number` commits to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClaimHashes {
+    pub message_digest_hash: [u8; HASH_LEN],
+    pub signer_key_hash: [u8; HASH_LEN],
+    pub substring_hash: [u8; HASH_LEN],
+    pub nullifier: [u8; HASH_LEN],
+}
+
+impl ClaimHashes {
+    pub fn new(
+        signature: &PdfSignatureResult,
+        substring: &str,
+        page_number: u8,
+        offset: u32,
+    ) -> Self {
+        Self::with_suite(
+            HashSuite::Keccak256,
+            signature,
+            substring,
+            page_number,
+            offset,
+        )
+    }
+
+    /// `new`, with every hash and the nullifier computed under `suite`.
+    pub fn with_suite(
+        suite: HashSuite,
+        signature: &PdfSignatureResult,
+        substring: &str,
+        page_number: u8,
+        offset: u32,
+    ) -> Self {
+        let message_digest_hash = suite.hash(&signature.message_digest);
+        let signer_key_hash = suite.hash(&signature.public_key);
+        let substring_hash = suite.hash(substring.as_bytes());
+        let nullifier = compute_nullifier_with(
+            suite,
+            &message_digest_hash,
+            &signer_key_hash,
+            &substring_hash,
+            page_number,
+            offset,
+        );
+
+        Self {
+            message_digest_hash,
+            signer_key_hash,
+            substring_hash,
+            nullifier,
+        }
+    }
+
+    /// The hashes a proof that `substring` appears nowhere in the document commits to.
+    pub fn absent(signature: &PdfSignatureResult, substring: &str) -> Self {
+        let message_digest_hash = keccak256(&signature.message_digest);
+        let signer_key_hash = keccak256(&signature.public_key);
+        let substring_hash = keccak256(substring.as_bytes());
+        let nullifier =
+            compute_absence_nullifier(&message_digest_hash, &signer_key_hash, &substring_hash);
+
+        Self {
+            message_digest_hash,
+            signer_key_hash,
+            substring_hash,
+            nullifier,
+        }
+    }
+
+    /// The hashes a proof that the attribute `selector` of a signed XML document is `value`
+    /// commits to; `substring_hash` is the hash of the value.
+    pub fn xml(signature: &XmlSignatureResult, selector: &str, value: &str) -> Self {
+        let message_digest_hash = keccak256(&signature.message_digest);
+        let signer_key_hash = keccak256(&signature.public_key);
+        let substring_hash = keccak256(value.as_bytes());
+        let nullifier = compute_xml_nullifier(
+            &message_digest_hash,
+            &signer_key_hash,
+            &substring_hash,
+            &keccak256(selector.as_bytes()),
+        );
+
+        Self {
+            message_digest_hash,
+            signer_key_hash,
+            substring_hash,
+            nullifier,
+        }
+    }
+}
@@ -0,0 +1,85 @@
+//! Poseidon over the BN254 scalar field with circom's parameters, as `light-poseidon` and
+//! circomlib compute it, behind `HashSuite::Poseidon`. Claim hashes under it can be recomputed
+//! cheaply by a SNARK over BN254, such as a circom circuit that consumes a proof's public values.
+
+use alloc::vec::Vec;
+
+use ark_bn254::Fr;
+use ark_ff::{BigInteger, PrimeField};
+use light_poseidon::{Poseidon, PoseidonHasher};
+
+/// Bytes absorbed per field element when hashing a byte string; 31 bytes are always below the
+/// modulus.
+pub const CHUNK_LEN: usize = 31;
+
+/// Most inputs `hash_fields` takes, the widest circom Poseidon.
+pub const MAX_INPUTS: usize = 12;
+
+/// Hash of a byte string: the state starts as the length in bytes, then absorbs each 31-byte
+/// big-endian chunk in turn as `state = Poseidon(state, chunk)`, at least one even for empty
+/// data. The result is the final state, as 32 big-endian bytes.
+pub fn hash_bytes(data: &[u8]) -> [u8; 32] {
+    let mut poseidon = Poseidon::<Fr>::new_circom(2).expect("circom Poseidon takes two inputs");
+    let blocks = data.len().div_ceil(CHUNK_LEN).max(1);
+    let state = (0..blocks).fold(Fr::from(data.len() as u64), |state, block| {
+        let chunk = &data[block * CHUNK_LEN..data.len().min((block + 1) * CHUNK_LEN)];
+        poseidon
+            .hash(&[state, Fr::from_be_bytes_mod_order(chunk)])
+            .expect("two inputs")
+    });
+    to_bytes(state)
+}
+
+/// Poseidon of `inputs`, each read as a big-endian integer reduced modulo the field order, as
+/// 32 big-endian bytes. Panics for no inputs or more than `MAX_INPUTS`.
+pub fn hash_fields(inputs: &[&[u8]]) -> [u8; 32] {
+    assert!(
+        (1..=MAX_INPUTS).contains(&inputs.len()),
+        "circom Poseidon takes 1 to {} inputs",
+        MAX_INPUTS
+    );
+    let elements: Vec<Fr> = inputs
+        .iter()
+        .map(|input| Fr::from_be_bytes_mod_order(input))
+        .collect();
+    let mut poseidon = Poseidon::<Fr>::new_circom(elements.len()).expect("checked above");
+    to_bytes(poseidon.hash(&elements).expect("checked above"))
+}
+
+fn to_bytes(element: Fr) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    let be = element.into_bigint().to_bytes_be();
+    bytes[32 - be.len()..].copy_from_slice(&be);
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_poseidon_matches_circomlib() {
+        // circomlibjs `poseidon([1, 2])`
+        assert_eq!(
+            hex(&hash_fields(&[&[1], &[2]])),
+            "115cc0f5e7d690413df64c6b9662e9cf2a3617f2743245519e19607a4417189a"
+        );
+
+        let one_block = hash_bytes(b"Sample Signed PDF Document");
+        assert_eq!(
+            one_block,
+            hash_fields(&[&[26], b"Sample Signed PDF Document"])
+        );
+        assert_ne!(hash_bytes(b""), hash_bytes(b"\0"));
+        let long = [7u8; 40];
+        let first = hash_fields(&[&[40], &long[..CHUNK_LEN]]);
+        assert_eq!(
+            hash_bytes(&long),
+            hash_fields(&[&first, &long[CHUNK_LEN..]])
+        );
+    }
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+}
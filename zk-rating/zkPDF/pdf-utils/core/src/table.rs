@@ -0,0 +1,162 @@
+//! Reading a column of a table, such as the balance column of a bank statement, from the word
+//! boxes `extract_pages` returns.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{Page, Word};
+
+/// One cell of a column: the words of one row that lie under the column's header, joined by
+/// single spaces.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cell {
+    /// Zero-based page index.
+    pub page: usize,
+    pub text: String,
+    /// Baseline of the row, in default user space.
+    pub y: f32,
+}
+
+/// The words of `page`, grouped into lines: a word on the previous word's baseline, give or
+/// take half its height, continues the line.
+fn lines(page: &Page) -> Vec<&[Word]> {
+    let mut lines = Vec::new();
+    let mut start = 0;
+    for i in 1..=page.words.len() {
+        let breaks = page.words.get(i).is_none_or(|word| {
+            let previous = &page.words[i - 1];
+            (word.y - previous.y).abs() > previous.height.max(word.height) / 2.0
+        });
+        if breaks {
+            lines.push(&page.words[start..i]);
+            start = i;
+        }
+    }
+    lines
+}
+
+/// The horizontal extent of `header` in `line`, if its words appear there consecutively,
+/// compared ignoring case.
+fn header_span(line: &[Word], header: &[&str]) -> Option<(f32, f32)> {
+    if header.is_empty() || line.len() < header.len() {
+        return None;
+    }
+    line.windows(header.len()).find_map(|words| {
+        words
+            .iter()
+            .zip(header)
+            .all(|(word, name)| word.text.eq_ignore_ascii_case(name))
+            .then(|| {
+                let last = &words[words.len() - 1];
+                (words[0].x, last.x + last.width)
+            })
+    })
+}
+
+/// The cells of the column headed `header` (e.g. `Closing Balance`), top to bottom and page by
+/// page. A word belongs to the column if it overlaps the header horizontally, so right-aligned
+/// amounts wider than their header are read whole. The column continues on later pages,
+/// restarting below the header wherever the table repeats it; rows without a word under the
+/// header give no cell.
+pub fn column(pages: &[Page], header: &str) -> Vec<Cell> {
+    let header: Vec<&str> = header.split_whitespace().collect();
+    let mut span: Option<(f32, f32)> = None;
+    let mut cells = Vec::new();
+    for page in pages {
+        for line in lines(page) {
+            if let Some(found) = header_span(line, &header) {
+                span = Some(found);
+                continue;
+            }
+            let Some((left, right)) = span else {
+                continue;
+            };
+            let words: Vec<&str> = line
+                .iter()
+                .filter(|word| word.x < right && word.x + word.width > left)
+                .map(|word| word.text.as_str())
+                .collect();
+            if let Some(first) = line.first().filter(|_| !words.is_empty()) {
+                cells.push(Cell {
+                    page: page.index,
+                    text: words.join(" "),
+                    y: first.y,
+                });
+            }
+        }
+    }
+    cells
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    fn word(text: &str, x: f32, y: f32) -> Word {
+        Word {
+            text: text.to_string(),
+            x,
+            y,
+            width: 6.0 * text.len() as f32,
+            height: 10.0,
+        }
+    }
+
+    fn page(index: usize, words: Vec<Word>) -> Page {
+        Page {
+            index,
+            text: String::new(),
+            words,
+            fonts: Vec::new(),
+            rotation: 0,
+            media_box: [0.0, 0.0, 612.0, 792.0],
+        }
+    }
+
+    #[test]
+    fn test_column_across_pages() {
+        let pages = vec![
+            page(
+                0,
+                vec![
+                    word("Opening", 72.0, 740.0),
+                    word("Balance", 120.0, 740.0),
+                    word("5,000.00", 300.0, 740.0),
+                    word("Date", 72.0, 700.0),
+                    word("Amount", 200.0, 700.0),
+                    word("Balance", 300.0, 700.0),
+                    word("01/04/24", 72.0, 686.0),
+                    word("2,000.00", 200.0, 686.0),
+                    // Right-aligned, so it starts left of the header
+                    word("1,20,000.50", 290.0, 686.5),
+                    word("Dr", 360.0, 686.0),
+                ],
+            ),
+            page(
+                1,
+                vec![
+                    word("02/04/24", 72.0, 740.0),
+                    word("500.00", 200.0, 740.0),
+                    word("1,19,500.50", 290.0, 740.0),
+                    word("Balance", 300.0, 600.0),
+                    word("7.00", 306.0, 586.0),
+                ],
+            ),
+        ];
+
+        let cells = column(&pages, "balance");
+        let texts: Vec<&str> = cells.iter().map(|cell| cell.text.as_str()).collect();
+        assert_eq!(texts, ["1,20,000.50", "1,19,500.50", "7.00"]);
+        assert_eq!((cells[1].page, cells[1].y), (1, 740.0));
+
+        // A header spanning two words, which must be consecutive on one line
+        let texts: Vec<String> = column(&pages, "Opening Balance")
+            .into_iter()
+            .map(|cell| cell.text)
+            .collect();
+        assert_eq!(texts, ["Date", "01/04/24", "02/04/24"]);
+        assert!(column(&pages, "Closing Balance").is_empty());
+        assert!(column(&pages, "").is_empty());
+    }
+}
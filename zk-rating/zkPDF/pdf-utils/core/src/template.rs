@@ -0,0 +1,106 @@
+use regex::Regex;
+
+use crate::{
+    verify_and_extract_with_options, Error, ExtractOptions, PdfSignatureResult, PdfVerifiedContent,
+    VerificationPolicy,
+};
+
+/// A named value located in the extracted text by a regular expression.
+#[derive(Debug, Clone)]
+pub struct FieldPattern {
+    pub name: String,
+    pub pattern: String,
+    /// Capture group holding the value; `0` uses the whole match.
+    pub group: usize,
+}
+
+/// A set of fields to extract from one kind of document.
+#[derive(Debug, Clone, Default)]
+pub struct Template {
+    pub fields: Vec<FieldPattern>,
+}
+
+/// A field value together with the page and byte offset that `verify_text` expects for it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtractedField {
+    pub name: String,
+    pub value: String,
+    pub page: usize,
+    pub offset: usize,
+}
+
+/// Result returned by `apply_template`.
+#[derive(Debug, Clone)]
+pub struct TemplateResult {
+    pub fields: Vec<ExtractedField>,
+    pub signature: PdfSignatureResult,
+}
+
+impl Template {
+    /// GST registration certificate (Form GST REG-06): GST number and legal name.
+    pub fn gst_certificate() -> Self {
+        Self {
+            fields: vec![
+                FieldPattern {
+                    name: "gst_number".to_string(),
+                    pattern: r"([0-9]{2}[A-Z]{5}[0-9]{4}[A-Z]{1}[1-9A-Z]{1}[Z]{1}[0-9A-Z]{1})"
+                        .to_string(),
+                    group: 1,
+                },
+                FieldPattern {
+                    name: "legal_name".to_string(),
+                    pattern: r"Legal Name\s*([A-Za-z\s&.,]+?)(?:\n|Trade Name|Additional|$)"
+                        .to_string(),
+                    group: 1,
+                },
+            ],
+        }
+    }
+
+    /// Finds every field in `pages`, taking the first match in page order. Values are trimmed of
+    /// surrounding whitespace and offsets point at the trimmed value.
+    pub fn match_pages(&self, pages: &[String]) -> Result<Vec<ExtractedField>, Error> {
+        self.fields
+            .iter()
+            .map(|field| {
+                let regex = Regex::new(&field.pattern).map_err(|e| Error::InvalidPattern {
+                    field: Some(field.name.clone()),
+                    message: e.to_string(),
+                })?;
+
+                pages
+                    .iter()
+                    .enumerate()
+                    .find_map(|(page, text)| {
+                        let m = regex.captures(text)?.get(field.group)?;
+                        let raw = m.as_str();
+                        let value = raw.trim();
+                        let leading = raw.len() - raw.trim_start().len();
+                        Some(ExtractedField {
+                            name: field.name.clone(),
+                            value: value.to_string(),
+                            page,
+                            offset: m.start() + leading,
+                        })
+                    })
+                    .filter(|extracted| !extracted.value.is_empty())
+                    .ok_or_else(|| Error::FieldNotFound(field.name.clone()))
+            })
+            .collect()
+    }
+}
+
+/// Verifies the PDF's signature, extracts its text and locates every field of `template`.
+/// Fails if any field is missing.
+pub fn apply_template(
+    pdf_bytes: Vec<u8>,
+    template: &Template,
+    options: &ExtractOptions,
+    policy: &VerificationPolicy,
+) -> Result<TemplateResult, Error> {
+    let PdfVerifiedContent { pages, signature } =
+        verify_and_extract_with_options(pdf_bytes, options, policy)?;
+    let fields = template.match_pages(&pages)?;
+
+    Ok(TemplateResult { fields, signature })
+}
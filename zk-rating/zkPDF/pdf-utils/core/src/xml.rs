@@ -0,0 +1,84 @@
+//! Claims about signed XML documents, such as DigiLocker certificates, checked with
+//! `zkpdf-xml-validator`.
+
+use alloc::string::{String, ToString};
+
+use zkpdf_xml_validator::verify_xml;
+pub use zkpdf_xml_validator::{VerifiedXml, XmlError, XmlSignatureResult};
+
+use crate::Error;
+
+/// Result returned by `verify_xml_attribute`.
+#[derive(Debug, Clone)]
+pub struct XmlAttributeResult {
+    /// The attribute's value, with entity and character references resolved.
+    pub value: String,
+    pub signature: XmlSignatureResult,
+}
+
+/// Verifies an XML document's enveloped signature and reads the attribute `selector` names,
+/// such as `Certificate/IssuedTo/Person@name`, from the signed content. A signature that does
+/// not verify is `Error::InvalidSignature`; an attribute that is missing, or outside what the
+/// signature covers, is `Error::FieldNotFound`.
+pub fn verify_xml_attribute(xml_bytes: &[u8], selector: &str) -> Result<XmlAttributeResult, Error> {
+    let verified = verify_xml(xml_bytes)?;
+    if !verified.signature.is_valid {
+        return Err(Error::InvalidSignature);
+    }
+    let value = verified
+        .attribute(selector)
+        .ok_or_else(|| Error::FieldNotFound(selector.to_string()))?
+        .to_string();
+    Ok(XmlAttributeResult {
+        value,
+        signature: verified.signature,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nullifier::ClaimHashes;
+
+    static SIGNED: &str = include_str!("../../xml-validator/tests/data/signed_certificate.xml");
+
+    #[test]
+    fn test_verify_xml_attribute() {
+        let result =
+            verify_xml_attribute(SIGNED.as_bytes(), "Certificate/IssuedTo/Person@name").unwrap();
+        assert_eq!(result.value, "Asha Kumari");
+        assert!(result.signature.is_valid);
+
+        assert!(matches!(
+            verify_xml_attribute(SIGNED.as_bytes(), "Certificate/IssuedTo/Person@email"),
+            Err(Error::FieldNotFound(selector)) if selector == "Certificate/IssuedTo/Person@email"
+        ));
+        assert!(matches!(
+            verify_xml_attribute(
+                SIGNED.replacen("Asha", "Usha", 1).as_bytes(),
+                "Certificate/IssuedTo/Person@name"
+            ),
+            Err(Error::Xml(XmlError::DigestMismatch { .. }))
+        ));
+        let start = SIGNED.find("<SignatureValue>\n").unwrap() + "<SignatureValue>\n".len();
+        let mut forged = SIGNED.to_string();
+        let flipped = if &SIGNED[start..start + 1] == "A" {
+            "B"
+        } else {
+            "A"
+        };
+        forged.replace_range(start..start + 1, flipped);
+        assert!(matches!(
+            verify_xml_attribute(forged.as_bytes(), "Certificate/IssuedTo/Person@name"),
+            Err(Error::InvalidSignature)
+        ));
+
+        // The nullifier is bound to the selector, not only to the value
+        let dob =
+            verify_xml_attribute(SIGNED.as_bytes(), "Certificate/IssuedTo/Person@dob").unwrap();
+        let name = ClaimHashes::xml(&result.signature, "Certificate/IssuedTo/Person@name", "x");
+        let other = ClaimHashes::xml(&dob.signature, "Certificate/IssuedTo/Person@dob", "x");
+        assert_eq!(name.substring_hash, other.substring_hash);
+        assert_ne!(name.nullifier, other.nullifier);
+    }
+}
@@ -0,0 +1,291 @@
+//! Reading files out of a ZIP archive, such as the share-code protected archive UIDAI's
+//! offline e-KYC comes in. Entries may be stored or deflated, and encrypted with traditional
+//! PKWARE encryption (ZipCrypto); AES encryption, ZIP64 and multi-disk archives are not
+//! supported.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use miniz_oxide::inflate::decompress_to_vec_with_limit;
+
+const END_OF_CENTRAL_DIRECTORY: u32 = 0x0605_4b50;
+const CENTRAL_DIRECTORY_HEADER: u32 = 0x0201_4b50;
+const LOCAL_FILE_HEADER: u32 = 0x0403_4b50;
+
+const STORED: u16 = 0;
+const DEFLATED: u16 = 8;
+
+const FLAG_ENCRYPTED: u16 = 1;
+/// Sizes and CRC follow the data, so an encrypted entry's check byte is from the time.
+const FLAG_DATA_DESCRIPTOR: u16 = 1 << 3;
+
+/// Length of the encryption header that precedes an encrypted entry's data.
+const ENCRYPTION_HEADER_LEN: usize = 12;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ZipError {
+    /// The archive is truncated or a header is invalid.
+    Malformed(&'static str),
+    /// An entry uses a compression method other than stored or deflate; AES encryption
+    /// shows up as method 99.
+    UnsupportedMethod(u16),
+    /// The password does not decrypt the entry, or none was given for an encrypted one.
+    WrongPassword,
+    /// No entry has the requested name.
+    NotFound(String),
+}
+
+impl fmt::Display for ZipError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ZipError::Malformed(msg) => write!(f, "malformed ZIP archive: {}", msg),
+            ZipError::UnsupportedMethod(method) => {
+                write!(f, "unsupported ZIP compression method {}", method)
+            }
+            ZipError::WrongPassword => write!(f, "wrong password for ZIP entry"),
+            ZipError::NotFound(name) => write!(f, "ZIP entry `{}` not found", name),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ZipError {}
+
+/// One file of an archive, as its central directory describes it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ZipEntry {
+    pub name: String,
+    pub encrypted: bool,
+    pub uncompressed_size: u32,
+    flags: u16,
+    method: u16,
+    modified_time: u16,
+    crc32: u32,
+    compressed_size: u32,
+    local_header_offset: u32,
+}
+
+/// The entries of `archive`, in central directory order.
+pub fn entries(archive: &[u8]) -> Result<Vec<ZipEntry>, ZipError> {
+    // The end of central directory record is last, followed by a comment of up to 64 KiB
+    let end = (0..=archive.len().saturating_sub(22))
+        .rev()
+        .take(22 + 0xffff)
+        .find(|&at| u32_at(archive, at) == Some(END_OF_CENTRAL_DIRECTORY))
+        .ok_or(ZipError::Malformed("end of central directory not found"))?;
+    let count = u16_at(archive, end + 10).ok_or(ZipError::Malformed("truncated"))?;
+    let mut at = u32_at(archive, end + 16).ok_or(ZipError::Malformed("truncated"))? as usize;
+
+    let mut entries = Vec::with_capacity(count.into());
+    for _ in 0..count {
+        let field = |offset: usize| u32_at(archive, at + offset);
+        let short = |offset: usize| u16_at(archive, at + offset);
+        if field(0) != Some(CENTRAL_DIRECTORY_HEADER) {
+            return Err(ZipError::Malformed("bad central directory header"));
+        }
+        let (Some(name_len), Some(extra_len), Some(comment_len)) =
+            (short(28), short(30), short(32))
+        else {
+            return Err(ZipError::Malformed("truncated"));
+        };
+        let name_start = at + 46;
+        let name = archive
+            .get(name_start..name_start + usize::from(name_len))
+            .ok_or(ZipError::Malformed("truncated"))?;
+        let flags = short(8).ok_or(ZipError::Malformed("truncated"))?;
+        let (Some(method), Some(modified_time)) = (short(10), short(12)) else {
+            return Err(ZipError::Malformed("truncated"));
+        };
+        let (Some(crc32), Some(compressed_size), Some(uncompressed_size), Some(offset)) =
+            (field(16), field(20), field(24), field(42))
+        else {
+            return Err(ZipError::Malformed("truncated"));
+        };
+        entries.push(ZipEntry {
+            name: String::from_utf8_lossy(name).into_owned(),
+            encrypted: flags & FLAG_ENCRYPTED != 0,
+            uncompressed_size,
+            flags,
+            method,
+            modified_time,
+            crc32,
+            compressed_size,
+            local_header_offset: offset,
+        });
+        at = name_start + usize::from(name_len) + usize::from(extra_len) + usize::from(comment_len);
+    }
+    Ok(entries)
+}
+
+/// The contents of `entry`, decrypted with `password` if it is encrypted. The result is
+/// checked against the entry's CRC-32, so a wrong password is `ZipError::WrongPassword`
+/// rather than garbage.
+pub fn read(archive: &[u8], entry: &ZipEntry, password: &[u8]) -> Result<Vec<u8>, ZipError> {
+    if entry.method != STORED && entry.method != DEFLATED {
+        return Err(ZipError::UnsupportedMethod(entry.method));
+    }
+    let at = entry.local_header_offset as usize;
+    if u32_at(archive, at) != Some(LOCAL_FILE_HEADER) {
+        return Err(ZipError::Malformed("bad local file header"));
+    }
+    let (Some(name_len), Some(extra_len)) = (u16_at(archive, at + 26), u16_at(archive, at + 28))
+    else {
+        return Err(ZipError::Malformed("truncated"));
+    };
+    let start = at + 30 + usize::from(name_len) + usize::from(extra_len);
+    let data = archive
+        .get(start..start + entry.compressed_size as usize)
+        .ok_or(ZipError::Malformed("truncated"))?;
+
+    let decrypted;
+    let data = if entry.encrypted {
+        let check = if entry.flags & FLAG_DATA_DESCRIPTOR != 0 {
+            (entry.modified_time >> 8) as u8
+        } else {
+            (entry.crc32 >> 24) as u8
+        };
+        decrypted = decrypt(data, password, check)?;
+        &decrypted[..]
+    } else {
+        data
+    };
+
+    let limit = entry.uncompressed_size as usize;
+    let contents = match entry.method {
+        STORED => Some(data.to_vec()),
+        _ => decompress_to_vec_with_limit(data, limit).ok(),
+    };
+    match contents {
+        Some(contents) if contents.len() == limit && crc32(&contents) == entry.crc32 => {
+            Ok(contents)
+        }
+        _ if entry.encrypted => Err(ZipError::WrongPassword),
+        _ => Err(ZipError::Malformed("entry does not match its CRC-32")),
+    }
+}
+
+/// The contents of the entry named `name`.
+pub fn read_named(archive: &[u8], name: &str, password: &[u8]) -> Result<Vec<u8>, ZipError> {
+    let entry = entries(archive)?
+        .into_iter()
+        .find(|entry| entry.name == name)
+        .ok_or_else(|| ZipError::NotFound(name.into()))?;
+    read(archive, &entry, password)
+}
+
+/// Decrypts ZipCrypto `data`: the 12-byte encryption header, whose last byte must be `check`,
+/// then the entry's data, which is returned.
+fn decrypt(data: &[u8], password: &[u8], check: u8) -> Result<Vec<u8>, ZipError> {
+    if data.len() < ENCRYPTION_HEADER_LEN {
+        return Err(ZipError::Malformed("truncated encryption header"));
+    }
+    let mut keys = Keys::new(password);
+    let mut plain: Vec<u8> = data.iter().map(|&byte| keys.decrypt(byte)).collect();
+    if plain[ENCRYPTION_HEADER_LEN - 1] != check {
+        return Err(ZipError::WrongPassword);
+    }
+    plain.drain(..ENCRYPTION_HEADER_LEN);
+    Ok(plain)
+}
+
+/// The traditional PKWARE cipher state (APPNOTE.TXT section 6.1).
+struct Keys([u32; 3]);
+
+impl Keys {
+    fn new(password: &[u8]) -> Self {
+        let mut keys = Keys([0x1234_5678, 0x2345_6789, 0x3456_7890]);
+        for &byte in password {
+            keys.update(byte);
+        }
+        keys
+    }
+
+    fn update(&mut self, byte: u8) {
+        let [k0, k1, k2] = &mut self.0;
+        *k0 = crc32_update(*k0, byte);
+        *k1 = k1
+            .wrapping_add(*k0 & 0xff)
+            .wrapping_mul(134_775_813)
+            .wrapping_add(1);
+        *k2 = crc32_update(*k2, (*k1 >> 24) as u8);
+    }
+
+    fn decrypt(&mut self, byte: u8) -> u8 {
+        let temp = (self.0[2] | 2) & 0xffff;
+        let plain = byte ^ ((temp.wrapping_mul(temp ^ 1) >> 8) as u8);
+        self.update(plain);
+        plain
+    }
+}
+
+/// The CRC-32 lookup table for the reflected polynomial 0xEDB88320.
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                0xedb8_8320 ^ (crc >> 1)
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+fn crc32_update(crc: u32, byte: u8) -> u32 {
+    (crc >> 8) ^ CRC32_TABLE[((crc ^ u32::from(byte)) & 0xff) as usize]
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0, |crc, &byte| crc32_update(crc, byte))
+}
+
+fn u16_at(data: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(data.get(at..at + 2)?.try_into().ok()?))
+}
+
+fn u32_at(data: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(data.get(at..at + 4)?.try_into().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static ARCHIVE: &[u8] = include_bytes!("../../xml-validator/tests/data/offline_ekyc.zip");
+    static XML: &[u8] = include_bytes!("../../xml-validator/tests/data/offline_ekyc.xml");
+    const NAME: &str = "offlineaadhaar20190308112233444.xml";
+
+    #[test]
+    fn test_read_encrypted_entry() {
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+
+        let entries = entries(ARCHIVE).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].name, NAME);
+        assert!(entries[0].encrypted);
+        assert_eq!(read_named(ARCHIVE, NAME, b"Ab12").unwrap(), XML);
+
+        assert_eq!(
+            read_named(ARCHIVE, NAME, b"Ab13"),
+            Err(ZipError::WrongPassword)
+        );
+        assert_eq!(read_named(ARCHIVE, NAME, b""), Err(ZipError::WrongPassword));
+        assert_eq!(
+            read_named(ARCHIVE, "other.xml", b"Ab12"),
+            Err(ZipError::NotFound("other.xml".into()))
+        );
+        assert!(matches!(
+            read_named(&ARCHIVE[..ARCHIVE.len() - 30], NAME, b"Ab12"),
+            Err(ZipError::Malformed(_))
+        ));
+    }
+}
@@ -0,0 +1,183 @@
+//! Golden-corpus regression harness: extraction and signature verification over every fixture
+//! in `golden-corpus/` (or `$ZKPDF_GOLDEN_DIR`), compared against the stored expectations.
+//!
+//! Each `<name>.json` names its PDF (`file`, relative to the corpus directory) and records the
+//! keccak256 of every page's text and the signature outcome. Only hashes are stored, so
+//! expectations for private documents do not leak their contents.
+//!
+//! - `ZKPDF_GOLDEN_BLESS=1` rewrites the expectations, and creates them for PDFs (`*.pdf`,
+//!   `*.pdf.enc`) in the corpus directory that have none.
+//! - `ZKPDF_GOLDEN_KEY` decrypts `*.pdf.enc` fixtures; without it they are skipped.
+
+mod golden_support;
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use core::nullifier::keccak256;
+use extractor::extract_text;
+use serde::{Deserialize, Serialize};
+use signature_validator::verify_pdf_signature;
+
+use golden_support::{apply_keystream, KEY_VAR};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Expectation {
+    file: String,
+    extraction: Outcome<Vec<String>>,
+    signature: Outcome<SignatureOutcome>,
+}
+
+impl Expectation {
+    // Overwritten with the observed outcome before anything compares against it
+    fn placeholder(file: String) -> Self {
+        Self {
+            file,
+            extraction: Outcome::Error(String::new()),
+            signature: Outcome::Error(String::new()),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Outcome<T> {
+    Ok(T),
+    Error(String),
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct SignatureOutcome {
+    is_valid: bool,
+    message_digest_keccak: String,
+    public_key_keccak: String,
+}
+
+fn hex_keccak(data: &[u8]) -> String {
+    keccak256(data)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn corpus_dir() -> PathBuf {
+    match std::env::var("ZKPDF_GOLDEN_DIR") {
+        Ok(dir) => PathBuf::from(dir),
+        Err(_) => Path::new(env!("CARGO_MANIFEST_DIR")).join("../golden-corpus"),
+    }
+}
+
+/// Reads a fixture, decrypting `*.enc` files. `None` if it is encrypted and no key is set.
+fn load_fixture(path: &Path) -> Option<Vec<u8>> {
+    let mut data =
+        fs::read(path).unwrap_or_else(|e| panic!("failed to read {}: {}", path.display(), e));
+    if path.extension().is_some_and(|ext| ext == "enc") {
+        let passphrase = std::env::var(KEY_VAR).ok()?;
+        apply_keystream(&passphrase, &mut data);
+    }
+    Some(data)
+}
+
+fn observe(file: &str, data: &[u8]) -> Expectation {
+    let extraction = match extract_text(data.to_vec()) {
+        Ok(pages) => Outcome::Ok(pages.iter().map(|p| hex_keccak(p.as_bytes())).collect()),
+        Err(e) => Outcome::Error(e.to_string()),
+    };
+    let signature = match verify_pdf_signature(data) {
+        Ok(signature) => Outcome::Ok(SignatureOutcome {
+            is_valid: signature.is_valid,
+            message_digest_keccak: hex_keccak(&signature.message_digest),
+            public_key_keccak: hex_keccak(&signature.public_key),
+        }),
+        Err(e) => Outcome::Error(e.to_string()),
+    };
+    Expectation {
+        file: file.to_string(),
+        extraction,
+        signature,
+    }
+}
+
+fn write_expectation(path: &Path, expectation: &Expectation) {
+    let json = serde_json::to_string_pretty(expectation).unwrap() + "\n";
+    fs::write(path, json).unwrap_or_else(|e| panic!("failed to write {}: {}", path.display(), e));
+}
+
+#[test]
+fn golden_corpus() {
+    let dir = corpus_dir();
+    let bless = std::env::var_os("ZKPDF_GOLDEN_BLESS").is_some();
+
+    let mut expectations = BTreeMap::new();
+    let mut unlisted = Vec::new();
+    for entry in fs::read_dir(&dir).expect("golden corpus directory not found") {
+        let path = entry.unwrap().path();
+        let name = path.file_name().unwrap().to_string_lossy().into_owned();
+        if name.ends_with(".json") {
+            let expectation: Expectation = serde_json::from_slice(&fs::read(&path).unwrap())
+                .unwrap_or_else(|e| panic!("invalid expectation {}: {}", name, e));
+            expectations.insert(path, expectation);
+        } else if name.ends_with(".pdf") || name.ends_with(".pdf.enc") {
+            unlisted.push(name);
+        }
+    }
+    unlisted.retain(|name| !expectations.values().any(|e| &e.file == name));
+
+    if bless {
+        for name in unlisted.drain(..) {
+            let stem = name.trim_end_matches(".enc").trim_end_matches(".pdf");
+            let path = dir.join(format!("{}.json", stem));
+            expectations.insert(path, Expectation::placeholder(name));
+        }
+    }
+    assert!(
+        unlisted.is_empty(),
+        "fixtures without expectations (run with ZKPDF_GOLDEN_BLESS=1): {:?}",
+        unlisted
+    );
+
+    let mut failures = Vec::new();
+    let mut skipped = 0;
+    for (path, expected) in &expectations {
+        let Some(data) = load_fixture(&dir.join(&expected.file)) else {
+            skipped += 1;
+            continue;
+        };
+        let actual = observe(&expected.file, &data);
+        if bless {
+            write_expectation(path, &actual);
+        } else if &actual != expected {
+            failures.push(format!(
+                "{}:\n  expected {:?}\n  actual   {:?}",
+                path.display(),
+                expected,
+                actual
+            ));
+        }
+    }
+
+    if skipped > 0 {
+        eprintln!(
+            "skipped {} encrypted fixtures: {} is not set",
+            skipped, KEY_VAR
+        );
+    }
+    assert!(
+        failures.is_empty(),
+        "{} of {} golden fixtures changed (rerun with ZKPDF_GOLDEN_BLESS=1 if intended):\n{}",
+        failures.len(),
+        expectations.len(),
+        failures.join("\n")
+    );
+}
+
+#[test]
+fn keystream_round_trips() {
+    let original = b"%PDF-1.7 fixture bytes spanning more than one keystream block".to_vec();
+    let mut data = original.clone();
+    apply_keystream("passphrase", &mut data);
+    assert_ne!(data, original);
+    apply_keystream("passphrase", &mut data);
+    assert_eq!(data, original);
+}
@@ -0,0 +1,30 @@
+//! Shared by the golden-corpus harness (`tests/golden.rs`) and the `golden_crypt` example.
+//!
+//! Fixtures derived from real documents may be stored encrypted at rest as `*.pdf.enc`. The
+//! cipher XORs the file with a keccak keystream derived from `ZKPDF_GOLDEN_KEY`; it keeps
+//! personal documents unreadable in a checkout, it does not authenticate them.
+
+use tiny_keccak::{Hasher, Keccak};
+
+pub const KEY_VAR: &str = "ZKPDF_GOLDEN_KEY";
+
+fn keccak(parts: &[&[u8]]) -> [u8; 32] {
+    let mut hasher = Keccak::v256();
+    for part in parts {
+        hasher.update(part);
+    }
+    let mut output = [0u8; 32];
+    hasher.finalize(&mut output);
+    output
+}
+
+/// Encrypts or decrypts `data` in place; the transform is its own inverse.
+pub fn apply_keystream(passphrase: &str, data: &mut [u8]) {
+    let key = keccak(&[b"zkpdf-golden-v0", passphrase.as_bytes()]);
+    for (counter, chunk) in data.chunks_mut(32).enumerate() {
+        let block = keccak(&[&key, &(counter as u64).to_le_bytes()]);
+        for (byte, k) in chunk.iter_mut().zip(block) {
+            *byte ^= k;
+        }
+    }
+}
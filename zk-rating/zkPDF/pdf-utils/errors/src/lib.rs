@@ -0,0 +1,411 @@
+//! Stable numeric error codes shared by every zkPDF surface.
+//!
+//! The CLI exit status, the prover server's HTTP responses, the WASM error objects and the
+//! circuit failure log all derive from [`ErrorCode`], so a document that fails in one place
+//! reports the same code everywhere. Codes are grouped by the thousands digit:
+//!
+//! | Range | Category                                       |
+//! | ----- | ---------------------------------------------- |
+//! | 1xxx  | caller input (bad arguments, unreadable files) |
+//! | 2xxx  | PDF and XML parsing, text extraction           |
+//! | 3xxx  | signature checking                             |
+//! | 4xxx  | claims and templates                           |
+//! | 5xxx  | proving and internal failures                  |
+//!
+//! Numbers are never reused or renumbered; new codes are appended within their range.
+
+#![no_std]
+
+extern crate alloc;
+
+use alloc::string::{String, ToString};
+use core::fmt;
+
+use extractor::types::PdfError;
+#[cfg(feature = "verify")]
+use pdf_core::xml::XmlError;
+#[cfg(feature = "verify")]
+use pdf_core::zip::ZipError;
+#[cfg(feature = "verify")]
+use signature_validator::types::{
+    FailureReason, Pkcs7Error, SignatureValidationError, SignedBytesError,
+};
+
+macro_rules! error_codes {
+    ($($(#[$doc:meta])* $variant:ident = $code:literal, $name:literal;)*) => {
+        /// A stable diagnostic code. See the crate docs for the numbering scheme.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #[repr(u16)]
+        pub enum ErrorCode {
+            $($(#[$doc])* $variant = $code,)*
+        }
+
+        impl ErrorCode {
+            /// Every code, in numeric order.
+            pub const ALL: &'static [ErrorCode] = &[$(ErrorCode::$variant,)*];
+
+            /// The `snake_case` name, stable like the number.
+            pub const fn name(self) -> &'static str {
+                match self {
+                    $(ErrorCode::$variant => $name,)*
+                }
+            }
+
+            /// Looks up a code by number.
+            pub const fn from_code(code: u16) -> Option<ErrorCode> {
+                match code {
+                    $($code => Some(ErrorCode::$variant),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+error_codes! {
+    /// The request or command line is malformed.
+    InvalidInput = 1000, "invalid_input";
+    /// An input file could not be read.
+    Io = 1001, "io";
+    /// The password, such as an offline e-KYC share code, does not decrypt the archive.
+    WrongPassword = 1002, "wrong_password";
+    /// The PDF structure could not be parsed.
+    PdfParse = 2000, "pdf_parse";
+    /// A content stream failed to decompress.
+    PdfDecompression = 2001, "pdf_decompression";
+    /// The requested page does not exist.
+    PageOutOfBounds = 2002, "page_out_of_bounds";
+    /// The PDF is encrypted with a user password or an unsupported security handler.
+    PdfEncrypted = 2003, "pdf_encrypted";
+    /// An XML document is not well-formed, or declares a DOCTYPE.
+    XmlParse = 2004, "xml_parse";
+    /// A ZIP archive is malformed, or uses compression or encryption we do not support.
+    ArchiveParse = 2005, "archive_parse";
+    /// The PDF has no `/ByteRange`, or only document timestamps, so it is not signed.
+    NotSigned = 3000, "not_signed";
+    /// The signature dictionary or PKCS#7 blob is malformed.
+    MalformedSignature = 3001, "malformed_signature";
+    /// The signature uses a digest or signature algorithm we do not support.
+    UnsupportedAlgorithm = 3002, "unsupported_algorithm";
+    /// The signed bytes do not hash to the signed message digest: the document was modified.
+    DigestMismatch = 3003, "digest_mismatch";
+    /// The signer's public key could not be decoded.
+    InvalidPublicKey = 3004, "invalid_public_key";
+    /// The RSA signature does not verify.
+    InvalidSignature = 3005, "invalid_signature";
+    /// The signature verifies but breaks the caller's verification policy.
+    PolicyViolation = 3006, "policy_violation";
+    /// A claim or template pattern does not compile.
+    InvalidPattern = 4000, "invalid_pattern";
+    /// A template field did not match on any page.
+    FieldNotFound = 4001, "field_not_found";
+    /// The claimed text is not at the claimed offset.
+    ClaimMismatch = 4002, "claim_mismatch";
+    /// No document template is registered under the requested id.
+    UnknownTemplate = 4003, "unknown_template";
+    /// Proving failed and will fail again for the same input.
+    ProvingFailed = 5000, "proving_failed";
+    /// The prover is temporarily unavailable; retrying may succeed.
+    ProverUnavailable = 5001, "prover_unavailable";
+    /// The server is at capacity.
+    ProverBusy = 5002, "prover_busy";
+    /// An unexpected internal error.
+    Internal = 5999, "internal";
+}
+
+impl ErrorCode {
+    /// The numeric code.
+    pub const fn code(self) -> u16 {
+        self as u16
+    }
+
+    /// The HTTP status a server should answer with.
+    pub const fn http_status(self) -> u16 {
+        match self {
+            ErrorCode::InvalidInput
+            | ErrorCode::Io
+            | ErrorCode::WrongPassword
+            | ErrorCode::InvalidPattern
+            | ErrorCode::UnknownTemplate => 400,
+            ErrorCode::ProverBusy => 429,
+            ErrorCode::Internal => 500,
+            ErrorCode::ProverUnavailable => 503,
+            _ => 422,
+        }
+    }
+
+    /// The process exit status for command-line tools. `0` is success and `1` is reserved for
+    /// unexpected failures; each category gets its own status, with a separate one for
+    /// documents whose signature is present but wrong.
+    pub const fn exit_code(self) -> u8 {
+        match self {
+            ErrorCode::InvalidInput | ErrorCode::Io | ErrorCode::WrongPassword => 2,
+            ErrorCode::PdfParse
+            | ErrorCode::PdfDecompression
+            | ErrorCode::PageOutOfBounds
+            | ErrorCode::PdfEncrypted
+            | ErrorCode::XmlParse
+            | ErrorCode::ArchiveParse => 3,
+            ErrorCode::DigestMismatch
+            | ErrorCode::InvalidSignature
+            | ErrorCode::PolicyViolation => 5,
+            ErrorCode::NotSigned
+            | ErrorCode::MalformedSignature
+            | ErrorCode::UnsupportedAlgorithm
+            | ErrorCode::InvalidPublicKey => 4,
+            ErrorCode::InvalidPattern
+            | ErrorCode::FieldNotFound
+            | ErrorCode::ClaimMismatch
+            | ErrorCode::UnknownTemplate => 6,
+            ErrorCode::ProvingFailed
+            | ErrorCode::ProverUnavailable
+            | ErrorCode::ProverBusy
+            | ErrorCode::Internal => 1,
+        }
+    }
+}
+
+/// Formats as `E` followed by the number, e.g. `E3003`.
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "E{}", self.code())
+    }
+}
+
+impl From<&PdfError> for ErrorCode {
+    fn from(e: &PdfError) -> Self {
+        match e {
+            PdfError::ParseError(_) | PdfError::ObjectError { .. } => ErrorCode::PdfParse,
+            PdfError::DecompressionError => ErrorCode::PdfDecompression,
+            PdfError::EncryptionError(_) => ErrorCode::PdfEncrypted,
+        }
+    }
+}
+
+#[cfg(feature = "verify")]
+impl From<&SignatureValidationError> for ErrorCode {
+    fn from(e: &SignatureValidationError) -> Self {
+        match e {
+            SignatureValidationError::SignedBytes(
+                SignedBytesError::ByteRangeNotFound
+                | SignedBytesError::ByteRangeStartMissing
+                | SignedBytesError::ByteRangeEndMissing
+                | SignedBytesError::NoApprovalSignature,
+            ) => ErrorCode::NotSigned,
+            SignatureValidationError::SignedBytes(_) => ErrorCode::MalformedSignature,
+            SignatureValidationError::Pkcs7(Pkcs7Error::UnsupportedDigestOid(_))
+            | SignatureValidationError::UnsupportedAlgorithm(_) => ErrorCode::UnsupportedAlgorithm,
+            SignatureValidationError::Pkcs7(_) => ErrorCode::MalformedSignature,
+            SignatureValidationError::MessageDigestMismatch { .. } => ErrorCode::DigestMismatch,
+            SignatureValidationError::InvalidPublicKey(_) => ErrorCode::InvalidPublicKey,
+            SignatureValidationError::SignatureVerification(_) => ErrorCode::MalformedSignature,
+            SignatureValidationError::Policy(_) => ErrorCode::PolicyViolation,
+            SignatureValidationError::SigningCertificateMismatch
+            | SignatureValidationError::Profile(_) => ErrorCode::MalformedSignature,
+        }
+    }
+}
+
+/// The code `verify_pdf_signature` reports for a signature failing this way.
+#[cfg(feature = "verify")]
+impl From<&FailureReason> for ErrorCode {
+    fn from(reason: &FailureReason) -> Self {
+        match reason {
+            FailureReason::DigestMismatch { .. } => ErrorCode::DigestMismatch,
+            FailureReason::BadSignature => ErrorCode::InvalidSignature,
+            FailureReason::SigningCertificateMismatch | FailureReason::Profile(_) => {
+                ErrorCode::MalformedSignature
+            }
+        }
+    }
+}
+
+#[cfg(feature = "verify")]
+impl From<&pdf_core::Error> for ErrorCode {
+    fn from(e: &pdf_core::Error) -> Self {
+        match e {
+            pdf_core::Error::Signature(e) => e.into(),
+            pdf_core::Error::InvalidSignature => ErrorCode::InvalidSignature,
+            pdf_core::Error::Extraction(e) => e.into(),
+            pdf_core::Error::PageOutOfBounds { .. } => ErrorCode::PageOutOfBounds,
+            pdf_core::Error::InvalidPattern { .. } => ErrorCode::InvalidPattern,
+            pdf_core::Error::FieldNotFound(_) => ErrorCode::FieldNotFound,
+            pdf_core::Error::Xml(e) => e.into(),
+            pdf_core::Error::UnknownTemplate(_) => ErrorCode::UnknownTemplate,
+            pdf_core::Error::UntrustedSigner | pdf_core::Error::UnsignedUpdates { .. } => {
+                ErrorCode::PolicyViolation
+            }
+            pdf_core::Error::Zip(ZipError::WrongPassword) => ErrorCode::WrongPassword,
+            pdf_core::Error::Zip(_) => ErrorCode::ArchiveParse,
+        }
+    }
+}
+
+#[cfg(feature = "verify")]
+impl From<&XmlError> for ErrorCode {
+    fn from(e: &XmlError) -> Self {
+        match e {
+            XmlError::Parse { .. } => ErrorCode::XmlParse,
+            XmlError::NotSigned => ErrorCode::NotSigned,
+            XmlError::Malformed(_) | XmlError::ReferenceNotFound(_) | XmlError::Certificate(_) => {
+                ErrorCode::MalformedSignature
+            }
+            XmlError::UnsupportedAlgorithm(_) => ErrorCode::UnsupportedAlgorithm,
+            XmlError::DigestMismatch { .. } => ErrorCode::DigestMismatch,
+            XmlError::InvalidPublicKey => ErrorCode::InvalidPublicKey,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for ErrorCode {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u16(self.code())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for ErrorCode {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let code = u16::deserialize(deserializer)?;
+        ErrorCode::from_code(code).ok_or_else(|| {
+            serde::de::Error::invalid_value(
+                serde::de::Unexpected::Unsigned(code.into()),
+                &"a zkpdf error code",
+            )
+        })
+    }
+}
+
+/// A code plus the human-readable message of the error it came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ZkpdfError {
+    pub code: ErrorCode,
+    pub message: String,
+}
+
+impl ZkpdfError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+/// Formats as `error[E3003]: <message>`.
+impl fmt::Display for ZkpdfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "error[{}]: {}", self.code, self.message)
+    }
+}
+
+impl core::error::Error for ZkpdfError {}
+
+macro_rules! impl_from_error {
+    ($($ty:ty),*) => {
+        $(impl From<$ty> for ZkpdfError {
+            fn from(e: $ty) -> Self {
+                ZkpdfError::new(ErrorCode::from(&e), e.to_string())
+            }
+        })*
+    };
+}
+
+impl_from_error!(PdfError);
+#[cfg(feature = "verify")]
+impl_from_error!(SignatureValidationError, XmlError, pdf_core::Error);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codes_are_unique_and_round_trip() {
+        for (i, code) in ErrorCode::ALL.iter().enumerate() {
+            assert_eq!(ErrorCode::from_code(code.code()), Some(*code));
+            assert!(ErrorCode::ALL[i + 1..]
+                .iter()
+                .all(|c| c.code() > code.code()));
+            assert!(ErrorCode::ALL[i + 1..]
+                .iter()
+                .all(|c| c.name() != code.name()));
+        }
+        assert_eq!(ErrorCode::from_code(0), None);
+    }
+
+    #[test]
+    fn codes_are_stable() {
+        // These numbers are part of the public interface; changing one is a breaking change.
+        assert_eq!(ErrorCode::NotSigned.code(), 3000);
+        assert_eq!(ErrorCode::DigestMismatch.code(), 3003);
+        assert_eq!(ErrorCode::InvalidSignature.code(), 3005);
+        assert_eq!(ErrorCode::DigestMismatch.to_string(), "E3003");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serializes_code_as_number() {
+        let error = ZkpdfError::new(ErrorCode::DigestMismatch, "modified");
+        let json = serde_json::to_string(&error).unwrap();
+        assert_eq!(json, r#"{"code":3003,"message":"modified"}"#);
+        assert_eq!(serde_json::from_str::<ZkpdfError>(&json).unwrap(), error);
+        assert!(serde_json::from_str::<ErrorCode>("3999").is_err());
+    }
+
+    #[cfg(feature = "verify")]
+    #[test]
+    fn maps_library_errors() {
+        let unsigned = signature_validator::verify_pdf_signature(b"%PDF-1.7\n%%EOF").unwrap_err();
+        assert_eq!(ErrorCode::from(&unsigned), ErrorCode::NotSigned);
+        assert_eq!(
+            ErrorCode::from(&FailureReason::BadSignature),
+            ErrorCode::InvalidSignature
+        );
+
+        let core_err = pdf_core::Error::from(PdfError::DecompressionError);
+        let e = ZkpdfError::from(core_err);
+        assert_eq!(e.code, ErrorCode::PdfDecompression);
+        assert_eq!(e.code.exit_code(), 3);
+        assert_eq!(
+            e.to_string(),
+            "error[E2001]: text extraction error: Decompression failed"
+        );
+
+        let page = ZkpdfError::from(pdf_core::Error::PageOutOfBounds { page: 3, total: 1 });
+        assert_eq!(page.code.http_status(), 422);
+
+        let doctype = pdf_core::verify_xml_attribute(b"<!DOCTYPE a><a/>", "a@b").unwrap_err();
+        assert_eq!(ErrorCode::from(&doctype), ErrorCode::XmlParse);
+        let unsigned = pdf_core::verify_xml_attribute(b"<a b=\"1\"/>", "a@b").unwrap_err();
+        assert_eq!(ZkpdfError::from(unsigned).code.exit_code(), 4);
+
+        let template = ZkpdfError::from(pdf_core::Error::UnknownTemplate("passport".into()));
+        assert_eq!(template.code, ErrorCode::UnknownTemplate);
+        assert_eq!(template.code.http_status(), 400);
+        assert_eq!(
+            template.to_string(),
+            "error[E4003]: unknown document template `passport`"
+        );
+        assert_eq!(
+            ErrorCode::from(&pdf_core::Error::UntrustedSigner),
+            ErrorCode::PolicyViolation
+        );
+        assert_eq!(
+            ErrorCode::from(&pdf_core::Error::UnsignedUpdates { appended_bytes: 1 }),
+            ErrorCode::PolicyViolation
+        );
+
+        let share_code = ZkpdfError::from(pdf_core::Error::Zip(ZipError::WrongPassword));
+        assert_eq!(share_code.code, ErrorCode::WrongPassword);
+        assert_eq!(
+            (share_code.code.http_status(), share_code.code.exit_code()),
+            (400, 2)
+        );
+        assert_eq!(
+            ErrorCode::from(&pdf_core::Error::Zip(ZipError::UnsupportedMethod(99))),
+            ErrorCode::ArchiveParse
+        );
+    }
+}
@@ -0,0 +1,76 @@
+//! Writes `src/glyph_list.rs` from a glyph list in the format of Adobe's `glyphlist.txt`.
+//!
+//! `cargo run -p extractor --example gen_glyph_list -- extractor/data/glyphlist.txt > extractor/src/glyph_list.rs`
+//!
+//! Names that map into the Private Use Area are left out: font-specific code points are no
+//! better than the glyph name as text. A name listed twice keeps its lower mapping, which is
+//! the letter rather than the symbol (`Delta` is U+0394, not U+2206).
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let [input] = args.as_slice() else {
+        eprintln!("usage: gen_glyph_list <glyphlist.txt>");
+        std::process::exit(2);
+    };
+    let list = std::fs::read_to_string(input).expect("failed to read glyph list");
+
+    let private_use = |ch: &char| ('\u{E000}'..='\u{F8FF}').contains(ch);
+    let mut names: BTreeMap<&str, Vec<char>> = BTreeMap::new();
+    for (number, line) in list.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (name, code_points) = line
+            .split_once(';')
+            .unwrap_or_else(|| panic!("line {}: expected `name;code points`", number + 1));
+        let chars: Vec<char> = code_points
+            .split_whitespace()
+            .map(|hex| {
+                u32::from_str_radix(hex, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .unwrap_or_else(|| panic!("line {}: bad code point {hex}", number + 1))
+            })
+            .collect();
+        if chars.is_empty() || chars.iter().any(private_use) {
+            continue;
+        }
+        let kept = names.entry(name).or_insert_with(|| chars.clone());
+        if chars < *kept {
+            *kept = chars;
+        }
+    }
+
+    let mut out = String::new();
+    out.push_str(
+        "//! Glyph names from the Adobe Glyph List, as far as `data/glyphlist.txt` carries it (see\n\
+         //! the header of that file): Latin, Greek, Cyrillic, Hebrew and Arabic letters,\n\
+         //! punctuation and the symbols of the standard PDF fonts. Names outside it are still read\n\
+         //! when they spell their code points (`uni0915`, `u1F600`); see\n\
+         //! [`crate::encoding::glyph_to_unicode`].\n\
+         //!\n\
+         //! Generated from `data/glyphlist.txt` by `examples/gen_glyph_list.rs`; edit the list and\n\
+         //! regenerate rather than editing this file.\n\
+         \n\
+         /// `(name, text)`, sorted by name for binary search.\n",
+    );
+    writeln!(
+        out,
+        "pub(crate) static GLYPH_LIST: [(&str, &str); {}] = [",
+        names.len()
+    )
+    .unwrap();
+    for (name, chars) in &names {
+        let text: String = chars
+            .iter()
+            .map(|&ch| format!("\\u{{{:04X}}}", ch as u32))
+            .collect();
+        writeln!(out, "    (\"{name}\", \"{text}\"),").unwrap();
+    }
+    out.push_str("];\n");
+    print!("{out}");
+}
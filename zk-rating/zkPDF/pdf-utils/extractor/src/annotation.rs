@@ -0,0 +1,183 @@
+//! Annotation appearances: the normal appearance stream (`/AP /N`) of every visible annotation
+//! on a page, decoded and placed on the page, for text that issuers stamp into FreeText
+//! annotations or widget appearances instead of the page content.
+
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec::Vec;
+use miniz_oxide::inflate::decompress_to_vec_zlib;
+
+use crate::font::collect_fonts_from_resources;
+use crate::types::{PageContent, PdfError, PdfFont, PdfObj, PdfStream};
+use crate::{handle_stream_filters, numbers, resolve, HashMap};
+
+type Objects<'a> = HashMap<(u32, u16), PdfObj<'a>>;
+type Decompress<'a> = &'a dyn Fn(&[u8]) -> Result<Vec<u8>, PdfError>;
+
+// Annotation flags (`/F`) that keep an annotation off the screen
+const HIDDEN: u32 = 1 << 1;
+const NO_VIEW: u32 = 1 << 5;
+
+/// An appearance stream ready to be interpreted like a form XObject.
+pub(crate) struct Appearance<'a> {
+    pub content: Cow<'a, [u8]>,
+    pub resources: &'a HashMap<String, PdfObj<'a>>,
+    pub fonts: HashMap<String, PdfFont>,
+    /// Maps the form's space to the page's default user space: the form's `/Matrix`, then its
+    /// bounding box fitted to the annotation's `/Rect`.
+    pub matrix: [f32; 6],
+}
+
+/// Appearances of the page's visible annotations, in `/Annots` order. Annotations without a
+/// usable appearance are skipped, as are appearance streams that fail to decode.
+pub(crate) fn appearances<'a>(
+    page: &'a PageContent,
+    objects: &'a Objects<'a>,
+) -> Vec<Appearance<'a>> {
+    page.annotations
+        .iter()
+        .filter_map(|annotation| match resolve(Some(annotation), objects) {
+            Some(PdfObj::Dictionary(annotation)) => appearance(annotation, page, objects),
+            _ => None,
+        })
+        .collect()
+}
+
+fn appearance<'a>(
+    annotation: &'a HashMap<String, PdfObj<'a>>,
+    page: &'a PageContent,
+    objects: &'a Objects<'a>,
+) -> Option<Appearance<'a>> {
+    let flags = match resolve(annotation.get("F"), objects) {
+        Some(PdfObj::Number(n)) => *n as u32,
+        _ => 0,
+    };
+    if flags & (HIDDEN | NO_VIEW) != 0 {
+        return None;
+    }
+
+    // `/N` is a stream, or a dictionary of streams by appearance state (checkboxes, radio
+    // buttons) of which `/AS` picks one
+    let Some(PdfObj::Dictionary(ap)) = resolve(annotation.get("AP"), objects) else {
+        return None;
+    };
+    let stream = match resolve(ap.get("N"), objects)? {
+        PdfObj::Stream(stream) => stream,
+        PdfObj::Dictionary(states) => match resolve(annotation.get("AS"), objects) {
+            Some(PdfObj::Name(state)) => match resolve(states.get(state), objects) {
+                Some(PdfObj::Stream(stream)) => stream,
+                _ => return None,
+            },
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    let decompress =
+        |data: &[u8]| decompress_to_vec_zlib(data).map_err(|_| PdfError::DecompressionError);
+    let content = decoded(stream, &decompress)?;
+    let resources = match resolve(stream.dict.get("Resources"), objects) {
+        Some(PdfObj::Dictionary(resources)) => resources,
+        _ => &page.resources,
+    };
+    let fonts = collect_fonts_from_resources(resources, objects, &decompress).ok()?;
+    let matrix =
+        numbers(stream.dict.get("Matrix"), objects).unwrap_or([1.0, 0.0, 0.0, 1.0, 0.0, 0.0]);
+    let matrix = match (
+        numbers::<4>(annotation.get("Rect"), objects),
+        numbers::<4>(stream.dict.get("BBox"), objects),
+    ) {
+        (Some(rect), Some(bbox)) => fit(matrix, bbox, rect),
+        _ => matrix,
+    };
+    Some(Appearance {
+        content,
+        resources,
+        fonts,
+        matrix,
+    })
+}
+
+fn decoded<'a>(stream: &'a PdfStream, decompress: Decompress<'_>) -> Option<Cow<'a, [u8]>> {
+    let Some(filter) = stream.dict.get("Filter") else {
+        return Some(Cow::Borrowed(&stream.data));
+    };
+    let mut decoded = Vec::new();
+    handle_stream_filters(
+        filter,
+        stream.dict.get("DecodeParms"),
+        &stream.data,
+        decompress,
+        &mut decoded,
+    )
+    .ok()?;
+    decoded.pop().map(Cow::Owned)
+}
+
+// `matrix` followed by the scale and translation taking the transformed bounding box onto
+// `rect` (PDF 32000-1, 12.5.5)
+fn fit(matrix: [f32; 6], bbox: [f32; 4], rect: [f32; 4]) -> [f32; 6] {
+    let corners = [
+        (bbox[0], bbox[1]),
+        (bbox[0], bbox[3]),
+        (bbox[2], bbox[1]),
+        (bbox[2], bbox[3]),
+    ]
+    .map(|(x, y)| {
+        (
+            matrix[0] * x + matrix[2] * y + matrix[4],
+            matrix[1] * x + matrix[3] * y + matrix[5],
+        )
+    });
+    let min_x = corners.iter().map(|c| c.0).fold(f32::INFINITY, f32::min);
+    let max_x = corners
+        .iter()
+        .map(|c| c.0)
+        .fold(f32::NEG_INFINITY, f32::max);
+    let min_y = corners.iter().map(|c| c.1).fold(f32::INFINITY, f32::min);
+    let max_y = corners
+        .iter()
+        .map(|c| c.1)
+        .fold(f32::NEG_INFINITY, f32::max);
+
+    let (left, right) = (rect[0].min(rect[2]), rect[0].max(rect[2]));
+    let (bottom, top) = (rect[1].min(rect[3]), rect[1].max(rect[3]));
+    // A degenerate bounding box keeps its size
+    let scale = |from: f32, to: f32| if from > 0.0 { to / from } else { 1.0 };
+    let sx = scale(max_x - min_x, right - left);
+    let sy = scale(max_y - min_y, top - bottom);
+    let tx = left - min_x * sx;
+    let ty = bottom - min_y * sy;
+    [
+        matrix[0] * sx,
+        matrix[1] * sy,
+        matrix[2] * sx,
+        matrix[3] * sy,
+        matrix[4] * sx + tx,
+        matrix[5] * sy + ty,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bounding_box_is_fitted_to_rect() {
+        // A 100x20 form drawn into a 50x10 rectangle at (200, 300)
+        let matrix = fit(
+            [1.0, 0.0, 0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 100.0, 20.0],
+            [200.0, 300.0, 250.0, 310.0],
+        );
+        assert_eq!(matrix, [0.5, 0.0, 0.0, 0.5, 200.0, 300.0]);
+
+        // The form's own matrix applies first: shifted by (10, 10) before fitting
+        let matrix = fit(
+            [1.0, 0.0, 0.0, 1.0, 10.0, 10.0],
+            [0.0, 0.0, 100.0, 20.0],
+            [200.0, 300.0, 300.0, 320.0],
+        );
+        assert_eq!(matrix, [1.0, 0.0, 0.0, 1.0, 200.0, 300.0]);
+    }
+}
@@ -1,11 +1,16 @@
-use std::collections::{BTreeMap, HashMap};
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::HashMap;
 
 use crate::{
     encoding::{
-        glyph_to_unicode, mac_expert_to_unicode, mac_roman_to_unicode, pdf_doc_to_unicode,
+        glyph_name_to_text, mac_expert_to_unicode, mac_roman_to_unicode, pdf_doc_to_unicode,
         standard_to_unicode, winansi_to_unicode,
     },
-    types::PdfFont,
+    types::{CidEncoding, CidFont, PdfFont},
 };
 
 /// Split a line that may contain hex values with or without spaces
@@ -63,11 +68,6 @@ fn split_hex_values(line: &str) -> Vec<String> {
     result
 }
 
-// Use HashMap from std if available, otherwise use BTreeMap as a fallback for no_std
-// #[cfg(feature = "std")]
-// #[cfg(not(feature = "std"))]
-// type HashMap<K, V> = BTreeMap<K, V>;
-
 // parse ToUnicode CMap content to a mapping from character codes to Unicode strings
 pub fn parse_cmap(cmap_data: &[u8]) -> HashMap<u32, String> {
     let mut map = BTreeMap::new();
@@ -173,47 +173,33 @@ fn parse_cmap_hex_to_string(hex: &str) -> Option<String> {
         return None;
     }
 
-    let chunks: Vec<&[u8]> = hex.as_bytes().chunks(4).collect();
-    let mut out = String::new();
-    let mut i = 0;
-
-    while i < chunks.len() {
-        let chunk = chunks[i];
-        if chunk.len() < 4 {
-            break;
-        }
-        let part = core::str::from_utf8(chunk).ok()?;
-        let code = u16::from_str_radix(part, 16).ok()?;
+    let units = hex
+        .as_bytes()
+        .chunks(4)
+        .map(|chunk| {
+            let part = core::str::from_utf8(chunk).ok()?;
+            u16::from_str_radix(part, 16).ok()
+        })
+        .collect::<Option<Vec<u16>>>()?;
+    Some(utf16_to_string(units))
+}
 
-        if (0xD800..=0xDBFF).contains(&code) {
-            if i + 1 < chunks.len() {
-                let next_part = core::str::from_utf8(chunks[i + 1]).ok()?;
-                if let Ok(low) = u16::from_str_radix(next_part, 16) {
-                    if (0xDC00..=0xDFFF).contains(&low) {
-                        let combined =
-                            0x10000 + (((code - 0xD800) as u32) << 10) + ((low - 0xDC00) as u32);
-                        if let Some(ch) = char::from_u32(combined) {
-                            out.push(ch);
-                            i += 2;
-                            continue;
-                        }
-                    }
-                }
-            }
-            out.push('�');
-            i += 1;
-            continue;
-        } else if (0xDC00..=0xDFFF).contains(&code) {
-            out.push('�');
-        } else if let Some(ch) = char::from_u32(code as u32) {
-            out.push(ch);
-        } else {
-            out.push('�');
-        }
-        i += 1;
-    }
+/// Text of a string that starts with the UTF-16BE byte order mark `FE FF`, `None` for any
+/// other string. A trailing odd byte is dropped.
+pub(crate) fn utf16_be_text(bytes: &[u8]) -> Option<String> {
+    let units = bytes.strip_prefix(&[0xFE, 0xFF])?;
+    Some(utf16_to_string(
+        units
+            .chunks_exact(2)
+            .map(|pair| u16::from_be_bytes([pair[0], pair[1]])),
+    ))
+}
 
-    Some(out)
+/// Joins surrogate pairs; unpaired surrogates become U+FFFD.
+fn utf16_to_string(units: impl IntoIterator<Item = u16>) -> String {
+    char::decode_utf16(units)
+        .map(|ch| ch.unwrap_or('�'))
+        .collect()
 }
 
 pub fn cmap_decode_bytes(bytes: &[u8], cmap: &HashMap<u32, String>, is_cid: bool) -> String {
@@ -252,29 +238,62 @@ pub fn decode_bytes(bytes: &[u8], font: &PdfFont) -> String {
         let is_cid = font.subtype.as_deref() == Some("Type0");
         return cmap_decode_bytes(bytes, cmap, is_cid);
     }
+    if let Some(cid) = &font.cid {
+        return cid_decode_bytes(bytes, cid);
+    }
     base_encode_bytes(bytes, font)
 }
 
+// A Type0 font without ToUnicode: Unicode CMaps carry the text in their codes; otherwise each
+// CID goes through /CIDToGIDMap to a glyph of the embedded font program.
+fn cid_decode_bytes(bytes: &[u8], cid: &CidFont) -> String {
+    let units = bytes
+        .chunks(2)
+        .map(|pair| ((pair[0] as u16) << 8) | pair.get(1).copied().unwrap_or(0) as u16);
+    if let CidEncoding::Utf16 { .. } = cid.encoding {
+        return utf16_to_string(units);
+    }
+    units
+        .map(|code| {
+            let glyph = match &cid.cid_to_gid {
+                Some(map) => map.get(code as usize).copied().unwrap_or(0),
+                None => code,
+            };
+            cid.glyph_unicode.get(&glyph).copied().unwrap_or('�')
+        })
+        .collect()
+}
+
 fn base_encode_bytes(bytes: &[u8], font: &PdfFont) -> String {
+    // Strings with a byte order mark are UTF-16BE text, whatever the encoding
+    if let Some(text) = utf16_be_text(bytes) {
+        return text;
+    }
     let mut result = String::new();
     for &b in bytes {
         let code = b as u32;
-        let mut decoded = false;
 
-        if let Some(diffs) = &font.differences {
-            if let Some(glyph_name) = diffs.get(&code) {
-                match glyph_to_unicode(glyph_name) {
-                    Some(ch) => {
-                        result.push(ch);
-                        decoded = true;
-                    }
-                    _ => (),
-                }
-            }
+        if let Some(text) = font
+            .differences
+            .as_ref()
+            .and_then(|diffs| diffs.get(&code))
+            .and_then(|name| glyph_name_to_text(name))
+        {
+            result.push_str(&text);
+            continue;
         }
 
-        if decoded {
-            continue;
+        // Without a named encoding the font program's own encoding applies
+        if font.encoding.is_none() {
+            if let Some(text) = font
+                .builtin_encoding
+                .as_ref()
+                .and_then(|names| names.get(&code))
+                .and_then(|name| glyph_name_to_text(name))
+            {
+                result.push_str(&text);
+                continue;
+            }
         }
 
         let ch = match font.encoding.as_deref() {
@@ -302,3 +321,36 @@ fn base_encode_bytes(bytes: &[u8], font: &PdfFont) -> String {
     }
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn surrogate_pairs_are_joined() {
+        assert_eq!(parse_cmap_hex_to_string("D83DDE000041").unwrap(), "😀A");
+        assert_eq!(parse_cmap_hex_to_string("D83D0041").unwrap(), "�A");
+        assert_eq!(parse_cmap_hex_to_string("00G1"), None);
+    }
+
+    #[test]
+    fn byte_order_mark_selects_utf16() {
+        let font = PdfFont {
+            base_name: None,
+            subtype: Some("Type1".to_string()),
+            encoding: Some("WinAnsiEncoding".to_string()),
+            to_unicode_map: None,
+            differences: None,
+            cid: None,
+            builtin_encoding: None,
+            widths: None,
+            type3: None,
+        };
+        assert_eq!(
+            decode_bytes(b"\xFE\xFF\x00T\x00\xE9\xD8\x3D\xDE\x00", &font),
+            "Té😀"
+        );
+        assert_eq!(decode_bytes(b"\xFE\xFF\xDE\x00", &font), "�");
+        assert_eq!(decode_bytes(b"\xE9t\xE9", &font), "été");
+    }
+}
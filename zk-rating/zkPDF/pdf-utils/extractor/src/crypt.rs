@@ -0,0 +1,430 @@
+//! Standard security handler (PDF 32000-2, 7.6.4). Documents encrypted with an empty user
+//! password, as many issuers do only to set permissions, are decrypted as they are loaded:
+//! every string and stream of every indirect object is replaced by its plaintext before object
+//! streams are expanded. Revisions 2 to 6 are supported, with RC4, AES-128 and AES-256.
+
+use aes::cipher::{block_padding::NoPadding, BlockDecryptMut, BlockEncryptMut, KeyIvInit};
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec::Vec;
+use md5::{Digest, Md5};
+use sha2::{Sha256, Sha384, Sha512};
+
+use crate::types::{PdfError, PdfObj};
+use crate::{resolve, HashMap};
+
+type Objects<'a> = HashMap<(u32, u16), PdfObj<'a>>;
+
+/// Padding string that stands in for the (empty) password in revisions 2 to 4.
+const PAD: [u8; 32] = [
+    0x28, 0xBF, 0x4E, 0x5E, 0x4E, 0x75, 0x8A, 0x41, 0x64, 0x00, 0x4E, 0x56, 0xFF, 0xFA, 0x01, 0x08,
+    0x2E, 0x2E, 0x00, 0xB6, 0xD0, 0x68, 0x3E, 0x80, 0x2F, 0x0C, 0xA9, 0xFE, 0x64, 0x53, 0x69, 0x7A,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Method {
+    /// The `Identity` crypt filter: data is stored in the clear.
+    Identity,
+    Rc4,
+    /// AES-128 in CBC mode (`AESV2`), with a per-object key.
+    Aes128,
+    /// AES-256 in CBC mode (`AESV3`), with the file key itself.
+    Aes256,
+}
+
+/// Decrypts the objects of a document whose user password is empty.
+pub(crate) struct Decryptor {
+    key: Vec<u8>,
+    strings: Method,
+    streams: Method,
+    encrypt_metadata: bool,
+    /// The `/Encrypt` dictionary, which is never encrypted.
+    dictionary: Option<(u32, u16)>,
+}
+
+impl Decryptor {
+    /// The decryptor for a document with this trailer, `None` if it is not encrypted. Fails
+    /// for other security handlers and for documents that need a password to open.
+    pub(crate) fn new(
+        trailer: &HashMap<String, PdfObj>,
+        objects: &Objects,
+    ) -> Result<Option<Self>, PdfError> {
+        let Some(entry) = trailer.get("Encrypt") else {
+            return Ok(None);
+        };
+        let Some(PdfObj::Dictionary(encrypt)) = resolve(Some(entry), objects) else {
+            return Err(PdfError::EncryptionError("/Encrypt is not a dictionary"));
+        };
+        if !matches!(encrypt.get("Filter"), Some(PdfObj::Name(name)) if name == "Standard") {
+            return Err(PdfError::EncryptionError("unsupported security handler"));
+        }
+
+        let number = |key: &str| match resolve(encrypt.get(key), objects) {
+            Some(PdfObj::Number(n)) => Some(*n),
+            _ => None,
+        };
+        let bytes = |key: &str| match resolve(encrypt.get(key), objects) {
+            Some(PdfObj::String(bytes)) => Ok(bytes.as_slice()),
+            _ => Err(PdfError::EncryptionError(
+                "/Encrypt lacks /O, /U, /OE or /UE",
+            )),
+        };
+        let version = number("V").unwrap_or(0.0) as u32;
+        let revision = number("R").unwrap_or(0.0) as u32;
+        let encrypt_metadata = !matches!(
+            resolve(encrypt.get("EncryptMetadata"), objects),
+            Some(PdfObj::Boolean(false))
+        );
+
+        let (strings, streams) = match version {
+            1 | 2 => (Method::Rc4, Method::Rc4),
+            4 | 5 => {
+                let filter = |key: &str| crypt_filter(encrypt, key, objects);
+                (filter("StrF")?, filter("StmF")?)
+            }
+            _ => return Err(PdfError::EncryptionError("unsupported encryption version")),
+        };
+
+        let key = match revision {
+            2..=4 => {
+                let length = match version {
+                    1 => 5,
+                    _ => (number("Length").unwrap_or(128.0) as usize / 8).clamp(5, 16),
+                };
+                let id = match resolve(trailer.get("ID"), objects) {
+                    Some(PdfObj::Array(ids)) => match resolve(ids.first(), objects) {
+                        Some(PdfObj::String(id)) => id.as_slice(),
+                        _ => &[],
+                    },
+                    _ => &[],
+                };
+                let permissions = number("P").unwrap_or(0.0) as i64 as u32;
+                let key = legacy_key(
+                    revision,
+                    length,
+                    bytes("O")?,
+                    permissions,
+                    id,
+                    encrypt_metadata,
+                );
+                if !legacy_user_password_matches(revision, &key, bytes("U")?, id) {
+                    return Err(PdfError::EncryptionError("document needs a password"));
+                }
+                key
+            }
+            5 | 6 => {
+                let user = bytes("U")?;
+                if user.len() < 48 {
+                    return Err(PdfError::EncryptionError("/U is too short"));
+                }
+                if hash(revision, &user[32..40]) != user[..32] {
+                    return Err(PdfError::EncryptionError("document needs a password"));
+                }
+                let mut key = bytes("UE")?
+                    .get(..32)
+                    .map(<[u8]>::to_vec)
+                    .ok_or(PdfError::EncryptionError("/UE is too short"))?;
+                cbc::Decryptor::<aes::Aes256>::new(
+                    hash(revision, &user[40..48]).as_slice().into(),
+                    &[0; 16].into(),
+                )
+                .decrypt_padded_mut::<NoPadding>(&mut key)
+                .map_err(|_| PdfError::EncryptionError("/UE is not whole blocks"))?;
+                key
+            }
+            _ => return Err(PdfError::EncryptionError("unsupported encryption revision")),
+        };
+
+        Ok(Some(Decryptor {
+            key,
+            strings,
+            streams,
+            encrypt_metadata,
+            dictionary: match entry {
+                PdfObj::Reference(id) => Some(*id),
+                _ => None,
+            },
+        }))
+    }
+
+    /// Replaces the strings and stream data of every object with their plaintext.
+    pub(crate) fn decrypt_objects(&self, objects: &mut Objects) {
+        for (&id, object) in objects.iter_mut() {
+            self.decrypt_indirect(id, object);
+        }
+    }
+
+    /// Replaces the strings and stream data of indirect object `id` with their plaintext.
+    pub(crate) fn decrypt_indirect(&self, id: (u32, u16), object: &mut PdfObj) {
+        if Some(id) != self.dictionary {
+            self.decrypt_object(id, object);
+        }
+    }
+
+    fn decrypt_object(&self, id: (u32, u16), object: &mut PdfObj) {
+        match object {
+            PdfObj::String(bytes) => *bytes = self.decrypt(id, self.strings, bytes),
+            PdfObj::Array(items) => {
+                for item in items {
+                    self.decrypt_object(id, item);
+                }
+            }
+            PdfObj::Dictionary(dict) => self.decrypt_dictionary(id, dict),
+            PdfObj::Stream(stream) => {
+                self.decrypt_dictionary(id, &mut stream.dict);
+                let kind = match stream.dict.get("Type") {
+                    Some(PdfObj::Name(kind)) => kind.as_str(),
+                    _ => "",
+                };
+                // Cross-reference streams are stored in the clear, metadata optionally, and a
+                // `/Crypt` filter names the stream's own crypt filter (in practice `Identity`)
+                let clear = kind == "XRef"
+                    || (kind == "Metadata" && !self.encrypt_metadata)
+                    || has_crypt_filter(stream.dict.get("Filter"));
+                if !clear {
+                    stream.data = Cow::Owned(self.decrypt(id, self.streams, &stream.data));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn decrypt_dictionary(&self, id: (u32, u16), dict: &mut HashMap<String, PdfObj>) {
+        // The `/Contents` of a signature dictionary is stored in the clear so the signed byte
+        // ranges can be located without decrypting
+        let signature = matches!(dict.get("Type"), Some(PdfObj::Name(kind)) if kind == "Sig")
+            || dict.contains_key("ByteRange");
+        for (key, value) in dict.iter_mut() {
+            if signature && key == "Contents" {
+                continue;
+            }
+            self.decrypt_object(id, value);
+        }
+    }
+
+    fn decrypt(&self, (number, generation): (u32, u16), method: Method, data: &[u8]) -> Vec<u8> {
+        match method {
+            Method::Identity => data.to_vec(),
+            Method::Aes256 => aes_cbc_decrypt::<aes::Aes256>(&self.key, data),
+            Method::Rc4 | Method::Aes128 => {
+                // Algorithm 1: the file key extended with the object number and generation
+                let mut md5 = Md5::new();
+                md5.update(&self.key);
+                md5.update(&number.to_le_bytes()[..3]);
+                md5.update(generation.to_le_bytes());
+                if method == Method::Aes128 {
+                    md5.update(b"sAlT");
+                }
+                let digest = md5.finalize();
+                let key = &digest[..(self.key.len() + 5).min(16)];
+                if method == Method::Rc4 {
+                    rc4(key, data)
+                } else {
+                    aes_cbc_decrypt::<aes::Aes128>(key, data)
+                }
+            }
+        }
+    }
+}
+
+// The method of the crypt filter `/StrF` or `/StmF` names in `/CF`
+fn crypt_filter(
+    encrypt: &HashMap<String, PdfObj>,
+    key: &str,
+    objects: &Objects,
+) -> Result<Method, PdfError> {
+    let name = match resolve(encrypt.get(key), objects) {
+        Some(PdfObj::Name(name)) => name.as_str(),
+        _ => "Identity",
+    };
+    if name == "Identity" {
+        return Ok(Method::Identity);
+    }
+    let filter = match resolve(encrypt.get("CF"), objects) {
+        Some(PdfObj::Dictionary(filters)) => resolve(filters.get(name), objects),
+        _ => None,
+    };
+    let Some(PdfObj::Dictionary(filter)) = filter else {
+        return Err(PdfError::EncryptionError("crypt filter not found"));
+    };
+    match resolve(filter.get("CFM"), objects) {
+        Some(PdfObj::Name(method)) => match method.as_str() {
+            "V2" => Ok(Method::Rc4),
+            "AESV2" => Ok(Method::Aes128),
+            "AESV3" => Ok(Method::Aes256),
+            "None" => Ok(Method::Identity),
+            _ => Err(PdfError::EncryptionError("unsupported crypt filter method")),
+        },
+        _ => Ok(Method::Identity),
+    }
+}
+
+fn has_crypt_filter(filter: Option<&PdfObj>) -> bool {
+    match filter {
+        Some(PdfObj::Name(name)) => name == "Crypt",
+        Some(PdfObj::Array(filters)) => filters
+            .iter()
+            .any(|filter| matches!(filter, PdfObj::Name(name) if name == "Crypt")),
+        _ => false,
+    }
+}
+
+// Algorithm 2: the file key of revisions 2 to 4, from the empty password
+fn legacy_key(
+    revision: u32,
+    length: usize,
+    owner: &[u8],
+    permissions: u32,
+    id: &[u8],
+    encrypt_metadata: bool,
+) -> Vec<u8> {
+    let mut md5 = Md5::new();
+    md5.update(PAD);
+    md5.update(&owner[..owner.len().min(32)]);
+    md5.update(permissions.to_le_bytes());
+    md5.update(id);
+    if revision >= 4 && !encrypt_metadata {
+        md5.update([0xFF; 4]);
+    }
+    let mut digest = md5.finalize();
+    if revision >= 3 {
+        for _ in 0..50 {
+            digest = Md5::digest(&digest[..length]);
+        }
+    }
+    let length = if revision == 2 { 5 } else { length };
+    digest[..length].to_vec()
+}
+
+// Algorithms 4 and 5: `/U` is the padding (revision 2) or its hash with the document id
+// (revisions 3 and 4) encrypted with the key, so a match means the password is empty
+fn legacy_user_password_matches(revision: u32, key: &[u8], user: &[u8], id: &[u8]) -> bool {
+    if revision == 2 {
+        return user.get(..32) == Some(&rc4(key, &PAD)[..]);
+    }
+    let mut md5 = Md5::new();
+    md5.update(PAD);
+    md5.update(id);
+    let mut check = rc4(key, &md5.finalize());
+    for round in 1..=19u8 {
+        let round_key: Vec<u8> = key.iter().map(|b| b ^ round).collect();
+        check = rc4(&round_key, &check);
+    }
+    user.get(..16) == Some(&check[..])
+}
+
+// Algorithms 2.A and 2.B: the hash of the empty password with a salt from `/U`
+fn hash(revision: u32, salt: &[u8]) -> Vec<u8> {
+    let mut k = Sha256::digest(salt).to_vec();
+    if revision == 5 {
+        return k;
+    }
+    let mut round = 0u32;
+    let mut last = 0u8;
+    while round < 64 || u32::from(last) > round - 32 {
+        // The password and user key are empty, so each of the 64 repetitions is just `k`
+        let mut e = k.repeat(64);
+        cbc::Encryptor::<aes::Aes128>::new(k[..16].into(), k[16..32].into())
+            .encrypt_padded_mut::<NoPadding>(&mut e, k.len() * 64)
+            .expect("64 repetitions are whole blocks");
+        let remainder = e[..16].iter().map(|&b| u32::from(b)).sum::<u32>() % 3;
+        k = match remainder {
+            0 => Sha256::digest(&e).to_vec(),
+            1 => Sha384::digest(&e).to_vec(),
+            _ => Sha512::digest(&e).to_vec(),
+        };
+        last = e[e.len() - 1];
+        round += 1;
+    }
+    k.truncate(32);
+    k
+}
+
+fn rc4(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut state: [u8; 256] = core::array::from_fn(|i| i as u8);
+    let mut j = 0u8;
+    for i in 0..256 {
+        j = j.wrapping_add(state[i]).wrapping_add(key[i % key.len()]);
+        state.swap(i, usize::from(j));
+    }
+    let (mut i, mut j) = (0u8, 0u8);
+    data.iter()
+        .map(|&byte| {
+            i = i.wrapping_add(1);
+            j = j.wrapping_add(state[usize::from(i)]);
+            state.swap(usize::from(i), usize::from(j));
+            byte ^ state[usize::from(state[usize::from(i)].wrapping_add(state[usize::from(j)]))]
+        })
+        .collect()
+}
+
+// The first block is the initialization vector. A trailing partial block is dropped, and
+// padding that is not valid PKCS#5 is left in place rather than failing the object.
+fn aes_cbc_decrypt<C>(key: &[u8], data: &[u8]) -> Vec<u8>
+where
+    C: aes::cipher::BlockDecrypt + aes::cipher::BlockCipher + aes::cipher::KeyInit,
+    cbc::Decryptor<C>: KeyIvInit + BlockDecryptMut,
+{
+    if data.len() < 32 {
+        return Vec::new();
+    }
+    let (iv, body) = data.split_at(16);
+    let mut plain = body[..body.len() / 16 * 16].to_vec();
+    let Ok(decryptor) = cbc::Decryptor::<C>::new_from_slices(key, iv) else {
+        return Vec::new();
+    };
+    if decryptor
+        .decrypt_padded_mut::<NoPadding>(&mut plain)
+        .is_err()
+    {
+        return Vec::new();
+    }
+    let pad = usize::from(plain.last().copied().unwrap_or(0));
+    if (1..=16).contains(&pad)
+        && plain[plain.len() - pad..]
+            .iter()
+            .all(|&b| usize::from(b) == pad)
+    {
+        plain.truncate(plain.len() - pad);
+    }
+    plain
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rc4_matches_reference_vectors() {
+        assert_eq!(
+            rc4(b"Key", b"Plaintext"),
+            [0xBB, 0xF3, 0x16, 0xE8, 0xD9, 0x40, 0xAF, 0x0A, 0xD3]
+        );
+        assert_eq!(rc4(b"Wiki", b"pedia"), [0x10, 0x21, 0xBF, 0x04, 0x20]);
+    }
+
+    #[test]
+    fn documents_with_a_user_password_are_rejected() {
+        let encrypt: HashMap<String, PdfObj> = [
+            ("Filter", PdfObj::Name("Standard".into())),
+            ("V", PdfObj::Number(2.0)),
+            ("R", PdfObj::Number(3.0)),
+            ("P", PdfObj::Number(-4.0)),
+            ("O", PdfObj::String([0x11; 32].to_vec())),
+            ("U", PdfObj::String([0x22; 32].to_vec())),
+        ]
+        .into_iter()
+        .map(|(key, value)| (key.into(), value))
+        .collect();
+        let trailer: HashMap<String, PdfObj> =
+            [("Encrypt".into(), PdfObj::Dictionary(encrypt))].into();
+        assert!(matches!(
+            Decryptor::new(&trailer, &HashMap::new()),
+            Err(PdfError::EncryptionError("document needs a password"))
+        ));
+        assert!(matches!(
+            Decryptor::new(&HashMap::new(), &HashMap::new()),
+            Ok(None)
+        ));
+    }
+}
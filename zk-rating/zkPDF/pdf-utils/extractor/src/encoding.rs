@@ -1,58 +1,71 @@
-/// Map a glyph name to a Unicode character (for Differences).
+use alloc::string::String;
+
+use crate::glyph_list::GLYPH_LIST;
+
+/// Map a glyph name to a Unicode character (for Differences). Names that stand for several
+/// characters, like ligatures, give `None`; see [`glyph_name_to_text`].
 pub fn glyph_to_unicode(name: &str) -> Option<char> {
-    // Common glyphs:
-    let mapping = [
-        ("space", ' '),
-        ("parenleft", '('),
-        ("parenright", ')'),
-        ("minus", '-'),
-        ("period", '.'),
-        ("comma", ','),
-        ("colon", ':'),
-        ("semicolon", ';'),
-        ("question", '?'),
-        ("exclam", '!'),
-        ("trademark", '™'),
-        ("Trademark", '™'),
-        ("bullet", '•'),
-        ("Euro", '€'),
-        ("Euroglyph", '€'),
-        ("yen", '¥'),
-        ("florin", 'ƒ'),
-        ("emdash", '—'),
-        ("endash", '–'),
-        ("quotedblleft", '“'),
-        ("quotedblright", '”'),
-        ("quoteleft", '‘'),
-        ("quoteright", '’'),
-        ("AE", 'Æ'),
-        ("ae", 'æ'),
-        ("OE", 'Œ'),
-        ("oe", 'œ'),
-        ("fi", 'ﬁ'),
-        ("fl", 'ﬂ'),
-        ("ffi", 'ﬃ'),
-        ("ffl", 'ﬄ'),
-        ("ff", 'ﬀ'),
-        ("dotlessi", 'ı'),
-        ("dotlessj", 'ȷ'),
-        ("germandbls", 'ß'),
-        ("registered", '®'),
-        ("copyright", '©'),
-        ("trademark", '™'),
-        // ... more can be added as needed
-    ];
-    for (glyph, ch) in mapping.iter() {
-        if glyph.eq_ignore_ascii_case(name) {
-            return Some(*ch);
+    let text = glyph_name_to_text(name)?;
+    let mut chars = text.chars();
+    match (chars.next(), chars.next()) {
+        (Some(ch), None) => Some(ch),
+        _ => None,
+    }
+}
+
+/// The text a glyph name stands for, following the Adobe Glyph List specification: a
+/// `.suffix` is dropped, `_` joins the components of a ligature, and each component is a
+/// glyph list name, `uniXXXX` (one or more code points) or `uXXXX` to `uXXXXXX`.
+pub fn glyph_name_to_text(name: &str) -> Option<String> {
+    let base = name.split('.').next().unwrap_or_default();
+    let mut text = String::new();
+    for component in base.split('_') {
+        push_component(component, &mut text)?;
+    }
+    (!text.is_empty()).then_some(text)
+}
+
+fn push_component(component: &str, text: &mut String) -> Option<()> {
+    if let Ok(at) = GLYPH_LIST.binary_search_by(|(name, _)| name.cmp(&component)) {
+        text.push_str(GLYPH_LIST[at].1);
+        return Some(());
+    }
+    let code_point = |hex: &str| {
+        // Lowercase digits are outside the specification but common in subset fonts
+        if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+        char::from_u32(u32::from_str_radix(hex, 16).ok()?)
+    };
+    if let Some(hex) = component.strip_prefix("uni") {
+        if !hex.is_empty() && hex.len() % 4 == 0 {
+            let chars: Option<String> = (0..hex.len())
+                .step_by(4)
+                .map(|at| code_point(&hex[at..at + 4]))
+                .collect();
+            if let Some(chars) = chars {
+                text.push_str(&chars);
+                return Some(());
+            }
         }
     }
-    // If not found, attempt basic single-letter names (like A, B, a, b, etc.)
-    if name.len() == 1 {
-        let c = name.chars().next().unwrap();
-        return Some(c);
+    if let Some(hex) = component.strip_prefix('u') {
+        if (4..=6).contains(&hex.len()) {
+            if let Some(ch) = code_point(hex) {
+                text.push(ch);
+                return Some(());
+            }
+        }
+    }
+    // Nonstandard single-character names (`/A`, `/1`) stand for themselves
+    let mut chars = component.chars();
+    match (chars.next(), chars.next()) {
+        (Some(ch), None) => {
+            text.push(ch);
+            Some(())
+        }
+        _ => None,
     }
-    None
 }
 
 /// WinAnsi (CP1252) encoding mapping
@@ -460,3 +473,30 @@ pub fn pdf_doc_to_unicode(byte: u8) -> char {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glyph_names() {
+        assert_eq!(glyph_to_unicode("Adieresis"), Some('Ä'));
+        assert_eq!(glyph_to_unicode("afii10017"), Some('А'));
+        assert_eq!(glyph_to_unicode("afii57414"), Some('\u{0626}'));
+        assert_eq!(glyph_to_unicode("Ohorn"), Some('Ơ'));
+        assert_eq!(glyph_to_unicode("SF100000"), Some('─'));
+        assert_eq!(glyph_to_unicode("gravecomb"), Some('\u{0300}'));
+        assert_eq!(glyph_to_unicode("uni0915"), Some('क'));
+        assert_eq!(glyph_to_unicode("u1F600"), Some('😀'));
+        assert_eq!(glyph_to_unicode("a.sc"), Some('a'));
+        assert_eq!(glyph_to_unicode("seven.oldstyle"), Some('7'));
+        // A surrogate, an unknown name, and a name that is only a suffix
+        assert_eq!(glyph_to_unicode("uniD800"), None);
+        assert_eq!(glyph_to_unicode("g123"), None);
+        assert_eq!(glyph_to_unicode(".notdef"), None);
+
+        assert_eq!(glyph_name_to_text("f_f_i").as_deref(), Some("ffi"));
+        assert_eq!(glyph_name_to_text("uni00660069").as_deref(), Some("fi"));
+        assert_eq!(glyph_to_unicode("f_i"), None);
+    }
+}
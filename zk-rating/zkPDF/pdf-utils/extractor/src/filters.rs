@@ -0,0 +1,277 @@
+//! The ASCII stream filters and the Flate predictors. Flate itself is left to the
+//! caller-supplied decompressor.
+
+use alloc::vec::Vec;
+
+use crate::types::PdfError;
+
+/// `ASCIIHexDecode`: pairs of hex digits up to the `>` end marker, whitespace ignored. An odd
+/// final digit is read as if followed by `0`.
+pub(crate) fn ascii_hex_decode(data: &[u8]) -> Result<Vec<u8>, PdfError> {
+    let mut out = Vec::with_capacity(data.len() / 2);
+    let mut high: Option<u8> = None;
+    for &b in data {
+        let digit = match b {
+            b'0'..=b'9' => b - b'0',
+            b'a'..=b'f' => b - b'a' + 10,
+            b'A'..=b'F' => b - b'A' + 10,
+            b'>' => break,
+            _ if b.is_ascii_whitespace() || b == 0 => continue,
+            _ => return Err(PdfError::DecompressionError),
+        };
+        match high.take() {
+            Some(h) => out.push(h << 4 | digit),
+            None => high = Some(digit),
+        }
+    }
+    if let Some(h) = high {
+        out.push(h << 4);
+    }
+    Ok(out)
+}
+
+/// `ASCII85Decode`: groups of five base-85 digits (`!` to `u`) for four bytes, `z` for four
+/// zero bytes, up to the `~>` end marker. A final partial group of n digits gives n - 1 bytes.
+pub(crate) fn ascii85_decode(data: &[u8]) -> Result<Vec<u8>, PdfError> {
+    let data = data.strip_prefix(b"<~").unwrap_or(data);
+    let mut out = Vec::with_capacity(data.len() / 5 * 4);
+    let mut group = [0u8; 5];
+    let mut len = 0;
+    for &b in data {
+        match b {
+            b'!'..=b'u' => {
+                group[len] = b - b'!';
+                len += 1;
+                if len == 5 {
+                    out.extend_from_slice(&ascii85_group(&group)?);
+                    len = 0;
+                }
+            }
+            b'z' if len == 0 => out.extend_from_slice(&[0; 4]),
+            b'~' => break,
+            _ if b.is_ascii_whitespace() || b == 0 => {}
+            _ => return Err(PdfError::DecompressionError),
+        }
+    }
+    match len {
+        0 => {}
+        1 => return Err(PdfError::DecompressionError),
+        _ => {
+            // Pad with the highest digit so the truncated bytes round up correctly
+            group[len..].fill(84);
+            out.extend_from_slice(&ascii85_group(&group)?[..len - 1]);
+        }
+    }
+    Ok(out)
+}
+
+fn ascii85_group(group: &[u8; 5]) -> Result<[u8; 4], PdfError> {
+    let value = group
+        .iter()
+        .try_fold(0u32, |acc, &d| acc.checked_mul(85)?.checked_add(d as u32))
+        .ok_or(PdfError::DecompressionError)?;
+    Ok(value.to_be_bytes())
+}
+
+/// Reverses the `/Predictor` of Flate `/DecodeParms`, reading its entries through `param`.
+/// Returns `None` for predictors that are not supported and for parameters that do not
+/// describe valid rows.
+pub(crate) fn undo_predictor(
+    data: Vec<u8>,
+    param: impl Fn(&str) -> Option<f64>,
+) -> Option<Vec<u8>> {
+    let predictor = param("Predictor").unwrap_or(1.0) as u32;
+    if predictor == 1 || data.is_empty() {
+        return Some(data);
+    }
+    let colors = param("Colors").unwrap_or(1.0) as usize;
+    let bits = param("BitsPerComponent").unwrap_or(8.0) as usize;
+    let columns = param("Columns").unwrap_or(1.0) as usize;
+    if !(1..=32).contains(&colors) || !matches!(bits, 1 | 2 | 4 | 8 | 16) || columns == 0 {
+        return None;
+    }
+    let row_len = columns.checked_mul(colors * bits)?.div_ceil(8);
+    if row_len > data.len() {
+        return None;
+    }
+    let bpp = (colors * bits).div_ceil(8);
+    match predictor {
+        2 if bits == 8 => {
+            let mut data = data;
+            for row in data.chunks_mut(row_len) {
+                for i in bpp..row.len() {
+                    row[i] = row[i].wrapping_add(row[i - bpp]);
+                }
+            }
+            Some(data)
+        }
+        10..=15 => Some(undo_png_predictor(&data, row_len, bpp)),
+        _ => None,
+    }
+}
+
+/// PNG filtering: every row starts with a filter type byte (None, Sub, Up, Average, Paeth).
+pub(crate) fn undo_png_predictor(data: &[u8], row_len: usize, bpp: usize) -> Vec<u8> {
+    let mut out: Vec<u8> = Vec::with_capacity(data.len());
+    let mut prev = alloc::vec![0u8; row_len];
+    for chunk in data.chunks(row_len + 1) {
+        let (&filter, encoded) = match chunk.split_first() {
+            Some(split) => split,
+            None => break,
+        };
+        let mut row = alloc::vec![0u8; row_len];
+        for i in 0..encoded.len() {
+            let left = if i >= bpp { row[i - bpp] } else { 0 };
+            let up = prev[i];
+            let up_left = if i >= bpp { prev[i - bpp] } else { 0 };
+            let predicted = match filter {
+                1 => left,
+                2 => up,
+                3 => ((left as u16 + up as u16) / 2) as u8,
+                4 => paeth(left, up, up_left),
+                _ => 0,
+            };
+            row[i] = encoded[i].wrapping_add(predicted);
+        }
+        out.extend_from_slice(&row[..encoded.len()]);
+        prev = row;
+    }
+    out
+}
+
+fn paeth(a: u8, b: u8, c: u8) -> u8 {
+    let p = a as i16 + b as i16 - c as i16;
+    let (pa, pb, pc) = (
+        (p - a as i16).abs(),
+        (p - b as i16).abs(),
+        (p - c as i16).abs(),
+    );
+    if pa <= pb && pa <= pc {
+        a
+    } else if pb <= pc {
+        b
+    } else {
+        c
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn png_predictors_round_trip() {
+        // Two RGB pixels per row; each row uses a different filter type.
+        let rows: [[u8; 6]; 4] = [
+            [10, 20, 30, 40, 50, 60],
+            [11, 22, 33, 44, 55, 66],
+            [0, 255, 128, 1, 2, 3],
+            [9, 9, 9, 200, 100, 50],
+        ];
+        let mut encoded = Vec::new();
+        let mut prev = [0u8; 6];
+        for (filter, row) in [1u8, 2, 3, 4].into_iter().zip(rows) {
+            encoded.push(filter);
+            for i in 0..6 {
+                let left = if i >= 3 { row[i - 3] } else { 0 };
+                let up_left = if i >= 3 { prev[i - 3] } else { 0 };
+                let predicted = match filter {
+                    1 => left,
+                    2 => prev[i],
+                    3 => ((left as u16 + prev[i] as u16) / 2) as u8,
+                    _ => paeth(left, prev[i], up_left),
+                };
+                encoded.push(row[i].wrapping_sub(predicted));
+            }
+            prev = row;
+        }
+        assert_eq!(undo_png_predictor(&encoded, 6, 3), rows.concat());
+    }
+
+    use super::*;
+
+    #[test]
+    fn ascii_hex() {
+        assert_eq!(ascii_hex_decode(b"48 65\n6C6c6F>").unwrap(), b"Hello");
+        assert_eq!(ascii_hex_decode(b"7").unwrap(), [0x70]);
+        assert!(ascii_hex_decode(b"4g").is_err());
+    }
+
+    #[test]
+    fn ascii85() {
+        assert_eq!(
+            ascii85_decode(b"87cURD]i,\"Ebo80~>").unwrap(),
+            b"Hello World!"
+        );
+        assert_eq!(ascii85_decode(b"<~z87cU\nRDZ~>").unwrap(), b"\0\0\0\0Hello");
+        assert_eq!(ascii85_decode(b"").unwrap(), b"");
+        // A lone final digit, a digit out of range, a group above 2^32
+        assert!(ascii85_decode(b"87cURD]i,\"Ebo80!~>").is_err());
+        assert!(ascii85_decode(b"87v").is_err());
+        assert!(ascii85_decode(b"uuuuu").is_err());
+    }
+
+    #[test]
+    fn filter_chain() {
+        use crate::types::PdfObj;
+        use alloc::vec;
+
+        let inflate = |data: &[u8]| {
+            miniz_oxide::inflate::decompress_to_vec_zlib(data)
+                .map_err(|_| PdfError::DecompressionError)
+        };
+        let flate = miniz_oxide::deflate::compress_to_vec_zlib(b"BT (Hi) Tj ET", 6);
+        let hex: Vec<u8> = flate
+            .iter()
+            .flat_map(|b| alloc::format!("{:02x}", b).into_bytes())
+            .collect();
+        let chain = PdfObj::Array(vec![
+            PdfObj::Name("ASCIIHexDecode".into()),
+            PdfObj::Name("FlateDecode".into()),
+        ]);
+
+        let mut out = Vec::new();
+        let params = PdfObj::Array(vec![PdfObj::Null, PdfObj::Null]);
+        crate::handle_stream_filters(&chain, Some(&params), &hex, &inflate, &mut out).unwrap();
+        assert_eq!(out, [b"BT (Hi) Tj ET".to_vec()]);
+
+        let short = PdfObj::Array(vec![PdfObj::Null]);
+        assert!(
+            crate::handle_stream_filters(&chain, Some(&short), &hex, &inflate, &mut out).is_err()
+        );
+        let lzw = PdfObj::Name("LZWDecode".into());
+        assert!(crate::handle_stream_filters(&lzw, None, &hex, &inflate, &mut out).is_err());
+    }
+
+    #[test]
+    fn predictor_parameters() {
+        let params = |entries: &'static [(&'static str, f64)]| {
+            move |key: &str| entries.iter().find(|(k, _)| *k == key).map(|(_, v)| *v)
+        };
+        // TIFF: each byte is the difference from the one a pixel to the left
+        let tiff = params(&[("Predictor", 2.0), ("Colors", 2.0), ("Columns", 2.0)]);
+        assert_eq!(
+            undo_predictor(alloc::vec![1, 2, 3, 4], tiff).unwrap(),
+            [1, 2, 4, 6]
+        );
+        assert_eq!(
+            undo_predictor(
+                alloc::vec![2, 1, 1],
+                params(&[("Predictor", 12.0), ("Columns", 2.0)])
+            )
+            .unwrap(),
+            [1, 1]
+        );
+        assert_eq!(undo_predictor(alloc::vec![7], params(&[])).unwrap(), [7]);
+
+        assert!(undo_predictor(alloc::vec![0; 4], params(&[("Predictor", 3.0)])).is_none());
+        assert!(undo_predictor(
+            alloc::vec![0; 4],
+            params(&[("Predictor", 12.0), ("Columns", 1e12)])
+        )
+        .is_none());
+        assert!(undo_predictor(
+            alloc::vec![0; 4],
+            params(&[("Predictor", 12.0), ("BitsPerComponent", 3.0)])
+        )
+        .is_none());
+    }
+}
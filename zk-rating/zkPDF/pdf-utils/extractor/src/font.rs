@@ -1,16 +1,40 @@
-use std::collections::HashMap;
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec::Vec;
 
 use crate::{
     cmap::parse_cmap,
-    handle_stream_filters,
-    types::{PdfError, PdfFont, PdfObj},
+    font_program::{
+        cff_code_names, opentype_code_names, truetype_code_names, truetype_glyph_unicode,
+        type1_code_names,
+    },
+    handle_stream_filters, numbers,
+    types::{
+        CidEncoding, CidFont, CidSystemInfo, FontWidths, PdfError, PdfFont, PdfObj, PdfStream,
+        Type3Glyphs,
+    },
+    HashMap,
 };
 
+type Decompress<'a> = &'a dyn Fn(&[u8]) -> Result<Vec<u8>, PdfError>;
+
 pub fn collect_fonts_from_resources(
     resources: &HashMap<String, PdfObj>,
     objects: &HashMap<(u32, u16), PdfObj>,
-    decompress: &dyn Fn(&[u8]) -> Result<Vec<u8>, PdfError>,
+    decompress: Decompress<'_>,
+) -> Result<HashMap<String, PdfFont>, PdfError> {
+    collect_fonts(resources, objects, decompress, true)
+}
+
+// With `glyph_procs`, Type3 fonts come with the glyph procedures that show text. The fonts those
+// procedures use are collected without, so a Type3 font cannot recurse into itself.
+fn collect_fonts(
+    resources: &HashMap<String, PdfObj>,
+    objects: &HashMap<(u32, u16), PdfObj>,
+    decompress: Decompress<'_>,
+    glyph_procs: bool,
 ) -> Result<HashMap<String, PdfFont>, PdfError> {
+    let _timed = timed!("collect_fonts");
     let mut fonts_map: HashMap<String, PdfFont> = HashMap::new();
     if let Some(fonts_entry) = resources.get("Font") {
         let font_dict = match fonts_entry {
@@ -99,36 +123,410 @@ pub fn collect_fonts_from_resources(
                 let mut to_uni_map: Option<HashMap<u32, String>> = None;
                 if let Some(PdfObj::Reference(tu_ref)) = font_dic.get("ToUnicode") {
                     if let Some(PdfObj::Stream(tu_stream)) = objects.get(tu_ref) {
-                        let cmap_bytes = if let Some(filter) = tu_stream.dict.get("Filter") {
-                            let mut temp_vecs: Vec<Vec<u8>> = Vec::new();
-                            handle_stream_filters(
-                                filter,
-                                &tu_stream.data,
-                                decompress,
-                                &mut temp_vecs,
-                            )?;
-                            if !temp_vecs.is_empty() {
-                                temp_vecs.remove(0)
-                            } else {
-                                tu_stream.data.clone()
-                            }
-                        } else {
-                            tu_stream.data.clone()
-                        };
-                        to_uni_map = Some(parse_cmap(&cmap_bytes));
+                        to_uni_map = Some(parse_cmap(&stream_bytes(tu_stream, decompress)?));
                     }
                 }
 
+                let cid = match subtype.as_deref() {
+                    Some("Type0") => Some(cid_font(&font_dic, objects, decompress)?),
+                    _ => None,
+                };
+
+                let builtin_encoding = match (&to_uni_map, subtype.as_deref()) {
+                    (None, Some("Type1" | "MMType1" | "TrueType")) => {
+                        builtin_encoding(&font_dic, objects, decompress)
+                    }
+                    _ => None,
+                };
+
+                let type3 = match (subtype.as_deref(), &differences_map) {
+                    (Some("Type3"), Some(differences)) if glyph_procs => {
+                        type3_glyphs(&font_dic, differences, objects, decompress)
+                    }
+                    _ => None,
+                };
+
                 let pdf_font = PdfFont {
                     base_name,
                     subtype,
                     encoding: encoding_name,
                     to_unicode_map: to_uni_map.map(|m| m.into_iter().collect()),
                     differences: differences_map,
+                    cid,
+                    builtin_encoding,
+                    widths: font_widths(&font_dic, objects),
+                    type3,
                 };
                 fonts_map.insert(font_key.clone(), pdf_font);
             }
         }
     }
+    trace_event!(fonts = fonts_map.len(), "fonts collected");
     Ok(fonts_map)
 }
+
+// The glyph procedures of a Type3 font that show text, by the code `/Differences` gives their
+// glyph name. Procedures that only draw paths, nearly all of them, are not kept.
+fn type3_glyphs(
+    font: &HashMap<String, PdfObj>,
+    differences: &HashMap<u32, String>,
+    objects: &HashMap<(u32, u16), PdfObj>,
+    decompress: Decompress<'_>,
+) -> Option<Type3Glyphs> {
+    let Some(PdfObj::Dictionary(char_procs)) = resolve(font.get("CharProcs"), objects) else {
+        return None;
+    };
+    let mut procs = HashMap::new();
+    for (&code, name) in differences {
+        let Some(PdfObj::Stream(glyph)) = resolve(char_procs.get(name), objects) else {
+            continue;
+        };
+        let Ok(content) = stream_bytes(glyph, decompress) else {
+            continue;
+        };
+        if content.windows(2).any(|w| w == b"BT") {
+            procs.insert(code, content.into_owned());
+        }
+    }
+    if procs.is_empty() {
+        return None;
+    }
+    let fonts = match resolve(font.get("Resources"), objects) {
+        Some(PdfObj::Dictionary(resources)) => {
+            collect_fonts(resources, objects, decompress, false).unwrap_or_default()
+        }
+        _ => HashMap::new(),
+    };
+    Some(Type3Glyphs {
+        matrix: numbers(font.get("FontMatrix"), objects)
+            .unwrap_or([0.001, 0.0, 0.0, 0.001, 0.0, 0.0]),
+        procs,
+        fonts,
+    })
+}
+
+// The data of a stream with its filters applied
+fn stream_bytes<'s>(
+    stream: &'s PdfStream,
+    decompress: Decompress<'_>,
+) -> Result<Cow<'s, [u8]>, PdfError> {
+    let Some(filter) = stream.dict.get("Filter") else {
+        return Ok(Cow::Borrowed(&stream.data));
+    };
+    let mut decoded = Vec::new();
+    handle_stream_filters(
+        filter,
+        stream.dict.get("DecodeParms"),
+        &stream.data,
+        decompress,
+        &mut decoded,
+    )?;
+    Ok(decoded
+        .pop()
+        .map_or(Cow::Borrowed(&stream.data), Cow::Owned))
+}
+
+fn resolve<'o, 'a>(
+    obj: Option<&'o PdfObj<'a>>,
+    objects: &'o HashMap<(u32, u16), PdfObj<'a>>,
+) -> Option<&'o PdfObj<'a>> {
+    match obj {
+        Some(PdfObj::Reference(id)) => objects.get(id),
+        other => other,
+    }
+}
+
+// The encoding CMap of a Type0 font and what its descendant CIDFont says about CIDs and glyphs
+fn cid_font(
+    font: &HashMap<String, PdfObj>,
+    objects: &HashMap<(u32, u16), PdfObj>,
+    decompress: Decompress<'_>,
+) -> Result<CidFont, PdfError> {
+    let encoding = match resolve(font.get("Encoding"), objects) {
+        Some(PdfObj::Name(name)) => CidEncoding::from_name(name),
+        Some(PdfObj::Stream(cmap)) => match cmap.dict.get("CMapName") {
+            Some(PdfObj::Name(name)) => CidEncoding::Other(name.clone()),
+            _ => CidEncoding::Other(String::new()),
+        },
+        _ => CidEncoding::Identity { vertical: false },
+    };
+    let mut cid = CidFont {
+        encoding,
+        system_info: None,
+        cid_to_gid: None,
+        glyph_unicode: HashMap::new(),
+    };
+
+    let descendant = match resolve(font.get("DescendantFonts"), objects) {
+        Some(PdfObj::Array(fonts)) => resolve(fonts.first(), objects),
+        _ => None,
+    };
+    let Some(PdfObj::Dictionary(descendant)) = descendant else {
+        return Ok(cid);
+    };
+
+    if let Some(PdfObj::Dictionary(info)) = resolve(descendant.get("CIDSystemInfo"), objects) {
+        let text = |key: &str| match resolve(info.get(key), objects) {
+            Some(PdfObj::String(bytes)) => String::from_utf8_lossy(bytes).into_owned(),
+            _ => String::new(),
+        };
+        cid.system_info = Some(CidSystemInfo {
+            registry: text("Registry"),
+            ordering: text("Ordering"),
+            supplement: match resolve(info.get("Supplement"), objects) {
+                Some(PdfObj::Number(n)) if *n >= 0.0 => *n as u32,
+                _ => 0,
+            },
+        });
+    }
+
+    if let Some(PdfObj::Stream(map)) = resolve(descendant.get("CIDToGIDMap"), objects) {
+        let map = stream_bytes(map, decompress)?;
+        cid.cid_to_gid = Some(
+            map.chunks_exact(2)
+                .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+                .collect(),
+        );
+    }
+
+    if let Some(PdfObj::Dictionary(descriptor)) = resolve(descendant.get("FontDescriptor"), objects)
+    {
+        if let Some(PdfObj::Stream(program)) = resolve(descriptor.get("FontFile2"), objects) {
+            // A damaged font program only costs the glyph fallback
+            if let Ok(program) = stream_bytes(program, decompress) {
+                cid.glyph_unicode = truetype_glyph_unicode(&program);
+            }
+        }
+    }
+    Ok(cid)
+}
+
+// Glyph names by code from the font program embedded in a simple font's descriptor. A missing
+// or damaged program only costs the fallback, like in `cid_font`.
+fn builtin_encoding(
+    font: &HashMap<String, PdfObj>,
+    objects: &HashMap<(u32, u16), PdfObj>,
+    decompress: Decompress<'_>,
+) -> Option<HashMap<u32, String>> {
+    let Some(PdfObj::Dictionary(descriptor)) = resolve(font.get("FontDescriptor"), objects) else {
+        return None;
+    };
+    let program = |key: &str| match resolve(descriptor.get(key), objects) {
+        Some(PdfObj::Stream(stream)) => stream_bytes(stream, decompress)
+            .ok()
+            .map(|data| (stream, data)),
+        _ => None,
+    };
+    let names = if let Some((_, data)) = program("FontFile") {
+        type1_code_names(&data)
+    } else if let Some((_, data)) = program("FontFile2") {
+        truetype_code_names(&data)
+    } else if let Some((stream, data)) = program("FontFile3") {
+        match stream.dict.get("Subtype") {
+            Some(PdfObj::Name(subtype)) if subtype == "OpenType" => opentype_code_names(&data),
+            _ => cff_code_names(&data),
+        }
+    } else {
+        return None;
+    };
+    (!names.is_empty()).then_some(names)
+}
+
+/// Width of the space glyph assumed for fonts that do not give one, in thousandths of a text
+/// space unit per unit of font size.
+pub(crate) const DEFAULT_SPACE_WIDTH: f32 = 250.0;
+
+/// Share of the space width a gap between two pieces of a line must reach to read as a space.
+pub(crate) const WORD_GAP: f32 = 0.5;
+
+// `/Widths` of a simple font, `/W` and `/DW` of a Type0 font's descendant
+fn font_widths(
+    font: &HashMap<String, PdfObj>,
+    objects: &HashMap<(u32, u16), PdfObj>,
+) -> Option<FontWidths> {
+    let number = |obj: Option<&PdfObj>| match resolve(obj, objects) {
+        Some(PdfObj::Number(n)) => Some(*n as f32),
+        _ => None,
+    };
+
+    if matches!(font.get("Subtype"), Some(PdfObj::Name(subtype)) if subtype == "Type0") {
+        let descendant = match resolve(font.get("DescendantFonts"), objects) {
+            Some(PdfObj::Array(fonts)) => resolve(fonts.first(), objects),
+            _ => None,
+        };
+        let Some(PdfObj::Dictionary(descendant)) = descendant else {
+            return None;
+        };
+        let mut widths = FontWidths {
+            widths: HashMap::new(),
+            default: number(descendant.get("DW")).unwrap_or(1000.0),
+        };
+        // `c [w1 w2 ...]` gives consecutive CIDs from c; `first last w` a range of one width
+        if let Some(PdfObj::Array(entries)) = resolve(descendant.get("W"), objects) {
+            let mut i = 0;
+            while i + 1 < entries.len() {
+                let Some(first) = number(entries.get(i)) else {
+                    break;
+                };
+                let first = first as u32;
+                match resolve(entries.get(i + 1), objects) {
+                    Some(PdfObj::Array(run)) => {
+                        for (cid, width) in (first..).zip(run) {
+                            if let Some(width) = number(Some(width)) {
+                                widths.widths.insert(cid, width);
+                            }
+                        }
+                        i += 2;
+                    }
+                    _ => {
+                        let (Some(last), Some(width)) =
+                            (number(entries.get(i + 1)), number(entries.get(i + 2)))
+                        else {
+                            break;
+                        };
+                        // Bounded, so a corrupt range cannot exhaust memory
+                        for cid in first..=(last as u32).min(first.saturating_add(0xFFFF)) {
+                            widths.widths.insert(cid, width);
+                        }
+                        i += 3;
+                    }
+                }
+            }
+        }
+        return Some(widths);
+    }
+
+    let Some(PdfObj::Array(entries)) = resolve(font.get("Widths"), objects) else {
+        return None;
+    };
+    let first = number(font.get("FirstChar")).unwrap_or(0.0) as u32;
+    let missing = match resolve(font.get("FontDescriptor"), objects) {
+        Some(PdfObj::Dictionary(descriptor)) => number(descriptor.get("MissingWidth")),
+        _ => None,
+    };
+    Some(FontWidths {
+        widths: (first..)
+            .zip(entries)
+            .filter_map(|(code, width)| Some((code, number(Some(width))?)))
+            .collect(),
+        default: missing.unwrap_or(0.0),
+    })
+}
+
+/// Horizontal advance of a string shown in `font`, in unscaled text space units: glyph widths
+/// at `size`, plus character spacing (`Tc`) for every glyph and word spacing (`Tw`) for every
+/// single-byte code 32, all scaled horizontally by `scale` (`Tz` / 100). `None` when the font
+/// has no widths.
+pub(crate) fn string_advance(
+    font: &PdfFont,
+    bytes: &[u8],
+    size: f32,
+    char_spacing: f32,
+    word_spacing: f32,
+    scale: f32,
+) -> Option<f32> {
+    let widths = font.widths.as_ref()?;
+    let width = |code: u32| widths.widths.get(&code).copied().unwrap_or(widths.default);
+    let advance: f32 = match &font.cid {
+        Some(cid) => bytes
+            .chunks(2)
+            .map(|pair| {
+                let code = pair.iter().fold(0, |code, &b| code << 8 | u32::from(b));
+                // Unicode CMaps' codes are not CIDs
+                let glyph = match cid.encoding {
+                    CidEncoding::Utf16 { .. } => widths.default,
+                    _ => width(code),
+                };
+                glyph * size / 1000.0 + char_spacing
+            })
+            .sum(),
+        None => bytes
+            .iter()
+            .map(|&code| {
+                let spacing = if code == b' ' { word_spacing } else { 0.0 };
+                width(u32::from(code)) * size / 1000.0 + char_spacing + spacing
+            })
+            .sum(),
+    };
+    Some(advance * scale)
+}
+
+/// Whether a horizontal gap of `gap` unscaled text space units, between two pieces of text on
+/// one line, separates words: it must reach half the width of the font's space glyph.
+pub(crate) fn is_word_gap(font: &PdfFont, gap: f32, size: f32, scale: f32) -> bool {
+    let space = match (&font.cid, &font.widths) {
+        (None, Some(widths)) => widths
+            .widths
+            .get(&32)
+            .copied()
+            .filter(|&width| width > 0.0)
+            .unwrap_or(DEFAULT_SPACE_WIDTH),
+        _ => DEFAULT_SPACE_WIDTH,
+    };
+    gap > 0.0 && gap >= WORD_GAP * space * size.abs() / 1000.0 * scale.abs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    fn numbers(values: &[f64]) -> PdfObj<'static> {
+        PdfObj::Array(values.iter().map(|&n| PdfObj::Number(n)).collect())
+    }
+
+    #[test]
+    fn cid_widths_read_both_w_forms() {
+        // `1 [300 400]` and `10 12 700`; the descendant is indirect
+        let w = vec![
+            PdfObj::Number(1.0),
+            numbers(&[300.0, 400.0]),
+            PdfObj::Number(10.0),
+            PdfObj::Number(12.0),
+            PdfObj::Number(700.0),
+        ];
+        let descendant = HashMap::from([
+            ("W".into(), PdfObj::Array(w)),
+            ("DW".into(), PdfObj::Number(900.0)),
+        ]);
+        let objects = HashMap::from([((5, 0), PdfObj::Dictionary(descendant))]);
+        let font = HashMap::from([
+            ("Subtype".into(), PdfObj::Name("Type0".into())),
+            (
+                "DescendantFonts".into(),
+                PdfObj::Array(vec![PdfObj::Reference((5, 0))]),
+            ),
+        ]);
+
+        let widths = font_widths(&font, &objects).unwrap();
+        assert_eq!(widths.default, 900.0);
+        assert_eq!(widths.widths.get(&2), Some(&400.0));
+        assert_eq!(widths.widths.get(&12), Some(&700.0));
+        assert_eq!(widths.widths.get(&13), None);
+    }
+
+    #[test]
+    fn advance_includes_text_state() {
+        let font = PdfFont {
+            base_name: None,
+            subtype: Some("TrueType".into()),
+            encoding: None,
+            to_unicode_map: None,
+            differences: None,
+            cid: None,
+            builtin_encoding: None,
+            widths: Some(FontWidths {
+                widths: HashMap::from([(32, 250.0), (u32::from(b'a'), 500.0)]),
+                default: 0.0,
+            }),
+            type3: None,
+        };
+
+        // "a a" at 10pt: glyphs 5 + 2.5 + 5, Tc 1 per glyph, Tw 2 on the space, Tz 50
+        let advance = string_advance(&font, b"a a", 10.0, 1.0, 2.0, 0.5).unwrap();
+        assert_eq!(advance, (12.5 + 3.0 + 2.0) * 0.5);
+        // Half a space: 1.25 at 10pt
+        assert!(is_word_gap(&font, 1.25, 10.0, 1.0));
+        assert!(!is_word_gap(&font, 1.0, 10.0, 1.0));
+    }
+}
@@ -0,0 +1,1260 @@
+//! Glyph names and Unicode values read from font programs embedded in a font descriptor
+//! (`/FontFile`, `/FontFile2`, `/FontFile3`), for fonts that come without a ToUnicode CMap.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::{encoding::glyph_to_unicode, HashMap};
+
+/// Glyph id to Unicode for a TrueType program, inverted from the Unicode subtable of its
+/// `cmap` table (format 12 for the full range, else format 4). The lowest code point wins
+/// when glyphs are shared; glyphs the subtable leaves out fall back to their `post` names.
+pub(crate) fn truetype_glyph_unicode(font: &[u8]) -> HashMap<u16, char> {
+    let mut glyphs = HashMap::new();
+    let subtables = cmap_subtables(font);
+    let unicode = [12, 4].into_iter().find_map(|wanted| {
+        subtables.iter().find_map(|&(platform, encoding, table)| {
+            (u16_at(table, 0) == Some(wanted)
+                && (platform == 0 || (platform == 3 && matches!(encoding, 1 | 10))))
+            .then_some(table)
+        })
+    });
+    for (code, glyph) in unicode.map(subtable_mappings).unwrap_or_default() {
+        if let (true, Some(ch)) = (glyph != 0, char::from_u32(code)) {
+            glyphs.entry(glyph).or_insert(ch);
+        }
+    }
+    for (glyph, name) in post_glyph_names(font).into_iter().enumerate().skip(1) {
+        if let (Ok(glyph), Some(ch)) = (u16::try_from(glyph), glyph_to_unicode(name)) {
+            glyphs.entry(glyph).or_insert(ch);
+        }
+    }
+    glyphs
+}
+
+/// Glyph names by code of a TrueType program used as a simple font: codes go through the
+/// symbolic (3, 0) or Mac Roman (1, 0) subtable of `cmap` to glyphs named in `post`.
+pub(crate) fn truetype_code_names(font: &[u8]) -> HashMap<u32, String> {
+    let mut names = HashMap::new();
+    let glyph_names = post_glyph_names(font);
+    if glyph_names.is_empty() {
+        return names;
+    }
+    let subtables = cmap_subtables(font);
+    let Some(table) = [(3, 0), (1, 0)].into_iter().find_map(|wanted| {
+        subtables.iter().find_map(|&(platform, encoding, table)| {
+            ((platform, encoding) == wanted).then_some(table)
+        })
+    }) else {
+        return names;
+    };
+    for (code, glyph) in subtable_mappings(table) {
+        // Symbolic subtables may put the codes at U+F000 + code
+        let code = match code {
+            0..=0xFF => code,
+            0xF000..=0xF0FF => code - 0xF000,
+            _ => continue,
+        };
+        match glyph_names.get(glyph as usize) {
+            Some(&name) if !name.is_empty() && name != ".notdef" => {
+                names.entry(code).or_insert_with(|| name.to_string());
+            }
+            _ => {}
+        }
+    }
+    names
+}
+
+/// Glyph names by code from the `/Encoding` array in the cleartext part of a Type1 program
+/// (`dup 65 /A put`). Empty for `StandardEncoding`, which the caller already knows.
+pub(crate) fn type1_code_names(font: &[u8]) -> HashMap<u32, String> {
+    let mut names = HashMap::new();
+    let clear = match font.windows(5).position(|w| w == b"eexec") {
+        Some(at) => &font[..at],
+        None => font,
+    };
+    let text = String::from_utf8_lossy(clear);
+    let Some(at) = text.find("/Encoding") else {
+        return names;
+    };
+    let mut tokens = text[at + "/Encoding".len()..].split_ascii_whitespace();
+    while let Some(token) = tokens.next() {
+        match token {
+            "dup" => {
+                let (Some(code), Some(name)) = (tokens.next(), tokens.next()) else {
+                    break;
+                };
+                if let (Ok(code), Some(name)) = (code.parse::<u32>(), name.strip_prefix('/')) {
+                    if name != ".notdef" {
+                        names.insert(code, name.to_string());
+                    }
+                }
+            }
+            "def" | "readonly" => break,
+            _ => {}
+        }
+    }
+    names
+}
+
+/// Glyph names by code from the encoding and charset of a bare CFF program (`/FontFile3`,
+/// `/Subtype /Type1C`). Empty for the predefined encodings and CID-keyed fonts.
+pub(crate) fn cff_code_names(cff: &[u8]) -> HashMap<u32, String> {
+    cff_names(cff).unwrap_or_default()
+}
+
+/// An OpenType program (`/FontFile3`, `/Subtype /OpenType`): its `CFF ` table if it has
+/// one, else its TrueType tables.
+pub(crate) fn opentype_code_names(font: &[u8]) -> HashMap<u32, String> {
+    match sfnt_table(font, b"CFF ") {
+        Some(cff) => cff_code_names(cff),
+        None => truetype_code_names(font),
+    }
+}
+
+// A table of a TrueType (sfnt) font program, by tag
+fn sfnt_table<'a>(font: &'a [u8], tag: &[u8; 4]) -> Option<&'a [u8]> {
+    let num_tables = u16_at(font, 4)? as usize;
+    (0..num_tables).find_map(|i| {
+        let record = font.get(12 + i * 16..28 + i * 16)?;
+        if &record[..4] != tag {
+            return None;
+        }
+        let offset = u32_at(record, 8)? as usize;
+        let length = u32_at(record, 12)? as usize;
+        font.get(offset..offset.checked_add(length)?)
+    })
+}
+
+fn u16_at(data: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(data.get(at..at + 2)?.try_into().ok()?))
+}
+
+fn u32_at(data: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(data.get(at..at + 4)?.try_into().ok()?))
+}
+
+// (platform, encoding, subtable) of each encoding record of the `cmap` table
+fn cmap_subtables(font: &[u8]) -> Vec<(u16, u16, &[u8])> {
+    let Some(cmap) = sfnt_table(font, b"cmap") else {
+        return Vec::new();
+    };
+    (0..u16_at(cmap, 2).unwrap_or(0) as usize)
+        .filter_map(|i| {
+            let platform = u16_at(cmap, 4 + i * 8)?;
+            let encoding = u16_at(cmap, 6 + i * 8)?;
+            let table = cmap.get(u32_at(cmap, 8 + i * 8)? as usize..)?;
+            Some((platform, encoding, table))
+        })
+        .collect()
+}
+
+// (code, glyph) pairs of a `cmap` subtable in format 0, 4, 6 or 12, in code order
+fn subtable_mappings(table: &[u8]) -> Vec<(u32, u16)> {
+    let mut mappings = Vec::new();
+    match u16_at(table, 0) {
+        Some(0) => {
+            if let Some(glyphs) = table.get(6..262) {
+                mappings.extend((0..).zip(glyphs.iter().map(|&glyph| glyph as u16)));
+            }
+        }
+        Some(6) => {
+            let first = u16_at(table, 6).unwrap_or(0) as u32;
+            let count = u16_at(table, 8).unwrap_or(0) as usize;
+            for i in 0..count {
+                let Some(glyph) = u16_at(table, 10 + i * 2) else {
+                    break;
+                };
+                mappings.push((first + i as u32, glyph));
+            }
+        }
+        Some(4) => {
+            let segments = u16_at(table, 6).unwrap_or(0) as usize / 2;
+            let ends = 14;
+            let starts = ends + segments * 2 + 2;
+            let deltas = starts + segments * 2;
+            let range_offsets = deltas + segments * 2;
+            for segment in 0..segments {
+                let (Some(end), Some(start), Some(delta), Some(range_offset)) = (
+                    u16_at(table, ends + segment * 2),
+                    u16_at(table, starts + segment * 2),
+                    u16_at(table, deltas + segment * 2),
+                    u16_at(table, range_offsets + segment * 2),
+                ) else {
+                    break;
+                };
+                for code in start..=end.min(0xFFFE) {
+                    let glyph = if range_offset == 0 {
+                        code.wrapping_add(delta)
+                    } else {
+                        let at = range_offsets
+                            + segment * 2
+                            + range_offset as usize
+                            + (code - start) as usize * 2;
+                        match u16_at(table, at) {
+                            Some(0) | None => continue,
+                            Some(glyph) => glyph.wrapping_add(delta),
+                        }
+                    };
+                    mappings.push((code as u32, glyph));
+                }
+            }
+        }
+        Some(12) => {
+            let groups = u32_at(table, 12).unwrap_or(0) as usize;
+            // Bound the work on hostile tables to one pass over the Unicode range
+            let mut budget = 0x11_0000u32;
+            for group in 0..groups {
+                let (Some(start), Some(end), Some(first)) = (
+                    u32_at(table, 16 + group * 12),
+                    u32_at(table, 20 + group * 12),
+                    u32_at(table, 24 + group * 12),
+                ) else {
+                    break;
+                };
+                let end = end.min(0x10_FFFF);
+                if start > end || end - start >= budget {
+                    break;
+                }
+                budget -= end - start + 1;
+                for code in start..=end {
+                    if let Ok(glyph) = u16::try_from(first.wrapping_add(code - start)) {
+                        mappings.push((code, glyph));
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+    mappings
+}
+
+// Glyph names by glyph id from the `post` table (versions 1 and 2); unnamed glyphs are empty
+fn post_glyph_names(font: &[u8]) -> Vec<&str> {
+    let Some(post) = sfnt_table(font, b"post") else {
+        return Vec::new();
+    };
+    match u32_at(post, 0) {
+        Some(0x0001_0000) => MAC_GLYPH_NAMES.to_vec(),
+        Some(0x0002_0000) => {
+            let count = u16_at(post, 32).unwrap_or(0) as usize;
+            let mut custom = Vec::new();
+            let mut at = 34 + count * 2;
+            while let Some(&len) = post.get(at) {
+                let Some(name) = post.get(at + 1..at + 1 + len as usize) else {
+                    break;
+                };
+                custom.push(core::str::from_utf8(name).unwrap_or(""));
+                at += 1 + len as usize;
+            }
+            (0..count)
+                .map(
+                    |glyph| match u16_at(post, 34 + glyph * 2).map(usize::from) {
+                        Some(index) if index < MAC_GLYPH_NAMES.len() => MAC_GLYPH_NAMES[index],
+                        Some(index) => custom
+                            .get(index - MAC_GLYPH_NAMES.len())
+                            .copied()
+                            .unwrap_or(""),
+                        None => "",
+                    },
+                )
+                .collect()
+        }
+        _ => Vec::new(),
+    }
+}
+
+fn cff_names(cff: &[u8]) -> Option<HashMap<u32, String>> {
+    let (_, after_names) = cff_index(cff, *cff.get(2)? as usize)?;
+    let (top_dicts, after_top) = cff_index(cff, after_names)?;
+    let (strings, _) = cff_index(cff, after_top)?;
+    let top = cff_dict(top_dicts.first()?);
+    // ROS: CID-keyed fonts are reached through Type0 fonts, by CID
+    if top.contains_key(&1230) {
+        return None;
+    }
+    let offset = |op: u16| match top.get(&op).and_then(|operands| operands.first()) {
+        Some(&value) => usize::try_from(value).ok(),
+        None => Some(0),
+    };
+    let encoding = offset(16)?;
+    // 0 and 1 are the Standard and Expert encodings
+    if encoding <= 1 {
+        return None;
+    }
+    let (charstrings, _) = cff_index(cff, offset(17).filter(|&at| at != 0)?)?;
+    let glyph_count = charstrings.len();
+
+    // SIDs by glyph id
+    let charset = offset(15)?;
+    let mut sids: Vec<u16> = Vec::with_capacity(glyph_count);
+    match charset {
+        // ISOAdobe: SIDs 0 to 228 in order
+        0 => sids.extend((0..glyph_count.min(229)).map(|sid| sid as u16)),
+        1 | 2 => return None,
+        _ => {
+            sids.push(0);
+            let format = *cff.get(charset)?;
+            let mut at = charset + 1;
+            while sids.len() < glyph_count {
+                if format == 0 {
+                    sids.push(u16_at(cff, at)?);
+                    at += 2;
+                    continue;
+                }
+                let first = u16_at(cff, at)?;
+                let left = match format {
+                    1 => *cff.get(at + 2)? as u16,
+                    2 => u16_at(cff, at + 2)?,
+                    _ => return None,
+                };
+                at += if format == 1 { 3 } else { 4 };
+                for sid in (first..=first.saturating_add(left)).take(glyph_count - sids.len()) {
+                    sids.push(sid);
+                }
+            }
+        }
+    }
+    let name = |sid: u16| match sid as usize {
+        0 => None,
+        sid if sid < CFF_STANDARD_STRINGS.len() => Some(CFF_STANDARD_STRINGS[sid].to_string()),
+        sid => strings
+            .get(sid - CFF_STANDARD_STRINGS.len())
+            .and_then(|name| core::str::from_utf8(name).ok())
+            .map(ToString::to_string),
+    };
+
+    let mut names = HashMap::new();
+    let format = *cff.get(encoding)?;
+    let count = *cff.get(encoding + 1)? as usize;
+    let mut at = encoding + 2;
+    let mut glyph = 1;
+    match format & 0x7F {
+        0 => {
+            for code in cff.get(at..at + count)? {
+                if let Some(name) = sids.get(glyph).and_then(|&sid| name(sid)) {
+                    names.insert(*code as u32, name);
+                }
+                glyph += 1;
+            }
+            at += count;
+        }
+        1 => {
+            for range in cff.get(at..at + count * 2)?.chunks_exact(2) {
+                for code in range[0] as u32..=range[0] as u32 + range[1] as u32 {
+                    if let Some(name) = sids.get(glyph).and_then(|&sid| name(sid)) {
+                        names.insert(code, name);
+                    }
+                    glyph += 1;
+                }
+            }
+            at += count * 2;
+        }
+        _ => return None,
+    }
+    // Supplements: more codes for glyphs already encoded
+    if format & 0x80 != 0 {
+        let count = *cff.get(at)? as usize;
+        for supplement in cff.get(at + 1..at + 1 + count * 3)?.chunks_exact(3) {
+            if let Some(name) = name(u16::from_be_bytes([supplement[1], supplement[2]])) {
+                names.insert(supplement[0] as u32, name);
+            }
+        }
+    }
+    Some(names)
+}
+
+// The items of the CFF INDEX at `at`, and the offset just past it
+fn cff_index(cff: &[u8], at: usize) -> Option<(Vec<&[u8]>, usize)> {
+    let count = u16_at(cff, at)? as usize;
+    if count == 0 {
+        return Some((Vec::new(), at + 2));
+    }
+    let off_size = *cff.get(at + 2)? as usize;
+    if !(1..=4).contains(&off_size) {
+        return None;
+    }
+    let offset = |i: usize| {
+        let bytes = cff.get(at + 3 + i * off_size..at + 3 + (i + 1) * off_size)?;
+        Some(bytes.iter().fold(0usize, |n, &b| (n << 8) | b as usize))
+    };
+    // Offsets count from 1, at the byte before the data
+    let base = at + 2 + (count + 1) * off_size;
+    let mut items = Vec::with_capacity(count);
+    for i in 0..count {
+        items.push(cff.get(base + offset(i)?..base + offset(i + 1)?)?);
+    }
+    Some((items, base + offset(count)?))
+}
+
+// Operands by operator in a CFF DICT; two-byte operators are keyed 1200 + their second byte.
+// Real numbers are only skipped: none of the operators read here takes one.
+fn cff_dict(data: &[u8]) -> HashMap<u16, Vec<i32>> {
+    let mut dict = HashMap::new();
+    let mut operands = Vec::new();
+    let mut i = 0;
+    let byte = |at: usize| data.get(at).map(|&b| b as i32);
+    while let Some(&b) = data.get(i) {
+        i += 1;
+        let operand = match b {
+            0..=21 => {
+                let operator = if b == 12 {
+                    i += 1;
+                    1200 + data.get(i - 1).copied().unwrap_or(0) as u16
+                } else {
+                    b as u16
+                };
+                dict.insert(operator, core::mem::take(&mut operands));
+                continue;
+            }
+            28 => {
+                i += 2;
+                u16_at(data, i - 2).map(|n| n as i16 as i32)
+            }
+            29 => {
+                i += 4;
+                u32_at(data, i - 4).map(|n| n as i32)
+            }
+            30 => {
+                while let Some(&nibbles) = data.get(i) {
+                    i += 1;
+                    if nibbles & 0x0F == 0x0F || nibbles >> 4 == 0x0F {
+                        break;
+                    }
+                }
+                Some(0)
+            }
+            32..=246 => Some(b as i32 - 139),
+            247..=250 => {
+                i += 1;
+                byte(i - 1).map(|w| (b as i32 - 247) * 256 + w + 108)
+            }
+            251..=254 => {
+                i += 1;
+                byte(i - 1).map(|w| -(b as i32 - 251) * 256 - w - 108)
+            }
+            _ => continue,
+        };
+        match operand {
+            Some(operand) => operands.push(operand),
+            None => break,
+        }
+    }
+    dict
+}
+
+/// Names of the standard strings, SIDs 0 to 390 of the CFF specification (Appendix A).
+static CFF_STANDARD_STRINGS: [&str; 391] = [
+    ".notdef",
+    "space",
+    "exclam",
+    "quotedbl",
+    "numbersign",
+    "dollar",
+    "percent",
+    "ampersand",
+    "quoteright",
+    "parenleft",
+    "parenright",
+    "asterisk",
+    "plus",
+    "comma",
+    "hyphen",
+    "period",
+    "slash",
+    "zero",
+    "one",
+    "two",
+    "three",
+    "four",
+    "five",
+    "six",
+    "seven",
+    "eight",
+    "nine",
+    "colon",
+    "semicolon",
+    "less",
+    "equal",
+    "greater",
+    "question",
+    "at",
+    "A",
+    "B",
+    "C",
+    "D",
+    "E",
+    "F",
+    "G",
+    "H",
+    "I",
+    "J",
+    "K",
+    "L",
+    "M",
+    "N",
+    "O",
+    "P",
+    "Q",
+    "R",
+    "S",
+    "T",
+    "U",
+    "V",
+    "W",
+    "X",
+    "Y",
+    "Z",
+    "bracketleft",
+    "backslash",
+    "bracketright",
+    "asciicircum",
+    "underscore",
+    "quoteleft",
+    "a",
+    "b",
+    "c",
+    "d",
+    "e",
+    "f",
+    "g",
+    "h",
+    "i",
+    "j",
+    "k",
+    "l",
+    "m",
+    "n",
+    "o",
+    "p",
+    "q",
+    "r",
+    "s",
+    "t",
+    "u",
+    "v",
+    "w",
+    "x",
+    "y",
+    "z",
+    "braceleft",
+    "bar",
+    "braceright",
+    "asciitilde",
+    "exclamdown",
+    "cent",
+    "sterling",
+    "fraction",
+    "yen",
+    "florin",
+    "section",
+    "currency",
+    "quotesingle",
+    "quotedblleft",
+    "guillemotleft",
+    "guilsinglleft",
+    "guilsinglright",
+    "fi",
+    "fl",
+    "endash",
+    "dagger",
+    "daggerdbl",
+    "periodcentered",
+    "paragraph",
+    "bullet",
+    "quotesinglbase",
+    "quotedblbase",
+    "quotedblright",
+    "guillemotright",
+    "ellipsis",
+    "perthousand",
+    "questiondown",
+    "grave",
+    "acute",
+    "circumflex",
+    "tilde",
+    "macron",
+    "breve",
+    "dotaccent",
+    "dieresis",
+    "ring",
+    "cedilla",
+    "hungarumlaut",
+    "ogonek",
+    "caron",
+    "emdash",
+    "AE",
+    "ordfeminine",
+    "Lslash",
+    "Oslash",
+    "OE",
+    "ordmasculine",
+    "ae",
+    "dotlessi",
+    "lslash",
+    "oslash",
+    "oe",
+    "germandbls",
+    "onesuperior",
+    "logicalnot",
+    "mu",
+    "trademark",
+    "Eth",
+    "onehalf",
+    "plusminus",
+    "Thorn",
+    "onequarter",
+    "divide",
+    "brokenbar",
+    "degree",
+    "thorn",
+    "threequarters",
+    "twosuperior",
+    "registered",
+    "minus",
+    "eth",
+    "multiply",
+    "threesuperior",
+    "copyright",
+    "Aacute",
+    "Acircumflex",
+    "Adieresis",
+    "Agrave",
+    "Aring",
+    "Atilde",
+    "Ccedilla",
+    "Eacute",
+    "Ecircumflex",
+    "Edieresis",
+    "Egrave",
+    "Iacute",
+    "Icircumflex",
+    "Idieresis",
+    "Igrave",
+    "Ntilde",
+    "Oacute",
+    "Ocircumflex",
+    "Odieresis",
+    "Ograve",
+    "Otilde",
+    "Scaron",
+    "Uacute",
+    "Ucircumflex",
+    "Udieresis",
+    "Ugrave",
+    "Yacute",
+    "Ydieresis",
+    "Zcaron",
+    "aacute",
+    "acircumflex",
+    "adieresis",
+    "agrave",
+    "aring",
+    "atilde",
+    "ccedilla",
+    "eacute",
+    "ecircumflex",
+    "edieresis",
+    "egrave",
+    "iacute",
+    "icircumflex",
+    "idieresis",
+    "igrave",
+    "ntilde",
+    "oacute",
+    "ocircumflex",
+    "odieresis",
+    "ograve",
+    "otilde",
+    "scaron",
+    "uacute",
+    "ucircumflex",
+    "udieresis",
+    "ugrave",
+    "yacute",
+    "ydieresis",
+    "zcaron",
+    "exclamsmall",
+    "Hungarumlautsmall",
+    "dollaroldstyle",
+    "dollarsuperior",
+    "ampersandsmall",
+    "Acutesmall",
+    "parenleftsuperior",
+    "parenrightsuperior",
+    "twodotenleader",
+    "onedotenleader",
+    "zerooldstyle",
+    "oneoldstyle",
+    "twooldstyle",
+    "threeoldstyle",
+    "fouroldstyle",
+    "fiveoldstyle",
+    "sixoldstyle",
+    "sevenoldstyle",
+    "eightoldstyle",
+    "nineoldstyle",
+    "commasuperior",
+    "threequartersemdash",
+    "periodsuperior",
+    "questionsmall",
+    "asuperior",
+    "bsuperior",
+    "centsuperior",
+    "dsuperior",
+    "esuperior",
+    "isuperior",
+    "lsuperior",
+    "msuperior",
+    "nsuperior",
+    "osuperior",
+    "rsuperior",
+    "ssuperior",
+    "tsuperior",
+    "ff",
+    "ffi",
+    "ffl",
+    "parenleftinferior",
+    "parenrightinferior",
+    "Circumflexsmall",
+    "hyphensuperior",
+    "Gravesmall",
+    "Asmall",
+    "Bsmall",
+    "Csmall",
+    "Dsmall",
+    "Esmall",
+    "Fsmall",
+    "Gsmall",
+    "Hsmall",
+    "Ismall",
+    "Jsmall",
+    "Ksmall",
+    "Lsmall",
+    "Msmall",
+    "Nsmall",
+    "Osmall",
+    "Psmall",
+    "Qsmall",
+    "Rsmall",
+    "Ssmall",
+    "Tsmall",
+    "Usmall",
+    "Vsmall",
+    "Wsmall",
+    "Xsmall",
+    "Ysmall",
+    "Zsmall",
+    "colonmonetary",
+    "onefitted",
+    "rupiah",
+    "Tildesmall",
+    "exclamdownsmall",
+    "centoldstyle",
+    "Lslashsmall",
+    "Scaronsmall",
+    "Zcaronsmall",
+    "Dieresissmall",
+    "Brevesmall",
+    "Caronsmall",
+    "Dotaccentsmall",
+    "Macronsmall",
+    "figuredash",
+    "hypheninferior",
+    "Ogoneksmall",
+    "Ringsmall",
+    "Cedillasmall",
+    "questiondownsmall",
+    "oneeighth",
+    "threeeighths",
+    "fiveeighths",
+    "seveneighths",
+    "onethird",
+    "twothirds",
+    "zerosuperior",
+    "foursuperior",
+    "fivesuperior",
+    "sixsuperior",
+    "sevensuperior",
+    "eightsuperior",
+    "ninesuperior",
+    "zeroinferior",
+    "oneinferior",
+    "twoinferior",
+    "threeinferior",
+    "fourinferior",
+    "fiveinferior",
+    "sixinferior",
+    "seveninferior",
+    "eightinferior",
+    "nineinferior",
+    "centinferior",
+    "dollarinferior",
+    "periodinferior",
+    "commainferior",
+    "Agravesmall",
+    "Aacutesmall",
+    "Acircumflexsmall",
+    "Atildesmall",
+    "Adieresissmall",
+    "Aringsmall",
+    "AEsmall",
+    "Ccedillasmall",
+    "Egravesmall",
+    "Eacutesmall",
+    "Ecircumflexsmall",
+    "Edieresissmall",
+    "Igravesmall",
+    "Iacutesmall",
+    "Icircumflexsmall",
+    "Idieresissmall",
+    "Ethsmall",
+    "Ntildesmall",
+    "Ogravesmall",
+    "Oacutesmall",
+    "Ocircumflexsmall",
+    "Otildesmall",
+    "Odieresissmall",
+    "OEsmall",
+    "Oslashsmall",
+    "Ugravesmall",
+    "Uacutesmall",
+    "Ucircumflexsmall",
+    "Udieresissmall",
+    "Yacutesmall",
+    "Thornsmall",
+    "Ydieresissmall",
+    "001.000",
+    "001.001",
+    "001.002",
+    "001.003",
+    "Black",
+    "Bold",
+    "Book",
+    "Light",
+    "Medium",
+    "Regular",
+    "Roman",
+    "Semibold",
+];
+
+/// The standard Macintosh glyph order, which `post` table names below 258 index.
+static MAC_GLYPH_NAMES: [&str; 258] = [
+    ".notdef",
+    ".null",
+    "nonmarkingreturn",
+    "space",
+    "exclam",
+    "quotedbl",
+    "numbersign",
+    "dollar",
+    "percent",
+    "ampersand",
+    "quotesingle",
+    "parenleft",
+    "parenright",
+    "asterisk",
+    "plus",
+    "comma",
+    "hyphen",
+    "period",
+    "slash",
+    "zero",
+    "one",
+    "two",
+    "three",
+    "four",
+    "five",
+    "six",
+    "seven",
+    "eight",
+    "nine",
+    "colon",
+    "semicolon",
+    "less",
+    "equal",
+    "greater",
+    "question",
+    "at",
+    "A",
+    "B",
+    "C",
+    "D",
+    "E",
+    "F",
+    "G",
+    "H",
+    "I",
+    "J",
+    "K",
+    "L",
+    "M",
+    "N",
+    "O",
+    "P",
+    "Q",
+    "R",
+    "S",
+    "T",
+    "U",
+    "V",
+    "W",
+    "X",
+    "Y",
+    "Z",
+    "bracketleft",
+    "backslash",
+    "bracketright",
+    "asciicircum",
+    "underscore",
+    "grave",
+    "a",
+    "b",
+    "c",
+    "d",
+    "e",
+    "f",
+    "g",
+    "h",
+    "i",
+    "j",
+    "k",
+    "l",
+    "m",
+    "n",
+    "o",
+    "p",
+    "q",
+    "r",
+    "s",
+    "t",
+    "u",
+    "v",
+    "w",
+    "x",
+    "y",
+    "z",
+    "braceleft",
+    "bar",
+    "braceright",
+    "asciitilde",
+    "Adieresis",
+    "Aring",
+    "Ccedilla",
+    "Eacute",
+    "Ntilde",
+    "Odieresis",
+    "Udieresis",
+    "aacute",
+    "agrave",
+    "acircumflex",
+    "adieresis",
+    "atilde",
+    "aring",
+    "ccedilla",
+    "eacute",
+    "egrave",
+    "ecircumflex",
+    "edieresis",
+    "iacute",
+    "igrave",
+    "icircumflex",
+    "idieresis",
+    "ntilde",
+    "oacute",
+    "ograve",
+    "ocircumflex",
+    "odieresis",
+    "otilde",
+    "uacute",
+    "ugrave",
+    "ucircumflex",
+    "udieresis",
+    "dagger",
+    "degree",
+    "cent",
+    "sterling",
+    "section",
+    "bullet",
+    "paragraph",
+    "germandbls",
+    "registered",
+    "copyright",
+    "trademark",
+    "acute",
+    "dieresis",
+    "notequal",
+    "AE",
+    "Oslash",
+    "infinity",
+    "plusminus",
+    "lessequal",
+    "greaterequal",
+    "yen",
+    "mu",
+    "partialdiff",
+    "summation",
+    "product",
+    "pi",
+    "integral",
+    "ordfeminine",
+    "ordmasculine",
+    "Omega",
+    "ae",
+    "oslash",
+    "questiondown",
+    "exclamdown",
+    "logicalnot",
+    "radical",
+    "florin",
+    "approxequal",
+    "Delta",
+    "guillemotleft",
+    "guillemotright",
+    "ellipsis",
+    "nonbreakingspace",
+    "Agrave",
+    "Atilde",
+    "Otilde",
+    "OE",
+    "oe",
+    "endash",
+    "emdash",
+    "quotedblleft",
+    "quotedblright",
+    "quoteleft",
+    "quoteright",
+    "divide",
+    "lozenge",
+    "ydieresis",
+    "Ydieresis",
+    "fraction",
+    "currency",
+    "guilsinglleft",
+    "guilsinglright",
+    "fi",
+    "fl",
+    "daggerdbl",
+    "periodcentered",
+    "quotesinglbase",
+    "quotedblbase",
+    "perthousand",
+    "Acircumflex",
+    "Ecircumflex",
+    "Aacute",
+    "Edieresis",
+    "Egrave",
+    "Iacute",
+    "Icircumflex",
+    "Idieresis",
+    "Igrave",
+    "Oacute",
+    "Ocircumflex",
+    "apple",
+    "Ograve",
+    "Uacute",
+    "Ucircumflex",
+    "Ugrave",
+    "dotlessi",
+    "circumflex",
+    "tilde",
+    "macron",
+    "breve",
+    "dotaccent",
+    "ring",
+    "cedilla",
+    "hungarumlaut",
+    "ogonek",
+    "caron",
+    "Lslash",
+    "lslash",
+    "Scaron",
+    "scaron",
+    "Zcaron",
+    "zcaron",
+    "brokenbar",
+    "Eth",
+    "eth",
+    "Yacute",
+    "yacute",
+    "Thorn",
+    "thorn",
+    "minus",
+    "multiply",
+    "onesuperior",
+    "twosuperior",
+    "threesuperior",
+    "onehalf",
+    "onequarter",
+    "threequarters",
+    "franc",
+    "Gbreve",
+    "gbreve",
+    "Idotaccent",
+    "Scedilla",
+    "scedilla",
+    "Cacute",
+    "cacute",
+    "Ccaron",
+    "ccaron",
+    "dcroat",
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::format;
+    use alloc::vec;
+
+    fn words(values: &[u16]) -> Vec<u8> {
+        values.iter().flat_map(|v| v.to_be_bytes()).collect()
+    }
+
+    // An sfnt wrapping the given tables
+    fn sfnt(tables: &[(&[u8; 4], Vec<u8>)]) -> Vec<u8> {
+        let mut font = words(&[1, 0, tables.len() as u16, 0, 0, 0]);
+        let mut offset = 12 + tables.len() * 16;
+        for (tag, table) in tables {
+            font.extend_from_slice(*tag);
+            font.extend(words(&[0, 0, 0, offset as u16, 0, table.len() as u16]));
+            offset += table.len();
+        }
+        for (_, table) in tables {
+            font.extend_from_slice(table);
+        }
+        font
+    }
+
+    // Offset size 1
+    fn index(items: &[&[u8]]) -> Vec<u8> {
+        let mut out = words(&[items.len() as u16]);
+        out.push(1);
+        let mut offset = 1;
+        out.push(offset);
+        for item in items {
+            offset += item.len() as u8;
+            out.push(offset);
+        }
+        for item in items {
+            out.extend_from_slice(item);
+        }
+        out
+    }
+
+    #[test]
+    fn truetype_names_from_post() {
+        // (3, 0) format 4 subtable: U+F041..U+F043 to glyphs 1..3
+        let mut cmap = words(&[0, 1, 3, 0, 0, 12]);
+        cmap.extend(words(&[4, 32, 0, 4, 0, 0, 0]));
+        cmap.extend(words(&[0xF043, 0xFFFF, 0, 0xF041, 0xFFFF]));
+        cmap.extend(words(&[1u16.wrapping_sub(0xF041), 1, 0, 0]));
+        // Glyphs .notdef, A, a custom name and space
+        let mut post = words(&[2, 0]);
+        post.extend([0; 28]);
+        post.extend(words(&[4, 0, 36, 258, 3]));
+        post.extend(b"\x06custom");
+
+        let font = sfnt(&[(b"cmap", cmap), (b"post", post)]);
+        let names = truetype_code_names(&font);
+        assert_eq!(names.len(), 3);
+        assert_eq!(names[&0x41], "A");
+        assert_eq!(names[&0x42], "custom");
+        assert_eq!(names[&0x43], "space");
+
+        // No Unicode subtable: the names that are known glyphs stand in
+        let glyphs = truetype_glyph_unicode(&font);
+        assert_eq!(glyphs.get(&1), Some(&'A'));
+        assert_eq!(glyphs.get(&2), None);
+        assert_eq!(glyphs.get(&3), Some(&' '));
+    }
+
+    #[test]
+    fn type1_names_from_encoding_array() {
+        let font = b"%!PS-AdobeFont-1.0: Test 001.000\n/FontName /Test def\n\
+            /Encoding 256 array\n0 1 255 {1 index exch /.notdef put} for\n\
+            dup 128 /A put\ndup 129 /space put\nreadonly def\ncurrentfile eexec\n\x9f\x31dup 1 /B put";
+        let names = type1_code_names(font);
+        assert_eq!(names.len(), 2);
+        assert_eq!(names[&128], "A");
+        assert_eq!(names[&129], "space");
+
+        assert!(type1_code_names(b"/Encoding StandardEncoding def\ndup 1 /B put").is_empty());
+    }
+
+    #[test]
+    fn cff_names_from_encoding_and_charset() {
+        let offset = |op: u8, at: usize| {
+            let mut operand = vec![29];
+            operand.extend((at as u32).to_be_bytes());
+            operand.push(op);
+            operand
+        };
+        // Header, Name, Top DICT, String and Global Subr INDEXes, then the CharStrings
+        let strings = index(&[b"custom"]);
+        let charstrings_at =
+            4 + index(&[b"F"]).len() + index(&[&[0; 18]]).len() + strings.len() + 2;
+        let charstrings = index(&[b"\x0e", b"\x0e", b"\x0e"]);
+        let charset_at = charstrings_at + charstrings.len();
+        // Glyph 1 is A, glyph 2 the custom string (SID 391)
+        let charset = [0, 0, 34, 0x01, 0x87];
+        let encoding_at = charset_at + charset.len();
+        // Codes 0x61 and 0x62, and a supplement mapping 0x63 to space
+        let encoding = [0x80, 2, 0x61, 0x62, 1, 0x63, 0, 1];
+
+        let mut top = offset(15, charset_at);
+        top.extend(offset(16, encoding_at));
+        top.extend(offset(17, charstrings_at));
+        let mut cff = vec![1, 0, 4, 1];
+        cff.extend(index(&[b"F"]));
+        cff.extend(index(&[&top]));
+        cff.extend(strings);
+        cff.extend(words(&[0]));
+        cff.extend(charstrings);
+        cff.extend(charset);
+        cff.extend(encoding);
+
+        let names = cff_code_names(&cff);
+        assert_eq!(names.len(), 3);
+        assert_eq!(names[&0x61], "A");
+        assert_eq!(names[&0x62], "custom");
+        assert_eq!(names[&0x63], "space");
+
+        // The Standard encoding is left to the caller
+        let mut standard = cff.clone();
+        let at = standard.windows(6).position(|w| w == &top[5..11]).unwrap();
+        standard[at + 1..at + 5].copy_from_slice(&[0; 4]);
+        assert!(cff_code_names(&standard).is_empty());
+    }
+
+    #[test]
+    fn builtin_encoding_decodes_text() {
+        let program = b"/Encoding 256 array\ndup 128 /A put\ndup 129 /space put\nreadonly def\n";
+        let content = "BT /F1 12 Tf 72 720 Td <808180> Tj ET";
+        let bodies = [
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            "<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_string(),
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 612 792] \
+             /Resources << /Font << /F1 4 0 R >> >> /Contents 6 0 R >>"
+                .to_string(),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Test /FontDescriptor 5 0 R >>".to_string(),
+            "<< /Type /FontDescriptor /FontName /Test /Flags 4 /FontFile 7 0 R >>".to_string(),
+            format!(
+                "<< /Length {} >>\nstream\n{}\nendstream",
+                content.len(),
+                content
+            ),
+            format!(
+                "<< /Length {} /Length1 {0} /Length2 0 /Length3 0 >>\nstream\n{}\nendstream",
+                program.len(),
+                core::str::from_utf8(program).unwrap()
+            ),
+        ];
+        let mut pdf = b"%PDF-1.7\n".to_vec();
+        for (i, body) in bodies.iter().enumerate() {
+            pdf.extend(format!("{} 0 obj\n{}\nendobj\n", i + 1, body).bytes());
+        }
+        pdf.extend(b"trailer\n<< /Size 8 /Root 1 0 R >>\n%%EOF\n");
+
+        assert_eq!(crate::extract_text(pdf).unwrap(), ["A A"]);
+    }
+}
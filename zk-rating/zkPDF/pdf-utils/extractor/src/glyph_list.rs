@@ -0,0 +1,945 @@
+//! Glyph names from the Adobe Glyph List, as far as `data/glyphlist.txt` carries it (see
+//! the header of that file): Latin, Greek, Cyrillic, Hebrew and Arabic letters,
+//! punctuation and the symbols of the standard PDF fonts. Names outside it are still read
+//! when they spell their code points (`uni0915`, `u1F600`); see
+//! [`crate::encoding::glyph_to_unicode`].
+//!
+//! Generated from `data/glyphlist.txt` by `examples/gen_glyph_list.rs`; edit the list and
+//! regenerate rather than editing this file.
+
+/// `(name, text)`, sorted by name for binary search.
+pub(crate) static GLYPH_LIST: [(&str, &str); 933] = [
+    ("A", "\u{0041}"),
+    ("AE", "\u{00C6}"),
+    ("AEacute", "\u{01FC}"),
+    ("Aacute", "\u{00C1}"),
+    ("Abreve", "\u{0102}"),
+    ("Acircumflex", "\u{00C2}"),
+    ("Adieresis", "\u{00C4}"),
+    ("Agrave", "\u{00C0}"),
+    ("Alpha", "\u{0391}"),
+    ("Alphatonos", "\u{0386}"),
+    ("Amacron", "\u{0100}"),
+    ("Aogonek", "\u{0104}"),
+    ("Aring", "\u{00C5}"),
+    ("Aringacute", "\u{01FA}"),
+    ("Atilde", "\u{00C3}"),
+    ("B", "\u{0042}"),
+    ("Beta", "\u{0392}"),
+    ("C", "\u{0043}"),
+    ("Cacute", "\u{0106}"),
+    ("Ccaron", "\u{010C}"),
+    ("Ccedilla", "\u{00C7}"),
+    ("Ccircumflex", "\u{0108}"),
+    ("Cdotaccent", "\u{010A}"),
+    ("Chi", "\u{03A7}"),
+    ("D", "\u{0044}"),
+    ("Dcaron", "\u{010E}"),
+    ("Dcroat", "\u{0110}"),
+    ("Delta", "\u{2206}"),
+    ("E", "\u{0045}"),
+    ("Eacute", "\u{00C9}"),
+    ("Ebreve", "\u{0114}"),
+    ("Ecaron", "\u{011A}"),
+    ("Ecircumflex", "\u{00CA}"),
+    ("Edieresis", "\u{00CB}"),
+    ("Edot", "\u{0116}"),
+    ("Edotaccent", "\u{0116}"),
+    ("Egrave", "\u{00C8}"),
+    ("Emacron", "\u{0112}"),
+    ("Eng", "\u{014A}"),
+    ("Eogonek", "\u{0118}"),
+    ("Epsilon", "\u{0395}"),
+    ("Epsilontonos", "\u{0388}"),
+    ("Eta", "\u{0397}"),
+    ("Etatonos", "\u{0389}"),
+    ("Eth", "\u{00D0}"),
+    ("Euro", "\u{20AC}"),
+    ("F", "\u{0046}"),
+    ("G", "\u{0047}"),
+    ("Gamma", "\u{0393}"),
+    ("Gbreve", "\u{011E}"),
+    ("Gcaron", "\u{01E6}"),
+    ("Gcedilla", "\u{0122}"),
+    ("Gcircumflex", "\u{011C}"),
+    ("Gcommaaccent", "\u{0122}"),
+    ("Gdotaccent", "\u{0120}"),
+    ("H", "\u{0048}"),
+    ("H18533", "\u{25CF}"),
+    ("H18543", "\u{25AA}"),
+    ("H18551", "\u{25AB}"),
+    ("H22073", "\u{25A1}"),
+    ("Hbar", "\u{0126}"),
+    ("Hcircumflex", "\u{0124}"),
+    ("I", "\u{0049}"),
+    ("IJ", "\u{0132}"),
+    ("Iacute", "\u{00CD}"),
+    ("Ibreve", "\u{012C}"),
+    ("Icircumflex", "\u{00CE}"),
+    ("Idieresis", "\u{00CF}"),
+    ("Idotaccent", "\u{0130}"),
+    ("Ifraktur", "\u{2111}"),
+    ("Igrave", "\u{00CC}"),
+    ("Imacron", "\u{012A}"),
+    ("Iogonek", "\u{012E}"),
+    ("Iota", "\u{0399}"),
+    ("Iotadieresis", "\u{03AA}"),
+    ("Iotatonos", "\u{038A}"),
+    ("Itilde", "\u{0128}"),
+    ("J", "\u{004A}"),
+    ("Jcircumflex", "\u{0134}"),
+    ("K", "\u{004B}"),
+    ("Kappa", "\u{039A}"),
+    ("Kcedilla", "\u{0136}"),
+    ("Kcommaaccent", "\u{0136}"),
+    ("L", "\u{004C}"),
+    ("Lacute", "\u{0139}"),
+    ("Lambda", "\u{039B}"),
+    ("Lcaron", "\u{013D}"),
+    ("Lcedilla", "\u{013B}"),
+    ("Lcommaaccent", "\u{013B}"),
+    ("Ldot", "\u{013F}"),
+    ("Lslash", "\u{0141}"),
+    ("M", "\u{004D}"),
+    ("Mu", "\u{039C}"),
+    ("N", "\u{004E}"),
+    ("Nacute", "\u{0143}"),
+    ("Ncaron", "\u{0147}"),
+    ("Ncedilla", "\u{0145}"),
+    ("Ncommaaccent", "\u{0145}"),
+    ("Ntilde", "\u{00D1}"),
+    ("Nu", "\u{039D}"),
+    ("O", "\u{004F}"),
+    ("OE", "\u{0152}"),
+    ("Oacute", "\u{00D3}"),
+    ("Obreve", "\u{014E}"),
+    ("Ocircumflex", "\u{00D4}"),
+    ("Odieresis", "\u{00D6}"),
+    ("Ograve", "\u{00D2}"),
+    ("Ohorn", "\u{01A0}"),
+    ("Ohungarumlaut", "\u{0150}"),
+    ("Omacron", "\u{014C}"),
+    ("Omega", "\u{2126}"),
+    ("Omegatonos", "\u{038F}"),
+    ("Omicron", "\u{039F}"),
+    ("Omicrontonos", "\u{038C}"),
+    ("Oslash", "\u{00D8}"),
+    ("Oslashacute", "\u{01FE}"),
+    ("Otilde", "\u{00D5}"),
+    ("P", "\u{0050}"),
+    ("Phi", "\u{03A6}"),
+    ("Pi", "\u{03A0}"),
+    ("Psi", "\u{03A8}"),
+    ("Q", "\u{0051}"),
+    ("R", "\u{0052}"),
+    ("Racute", "\u{0154}"),
+    ("Rcaron", "\u{0158}"),
+    ("Rcedilla", "\u{0156}"),
+    ("Rcommaaccent", "\u{0156}"),
+    ("Rfraktur", "\u{211C}"),
+    ("Rho", "\u{03A1}"),
+    ("S", "\u{0053}"),
+    ("SF010000", "\u{250C}"),
+    ("SF020000", "\u{2514}"),
+    ("SF030000", "\u{2510}"),
+    ("SF040000", "\u{2518}"),
+    ("SF050000", "\u{253C}"),
+    ("SF060000", "\u{252C}"),
+    ("SF070000", "\u{2534}"),
+    ("SF080000", "\u{251C}"),
+    ("SF090000", "\u{2524}"),
+    ("SF100000", "\u{2500}"),
+    ("SF110000", "\u{2502}"),
+    ("SF190000", "\u{2561}"),
+    ("SF200000", "\u{2562}"),
+    ("SF210000", "\u{2556}"),
+    ("SF220000", "\u{2555}"),
+    ("SF230000", "\u{2563}"),
+    ("SF240000", "\u{2551}"),
+    ("SF250000", "\u{2557}"),
+    ("SF260000", "\u{255D}"),
+    ("SF270000", "\u{255C}"),
+    ("SF280000", "\u{255B}"),
+    ("SF360000", "\u{255E}"),
+    ("SF370000", "\u{255F}"),
+    ("SF380000", "\u{255A}"),
+    ("SF390000", "\u{2554}"),
+    ("SF400000", "\u{2569}"),
+    ("SF410000", "\u{2566}"),
+    ("SF420000", "\u{2560}"),
+    ("SF430000", "\u{2550}"),
+    ("SF440000", "\u{256C}"),
+    ("SF450000", "\u{2567}"),
+    ("SF460000", "\u{2568}"),
+    ("SF470000", "\u{2564}"),
+    ("SF480000", "\u{2565}"),
+    ("SF490000", "\u{2559}"),
+    ("SF500000", "\u{2558}"),
+    ("SF510000", "\u{2552}"),
+    ("SF520000", "\u{2553}"),
+    ("SF530000", "\u{256B}"),
+    ("SF540000", "\u{256A}"),
+    ("Sacute", "\u{015A}"),
+    ("Scaron", "\u{0160}"),
+    ("Scedilla", "\u{015E}"),
+    ("Scircumflex", "\u{015C}"),
+    ("Scommaaccent", "\u{0218}"),
+    ("Sigma", "\u{03A3}"),
+    ("T", "\u{0054}"),
+    ("Tau", "\u{03A4}"),
+    ("Tbar", "\u{0166}"),
+    ("Tcaron", "\u{0164}"),
+    ("Tcedilla", "\u{0162}"),
+    ("Tcommaaccent", "\u{0162}"),
+    ("Theta", "\u{0398}"),
+    ("Thorn", "\u{00DE}"),
+    ("U", "\u{0055}"),
+    ("Uacute", "\u{00DA}"),
+    ("Ubreve", "\u{016C}"),
+    ("Ucircumflex", "\u{00DB}"),
+    ("Udieresis", "\u{00DC}"),
+    ("Ugrave", "\u{00D9}"),
+    ("Uhorn", "\u{01AF}"),
+    ("Uhungarumlaut", "\u{0170}"),
+    ("Umacron", "\u{016A}"),
+    ("Uogonek", "\u{0172}"),
+    ("Upsilon", "\u{03A5}"),
+    ("Upsilon1", "\u{03D2}"),
+    ("Upsilondieresis", "\u{03AB}"),
+    ("Upsilontonos", "\u{038E}"),
+    ("Uring", "\u{016E}"),
+    ("Utilde", "\u{0168}"),
+    ("V", "\u{0056}"),
+    ("W", "\u{0057}"),
+    ("Wacute", "\u{1E82}"),
+    ("Wcircumflex", "\u{0174}"),
+    ("Wdieresis", "\u{1E84}"),
+    ("Wgrave", "\u{1E80}"),
+    ("X", "\u{0058}"),
+    ("Xi", "\u{039E}"),
+    ("Y", "\u{0059}"),
+    ("Yacute", "\u{00DD}"),
+    ("Ycircumflex", "\u{0176}"),
+    ("Ydieresis", "\u{0178}"),
+    ("Ygrave", "\u{1EF2}"),
+    ("Z", "\u{005A}"),
+    ("Zacute", "\u{0179}"),
+    ("Zcaron", "\u{017D}"),
+    ("Zdotaccent", "\u{017B}"),
+    ("Zeta", "\u{0396}"),
+    ("a", "\u{0061}"),
+    ("aacute", "\u{00E1}"),
+    ("abreve", "\u{0103}"),
+    ("acircumflex", "\u{00E2}"),
+    ("acute", "\u{00B4}"),
+    ("acutecomb", "\u{0301}"),
+    ("adieresis", "\u{00E4}"),
+    ("ae", "\u{00E6}"),
+    ("aeacute", "\u{01FD}"),
+    ("afii00208", "\u{2015}"),
+    ("afii10017", "\u{0410}"),
+    ("afii10018", "\u{0411}"),
+    ("afii10019", "\u{0412}"),
+    ("afii10020", "\u{0413}"),
+    ("afii10021", "\u{0414}"),
+    ("afii10022", "\u{0415}"),
+    ("afii10023", "\u{0401}"),
+    ("afii10024", "\u{0416}"),
+    ("afii10025", "\u{0417}"),
+    ("afii10026", "\u{0418}"),
+    ("afii10027", "\u{0419}"),
+    ("afii10028", "\u{041A}"),
+    ("afii10029", "\u{041B}"),
+    ("afii10030", "\u{041C}"),
+    ("afii10031", "\u{041D}"),
+    ("afii10032", "\u{041E}"),
+    ("afii10033", "\u{041F}"),
+    ("afii10034", "\u{0420}"),
+    ("afii10035", "\u{0421}"),
+    ("afii10036", "\u{0422}"),
+    ("afii10037", "\u{0423}"),
+    ("afii10038", "\u{0424}"),
+    ("afii10039", "\u{0425}"),
+    ("afii10040", "\u{0426}"),
+    ("afii10041", "\u{0427}"),
+    ("afii10042", "\u{0428}"),
+    ("afii10043", "\u{0429}"),
+    ("afii10044", "\u{042A}"),
+    ("afii10045", "\u{042B}"),
+    ("afii10046", "\u{042C}"),
+    ("afii10047", "\u{042D}"),
+    ("afii10048", "\u{042E}"),
+    ("afii10049", "\u{042F}"),
+    ("afii10050", "\u{0490}"),
+    ("afii10051", "\u{0402}"),
+    ("afii10052", "\u{0403}"),
+    ("afii10053", "\u{0404}"),
+    ("afii10054", "\u{0405}"),
+    ("afii10055", "\u{0406}"),
+    ("afii10056", "\u{0407}"),
+    ("afii10057", "\u{0408}"),
+    ("afii10058", "\u{0409}"),
+    ("afii10059", "\u{040A}"),
+    ("afii10060", "\u{040B}"),
+    ("afii10061", "\u{040C}"),
+    ("afii10062", "\u{040E}"),
+    ("afii10065", "\u{0430}"),
+    ("afii10066", "\u{0431}"),
+    ("afii10067", "\u{0432}"),
+    ("afii10068", "\u{0433}"),
+    ("afii10069", "\u{0434}"),
+    ("afii10070", "\u{0435}"),
+    ("afii10071", "\u{0451}"),
+    ("afii10072", "\u{0436}"),
+    ("afii10073", "\u{0437}"),
+    ("afii10074", "\u{0438}"),
+    ("afii10075", "\u{0439}"),
+    ("afii10076", "\u{043A}"),
+    ("afii10077", "\u{043B}"),
+    ("afii10078", "\u{043C}"),
+    ("afii10079", "\u{043D}"),
+    ("afii10080", "\u{043E}"),
+    ("afii10081", "\u{043F}"),
+    ("afii10082", "\u{0440}"),
+    ("afii10083", "\u{0441}"),
+    ("afii10084", "\u{0442}"),
+    ("afii10085", "\u{0443}"),
+    ("afii10086", "\u{0444}"),
+    ("afii10087", "\u{0445}"),
+    ("afii10088", "\u{0446}"),
+    ("afii10089", "\u{0447}"),
+    ("afii10090", "\u{0448}"),
+    ("afii10091", "\u{0449}"),
+    ("afii10092", "\u{044A}"),
+    ("afii10093", "\u{044B}"),
+    ("afii10094", "\u{044C}"),
+    ("afii10095", "\u{044D}"),
+    ("afii10096", "\u{044E}"),
+    ("afii10097", "\u{044F}"),
+    ("afii10098", "\u{0491}"),
+    ("afii10099", "\u{0452}"),
+    ("afii10100", "\u{0453}"),
+    ("afii10101", "\u{0454}"),
+    ("afii10102", "\u{0455}"),
+    ("afii10103", "\u{0456}"),
+    ("afii10104", "\u{0457}"),
+    ("afii10105", "\u{0458}"),
+    ("afii10106", "\u{0459}"),
+    ("afii10107", "\u{045A}"),
+    ("afii10108", "\u{045B}"),
+    ("afii10109", "\u{045C}"),
+    ("afii10110", "\u{045E}"),
+    ("afii10145", "\u{040F}"),
+    ("afii10146", "\u{0462}"),
+    ("afii10147", "\u{0472}"),
+    ("afii10148", "\u{0474}"),
+    ("afii10193", "\u{045F}"),
+    ("afii10194", "\u{0463}"),
+    ("afii10195", "\u{0473}"),
+    ("afii10196", "\u{0475}"),
+    ("afii10846", "\u{04D9}"),
+    ("afii299", "\u{200E}"),
+    ("afii300", "\u{200F}"),
+    ("afii301", "\u{200D}"),
+    ("afii57381", "\u{066A}"),
+    ("afii57388", "\u{060C}"),
+    ("afii57392", "\u{0660}"),
+    ("afii57393", "\u{0661}"),
+    ("afii57394", "\u{0662}"),
+    ("afii57395", "\u{0663}"),
+    ("afii57396", "\u{0664}"),
+    ("afii57397", "\u{0665}"),
+    ("afii57398", "\u{0666}"),
+    ("afii57399", "\u{0667}"),
+    ("afii57400", "\u{0668}"),
+    ("afii57401", "\u{0669}"),
+    ("afii57403", "\u{061B}"),
+    ("afii57407", "\u{061F}"),
+    ("afii57409", "\u{0621}"),
+    ("afii57410", "\u{0622}"),
+    ("afii57411", "\u{0623}"),
+    ("afii57412", "\u{0624}"),
+    ("afii57413", "\u{0625}"),
+    ("afii57414", "\u{0626}"),
+    ("afii57415", "\u{0627}"),
+    ("afii57416", "\u{0628}"),
+    ("afii57417", "\u{0629}"),
+    ("afii57418", "\u{062A}"),
+    ("afii57419", "\u{062B}"),
+    ("afii57420", "\u{062C}"),
+    ("afii57421", "\u{062D}"),
+    ("afii57422", "\u{062E}"),
+    ("afii57423", "\u{062F}"),
+    ("afii57424", "\u{0630}"),
+    ("afii57425", "\u{0631}"),
+    ("afii57426", "\u{0632}"),
+    ("afii57427", "\u{0633}"),
+    ("afii57428", "\u{0634}"),
+    ("afii57429", "\u{0635}"),
+    ("afii57430", "\u{0636}"),
+    ("afii57431", "\u{0637}"),
+    ("afii57432", "\u{0638}"),
+    ("afii57433", "\u{0639}"),
+    ("afii57434", "\u{063A}"),
+    ("afii57440", "\u{0640}"),
+    ("afii57441", "\u{0641}"),
+    ("afii57442", "\u{0642}"),
+    ("afii57443", "\u{0643}"),
+    ("afii57444", "\u{0644}"),
+    ("afii57445", "\u{0645}"),
+    ("afii57446", "\u{0646}"),
+    ("afii57448", "\u{0648}"),
+    ("afii57449", "\u{0649}"),
+    ("afii57450", "\u{064A}"),
+    ("afii57451", "\u{064B}"),
+    ("afii57452", "\u{064C}"),
+    ("afii57453", "\u{064D}"),
+    ("afii57454", "\u{064E}"),
+    ("afii57455", "\u{064F}"),
+    ("afii57456", "\u{0650}"),
+    ("afii57457", "\u{0651}"),
+    ("afii57458", "\u{0652}"),
+    ("afii57470", "\u{0647}"),
+    ("afii57505", "\u{06A4}"),
+    ("afii57506", "\u{067E}"),
+    ("afii57507", "\u{0686}"),
+    ("afii57508", "\u{0698}"),
+    ("afii57509", "\u{06AF}"),
+    ("afii57511", "\u{0679}"),
+    ("afii57512", "\u{0688}"),
+    ("afii57513", "\u{0691}"),
+    ("afii57514", "\u{06BA}"),
+    ("afii57519", "\u{06D2}"),
+    ("afii57534", "\u{06D5}"),
+    ("afii57636", "\u{20AA}"),
+    ("afii57645", "\u{05BE}"),
+    ("afii57658", "\u{05C3}"),
+    ("afii57664", "\u{05D0}"),
+    ("afii57665", "\u{05D1}"),
+    ("afii57666", "\u{05D2}"),
+    ("afii57667", "\u{05D3}"),
+    ("afii57668", "\u{05D4}"),
+    ("afii57669", "\u{05D5}"),
+    ("afii57670", "\u{05D6}"),
+    ("afii57671", "\u{05D7}"),
+    ("afii57672", "\u{05D8}"),
+    ("afii57673", "\u{05D9}"),
+    ("afii57674", "\u{05DA}"),
+    ("afii57675", "\u{05DB}"),
+    ("afii57676", "\u{05DC}"),
+    ("afii57677", "\u{05DD}"),
+    ("afii57678", "\u{05DE}"),
+    ("afii57679", "\u{05DF}"),
+    ("afii57680", "\u{05E0}"),
+    ("afii57681", "\u{05E1}"),
+    ("afii57682", "\u{05E2}"),
+    ("afii57683", "\u{05E3}"),
+    ("afii57684", "\u{05E4}"),
+    ("afii57685", "\u{05E5}"),
+    ("afii57686", "\u{05E6}"),
+    ("afii57687", "\u{05E7}"),
+    ("afii57688", "\u{05E8}"),
+    ("afii57689", "\u{05E9}"),
+    ("afii57690", "\u{05EA}"),
+    ("afii57694", "\u{FB2A}"),
+    ("afii57695", "\u{FB2B}"),
+    ("afii57700", "\u{FB4B}"),
+    ("afii57705", "\u{FB1F}"),
+    ("afii57716", "\u{05F0}"),
+    ("afii57717", "\u{05F1}"),
+    ("afii57718", "\u{05F2}"),
+    ("afii57723", "\u{FB35}"),
+    ("afii57793", "\u{05B4}"),
+    ("afii57794", "\u{05B5}"),
+    ("afii57795", "\u{05B6}"),
+    ("afii57796", "\u{05BB}"),
+    ("afii57797", "\u{05B8}"),
+    ("afii57798", "\u{05B7}"),
+    ("afii57799", "\u{05B0}"),
+    ("afii57800", "\u{05B2}"),
+    ("afii57801", "\u{05B1}"),
+    ("afii57802", "\u{05B3}"),
+    ("afii57803", "\u{05C2}"),
+    ("afii57804", "\u{05C1}"),
+    ("afii57806", "\u{05B9}"),
+    ("afii57807", "\u{05BC}"),
+    ("afii57839", "\u{05BD}"),
+    ("afii57841", "\u{05BF}"),
+    ("afii57842", "\u{05C0}"),
+    ("afii57929", "\u{02BC}"),
+    ("afii61248", "\u{2105}"),
+    ("afii61289", "\u{2113}"),
+    ("afii61352", "\u{2116}"),
+    ("afii61573", "\u{202C}"),
+    ("afii61574", "\u{202D}"),
+    ("afii61575", "\u{202E}"),
+    ("afii61664", "\u{200C}"),
+    ("afii63167", "\u{066D}"),
+    ("afii64937", "\u{02BD}"),
+    ("agrave", "\u{00E0}"),
+    ("alef", "\u{05D0}"),
+    ("aleph", "\u{2135}"),
+    ("alpha", "\u{03B1}"),
+    ("alphatonos", "\u{03AC}"),
+    ("amacron", "\u{0101}"),
+    ("ampersand", "\u{0026}"),
+    ("angle", "\u{2220}"),
+    ("angleleft", "\u{2329}"),
+    ("angleright", "\u{232A}"),
+    ("anoteleia", "\u{0387}"),
+    ("aogonek", "\u{0105}"),
+    ("approxequal", "\u{2248}"),
+    ("aring", "\u{00E5}"),
+    ("aringacute", "\u{01FB}"),
+    ("arrowboth", "\u{2194}"),
+    ("arrowdblboth", "\u{21D4}"),
+    ("arrowdbldown", "\u{21D3}"),
+    ("arrowdblleft", "\u{21D0}"),
+    ("arrowdblright", "\u{21D2}"),
+    ("arrowdblup", "\u{21D1}"),
+    ("arrowdown", "\u{2193}"),
+    ("arrowleft", "\u{2190}"),
+    ("arrowright", "\u{2192}"),
+    ("arrowup", "\u{2191}"),
+    ("arrowupdn", "\u{2195}"),
+    ("arrowupdnbse", "\u{21A8}"),
+    ("asciicircum", "\u{005E}"),
+    ("asciitilde", "\u{007E}"),
+    ("asterisk", "\u{002A}"),
+    ("asteriskmath", "\u{2217}"),
+    ("at", "\u{0040}"),
+    ("atilde", "\u{00E3}"),
+    ("ayin", "\u{05E2}"),
+    ("b", "\u{0062}"),
+    ("backslash", "\u{005C}"),
+    ("bar", "\u{007C}"),
+    ("bet", "\u{05D1}"),
+    ("beta", "\u{03B2}"),
+    ("block", "\u{2588}"),
+    ("braceleft", "\u{007B}"),
+    ("braceright", "\u{007D}"),
+    ("bracketleft", "\u{005B}"),
+    ("bracketright", "\u{005D}"),
+    ("breve", "\u{02D8}"),
+    ("brokenbar", "\u{00A6}"),
+    ("bullet", "\u{2022}"),
+    ("c", "\u{0063}"),
+    ("cacute", "\u{0107}"),
+    ("caron", "\u{02C7}"),
+    ("carriagereturn", "\u{21B5}"),
+    ("ccaron", "\u{010D}"),
+    ("ccedilla", "\u{00E7}"),
+    ("ccircumflex", "\u{0109}"),
+    ("cdotaccent", "\u{010B}"),
+    ("cedilla", "\u{00B8}"),
+    ("cent", "\u{00A2}"),
+    ("chi", "\u{03C7}"),
+    ("circle", "\u{25CB}"),
+    ("circlemultiply", "\u{2297}"),
+    ("circleplus", "\u{2295}"),
+    ("circumflex", "\u{02C6}"),
+    ("club", "\u{2663}"),
+    ("colon", "\u{003A}"),
+    ("colonmonetary", "\u{20A1}"),
+    ("comma", "\u{002C}"),
+    ("congruent", "\u{2245}"),
+    ("copyright", "\u{00A9}"),
+    ("currency", "\u{00A4}"),
+    ("d", "\u{0064}"),
+    ("dagesh", "\u{05BC}"),
+    ("dagger", "\u{2020}"),
+    ("daggerdbl", "\u{2021}"),
+    ("dalet", "\u{05D3}"),
+    ("dcaron", "\u{010F}"),
+    ("dcroat", "\u{0111}"),
+    ("degree", "\u{00B0}"),
+    ("delta", "\u{03B4}"),
+    ("diamond", "\u{2666}"),
+    ("dieresis", "\u{00A8}"),
+    ("dieresistonos", "\u{0385}"),
+    ("divide", "\u{00F7}"),
+    ("dkshade", "\u{2593}"),
+    ("dnblock", "\u{2584}"),
+    ("dollar", "\u{0024}"),
+    ("dong", "\u{20AB}"),
+    ("dotaccent", "\u{02D9}"),
+    ("dotbelowcomb", "\u{0323}"),
+    ("dotlessi", "\u{0131}"),
+    ("dotmath", "\u{22C5}"),
+    ("doublevav", "\u{05F0}"),
+    ("doubleyod", "\u{05F2}"),
+    ("e", "\u{0065}"),
+    ("eacute", "\u{00E9}"),
+    ("ebreve", "\u{0115}"),
+    ("ecaron", "\u{011B}"),
+    ("ecircumflex", "\u{00EA}"),
+    ("edieresis", "\u{00EB}"),
+    ("edot", "\u{0117}"),
+    ("edotaccent", "\u{0117}"),
+    ("egrave", "\u{00E8}"),
+    ("eight", "\u{0038}"),
+    ("eightinferior", "\u{2088}"),
+    ("eightsuperior", "\u{2078}"),
+    ("element", "\u{2208}"),
+    ("ellipsis", "\u{2026}"),
+    ("emacron", "\u{0113}"),
+    ("emdash", "\u{2014}"),
+    ("emptyset", "\u{2205}"),
+    ("endash", "\u{2013}"),
+    ("eng", "\u{014B}"),
+    ("eogonek", "\u{0119}"),
+    ("epsilon", "\u{03B5}"),
+    ("epsilontonos", "\u{03AD}"),
+    ("equal", "\u{003D}"),
+    ("equivalence", "\u{2261}"),
+    ("estimated", "\u{212E}"),
+    ("eta", "\u{03B7}"),
+    ("etatonos", "\u{03AE}"),
+    ("eth", "\u{00F0}"),
+    ("exclam", "\u{0021}"),
+    ("exclamdbl", "\u{203C}"),
+    ("exclamdown", "\u{00A1}"),
+    ("existential", "\u{2203}"),
+    ("f", "\u{0066}"),
+    ("female", "\u{2640}"),
+    ("ff", "\u{FB00}"),
+    ("ffi", "\u{FB03}"),
+    ("ffl", "\u{FB04}"),
+    ("fi", "\u{FB01}"),
+    ("figuredash", "\u{2012}"),
+    ("filledbox", "\u{25A0}"),
+    ("filledrect", "\u{25AC}"),
+    ("finalkaf", "\u{05DA}"),
+    ("finalmem", "\u{05DD}"),
+    ("finalnun", "\u{05DF}"),
+    ("finalpe", "\u{05E3}"),
+    ("finaltsadi", "\u{05E5}"),
+    ("five", "\u{0035}"),
+    ("fiveeighths", "\u{215D}"),
+    ("fiveinferior", "\u{2085}"),
+    ("fivesuperior", "\u{2075}"),
+    ("fl", "\u{FB02}"),
+    ("florin", "\u{0192}"),
+    ("four", "\u{0034}"),
+    ("fourinferior", "\u{2084}"),
+    ("foursuperior", "\u{2074}"),
+    ("fraction", "\u{2044}"),
+    ("franc", "\u{20A3}"),
+    ("g", "\u{0067}"),
+    ("gamma", "\u{03B3}"),
+    ("gbreve", "\u{011F}"),
+    ("gcaron", "\u{01E7}"),
+    ("gcedilla", "\u{0123}"),
+    ("gcircumflex", "\u{011D}"),
+    ("gcommaaccent", "\u{0123}"),
+    ("gdotaccent", "\u{0121}"),
+    ("germandbls", "\u{00DF}"),
+    ("gimel", "\u{05D2}"),
+    ("gradient", "\u{2207}"),
+    ("grave", "\u{0060}"),
+    ("gravecomb", "\u{0300}"),
+    ("greater", "\u{003E}"),
+    ("greaterequal", "\u{2265}"),
+    ("guillemotleft", "\u{00AB}"),
+    ("guillemotright", "\u{00BB}"),
+    ("guilsinglleft", "\u{2039}"),
+    ("guilsinglright", "\u{203A}"),
+    ("h", "\u{0068}"),
+    ("hatafpatah", "\u{05B2}"),
+    ("hatafqamats", "\u{05B3}"),
+    ("hatafsegol", "\u{05B1}"),
+    ("hbar", "\u{0127}"),
+    ("hcircumflex", "\u{0125}"),
+    ("he", "\u{05D4}"),
+    ("heart", "\u{2665}"),
+    ("het", "\u{05D7}"),
+    ("hiriq", "\u{05B4}"),
+    ("holam", "\u{05B9}"),
+    ("hookabovecomb", "\u{0309}"),
+    ("house", "\u{2302}"),
+    ("hungarumlaut", "\u{02DD}"),
+    ("hyphen", "\u{002D}"),
+    ("i", "\u{0069}"),
+    ("iacute", "\u{00ED}"),
+    ("ibreve", "\u{012D}"),
+    ("icircumflex", "\u{00EE}"),
+    ("idieresis", "\u{00EF}"),
+    ("igrave", "\u{00EC}"),
+    ("ij", "\u{0133}"),
+    ("imacron", "\u{012B}"),
+    ("infinity", "\u{221E}"),
+    ("integral", "\u{222B}"),
+    ("integralbt", "\u{2321}"),
+    ("integraltp", "\u{2320}"),
+    ("intersection", "\u{2229}"),
+    ("invbullet", "\u{25D8}"),
+    ("invcircle", "\u{25D9}"),
+    ("invsmileface", "\u{263B}"),
+    ("iogonek", "\u{012F}"),
+    ("iota", "\u{03B9}"),
+    ("iotadieresis", "\u{03CA}"),
+    ("iotadieresistonos", "\u{0390}"),
+    ("iotatonos", "\u{03AF}"),
+    ("itilde", "\u{0129}"),
+    ("j", "\u{006A}"),
+    ("jcircumflex", "\u{0135}"),
+    ("k", "\u{006B}"),
+    ("kaf", "\u{05DB}"),
+    ("kappa", "\u{03BA}"),
+    ("kcedilla", "\u{0137}"),
+    ("kcommaaccent", "\u{0137}"),
+    ("kgreenlandic", "\u{0138}"),
+    ("l", "\u{006C}"),
+    ("lacute", "\u{013A}"),
+    ("lambda", "\u{03BB}"),
+    ("lamed", "\u{05DC}"),
+    ("lcaron", "\u{013E}"),
+    ("lcedilla", "\u{013C}"),
+    ("lcommaaccent", "\u{013C}"),
+    ("ldot", "\u{0140}"),
+    ("less", "\u{003C}"),
+    ("lessequal", "\u{2264}"),
+    ("lfblock", "\u{258C}"),
+    ("lira", "\u{20A4}"),
+    ("logicaland", "\u{2227}"),
+    ("logicalnot", "\u{00AC}"),
+    ("logicalor", "\u{2228}"),
+    ("longs", "\u{017F}"),
+    ("lozenge", "\u{25CA}"),
+    ("lslash", "\u{0142}"),
+    ("ltshade", "\u{2591}"),
+    ("m", "\u{006D}"),
+    ("macron", "\u{00AF}"),
+    ("male", "\u{2642}"),
+    ("maqaf", "\u{05BE}"),
+    ("mem", "\u{05DE}"),
+    ("meteg", "\u{05BD}"),
+    ("minus", "\u{2212}"),
+    ("minute", "\u{2032}"),
+    ("mu", "\u{00B5}"),
+    ("multiply", "\u{00D7}"),
+    ("musicalnote", "\u{266A}"),
+    ("musicalnotedbl", "\u{266B}"),
+    ("n", "\u{006E}"),
+    ("nacute", "\u{0144}"),
+    ("napostrophe", "\u{0149}"),
+    ("ncaron", "\u{0148}"),
+    ("ncedilla", "\u{0146}"),
+    ("ncommaaccent", "\u{0146}"),
+    ("nine", "\u{0039}"),
+    ("nineinferior", "\u{2089}"),
+    ("ninesuperior", "\u{2079}"),
+    ("notelement", "\u{2209}"),
+    ("notequal", "\u{2260}"),
+    ("notsubset", "\u{2284}"),
+    ("nsuperior", "\u{207F}"),
+    ("ntilde", "\u{00F1}"),
+    ("nu", "\u{03BD}"),
+    ("numbersign", "\u{0023}"),
+    ("nun", "\u{05E0}"),
+    ("o", "\u{006F}"),
+    ("oacute", "\u{00F3}"),
+    ("obreve", "\u{014F}"),
+    ("ocircumflex", "\u{00F4}"),
+    ("odieresis", "\u{00F6}"),
+    ("oe", "\u{0153}"),
+    ("ogonek", "\u{02DB}"),
+    ("ograve", "\u{00F2}"),
+    ("ohorn", "\u{01A1}"),
+    ("ohungarumlaut", "\u{0151}"),
+    ("omacron", "\u{014D}"),
+    ("omega", "\u{03C9}"),
+    ("omega1", "\u{03D6}"),
+    ("omegatonos", "\u{03CE}"),
+    ("omicron", "\u{03BF}"),
+    ("omicrontonos", "\u{03CC}"),
+    ("one", "\u{0031}"),
+    ("onedotenleader", "\u{2024}"),
+    ("oneeighth", "\u{215B}"),
+    ("onehalf", "\u{00BD}"),
+    ("oneinferior", "\u{2081}"),
+    ("onequarter", "\u{00BC}"),
+    ("onesuperior", "\u{00B9}"),
+    ("onethird", "\u{2153}"),
+    ("openbullet", "\u{25E6}"),
+    ("ordfeminine", "\u{00AA}"),
+    ("ordmasculine", "\u{00BA}"),
+    ("orthogonal", "\u{221F}"),
+    ("oslash", "\u{00F8}"),
+    ("oslashacute", "\u{01FF}"),
+    ("otilde", "\u{00F5}"),
+    ("p", "\u{0070}"),
+    ("paragraph", "\u{00B6}"),
+    ("parenleft", "\u{0028}"),
+    ("parenleftinferior", "\u{208D}"),
+    ("parenleftsuperior", "\u{207D}"),
+    ("parenright", "\u{0029}"),
+    ("parenrightinferior", "\u{208E}"),
+    ("parenrightsuperior", "\u{207E}"),
+    ("partialdiff", "\u{2202}"),
+    ("paseq", "\u{05C0}"),
+    ("patah", "\u{05B7}"),
+    ("pe", "\u{05E4}"),
+    ("percent", "\u{0025}"),
+    ("period", "\u{002E}"),
+    ("periodcentered", "\u{00B7}"),
+    ("perpendicular", "\u{22A5}"),
+    ("perthousand", "\u{2030}"),
+    ("peseta", "\u{20A7}"),
+    ("phi", "\u{03C6}"),
+    ("phi1", "\u{03D5}"),
+    ("pi", "\u{03C0}"),
+    ("plus", "\u{002B}"),
+    ("plusminus", "\u{00B1}"),
+    ("prescription", "\u{211E}"),
+    ("product", "\u{220F}"),
+    ("propersubset", "\u{2282}"),
+    ("propersuperset", "\u{2283}"),
+    ("proportional", "\u{221D}"),
+    ("psi", "\u{03C8}"),
+    ("q", "\u{0071}"),
+    ("qamats", "\u{05B8}"),
+    ("qof", "\u{05E7}"),
+    ("qubuts", "\u{05BB}"),
+    ("question", "\u{003F}"),
+    ("questiondown", "\u{00BF}"),
+    ("quotedbl", "\u{0022}"),
+    ("quotedblbase", "\u{201E}"),
+    ("quotedblleft", "\u{201C}"),
+    ("quotedblright", "\u{201D}"),
+    ("quoteleft", "\u{2018}"),
+    ("quotereversed", "\u{201B}"),
+    ("quoteright", "\u{2019}"),
+    ("quotesinglbase", "\u{201A}"),
+    ("quotesingle", "\u{0027}"),
+    ("r", "\u{0072}"),
+    ("racute", "\u{0155}"),
+    ("radical", "\u{221A}"),
+    ("rafe", "\u{05BF}"),
+    ("rcaron", "\u{0159}"),
+    ("rcedilla", "\u{0157}"),
+    ("rcommaaccent", "\u{0157}"),
+    ("reflexsubset", "\u{2286}"),
+    ("reflexsuperset", "\u{2287}"),
+    ("registered", "\u{00AE}"),
+    ("resh", "\u{05E8}"),
+    ("revlogicalnot", "\u{2310}"),
+    ("rho", "\u{03C1}"),
+    ("ring", "\u{02DA}"),
+    ("rtblock", "\u{2590}"),
+    ("s", "\u{0073}"),
+    ("sacute", "\u{015B}"),
+    ("samekh", "\u{05E1}"),
+    ("scaron", "\u{0161}"),
+    ("scedilla", "\u{015F}"),
+    ("scircumflex", "\u{015D}"),
+    ("scommaaccent", "\u{0219}"),
+    ("second", "\u{2033}"),
+    ("section", "\u{00A7}"),
+    ("segol", "\u{05B6}"),
+    ("semicolon", "\u{003B}"),
+    ("seven", "\u{0037}"),
+    ("seveneighths", "\u{215E}"),
+    ("seveninferior", "\u{2087}"),
+    ("sevensuperior", "\u{2077}"),
+    ("shade", "\u{2592}"),
+    ("sheva", "\u{05B0}"),
+    ("shin", "\u{05E9}"),
+    ("shindot", "\u{05C1}"),
+    ("sigma", "\u{03C3}"),
+    ("sigma1", "\u{03C2}"),
+    ("similar", "\u{223C}"),
+    ("sindot", "\u{05C2}"),
+    ("six", "\u{0036}"),
+    ("sixinferior", "\u{2086}"),
+    ("sixsuperior", "\u{2076}"),
+    ("slash", "\u{002F}"),
+    ("smileface", "\u{263A}"),
+    ("sofpasuq", "\u{05C3}"),
+    ("space", "\u{0020}"),
+    ("spade", "\u{2660}"),
+    ("sterling", "\u{00A3}"),
+    ("suchthat", "\u{220B}"),
+    ("summation", "\u{2211}"),
+    ("sun", "\u{263C}"),
+    ("t", "\u{0074}"),
+    ("tau", "\u{03C4}"),
+    ("tav", "\u{05EA}"),
+    ("tbar", "\u{0167}"),
+    ("tcaron", "\u{0165}"),
+    ("tcedilla", "\u{0163}"),
+    ("tcommaaccent", "\u{0163}"),
+    ("tet", "\u{05D8}"),
+    ("therefore", "\u{2234}"),
+    ("theta", "\u{03B8}"),
+    ("theta1", "\u{03D1}"),
+    ("thorn", "\u{00FE}"),
+    ("three", "\u{0033}"),
+    ("threeeighths", "\u{215C}"),
+    ("threeinferior", "\u{2083}"),
+    ("threequarters", "\u{00BE}"),
+    ("threesuperior", "\u{00B3}"),
+    ("tilde", "\u{02DC}"),
+    ("tildecomb", "\u{0303}"),
+    ("tonos", "\u{0384}"),
+    ("trademark", "\u{2122}"),
+    ("triagdn", "\u{25BC}"),
+    ("triaglf", "\u{25C4}"),
+    ("triagrt", "\u{25BA}"),
+    ("triagup", "\u{25B2}"),
+    ("tsadi", "\u{05E6}"),
+    ("tsere", "\u{05B5}"),
+    ("two", "\u{0032}"),
+    ("twodotenleader", "\u{2025}"),
+    ("twoinferior", "\u{2082}"),
+    ("twosuperior", "\u{00B2}"),
+    ("twothirds", "\u{2154}"),
+    ("u", "\u{0075}"),
+    ("uacute", "\u{00FA}"),
+    ("ubreve", "\u{016D}"),
+    ("ucircumflex", "\u{00FB}"),
+    ("udieresis", "\u{00FC}"),
+    ("ugrave", "\u{00F9}"),
+    ("uhorn", "\u{01B0}"),
+    ("uhungarumlaut", "\u{0171}"),
+    ("umacron", "\u{016B}"),
+    ("underscore", "\u{005F}"),
+    ("underscoredbl", "\u{2017}"),
+    ("union", "\u{222A}"),
+    ("universal", "\u{2200}"),
+    ("uogonek", "\u{0173}"),
+    ("upblock", "\u{2580}"),
+    ("upsilon", "\u{03C5}"),
+    ("upsilondieresis", "\u{03CB}"),
+    ("upsilondieresistonos", "\u{03B0}"),
+    ("upsilontonos", "\u{03CD}"),
+    ("uring", "\u{016F}"),
+    ("utilde", "\u{0169}"),
+    ("v", "\u{0076}"),
+    ("vav", "\u{05D5}"),
+    ("vavyod", "\u{05F1}"),
+    ("w", "\u{0077}"),
+    ("wacute", "\u{1E83}"),
+    ("wcircumflex", "\u{0175}"),
+    ("wdieresis", "\u{1E85}"),
+    ("weierstrass", "\u{2118}"),
+    ("wgrave", "\u{1E81}"),
+    ("x", "\u{0078}"),
+    ("xi", "\u{03BE}"),
+    ("y", "\u{0079}"),
+    ("yacute", "\u{00FD}"),
+    ("ycircumflex", "\u{0177}"),
+    ("ydieresis", "\u{00FF}"),
+    ("yen", "\u{00A5}"),
+    ("ygrave", "\u{1EF3}"),
+    ("yod", "\u{05D9}"),
+    ("z", "\u{007A}"),
+    ("zacute", "\u{017A}"),
+    ("zayin", "\u{05D6}"),
+    ("zcaron", "\u{017E}"),
+    ("zdotaccent", "\u{017C}"),
+    ("zero", "\u{0030}"),
+    ("zeroinferior", "\u{2080}"),
+    ("zerosuperior", "\u{2070}"),
+    ("zeta", "\u{03B6}"),
+];
@@ -0,0 +1,386 @@
+//! Image XObjects: which ones each page draws, their decoded samples, and a luminance view for
+//! callers that look for barcodes or other machine-readable marks.
+
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use miniz_oxide::inflate::decompress_to_vec_zlib;
+
+use crate::filters::{ascii85_decode, ascii_hex_decode, undo_predictor};
+use crate::types::{ImageColorSpace, PageContent, PdfImage, PdfObj, PdfStream};
+use crate::{resolve, HashMap, HashSet};
+
+type Objects<'a> = HashMap<(u32, u16), PdfObj<'a>>;
+
+/// Images drawn by page `page`, by resource name.
+pub(crate) fn page_images(content: &PageContent, page: usize, objects: &Objects) -> Vec<PdfImage> {
+    let mut images = Vec::new();
+    collect_xobjects(
+        &content.resources,
+        "",
+        page,
+        objects,
+        &mut HashSet::new(),
+        &mut images,
+    );
+    images
+}
+
+// Form XObjects are followed into their own resources; `visited` stops forms that draw
+// themselves
+fn collect_xobjects(
+    resources: &HashMap<String, PdfObj>,
+    prefix: &str,
+    page: usize,
+    objects: &Objects,
+    visited: &mut HashSet<(u32, u16)>,
+    images: &mut Vec<PdfImage>,
+) {
+    let Some(PdfObj::Dictionary(xobjects)) = resolve(resources.get("XObject"), objects) else {
+        return;
+    };
+    let mut names: Vec<&String> = xobjects.keys().collect();
+    names.sort();
+    for name in names {
+        let entry = &xobjects[name];
+        let Some(PdfObj::Stream(stream)) = resolve(Some(entry), objects) else {
+            continue;
+        };
+        let path = if prefix.is_empty() {
+            name.clone()
+        } else {
+            format!("{}/{}", prefix, name)
+        };
+        match stream.dict.get("Subtype") {
+            Some(PdfObj::Name(subtype)) if subtype == "Image" => {
+                if let Some(image) = image_from_stream(stream, page, path, objects) {
+                    images.push(image);
+                }
+            }
+            Some(PdfObj::Name(subtype)) if subtype == "Form" => {
+                if let PdfObj::Reference(id) = entry {
+                    if !visited.insert(*id) {
+                        continue;
+                    }
+                }
+                if let Some(PdfObj::Dictionary(form_resources)) =
+                    resolve(stream.dict.get("Resources"), objects)
+                {
+                    collect_xobjects(form_resources, &path, page, objects, visited, images);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn number(obj: Option<&PdfObj>, objects: &Objects) -> Option<f64> {
+    match resolve(obj, objects) {
+        Some(PdfObj::Number(n)) => Some(*n),
+        _ => None,
+    }
+}
+
+fn image_from_stream(
+    stream: &PdfStream,
+    page: usize,
+    name: String,
+    objects: &Objects,
+) -> Option<PdfImage> {
+    let dict = &stream.dict;
+    let width = number(dict.get("Width"), objects)? as usize;
+    let height = number(dict.get("Height"), objects)? as usize;
+    let is_mask = matches!(dict.get("ImageMask"), Some(PdfObj::Boolean(true)));
+    let (bits_per_component, color_space) = if is_mask {
+        (1, ImageColorSpace::Mask)
+    } else {
+        (
+            number(dict.get("BitsPerComponent"), objects).unwrap_or(8.0) as u8,
+            color_space(resolve(dict.get("ColorSpace"), objects), objects),
+        )
+    };
+    let inverted = match resolve(dict.get("Decode"), objects) {
+        Some(PdfObj::Array(decode)) => matches!(
+            decode.as_slice(),
+            [PdfObj::Number(a), PdfObj::Number(b), ..] if a > b
+        ),
+        _ => false,
+    };
+    let (encoding, data) = decode_image_data(stream, objects);
+    Some(PdfImage {
+        page,
+        name,
+        width,
+        height,
+        bits_per_component,
+        color_space,
+        inverted,
+        encoding,
+        data,
+    })
+}
+
+fn color_space(obj: Option<&PdfObj>, objects: &Objects) -> ImageColorSpace {
+    match obj {
+        Some(PdfObj::Name(name)) => match name.as_str() {
+            "DeviceGray" | "G" | "CalGray" => ImageColorSpace::Gray,
+            "DeviceRGB" | "RGB" | "CalRGB" => ImageColorSpace::Rgb,
+            "DeviceCMYK" | "CMYK" => ImageColorSpace::Cmyk,
+            other => ImageColorSpace::Other(other.to_string()),
+        },
+        Some(PdfObj::Array(items)) => {
+            let Some(PdfObj::Name(family)) = items.first() else {
+                return ImageColorSpace::Other(String::new());
+            };
+            match family.as_str() {
+                "CalGray" => ImageColorSpace::Gray,
+                "CalRGB" => ImageColorSpace::Rgb,
+                "ICCBased" => match resolve(items.get(1), objects) {
+                    Some(PdfObj::Stream(profile)) => match number(profile.dict.get("N"), objects) {
+                        Some(1.0) => ImageColorSpace::Gray,
+                        Some(3.0) => ImageColorSpace::Rgb,
+                        Some(4.0) => ImageColorSpace::Cmyk,
+                        _ => ImageColorSpace::Other(family.clone()),
+                    },
+                    _ => ImageColorSpace::Other(family.clone()),
+                },
+                "Indexed" | "I" => {
+                    let base = match resolve(items.get(1), objects) {
+                        // An indexed base is invalid; do not recurse into it
+                        Some(PdfObj::Array(base)) if matches!(base.first(), Some(PdfObj::Name(n)) if n == "Indexed" || n == "I") => {
+                            ImageColorSpace::Other(family.clone())
+                        }
+                        base => color_space(base, objects),
+                    };
+                    let palette = match resolve(items.get(3), objects) {
+                        Some(PdfObj::String(bytes)) => bytes.clone(),
+                        Some(PdfObj::Stream(lookup)) => decode_image_data(lookup, objects).1,
+                        _ => Vec::new(),
+                    };
+                    ImageColorSpace::Indexed {
+                        base: Box::new(base),
+                        palette,
+                    }
+                }
+                other => ImageColorSpace::Other(other.to_string()),
+            }
+        }
+        _ => ImageColorSpace::Other(String::new()),
+    }
+}
+
+// Applies leading Flate (with its predictors) and ASCII filters. Returns the first filter left
+// undecoded, if any, along with the data as far as it was decoded.
+fn decode_image_data(stream: &PdfStream, objects: &Objects) -> (Option<String>, Vec<u8>) {
+    let filters: Vec<&PdfObj> = match resolve(stream.dict.get("Filter"), objects) {
+        Some(PdfObj::Array(filters)) => filters.iter().collect(),
+        Some(filter) => alloc::vec![filter],
+        None => Vec::new(),
+    };
+    let params: Vec<Option<&PdfObj>> = match resolve(stream.dict.get("DecodeParms"), objects) {
+        Some(PdfObj::Array(params)) => params.iter().map(Some).collect(),
+        Some(params) => alloc::vec![Some(params)],
+        None => Vec::new(),
+    };
+
+    let mut data = stream.data.to_vec();
+    for (i, filter) in filters.iter().enumerate() {
+        let name = match resolve(Some(filter), objects) {
+            Some(PdfObj::Name(name)) => name.as_str(),
+            _ => "",
+        };
+        let params = match resolve(params.get(i).copied().flatten(), objects) {
+            Some(PdfObj::Dictionary(params)) => Some(params),
+            _ => None,
+        };
+        let decoded = match name {
+            "FlateDecode" | "Fl" => {
+                decompress_to_vec_zlib(&data)
+                    .ok()
+                    .and_then(|inflated| match params {
+                        Some(params) => {
+                            undo_predictor(inflated, |key| number(params.get(key), objects))
+                        }
+                        None => Some(inflated),
+                    })
+            }
+            "ASCIIHexDecode" | "AHx" => ascii_hex_decode(&data).ok(),
+            "ASCII85Decode" | "A85" => ascii85_decode(&data).ok(),
+            _ => return (Some(name.to_string()), data),
+        };
+        match decoded {
+            Some(decoded) => data = decoded,
+            None => return (Some(name.to_string()), data),
+        }
+    }
+    (None, data)
+}
+
+impl PdfImage {
+    /// One 8-bit luminance value per pixel, row-major, 0 black and 255 white. `None` when the
+    /// data is still encoded (JPEG, JBIG2, ...), the colour space is not supported, or the data
+    /// is short.
+    pub fn luminance(&self) -> Option<Vec<u8>> {
+        if self.encoding.is_some() || !matches!(self.bits_per_component, 1 | 2 | 4 | 8 | 16) {
+            return None;
+        }
+        let bits = self.bits_per_component as usize;
+        let components = self.color_space.components();
+        let row_len = (self.width * components * bits).div_ceil(8);
+        if self.data.len() < row_len * self.height {
+            return None;
+        }
+        let max = (1u32 << bits.min(8)) - 1;
+
+        let mut out = Vec::with_capacity(self.width * self.height);
+        for row in self.data.chunks(row_len).take(self.height) {
+            // Raw sample values, scaled to 8 bits except for palette indices
+            let sample = |index: usize| -> u32 {
+                let value = match bits {
+                    16 => row[index * 2] as u32,
+                    8 => row[index] as u32,
+                    _ => {
+                        let bit = index * bits;
+                        ((row[bit / 8] >> (8 - bits - bit % 8)) as u32) & max
+                    }
+                };
+                if self.inverted {
+                    max - value
+                } else {
+                    value
+                }
+            };
+            let scale = |value: u32| (value * 255 / max) as u8;
+            for x in 0..self.width {
+                let base = x * components;
+                let luma = match &self.color_space {
+                    ImageColorSpace::Gray => scale(sample(base)),
+                    ImageColorSpace::Mask => {
+                        if sample(base) == 0 {
+                            0
+                        } else {
+                            255
+                        }
+                    }
+                    ImageColorSpace::Rgb => rgb_luma(
+                        scale(sample(base)),
+                        scale(sample(base + 1)),
+                        scale(sample(base + 2)),
+                    ),
+                    ImageColorSpace::Cmyk => cmyk_luma([
+                        scale(sample(base)),
+                        scale(sample(base + 1)),
+                        scale(sample(base + 2)),
+                        scale(sample(base + 3)),
+                    ]),
+                    ImageColorSpace::Indexed {
+                        base: space,
+                        palette,
+                    } => {
+                        let n = space.components();
+                        let entry = palette.get(sample(base) as usize * n..)?;
+                        match (space.as_ref(), entry) {
+                            (ImageColorSpace::Gray, [g, ..]) => *g,
+                            (ImageColorSpace::Rgb, [r, g, b, ..]) => rgb_luma(*r, *g, *b),
+                            (ImageColorSpace::Cmyk, [c, m, y, k, ..]) => {
+                                cmyk_luma([*c, *m, *y, *k])
+                            }
+                            _ => return None,
+                        }
+                    }
+                    ImageColorSpace::Other(_) => return None,
+                };
+                out.push(luma);
+            }
+        }
+        Some(out)
+    }
+}
+
+fn rgb_luma(r: u8, g: u8, b: u8) -> u8 {
+    ((299 * r as u32 + 587 * g as u32 + 114 * b as u32) / 1000) as u8
+}
+
+fn cmyk_luma([c, m, y, k]: [u8; 4]) -> u8 {
+    let channel = |v: u8| 255 - (v as u32 + k as u32).min(255) as u8;
+    rgb_luma(channel(c), channel(m), channel(y))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_written_images() {
+        use zkpdf_writer::{Document, Image, Page};
+
+        let samples: Vec<u8> = (0..12u8).map(|i| i * 20).collect();
+        for compress in [false, true] {
+            let mut page = Page::from_lines(&["Scan me"]);
+            page.images.push(Image {
+                width: 4,
+                height: 3,
+                samples: samples.clone(),
+                x: 72.0,
+                y: 500.0,
+                display_width: 40.0,
+                display_height: 30.0,
+            });
+            let doc = Document {
+                pages: alloc::vec![Page::default(), page],
+                compress,
+                ..Document::default()
+            };
+            let images = crate::extract_images(&doc.to_bytes().unwrap()).unwrap();
+
+            assert_eq!(images.len(), 1);
+            let image = &images[0];
+            assert_eq!((image.page, image.name.as_str()), (1, "Im1"));
+            assert_eq!((image.width, image.height), (4, 3));
+            assert_eq!(image.color_space, ImageColorSpace::Gray);
+            assert_eq!(image.encoding, None);
+            assert_eq!(image.luminance().unwrap(), samples);
+        }
+    }
+
+    #[test]
+    fn luminance_of_masks_and_palettes() {
+        let image = |color_space, bits, data: Vec<u8>| PdfImage {
+            page: 0,
+            name: "Im1".into(),
+            width: 3,
+            height: 1,
+            bits_per_component: bits,
+            color_space,
+            inverted: false,
+            encoding: None,
+            data,
+        };
+
+        let mask = image(ImageColorSpace::Mask, 1, alloc::vec![0b0100_0000]);
+        assert_eq!(mask.luminance().unwrap(), [0, 255, 0]);
+
+        let indexed = image(
+            ImageColorSpace::Indexed {
+                base: Box::new(ImageColorSpace::Rgb),
+                palette: alloc::vec![0, 0, 0, 255, 255, 255],
+            },
+            2,
+            alloc::vec![0b0001_0000],
+        );
+        assert_eq!(indexed.luminance().unwrap(), [0, 255, 0]);
+
+        let gray = PdfImage {
+            inverted: true,
+            ..image(ImageColorSpace::Gray, 8, alloc::vec![0, 128, 255])
+        };
+        assert_eq!(gray.luminance().unwrap(), [255, 127, 0]);
+
+        let jpeg = PdfImage {
+            encoding: Some("DCTDecode".into()),
+            ..image(ImageColorSpace::Rgb, 8, alloc::vec![0xff, 0xd8])
+        };
+        assert_eq!(jpeg.luminance(), None);
+    }
+}
@@ -0,0 +1,675 @@
+//! Positional text extraction: content streams are interpreted with the text and graphics
+//! state (`cm`, `Tm`, `Td`, `TL`, `Tc`, ...), so every string lands at its position in user
+//! space, and page text can be rebuilt line by line from where the glyphs are drawn instead of
+//! the order they are drawn in.
+
+use alloc::borrow::Cow;
+use alloc::collections::BTreeSet;
+use alloc::string::String;
+use alloc::vec::Vec;
+use miniz_oxide::inflate::decompress_to_vec_zlib;
+
+use crate::annotation;
+use crate::cmap::decode_bytes;
+use crate::font::{collect_fonts_from_resources, string_advance, DEFAULT_SPACE_WIDTH, WORD_GAP};
+use crate::types::{
+    PageContent, PdfError, PdfFont, PdfObj, PdfStream, TextRun, Token, Type3Glyphs, Word,
+};
+use crate::{
+    content_tokens, handle_stream_filters, named_stream, parse_content_tokens, resolve, HashMap,
+    HashSet,
+};
+
+type Objects<'a> = HashMap<(u32, u16), PdfObj<'a>>;
+type Matrix = [f32; 6];
+
+const IDENTITY: Matrix = [1.0, 0.0, 0.0, 1.0, 0.0, 0.0];
+
+/// Advance of every glyph of a font without widths, in text space units per unit of font
+/// size; half an em is close to the average Latin glyph.
+const GLYPH_WIDTH: f32 = 0.5;
+
+/// Vertical distance, in font sizes, within which two runs share a baseline.
+const LINE_TOLERANCE: f32 = 0.5;
+
+/// Runs drawn by a page, in content stream order.
+pub(crate) fn text_runs(page: &PageContent, index: usize, objects: &Objects) -> Vec<TextRun> {
+    let mut interpreter = Interpreter::new(index, objects);
+    interpreter.draw_page(page);
+    interpreter.runs
+}
+
+/// Runs drawn by the appearance streams of a page's annotations, placed on the page.
+pub(crate) fn annotation_runs(page: &PageContent, index: usize, objects: &Objects) -> Vec<TextRun> {
+    let mut interpreter = Interpreter::new(index, objects);
+    interpreter.draw_annotations(page);
+    interpreter.runs
+}
+
+/// Runs drawn by a page, followed by those of its annotations if `annotations` is set, and
+/// the base names of the fonts they are shown in.
+pub(crate) fn page_runs(
+    page: &PageContent,
+    index: usize,
+    objects: &Objects,
+    annotations: bool,
+) -> (Vec<TextRun>, BTreeSet<String>) {
+    let mut interpreter = Interpreter::new(index, objects);
+    interpreter.draw_page(page);
+    if annotations {
+        interpreter.draw_annotations(page);
+    }
+    (interpreter.runs, interpreter.fonts)
+}
+
+/// Page text rebuilt from run positions: runs are clustered into lines by baseline, lines are
+/// ordered top to bottom and runs left to right, with a space where a line has a gap.
+pub(crate) fn layout_text(runs: &[TextRun]) -> String {
+    let mut text = String::new();
+    for line in lines(runs) {
+        if !text.is_empty() {
+            text.push('\n');
+        }
+        let mut previous: Option<&TextRun> = None;
+        for run in line {
+            if let Some(previous) = previous {
+                if separated(previous, run)
+                    && !previous.text.ends_with(char::is_whitespace)
+                    && !run.text.starts_with(char::is_whitespace)
+                {
+                    text.push(' ');
+                }
+            }
+            text.push_str(&run.text);
+            previous = Some(run);
+        }
+    }
+    text
+}
+
+/// Words in the order `layout_text` reads them, split at whitespace and at gaps between runs.
+/// A run's advance is shared equally by its characters, so boxes of words that start or end
+/// inside a run are approximate.
+pub(crate) fn words(runs: &[TextRun]) -> Vec<Word> {
+    // Corners of the word being read: left, bottom, right, top
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut bounds = [0.0f32; 4];
+    let mut finish = |word: &mut String, bounds: &[f32; 4]| {
+        if !word.is_empty() {
+            words.push(Word {
+                text: core::mem::take(word),
+                x: bounds[0],
+                y: bounds[1],
+                width: bounds[2] - bounds[0],
+                height: bounds[3] - bounds[1],
+            });
+        }
+    };
+
+    for line in lines(runs) {
+        let mut previous: Option<&TextRun> = None;
+        for run in line {
+            if previous.is_some_and(|previous| separated(previous, run)) {
+                finish(&mut word, &bounds);
+            }
+            let advance = run.width / run.text.chars().count() as f32;
+            for (i, ch) in run.text.chars().enumerate() {
+                if ch.is_whitespace() {
+                    finish(&mut word, &bounds);
+                    continue;
+                }
+                let x = run.x + advance * i as f32;
+                let glyph = [
+                    x.min(x + advance),
+                    run.y,
+                    x.max(x + advance),
+                    run.y + run.font_size,
+                ];
+                if word.is_empty() {
+                    bounds = glyph;
+                } else {
+                    bounds = [
+                        bounds[0].min(glyph[0]),
+                        bounds[1].min(glyph[1]),
+                        bounds[2].max(glyph[2]),
+                        bounds[3].max(glyph[3]),
+                    ];
+                }
+                word.push(ch);
+            }
+            previous = Some(run);
+        }
+        finish(&mut word, &bounds);
+    }
+    words
+}
+
+// Non-empty runs clustered into lines by baseline, top to bottom, each sorted left to right
+fn lines(runs: &[TextRun]) -> Vec<Vec<&TextRun>> {
+    let mut sorted: Vec<&TextRun> = runs.iter().filter(|run| !run.text.is_empty()).collect();
+    sorted.sort_by(|a, b| b.y.total_cmp(&a.y));
+
+    let mut lines: Vec<Vec<&TextRun>> = Vec::new();
+    for run in sorted {
+        match lines.last_mut() {
+            Some(line)
+                if (line[0].y - run.y).abs()
+                    <= LINE_TOLERANCE * line[0].font_size.max(run.font_size).max(1.0) =>
+            {
+                line.push(run)
+            }
+            _ => lines.push(alloc::vec![run]),
+        }
+    }
+    for line in &mut lines {
+        line.sort_by(|a, b| a.x.total_cmp(&b.x));
+    }
+    lines
+}
+
+// Whether the gap between two runs of a line is wide enough to be a space. Runs do not keep
+// their font, so every font's space is taken as the default
+fn separated(previous: &TextRun, run: &TextRun) -> bool {
+    let gap = run.x - (previous.x + previous.width);
+    let size = previous.font_size.max(run.font_size);
+    gap >= WORD_GAP * DEFAULT_SPACE_WIDTH / 1000.0 * size
+}
+
+// Row vectors, as in the PDF specification: `multiply(a, b)` applies `a` first
+fn multiply(a: Matrix, b: Matrix) -> Matrix {
+    [
+        a[0] * b[0] + a[1] * b[2],
+        a[0] * b[1] + a[1] * b[3],
+        a[2] * b[0] + a[3] * b[2],
+        a[2] * b[1] + a[3] * b[3],
+        a[4] * b[0] + a[5] * b[2] + b[4],
+        a[4] * b[1] + a[5] * b[3] + b[5],
+    ]
+}
+
+fn translate(tx: f32, ty: f32) -> Matrix {
+    [1.0, 0.0, 0.0, 1.0, tx, ty]
+}
+
+// The parts of the graphics state that `q` and `Q` save and restore
+#[derive(Clone, Copy)]
+struct GraphicsState<'a> {
+    ctm: Matrix,
+    font: Option<&'a PdfFont>,
+    font_size: f32,
+    char_spacing: f32,
+    word_spacing: f32,
+    /// `Tz` / 100
+    horizontal_scale: f32,
+    leading: f32,
+    rise: f32,
+}
+
+struct Interpreter<'o> {
+    page: usize,
+    objects: &'o Objects<'o>,
+    // Form XObjects being drawn, so a form that draws itself stops
+    visited: HashSet<(u32, u16)>,
+    runs: Vec<TextRun>,
+    /// Base names of the fonts of the runs
+    fonts: BTreeSet<String>,
+    // Forms, patterns, glyph procedures and appearances being drawn. Marked content is only
+    // read in the page's own content, whose MCIDs the structure tree refers to; what these
+    // draw belongs to the sequence they are drawn in.
+    nested: usize,
+    mcid: Option<u32>,
+}
+
+impl<'o> Interpreter<'o> {
+    fn new(page: usize, objects: &'o Objects<'o>) -> Self {
+        Interpreter {
+            page,
+            objects,
+            visited: HashSet::new(),
+            runs: Vec::new(),
+            fonts: BTreeSet::new(),
+            nested: 0,
+            mcid: None,
+        }
+    }
+
+    fn draw_page(&mut self, page: &PageContent) {
+        let tokens = content_tokens(&page.content_streams);
+        self.run(&tokens, &page.fonts, &page.resources, IDENTITY);
+    }
+
+    fn draw_annotations(&mut self, page: &PageContent) {
+        self.nested += 1;
+        for appearance in annotation::appearances(page, self.objects) {
+            let tokens = parse_content_tokens(&appearance.content);
+            self.run(
+                &tokens,
+                &appearance.fonts,
+                appearance.resources,
+                appearance.matrix,
+            );
+        }
+        self.nested -= 1;
+    }
+
+    fn run(
+        &mut self,
+        tokens: &[Token],
+        fonts: &HashMap<String, PdfFont>,
+        resources: &HashMap<String, PdfObj>,
+        ctm: Matrix,
+    ) {
+        let mut state = GraphicsState {
+            ctm,
+            font: None,
+            font_size: 0.0,
+            char_spacing: 0.0,
+            word_spacing: 0.0,
+            horizontal_scale: 1.0,
+            leading: 0.0,
+            rise: 0.0,
+        };
+        let mut saved = Vec::new();
+        // Open marked-content sequences, with their MCIDs
+        let mut marked: Vec<Option<u32>> = Vec::new();
+        let mut text_matrix = IDENTITY;
+        let mut line_matrix = IDENTITY;
+
+        for (i, token) in tokens.iter().enumerate() {
+            let Token::Operator(op) = token else {
+                continue;
+            };
+            let number = |back: usize| match i.checked_sub(back).map(|at| &tokens[at]) {
+                Some(Token::Number(n)) => Some(*n),
+                _ => None,
+            };
+            let matrix = || -> Option<Matrix> {
+                Some([
+                    number(6)?,
+                    number(5)?,
+                    number(4)?,
+                    number(3)?,
+                    number(2)?,
+                    number(1)?,
+                ])
+            };
+            match op.as_str() {
+                "q" => saved.push(state),
+                "Q" => state = saved.pop().unwrap_or(state),
+                "cm" => {
+                    if let Some(m) = matrix() {
+                        state.ctm = multiply(m, state.ctm);
+                    }
+                }
+                "BT" => {
+                    text_matrix = IDENTITY;
+                    line_matrix = IDENTITY;
+                }
+                "Tf" => {
+                    if let (Some(Token::Name(name)), Some(size)) =
+                        (i.checked_sub(2).map(|at| &tokens[at]), number(1))
+                    {
+                        state.font = fonts.get(name);
+                        state.font_size = size;
+                    }
+                }
+                "Tc" => state.char_spacing = number(1).unwrap_or(state.char_spacing),
+                "Tw" => state.word_spacing = number(1).unwrap_or(state.word_spacing),
+                "Tz" => {
+                    state.horizontal_scale =
+                        number(1).map_or(state.horizontal_scale, |scale| scale / 100.0)
+                }
+                "TL" => state.leading = number(1).unwrap_or(state.leading),
+                "Ts" => state.rise = number(1).unwrap_or(state.rise),
+                "Td" | "TD" => {
+                    if let (Some(tx), Some(ty)) = (number(2), number(1)) {
+                        if op == "TD" {
+                            state.leading = -ty;
+                        }
+                        line_matrix = multiply(translate(tx, ty), line_matrix);
+                        text_matrix = line_matrix;
+                    }
+                }
+                "Tm" => {
+                    if let Some(m) = matrix() {
+                        line_matrix = m;
+                        text_matrix = m;
+                    }
+                }
+                "T*" | "'" | "\"" => {
+                    if op == "\"" {
+                        state.word_spacing = number(3).unwrap_or(state.word_spacing);
+                        state.char_spacing = number(2).unwrap_or(state.char_spacing);
+                    }
+                    line_matrix = multiply(translate(0.0, -state.leading), line_matrix);
+                    text_matrix = line_matrix;
+                    if op != "T*" {
+                        if let Some(Token::String(bytes)) = i.checked_sub(1).map(|at| &tokens[at]) {
+                            self.show(bytes, &state, &mut text_matrix);
+                        }
+                    }
+                }
+                "Tj" => {
+                    if let Some(Token::String(bytes)) = i.checked_sub(1).map(|at| &tokens[at]) {
+                        self.show(bytes, &state, &mut text_matrix);
+                    }
+                }
+                "TJ" => {
+                    if let Some(Token::Array(elements)) = i.checked_sub(1).map(|at| &tokens[at]) {
+                        for element in elements {
+                            match element {
+                                Token::String(bytes) => {
+                                    self.show(bytes, &state, &mut text_matrix);
+                                }
+                                Token::Number(adjustment) => {
+                                    let tx = -adjustment / 1000.0
+                                        * state.font_size
+                                        * state.horizontal_scale;
+                                    text_matrix = multiply(translate(tx, 0.0), text_matrix);
+                                }
+                                _ => {}
+                            }
+                        }
+                    }
+                }
+                "Do" => {
+                    if let Some(Token::Name(name)) = i.checked_sub(1).map(|at| &tokens[at]) {
+                        self.draw_form(name, resources, state.ctm);
+                    }
+                }
+                "scn" | "SCN" => {
+                    if let Some(Token::Name(name)) = i.checked_sub(1).map(|at| &tokens[at]) {
+                        self.draw_pattern(name, resources, ctm);
+                    }
+                }
+                "BMC" | "BDC" | "EMC" if self.nested == 0 => {
+                    match op.as_str() {
+                        "BMC" => marked.push(None),
+                        "BDC" => {
+                            let properties = i.checked_sub(1).map(|at| &tokens[at]);
+                            marked.push(self.marked_content_id(properties, resources));
+                        }
+                        _ => {
+                            marked.pop();
+                        }
+                    }
+                    self.mcid = marked.iter().rev().find_map(|mcid| *mcid);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // The `/MCID` of `BDC` properties given inline or by name in the `/Properties` resources
+    fn marked_content_id(
+        &self,
+        properties: Option<&Token>,
+        resources: &HashMap<String, PdfObj>,
+    ) -> Option<u32> {
+        match properties? {
+            Token::Dictionary(properties) => match properties.get("MCID") {
+                Some(Token::Number(mcid)) => Some(*mcid as u32),
+                _ => None,
+            },
+            Token::Name(name) => {
+                let Some(PdfObj::Dictionary(named)) =
+                    resolve(resources.get("Properties"), self.objects)
+                else {
+                    return None;
+                };
+                let Some(PdfObj::Dictionary(properties)) = resolve(named.get(name), self.objects)
+                else {
+                    return None;
+                };
+                match resolve(properties.get("MCID"), self.objects) {
+                    Some(PdfObj::Number(mcid)) => Some(*mcid as u32),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    // Decodes a string, records it as a run and moves the text matrix past it
+    fn show(&mut self, bytes: &[u8], state: &GraphicsState, text_matrix: &mut Matrix) {
+        let Some(font) = state.font else {
+            return;
+        };
+        let advance = string_advance(
+            font,
+            bytes,
+            state.font_size,
+            state.char_spacing,
+            state.word_spacing,
+            state.horizontal_scale,
+        )
+        .unwrap_or_else(|| {
+            let two_byte = font.cid.is_some();
+            let glyphs = if two_byte {
+                bytes.len().div_ceil(2)
+            } else {
+                bytes.len()
+            };
+            // Word spacing applies to the single-byte code 32 only
+            let spaces = if two_byte {
+                0
+            } else {
+                bytes.iter().filter(|&&b| b == b' ').count()
+            };
+            (glyphs as f32 * (GLYPH_WIDTH * state.font_size + state.char_spacing)
+                + spaces as f32 * state.word_spacing)
+                * state.horizontal_scale
+        });
+
+        if let Some(type3) = &font.type3 {
+            self.draw_glyphs(type3, font, bytes, state, *text_matrix);
+        }
+        let start = multiply(
+            translate(0.0, state.rise),
+            multiply(*text_matrix, state.ctm),
+        );
+        *text_matrix = multiply(translate(advance, 0.0), *text_matrix);
+        let end = multiply(
+            translate(0.0, state.rise),
+            multiply(*text_matrix, state.ctm),
+        );
+
+        let text = decode_bytes(bytes, font);
+        if text.is_empty() {
+            return;
+        }
+        if let Some(name) = &font.base_name {
+            self.fonts.insert(name.clone());
+        }
+        self.runs.push(TextRun {
+            page: self.page,
+            text,
+            x: start[4],
+            y: start[5],
+            width: end[4] - start[4],
+            font_size: state.font_size * start[2].abs().max(start[3].abs()),
+            mcid: self.mcid,
+        });
+    }
+
+    // Runs the glyph procedures of a Type3 font that show text, each where its glyph is drawn:
+    // glyph space maps to text space through the font matrix, scaled by the font size and
+    // horizontal scaling (PDF 32000-1, 9.4.4)
+    fn draw_glyphs(
+        &mut self,
+        type3: &Type3Glyphs,
+        font: &PdfFont,
+        bytes: &[u8],
+        state: &GraphicsState,
+        mut text_matrix: Matrix,
+    ) {
+        let size = [
+            state.font_size * state.horizontal_scale,
+            0.0,
+            0.0,
+            state.font_size,
+            0.0,
+            state.rise,
+        ];
+        for &code in bytes {
+            if let Some(content) = type3.procs.get(&u32::from(code)) {
+                let glyph = multiply(
+                    type3.matrix,
+                    multiply(size, multiply(text_matrix, state.ctm)),
+                );
+                let tokens = parse_content_tokens(content);
+                self.nested += 1;
+                self.run(&tokens, &type3.fonts, &HashMap::new(), glyph);
+                self.nested -= 1;
+            }
+            let advance = string_advance(
+                font,
+                &[code],
+                state.font_size,
+                state.char_spacing,
+                state.word_spacing,
+                state.horizontal_scale,
+            )
+            .unwrap_or(0.0);
+            text_matrix = multiply(translate(advance, 0.0), text_matrix);
+        }
+    }
+
+    fn draw_form(&mut self, name: &str, resources: &HashMap<String, PdfObj>, ctm: Matrix) {
+        let Some((form, id)) = named_stream(resources, "XObject", name, self.objects) else {
+            return;
+        };
+        if matches!(form.dict.get("Subtype"), Some(PdfObj::Name(subtype)) if subtype == "Form") {
+            self.draw_stream(form, id, resources, ctm);
+        }
+    }
+
+    // A tiling pattern selected as the colour is drawn once, in the pattern space of the
+    // content stream selecting it (`base`); shading patterns have no content
+    fn draw_pattern(&mut self, name: &str, resources: &HashMap<String, PdfObj>, base: Matrix) {
+        let Some((pattern, id)) = named_stream(resources, "Pattern", name, self.objects) else {
+            return;
+        };
+        if matches!(pattern.dict.get("PatternType"), Some(PdfObj::Number(t)) if *t == 1.0) {
+            self.draw_stream(pattern, id, resources, base);
+        }
+    }
+
+    // Draws the content of a form or pattern through its `/Matrix`, with its own `/Resources`
+    // or else `resources`
+    fn draw_stream(
+        &mut self,
+        form: &PdfStream,
+        id: Option<(u32, u16)>,
+        resources: &HashMap<String, PdfObj>,
+        ctm: Matrix,
+    ) {
+        let objects = self.objects;
+        if id.is_some_and(|id| !self.visited.insert(id)) {
+            return;
+        }
+
+        let decompress =
+            |data: &[u8]| decompress_to_vec_zlib(data).map_err(|_| PdfError::DecompressionError);
+        let content = match form.dict.get("Filter") {
+            Some(filter) => {
+                let mut decoded = Vec::new();
+                match handle_stream_filters(
+                    filter,
+                    form.dict.get("DecodeParms"),
+                    &form.data,
+                    &decompress,
+                    &mut decoded,
+                ) {
+                    Ok(()) => Cow::Owned(decoded.pop().unwrap_or_default()),
+                    Err(_) => Cow::Owned(Vec::new()),
+                }
+            }
+            None => Cow::Borrowed(&form.data[..]),
+        };
+        let form_resources = match resolve(form.dict.get("Resources"), objects) {
+            Some(PdfObj::Dictionary(map)) => map,
+            _ => resources,
+        };
+        let fonts =
+            collect_fonts_from_resources(form_resources, objects, &decompress).unwrap_or_default();
+        let matrix = match resolve(form.dict.get("Matrix"), objects) {
+            Some(PdfObj::Array(values)) if values.len() == 6 => {
+                let mut m = IDENTITY;
+                for (slot, value) in m.iter_mut().zip(values) {
+                    if let PdfObj::Number(n) = value {
+                        *slot = *n as f32;
+                    }
+                }
+                m
+            }
+            _ => IDENTITY,
+        };
+
+        let tokens = parse_content_tokens(&content);
+        self.nested += 1;
+        self.run(&tokens, &fonts, form_resources, multiply(matrix, ctm));
+        self.nested -= 1;
+        if let Some(id) = id {
+            self.visited.remove(&id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use alloc::vec;
+
+    fn run(text: &str, x: f32, y: f32, width: f32) -> TextRun {
+        TextRun {
+            page: 0,
+            text: text.to_string(),
+            x,
+            y,
+            width,
+            font_size: 10.0,
+            mcid: None,
+        }
+    }
+
+    #[test]
+    fn lines_are_rebuilt_by_position() {
+        // A two-column table drawn column by column, with a baseline wobble in the second row
+        let runs = vec![
+            run("Name", 72.0, 700.0, 20.0),
+            run("Jose", 72.0, 686.0, 20.0),
+            run("Amount", 300.0, 700.0, 30.0),
+            run("120", 300.0, 687.5, 15.0),
+            // Drawn in two parts with no gap between them
+            run(".00", 315.0, 687.0, 15.0),
+        ];
+        assert_eq!(layout_text(&runs), "Name Amount\nJose 120.00");
+    }
+
+    #[test]
+    fn words_are_boxed_across_runs() {
+        let runs = vec![
+            run("Total: 12", 72.0, 700.0, 90.0),
+            // Continues the last word without a gap
+            run("0.50", 162.0, 700.0, 40.0),
+            run("paid", 72.0, 686.0, 40.0),
+        ];
+        let words = words(&runs);
+        let texts: Vec<&str> = words.iter().map(|word| word.text.as_str()).collect();
+        assert_eq!(texts, ["Total:", "120.50", "paid"]);
+        assert_eq!(
+            (words[1].x, words[1].y, words[1].width, words[1].height),
+            (142.0, 700.0, 60.0, 10.0)
+        );
+        assert_eq!((words[2].x, words[2].y), (72.0, 686.0));
+    }
+
+    #[test]
+    fn matrices_compose() {
+        let scaled = multiply(translate(10.0, 20.0), [2.0, 0.0, 0.0, 2.0, 5.0, 5.0]);
+        assert_eq!(scaled, [2.0, 0.0, 0.0, 2.0, 25.0, 45.0]);
+    }
+}
@@ -1,34 +1,563 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+#[macro_use]
+mod trace;
+
 pub mod parser_utils;
 pub mod types;
 
+mod annotation;
 mod cmap;
+mod crypt;
 mod encoding;
+mod filters;
 mod font;
+mod font_program;
+mod glyph_list;
+mod image;
+mod layout;
 mod parser;
+mod partial;
+mod script;
+mod structure;
+mod xref;
+
+/// Internal parsers re-exported for the fuzz targets. Not a stable API.
+#[cfg(feature = "fuzzing")]
+#[doc(hidden)]
+pub mod fuzzing {
+    pub use crate::cmap::parse_cmap;
+
+    pub fn parse_content_tokens(data: &[u8]) -> Vec<crate::Token> {
+        crate::parse_content_tokens(data)
+    }
+}
 
 extern crate alloc;
 
-use crate::cmap::decode_bytes;
-use crate::font::collect_fonts_from_resources;
+// Without std the maps fall back to B-trees; every key type used here is `Ord`
+#[cfg(not(feature = "std"))]
+pub(crate) use alloc::collections::{BTreeMap as HashMap, BTreeSet as HashSet};
+#[cfg(feature = "std")]
+pub(crate) use std::collections::{HashMap, HashSet};
+
+use crate::cmap::{decode_bytes, utf16_be_text};
+use crate::encoding::pdf_doc_to_unicode;
+use crate::font::{collect_fonts_from_resources, is_word_gap, string_advance};
 use crate::parser::Parser;
 use crate::parser_utils::{
     fold_array_tokens, is_delimiter, parse_hex_string, parse_literal_string, parse_name,
     parse_number,
 };
-use crate::types::{PageContent, PdfError, PdfFont, PdfObj, PdfStream, Token};
-use alloc::string::String;
+use crate::types::{
+    DocumentInfo, DocumentSecurityStore, ExtractOptions, FormField, FormFieldType, Normalization,
+    Page, PageContent, PageText, PageTokens, ParseWarning, PdfError, PdfFont, PdfImage, PdfObj,
+    PdfStream, RecoveredPdf, SignatureField, StructElement, TextMatch, TextRun, Token,
+};
+use alloc::borrow::Cow;
+use alloc::collections::{btree_map, BTreeMap};
+use alloc::format;
+use alloc::string::{String, ToString};
 use alloc::vec::Vec;
+use core::str;
 use miniz_oxide::inflate::decompress_to_vec_zlib;
-use std::collections::HashMap;
-use std::collections::HashSet;
-use std::str;
+
+type Decompress<'a> = &'a dyn Fn(&[u8]) -> Result<Vec<u8>, PdfError>;
 
 /// Extracts text from a PDF and returns per-page strings
 pub fn extract_text(pdf_bytes: Vec<u8>) -> Result<Vec<String>, PdfError> {
-    let (page_content, objects) = parse_pdf(&pdf_bytes)?;
-    let text_per_page = extract_text_from_document(&page_content, &objects)
-        .map_err(|_| PdfError::ParseError("text extraction failed"))?;
-    Ok(text_per_page)
+    extract_text_with_options(pdf_bytes, &ExtractOptions::default())
+}
+
+/// Extracts per-page text like `extract_text`, tuned by `options`. The default options produce
+/// exactly the output of `extract_text`.
+pub fn extract_text_with_options(
+    pdf_bytes: Vec<u8>,
+    options: &ExtractOptions,
+) -> Result<Vec<String>, PdfError> {
+    let (objects, trailer) = parse_objects_for(&pdf_bytes, options)?;
+    map_pages(
+        &objects,
+        &trailer,
+        options.lenient,
+        options.max_pages,
+        |_, page| normalized_page_text(page, &objects, options),
+    )
+}
+
+/// Extracts per-page text as of `revision`, 0 being the original document and each incremental
+/// update adding one. Comparing the revision a signature covers with the latest one shows what
+/// was added after signing.
+pub fn extract_text_at_revision(
+    pdf_bytes: &[u8],
+    revision: usize,
+) -> Result<Vec<String>, PdfError> {
+    let options = ExtractOptions {
+        revision: Some(revision),
+        ..ExtractOptions::default()
+    };
+    extract_text_with_options(pdf_bytes.to_vec(), &options)
+}
+
+/// Extracts the text of a single page. Only the page tree and the objects the page refers to are
+/// parsed, and content streams and fonts of the other pages are never decoded, so previewing one
+/// page of a long document stays cheap. `max_pages` is ignored.
+pub fn extract_page_text(
+    pdf_bytes: &[u8],
+    page: usize,
+    options: &ExtractOptions,
+) -> Result<PageText, PdfError> {
+    let (objects, trailer) = match partial::page_objects(pdf_bytes, options.revision, page) {
+        Some(parsed) => parsed,
+        None => parse_objects_for(pdf_bytes, options)?,
+    };
+    let page_content = load_pages(&objects, &trailer, options.lenient, Some(page))?;
+    let content = page_content
+        .get(page)
+        .ok_or(PdfError::ParseError("page out of bounds"))?;
+    let text = normalized_page_text(content, &objects, options);
+    Ok(PageText {
+        text,
+        page_count: page_content.len(),
+    })
+}
+
+/// Extracts the text of page `page_index`, the same as `extract_text(pdf_bytes)[page_index]`
+/// without reading the rest of the document (see `extract_page_text`).
+pub fn extract_text_page(pdf_bytes: &[u8], page_index: usize) -> Result<String, PdfError> {
+    Ok(extract_page_text(pdf_bytes, page_index, &ExtractOptions::default())?.text)
+}
+
+/// A parsed PDF whose pages are decoded one at a time, so callers can report progress or yield
+/// between pages of a long document.
+pub struct PdfDocument<'a> {
+    objects: HashMap<(u32, u16), PdfObj<'a>>,
+    trailer: HashMap<String, PdfObj<'a>>,
+    page_count: usize,
+    options: ExtractOptions,
+}
+
+impl<'a> PdfDocument<'a> {
+    /// Parses all objects and counts the pages without decoding any page content.
+    pub fn parse(pdf_bytes: &'a [u8], options: &ExtractOptions) -> Result<Self, PdfError> {
+        let (objects, trailer) = parse_objects_for(pdf_bytes, options)?;
+        let page_count = page_nodes(&objects, &trailer)?.len();
+        Ok(Self {
+            objects,
+            trailer,
+            page_count,
+            options: options.clone(),
+        })
+    }
+
+    /// Number of pages to extract: the document's page count, capped by `max_pages`.
+    pub fn page_count(&self) -> usize {
+        self.options
+            .max_pages
+            .map_or(self.page_count, |max| max.min(self.page_count))
+    }
+
+    /// Decodes and extracts the text of one page, exactly as `extract_text_with_options` would.
+    pub fn page_text(&self, page: usize) -> Result<String, PdfError> {
+        if page >= self.page_count() {
+            return Err(PdfError::ParseError("page out of bounds"));
+        }
+        let nodes = page_nodes(&self.objects, &self.trailer)?;
+        let content =
+            tolerate_page_error(load_page(&nodes[page], &self.objects), self.options.lenient)?;
+        Ok(normalized_page_text(&content, &self.objects, &self.options))
+    }
+}
+
+/// Finds all non-overlapping occurrences of `needle` in extracted page text, optionally limited
+/// to a single page. Offsets index the same text that `verify_text` checks against.
+pub fn find_text(pages: &[String], needle: &str, page: Option<usize>) -> Vec<TextMatch> {
+    if needle.is_empty() {
+        return Vec::new();
+    }
+    pages
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| page.is_none_or(|p| p == *index))
+        .flat_map(|(index, text)| {
+            text.match_indices(needle)
+                .map(move |(offset, found)| TextMatch {
+                    page: index,
+                    offset,
+                    len: found.len(),
+                })
+        })
+        .collect()
+}
+
+/// Extracts the text with `options` and finds every occurrence of `needle` in it, like
+/// `find_text`. Offsets refer to the text `options` produce, so a claim verified with the same
+/// options holds at any of them.
+pub fn find_substring(
+    pdf_bytes: &[u8],
+    needle: &str,
+    options: &ExtractOptions,
+) -> Result<Vec<TextMatch>, PdfError> {
+    let pages = extract_text_with_options(pdf_bytes.to_vec(), options)?;
+    Ok(find_text(&pages, needle, None))
+}
+
+/// Summarises a PDF without verifying its signature: per-page text sizes and the text entries
+/// of the trailer `/Info` dictionary.
+pub fn document_info(pdf_bytes: &[u8]) -> Result<DocumentInfo, PdfError> {
+    let (objects, trailer) = parse_objects(pdf_bytes, None)?;
+    let page_char_counts = map_pages(&objects, &trailer, false, None, |_, page| {
+        extract_text_from_page(page, &objects).chars().count()
+    })?;
+
+    let info = match trailer.get("Info") {
+        Some(PdfObj::Reference(id)) => objects.get(id),
+        other => other,
+    };
+    let mut metadata = BTreeMap::new();
+    if let Some(PdfObj::Dictionary(entries)) = info {
+        for (key, value) in entries {
+            if let PdfObj::String(bytes) = value {
+                metadata.insert(key.clone(), decode_text_string(bytes));
+            }
+        }
+    }
+
+    Ok(DocumentInfo {
+        page_char_counts,
+        metadata,
+    })
+}
+
+/// Number of revisions in the document: the original plus one for each incremental update.
+/// Any of them can be selected with `ExtractOptions::revision`.
+pub fn revision_count(pdf_bytes: &[u8]) -> Result<usize, PdfError> {
+    if let Some(revisions) = xref::read_revisions(pdf_bytes) {
+        return Ok(revisions.len());
+    }
+    // Without a usable cross-reference chain, count the `startxref` each revision ends with
+    scan_objects(pdf_bytes, None, None)?;
+    let sections = pdf_bytes.windows(9).filter(|w| *w == b"startxref").count();
+    Ok(sections.max(1))
+}
+
+/// Lists the terminal fields of the document's interactive form (`/AcroForm`) with their fully
+/// qualified names, types and values, as of the latest incremental update. Returns an empty list
+/// for documents without a form.
+pub fn extract_form_fields(pdf_bytes: &[u8]) -> Result<Vec<FormField>, PdfError> {
+    let (objects, trailer) = parse_objects(pdf_bytes, None)?;
+    let mut result = Vec::new();
+    for_each_form_field(&objects, &trailer, |name, field_type, dict| {
+        result.push(FormField {
+            name,
+            field_type,
+            value: form_field_value(resolve(dict.get("V"), &objects), &objects),
+        });
+    })?;
+    Ok(result)
+}
+
+/// Lists the signed signature fields of the document's interactive form with the `/ByteRange`
+/// and `/Contents` of their signature dictionaries, as of the latest incremental update. The
+/// signature is neither extracted from the signed bytes nor verified; fields without a `/V`
+/// are left out.
+pub fn extract_signature_fields(pdf_bytes: &[u8]) -> Result<Vec<SignatureField>, PdfError> {
+    let (objects, trailer) = parse_objects(pdf_bytes, None)?;
+    let mut result = Vec::new();
+    for_each_form_field(&objects, &trailer, |name, field_type, dict| {
+        if field_type != FormFieldType::Signature {
+            return;
+        }
+        let Some(PdfObj::Dictionary(value)) = resolve(dict.get("V"), &objects) else {
+            return;
+        };
+        let name_of = |key| match resolve(value.get(key), &objects) {
+            Some(PdfObj::Name(name)) => Some(name.clone()),
+            _ => None,
+        };
+        let byte_range = match resolve(value.get("ByteRange"), &objects) {
+            Some(PdfObj::Array(items)) => items
+                .iter()
+                .map(|item| match resolve(Some(item), &objects) {
+                    Some(&PdfObj::Number(n)) if n >= 0.0 && n == (n as usize) as f64 => {
+                        Some(n as usize)
+                    }
+                    _ => None,
+                })
+                .collect::<Option<Vec<usize>>>(),
+            _ => None,
+        };
+        let (Some(byte_range), Some(PdfObj::String(contents))) =
+            (byte_range, resolve(value.get("Contents"), &objects))
+        else {
+            return;
+        };
+        result.push(SignatureField {
+            name,
+            sig_type: name_of("Type"),
+            sub_filter: name_of("SubFilter"),
+            byte_range,
+            contents: contents.clone(),
+        });
+    })?;
+    Ok(result)
+}
+
+/// Reads the document security store (`/DSS` in the catalog) that PAdES-LT documents keep
+/// validation data in: the decoded streams of its `/Certs`, `/OCSPs` and `/CRLs` arrays, as of
+/// the latest incremental update. Returns an empty store for documents without one. Whether the
+/// data is well formed, or relevant to any signature, is not checked.
+pub fn extract_dss(pdf_bytes: &[u8]) -> Result<DocumentSecurityStore, PdfError> {
+    let (objects, trailer) = parse_objects(pdf_bytes, None)?;
+    let Some(PdfObj::Dictionary(catalog)) = resolve(trailer.get("Root"), &objects) else {
+        return Err(PdfError::ParseError("Catalog object is not a dictionary"));
+    };
+    let Some(PdfObj::Dictionary(dss)) = resolve(catalog.get("DSS"), &objects) else {
+        return Ok(DocumentSecurityStore::default());
+    };
+    let decompress =
+        |bytes: &[u8]| decompress_to_vec_zlib(bytes).map_err(|_| PdfError::DecompressionError);
+    let streams = |key| -> Result<Vec<Vec<u8>>, PdfError> {
+        let Some(PdfObj::Array(items)) = resolve(dss.get(key), &objects) else {
+            return Ok(Vec::new());
+        };
+        let mut result = Vec::new();
+        for item in items {
+            if let Some(PdfObj::Stream(stream)) = resolve(Some(item), &objects) {
+                result.push(decoded_content(stream, &decompress)?.into_owned());
+            }
+        }
+        Ok(result)
+    };
+    Ok(DocumentSecurityStore {
+        certs: streams("Certs")?,
+        ocsps: streams("OCSPs")?,
+        crls: streams("CRLs")?,
+    })
+}
+
+/// Calls `visit` with the fully qualified name, type and dictionary of every terminal field of
+/// the `/AcroForm`, if there is one.
+fn for_each_form_field<'o, 'a>(
+    objects: &'o HashMap<(u32, u16), PdfObj<'a>>,
+    trailer: &'o HashMap<String, PdfObj<'a>>,
+    mut visit: impl FnMut(String, FormFieldType, &'o HashMap<String, PdfObj<'a>>),
+) -> Result<(), PdfError> {
+    let Some(PdfObj::Dictionary(catalog)) = resolve(trailer.get("Root"), objects) else {
+        return Err(PdfError::ParseError("Catalog object is not a dictionary"));
+    };
+    let Some(PdfObj::Dictionary(acro_form)) = resolve(catalog.get("AcroForm"), objects) else {
+        return Ok(());
+    };
+    let Some(PdfObj::Array(fields)) = resolve(acro_form.get("Fields"), objects) else {
+        return Ok(());
+    };
+
+    let mut visited = HashSet::new();
+    for field in fields {
+        collect_form_field(field, "", None, objects, &mut visited, &mut visit);
+    }
+    Ok(())
+}
+
+/// Lists the image XObjects each page draws, including those inside form XObjects. Flate and
+/// unfiltered images come back decoded; see `PdfImage::luminance` for a grayscale view.
+pub fn extract_images(pdf_bytes: &[u8]) -> Result<Vec<PdfImage>, PdfError> {
+    let (objects, trailer) = parse_objects(pdf_bytes, None)?;
+    let images = map_pages(&objects, &trailer, false, None, |index, page| {
+        image::page_images(page, index, &objects)
+    })?;
+    Ok(images.concat())
+}
+
+/// Lists the text each page draws with its position, in page order and content stream order
+/// within a page. Text inside form XObjects and tiling patterns is placed through their
+/// `/Matrix`, and text shown by Type3 glyph procedures through the font's `/FontMatrix`.
+pub fn extract_text_positions(pdf_bytes: &[u8]) -> Result<Vec<TextRun>, PdfError> {
+    let (objects, trailer) = parse_objects(pdf_bytes, None)?;
+    let runs = map_pages(&objects, &trailer, false, None, |index, page| {
+        layout::text_runs(page, index, &objects)
+    })?;
+    Ok(runs.concat())
+}
+
+/// Extracts every page with its text, word boxes, fonts, rotation and media box. `options`
+/// apply as in `extract_text_with_options`; word boxes include annotation text when
+/// `annotations` is set.
+pub fn extract_pages(pdf_bytes: &[u8], options: &ExtractOptions) -> Result<Vec<Page>, PdfError> {
+    let (objects, trailer) = parse_objects_for(pdf_bytes, options)?;
+    map_pages(
+        &objects,
+        &trailer,
+        options.lenient,
+        options.max_pages,
+        |index, page| {
+            let (runs, fonts) = layout::page_runs(page, index, &objects, options.annotations);
+            let mut words = layout::words(&runs);
+            for word in &mut words {
+                if options.unicode_normalization {
+                    word.text = script::nfc(&word.text);
+                }
+                if options.bidi {
+                    word.text = script::reorder(&word.text);
+                }
+            }
+            Page {
+                index,
+                text: normalized_page_text(page, &objects, options),
+                words,
+                fonts: fonts.into_iter().collect(),
+                rotation: page.rotation,
+                media_box: page.media_box.unwrap_or([0.0, 0.0, 612.0, 792.0]),
+            }
+        },
+    )
+}
+
+/// Tokenizes every page's content with its fonts resolved, for callers that pick fields out of
+/// the operators themselves (the string after a given label, the cell of a `TJ` array)
+/// instead of searching the flattened text.
+pub fn extract_structured(pdf_bytes: &[u8]) -> Result<Vec<PageTokens>, PdfError> {
+    let (objects, trailer) = parse_objects(pdf_bytes, None)?;
+    map_pages(&objects, &trailer, false, None, |_, page| PageTokens {
+        tokens: content_tokens(&page.content_streams),
+        fonts: page.fonts.clone(),
+    })
+}
+
+/// Reads the logical structure of a tagged PDF (`/StructTreeRoot`): its elements in logical
+/// reading order with their roles, each holding the text of the marked content it refers to.
+/// Tables come back as `Table`, `TR` and `TD` elements however their cells are drawn. Returns
+/// an empty list for untagged documents.
+pub fn extract_structure(pdf_bytes: &[u8]) -> Result<Vec<StructElement>, PdfError> {
+    let (objects, trailer) = parse_objects(pdf_bytes, None)?;
+    let Some(root) = structure::tree_root(&objects, &trailer) else {
+        return Ok(Vec::new());
+    };
+    let runs = map_pages(&objects, &trailer, false, None, |index, page| {
+        layout::text_runs(page, index, &objects)
+    })?;
+    let pages: Vec<&PdfObj> = page_nodes(&objects, &trailer)?
+        .iter()
+        .map(|node| node.page)
+        .collect();
+    Ok(structure::elements(root, &objects, &pages, &runs.concat()))
+}
+
+// Follow an indirect reference, if any
+fn resolve<'o, 'a>(
+    obj: Option<&'o PdfObj<'a>>,
+    objects: &'o HashMap<(u32, u16), PdfObj<'a>>,
+) -> Option<&'o PdfObj<'a>> {
+    match obj {
+        Some(PdfObj::Reference(id)) => objects.get(id),
+        other => other,
+    }
+}
+
+// An array of exactly `N` numbers, such as a rectangle or a matrix
+fn numbers<const N: usize>(
+    obj: Option<&PdfObj>,
+    objects: &HashMap<(u32, u16), PdfObj>,
+) -> Option<[f32; N]> {
+    let Some(PdfObj::Array(values)) = resolve(obj, objects) else {
+        return None;
+    };
+    if values.len() != N {
+        return None;
+    }
+    let mut out = [0.0; N];
+    for (slot, value) in out.iter_mut().zip(values) {
+        match resolve(Some(value), objects) {
+            Some(PdfObj::Number(n)) => *slot = *n as f32,
+            _ => return None,
+        }
+    }
+    Some(out)
+}
+
+// Fields form a tree: partial names (/T) are joined with '.', and /FT is inherited by kids.
+// Kids without a /T are widget annotations of their parent, which is then a terminal field.
+fn collect_form_field<'o, 'a>(
+    node: &'o PdfObj<'a>,
+    parent_name: &str,
+    inherited_type: Option<FormFieldType>,
+    objects: &'o HashMap<(u32, u16), PdfObj<'a>>,
+    visited: &mut HashSet<(u32, u16)>,
+    visit: &mut impl FnMut(String, FormFieldType, &'o HashMap<String, PdfObj<'a>>),
+) {
+    let node = match node {
+        PdfObj::Reference(id) => {
+            if !visited.insert(*id) {
+                return;
+            }
+            match objects.get(id) {
+                Some(obj) => obj,
+                None => return,
+            }
+        }
+        other => other,
+    };
+    let dict = match node {
+        PdfObj::Dictionary(dict) => dict,
+        PdfObj::Stream(stream) => &stream.dict,
+        _ => return,
+    };
+
+    let name = match resolve(dict.get("T"), objects) {
+        Some(PdfObj::String(partial)) if parent_name.is_empty() => decode_text_string(partial),
+        Some(PdfObj::String(partial)) => {
+            format!("{}.{}", parent_name, decode_text_string(partial))
+        }
+        _ => parent_name.to_string(),
+    };
+    let field_type = match resolve(dict.get("FT"), objects) {
+        Some(PdfObj::Name(ft)) => Some(FormFieldType::from_name(ft)),
+        _ => inherited_type,
+    };
+
+    let kids = match resolve(dict.get("Kids"), objects) {
+        Some(PdfObj::Array(kids)) => kids.as_slice(),
+        _ => &[],
+    };
+    let has_child_fields = kids.iter().any(|kid| match resolve(Some(kid), objects) {
+        Some(PdfObj::Dictionary(kid)) => kid.contains_key("T"),
+        Some(PdfObj::Stream(kid)) => kid.dict.contains_key("T"),
+        _ => false,
+    });
+
+    if has_child_fields {
+        for kid in kids {
+            collect_form_field(kid, &name, field_type, objects, visited, visit);
+        }
+    } else {
+        visit(name, field_type.unwrap_or(FormFieldType::Unknown), dict);
+    }
+}
+
+fn form_field_value(
+    value: Option<&PdfObj>,
+    objects: &HashMap<(u32, u16), PdfObj>,
+) -> Option<String> {
+    match value? {
+        PdfObj::String(bytes) => Some(decode_text_string(bytes)),
+        PdfObj::Name(name) => Some(name.clone()),
+        PdfObj::Number(n) => Some(n.to_string()),
+        PdfObj::Array(items) => {
+            let options: Vec<String> = items
+                .iter()
+                .filter_map(|item| form_field_value(resolve(Some(item), objects), objects))
+                .collect();
+            Some(options.join(", "))
+        }
+        _ => None,
+    }
+}
+
+/// Decodes a PDF text string, which is either UTF-16BE with a byte order mark or
+/// PDFDocEncoding.
+pub fn decode_text_string(bytes: &[u8]) -> String {
+    utf16_be_text(bytes).unwrap_or_else(|| bytes.iter().map(|&b| pdf_doc_to_unicode(b)).collect())
 }
 
 /// Extracts text from all pages of a document.
@@ -36,6 +565,7 @@ pub fn extract_text_from_document(
     pages: &[PageContent],
     objects: &HashMap<(u32, u16), PdfObj>,
 ) -> Result<Vec<String>, String> {
+    let _timed = timed!("decode_text", pages = pages.len());
     let mut pages_text = Vec::new();
     for page in pages {
         pages_text.push(extract_text_from_page(page, objects));
@@ -47,8 +577,72 @@ pub fn extract_text_from_page(
     page: &PageContent,
     _objects: &HashMap<(u32, u16), PdfObj>,
 ) -> String {
+    canonicalize(&extract_raw_text_from_page(page, _objects))
+}
+
+fn normalized_page_text(
+    page: &PageContent,
+    objects: &HashMap<(u32, u16), PdfObj>,
+    options: &ExtractOptions,
+) -> String {
+    let mut text = if let Normalization::Layout = options.normalization {
+        let mut runs = layout::text_runs(page, 0, objects);
+        if options.annotations {
+            runs.extend(layout::annotation_runs(page, 0, objects));
+        }
+        canonicalize(&layout::layout_text(&runs))
+    } else {
+        let mut text = extract_raw_text_from_page(page, objects);
+        if options.annotations {
+            text.push_str(&annotation_text(page, objects));
+        }
+        match options.normalization {
+            Normalization::Raw => text,
+            _ => canonicalize(&text),
+        }
+    };
+    if options.unicode_normalization {
+        text = script::nfc(&text);
+    }
+    if options.bidi {
+        text = script::reorder(&text);
+    }
+    text
+}
+
+// Raw text of the page's annotation appearances, each on its own line
+fn annotation_text(page: &PageContent, objects: &HashMap<(u32, u16), PdfObj>) -> String {
     let mut output = String::new();
-    let tokens = parse_content_tokens(&page.content_streams.concat());
+    for appearance in annotation::appearances(page, objects) {
+        output.push('\n');
+        extract_from_tokens(
+            &parse_content_tokens(&appearance.content),
+            &appearance.fonts,
+            appearance.resources,
+            &mut output,
+            objects,
+            &mut HashSet::new(),
+        );
+    }
+    output
+}
+
+// Runs of whitespace collapsed to one space, empty lines dropped
+fn canonicalize(text: &str) -> String {
+    text.lines()
+        .map(|l| l.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|l| !l.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// Text as laid out by the content stream operators, before whitespace is collapsed
+fn extract_raw_text_from_page(
+    page: &PageContent,
+    _objects: &HashMap<(u32, u16), PdfObj>,
+) -> String {
+    let mut output = String::new();
+    let tokens = content_tokens(&page.content_streams);
     let mut visited = HashSet::new();
     extract_from_tokens(
         &tokens,
@@ -59,23 +653,38 @@ pub fn extract_text_from_page(
         &mut visited,
     );
     output
-        .lines()
-        .map(|l| l.split_whitespace().collect::<Vec<_>>().join(" "))
-        .filter(|l| !l.is_empty())
-        .collect::<Vec<_>>()
-        .join("\n")
 }
 
-// Use a recursive function to traverse the Pages tree
-fn traverse_pages(
+// Page attributes a `/Pages` node passes down to the pages that do not set them. `'o` borrows
+// the parsed objects, `'a` the document bytes.
+#[derive(Clone, Copy, Default)]
+struct Inherited<'o, 'a> {
+    resources: Option<&'o HashMap<String, PdfObj<'a>>>,
+    media_box: Option<&'o PdfObj<'a>>,
+    rotate: Option<&'o PdfObj<'a>>,
+}
+
+// A leaf of the page tree: a page dictionary, or a page given as a stream (dictionary and
+// content in one), with the attributes it inherits. Nothing of the page is decoded yet.
+#[derive(Clone, Copy)]
+struct PageNode<'o, 'a> {
+    page: &'o PdfObj<'a>,
+    inherited: Inherited<'o, 'a>,
+}
+
+// Use a recursive function to traverse the Pages tree. `visited` holds the nodes entered so
+// far, so a Kids entry pointing back up the tree is an error instead of unbounded recursion
+fn traverse_pages<'o, 'a>(
     obj_id: (u32, u16),
-    objects: &HashMap<(u32, u16), PdfObj>,
-    inherited_resources: Option<&HashMap<String, PdfObj>>,
-    result: &mut Vec<PageContent>,
-    decompress: &dyn Fn(&[u8]) -> Result<Vec<u8>, PdfError>,
+    objects: &'o HashMap<(u32, u16), PdfObj<'a>>,
+    inherited: Inherited<'o, 'a>,
+    result: &mut Vec<PageNode<'o, 'a>>,
+    visited: &mut HashSet<(u32, u16)>,
 ) -> Result<(), PdfError> {
     let obj = if obj_id == (0, 0) {
         return Err(PdfError::ParseError("Pages object missing"));
+    } else if !visited.insert(obj_id) {
+        return Err(PdfError::ParseError("Cycle in page tree"));
     } else {
         objects
             .get(&obj_id)
@@ -93,20 +702,25 @@ fn traverse_pages(
                             if let Some(PdfObj::Dictionary(res_dict)) = objects.get(res_ref) {
                                 Some(res_dict)
                             } else {
-                                inherited_resources
+                                inherited.resources
                             }
                         } else {
-                            inherited_resources
+                            inherited.resources
                         };
+                    let inherited = Inherited {
+                        resources: new_inherited_res,
+                        media_box: dict.get("MediaBox").or(inherited.media_box),
+                        rotate: dict.get("Rotate").or(inherited.rotate),
+                    };
 
                     let kids_obj = dict
                         .get("Kids")
                         .ok_or(PdfError::ParseError("Pages node missing Kids"))?;
                     let kids_list = match kids_obj {
-                        PdfObj::Array(arr) => arr.clone(),
+                        PdfObj::Array(arr) => arr,
                         PdfObj::Reference(kid_ref) => {
                             if let Some(PdfObj::Array(arr)) = objects.get(kid_ref) {
-                                arr.clone()
+                                arr
                             } else {
                                 return Err(PdfError::ParseError("Kids reference is not an array"));
                             }
@@ -117,31 +731,22 @@ fn traverse_pages(
                         match kid {
                             PdfObj::Reference(child_id) => {
                                 // Recurse for each kid
-                                traverse_pages(
-                                    child_id,
-                                    objects,
-                                    new_inherited_res.or(inherited_resources),
-                                    result,
-                                    &decompress,
-                                )?;
+                                traverse_pages(*child_id, objects, inherited, result, visited)?;
                             }
-                            PdfObj::Dictionary(ref child_dict) => {
+                            PdfObj::Dictionary(child_dict) => {
                                 if let Some(PdfObj::Name(t)) = child_dict.get("Type") {
                                     if t == "Page" {
-                                        process_page_dict(
-                                            child_dict,
-                                            new_inherited_res.or(inherited_resources),
-                                            objects,
-                                            result,
-                                            &decompress,
-                                        )?;
+                                        result.push(PageNode {
+                                            page: kid,
+                                            inherited,
+                                        });
                                     } else if t == "Pages" {
                                         traverse_pages(
                                             (0, 0),
                                             objects,
-                                            new_inherited_res.or(inherited_resources),
+                                            inherited,
                                             result,
-                                            &decompress,
+                                            visited,
                                         )?;
                                     }
                                 }
@@ -150,7 +755,10 @@ fn traverse_pages(
                         }
                     }
                 } else if type_str == "Page" {
-                    process_page_dict(dict, inherited_resources, objects, result, &decompress)?;
+                    result.push(PageNode {
+                        page: obj,
+                        inherited,
+                    });
                 } else {
                     return Err(PdfError::ParseError("Unknown object in page tree"));
                 }
@@ -161,7 +769,10 @@ fn traverse_pages(
         PdfObj::Stream(stream) => {
             if let Some(PdfObj::Name(t)) = stream.dict.get("Type") {
                 if t == "Page" {
-                    process_page_stream(stream, inherited_resources, objects, result, &decompress)?;
+                    result.push(PageNode {
+                        page: obj,
+                        inherited,
+                    });
                 } else if t == "Pages" {
                     return Err(PdfError::ParseError(
                         "Pages object in stream form is not supported",
@@ -178,14 +789,39 @@ fn traverse_pages(
     Ok(())
 }
 
+// Decode a page's content streams and fonts
+fn load_page<'a>(
+    node: &PageNode<'_, 'a>,
+    objects: &HashMap<(u32, u16), PdfObj<'a>>,
+) -> Result<PageContent<'a>, PdfError> {
+    let decompress =
+        |bytes: &[u8]| decompress_to_vec_zlib(bytes).map_err(|_| PdfError::DecompressionError);
+    match node.page {
+        PdfObj::Stream(stream) => process_page_stream(stream, node.inherited, objects, &decompress),
+        PdfObj::Dictionary(dict) => process_page_dict(dict, node.inherited, objects, &decompress),
+        _ => Err(PdfError::ParseError("Invalid object in page tree")),
+    }
+}
+
+// In lenient mode a page that fails to load is kept as an empty page, so page indices still match
+// the document
+fn tolerate_page_error(
+    outcome: Result<PageContent<'_>, PdfError>,
+    lenient: bool,
+) -> Result<PageContent<'_>, PdfError> {
+    match outcome {
+        Err(_) if lenient => Ok(PageContent::default()),
+        other => other,
+    }
+}
+
 // Helper to process a page given as a dictionary (no direct content in object)
-fn process_page_dict(
-    page_dict: &HashMap<String, PdfObj>,
-    inherited_res: Option<&HashMap<String, PdfObj>>,
-    objects: &HashMap<(u32, u16), PdfObj>,
-    result: &mut Vec<PageContent>,
-    decompress: &dyn Fn(&[u8]) -> Result<Vec<u8>, PdfError>,
-) -> Result<(), PdfError> {
+fn process_page_dict<'a>(
+    page_dict: &HashMap<String, PdfObj<'a>>,
+    inherited: Inherited<'_, 'a>,
+    objects: &HashMap<(u32, u16), PdfObj<'a>>,
+    decompress: Decompress<'_>,
+) -> Result<PageContent<'a>, PdfError> {
     let empty_map = HashMap::new();
     let resources_dict = if let Some(PdfObj::Dictionary(res)) = page_dict.get("Resources") {
         res
@@ -193,29 +829,18 @@ fn process_page_dict(
         if let Some(PdfObj::Dictionary(res)) = objects.get(res_ref) {
             res
         } else {
-            inherited_res.unwrap_or(&empty_map)
+            inherited.resources.unwrap_or(&empty_map)
         }
     } else {
-        inherited_res.unwrap_or(&empty_map)
+        inherited.resources.unwrap_or(&empty_map)
     };
-    let mut content_streams: Vec<Vec<u8>> = Vec::new();
+    let mut content_streams = Vec::new();
     if let Some(content_obj) = page_dict.get("Contents") {
         match content_obj {
             PdfObj::Reference(stream_ref) => {
                 if let Some(obj) = objects.get(stream_ref) {
                     match obj {
-                        PdfObj::Stream(s) => {
-                            if let Some(filter) = s.dict.get("Filter") {
-                                handle_stream_filters(
-                                    filter,
-                                    &s.data,
-                                    decompress,
-                                    &mut content_streams,
-                                )?;
-                            } else {
-                                content_streams.push(s.data.clone());
-                            }
-                        }
+                        PdfObj::Stream(s) => content_streams.push(decoded_content(s, decompress)?),
                         _ => {
                             return Err(PdfError::ParseError("Content reference is not a stream"));
                         }
@@ -226,48 +851,91 @@ fn process_page_dict(
                 for item in arr {
                     if let PdfObj::Reference(stream_ref) = item {
                         if let Some(PdfObj::Stream(s)) = objects.get(stream_ref) {
-                            if let Some(filter) = s.dict.get("Filter") {
-                                handle_stream_filters(
-                                    filter,
-                                    &s.data,
-                                    decompress,
-                                    &mut content_streams,
-                                )?;
-                            } else {
-                                content_streams.push(s.data.clone());
-                            }
+                            content_streams.push(decoded_content(s, decompress)?);
                         }
                     }
                 }
             }
-            PdfObj::Stream(s) => {
-                if let Some(filter) = s.dict.get("Filter") {
-                    handle_stream_filters(filter, &s.data, decompress, &mut content_streams)?;
-                } else {
-                    content_streams.push(s.data.clone());
-                }
-            }
+            PdfObj::Stream(s) => content_streams.push(decoded_content(s, decompress)?),
             _ => {}
         }
     }
 
     let fonts_map = collect_fonts_from_resources(resources_dict, objects, decompress)?;
-    result.push(PageContent {
+    Ok(PageContent {
         content_streams,
         fonts: fonts_map,
         resources: resources_dict.clone(),
-    });
-    Ok(())
+        annotations: page_annotations(page_dict, objects),
+        media_box: page_media_box(page_dict, inherited, objects),
+        rotation: page_rotation(page_dict, inherited, objects),
+    })
 }
 
-// Helper to process a page represented as a stream object (Page dictionary + content in one)
-fn process_page_stream(
-    page_stream: &PdfStream,
-    inherited_res: Option<&HashMap<String, PdfObj>>,
+// A content stream with its filters applied. Data stored without a filter stays borrowed from
+// the document bytes.
+fn decoded_content<'a>(
+    stream: &PdfStream<'a>,
+    decompress: Decompress<'_>,
+) -> Result<Cow<'a, [u8]>, PdfError> {
+    let Some(filter) = stream.dict.get("Filter") else {
+        return Ok(stream.data.clone());
+    };
+    let mut decoded = Vec::new();
+    handle_stream_filters(
+        filter,
+        stream.dict.get("DecodeParms"),
+        &stream.data,
+        decompress,
+        &mut decoded,
+    )?;
+    Ok(Cow::Owned(decoded.concat()))
+}
+
+// `/MediaBox` of a page or its nearest ancestor, with the lower left corner first
+fn page_media_box(
+    page_dict: &HashMap<String, PdfObj>,
+    inherited: Inherited,
     objects: &HashMap<(u32, u16), PdfObj>,
-    result: &mut Vec<PageContent>,
-    decompress: &dyn Fn(&[u8]) -> Result<Vec<u8>, PdfError>,
-) -> Result<(), PdfError> {
+) -> Option<[f32; 4]> {
+    let [x0, y0, x1, y1] =
+        numbers::<4>(page_dict.get("MediaBox").or(inherited.media_box), objects)?;
+    Some([x0.min(x1), y0.min(y1), x0.max(x1), y0.max(y1)])
+}
+
+// `/Rotate` of a page or its nearest ancestor, in 0..360. Values that are not multiples of 90
+// are invalid and read as 0
+fn page_rotation(
+    page_dict: &HashMap<String, PdfObj>,
+    inherited: Inherited,
+    objects: &HashMap<(u32, u16), PdfObj>,
+) -> u16 {
+    match resolve(page_dict.get("Rotate").or(inherited.rotate), objects) {
+        Some(PdfObj::Number(degrees)) if degrees % 90.0 == 0.0 => {
+            (*degrees as i64).rem_euclid(360) as u16
+        }
+        _ => 0,
+    }
+}
+
+// Entries of a page's `/Annots`, which may itself be a reference
+fn page_annotations<'a>(
+    page_dict: &HashMap<String, PdfObj<'a>>,
+    objects: &HashMap<(u32, u16), PdfObj<'a>>,
+) -> Vec<PdfObj<'a>> {
+    match resolve(page_dict.get("Annots"), objects) {
+        Some(PdfObj::Array(annotations)) => annotations.clone(),
+        _ => Vec::new(),
+    }
+}
+
+// Helper to process a page represented as a stream object (Page dictionary + content in one)
+fn process_page_stream<'a>(
+    page_stream: &PdfStream<'a>,
+    inherited: Inherited<'_, 'a>,
+    objects: &HashMap<(u32, u16), PdfObj<'a>>,
+    decompress: Decompress<'_>,
+) -> Result<PageContent<'a>, PdfError> {
     let page_dict = &page_stream.dict;
     let resources_obj = page_dict.get("Resources");
 
@@ -278,64 +946,272 @@ fn process_page_stream(
             if let Some(PdfObj::Dictionary(res)) = objects.get(res_ref) {
                 res
             } else {
-                inherited_res.unwrap_or(&empty_map)
+                inherited.resources.unwrap_or(&empty_map)
             }
         }
-        _ => inherited_res.unwrap_or(&empty_map),
+        _ => inherited.resources.unwrap_or(&empty_map),
     };
 
-    let mut content_streams: Vec<Vec<u8>> = Vec::new();
-    if let Some(filter) = page_stream.dict.get("Filter") {
-        handle_stream_filters(filter, &page_stream.data, decompress, &mut content_streams)?;
-    } else {
-        content_streams.push(page_stream.data.clone());
-    }
+    let content_streams = alloc::vec![decoded_content(page_stream, decompress)?];
 
     let fonts_map = collect_fonts_from_resources(resources_dict, objects, decompress)?;
-    result.push(PageContent {
+    Ok(PageContent {
         content_streams,
         fonts: fonts_map,
         resources: resources_dict.clone(),
-    });
-    Ok(())
+        annotations: page_annotations(page_dict, objects),
+        media_box: page_media_box(page_dict, inherited, objects),
+        rotation: page_rotation(page_dict, inherited, objects),
+    })
 }
 
+/// Decodes a stream through its `/Filter` (a name or an array applied in order) and pushes
+/// the result. `decode_parms` is the matching `/DecodeParms`, a dictionary or an array with one
+/// entry (possibly `null`) per filter; Flate predictors 2 and 10-15 are reversed.
 pub fn handle_stream_filters(
     filter_obj: &PdfObj,
+    decode_parms: Option<&PdfObj>,
     data: &[u8],
-    decompress: &dyn Fn(&[u8]) -> Result<Vec<u8>, PdfError>,
+    decompress: Decompress<'_>,
     output_streams: &mut Vec<Vec<u8>>,
 ) -> Result<(), PdfError> {
-    match filter_obj {
-        PdfObj::Name(name) => {
-            if name == "FlateDecode" || name == "Flate" {
-                // Single Flate decode
-                let decompressed = decompress(data).map_err(|_| PdfError::DecompressionError)?;
-                output_streams.push(decompressed);
-            } else {
-                // Unsupported single filter
-                return Err(PdfError::ParseError("Unsupported filter"));
-            }
-        }
-        PdfObj::Array(filters) => {
-            // If multiple filters, handle only simplest case: a single Flate filter in array
-            if filters.len() == 1 {
-                return handle_stream_filters(&filters[0], data, decompress, output_streams);
-            } else {
-                return Err(PdfError::ParseError("Multiple filters not supported"));
-            }
-        }
-        _ => {
-            return Err(PdfError::ParseError("Invalid Filter entry"));
+    let _timed = timed!("stream_filter", bytes = data.len());
+    let filters: Vec<&PdfObj> = match filter_obj {
+        PdfObj::Name(_) => alloc::vec![filter_obj],
+        PdfObj::Array(filters) => filters.iter().collect(),
+        _ => return Err(PdfError::ParseError("Invalid Filter entry")),
+    };
+    let params: Vec<Option<&PdfObj>> = match decode_parms {
+        None | Some(PdfObj::Null) => alloc::vec![None; filters.len()],
+        Some(PdfObj::Array(params)) if params.len() == filters.len() => {
+            params.iter().map(Some).collect()
         }
+        Some(params @ PdfObj::Dictionary(_)) if filters.len() == 1 => alloc::vec![Some(params)],
+        Some(_) => return Err(PdfError::ParseError("DecodeParms does not match Filter")),
+    };
+
+    let mut decoded: Option<Vec<u8>> = None;
+    for (filter, params) in filters.into_iter().zip(params) {
+        let input = decoded.as_deref().unwrap_or(data);
+        let output = match filter {
+            PdfObj::Name(name) => match name.as_str() {
+                "FlateDecode" | "Fl" | "Flate" => {
+                    let inflated = decompress(input).map_err(|_| PdfError::DecompressionError)?;
+                    match params {
+                        Some(PdfObj::Dictionary(params)) => {
+                            filters::undo_predictor(inflated, |key| match params.get(key) {
+                                Some(PdfObj::Number(n)) => Some(*n),
+                                _ => None,
+                            })
+                            .ok_or(PdfError::ParseError("Unsupported predictor"))?
+                        }
+                        _ => inflated,
+                    }
+                }
+                "ASCIIHexDecode" | "AHx" => filters::ascii_hex_decode(input)?,
+                "ASCII85Decode" | "A85" => filters::ascii85_decode(input)?,
+                _ => return Err(PdfError::ParseError("Unsupported filter")),
+            },
+            _ => return Err(PdfError::ParseError("Invalid Filter entry")),
+        };
+        decoded = Some(output);
     }
+    output_streams.push(decoded.unwrap_or_else(|| data.to_vec()));
     Ok(())
 }
 
+// Pages and objects of a parsed PDF
+type ParsedPdf<'a> = (Vec<PageContent<'a>>, HashMap<(u32, u16), PdfObj<'a>>);
+
 // Parse an entire PDF byte slice and produce page content data
-pub fn parse_pdf(data: &[u8]) -> Result<(Vec<PageContent>, HashMap<(u32, u16), PdfObj>), PdfError> {
+pub fn parse_pdf(data: &[u8]) -> Result<ParsedPdf<'_>, PdfError> {
+    let (pages, objects, _trailer) = parse_document(data, false, None, None)?;
+    Ok((pages, objects))
+}
+
+/// Like `parse_pdf`, for damaged files such as real-world scans. When the cross-reference data
+/// is unusable the file is scanned for objects: an object whose `/Length` is wrong or whose
+/// `endobj` is missing is read anyway, one that still fails to parse is skipped up to the next
+/// `obj` keyword, and both are recorded as warnings. Pages that fail to load come back empty.
+pub fn parse_pdf_lenient(data: &[u8]) -> Result<RecoveredPdf<'_>, PdfError> {
+    let mut warnings = Vec::new();
+    let (objects, trailer) = read_objects(data, None, Some(&mut warnings))?;
+    let pages = load_pages(&objects, &trailer, true, None)?;
+    trace_event!(warnings = warnings.len(), "lenient parse done");
+    Ok(RecoveredPdf {
+        pages,
+        objects,
+        warnings,
+    })
+}
+
+// Pages, objects and trailer dictionary of a parsed PDF
+type ParsedDocument<'a> = (
+    Vec<PageContent<'a>>,
+    HashMap<(u32, u16), PdfObj<'a>>,
+    HashMap<String, PdfObj<'a>>,
+);
+
+// Like `parse_pdf`, but also returns the trailer dictionary. With `lenient`, pages that fail to
+// load are returned empty instead of failing the whole document. With `only`, every other page is
+// returned empty without being decoded. `revision` is as for `parse_objects`.
+fn parse_document(
+    data: &[u8],
+    lenient: bool,
+    only: Option<usize>,
+    revision: Option<usize>,
+) -> Result<ParsedDocument<'_>, PdfError> {
+    let _timed = timed!("parse_pdf", bytes = data.len(), lenient, only = ?only);
+    let (objects, trailer_dict) = parse_objects(data, revision)?;
+    trace_event!(objects = objects.len(), "objects parsed");
+    let pages = load_pages(&objects, &trailer_dict, lenient, only)?;
+    trace_event!(pages = pages.len(), "pages loaded");
+    Ok((pages, objects, trailer_dict))
+}
+
+// Objects by (id, generation) and the trailer dictionary
+type ParsedObjects<'a> = (HashMap<(u32, u16), PdfObj<'a>>, HashMap<String, PdfObj<'a>>);
+
+// Parse every indirect object and the trailer as of `revision` (0 is the original document,
+// `None` the latest incremental update), without walking the page tree. Objects of later
+// revisions replace earlier versions, so each id resolves to its newest generation. Objects are
+// read at the offsets of the cross-reference chain, or found by scanning the file if it is
+// damaged.
+fn parse_objects(data: &[u8], revision: Option<usize>) -> Result<ParsedObjects<'_>, PdfError> {
+    read_objects(data, revision, None)
+}
+
+// `parse_objects` as `options` select it: damaged objects are skipped when they are lenient
+fn parse_objects_for<'a>(
+    data: &'a [u8],
+    options: &ExtractOptions,
+) -> Result<ParsedObjects<'a>, PdfError> {
+    let mut warnings = Vec::new();
+    read_objects(
+        data,
+        options.revision,
+        options.lenient.then_some(&mut warnings),
+    )
+}
+
+// `parse_objects`, recovering from damaged objects if `warnings` is given (see
+// `parse_pdf_lenient`)
+fn read_objects<'a>(
+    data: &'a [u8],
+    revision: Option<usize>,
+    warnings: Option<&mut Vec<ParseWarning>>,
+) -> Result<ParsedObjects<'a>, PdfError> {
+    if let Some(revisions) = xref::read_revisions(data) {
+        let last = revision.unwrap_or(revisions.len() - 1);
+        if last >= revisions.len() {
+            return Err(PdfError::ParseError("revision out of bounds"));
+        }
+        if let Ok(parsed) = load_revision(data, &xref::merge_revisions(&revisions[..=last])) {
+            return Ok(parsed);
+        }
+        trace_event!("cross-reference offsets unusable, scanning objects");
+    }
+    scan_objects(data, revision, warnings)
+}
+
+// Read every object a revision's cross-reference entries point to
+fn load_revision<'a>(
+    data: &'a [u8],
+    revision: &xref::Revision<'a>,
+) -> Result<ParsedObjects<'a>, PdfError> {
+    if !revision.trailer.contains_key("Root") {
+        return Err(PdfError::ParseError("Trailer has no /Root"));
+    }
+    let mut objects: HashMap<(u32, u16), PdfObj> = HashMap::new();
+
+    // In file order, so a stream /Length that refers to an earlier object resolves
+    let mut offsets: Vec<(usize, u32, u16)> = revision
+        .entries
+        .iter()
+        .filter_map(|(&id, entry)| match *entry {
+            xref::XrefEntry::InUse { offset, generation } => Some((offset, id, generation)),
+            _ => None,
+        })
+        .collect();
+    offsets.sort_unstable();
+    for (offset, id, generation) in offsets {
+        let object = Some((id, generation));
+        if offset >= data.len() {
+            return Err(PdfError::in_object(
+                "Object offset out of bounds",
+                offset,
+                object,
+            ));
+        }
+        let mut parser = Parser::new(data);
+        parser.pos = offset;
+        let (key, value) = parse_indirect_object(&mut parser, &objects)?;
+        if key != (id, generation) {
+            return Err(PdfError::in_object(
+                "Object is not at its xref offset",
+                offset,
+                object,
+            ));
+        }
+        objects.insert(key, value);
+    }
+
+    if let Some(decryptor) = crypt::Decryptor::new(&revision.trailer, &objects)? {
+        decryptor.decrypt_objects(&mut objects);
+    }
+
+    let mut streams: BTreeMap<u32, HashMap<(u32, u16), PdfObj>> = BTreeMap::new();
+    for (&id, entry) in &revision.entries {
+        let xref::XrefEntry::Compressed { stream, .. } = *entry else {
+            continue;
+        };
+        let contained = match streams.entry(stream) {
+            btree_map::Entry::Occupied(entry) => entry.into_mut(),
+            btree_map::Entry::Vacant(entry) => entry.insert(expand_obj_stream(&objects, stream)?),
+        };
+        if let Some(value) = contained.remove(&(id, 0)) {
+            objects.insert((id, 0), value);
+        }
+    }
+    Ok((objects, revision.trailer.clone()))
+}
+
+/// The objects packed in object stream `stream`.
+fn expand_obj_stream(
+    objects: &HashMap<(u32, u16), PdfObj>,
+    stream: u32,
+) -> Result<HashMap<(u32, u16), PdfObj<'static>>, PdfError> {
+    let Some(PdfObj::Stream(obj_stream)) = objects.get(&(stream, 0)) else {
+        return Err(PdfError::ParseError("Object stream not found"));
+    };
+    let (Some(PdfObj::Number(first)), Some(PdfObj::Number(n))) =
+        (obj_stream.dict.get("First"), obj_stream.dict.get("N"))
+    else {
+        return Err(PdfError::ParseError("Object stream without /First or /N"));
+    };
+    let decoded = decoded_content(obj_stream, &|bytes| {
+        decompress_to_vec_zlib(bytes).map_err(|_| PdfError::DecompressionError)
+    })?;
+    let mut contained = HashMap::new();
+    parse_obj_stream(&decoded, *first as usize, *n as usize, &mut contained)?;
+    Ok(contained)
+}
+
+// Find objects by scanning for "<id> <gen> obj", for files whose cross-reference data is
+// missing or wrong. Revisions are counted by their `xref` sections.
+// With `warnings`, objects that fail to parse are recovered or skipped as described for
+// `parse_pdf_lenient`, and a missing trailer is replaced by one pointing at the catalog.
+fn scan_objects<'a>(
+    data: &'a [u8],
+    revision: Option<usize>,
+    mut warnings: Option<&mut Vec<ParseWarning>>,
+) -> Result<ParsedObjects<'a>, PdfError> {
     let mut parser = Parser::new(data);
     let mut objects: HashMap<(u32, u16), PdfObj> = HashMap::new();
+    let mut sections = 0;
+    // Object streams of an encrypted document can only be read once the trailer is known
+    let encrypted = data.windows(8).any(|w| w == b"/Encrypt");
+    let mut obj_streams = Vec::new();
 
     // Skip PDF header (e.g. %PDF-1.7)
     // The header line ends with LF or CRLF. Skip until we hit a line break after "%PDF"
@@ -365,7 +1241,27 @@ pub fn parse_pdf(data: &[u8]) -> Result<(Vec<PageContent>, HashMap<(u32, u16), P
         }
 
         if parser.remaining_starts_with(b"xref") || parser.remaining_starts_with(b"trailer") {
-            break;
+            let eof = parser.data[parser.pos..]
+                .windows(5)
+                .position(|w| w == b"%%EOF");
+            if revision == Some(sections) {
+                // Read the trailer of this section rather than the last one in the file
+                let section =
+                    &parser.data[parser.pos..parser.pos + eof.unwrap_or(parser.len - parser.pos)];
+                if let Some(trailer) = section.windows(7).position(|w| w == b"trailer") {
+                    parser.pos += trailer;
+                }
+                break;
+            }
+            sections += 1;
+            // Skip this revision's cross-reference section up to its %%EOF marker
+            match eof {
+                Some(eof) => {
+                    parser.pos += eof + 5;
+                    continue;
+                }
+                None => break,
+            }
         }
         if parser.remaining_starts_with(b"startxref") {
             parser.pos += 9; // len("startxref")
@@ -379,175 +1275,410 @@ pub fn parse_pdf(data: &[u8]) -> Result<(Vec<PageContent>, HashMap<(u32, u16), P
             }
             continue;
         }
-        //  "<obj_id> <gen_id> obj"
-        let obj_id = match parser.parse_number()? {
-            PdfObj::Number(num) => num as u32,
-            _ => return Err(PdfError::ParseError("Invalid object id")),
-        };
-        parser.skip_whitespace_and_comments();
-        let gen1 = match parser.parse_number()? {
-            PdfObj::Number(num) => num as u16,
-            _ => return Err(PdfError::ParseError("Invalid generation number")),
+        let start = parser.pos;
+        let ((obj_id, gen1), obj_value) = match parse_indirect_object(&mut parser, &objects) {
+            Ok(parsed) => parsed,
+            Err(error) => {
+                let Some(warnings) = warnings.as_deref_mut() else {
+                    return Err(error);
+                };
+                parser.pos = start;
+                let recovered = read_indirect_object(&mut parser, &objects, true);
+                warnings.push(ParseWarning {
+                    offset: start,
+                    object: object_header(data, start),
+                    error,
+                    recovered: recovered.is_ok(),
+                });
+                match recovered {
+                    Ok(parsed) => parsed,
+                    Err(_) => match resync(data, start + 1) {
+                        Some(next) => {
+                            parser.pos = next;
+                            continue;
+                        }
+                        None => break,
+                    },
+                }
+            }
         };
-        parser.skip_whitespace_and_comments();
-        if !parser.remaining_starts_with(b"obj") {
-            return Err(PdfError::ParseError("Missing 'obj' keyword"));
+        if let PdfObj::Stream(stream_obj) = &obj_value {
+            if let Some(PdfObj::Name(t)) = stream_obj.dict.get("Type") {
+                if t == "ObjStm" && encrypted {
+                    obj_streams.push(obj_id);
+                } else if t == "ObjStm" {
+                    if let (Some(PdfObj::Number(first)), Some(PdfObj::Number(n))) =
+                        (stream_obj.dict.get("First"), stream_obj.dict.get("N"))
+                    {
+                        if let Ok(decompressed) = decompress_to_vec_zlib(&stream_obj.data) {
+                            parse_obj_stream(
+                                &decompressed,
+                                *first as usize,
+                                *n as usize,
+                                &mut objects,
+                            )?;
+                        }
+                    }
+                }
+            }
+        }
+        objects.insert((obj_id, gen1), obj_value);
+    }
+
+    if revision.is_some_and(|revision| revision > 0 && revision >= sections) {
+        return Err(PdfError::ParseError("revision out of bounds"));
+    }
+    let offset = parser.pos;
+    let trailer_dict = match (parse_trailer(&mut parser, &objects), warnings) {
+        (Ok(trailer), _) => trailer,
+        (Err(error), Some(warnings)) => {
+            let trailer = catalog_trailer(&objects).ok_or(error)?;
+            warnings.push(ParseWarning {
+                offset,
+                object: None,
+                error: PdfError::ParseError("Trailer dictionary not found"),
+                recovered: true,
+            });
+            trailer
         }
-        parser.pos += 3;
+        (Err(error), None) => return Err(error),
+    };
+    if let Some(decryptor) = crypt::Decryptor::new(&trailer_dict, &objects)? {
+        decryptor.decrypt_objects(&mut objects);
+    }
+    for stream in obj_streams {
+        if let Ok(contained) = expand_obj_stream(&objects, stream) {
+            for (id, value) in contained {
+                objects.entry(id).or_insert(value);
+            }
+        }
+    }
+    Ok((objects, trailer_dict))
+}
+
+// Id and generation of the "<id> <gen> obj" header at `pos`
+fn object_header(data: &[u8], pos: usize) -> Option<(u32, u16)> {
+    let mut parser = Parser::new(data);
+    parser.pos = pos;
+    let integer = |parser: &mut Parser| {
         parser.skip_whitespace_and_comments();
-        let obj_value = if parser.pos < parser.len
-            && parser.data[parser.pos] == b'<'
-            && parser.pos + 1 < parser.len
-            && parser.data[parser.pos + 1] == b'<'
-        {
-            parser.pos += 2;
-            let dict_obj = parser.parse_dictionary()?;
+        let digits = parser.data[parser.pos..]
+            .iter()
+            .take_while(|b| b.is_ascii_digit())
+            .count();
+        let value = str::from_utf8(&parser.data[parser.pos..parser.pos + digits]).ok()?;
+        parser.pos += digits;
+        value.parse::<u32>().ok()
+    };
+    let id = integer(&mut parser)?;
+    let generation = integer(&mut parser)?;
+    parser.skip_whitespace_and_comments();
+    parser
+        .remaining_starts_with(b"obj")
+        .then_some((id, generation as u16))
+}
 
-            parser.skip_whitespace_and_comments();
-            if parser.remaining_starts_with(b"stream") {
-                parser.pos += 6;
-                if parser.pos < parser.len && parser.data[parser.pos] == b'\r' {
-                    parser.pos += 1;
-                    if parser.pos < parser.len && parser.data[parser.pos] == b'\n' {
-                        parser.pos += 1;
-                    }
-                } else if parser.pos < parser.len && parser.data[parser.pos] == b'\n' {
+// Where scanning resumes after a damaged object: the next object header, or cross-reference
+// section or trailer, at or after `from`
+fn resync(data: &[u8], from: usize) -> Option<usize> {
+    (from..data.len()).find(|&i| {
+        let boundary = i == 0 || data[i - 1].is_ascii_whitespace() || is_delimiter(data[i - 1]);
+        boundary
+            && (data[i..].starts_with(b"xref")
+                || data[i..].starts_with(b"trailer")
+                || data[i..].starts_with(b"startxref")
+                || (data[i].is_ascii_digit() && object_header(data, i).is_some()))
+    })
+}
+
+// A trailer for a file that lost its own: `/Root` is the first object typed `/Catalog`
+fn catalog_trailer<'a>(
+    objects: &HashMap<(u32, u16), PdfObj<'a>>,
+) -> Option<HashMap<String, PdfObj<'a>>> {
+    let mut catalogs: Vec<_> = objects
+        .iter()
+        .filter(|(_, object)| {
+            matches!(object, PdfObj::Dictionary(dict)
+                if matches!(dict.get("Type"), Some(PdfObj::Name(t)) if t == "Catalog"))
+        })
+        .map(|(id, _)| *id)
+        .collect();
+    catalogs.sort_unstable();
+    let mut trailer = HashMap::new();
+    trailer.insert("Root".to_string(), PdfObj::Reference(*catalogs.first()?));
+    Some(trailer)
+}
+
+// Parse one "<id> <gen> obj ... endobj" at the parser position. `objects` resolves an indirect
+// stream /Length; without it the stream ends at `endstream`.
+pub(crate) fn parse_indirect_object<'a>(
+    parser: &mut Parser<'a>,
+    objects: &HashMap<(u32, u16), PdfObj>,
+) -> Result<((u32, u16), PdfObj<'a>), PdfError> {
+    read_indirect_object(parser, objects, false)
+}
+
+// `parse_indirect_object`, or with `recover` the same ignoring `/Length` and a missing `endobj`.
+// Parse errors carry the object and the offset where parsing stopped.
+fn read_indirect_object<'a>(
+    parser: &mut Parser<'a>,
+    objects: &HashMap<(u32, u16), PdfObj>,
+    recover: bool,
+) -> Result<((u32, u16), PdfObj<'a>), PdfError> {
+    let start = parser.pos;
+    indirect_object(parser, objects, recover).map_err(|error| match error {
+        PdfError::ParseError(message) => {
+            PdfError::in_object(message, parser.pos, object_header(parser.data, start))
+        }
+        error => error,
+    })
+}
+
+fn indirect_object<'a>(
+    parser: &mut Parser<'a>,
+    objects: &HashMap<(u32, u16), PdfObj>,
+    recover: bool,
+) -> Result<((u32, u16), PdfObj<'a>), PdfError> {
+    //  "<obj_id> <gen_id> obj"
+    let obj_id = match parser.parse_number()? {
+        PdfObj::Number(num) => num as u32,
+        _ => return Err(PdfError::ParseError("Invalid object id")),
+    };
+    parser.skip_whitespace_and_comments();
+    let gen1 = match parser.parse_number()? {
+        PdfObj::Number(num) => num as u16,
+        _ => return Err(PdfError::ParseError("Invalid generation number")),
+    };
+    parser.skip_whitespace_and_comments();
+    if !parser.remaining_starts_with(b"obj") {
+        return Err(PdfError::ParseError("Missing 'obj' keyword"));
+    }
+    parser.pos += 3;
+    parser.skip_whitespace_and_comments();
+    let obj_value = if parser.pos < parser.len
+        && parser.data[parser.pos] == b'<'
+        && parser.pos + 1 < parser.len
+        && parser.data[parser.pos + 1] == b'<'
+    {
+        parser.pos += 2;
+        let dict_obj = parser.parse_dictionary()?;
+
+        parser.skip_whitespace_and_comments();
+        if parser.remaining_starts_with(b"stream") {
+            parser.pos += 6;
+            if parser.pos < parser.len && parser.data[parser.pos] == b'\r' {
+                parser.pos += 1;
+                if parser.pos < parser.len && parser.data[parser.pos] == b'\n' {
                     parser.pos += 1;
                 }
+            } else if parser.pos < parser.len && parser.data[parser.pos] == b'\n' {
+                parser.pos += 1;
+            }
 
-                let stream_start = parser.pos;
+            let stream_start = parser.pos;
 
-                let mut length_opt: Option<usize> = None;
-                if let PdfObj::Dictionary(ref d) = dict_obj {
-                    if let Some(len_obj) = d.get("Length") {
-                        match len_obj {
-                            PdfObj::Number(n) => length_opt = Some(*n as usize),
-                            PdfObj::Reference((obj, generation)) => {
-                                if let Some(PdfObj::Number(n)) = objects.get(&(*obj, *generation)) {
-                                    length_opt = Some(*n as usize);
-                                }
+            let mut length_opt: Option<usize> = None;
+            if let (PdfObj::Dictionary(ref d), false) = (&dict_obj, recover) {
+                if let Some(len_obj) = d.get("Length") {
+                    match len_obj {
+                        PdfObj::Number(n) => length_opt = Some(*n as usize),
+                        PdfObj::Reference((obj, generation)) => {
+                            if let Some(PdfObj::Number(n)) = objects.get(&(*obj, *generation)) {
+                                length_opt = Some(*n as usize);
                             }
-                            _ => {}
                         }
+                        _ => {}
                     }
                 }
+            }
 
-                let search_term = b"endstream";
-                let search_len = search_term.len();
+            let search_term = b"endstream";
+            let search_len = search_term.len();
 
-                let stream_data = if let Some(len) = length_opt {
-                    if stream_start + len > parser.len {
-                        return Err(PdfError::ParseError("Unexpected EOF in stream"));
-                    }
-                    let data_end = stream_start + len;
-                    parser.pos = data_end;
-                    if parser.pos < parser.len && parser.data[parser.pos] == b'\r' {
-                        parser.pos += 1;
-                        if parser.pos < parser.len && parser.data[parser.pos] == b'\n' {
-                            parser.pos += 1;
-                        }
-                    } else if parser.pos < parser.len && parser.data[parser.pos] == b'\n' {
+            let stream_data = if let Some(len) = length_opt {
+                if stream_start + len > parser.len {
+                    return Err(PdfError::ParseError("Unexpected EOF in stream"));
+                }
+                let data_end = stream_start + len;
+                parser.pos = data_end;
+                if parser.pos < parser.len && parser.data[parser.pos] == b'\r' {
+                    parser.pos += 1;
+                    if parser.pos < parser.len && parser.data[parser.pos] == b'\n' {
                         parser.pos += 1;
                     }
-                    parser.skip_whitespace_and_comments();
-                    if !parser.remaining_starts_with(search_term) {
-                        return Err(PdfError::ParseError("Missing 'endstream'"));
-                    }
-                    parser.data[stream_start..data_end].to_vec()
-                } else {
-                    let mut endstream_index = None;
-                    let mut i = stream_start;
-                    while i + search_len <= parser.len {
-                        if &parser.data[i..i + search_len] == search_term {
-                            let prev_ok = if i == 0 {
-                                true
-                            } else {
-                                let prev = parser.data[i - 1];
-                                prev == b'\n' || prev == b'\r' || prev.is_ascii_whitespace()
-                            };
-                            let next_ok = if i + search_len >= parser.len {
-                                true
-                            } else if parser.data[i + search_len..].starts_with(b"endobj") {
-                                true
-                            } else {
-                                let next = parser.data[i + search_len];
-                                next.is_ascii_whitespace()
-                            };
-                            if prev_ok && next_ok {
-                                endstream_index = Some(i);
-                                break;
-                            }
-                        }
-                        i += 1;
-                    }
-                    let end_idx =
-                        endstream_index.ok_or(PdfError::ParseError("Missing 'endstream'"))?;
-                    parser.pos = end_idx;
-                    let mut data_end = end_idx;
-                    while data_end > stream_start && parser.data[data_end - 1].is_ascii_whitespace()
-                    {
-                        data_end -= 1;
-                    }
-                    parser.data[stream_start..data_end].to_vec()
-                };
-
-                parser.pos += search_len;
+                } else if parser.pos < parser.len && parser.data[parser.pos] == b'\n' {
+                    parser.pos += 1;
+                }
                 parser.skip_whitespace_and_comments();
-                if !parser.remaining_starts_with(b"endobj") {
-                    return Err(PdfError::ParseError("Missing 'endobj' after stream"));
+                if !parser.remaining_starts_with(search_term) {
+                    return Err(PdfError::ParseError("Missing 'endstream'"));
                 }
-                parser.pos += 6;
-                let dict = if let PdfObj::Dictionary(d) = dict_obj {
-                    d
-                } else {
-                    HashMap::new()
-                };
-                let stream_obj = PdfStream {
-                    dict,
-                    data: stream_data,
-                };
-
-                if let Some(PdfObj::Name(ref t)) = stream_obj.dict.get("Type") {
-                    if t == "ObjStm" {
-                        if let (Some(PdfObj::Number(first)), Some(PdfObj::Number(n))) =
-                            (stream_obj.dict.get("First"), stream_obj.dict.get("N"))
-                        {
-                            if let Ok(decompressed) = decompress_to_vec_zlib(&stream_obj.data) {
-                                parse_obj_stream(
-                                    &decompressed,
-                                    *first as usize,
-                                    *n as usize,
-                                    &mut objects,
-                                )?;
-                            }
+                Cow::Borrowed(&parser.data[stream_start..data_end])
+            } else {
+                let mut endstream_index = None;
+                let mut i = stream_start;
+                while i + search_len <= parser.len {
+                    if &parser.data[i..i + search_len] == search_term {
+                        let prev_ok = if i == 0 {
+                            true
+                        } else {
+                            let prev = parser.data[i - 1];
+                            prev == b'\n' || prev == b'\r' || prev.is_ascii_whitespace()
+                        };
+                        let next_ok = if i + search_len >= parser.len {
+                            true
+                        } else if parser.data[i + search_len..].starts_with(b"endobj") {
+                            true
+                        } else {
+                            let next = parser.data[i + search_len];
+                            next.is_ascii_whitespace()
+                        };
+                        if prev_ok && next_ok {
+                            endstream_index = Some(i);
+                            break;
                         }
                     }
+                    i += 1;
                 }
-
-                PdfObj::Stream(stream_obj)
-            } else {
-                // "endobj"
-                parser.skip_whitespace_and_comments();
-                if !parser.remaining_starts_with(b"endobj") {
-                    return Err(PdfError::ParseError(
-                        "Missing 'endobj' for dictionary object",
-                    ));
+                let end_idx = endstream_index.ok_or(PdfError::ParseError("Missing 'endstream'"))?;
+                parser.pos = end_idx;
+                let mut data_end = end_idx;
+                while data_end > stream_start && parser.data[data_end - 1].is_ascii_whitespace() {
+                    data_end -= 1;
                 }
+                Cow::Borrowed(&parser.data[stream_start..data_end])
+            };
+
+            parser.pos += search_len;
+            parser.skip_whitespace_and_comments();
+            if parser.remaining_starts_with(b"endobj") {
                 parser.pos += 6;
-                dict_obj
+            } else if !recover {
+                return Err(PdfError::ParseError("Missing 'endobj' after stream"));
             }
+            let dict = if let PdfObj::Dictionary(d) = dict_obj {
+                d
+            } else {
+                HashMap::new()
+            };
+            let stream_obj = PdfStream {
+                dict,
+                data: stream_data,
+            };
+
+            PdfObj::Stream(stream_obj)
         } else {
-            let value_obj = parser.parse_value()?;
+            // "endobj"
             parser.skip_whitespace_and_comments();
-            if !parser.remaining_starts_with(b"endobj") {
-                return Err(PdfError::ParseError("Missing 'endobj' for object"));
+            if parser.remaining_starts_with(b"endobj") {
+                parser.pos += 6;
+            } else if !recover {
+                return Err(PdfError::ParseError(
+                    "Missing 'endobj' for dictionary object",
+                ));
             }
+            dict_obj
+        }
+    } else {
+        let value_obj = parser.parse_value()?;
+        parser.skip_whitespace_and_comments();
+        if parser.remaining_starts_with(b"endobj") {
             parser.pos += 6;
-            value_obj
-        };
-        objects.insert((obj_id, gen1), obj_value);
+        } else if !recover {
+            return Err(PdfError::ParseError("Missing 'endobj' for object"));
+        }
+        value_obj
+    };
+    Ok(((obj_id, gen1), obj_value))
+}
+
+// Walk the page tree from the catalog. `lenient` and `only` behave as in `parse_document`.
+fn load_pages<'a>(
+    objects: &HashMap<(u32, u16), PdfObj<'a>>,
+    trailer_dict: &HashMap<String, PdfObj<'a>>,
+    lenient: bool,
+    only: Option<usize>,
+) -> Result<Vec<PageContent<'a>>, PdfError> {
+    page_nodes(objects, trailer_dict)?
+        .iter()
+        .enumerate()
+        .map(|(index, node)| {
+            // Other pages are kept as empty placeholders without decoding anything
+            if only.is_some_and(|wanted| wanted != index) {
+                return Ok(PageContent::default());
+            }
+            tolerate_page_error(load_page(node, objects), lenient)
+        })
+        .collect()
+}
+
+// Decode the first `max_pages` pages (all of them with `None`) one at a time and map each with
+// `f`, so the content streams and fonts of only one page are held at once. `lenient` behaves as
+// in `parse_document`.
+fn map_pages<'a, T>(
+    objects: &HashMap<(u32, u16), PdfObj<'a>>,
+    trailer_dict: &HashMap<String, PdfObj<'a>>,
+    lenient: bool,
+    max_pages: Option<usize>,
+    mut f: impl FnMut(usize, &PageContent<'a>) -> T,
+) -> Result<Vec<T>, PdfError> {
+    let nodes = page_nodes(objects, trailer_dict)?;
+    let count = max_pages.map_or(nodes.len(), |max| max.min(nodes.len()));
+    let _timed = timed!("decode_pages", pages = count);
+    nodes[..count]
+        .iter()
+        .enumerate()
+        .map(|(index, node)| {
+            let page = tolerate_page_error(load_page(node, objects), lenient)?;
+            Ok(f(index, &page))
+        })
+        .collect()
+}
+
+// The leaves of the page tree under the catalog, in page order
+fn page_nodes<'o, 'a>(
+    objects: &'o HashMap<(u32, u16), PdfObj<'a>>,
+    trailer_dict: &'o HashMap<String, PdfObj<'a>>,
+) -> Result<Vec<PageNode<'o, 'a>>, PdfError> {
+    let root_obj = resolve(trailer_dict.get("Root"), objects)
+        .ok_or(PdfError::ParseError("Root object not found"))?;
+    let pages_obj_id = match root_obj {
+        PdfObj::Dictionary(m) => {
+            match m.get("Pages") {
+                Some(PdfObj::Reference(id)) => *id,
+                Some(PdfObj::Dictionary(_)) => {
+                    (0, 0) // use (0,0) as marker for embedded
+                }
+                _ => return Err(PdfError::ParseError("Pages reference not found in Catalog")),
+            }
+        }
+        _ => return Err(PdfError::ParseError("Catalog object is not a dictionary")),
+    };
+
+    let mut result = Vec::new();
+
+    if pages_obj_id != (0, 0) {
+        traverse_pages(
+            pages_obj_id,
+            objects,
+            Inherited::default(),
+            &mut result,
+            &mut HashSet::new(),
+        )?;
+    } else {
+        return Err(PdfError::ParseError(
+            "Pages object embedded in catalog is not supported",
+        ));
     }
 
+    Ok(result)
+}
+
+// Locate the trailer dictionary, falling back to the dictionary of a cross-reference stream
+fn parse_trailer<'a>(
+    parser: &mut Parser<'a>,
+    objects: &HashMap<(u32, u16), PdfObj<'a>>,
+) -> Result<HashMap<String, PdfObj<'a>>, PdfError> {
     let mut trailer_index = None;
     if parser.remaining_starts_with(b"trailer") {
         trailer_index = Some(parser.pos);
@@ -561,7 +1692,7 @@ pub fn parse_pdf(data: &[u8]) -> Result<(Vec<PageContent>, HashMap<(u32, u16), P
         }
     }
 
-    let trailer_dict = if let Some(idx) = trailer_index {
+    if let Some(idx) = trailer_index {
         parser.pos = idx;
         if parser.remaining_starts_with(b"trailer") {
             parser.pos += 7;
@@ -573,9 +1704,9 @@ pub fn parse_pdf(data: &[u8]) -> Result<(Vec<PageContent>, HashMap<(u32, u16), P
         parser.pos += 2;
         let trailer_dict_obj = parser.parse_dictionary()?;
         if let PdfObj::Dictionary(d) = trailer_dict_obj {
-            d
+            Ok(d)
         } else {
-            return Err(PdfError::ParseError("Trailer is not a dictionary"));
+            Err(PdfError::ParseError("Trailer is not a dictionary"))
         }
     } else {
         let mut dict_opt = None;
@@ -589,47 +1720,15 @@ pub fn parse_pdf(data: &[u8]) -> Result<(Vec<PageContent>, HashMap<(u32, u16), P
                 }
             }
         }
-        dict_opt.ok_or(PdfError::ParseError("Trailer dictionary not found"))?
-    };
-    let root_obj = match trailer_dict.get("Root") {
-        Some(PdfObj::Reference(obj_id)) => objects.get(obj_id).cloned(),
-        Some(other) => Some(other.clone()),
-        None => None,
-    };
-    let root_obj = root_obj.ok_or(PdfError::ParseError("Root object not found"))?;
-    let pages_obj_id = match root_obj {
-        PdfObj::Dictionary(ref m) => {
-            match m.get("Pages") {
-                Some(PdfObj::Reference(id)) => *id,
-                Some(PdfObj::Dictionary(_)) => {
-                    (0, 0) // use (0,0) as marker for embedded
-                }
-                _ => return Err(PdfError::ParseError("Pages reference not found in Catalog")),
-            }
-        }
-        _ => return Err(PdfError::ParseError("Catalog object is not a dictionary")),
-    };
-
-    let mut result = Vec::new();
-
-    if pages_obj_id != (0, 0) {
-        traverse_pages(pages_obj_id, &objects, None, &mut result, &|bytes| {
-            decompress_to_vec_zlib(bytes).map_err(|_| PdfError::DecompressionError)
-        })?;
-    } else {
-        return Err(PdfError::ParseError(
-            "Pages object embedded in catalog is not supported",
-        ));
+        dict_opt.ok_or(PdfError::ParseError("Trailer dictionary not found"))
     }
-
-    Ok((result, objects))
 }
 
 fn parse_obj_stream(
     data: &[u8],
     first: usize,
     count: usize,
-    objects: &mut HashMap<(u32, u16), PdfObj>,
+    objects: &mut HashMap<(u32, u16), PdfObj<'_>>,
 ) -> Result<(), PdfError> {
     let mut parser = Parser::new(data);
     let mut headers = Vec::new();
@@ -653,59 +1752,97 @@ fn parse_obj_stream(
             data.len()
         };
         let mut sub = Parser::new(&data[start..end]);
-        let value = sub.parse_value()?;
+        // Object streams hold no streams, so nothing borrows from `data`
+        let value = sub.parse_value()?.into_owned();
         objects.insert((headers[i].0, 0), value);
     }
     Ok(())
 }
 
 fn parse_content_tokens(data: &[u8]) -> Vec<Token> {
-    let mut tokens = Vec::new();
-    let mut i = 0;
+    content_tokens(&[data])
+}
+
+// Where the data of an inline image that starts after the `ID` ending at `i` stops: at the `EI`
+// that stands alone between whitespace, or at the end of `data`
+fn inline_image_end(data: &[u8], i: usize) -> usize {
+    // A single whitespace byte separates `ID` from the data
+    let start = (i + 1).min(data.len());
+    (start..data.len().saturating_sub(1))
+        .find(|&j| {
+            data[j..].starts_with(b"EI")
+                && data[j - 1].is_ascii_whitespace()
+                && data
+                    .get(j + 2)
+                    .is_none_or(|&b| b.is_ascii_whitespace() || is_delimiter(b))
+        })
+        .unwrap_or(data.len())
+}
+
+// Tokens of a page's content streams, which act as one stream (PDF 32000-1, 7.8.2). Each stream
+// is tokenized in place rather than concatenated, and an array left open at the end of one
+// continues into the next.
+fn content_tokens<S: AsRef<[u8]>>(streams: &[S]) -> Vec<Token> {
+    fold_array_tokens(
+        &mut ContentTokens {
+            streams,
+            stream: 0,
+            pos: 0,
+        },
+        false,
+    )
+}
+
+// Raw tokens of a sequence of content streams, arrays not yet folded. A stream boundary always
+// ends a token.
+struct ContentTokens<'s, S> {
+    streams: &'s [S],
+    stream: usize,
+    pos: usize,
+}
+
+impl<S: AsRef<[u8]>> Iterator for ContentTokens<'_, S> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Token> {
+        loop {
+            let data = self.streams.get(self.stream)?.as_ref();
+            if let Some((token, next)) = next_token(data, self.pos) {
+                self.pos = next;
+                return Some(token);
+            }
+            self.stream += 1;
+            self.pos = 0;
+        }
+    }
+}
+
+// The token starting at or after `i` and the index past it, or `None` at the end of `data`
+fn next_token(data: &[u8], mut i: usize) -> Option<(Token, usize)> {
     while i < data.len() {
         let byte = data[i];
         match byte {
             b' ' | b'\t' | b'\r' | b'\n' | 0x0C => {
                 i += 1;
             }
-            b'[' => {
-                tokens.push(Token::ArrayStart);
-                i += 1;
-            }
-            b']' => {
-                tokens.push(Token::ArrayEnd);
-                i += 1;
-            }
+            b'[' => return Some((Token::ArrayStart, i + 1)),
+            b']' => return Some((Token::ArrayEnd, i + 1)),
             b'(' => {
                 let (string_bytes, new_index) = parse_literal_string(data, i);
-                tokens.push(Token::String(string_bytes));
-                i = new_index;
+                return Some((Token::String(string_bytes), new_index));
             }
             b'<' => {
                 if i + 1 < data.len() && data[i + 1] == b'<' {
-                    i += 2;
-                    let mut depth = 1;
-                    while i < data.len() && depth > 0 {
-                        if i + 1 < data.len() && &data[i..i + 2] == b"<<" {
-                            depth += 1;
-                            i += 2;
-                        } else if i + 1 < data.len() && &data[i..i + 2] == b">>" {
-                            depth -= 1;
-                            i += 2;
-                        } else {
-                            i += 1;
-                        }
-                    }
+                    let (entries, new_index) = inline_dictionary(data, i + 2);
+                    return Some((Token::Dictionary(entries), new_index));
                 } else {
                     let (bytes, new_index) = parse_hex_string(data, i);
-                    tokens.push(Token::String(bytes));
-                    i = new_index;
+                    return Some((Token::String(bytes), new_index));
                 }
             }
             b'/' => {
                 let (name, new_index) = parse_name(data, i);
-                tokens.push(Token::Name(name));
-                i = new_index;
+                return Some((Token::Name(name), new_index));
             }
             b'%' => {
                 while i < data.len() && data[i] != b'\r' && data[i] != b'\n' {
@@ -714,8 +1851,12 @@ fn parse_content_tokens(data: &[u8]) -> Vec<Token> {
             }
             b'+' | b'-' | b'.' | b'0'..=b'9' => {
                 let (number, new_index) = parse_number(data, i);
-                tokens.push(Token::Number(number));
-                i = new_index;
+                return Some((Token::Number(number), new_index));
+            }
+            // Unbalanced delimiters start no token; skip them or the operator scan below would
+            // never advance
+            b')' | b'>' | b'{' | b'}' => {
+                i += 1;
             }
             _ => {
                 let start = i;
@@ -723,12 +1864,52 @@ fn parse_content_tokens(data: &[u8]) -> Vec<Token> {
                     i += 1;
                 }
                 if let Ok(op) = str::from_utf8(&data[start..i]) {
-                    tokens.push(Token::Operator(op.to_string()));
+                    // The binary data of an inline image would otherwise read as operators
+                    let next = if op == "ID" {
+                        inline_image_end(data, i)
+                    } else {
+                        i
+                    };
+                    return Some((Token::Operator(op.to_string()), next));
+                }
+            }
+        }
+    }
+    None
+}
+
+// The entries of an inline dictionary whose `<<` ends before `i`, and the index past its `>>`.
+// A dictionary never closed takes the rest of `data`.
+fn inline_dictionary(data: &[u8], mut i: usize) -> (HashMap<String, Token>, usize) {
+    let mut tokens = Vec::new();
+    while i < data.len() {
+        match data[i] {
+            b' ' | b'\t' | b'\r' | b'\n' | 0x0C => i += 1,
+            // A comment could otherwise hide the closing `>>` from the check below
+            b'%' => {
+                while i < data.len() && data[i] != b'\r' && data[i] != b'\n' {
+                    i += 1;
                 }
             }
+            b'>' if data.get(i + 1) == Some(&b'>') => {
+                i += 2;
+                break;
+            }
+            _ => match next_token(data, i) {
+                Some((token, next)) => {
+                    tokens.push(token);
+                    i = next;
+                }
+                None => i = data.len(),
+            },
         }
     }
-    fold_array_tokens(tokens)
+    let mut entries = HashMap::new();
+    let mut values = fold_array_tokens(&mut tokens.into_iter(), false).into_iter();
+    while let (Some(Token::Name(key)), Some(value)) = (values.next(), values.next()) {
+        entries.insert(key, value);
+    }
+    (entries, i)
 }
 
 fn extract_from_tokens(
@@ -741,14 +1922,36 @@ fn extract_from_tokens(
 ) {
     let mut in_text = false;
     let mut current_font: Option<&PdfFont> = None;
+    let mut state = TextState::default();
+    let mut saved: Vec<TextState> = Vec::new();
+    // Horizontal position of the start of the line and of the end of the text shown on it, in
+    // text space; `pen` is unknown after text in a font without widths
+    let mut line_x = 0.0;
+    let mut pen: Option<f32> = Some(0.0);
     let mut i = 0;
 
     while i < tokens.len() {
         if let Token::Operator(op) = &tokens[i] {
+            let number = |back: usize| match i.checked_sub(back).map(|at| &tokens[at]) {
+                Some(Token::Number(n)) => Some(*n),
+                _ => None,
+            };
             match op.as_str() {
+                "q" => saved.push(state),
+                "Q" => state = saved.pop().unwrap_or(state),
+                "Tc" => state.char_spacing = number(1).unwrap_or(state.char_spacing),
+                "Tw" => state.word_spacing = number(1).unwrap_or(state.word_spacing),
+                "Tz" => state.scale = number(1).map_or(state.scale, |scale| scale / 100.0),
                 "BT" => {
                     // Begin Text Object
                     in_text = true;
+                    line_x = 0.0;
+                    pen = Some(0.0);
+                }
+                "Tm" if in_text => {
+                    // Positions are now relative to the new matrix
+                    line_x = 0.0;
+                    pen = Some(0.0);
                 }
                 "ET" => {
                     // End Text Object
@@ -758,6 +1961,7 @@ fn extract_from_tokens(
                 }
                 "Tf" => {
                     // Set text font+size: /F1 12 Tf
+                    state.size = number(1).unwrap_or(state.size);
                     if i >= 2 {
                         if let Token::Name(font_name) = &tokens[i - 2] {
                             // Try to pick that font; otherwise warn
@@ -771,15 +1975,22 @@ fn extract_from_tokens(
                     }
                 }
                 "Tj" | "'" | "\"" if in_text => {
+                    if op == "\"" {
+                        state.word_spacing = number(3).unwrap_or(state.word_spacing);
+                        state.char_spacing = number(2).unwrap_or(state.char_spacing);
+                    }
                     if let Some(font) = current_font {
                         // If `'` or `"` used, start a new line
                         if op != "Tj" {
                             output.push('\n');
+                            pen = Some(line_x);
                         }
                         // The literal string to draw is immediately before the operator
                         if i >= 1 {
                             if let Token::String(bytes) = &tokens[i - 1] {
                                 output.push_str(&decode_bytes(bytes, font));
+                                extract_type3_glyphs(font, bytes, output, objects, visited);
+                                pen = state.advance(pen, font, bytes);
                             }
                         }
                     }
@@ -793,9 +2004,21 @@ fn extract_from_tokens(
                                     match elem {
                                         Token::String(bytes) => {
                                             output.push_str(&decode_bytes(bytes, font));
+                                            extract_type3_glyphs(
+                                                font, bytes, output, objects, visited,
+                                            );
+                                            pen = state.advance(pen, font, bytes);
                                         }
-                                        Token::Number(n) if *n < -200.0 => {
-                                            output.push(' ');
+                                        Token::Number(n) => {
+                                            // Adjustments are in thousandths of an em, positive
+                                            // ones moving left
+                                            let gap = -n / 1000.0 * state.size * state.scale;
+                                            pen = pen.map(|pen| pen + gap);
+                                            if is_word_gap(font, gap, state.size, state.scale)
+                                                && !output.ends_with(char::is_whitespace)
+                                            {
+                                                output.push(' ');
+                                            }
                                         }
                                         _ => {}
                                     }
@@ -807,178 +2030,54 @@ fn extract_from_tokens(
                 "T*" if in_text => {
                     // Move to next line
                     output.push('\n');
+                    pen = Some(line_x);
                 }
                 "Td" | "TD" if in_text => {
                     // `Td`/`TD` moves the text position. When the vertical
                     // displacement parameter is non-zero it usually indicates
                     // a new line, otherwise it's just horizontal positioning
                     // for individual glyphs. Only insert a newline when the
-                    // second operand (Ty) is not zero.
-                    if i >= 2 {
-                        if let (Token::Number(_tx), Token::Number(ty)) =
-                            (&tokens[i - 2], &tokens[i - 1])
-                        {
-                            if *ty != 0.0 {
-                                output.push('\n');
+                    // second operand (Ty) is not zero; otherwise a move far
+                    // enough past the text already shown is a space.
+                    if let (Some(tx), Some(ty)) = (number(2), number(1)) {
+                        line_x += tx;
+                        if ty != 0.0 {
+                            output.push('\n');
+                        } else if let (Some(font), Some(pen)) = (current_font, pen) {
+                            if is_word_gap(font, line_x - pen, state.size, state.scale)
+                                && !output.ends_with(char::is_whitespace)
+                            {
+                                output.push(' ');
                             }
                         }
+                        pen = Some(line_x);
                     }
                 }
 
                 "Do" => {
                     // `Do` operator invokes an XObject
-                    if i >= 1 {
-                        if let Token::Name(xobj_name_from_token) = &tokens[i - 1] {
-                            if let Some(xobjects_dict_obj) = resources.get("XObject") {
-                                let resolved_xobjects_dict: Option<&HashMap<String, PdfObj>> =
-                                    match xobjects_dict_obj {
-                                        PdfObj::Dictionary(map) => Some(map),
-                                        PdfObj::Reference(id) => objects.get(id).and_then(|obj| {
-                                            if let PdfObj::Dictionary(map) = obj {
-                                                Some(map)
-                                            } else {
-                                                None
-                                            }
-                                        }),
-                                        _ => None,
-                                    };
-
-                                if let Some(actual_xobjects_map) = resolved_xobjects_dict {
-                                    if let Some(original_xobj_entry) =
-                                        actual_xobjects_map.get(xobj_name_from_token)
-                                    {
-                                        let mut object_id_for_visited_check: Option<(u32, u16)> =
-                                            None;
-                                        if let PdfObj::Reference(id) = original_xobj_entry {
-                                            object_id_for_visited_check = Some(*id);
-                                        }
-
-                                        let form_stream_data: Option<&PdfStream> =
-                                            match original_xobj_entry {
-                                                PdfObj::Stream(s) => Some(s),
-                                                PdfObj::Reference(id) => {
-                                                    objects.get(id).and_then(|obj| {
-                                                        if let PdfObj::Stream(s) = obj {
-                                                            Some(s)
-                                                        } else {
-                                                            None
-                                                        }
-                                                    })
-                                                }
-                                                _ => None,
-                                            };
-
-                                        if let Some(xf) = form_stream_data {
-                                            let subtype =
-                                                xf.dict.get("Subtype").and_then(|v| match v {
-                                                    PdfObj::Name(name) => Some(name.as_str()),
-                                                    _ => None,
-                                                });
-
-                                            if subtype == Some("Form") {
-                                                let form_specific_resources: &HashMap<
-                                                    String,
-                                                    PdfObj,
-                                                > = xf
-                                                    .dict
-                                                    .get("Resources")
-                                                    .and_then(|res_obj| match res_obj {
-                                                        PdfObj::Dictionary(map) => Some(map),
-                                                        PdfObj::Reference(res_id) => {
-                                                            objects.get(res_id).and_then(|o| {
-                                                                if let PdfObj::Dictionary(map) = o {
-                                                                    Some(map)
-                                                                } else {
-                                                                    None
-                                                                }
-                                                            })
-                                                        }
-                                                        _ => None,
-                                                    })
-                                                    .unwrap_or(resources);
-
-                                                let form_content_bytes: Vec<u8>;
-                                                if let Some(filter_obj) = xf.dict.get("Filter") {
-                                                    let mut decompressed_holder: Vec<Vec<u8>> =
-                                                        Vec::new();
-                                                    match handle_stream_filters(
-                                                        filter_obj,
-                                                        &xf.data,
-                                                        &|bytes_to_decompress| {
-                                                            decompress_to_vec_zlib(
-                                                                bytes_to_decompress,
-                                                            )
-                                                            .map_err(|_| {
-                                                                PdfError::DecompressionError
-                                                            })
-                                                        },
-                                                        &mut decompressed_holder,
-                                                    ) {
-                                                        Ok(_)
-                                                            if !decompressed_holder.is_empty() =>
-                                                        {
-                                                            form_content_bytes =
-                                                                decompressed_holder.remove(0);
-                                                        }
-                                                        Ok(_) => {
-                                                            form_content_bytes = xf.data.clone();
-                                                        }
-                                                        Err(_e) => {
-                                                            form_content_bytes = xf.data.clone();
-                                                        }
-                                                    }
-                                                } else {
-                                                    form_content_bytes = xf.data.clone();
-                                                }
-
-                                                let mut should_recurse = true;
-                                                if let Some(id_to_check) =
-                                                    object_id_for_visited_check
-                                                {
-                                                    if !visited.insert(id_to_check) {
-                                                        should_recurse = false;
-                                                    }
-                                                }
-
-                                                if should_recurse {
-                                                    let nested_tokens =
-                                                        parse_content_tokens(&form_content_bytes);
-
-                                                    let form_fonts =
-                                                        match collect_fonts_from_resources(
-                                                            form_specific_resources,
-                                                            objects,
-                                                            &|b| {
-                                                                decompress_to_vec_zlib(b).map_err(
-                                                                    |_| {
-                                                                        PdfError::DecompressionError
-                                                                    },
-                                                                )
-                                                            },
-                                                        ) {
-                                                            Ok(ff) => ff,
-                                                            Err(_e) => HashMap::new(),
-                                                        };
-
-                                                    extract_from_tokens(
-                                                        &nested_tokens,
-                                                        &form_fonts,
-                                                        form_specific_resources,
-                                                        output,
-                                                        objects,
-                                                        visited,
-                                                    );
-
-                                                    if let Some(id_visited) =
-                                                        object_id_for_visited_check
-                                                    {
-                                                        visited.remove(&id_visited);
-                                                    }
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
+                    if let Some(Token::Name(name)) = i.checked_sub(1).map(|at| &tokens[at]) {
+                        if let Some((form, id)) = named_stream(resources, "XObject", name, objects)
+                        {
+                            if matches!(form.dict.get("Subtype"), Some(PdfObj::Name(subtype)) if subtype == "Form")
+                            {
+                                extract_from_stream(form, id, resources, output, objects, visited);
+                            }
+                        }
+                    }
+                }
+                "scn" | "SCN" => {
+                    // A tiling pattern selected as the colour paints its own content; shading
+                    // patterns have none
+                    if let Some(Token::Name(name)) = i.checked_sub(1).map(|at| &tokens[at]) {
+                        if let Some((pattern, id)) =
+                            named_stream(resources, "Pattern", name, objects)
+                        {
+                            if matches!(pattern.dict.get("PatternType"), Some(PdfObj::Number(t)) if *t == 1.0)
+                            {
+                                extract_from_stream(
+                                    pattern, id, resources, output, objects, visited,
+                                );
                             }
                         }
                     }
@@ -990,6 +2089,124 @@ fn extract_from_tokens(
     }
 }
 
+// The stream named `name` in the `category` dictionary of `resources` (`/XObject`, `/Pattern`),
+// with its id if it is indirect
+pub(crate) fn named_stream<'o, 'a>(
+    resources: &'o HashMap<String, PdfObj<'a>>,
+    category: &str,
+    name: &str,
+    objects: &'o HashMap<(u32, u16), PdfObj<'a>>,
+) -> Option<(&'o PdfStream<'a>, Option<(u32, u16)>)> {
+    let Some(PdfObj::Dictionary(named)) = resolve(resources.get(category), objects) else {
+        return None;
+    };
+    let entry = named.get(name)?;
+    let id = match entry {
+        PdfObj::Reference(id) => Some(*id),
+        _ => None,
+    };
+    match resolve(Some(entry), objects)? {
+        PdfObj::Stream(stream) => Some((stream, id)),
+        _ => None,
+    }
+}
+
+// Text of a form XObject or tiling pattern: its content, read with its own `/Resources` or
+// else `resources`. `visited` stops streams that draw themselves.
+fn extract_from_stream(
+    stream: &PdfStream,
+    id: Option<(u32, u16)>,
+    resources: &HashMap<String, PdfObj>,
+    output: &mut String,
+    objects: &HashMap<(u32, u16), PdfObj>,
+    visited: &mut HashSet<(u32, u16)>,
+) {
+    if id.is_some_and(|id| !visited.insert(id)) {
+        return;
+    }
+    let decompress =
+        |bytes: &[u8]| decompress_to_vec_zlib(bytes).map_err(|_| PdfError::DecompressionError);
+    // Content that fails to decode is read as stored
+    let content = decoded_content(stream, &decompress).unwrap_or(Cow::Borrowed(&stream.data));
+    let resources = match resolve(stream.dict.get("Resources"), objects) {
+        Some(PdfObj::Dictionary(own)) => own,
+        _ => resources,
+    };
+    let fonts = collect_fonts_from_resources(resources, objects, &decompress).unwrap_or_default();
+    extract_from_tokens(
+        &parse_content_tokens(&content),
+        &fonts,
+        resources,
+        output,
+        objects,
+        visited,
+    );
+    if let Some(id) = id {
+        visited.remove(&id);
+    }
+}
+
+// Text shown by the glyph procedures of a Type3 font for the codes of `bytes`
+fn extract_type3_glyphs(
+    font: &PdfFont,
+    bytes: &[u8],
+    output: &mut String,
+    objects: &HashMap<(u32, u16), PdfObj>,
+    visited: &mut HashSet<(u32, u16)>,
+) {
+    let Some(type3) = &font.type3 else {
+        return;
+    };
+    for code in bytes {
+        if let Some(content) = type3.procs.get(&u32::from(*code)) {
+            extract_from_tokens(
+                &parse_content_tokens(content),
+                &type3.fonts,
+                &HashMap::new(),
+                output,
+                objects,
+                visited,
+            );
+        }
+    }
+}
+
+// Text state parameters saved and restored by `q` and `Q`
+#[derive(Clone, Copy)]
+struct TextState {
+    size: f32,
+    char_spacing: f32,
+    word_spacing: f32,
+    // `Tz` / 100
+    scale: f32,
+}
+
+impl Default for TextState {
+    fn default() -> Self {
+        TextState {
+            size: 0.0,
+            char_spacing: 0.0,
+            word_spacing: 0.0,
+            scale: 1.0,
+        }
+    }
+}
+
+impl TextState {
+    // The pen position after showing `bytes`
+    fn advance(&self, pen: Option<f32>, font: &PdfFont, bytes: &[u8]) -> Option<f32> {
+        let advance = string_advance(
+            font,
+            bytes,
+            self.size,
+            self.char_spacing,
+            self.word_spacing,
+            self.scale,
+        )?;
+        Some(pen? + advance)
+    }
+}
+
 #[cfg(test)]
 mod extractor_tests {
     #[test]
@@ -1024,6 +2241,575 @@ mod extractor_tests {
             Err(e) => panic!("Failed to extract PDF text: {:?}", e),
         }
     }
+
+    #[test]
+    fn extract_options_default_matches_extract_text() {
+        let pdf_data = include_bytes!("../../sample-pdfs/GST-certificate.pdf").to_vec();
+        let expected = super::extract_text(pdf_data.clone()).expect("Failed to extract text");
+
+        let options = super::ExtractOptions::default();
+        let pages = super::extract_text_with_options(pdf_data.clone(), &options)
+            .expect("Failed to extract text with options");
+        assert_eq!(pages, expected);
+
+        let options = super::ExtractOptions {
+            normalization: super::Normalization::Raw,
+            max_pages: Some(1),
+            ..Default::default()
+        };
+        let pages = super::extract_text_with_options(pdf_data, &options)
+            .expect("Failed to extract raw text");
+        assert_eq!(pages.len(), 1);
+        assert!(pages[0].contains("Goods and Services Tax"));
+    }
+
+    #[test]
+    fn extract_page_text_matches_full_extraction() {
+        let pdf_data = include_bytes!("../../sample-pdfs/GST-certificate.pdf").to_vec();
+        let pages = super::extract_text(pdf_data.clone()).expect("Failed to extract text");
+        let options = super::ExtractOptions::default();
+
+        for (index, expected) in pages.iter().enumerate() {
+            let page = super::extract_page_text(&pdf_data, index, &options)
+                .expect("Failed to extract page");
+            assert_eq!(&page.text, expected);
+            assert_eq!(page.page_count, pages.len());
+        }
+        assert!(super::extract_page_text(&pdf_data, pages.len(), &options).is_err());
+    }
+
+    #[test]
+    fn pages_past_max_pages_are_not_decoded() {
+        let mut document =
+            zkpdf_writer::Document::from_pages(&[&["first"], &["second"], &["third"]]);
+        document.compress = true;
+        let mut pdf = document.to_bytes().unwrap();
+        // The last content stream gets a filter nothing can decode
+        let filter = pdf.windows(12).rposition(|w| w == b"/FlateDecode").unwrap();
+        pdf[filter..filter + 12].copy_from_slice(b"/Unsupported");
+
+        assert!(super::extract_text(pdf.clone()).is_err());
+        let options = super::ExtractOptions {
+            max_pages: Some(2),
+            ..Default::default()
+        };
+        let pages = super::extract_text_with_options(pdf, &options).unwrap();
+        assert_eq!(pages, ["first", "second"]);
+    }
+
+    #[test]
+    fn unfiltered_streams_borrow_the_input() {
+        use alloc::borrow::Cow;
+        let pdf = zkpdf_writer::Document::from_pages(&[&["Hello"]])
+            .to_bytes()
+            .unwrap();
+        let (pages, objects) = super::parse_pdf(&pdf).unwrap();
+        assert!(matches!(pages[0].content_streams[0], Cow::Borrowed(_)));
+        assert!(objects.values().all(|object| match object {
+            super::PdfObj::Stream(stream) => matches!(stream.data, Cow::Borrowed(_)),
+            _ => true,
+        }));
+    }
+
+    #[test]
+    fn lenient_parse_recovers_damaged_objects() {
+        let pdf = zkpdf_writer::Document::from_pages(&[&["first"], &["second"]])
+            .to_bytes()
+            .unwrap();
+        let pdf = alloc::string::String::from_utf8_lossy(&pdf)
+            // A font without `endobj`
+            .replacen("WinAnsiEncoding >>\nendobj", "WinAnsiEncoding >>", 1)
+            // A content stream whose /Length overshoots `endstream`
+            .replacen("/Length 45", "/Length 99", 1)
+            // An object that cannot be read at all
+            .replacen(
+                "6 0 obj",
+                "9 0 obj\n<< /Broken (unterminated >>\nendobj\n6 0 obj",
+                1,
+            )
+            .into_bytes();
+        assert!(super::parse_pdf(&pdf).is_err());
+
+        let recovered = super::parse_pdf_lenient(&pdf).unwrap();
+        let warnings: Vec<_> = recovered
+            .warnings
+            .iter()
+            .map(|warning| (warning.object, warning.recovered))
+            .collect();
+        assert_eq!(
+            warnings,
+            [
+                (Some((3, 0)), true),
+                (Some((9, 0)), false),
+                (Some((6, 0)), true)
+            ]
+        );
+        let pages =
+            super::extract_text_from_document(&recovered.pages, &recovered.objects).unwrap();
+        assert_eq!(pages, ["first", "second"]);
+
+        let options = super::ExtractOptions {
+            lenient: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            super::extract_text_with_options(pdf.clone(), &options).unwrap(),
+            ["first", "second"]
+        );
+        assert!(super::extract_text(pdf).is_err());
+    }
+
+    #[test]
+    fn parse_errors_locate_the_object() {
+        let pdf = zkpdf_writer::Document::from_pages(&[&["first"]])
+            .to_bytes()
+            .unwrap();
+        let pdf = alloc::string::String::from_utf8_lossy(&pdf)
+            .replacen("WinAnsiEncoding >>\nendobj", "WinAnsiEncoding >>", 1)
+            .into_bytes();
+        let error = super::extract_text(pdf.clone()).unwrap_err();
+        let super::PdfError::ObjectError {
+            offset,
+            object,
+            kind,
+            message,
+        } = error
+        else {
+            panic!("unexpected error {:?}", error);
+        };
+        assert_eq!(object, Some((3, 0)));
+        assert_eq!(kind, super::types::ParseErrorKind::MissingKeyword);
+        // Where `endobj` should have been
+        assert!(pdf[offset..].starts_with(b"4 0 obj"));
+        assert_eq!(
+            alloc::format!("{}", error),
+            alloc::format!(
+                "Parse error in object 3 0 at byte {}: {} (missing keyword)",
+                offset,
+                message
+            )
+        );
+    }
+
+    #[test]
+    fn pdf_document_pages_match_full_extraction() {
+        let pdf_data = include_bytes!("../../sample-pdfs/GST-certificate.pdf").to_vec();
+        let options = super::ExtractOptions {
+            max_pages: Some(2),
+            ..Default::default()
+        };
+        let expected = super::extract_text_with_options(pdf_data.clone(), &options)
+            .expect("Failed to extract text");
+
+        let document = super::PdfDocument::parse(&pdf_data, &options).expect("Failed to parse");
+        assert_eq!(document.page_count(), 2);
+        let pages: Vec<_> = (0..document.page_count())
+            .map(|page| document.page_text(page).expect("Failed to extract page"))
+            .collect();
+        assert_eq!(pages, expected);
+        assert!(document.page_text(2).is_err());
+    }
+
+    #[test]
+    fn extract_form_fields_lists_signature_field() {
+        let pdf_data = include_bytes!("../../sample-pdfs/GST-certificate.pdf");
+        let fields = super::extract_form_fields(pdf_data).expect("Failed to read form fields");
+
+        assert_eq!(fields.len(), 1);
+        assert!(fields[0].name.starts_with("N50Qaxhoo0JfhpSeePTjnXr9M1c="));
+        assert_eq!(fields[0].field_type, super::FormFieldType::Signature);
+        assert_eq!(fields[0].value, None);
+    }
+
+    #[test]
+    fn extract_signature_fields_reads_signature_dictionary() {
+        let pdf_data = include_bytes!("../../sample-pdfs/GST-certificate.pdf");
+        let fields = super::extract_signature_fields(pdf_data).expect("Failed to read fields");
+
+        assert_eq!(fields.len(), 1);
+        assert!(fields[0].name.starts_with("N50Qaxhoo0JfhpSeePTjnXr9M1c="));
+        assert_eq!(fields[0].sig_type.as_deref(), Some("Sig"));
+        assert_eq!(fields[0].byte_range, [0, 139459, 144131, 3797]);
+        // The hex string between the two ranges, without its angle brackets
+        assert_eq!(fields[0].contents.len(), (144131 - 139459 - 2) / 2);
+        assert_eq!(fields[0].contents[0], 0x30);
+
+        let unsigned = zkpdf_writer::Document::from_pages(&[&["first"]])
+            .to_bytes()
+            .unwrap();
+        assert_eq!(super::extract_signature_fields(&unsigned).unwrap(), []);
+    }
+
+    #[test]
+    fn extract_dss_reads_validation_data() {
+        let pdf = zkpdf_writer::Document::from_pages(&[&["first"]])
+            .to_bytes()
+            .unwrap();
+        assert_eq!(
+            super::extract_dss(&pdf).unwrap(),
+            super::DocumentSecurityStore::default()
+        );
+
+        // A catalog with a /DSS, its objects inserted before the cross-reference table
+        let pdf = alloc::string::String::from_utf8_lossy(&pdf)
+            .replacen("/Type /Catalog", "/Type /Catalog /DSS 20 0 R", 1)
+            .into_bytes();
+        let crl = miniz_oxide::deflate::compress_to_vec_zlib(b"\x30\x03crl", 6);
+        let mut objects = alloc::format!(
+            "20 0 obj\n<< /Certs [21 0 R] /CRLs [22 0 R] >>\nendobj\n\
+             21 0 obj\n<< /Length 5 >>\nstream\n\x30\x03crt\nendstream\nendobj\n\
+             22 0 obj\n<< /Filter /FlateDecode /Length {} >>\nstream\n",
+            crl.len()
+        )
+        .into_bytes();
+        objects.extend_from_slice(&crl);
+        objects.extend_from_slice(b"\nendstream\nendobj\n");
+        let xref = pdf.windows(4).position(|w| w == b"xref").unwrap();
+        let with_dss = [&pdf[..xref], &objects, &pdf[xref..]].concat();
+
+        let dss = super::extract_dss(&with_dss).unwrap();
+        assert_eq!(dss.certs, [b"\x30\x03crt".to_vec()]);
+        assert_eq!(dss.crls, [b"\x30\x03crl".to_vec()]);
+        assert!(dss.ocsps.is_empty());
+    }
+
+    #[test]
+    fn content_tokens_skip_stray_delimiters() {
+        let tokens = super::parse_content_tokens(b"BT ) } (Hi) > Tj ET");
+        let operators: Vec<_> = tokens
+            .iter()
+            .filter_map(|t| match t {
+                super::Token::Operator(op) => Some(op.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(operators, ["BT", "Tj", "ET"]);
+    }
+
+    #[test]
+    fn inline_image_data_is_skipped() {
+        // The image data holds bytes that look like a string shown with `Tj`, and `EI`s that
+        // are part of longer runs
+        let content =
+            b"BT (A) Tj ET BI /W 4 /H 1 /BPC 8 /CS /G ID \x00(x) Tj\xffEI EIx EI\nBT (B) Tj ET";
+        let tokens = super::parse_content_tokens(content);
+        let operators: Vec<_> = tokens
+            .iter()
+            .filter_map(|t| match t {
+                super::Token::Operator(op) => Some(op.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            operators,
+            ["BT", "Tj", "ET", "BI", "ID", "EI", "BT", "Tj", "ET"]
+        );
+        let strings = tokens
+            .iter()
+            .filter(|t| matches!(t, super::Token::String(_)))
+            .count();
+        assert_eq!(strings, 2);
+    }
+
+    // A one-page document whose page shows `first` and then `content`, with `resources`
+    // added to the page's and `objects` appended before the cross-reference table. The
+    // table no longer matches, so objects are found by scanning.
+    fn page_with(content: &str, resources: &str, objects: &str) -> Vec<u8> {
+        let pdf = zkpdf_writer::Document::from_pages(&[&["first"]])
+            .to_bytes()
+            .unwrap();
+        let content = alloc::format!("BT /F1 12 Tf 1 0 0 1 72 720 Tm (first) Tj ET {}", content);
+        alloc::string::String::from_utf8_lossy(&pdf)
+            .replacen(
+                "/Font << /F1 3 0 R >>",
+                &alloc::format!("/Font << /F1 3 0 R >> {}", resources),
+                1,
+            )
+            .replacen(
+                "/Length 45 >>\nstream\nBT /F1 12 Tf 1 0 0 1 72 720 Tm (first) Tj ET",
+                &alloc::format!("/Length {} >>\nstream\n{}", content.len() + 1, content),
+                1,
+            )
+            .replacen("xref", &alloc::format!("{}\nxref", objects), 1)
+            .into_bytes()
+    }
+
+    #[test]
+    fn tiling_pattern_text_is_extracted() {
+        let cell = "BT /F1 12 Tf 72 700 Td (pattern) Tj ET";
+        let pdf = page_with(
+            "/Pattern cs /P1 scn 0 0 612 792 re f",
+            "/Pattern << /P1 6 0 R >>",
+            &alloc::format!(
+                "6 0 obj\n<< /Type /Pattern /PatternType 1 /PaintType 1 /TilingType 1 \
+                 /BBox [0 0 612 792] /XStep 612 /YStep 792 /Matrix [1 0 0 1 0 -100] \
+                 /Resources << /Font << /F1 3 0 R >> >> /Length {} >>\nstream\n{}\nendstream\nendobj",
+                cell.len(),
+                cell
+            ),
+        );
+        let text = super::extract_text(pdf.clone()).unwrap();
+        assert!(text[0].contains("first") && text[0].contains("pattern"));
+
+        let runs = super::extract_text_positions(&pdf).unwrap();
+        let run = runs.iter().find(|run| run.text == "pattern").unwrap();
+        // Placed through the pattern's /Matrix
+        assert_eq!((run.x, run.y), (72.0, 600.0));
+    }
+
+    #[test]
+    fn type3_glyph_procedures_are_extracted() {
+        // Glyph 1 shows text in Helvetica, glyph 2 only paints
+        let shows = "1000 0 d0 BT /F1 1000 Tf 0 0 Td (glyph) Tj ET";
+        let paints = "500 0 d0 0 0 500 500 re f";
+        let pdf = page_with(
+            "BT /F2 10 Tf 1 0 0 1 100 500 Tm (\\002\\001) Tj ET",
+            "/Font << /F1 3 0 R /F2 6 0 R >>",
+            &alloc::format!(
+                "6 0 obj\n<< /Type /Font /Subtype /Type3 /FontBBox [0 0 1000 1000] \
+                 /FontMatrix [0.001 0 0 0.001 0 0] /CharProcs << /g1 7 0 R /g2 8 0 R >> \
+                 /Encoding << /Type /Encoding /Differences [1 /g1 /g2] >> /FirstChar 1 \
+                 /LastChar 2 /Widths [1000 500] /Resources << /Font << /F1 3 0 R >> >> >>\n\
+                 endobj\n7 0 obj\n<< /Length {} >>\nstream\n{}\nendstream\nendobj\n\
+                 8 0 obj\n<< /Length {} >>\nstream\n{}\nendstream\nendobj",
+                shows.len(),
+                shows,
+                paints.len(),
+                paints
+            ),
+        );
+        let text = super::extract_text(pdf.clone()).unwrap();
+        assert!(text[0].contains("glyph"));
+
+        let runs = super::extract_text_positions(&pdf).unwrap();
+        let run = runs.iter().find(|run| run.text == "glyph").unwrap();
+        // After glyph 2, 500 units of glyph space at size 10; 1000 units of glyph space are
+        // one text space unit, so the Helvetica text is 10 units high
+        assert!((run.x - 105.0).abs() < 1e-3 && (run.y - 500.0).abs() < 1e-3);
+        assert!((run.font_size - 10.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn structure_tree_reads_in_logical_order() {
+        // The table is drawn before the heading above it; the heading's role is custom, and
+        // the second cell's properties are given by name
+        let pdf = page_with(
+            "/P << /MCID 0 >> BDC BT /F1 12 Tf 72 600 Td (Amount) Tj ET EMC \
+             /Span /MC0 BDC BT /F1 12 Tf 200 600 Td (100) Tj ET EMC \
+             /Heading << /MCID 1 /Lang (en) >> BDC BT /F1 18 Tf 72 680 Td (Invoice) Tj ET EMC",
+            "/Properties << /MC0 << /MCID 2 >> >>",
+            "10 0 obj\n<< /Type /StructTreeRoot /K 11 0 R /RoleMap << /Heading /H1 >> >>\n\
+             endobj\n11 0 obj\n<< /Type /StructElem /S /Document /P 10 0 R /K [12 0 R 13 0 R] >>\n\
+             endobj\n12 0 obj\n<< /S /Heading /Pg 4 0 R /K 1 >>\nendobj\n\
+             13 0 obj\n<< /S /Table /Pg 4 0 R /K << /S /TR /K [14 0 R 15 0 R] >> >>\nendobj\n\
+             14 0 obj\n<< /S /TD /K << /Type /MCR /MCID 0 >> >>\nendobj\n\
+             15 0 obj\n<< /S /TD /K [2] >>\nendobj",
+        );
+        let pdf = alloc::string::String::from_utf8_lossy(&pdf)
+            .replacen("/Pages 2 0 R", "/Pages 2 0 R /StructTreeRoot 10 0 R", 1)
+            .into_bytes();
+
+        let elements = super::extract_structure(&pdf).unwrap();
+        assert_eq!(elements.len(), 1);
+        let document = &elements[0];
+        assert_eq!(document.role, "Document");
+        // `first` is not marked content, so no element holds it
+        assert_eq!(document.text(), "Invoice Amount 100");
+
+        let roles = |element: &super::StructElement| -> Vec<alloc::string::String> {
+            element
+                .kids
+                .iter()
+                .filter_map(|kid| match kid {
+                    super::types::StructKid::Element(element) => Some(element.role.clone()),
+                    super::types::StructKid::Text(_) => None,
+                })
+                .collect()
+        };
+        assert_eq!(roles(document), ["H1", "Table"]);
+        let super::types::StructKid::Element(table) = &document.kids[1] else {
+            panic!("table expected");
+        };
+        assert_eq!(table.page, Some(0));
+        let super::types::StructKid::Element(row) = &table.kids[0] else {
+            panic!("row expected");
+        };
+        assert_eq!(roles(row), ["TD", "TD"]);
+        assert_eq!(row.text(), "Amount 100");
+
+        let untagged = zkpdf_writer::Document::from_pages(&[&["first"]])
+            .to_bytes()
+            .unwrap();
+        assert!(super::extract_structure(&untagged).unwrap().is_empty());
+    }
+
+    #[test]
+    fn content_tokens_continue_across_streams() {
+        // Stream boundaries end tokens, and the array opened in the second stream closes in
+        // the third
+        let streams: [&[u8]; 4] = [b"BT /F1 12 Tf", b"[(A) -250", b"(B)] TJ", b"ET"];
+        let tokens = super::content_tokens(&streams);
+        let rendered: Vec<_> = tokens.iter().map(|t| alloc::format!("{:?}", t)).collect();
+        let expected = super::parse_content_tokens(b"BT /F1 12 Tf [(A) -250 (B)] TJ ET");
+        let expected: Vec<_> = expected.iter().map(|t| alloc::format!("{:?}", t)).collect();
+        assert_eq!(rendered, expected);
+        assert!(matches!(&tokens[3], super::Token::Operator(op) if op == "Tf"));
+        assert!(matches!(&tokens[4], super::Token::Array(items) if items.len() == 3));
+    }
+
+    #[test]
+    fn find_text_returns_offsets_usable_for_verification() {
+        let pages = vec!["Name: Jose\nJosé Jose".to_string(), "Jose".to_string()];
+
+        let matches = super::find_text(&pages, "Jose", None);
+        assert_eq!(matches.len(), 3);
+        assert_eq!((matches[1].page, matches[1].offset), (0, 17));
+        assert!(pages[0][matches[1].offset..].starts_with("Jose"));
+        assert_eq!(super::find_text(&pages, "Jose", Some(1)).len(), 1);
+        assert!(super::find_text(&pages, "", None).is_empty());
+    }
+
+    #[test]
+    fn find_substring_locates_text_in_the_pdf() {
+        let pdf = zkpdf_writer::Document::from_pages(&[&["Total 100", "Paid 100"], &["100"]])
+            .to_bytes()
+            .unwrap();
+        let options = super::ExtractOptions::default();
+        let pages = super::extract_text_with_options(pdf.clone(), &options).unwrap();
+
+        let matches = super::find_substring(&pdf, "100", &options).unwrap();
+        assert_eq!(matches.len(), 3);
+        assert_eq!(matches[2].page, 1);
+        for found in &matches {
+            assert_eq!(&pages[found.page][found.offset..][..found.len], "100");
+        }
+    }
+
+    #[test]
+    fn document_info_reports_pages_and_metadata() {
+        let pdf_data = include_bytes!("../../sample-pdfs/GST-certificate.pdf");
+
+        let info = super::document_info(pdf_data).expect("Failed to read document info");
+        assert_eq!(info.page_count(), 3);
+        assert!(info.page_char_counts.iter().all(|&count| count > 0));
+        assert!(
+            info.metadata
+                .get("Producer")
+                .is_some_and(|producer| producer.starts_with("iText")),
+            "Producer not found in metadata: {:?}",
+            info.metadata
+        );
+    }
+
+    #[test]
+    fn structured_tokens_decode_to_page_text() {
+        let pdf_data = include_bytes!("../../sample-pdfs/digitally_signed.pdf");
+
+        let pages = super::extract_structured(pdf_data).expect("Failed to tokenize pages");
+        let page = &pages[0];
+        let mut font = None;
+        let mut shown = Vec::new();
+        for (i, token) in page.tokens.iter().enumerate() {
+            match token {
+                super::Token::Operator(op) if op == "Tf" => {
+                    if let super::Token::Name(name) = &page.tokens[i - 2] {
+                        font = page.fonts.get(name);
+                    }
+                }
+                super::Token::Operator(op) if op == "Tj" => {
+                    if let (Some(font), super::Token::String(bytes)) = (font, &page.tokens[i - 1]) {
+                        shown.push(font.decode(bytes));
+                    }
+                }
+                super::Token::Operator(op) if op == "TJ" => {
+                    if let (Some(font), super::Token::Array(elements)) = (font, &page.tokens[i - 1])
+                    {
+                        for element in elements {
+                            if let super::Token::String(bytes) = element {
+                                shown.push(font.decode(bytes));
+                            }
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        assert_eq!(shown.concat().trim(), "Sample Signed PDF Document");
+    }
+
+    #[test]
+    fn text_positions_lie_on_the_page() {
+        let pdf_data = include_bytes!("../../sample-pdfs/GST-certificate.pdf");
+
+        let runs = super::extract_text_positions(pdf_data).expect("Failed to extract positions");
+        assert!(runs.iter().any(|run| run.page == 2));
+        // A4 portrait
+        assert!(runs
+            .iter()
+            .all(|run| (0.0..=596.0).contains(&run.x) && (0.0..=843.0).contains(&run.y)));
+
+        let options = super::ExtractOptions {
+            normalization: super::Normalization::Layout,
+            ..super::ExtractOptions::default()
+        };
+        let pages = super::extract_text_with_options(pdf_data.to_vec(), &options)
+            .expect("Failed to extract text");
+        assert!(pages[0].contains("Goods and Services Tax"));
+    }
+
+    #[test]
+    fn pages_carry_geometry_and_words() {
+        let pdf_data = include_bytes!("../../sample-pdfs/GST-certificate.pdf");
+
+        let pages = super::extract_pages(pdf_data, &super::ExtractOptions::default())
+            .expect("Failed to extract pages");
+        assert_eq!(pages.len(), 3);
+        let page = &pages[0];
+        assert_eq!(
+            page.text,
+            super::extract_text(pdf_data.to_vec()).unwrap()[0]
+        );
+        // A4 portrait
+        assert_eq!(page.rotation, 0);
+        assert!((page.media_box[2] - 595.0).abs() < 1.0 && (page.media_box[3] - 842.0).abs() < 1.0);
+        assert!(!page.fonts.is_empty());
+        let header = page
+            .words
+            .iter()
+            .find(|word| word.text == "Government")
+            .expect("Word not found");
+        assert!(
+            header.y > page.media_box[3] / 2.0,
+            "Header is not in the top half"
+        );
+    }
+
+    #[test]
+    fn script_normalization_matches_typed_text() {
+        use zkpdf_writer::{Document, Font, Page};
+
+        // Arabic presentation forms and Hebrew in drawing order, and a decomposed Tamil vowel sign
+        let lines = [
+            "\u{FEE1}\u{FE8E}\u{FEE0}\u{FEB3}",
+            "\u{0B95}\u{0BC6}\u{0BBE}",
+            "Name: \u{05DD}\u{05D5}\u{05DC}\u{05E9}",
+        ];
+        let doc = Document {
+            pages: alloc::vec![Page::from_lines_in(Font::UNICODE, &lines)],
+            ..Document::default()
+        };
+        let pdf = doc.to_bytes().unwrap();
+
+        let pages = super::extract_text(pdf.clone()).unwrap();
+        assert_eq!(pages[0], lines.join("\n"));
+        let options = super::ExtractOptions {
+            unicode_normalization: true,
+            bidi: true,
+            ..super::ExtractOptions::default()
+        };
+        let pages = super::extract_text_with_options(pdf, &options).unwrap();
+        assert_eq!(pages[0], "سلام\nகொ\nName: שלום");
+    }
 }
 
 #[cfg(feature = "private_tests")]
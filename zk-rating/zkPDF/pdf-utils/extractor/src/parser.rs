@@ -1,6 +1,8 @@
-use std::collections::HashMap;
+use alloc::string::String;
+use alloc::vec::Vec;
 
 use crate::types::{PdfError, PdfObj};
+use crate::HashMap;
 
 pub struct Parser<'a> {
     pub data: &'a [u8],
@@ -41,7 +43,7 @@ impl<'a> Parser<'a> {
     }
 
     // Parse a PDF name (starting after the initial '/')
-    pub fn parse_name(&mut self) -> Result<PdfObj, PdfError> {
+    pub fn parse_name(&mut self) -> Result<PdfObj<'a>, PdfError> {
         if self.pos >= self.len || self.data[self.pos] != b'/' {
             return Err(PdfError::ParseError("Name must start with '/'"));
         }
@@ -93,7 +95,7 @@ impl<'a> Parser<'a> {
     }
 
     // Parse a numeric value (integer or real)
-    pub fn parse_number(&mut self) -> Result<PdfObj, PdfError> {
+    pub fn parse_number(&mut self) -> Result<PdfObj<'a>, PdfError> {
         self.skip_whitespace_and_comments();
         let start = self.pos;
         if start >= self.len {
@@ -136,7 +138,7 @@ impl<'a> Parser<'a> {
     }
 
     // Parse a literal string enclosed in parentheses
-    pub fn parse_literal_string(&mut self) -> Result<PdfObj, PdfError> {
+    pub fn parse_literal_string(&mut self) -> Result<PdfObj<'a>, PdfError> {
         if self.pos >= self.len || self.data[self.pos] != b'(' {
             return Err(PdfError::ParseError("String must start with '('"));
         }
@@ -234,7 +236,7 @@ impl<'a> Parser<'a> {
     }
 
     // Parse a hex string enclosed in < >
-    pub fn parse_hex_string(&mut self) -> Result<PdfObj, PdfError> {
+    pub fn parse_hex_string(&mut self) -> Result<PdfObj<'a>, PdfError> {
         if self.pos >= self.len
             || self.data[self.pos] != b'<'
             || (self.pos + 1 < self.len && self.data[self.pos + 1] == b'<')
@@ -284,7 +286,7 @@ impl<'a> Parser<'a> {
         }
     }
 
-    pub fn parse_value(&mut self) -> Result<PdfObj, PdfError> {
+    pub fn parse_value(&mut self) -> Result<PdfObj<'a>, PdfError> {
         self.skip_whitespace_and_comments();
         if self.pos >= self.len {
             return Err(PdfError::ParseError("Unexpected EOF while parsing value"));
@@ -421,7 +423,7 @@ impl<'a> Parser<'a> {
     }
 
     // Parse a dictionary (assuming initial '<<' already consumed)
-    pub fn parse_dictionary(&mut self) -> Result<PdfObj, PdfError> {
+    pub fn parse_dictionary(&mut self) -> Result<PdfObj<'a>, PdfError> {
         let mut dict = HashMap::new();
         loop {
             self.skip_whitespace_and_comments();
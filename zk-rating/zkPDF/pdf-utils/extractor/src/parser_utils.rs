@@ -1,32 +1,20 @@
-use std::str;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::str;
 
 use crate::types::Token;
 
-pub fn fold_array_tokens(tokens: Vec<Token>) -> Vec<Token> {
+/// Folds the tokens between `ArrayStart` and its `ArrayEnd` into `Token::Array`, consuming
+/// `tokens` up to the `ArrayEnd` closing an array that is already open (`nested`) or to the end.
+/// An array never closed takes the remaining tokens.
+pub fn fold_array_tokens(tokens: &mut impl Iterator<Item = Token>, nested: bool) -> Vec<Token> {
     let mut result = Vec::new();
-    let mut i = 0;
-    while i < tokens.len() {
-        if let Token::ArrayStart = &tokens[i] {
-            let mut arr_elems = Vec::new();
-            i += 1;
-            let mut depth = 1;
-            while i < tokens.len() && depth > 0 {
-                match &tokens[i] {
-                    Token::ArrayStart => depth += 1,
-                    Token::ArrayEnd => depth -= 1,
-                    _ => {}
-                }
-                if depth == 0 {
-                    break;
-                }
-                arr_elems.push(tokens[i].clone());
-                i += 1;
-            }
-            result.push(Token::Array(fold_array_tokens(arr_elems)));
-        } else {
-            result.push(tokens[i].clone());
+    while let Some(token) = tokens.next() {
+        match token {
+            Token::ArrayStart => result.push(Token::Array(fold_array_tokens(tokens, true))),
+            Token::ArrayEnd if nested => break,
+            token => result.push(token),
         }
-        i += 1;
     }
     result
 }
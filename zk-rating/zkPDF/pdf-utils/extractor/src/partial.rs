@@ -0,0 +1,287 @@
+//! Loading only what one page needs. Extracting the whole document parses every object the
+//! cross-reference data lists; for a single page, objects are instead read at their offsets as
+//! they are reached: the nodes of the page tree, then the page's resources, content streams
+//! and annotations. Other pages are never entered and their fonts, images and object streams
+//! are neither parsed, decompressed nor decrypted.
+
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::crypt::Decryptor;
+use crate::parser::Parser;
+use crate::types::{PdfError, PdfObj};
+use crate::xref::{self, XrefEntry};
+use crate::{expand_obj_stream, parse_indirect_object, HashMap, HashSet, ParsedObjects};
+
+type Objects<'a> = HashMap<(u32, u16), PdfObj<'a>>;
+
+/// The objects page `page` is extracted from, and the trailer, as of `revision`. `None` if the
+/// cross-reference data is missing or damaged, or anything fails to load, in which case the
+/// whole document has to be parsed.
+pub(crate) fn page_objects(
+    data: &[u8],
+    revision: Option<usize>,
+    page: usize,
+) -> Option<ParsedObjects<'_>> {
+    let revisions = xref::read_revisions(data)?;
+    let last = revision.unwrap_or(revisions.len() - 1);
+    let revision = xref::merge_revisions(revisions.get(..=last)?);
+    let trailer = revision.trailer;
+    let mut loader = Loader {
+        data,
+        entries: revision.entries,
+        objects: HashMap::new(),
+        streams: BTreeMap::new(),
+        decryptor: None,
+    };
+    loader.load_page(&trailer, page).ok()?;
+    trace_event!(objects = loader.objects.len(), "page objects loaded");
+    Some((loader.objects, trailer))
+}
+
+struct Loader<'a> {
+    data: &'a [u8],
+    entries: BTreeMap<u32, XrefEntry>,
+    objects: Objects<'a>,
+    /// Object streams expanded so far, by object number.
+    streams: BTreeMap<u32, Objects<'a>>,
+    decryptor: Option<Decryptor>,
+}
+
+impl<'a> Loader<'a> {
+    fn load_page(
+        &mut self,
+        trailer: &HashMap<String, PdfObj>,
+        page: usize,
+    ) -> Result<(), PdfError> {
+        if let Some(PdfObj::Reference(id)) = trailer.get("Encrypt") {
+            self.load(*id)?;
+        }
+        self.decryptor = Decryptor::new(trailer, &self.objects)?;
+        let Some(PdfObj::Reference(root)) = trailer.get("Root") else {
+            return Err(PdfError::ParseError("Trailer has no /Root"));
+        };
+        self.load(*root)?;
+        let pages = match self.objects.get(root) {
+            Some(PdfObj::Dictionary(catalog)) => match catalog.get("Pages") {
+                Some(PdfObj::Reference(id)) => *id,
+                _ => return Err(PdfError::ParseError("Pages reference not found in Catalog")),
+            },
+            _ => return Err(PdfError::ParseError("Catalog object is not a dictionary")),
+        };
+
+        let mut tree = HashSet::new();
+        let mut pending = Vec::new();
+        self.load_tree(pages, page, &mut 0, &mut tree, &mut pending)?;
+
+        // Everything the page refers to, short of the page tree: its `/Parent` and the other
+        // pages an annotation may point at are already loaded
+        let mut seen = HashSet::new();
+        while let Some(id) = pending.pop() {
+            if tree.contains(&id) || !seen.insert(id) {
+                continue;
+            }
+            self.load(id)?;
+            if let Some(object) = self.objects.get(&id) {
+                references(object, &mut pending);
+            }
+        }
+        Ok(())
+    }
+
+    // Load the page tree below `node`, numbering pages from `count`. The references of page
+    // `page`, and of the nodes it inherits attributes from, are added to `pending`.
+    fn load_tree(
+        &mut self,
+        node: (u32, u16),
+        page: usize,
+        count: &mut usize,
+        tree: &mut HashSet<(u32, u16)>,
+        pending: &mut Vec<(u32, u16)>,
+    ) -> Result<(), PdfError> {
+        // A cycle is reported when the tree is walked again to extract the page
+        if !tree.insert(node) {
+            return Ok(());
+        }
+        self.load(node)?;
+        let first = *count;
+        let kids = match self.objects.get(&node) {
+            Some(object) if kind(object) == Some("Page") => {
+                if *count == page {
+                    references(object, pending);
+                }
+                *count += 1;
+                return Ok(());
+            }
+            Some(object @ PdfObj::Dictionary(dict)) if kind(object) == Some("Pages") => {
+                dict.get("Kids").cloned()
+            }
+            _ => return Ok(()),
+        };
+        let kids = match kids {
+            Some(PdfObj::Reference(id)) => {
+                self.load(id)?;
+                self.objects.get(&id).cloned()
+            }
+            kids => kids,
+        };
+        let Some(PdfObj::Array(kids)) = kids else {
+            return Ok(());
+        };
+        for kid in &kids {
+            match kid {
+                PdfObj::Reference(id) => self.load_tree(*id, page, count, tree, pending)?,
+                PdfObj::Dictionary(_) if kind(kid) == Some("Page") => {
+                    if *count == page {
+                        references(kid, pending);
+                    }
+                    *count += 1;
+                }
+                _ => {}
+            }
+        }
+        if (first..*count).contains(&page) {
+            if let Some(PdfObj::Dictionary(dict)) = self.objects.get(&node) {
+                for (key, value) in dict {
+                    if key != "Kids" && key != "Parent" {
+                        references(value, pending);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Read object `id` if it is not loaded yet. Objects the cross-reference data does not list
+    // are left out, as they are when the whole document is parsed.
+    fn load(&mut self, id: (u32, u16)) -> Result<(), PdfError> {
+        if self.objects.contains_key(&id) {
+            return Ok(());
+        }
+        match self.entries.get(&id.0) {
+            Some(&XrefEntry::InUse { offset, generation }) => {
+                let (key, mut value) = self.read_at(offset)?;
+                if key != (id.0, generation) {
+                    return Err(PdfError::in_object(
+                        "Object is not at its xref offset",
+                        offset,
+                        Some((id.0, generation)),
+                    ));
+                }
+                if let Some(decryptor) = &self.decryptor {
+                    decryptor.decrypt_indirect(key, &mut value);
+                }
+                self.objects.insert(key, value);
+            }
+            Some(&XrefEntry::Compressed { stream, .. }) => {
+                if !self.streams.contains_key(&stream) {
+                    self.load((stream, 0))?;
+                    let contained = expand_obj_stream(&self.objects, stream)?;
+                    self.streams.insert(stream, contained);
+                }
+                if let Some(value) = self.streams[&stream].get(&(id.0, 0)) {
+                    self.objects.insert((id.0, 0), value.clone());
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    // Parse the object at `offset`. Parsing the whole document reads objects in file order, so
+    // a stream's indirect `/Length` is used if it comes earlier in the file.
+    fn read_at(&mut self, offset: usize) -> Result<((u32, u16), PdfObj<'a>), PdfError> {
+        if offset >= self.data.len() {
+            return Err(PdfError::in_object(
+                "Object offset out of bounds",
+                offset,
+                None,
+            ));
+        }
+        let mut parser = Parser::new(self.data);
+        parser.pos = offset;
+        let parsed = parse_indirect_object(&mut parser, &self.objects)?;
+        let length = match &parsed.1 {
+            PdfObj::Stream(stream) => match stream.dict.get("Length") {
+                Some(PdfObj::Reference(length)) if !self.objects.contains_key(length) => *length,
+                _ => return Ok(parsed),
+            },
+            _ => return Ok(parsed),
+        };
+        match self.entries.get(&length.0) {
+            Some(&XrefEntry::InUse {
+                offset: earlier, ..
+            }) if earlier < offset => {
+                self.load(length)?;
+                let mut parser = Parser::new(self.data);
+                parser.pos = offset;
+                parse_indirect_object(&mut parser, &self.objects)
+            }
+            _ => Ok(parsed),
+        }
+    }
+}
+
+// Add the indirect references in `object` to `pending`
+fn references(object: &PdfObj, pending: &mut Vec<(u32, u16)>) {
+    match object {
+        PdfObj::Reference(id) => pending.push(*id),
+        PdfObj::Array(items) => {
+            for item in items {
+                references(item, pending);
+            }
+        }
+        PdfObj::Dictionary(dict) => {
+            for value in dict.values() {
+                references(value, pending);
+            }
+        }
+        PdfObj::Stream(stream) => {
+            for value in stream.dict.values() {
+                references(value, pending);
+            }
+        }
+        _ => {}
+    }
+}
+
+// The `/Type` of a dictionary or stream
+fn kind<'a>(object: &'a PdfObj) -> Option<&'a str> {
+    let dict = match object {
+        PdfObj::Dictionary(dict) => dict,
+        PdfObj::Stream(stream) => &stream.dict,
+        _ => return None,
+    };
+    match dict.get("Type") {
+        Some(PdfObj::Name(name)) => Some(name),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_objects;
+    use zkpdf_writer::Document;
+
+    #[test]
+    fn other_pages_are_not_loaded() {
+        let pdf = Document::from_pages(&[&["first"], &["second"], &["third"]])
+            .to_bytes()
+            .unwrap();
+        let streams = |objects: &Objects| {
+            objects
+                .values()
+                .filter(|object| matches!(object, PdfObj::Stream(_)))
+                .count()
+        };
+        let (all, _) = parse_objects(&pdf, None).unwrap();
+        let (objects, _) = page_objects(&pdf, None, 1).unwrap();
+        // Only the content stream of the page itself
+        assert_eq!(streams(&objects), 1);
+        assert_eq!(streams(&all), 3);
+        assert_eq!(crate::extract_text_page(&pdf, 1).unwrap(), "second");
+        assert!(page_objects(&pdf, Some(1), 0).is_none());
+    }
+}
@@ -0,0 +1,73 @@
+//! Script normalization of page text. Text extracted from certificates in Indic scripts,
+//! Arabic or Hebrew rarely matches what a user types: vowel signs and nuktas come out
+//! decomposed, Arabic and Hebrew come out as the presentation forms the font shapes glyphs
+//! with, and right-to-left runs come out in the order their glyphs were drawn, left to right.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use unicode_bidi::BidiInfo;
+use unicode_normalization::char::decompose_compatible;
+use unicode_normalization::UnicodeNormalization;
+
+/// NFC, with the Arabic and Hebrew presentation forms folded to the letters they shape.
+pub(crate) fn nfc(text: &str) -> String {
+    let mut folded = String::with_capacity(text.len());
+    for ch in text.chars() {
+        if is_presentation_form(ch) {
+            decompose_compatible(ch, |c| folded.push(c));
+        } else {
+            folded.push(ch);
+        }
+    }
+    folded.nfc().collect()
+}
+
+/// Each line reordered from visual to logical order by the Unicode bidi algorithm, which for
+/// a line drawn left to right reverses its right-to-left runs back. Lines without
+/// right-to-left text are unchanged.
+pub(crate) fn reorder(text: &str) -> String {
+    text.split('\n')
+        .map(|line| {
+            let info = BidiInfo::new(line, None);
+            if !info.has_rtl() {
+                return String::from(line);
+            }
+            info.paragraphs
+                .iter()
+                .map(|paragraph| info.reorder_line(paragraph, paragraph.range.clone()))
+                .collect()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// Alphabetic Presentation Forms from the Hebrew block on, and Arabic Presentation Forms-A and
+// -B. The Latin ligatures before them are left alone, as NFC leaves `ﬁ` elsewhere.
+fn is_presentation_form(ch: char) -> bool {
+    matches!(ch, '\u{FB1D}'..='\u{FDFF}' | '\u{FE70}'..='\u{FEFE}')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presentation_forms_fold_to_letters() {
+        // Initial, medial and final forms of "سلام", and the lam-alef ligature of "لا"
+        assert_eq!(nfc("\u{FEB3}\u{FEE0}\u{FE8E}\u{FEE1}"), "سلام");
+        assert_eq!(nfc("\u{FEFB}"), "\u{0644}\u{0627}");
+        // Hebrew shin with dagesh keeps its marks, in canonical order
+        assert_eq!(nfc("\u{FB49}"), "\u{05E9}\u{05BC}");
+        // A Devanagari nukta and a Tamil two-part vowel sign compose; Latin ligatures stay
+        assert_eq!(nfc("\u{0928}\u{093C}"), "\u{0929}");
+        assert_eq!(nfc("\u{0B95}\u{0BC6}\u{0BBE}"), "\u{0B95}\u{0BCA}");
+        assert_eq!(nfc("e\u{0301}\u{FB01}"), "é\u{FB01}");
+    }
+
+    #[test]
+    fn right_to_left_runs_are_put_in_logical_order() {
+        // "שלום" drawn left to right comes out last letter first
+        assert_eq!(reorder("Name: םולש\nplain"), "Name: שלום\nplain");
+        assert_eq!(reorder("no rtl here"), "no rtl here");
+    }
+}
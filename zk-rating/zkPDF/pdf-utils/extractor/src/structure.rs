@@ -0,0 +1,181 @@
+//! Logical structure of tagged PDFs: the structure tree under the catalog's `/StructTreeRoot`,
+//! whose elements refer to marked-content sequences of the pages by MCID. Reading the tree
+//! instead of the pages gives text in the order the author meant it to be read, with the role
+//! of every part (heading, paragraph, table cell).
+
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::layout::layout_text;
+use crate::types::{PdfObj, StructElement, StructKid, TextRun};
+use crate::{decode_text_string, resolve, HashMap, HashSet};
+
+type Objects<'a> = HashMap<(u32, u16), PdfObj<'a>>;
+
+/// Entries of the `/RoleMap` followed from one structure type; maps may chain, or loop.
+const ROLE_MAP_STEPS: usize = 8;
+
+/// The catalog's `/StructTreeRoot`, if the document is tagged.
+pub(crate) fn tree_root<'o, 'a>(
+    objects: &'o Objects<'a>,
+    trailer: &'o HashMap<String, PdfObj<'a>>,
+) -> Option<&'o HashMap<String, PdfObj<'a>>> {
+    let Some(PdfObj::Dictionary(catalog)) = resolve(trailer.get("Root"), objects) else {
+        return None;
+    };
+    match resolve(catalog.get("StructTreeRoot"), objects) {
+        Some(PdfObj::Dictionary(root)) => Some(root),
+        _ => None,
+    }
+}
+
+/// The top-level elements of the tree, with the text of the marked content they refer to
+/// taken from `runs`. `pages` are the page objects in page order, to number `/Pg` entries.
+pub(crate) fn elements(
+    root: &HashMap<String, PdfObj>,
+    objects: &Objects,
+    pages: &[&PdfObj],
+    runs: &[TextRun],
+) -> Vec<StructElement> {
+    let mut marked: BTreeMap<(usize, u32), Vec<TextRun>> = BTreeMap::new();
+    for run in runs {
+        if let Some(mcid) = run.mcid {
+            marked
+                .entry((run.page, mcid))
+                .or_default()
+                .push(run.clone());
+        }
+    }
+    let role_map = match resolve(root.get("RoleMap"), objects) {
+        Some(PdfObj::Dictionary(role_map)) => Some(role_map),
+        _ => None,
+    };
+    let mut reader = Reader {
+        objects,
+        pages,
+        role_map,
+        text: marked
+            .into_iter()
+            .map(|(key, runs)| (key, layout_text(&runs)))
+            .collect(),
+        visited: HashSet::new(),
+    };
+    let mut kids = Vec::new();
+    if let Some(k) = root.get("K") {
+        reader.kid(k, None, &mut kids);
+    }
+    kids.into_iter()
+        .filter_map(|kid| match kid {
+            StructKid::Element(element) => Some(element),
+            StructKid::Text(_) => None,
+        })
+        .collect()
+}
+
+struct Reader<'o, 'a> {
+    objects: &'o Objects<'a>,
+    pages: &'o [&'o PdfObj<'a>],
+    role_map: Option<&'o HashMap<String, PdfObj<'a>>>,
+    /// Text of each marked-content sequence, by page and MCID
+    text: BTreeMap<(usize, u32), String>,
+    /// Elements read so far, so an element listed twice or a kid pointing back up the tree
+    /// is read once
+    visited: HashSet<(u32, u16)>,
+}
+
+impl Reader<'_, '_> {
+    // Append what one entry of a `/K` array, or a whole `/K`, stands for to `out`. `page` is
+    // the page inherited from the element the kid belongs to.
+    fn kid(&mut self, kid: &PdfObj, page: Option<usize>, out: &mut Vec<StructKid>) {
+        match kid {
+            PdfObj::Number(mcid) => self.marked(page, *mcid as u32, out),
+            PdfObj::Array(items) => {
+                for item in items {
+                    self.kid(item, page, out);
+                }
+            }
+            PdfObj::Reference(id) if self.visited.insert(*id) => {
+                if let Some(object) = self.objects.get(id) {
+                    self.kid(object, page, out);
+                }
+            }
+            PdfObj::Dictionary(dict) => match dict.get("Type") {
+                // Marked content in a form XObject (`/Stm`) is numbered within the form, and
+                // is not read
+                Some(PdfObj::Name(kind)) if kind == "MCR" => {
+                    if dict.contains_key("Stm") {
+                        return;
+                    }
+                    let page = self.page(dict.get("Pg")).or(page);
+                    if let Some(PdfObj::Number(mcid)) = resolve(dict.get("MCID"), self.objects) {
+                        self.marked(page, *mcid as u32, out);
+                    }
+                }
+                // Annotations and XObjects as a whole
+                Some(PdfObj::Name(kind)) if kind == "OBJR" => {}
+                _ => {
+                    if let Some(element) = self.element(dict, page) {
+                        out.push(StructKid::Element(element));
+                    }
+                }
+            },
+            _ => {}
+        }
+    }
+
+    fn element(
+        &mut self,
+        dict: &HashMap<String, PdfObj>,
+        page: Option<usize>,
+    ) -> Option<StructElement> {
+        let Some(PdfObj::Name(tag)) = resolve(dict.get("S"), self.objects) else {
+            return None;
+        };
+        let page = self.page(dict.get("Pg")).or(page);
+        let mut kids = Vec::new();
+        // Replacement text stands for the element's whole content
+        if let Some(PdfObj::String(text)) = resolve(dict.get("ActualText"), self.objects) {
+            kids.push(StructKid::Text(decode_text_string(text)));
+        } else if let Some(k) = dict.get("K") {
+            self.kid(k, page, &mut kids);
+        }
+        Some(StructElement {
+            role: self.role(tag),
+            page,
+            kids,
+        })
+    }
+
+    fn marked(&self, page: Option<usize>, mcid: u32, out: &mut Vec<StructKid>) {
+        let Some(page) = page else {
+            return;
+        };
+        if let Some(text) = self.text.get(&(page, mcid)) {
+            out.push(StructKid::Text(text.clone()));
+        }
+    }
+
+    // Index of the page a `/Pg` entry refers to
+    fn page(&self, pg: Option<&PdfObj>) -> Option<usize> {
+        let Some(PdfObj::Reference(id)) = pg else {
+            return None;
+        };
+        let page = self.objects.get(id)?;
+        self.pages.iter().position(|p| core::ptr::eq(*p, page))
+    }
+
+    fn role(&self, tag: &str) -> String {
+        let mut role = tag;
+        for _ in 0..ROLE_MAP_STEPS {
+            match self
+                .role_map
+                .and_then(|map| resolve(map.get(role), self.objects))
+            {
+                Some(PdfObj::Name(mapped)) if mapped != role => role = mapped,
+                _ => break,
+            }
+        }
+        String::from(role)
+    }
+}
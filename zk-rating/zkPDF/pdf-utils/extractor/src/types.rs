@@ -1,10 +1,72 @@
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
 use core::fmt;
-use std::collections::HashMap;
+
+use crate::HashMap;
 
 #[derive(Debug)]
 pub enum PdfError {
     ParseError(&'static str),
     DecompressionError,
+    /// The document is encrypted in a way that cannot be read without a password.
+    EncryptionError(&'static str),
+    /// An indirect object could not be parsed.
+    ObjectError {
+        /// Byte offset in the file where parsing stopped.
+        offset: usize,
+        /// Id and generation of the object, if its header could be read.
+        object: Option<(u32, u16)>,
+        kind: ParseErrorKind,
+        message: &'static str,
+    },
+}
+
+/// What kind of damage stopped the parser inside an object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// The file ends in the middle of the object.
+    UnexpectedEof,
+    /// `obj`, `endobj` or `endstream` is missing.
+    MissingKeyword,
+    /// The cross-reference data points at something other than the object.
+    BadOffset,
+    /// A token or structure is malformed.
+    Syntax,
+}
+
+impl PdfError {
+    /// A parse error for the object `object` (if known), stopped at `offset`.
+    pub(crate) fn in_object(
+        message: &'static str,
+        offset: usize,
+        object: Option<(u32, u16)>,
+    ) -> Self {
+        let kind = match message {
+            "Unexpected EOF in stream"
+            | "Unexpected EOF while parsing value"
+            | "Unexpected EOF in number"
+            | "Unterminated literal string"
+            | "Unterminated array" => ParseErrorKind::UnexpectedEof,
+            "Missing 'obj' keyword"
+            | "Missing 'endstream'"
+            | "Missing 'endobj' after stream"
+            | "Missing 'endobj' for dictionary object"
+            | "Missing 'endobj' for object" => ParseErrorKind::MissingKeyword,
+            "Object offset out of bounds" | "Object is not at its xref offset" => {
+                ParseErrorKind::BadOffset
+            }
+            _ => ParseErrorKind::Syntax,
+        };
+        PdfError::ObjectError {
+            offset,
+            object,
+            kind,
+            message,
+        }
+    }
 }
 
 impl fmt::Display for PdfError {
@@ -12,10 +74,351 @@ impl fmt::Display for PdfError {
         match self {
             PdfError::ParseError(msg) => write!(f, "Parse error: {}", msg),
             PdfError::DecompressionError => write!(f, "Decompression failed"),
+            PdfError::EncryptionError(msg) => write!(f, "Encrypted document: {}", msg),
+            PdfError::ObjectError {
+                offset,
+                object,
+                kind,
+                message,
+            } => {
+                write!(f, "Parse error")?;
+                if let Some((id, generation)) = object {
+                    write!(f, " in object {} {}", id, generation)?;
+                }
+                write!(f, " at byte {}: {} ({})", offset, message, kind)
+            }
+        }
+    }
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ParseErrorKind::UnexpectedEof => "unexpected end of file",
+            ParseErrorKind::MissingKeyword => "missing keyword",
+            ParseErrorKind::BadOffset => "bad cross-reference offset",
+            ParseErrorKind::Syntax => "syntax error",
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PdfError {}
+
+/// How extracted page text is post-processed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Normalization {
+    /// Runs of whitespace collapsed to one space and empty lines dropped. This is the text that
+    /// offsets in proofs refer to.
+    #[default]
+    Canonical,
+    /// Text as emitted by the content stream operators.
+    Raw,
+    /// Lines rebuilt from where the text is drawn (see `extract_text_positions`), then
+    /// collapsed like `Canonical`. Columns and table cells come out row by row, whatever order
+    /// the content stream draws them in.
+    Layout,
+}
+
+/// Options for `extract_text_with_options`.
+#[derive(Debug, Clone, Default)]
+pub struct ExtractOptions {
+    pub normalization: Normalization,
+    /// Return pages that fail to load (bad fonts, corrupt streams) as empty strings instead of
+    /// failing the whole document, and skip objects that fail to parse as `parse_pdf_lenient`
+    /// does.
+    pub lenient: bool,
+    /// Only extract the first `max_pages` pages. Later pages are not decoded.
+    pub max_pages: Option<usize>,
+    /// Read the document as of this revision, 0 being the original document and each
+    /// incremental update adding one, instead of the latest.
+    pub revision: Option<usize>,
+    /// Also extract the text drawn by the appearance streams of the page's annotations, such
+    /// as FreeText stamps and filled-in form widgets, after the page's own text.
+    pub annotations: bool,
+    /// Normalize page text to NFC, folding Arabic and Hebrew presentation forms to their
+    /// letters, so Indic, Arabic and Hebrew text matches substrings typed by a user.
+    pub unicode_normalization: bool,
+    /// Reorder right-to-left runs from the visual order they were drawn in to logical order.
+    /// A heuristic: a line whose first strong character is right-to-left may come out with its
+    /// runs in the wrong order.
+    pub bidi: bool,
+}
+
+/// Summary of a document returned by `document_info`.
+#[derive(Debug, Clone)]
+pub struct DocumentInfo {
+    /// Number of characters of extracted text on each page.
+    pub page_char_counts: Vec<usize>,
+    /// Text entries of the trailer `/Info` dictionary, e.g. `Title` or `Producer`.
+    pub metadata: BTreeMap<String, String>,
+}
+
+impl DocumentInfo {
+    pub fn page_count(&self) -> usize {
+        self.page_char_counts.len()
+    }
+}
+
+/// An object `parse_pdf_lenient` could not read as written.
+#[derive(Debug)]
+pub struct ParseWarning {
+    /// Byte offset of the object, or of the trailer.
+    pub offset: usize,
+    /// Id and generation from the object's header, if it could be read.
+    pub object: Option<(u32, u16)>,
+    /// The error that stopped the strict parser. The object was kept if `recovered` is set.
+    pub error: PdfError,
+    /// Whether the object was read anyway, ignoring its `/Length` and a missing `endobj`.
+    pub recovered: bool,
+}
+
+/// Text of one page returned by `extract_page_text`.
+#[derive(Debug, Clone)]
+pub struct PageText {
+    pub text: String,
+    /// Total number of pages in the document.
+    pub page_count: usize,
+}
+
+/// Kind of an AcroForm field, from its `/FT` entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormFieldType {
+    Text,
+    Button,
+    Choice,
+    Signature,
+    Unknown,
+}
+
+impl FormFieldType {
+    pub(crate) fn from_name(name: &str) -> Self {
+        match name {
+            "Tx" => FormFieldType::Text,
+            "Btn" => FormFieldType::Button,
+            "Ch" => FormFieldType::Choice,
+            "Sig" => FormFieldType::Signature,
+            _ => FormFieldType::Unknown,
+        }
+    }
+}
+
+impl fmt::Display for FormFieldType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            FormFieldType::Text => "text",
+            FormFieldType::Button => "button",
+            FormFieldType::Choice => "choice",
+            FormFieldType::Signature => "signature",
+            FormFieldType::Unknown => "unknown",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A terminal AcroForm field returned by `extract_form_fields`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormField {
+    /// Fully qualified name: partial names of the field and its ancestors joined with `.`.
+    pub name: String,
+    pub field_type: FormFieldType,
+    /// Text value, export value of a button, or selected options joined with `, `. `None` for
+    /// unset fields and signature fields.
+    pub value: Option<String>,
+}
+
+/// A signed signature field returned by `extract_signature_fields`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureField {
+    /// Fully qualified name, as in `FormField::name`.
+    pub name: String,
+    /// `/Type` of the signature dictionary: `Sig`, `DocTimeStamp` or none.
+    pub sig_type: Option<String>,
+    pub sub_filter: Option<String>,
+    /// `/ByteRange` as written; whether it fits the file is not checked.
+    pub byte_range: Vec<usize>,
+    /// `/Contents`, decoded from its hex or literal string, padding included.
+    pub contents: Vec<u8>,
+}
+
+/// Validation data of a PAdES-LT document's `/DSS`, returned by `extract_dss`: DER-encoded
+/// certificates, OCSP responses and CRLs, one per stream.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DocumentSecurityStore {
+    pub certs: Vec<Vec<u8>>,
+    pub ocsps: Vec<Vec<u8>>,
+    pub crls: Vec<Vec<u8>>,
+}
+
+/// Occurrence of a search string in the extracted text of a page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextMatch {
+    /// Zero-based page index.
+    pub page: usize,
+    /// Byte offset into the page text, as expected by `verify_text`.
+    pub offset: usize,
+    /// Length of the match in bytes.
+    pub len: usize,
+}
+
+/// Content of one page as tokens, returned by `extract_structured`.
+#[derive(Debug, Clone)]
+pub struct PageTokens {
+    /// The page's content streams, concatenated and tokenized, with arrays folded into
+    /// `Token::Array`, and inline dictionaries into `Token::Dictionary`. Form XObjects drawn
+    /// with `Do` are not expanded.
+    pub tokens: Vec<Token>,
+    /// The page's fonts by resource name, the name a `Tf` operand gives; decode the strings
+    /// shown with one with `PdfFont::decode`.
+    pub fonts: HashMap<String, PdfFont>,
+}
+
+/// Text drawn by one string of a text-showing operator (`Tj`, `TJ`, `'`, `"`), returned by
+/// `extract_text_positions`. Coordinates are in default user space: points from the bottom
+/// left corner of the page.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextRun {
+    /// Zero-based page index.
+    pub page: usize,
+    pub text: String,
+    /// Start of the run on its baseline.
+    pub x: f32,
+    pub y: f32,
+    /// Horizontal advance of the run, from the font's widths, or estimated at half the font
+    /// size per glyph for fonts without widths.
+    pub width: f32,
+    /// Font size scaled by the text and transformation matrices.
+    pub font_size: f32,
+    /// Marked-content identifier of the sequence the run is drawn in (`BDC` with an `/MCID`
+    /// property), by which a tagged PDF's structure tree refers to it.
+    pub mcid: Option<u32>,
+}
+
+/// An element of a tagged PDF's logical structure (`/StructTreeRoot`), returned by
+/// `extract_structure`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructElement {
+    /// Structure type, mapped through the document's `/RoleMap`: `H1`, `P`, `Table`, `TR`,
+    /// `TD`, ...
+    pub role: String,
+    /// Zero-based index of the page the element's content is on, from its `/Pg` or an
+    /// ancestor's.
+    pub page: Option<usize>,
+    /// The element's marked content and child elements, in logical reading order.
+    pub kids: Vec<StructKid>,
+}
+
+impl StructElement {
+    /// Text of the element and everything below it in reading order, pieces separated by a
+    /// space.
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+        for kid in &self.kids {
+            let piece = match kid {
+                StructKid::Text(piece) => Cow::Borrowed(piece.as_str()),
+                StructKid::Element(element) => Cow::Owned(element.text()),
+            };
+            if piece.is_empty() {
+                continue;
+            }
+            if !text.is_empty() {
+                text.push(' ');
+            }
+            text.push_str(&piece);
         }
+        text
     }
 }
 
+/// One kid of a `StructElement`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StructKid {
+    /// Text of a marked-content sequence, or the element's `/ActualText`.
+    Text(String),
+    Element(StructElement),
+}
+
+/// A word of a `Page`, with its box in default user space: across the advances of its
+/// glyphs, and from the baseline up one font size.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Word {
+    pub text: String,
+    /// Lower left corner of the box.
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+/// A page returned by `extract_pages`: its text, where each word is drawn and the page's
+/// geometry, for claims about where on the page something appears. Coordinates are in
+/// default user space, before `rotation` is applied for display.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Page {
+    /// Zero-based page index.
+    pub index: usize,
+    /// The page's text, as `extract_text_with_options` returns it with the same options.
+    pub text: String,
+    /// Words by position: lines top to bottom, left to right within a line.
+    pub words: Vec<Word>,
+    /// Base names of the fonts text is shown in, sorted.
+    pub fonts: Vec<String>,
+    /// Degrees the page is turned clockwise when displayed: 0, 90, 180 or 270.
+    pub rotation: u16,
+    /// `[x0, y0, x1, y1]`, lower left corner first. US Letter when the page tree gives none.
+    pub media_box: [f32; 4],
+}
+
+/// Colour space of an image XObject.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImageColorSpace {
+    Gray,
+    Rgb,
+    Cmyk,
+    /// Palette of `base` colours, one entry of `base.components()` bytes per index.
+    Indexed {
+        base: Box<ImageColorSpace>,
+        palette: Vec<u8>,
+    },
+    /// A stencil mask (`/ImageMask true`): one bit per pixel, painted where the sample is 0.
+    Mask,
+    /// Anything else, by name (`Lab`, `Separation`, ...).
+    Other(String),
+}
+
+impl ImageColorSpace {
+    /// Samples per pixel.
+    pub fn components(&self) -> usize {
+        match self {
+            ImageColorSpace::Rgb => 3,
+            ImageColorSpace::Cmyk => 4,
+            _ => 1,
+        }
+    }
+}
+
+/// An image XObject drawn on a page, returned by `extract_images`.
+#[derive(Debug, Clone)]
+pub struct PdfImage {
+    /// Zero-based page index.
+    pub page: usize,
+    /// XObject resource name, e.g. `Im1`. Images inside form XObjects are named by their path,
+    /// e.g. `Fm1/Im1`.
+    pub name: String,
+    pub width: usize,
+    pub height: usize,
+    pub bits_per_component: u8,
+    pub color_space: ImageColorSpace,
+    /// `/Decode [1 0]`: sample values are inverted.
+    pub inverted: bool,
+    /// Filter the data is still encoded with (`DCTDecode`, `JPXDecode`, ...), or `None` when
+    /// `data` holds the decoded samples: unfiltered and Flate streams are decoded, PNG
+    /// predictors included.
+    pub encoding: Option<String>,
+    /// Rows of samples, each padded to a whole byte, unless `encoding` is set.
+    pub data: Vec<u8>,
+}
+
 #[derive(Debug, Clone)]
 pub struct PdfFont {
     pub base_name: Option<String>,
@@ -23,32 +426,168 @@ pub struct PdfFont {
     pub encoding: Option<String>,
     pub to_unicode_map: Option<HashMap<u32, String>>,
     pub differences: Option<HashMap<u32, String>>,
+    /// Set for Type0 fonts, from the `/Encoding` CMap and the descendant CIDFont.
+    pub cid: Option<CidFont>,
+    /// Glyph names by code from the built-in encoding of the embedded font program, read for
+    /// simple fonts without a ToUnicode CMap.
+    pub builtin_encoding: Option<HashMap<u32, String>>,
+    /// `None` when the font dictionary gives no widths, as for most standard 14 fonts.
+    pub widths: Option<FontWidths>,
+    /// Set for Type3 fonts whose glyph procedures show text themselves.
+    pub type3: Option<Type3Glyphs>,
 }
 
+/// The glyph procedures (`/CharProcs`) of a Type3 font that show text, for text drawn as
+/// glyphs of another font.
 #[derive(Debug, Clone)]
-pub struct PageContent {
-    pub content_streams: Vec<Vec<u8>>,
+pub struct Type3Glyphs {
+    /// `/FontMatrix`, from glyph space to text space.
+    pub matrix: [f32; 6],
+    /// Decoded content streams of the procedures, by code.
+    pub procs: HashMap<u32, Vec<u8>>,
+    /// Fonts of the Type3 font's `/Resources`, which the procedures show text in.
     pub fonts: HashMap<String, PdfFont>,
-    pub resources: HashMap<String, PdfObj>,
 }
 
+impl PdfFont {
+    /// Text of a string operand shown in this font, decoded the way page text is: through the
+    /// ToUnicode CMap, the CIDFont, or the font's encoding.
+    pub fn decode(&self, bytes: &[u8]) -> String {
+        crate::cmap::decode_bytes(bytes, self)
+    }
+}
+
+/// Glyph advances of a font, in thousandths of a text space unit per unit of font size.
+#[derive(Debug, Clone, Default)]
+pub struct FontWidths {
+    /// By code for simple fonts (`/FirstChar` and `/Widths`), by CID for Type0 fonts (`/W`
+    /// of the descendant CIDFont).
+    pub widths: HashMap<u32, f32>,
+    /// Width of any other glyph: the descriptor's `/MissingWidth`, or the CIDFont's `/DW`.
+    pub default: f32,
+}
+
+/// The predefined CMap named by a Type0 font's `/Encoding`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CidEncoding {
+    /// `Identity-H` / `Identity-V`: every two-byte code is its CID.
+    Identity { vertical: bool },
+    /// The `Uni*-UCS2-*` and `Uni*-UTF16-*` CMaps: codes are UTF-16BE text.
+    Utf16 { vertical: bool },
+    /// Any other CMap, predefined or embedded; codes are read as two-byte CIDs.
+    Other(String),
+}
+
+/// `/CIDSystemInfo` of a CIDFont: the character collection its CIDs index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CidSystemInfo {
+    pub registry: String,
+    pub ordering: String,
+    pub supplement: u32,
+}
+
+/// How a Type0 font's codes become glyphs, and glyphs text when there is no ToUnicode CMap.
+#[derive(Debug, Clone)]
+pub struct CidFont {
+    pub encoding: CidEncoding,
+    pub system_info: Option<CidSystemInfo>,
+    /// `/CIDToGIDMap` of a CIDFontType2 font, indexed by CID; `None` for the identity map.
+    pub cid_to_gid: Option<Vec<u16>>,
+    /// Unicode values of glyph ids, inverted from the `cmap` table of an embedded TrueType
+    /// program (`/FontFile2`).
+    pub glyph_unicode: HashMap<u16, char>,
+}
+
+impl CidEncoding {
+    pub(crate) fn from_name(name: &str) -> Self {
+        match name {
+            "Identity-H" => CidEncoding::Identity { vertical: false },
+            "Identity-V" => CidEncoding::Identity { vertical: true },
+            _ if name.starts_with("Uni")
+                && (name.contains("-UCS2-") || name.contains("-UTF16-")) =>
+            {
+                CidEncoding::Utf16 {
+                    vertical: name.ends_with("-V"),
+                }
+            }
+            _ => CidEncoding::Other(name.into()),
+        }
+    }
+}
+
+/// A decoded page. Content streams stored without a filter, and the stream data of resources,
+/// are borrowed from the document bytes rather than copied.
+#[derive(Debug, Clone, Default)]
+pub struct PageContent<'a> {
+    pub content_streams: Vec<Cow<'a, [u8]>>,
+    pub fonts: HashMap<String, PdfFont>,
+    pub resources: HashMap<String, PdfObj<'a>>,
+    /// Entries of the page's `/Annots` array, as stored (usually references).
+    pub annotations: Vec<PdfObj<'a>>,
+    /// `/MediaBox`, set on the page or inherited, as `[x0, y0, x1, y1]` with the lower left
+    /// corner first.
+    pub media_box: Option<[f32; 4]>,
+    /// `/Rotate`, set on the page or inherited: degrees clockwise, 0, 90, 180 or 270.
+    pub rotation: u16,
+}
+
+/// A document read by `parse_pdf_lenient`, with what had to be skipped or repaired.
+#[derive(Debug)]
+pub struct RecoveredPdf<'a> {
+    /// Pages in document order; pages that failed to load are empty.
+    pub pages: Vec<PageContent<'a>>,
+    pub objects: HashMap<(u32, u16), PdfObj<'a>>,
+    pub warnings: Vec<ParseWarning>,
+}
+
+/// A PDF object. `'a` is the lifetime of the document bytes, which stream data borrows from.
 #[derive(Debug, Clone)]
-pub enum PdfObj {
+pub enum PdfObj<'a> {
     Null,
     Boolean(bool),
     Number(f64),
     Name(String),
     String(Vec<u8>),
-    Array(Vec<PdfObj>),
-    Dictionary(HashMap<String, PdfObj>),
-    Stream(PdfStream),
+    Array(Vec<PdfObj<'a>>),
+    Dictionary(HashMap<String, PdfObj<'a>>),
+    Stream(PdfStream<'a>),
     Reference((u32, u16)),
 }
 
+impl PdfObj<'_> {
+    /// The object with any stream data copied, independent of the document bytes.
+    pub fn into_owned(self) -> PdfObj<'static> {
+        match self {
+            PdfObj::Null => PdfObj::Null,
+            PdfObj::Boolean(value) => PdfObj::Boolean(value),
+            PdfObj::Number(value) => PdfObj::Number(value),
+            PdfObj::Name(name) => PdfObj::Name(name),
+            PdfObj::String(bytes) => PdfObj::String(bytes),
+            PdfObj::Array(items) => {
+                PdfObj::Array(items.into_iter().map(PdfObj::into_owned).collect())
+            }
+            PdfObj::Dictionary(dict) => PdfObj::Dictionary(owned_dictionary(dict)),
+            PdfObj::Stream(stream) => PdfObj::Stream(PdfStream {
+                dict: owned_dictionary(stream.dict),
+                data: Cow::Owned(stream.data.into_owned()),
+            }),
+            PdfObj::Reference(id) => PdfObj::Reference(id),
+        }
+    }
+}
+
+fn owned_dictionary(dict: HashMap<String, PdfObj<'_>>) -> HashMap<String, PdfObj<'static>> {
+    dict.into_iter()
+        .map(|(key, value)| (key, value.into_owned()))
+        .collect()
+}
+
+/// A stream object. `data` is the raw (still encoded) stream, borrowed from the document bytes
+/// unless it had to be decrypted.
 #[derive(Debug, Clone)]
-pub struct PdfStream {
-    pub dict: HashMap<String, PdfObj>,
-    pub data: Vec<u8>,
+pub struct PdfStream<'a> {
+    pub dict: HashMap<String, PdfObj<'a>>,
+    pub data: Cow<'a, [u8]>,
 }
 
 #[derive(Debug, Clone)]
@@ -60,4 +599,6 @@ pub enum Token {
     Array(Vec<Token>),
     ArrayStart,
     ArrayEnd,
+    /// An inline dictionary, such as the properties of marked content (`/P << /MCID 0 >> BDC`).
+    Dictionary(HashMap<String, Token>),
 }
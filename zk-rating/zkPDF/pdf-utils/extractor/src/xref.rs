@@ -0,0 +1,575 @@
+//! Cross-reference tables and streams. `read_revisions` follows the `startxref` / `/Prev`
+//! chain and groups its sections into revisions, so objects can be read at their offsets
+//! instead of by scanning the file.
+
+use alloc::borrow::Cow;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use miniz_oxide::inflate::decompress_to_vec_zlib;
+
+use crate::parser::Parser;
+use crate::types::{PdfError, PdfObj};
+use crate::{handle_stream_filters, parse_indirect_object, HashMap, HashSet};
+
+/// Where an object lives, as recorded by a cross-reference section.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum XrefEntry {
+    Free,
+    /// An indirect object at a byte offset.
+    InUse {
+        offset: usize,
+        generation: u16,
+    },
+    /// The `index`th object of the object stream `stream`.
+    Compressed {
+        stream: u32,
+        index: usize,
+    },
+}
+
+/// The objects and trailer of one revision: an original document or an incremental update.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Revision<'a> {
+    pub entries: BTreeMap<u32, XrefEntry>,
+    pub trailer: HashMap<String, PdfObj<'a>>,
+}
+
+// One xref table or stream, and the offset it was read at
+struct Section<'a> {
+    offset: usize,
+    entries: BTreeMap<u32, XrefEntry>,
+    trailer: HashMap<String, PdfObj<'a>>,
+}
+
+/// Revisions from the original document to the latest update, or `None` if the chain of
+/// cross-reference sections is missing or damaged.
+pub(crate) fn read_revisions(data: &[u8]) -> Option<Vec<Revision<'_>>> {
+    let mut next = Some(last_startxref(data)?);
+    let mut sections: Vec<Section> = Vec::new();
+    let mut visited = HashSet::new();
+    while let Some(offset) = next {
+        if !visited.insert(offset) {
+            return None;
+        }
+        let (entries, trailer) = read_section(data, offset)?;
+        next = match trailer.get("Prev") {
+            Some(PdfObj::Number(prev)) if *prev >= 0.0 => Some(*prev as usize),
+            _ => None,
+        };
+        sections.push(Section {
+            offset,
+            entries,
+            trailer,
+        });
+    }
+
+    // Sections come newest first. An update's /Prev points back in the file; a /Prev
+    // pointing forward links the first-page section of a linearized file to the main one,
+    // which together make up a single revision.
+    let mut revisions: Vec<Vec<Section>> = Vec::new();
+    let mut prev_offset = None;
+    for section in sections {
+        let offset = section.offset;
+        match (revisions.last_mut(), prev_offset) {
+            (Some(group), Some(prev)) if offset > prev => group.push(section),
+            _ => revisions.push(alloc::vec![section]),
+        }
+        prev_offset = Some(offset);
+    }
+    revisions.reverse();
+    Some(
+        revisions
+            .into_iter()
+            .map(|group| merge(group.into_iter().rev().map(|s| (s.entries, s.trailer))))
+            .collect(),
+    )
+}
+
+/// Folds revisions, oldest first, into the view of the last one.
+pub(crate) fn merge_revisions<'a>(revisions: &[Revision<'a>]) -> Revision<'a> {
+    merge(
+        revisions
+            .iter()
+            .map(|r| (r.entries.clone(), r.trailer.clone())),
+    )
+}
+
+// Later entries and trailer keys replace earlier ones
+fn merge<'a>(
+    sections: impl Iterator<Item = (BTreeMap<u32, XrefEntry>, HashMap<String, PdfObj<'a>>)>,
+) -> Revision<'a> {
+    let mut merged = Revision::default();
+    for (entries, trailer) in sections {
+        merged.entries.extend(entries);
+        merged.trailer.extend(trailer);
+    }
+    merged.trailer.remove("Prev");
+    merged
+}
+
+// The offset after the last `startxref` keyword
+fn last_startxref(data: &[u8]) -> Option<usize> {
+    let pos = data.windows(9).rposition(|w| w == b"startxref")?;
+    let mut parser = Parser::new(&data[pos + 9..]);
+    match parser.parse_number().ok()? {
+        PdfObj::Number(offset) if offset > 0.0 => Some(offset as usize),
+        _ => None,
+    }
+}
+
+// An xref table with its trailer (and the entries of a hybrid file's /XRefStm), or an xref
+// stream with its dictionary as the trailer
+fn read_section(
+    data: &[u8],
+    offset: usize,
+) -> Option<(BTreeMap<u32, XrefEntry>, HashMap<String, PdfObj<'_>>)> {
+    let mut parser = Parser::new(data);
+    parser.pos = offset;
+    if !parser.remaining_starts_with(b"xref") {
+        return read_xref_stream(data, offset);
+    }
+    parser.pos += 4;
+
+    let mut entries = BTreeMap::new();
+    loop {
+        parser.skip_whitespace_and_comments();
+        if parser.remaining_starts_with(b"trailer") {
+            parser.pos += 7;
+            break;
+        }
+        let first = number(&mut parser)?;
+        let count = number(&mut parser)?;
+        for id in first..first.checked_add(count)? {
+            let offset = number(&mut parser)?;
+            let generation = number(&mut parser)?;
+            parser.skip_whitespace_and_comments();
+            let entry = match parser.data.get(parser.pos)? {
+                b'n' => XrefEntry::InUse {
+                    offset,
+                    generation: generation as u16,
+                },
+                b'f' => XrefEntry::Free,
+                _ => return None,
+            };
+            parser.pos += 1;
+            entries.insert(id as u32, entry);
+        }
+    }
+
+    parser.skip_whitespace_and_comments();
+    if !parser.remaining_starts_with(b"<<") {
+        return None;
+    }
+    parser.pos += 2;
+    let PdfObj::Dictionary(trailer) = parser.parse_dictionary().ok()? else {
+        return None;
+    };
+    // Objects the table leaves free may be compressed ones listed in the stream
+    if let Some(PdfObj::Number(stream_offset)) = trailer.get("XRefStm") {
+        let (stream_entries, _) = read_xref_stream(data, *stream_offset as usize)?;
+        for (id, entry) in stream_entries {
+            if matches!(entries.get(&id), None | Some(XrefEntry::Free)) {
+                entries.insert(id, entry);
+            }
+        }
+    }
+    Some((entries, trailer))
+}
+
+fn read_xref_stream(
+    data: &[u8],
+    offset: usize,
+) -> Option<(BTreeMap<u32, XrefEntry>, HashMap<String, PdfObj<'_>>)> {
+    let mut parser = Parser::new(data);
+    parser.pos = offset;
+    let (_, PdfObj::Stream(stream)) = parse_indirect_object(&mut parser, &HashMap::new()).ok()?
+    else {
+        return None;
+    };
+    if !matches!(stream.dict.get("Type"), Some(PdfObj::Name(t)) if t == "XRef") {
+        return None;
+    }
+    let decoded = match stream.dict.get("Filter") {
+        Some(filter) => {
+            let mut out = Vec::new();
+            handle_stream_filters(
+                filter,
+                stream.dict.get("DecodeParms"),
+                &stream.data,
+                &|bytes| decompress_to_vec_zlib(bytes).map_err(|_| PdfError::DecompressionError),
+                &mut out,
+            )
+            .ok()?;
+            Cow::Owned(out.pop()?)
+        }
+        None => stream.data,
+    };
+
+    let widths: Vec<usize> = match stream.dict.get("W") {
+        Some(PdfObj::Array(w)) if w.len() == 3 => w
+            .iter()
+            .map(|n| match n {
+                PdfObj::Number(n) if (0.0..=8.0).contains(n) => Some(*n as usize),
+                _ => None,
+            })
+            .collect::<Option<_>>()?,
+        _ => return None,
+    };
+    let row_len: usize = widths.iter().sum();
+    if row_len == 0 {
+        return None;
+    }
+    let size = match stream.dict.get("Size") {
+        Some(PdfObj::Number(n)) => *n as usize,
+        _ => return None,
+    };
+    let ranges: Vec<(usize, usize)> = match stream.dict.get("Index") {
+        Some(PdfObj::Array(index)) => index
+            .chunks(2)
+            .map(|pair| match pair {
+                [PdfObj::Number(first), PdfObj::Number(count)] => {
+                    Some((*first as usize, *count as usize))
+                }
+                _ => None,
+            })
+            .collect::<Option<_>>()?,
+        _ => alloc::vec![(0, size)],
+    };
+
+    let mut rows = decoded.chunks_exact(row_len);
+    let mut entries = BTreeMap::new();
+    for (first, count) in ranges {
+        for id in first..first.checked_add(count)? {
+            let row = rows.next()?;
+            let (kind, rest) = row.split_at(widths[0]);
+            let (field2, field3) = rest.split_at(widths[1]);
+            // A zero-width type field defaults to 1
+            let kind = if widths[0] == 0 { 1 } else { be(kind) };
+            let entry = match kind {
+                0 => XrefEntry::Free,
+                1 => XrefEntry::InUse {
+                    offset: be(field2) as usize,
+                    generation: be(field3) as u16,
+                },
+                2 => XrefEntry::Compressed {
+                    stream: be(field2) as u32,
+                    index: be(field3) as usize,
+                },
+                // Unknown types are to be read as null references
+                _ => XrefEntry::Free,
+            };
+            entries.insert(id as u32, entry);
+        }
+    }
+    Some((entries, stream.dict))
+}
+
+// A non-negative integer; `parse_number` reads anything else as 0 without moving
+fn number(parser: &mut Parser) -> Option<usize> {
+    parser.skip_whitespace_and_comments();
+    if !parser.data.get(parser.pos)?.is_ascii_digit() {
+        return None;
+    }
+    match parser.parse_number().ok()? {
+        PdfObj::Number(n) if n >= 0.0 => Some(n as usize),
+        _ => None,
+    }
+}
+
+fn be(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0, |n, &b| (n << 8) | b as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::format;
+    use alloc::string::ToString;
+    use alloc::vec;
+    use miniz_oxide::deflate::compress_to_vec_zlib;
+
+    const CONTENT: &str = "BT /F1 12 Tf 72 720 Td (Hello xref) Tj ET";
+
+    // Catalog, pages, page, font and content stream, numbered from 1
+    fn bodies() -> Vec<String> {
+        vec![
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            "<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_string(),
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 612 792] \
+             /Resources << /Font << /F1 4 0 R >> >> /Contents 5 0 R >>"
+                .to_string(),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>".to_string(),
+            format!(
+                "<< /Length {} >>\nstream\n{}\nendstream",
+                CONTENT.len(),
+                CONTENT
+            ),
+        ]
+    }
+
+    fn object(out: &mut Vec<u8>, id: usize, body: &str) -> usize {
+        let offset = out.len();
+        out.extend(format!("{} 0 obj\n{}\nendobj\n", id, body).bytes());
+        offset
+    }
+
+    fn table(first: usize, offsets: &[usize]) -> String {
+        let mut table = format!("xref\n{} {}\n", first, offsets.len());
+        for &offset in offsets {
+            match offset {
+                0 => table.push_str("0000000000 65535 f \n"),
+                _ => table.push_str(&format!("{:010} 00000 n \n", offset)),
+            }
+        }
+        table
+    }
+
+    // The bodies, each preceded by `junk`, and a classic xref table
+    fn with_table(junk: &str) -> Vec<u8> {
+        let mut out = b"%PDF-1.7\n".to_vec();
+        let mut offsets = vec![0];
+        for (i, body) in bodies().iter().enumerate() {
+            out.extend(junk.bytes());
+            offsets.push(object(&mut out, i + 1, body));
+        }
+        let xref = out.len();
+        out.extend(table(0, &offsets).bytes());
+        out.extend(
+            format!(
+                "trailer\n<< /Size 6 /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+                xref
+            )
+            .bytes(),
+        );
+        out
+    }
+
+    #[test]
+    fn reads_objects_at_table_offsets() {
+        let pdf = with_table("junk )\n");
+        // The linear scan stops at the junk
+        assert!(crate::scan_objects(&pdf, Some(0), None).is_err());
+        assert_eq!(crate::extract_text(pdf).unwrap(), ["Hello xref"]);
+
+        let pdf = with_table("");
+        let revisions = read_revisions(&pdf).unwrap();
+        assert_eq!(revisions.len(), 1);
+        assert_eq!(revisions[0].entries[&0], XrefEntry::Free);
+        assert_eq!(
+            revisions[0].entries[&1],
+            XrefEntry::InUse {
+                offset: 9,
+                generation: 0
+            }
+        );
+    }
+
+    #[test]
+    fn falls_back_to_scanning() {
+        let mut pdf = with_table("");
+        // Point the table one byte past object 1
+        let at = pdf.windows(10).position(|w| w == b"0000000009").unwrap();
+        pdf[at + 9] = b'8';
+        assert!(read_revisions(&pdf).is_some());
+        assert_eq!(crate::extract_text(pdf.clone()).unwrap(), ["Hello xref"]);
+
+        // A subsection header that is not a number
+        let at = pdf.windows(5).position(|w| w == b"xref\n").unwrap();
+        pdf[at + 5] = b'x';
+        assert!(read_revisions(&pdf).is_none());
+        assert_eq!(crate::extract_text(pdf).unwrap(), ["Hello xref"]);
+    }
+
+    #[test]
+    fn reads_xref_streams_and_object_streams() {
+        let bodies = bodies();
+        let mut out = b"%PDF-1.7\n".to_vec();
+        let font = object(&mut out, 4, &bodies[3]);
+        let content = object(&mut out, 5, &bodies[4]);
+
+        // Objects 1 to 3 in object stream 6
+        let mut header = String::new();
+        let mut packed = String::new();
+        for (i, body) in bodies[..3].iter().enumerate() {
+            header.push_str(&format!("{} {} ", i + 1, packed.len()));
+            packed.push_str(body);
+            packed.push('\n');
+        }
+        let obj_stream = object(
+            &mut out,
+            6,
+            &format!(
+                "<< /Type /ObjStm /N 3 /First {} /Length {} >>\nstream\n{}{}\nendstream",
+                header.len(),
+                header.len() + packed.len(),
+                header,
+                packed
+            ),
+        );
+
+        // W [1 2 1], PNG Up predicted over 4 columns
+        let xref = out.len();
+        let rows: Vec<[u8; 4]> = vec![
+            [0, 0, 0, 255],
+            [2, 0, 6, 0],
+            [2, 0, 6, 1],
+            [2, 0, 6, 2],
+            [1, (font >> 8) as u8, font as u8, 0],
+            [1, (content >> 8) as u8, content as u8, 0],
+            [1, (obj_stream >> 8) as u8, obj_stream as u8, 0],
+            [1, (xref >> 8) as u8, xref as u8, 0],
+        ];
+        let mut predicted = Vec::new();
+        let mut prev = [0u8; 4];
+        for row in &rows {
+            predicted.push(2);
+            predicted.extend(row.iter().zip(prev).map(|(b, p)| b.wrapping_sub(p)));
+            prev = *row;
+        }
+        let data = compress_to_vec_zlib(&predicted, 6);
+        out.extend(
+            format!(
+                "7 0 obj\n<< /Type /XRef /Size 8 /W [1 2 1] /Root 1 0 R /Length {} \
+                 /Filter /FlateDecode /DecodeParms << /Predictor 12 /Columns 4 >> >>\nstream\n",
+                data.len()
+            )
+            .bytes(),
+        );
+        out.extend(&data);
+        out.extend(format!("\nendstream\nendobj\nstartxref\n{}\n%%EOF\n", xref).bytes());
+
+        let revisions = read_revisions(&out).unwrap();
+        assert_eq!(
+            revisions[0].entries[&2],
+            XrefEntry::Compressed {
+                stream: 6,
+                index: 1
+            }
+        );
+        assert_eq!(crate::extract_text(out).unwrap(), ["Hello xref"]);
+    }
+
+    #[test]
+    fn linearized_sections_are_one_revision() {
+        // A first-page section up front for objects 3 to 5, whose /Prev is the main section
+        // at the end; the final startxref points at the first-page section
+        let render = |prev: usize| {
+            let mut out = b"%PDF-1.7\n".to_vec();
+            let first_page = out.len();
+            let mut offsets = [0; 6];
+            let first_table_len = table(3, &[1; 3]).len();
+            let trailer = format!(
+                "trailer\n<< /Size 6 /Root 1 0 R /Prev {:010} >>\nstartxref\n0\n%%EOF\n",
+                prev
+            );
+            let objects_start = out.len() + first_table_len + trailer.len();
+            let mut objects = Vec::new();
+            for (i, body) in bodies().iter().enumerate() {
+                offsets[i + 1] = objects_start + object(&mut objects, i + 1, body);
+            }
+            out.extend(table(3, &offsets[3..]).bytes());
+            out.extend(trailer.bytes());
+            out.extend(&objects);
+            let main = out.len();
+            out.extend(table(0, &offsets[..3]).bytes());
+            out.extend(
+                format!("trailer\n<< /Size 6 >>\nstartxref\n{}\n%%EOF\n", first_page).bytes(),
+            );
+            (out, main)
+        };
+        let (_, main) = render(0);
+        let (pdf, _) = render(main);
+
+        let revisions = read_revisions(&pdf).unwrap();
+        assert_eq!(revisions.len(), 1);
+        assert_eq!(revisions[0].entries.len(), 6);
+        assert!(!revisions[0].trailer.contains_key("Prev"));
+        assert_eq!(crate::extract_text(pdf).unwrap(), ["Hello xref"]);
+    }
+
+    // `with_table("")` and an update giving the content stream a new generation
+    fn with_update() -> Vec<u8> {
+        let mut pdf = with_table("");
+        let prev = pdf.windows(5).position(|w| w == b"xref\n").unwrap();
+        let content = "BT /F1 12 Tf 72 720 Td (Hello update) Tj ET";
+        let page = object(
+            &mut pdf,
+            3,
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 612 792] \
+             /Resources << /Font << /F1 4 0 R >> >> /Contents 5 1 R >>",
+        );
+        let stream = pdf.len();
+        pdf.extend(
+            format!(
+                "5 1 obj\n<< /Length {} >>\nstream\n{}\nendstream\nendobj\n",
+                content.len(),
+                content
+            )
+            .bytes(),
+        );
+        let xref = pdf.len();
+        pdf.extend(
+            format!(
+                "xref\n3 1\n{:010} 00000 n \n5 1\n{:010} 00001 n \n\
+                 trailer\n<< /Size 6 /Root 1 0 R /Prev {} >>\nstartxref\n{}\n%%EOF\n",
+                page, stream, prev, xref
+            )
+            .bytes(),
+        );
+        pdf
+    }
+
+    #[test]
+    fn incremental_updates_select_revisions() {
+        use crate::types::ExtractOptions;
+
+        let at = |revision| ExtractOptions {
+            revision: Some(revision),
+            ..ExtractOptions::default()
+        };
+        let mut pdf = with_update();
+        assert_eq!(crate::revision_count(&pdf).unwrap(), 2);
+        assert!(matches!(
+            read_revisions(&pdf).unwrap()[1].entries[&5],
+            XrefEntry::InUse { generation: 1, .. }
+        ));
+        assert_eq!(crate::extract_text(pdf.clone()).unwrap(), ["Hello update"]);
+        assert_eq!(
+            crate::extract_text_with_options(pdf.clone(), &at(0)).unwrap(),
+            ["Hello xref"]
+        );
+        assert_eq!(
+            crate::extract_text_with_options(pdf.clone(), &at(1)).unwrap(),
+            ["Hello update"]
+        );
+        assert!(crate::extract_text_with_options(pdf.clone(), &at(2)).is_err());
+
+        // The same revisions when the final startxref is damaged and the file is scanned
+        let at_startxref = pdf.windows(9).rposition(|w| w == b"startxref").unwrap();
+        pdf[at_startxref + 10] = b'x';
+        assert!(read_revisions(&pdf).is_none());
+        assert_eq!(crate::revision_count(&pdf).unwrap(), 2);
+        assert_eq!(crate::extract_text(pdf.clone()).unwrap(), ["Hello update"]);
+        assert_eq!(
+            crate::extract_text_with_options(pdf.clone(), &at(0)).unwrap(),
+            ["Hello xref"]
+        );
+        assert!(crate::extract_text_with_options(pdf, &at(2)).is_err());
+    }
+
+    #[test]
+    fn text_at_each_revision() {
+        let pdf = with_update();
+        let revisions: Vec<_> = (0..crate::revision_count(&pdf).unwrap())
+            .map(|revision| crate::extract_text_at_revision(&pdf, revision).unwrap())
+            .collect();
+        assert_eq!(revisions, [["Hello xref"], ["Hello update"]]);
+        assert_eq!(
+            revisions.last().unwrap(),
+            &crate::extract_text(pdf.clone()).unwrap()
+        );
+        assert!(crate::extract_text_at_revision(&pdf, 2).is_err());
+    }
+}
@@ -0,0 +1,178 @@
+//! Property tests over generated documents (see `synthetic_support`): well-formed PDFs must
+//! extract to exactly the text they were written with, and broken ones must fail cleanly.
+
+mod synthetic_support;
+
+use extractor::types::{ExtractOptions, Normalization};
+use extractor::{
+    document_info, extract_form_fields, extract_page_text, extract_pages, extract_text,
+    extract_text_page, extract_text_positions, extract_text_with_options, PdfDocument,
+};
+use proptest::prelude::*;
+use proptest::sample::Index;
+use synthetic_support::{defective_doc, encrypted_doc, valid_doc, Defect, TITLE};
+
+fn lenient() -> ExtractOptions {
+    ExtractOptions {
+        lenient: true,
+        ..ExtractOptions::default()
+    }
+}
+
+/// Runs every entry point over `pdf`. Only panics matter; errors are expected.
+fn exercise(pdf: &[u8]) {
+    let _ = extract_text(pdf.to_vec());
+    let _ = extract_text_with_options(pdf.to_vec(), &lenient());
+    let _ = extract_text_with_options(
+        pdf.to_vec(),
+        &ExtractOptions {
+            normalization: Normalization::Raw,
+            ..ExtractOptions::default()
+        },
+    );
+    let _ = extract_page_text(pdf, 0, &lenient());
+    let _ = extract_text_with_options(
+        pdf.to_vec(),
+        &ExtractOptions {
+            annotations: true,
+            ..lenient()
+        },
+    );
+    if let Ok(document) = PdfDocument::parse(pdf, &lenient()) {
+        for page in 0..document.page_count() {
+            let _ = document.page_text(page);
+        }
+    }
+    let _ = extract_text_positions(pdf);
+    let _ = extract_pages(pdf, &lenient());
+    let _ = document_info(pdf);
+    let _ = extract_form_fields(pdf);
+}
+
+proptest! {
+    #[test]
+    fn valid_documents_round_trip(doc in valid_doc()) {
+        let pdf = doc.render();
+        let expected = doc.expected_text();
+
+        prop_assert_eq!(&extract_text(pdf.clone()).unwrap(), &expected);
+        prop_assert_eq!(
+            &extract_text_with_options(pdf.clone(), &lenient()).unwrap(),
+            &expected
+        );
+        // Lines are drawn top to bottom, so rebuilding them by position changes nothing
+        prop_assert_eq!(
+            &extract_text_with_options(
+                pdf.clone(),
+                &ExtractOptions {
+                    normalization: Normalization::Layout,
+                    ..ExtractOptions::default()
+                },
+            )
+            .unwrap(),
+            &expected
+        );
+        // The stamp is only read with `annotations`, in stream order or by position alike
+        for normalization in [Normalization::Canonical, Normalization::Layout] {
+            let options = ExtractOptions {
+                normalization,
+                annotations: true,
+                ..ExtractOptions::default()
+            };
+            prop_assert_eq!(
+                &extract_text_with_options(pdf.clone(), &options).unwrap(),
+                &doc.expected_text_with_annotations()
+            );
+        }
+        // Lines are drawn top to bottom, so words by position are the words of the text
+        let pages = extract_pages(&pdf, &ExtractOptions::default()).unwrap();
+        prop_assert_eq!(pages.len(), expected.len());
+        for (page, text) in pages.iter().zip(&expected) {
+            prop_assert_eq!(&page.text, text);
+            let words: Vec<&str> = page.words.iter().map(|word| word.text.as_str()).collect();
+            prop_assert_eq!(words, text.split_whitespace().collect::<Vec<_>>());
+            prop_assert!(page.words.iter().all(|word| word.width > 0.0 && word.height > 0.0));
+            prop_assert_eq!(page.media_box, [0.0, 0.0, 612.0, 792.0]);
+            prop_assert_eq!(page.rotation, if doc.inherit_attributes { 90 } else { 0 });
+            prop_assert!(!page.fonts.is_empty());
+        }
+        for (page, text) in expected.iter().enumerate() {
+            let page_text = extract_page_text(&pdf, page, &ExtractOptions::default()).unwrap();
+            prop_assert_eq!(&page_text.text, text);
+            prop_assert_eq!(page_text.page_count, expected.len());
+            prop_assert_eq!(&extract_text_page(&pdf, page).unwrap(), text);
+        }
+    }
+
+    #[test]
+    fn defective_documents_fail_cleanly(doc in defective_doc()) {
+        let pdf = doc.render();
+        exercise(&pdf);
+
+        let expected = doc.expected_text();
+        match doc.defect {
+            // The broken first page is dropped in lenient mode; the rest is unaffected.
+            Some(Defect::UnsupportedFilter | Defect::CorruptFlate) => {
+                prop_assert!(extract_text(pdf.clone()).is_err());
+                let pages = extract_text_with_options(pdf, &lenient()).unwrap();
+                prop_assert_eq!(&pages[0], "");
+                prop_assert_eq!(&pages[1..], &expected[1..]);
+            }
+            // Text in an unknown font is skipped, not misdecoded.
+            Some(Defect::MissingFont) => {
+                let pages = extract_text(pdf).unwrap();
+                prop_assert_eq!(&pages[0], "");
+                prop_assert_eq!(&pages[1..], &expected[1..]);
+            }
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn damaged_bytes_never_panic(
+        doc in valid_doc(),
+        cut in any::<Index>(),
+        at in any::<Index>(),
+        garbage in prop::collection::vec(any::<u8>(), 1..16),
+    ) {
+        let pdf = doc.render();
+
+        exercise(&pdf[..cut.index(pdf.len())]);
+
+        let mut overwritten = pdf.clone();
+        let start = at.index(pdf.len());
+        let end = (start + garbage.len()).min(pdf.len());
+        overwritten[start..end].copy_from_slice(&garbage[..end - start]);
+        exercise(&overwritten);
+    }
+}
+
+proptest! {
+    // Key derivation for AES-256 takes a noticeable fraction of a second without optimizations
+    #![proptest_config(ProptestConfig::with_cases(32))]
+
+    #[test]
+    fn encrypted_documents_round_trip(doc in encrypted_doc()) {
+        let pdf = doc.render();
+
+        prop_assert_eq!(extract_text(pdf.clone()).unwrap(), doc.expected_text());
+        // Objects loaded one at a time for a single page are decrypted as well
+        for (page, text) in doc.expected_text().iter().enumerate() {
+            prop_assert_eq!(&extract_text_page(&pdf, page).unwrap(), text);
+        }
+        // Strings outside content streams are decrypted too
+        let info = document_info(&pdf).unwrap();
+        prop_assert_eq!(info.metadata.get("Title").map(String::as_str), Some(TITLE));
+        prop_assert_eq!(
+            extract_text_with_options(
+                pdf,
+                &ExtractOptions {
+                    annotations: true,
+                    ..ExtractOptions::default()
+                },
+            )
+            .unwrap(),
+            doc.expected_text_with_annotations()
+        );
+    }
+}
@@ -0,0 +1,1090 @@
+//! Generator for synthetic PDFs. A `Doc` describes pages of text plus how they are written out:
+//! font encoding, string syntax, stream filter, content stream splitting and page tree shape.
+//! `render` produces the bytes and `expected_text` what canonical extraction must return.
+//! `Defect` breaks the structure the way real-world files are broken; byte-level damage is left
+//! to the tests.
+
+use aes::cipher::{block_padding::NoPadding, BlockEncryptMut, KeyIvInit};
+use md5::{Digest, Md5};
+use miniz_oxide::deflate::compress_to_vec_zlib;
+use proptest::prelude::*;
+use sha2::{Sha256, Sha384, Sha512};
+
+/// `/Title` of every document's `/Info` dictionary.
+pub const TITLE: &str = "Synthetic document";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FontEncoding {
+    /// No `/Encoding` entry.
+    Default,
+    WinAnsi,
+    Standard,
+    MacRoman,
+    /// WinAnsi with `/Differences` moving the lowercase letters to codes 192-217, the digits
+    /// to 218-227 by their glyph list names and the uppercase letters to 160-185 as `uniXXXX`.
+    Differences,
+    /// Type0 font, Identity-H, two-byte codes mapped back by a ToUnicode CMap.
+    Type0,
+    /// Type0 font without ToUnicode, `/UniJIS-UCS2-H`: the codes are UTF-16BE text.
+    Type0Ucs2,
+    /// Type0 font without ToUnicode, Identity-H: CIDs go through a `/CIDToGIDMap` to glyphs of
+    /// an embedded TrueType program whose `cmap` table gives their Unicode values.
+    Type0Embedded,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StringSyntax {
+    Literal,
+    Hex,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Filter {
+    None,
+    Flate,
+    /// `/Filter [/FlateDecode]`
+    FlateArray,
+    /// `/Filter [/ASCII85Decode /FlateDecode]`
+    Ascii85Flate,
+    /// `/Filter /ASCIIHexDecode`
+    AsciiHex,
+    /// `/Filter /FlateDecode` over rows PNG-filtered with `/Predictor 12 /Columns 16`
+    FlatePng,
+}
+
+/// The operators used to start a line of text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineOp {
+    /// `0 -14 Td (...) Tj`
+    Td,
+    /// `(...) '`
+    Quote,
+    /// `T* (...) Tj`
+    NextLine,
+    /// `0 -14 Td [(..) -50 (..)] TJ`, split mid-line with a kerning adjustment too small to
+    /// read as a space
+    Kerned,
+    /// `0 -14 Td [(..) -150 (..)] TJ`, one string per word with the spaces left out: the
+    /// adjustment is the only thing separating the words
+    Spaced,
+    /// `0 -14 Td (..) Tj 27 0 Td (..) Tj`, one string per word placed by `Td` a little past the
+    /// end of the previous one
+    Positioned,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PageTree {
+    /// Every page is a kid of the root.
+    Flat,
+    /// Pages are grouped under intermediate `/Pages` nodes of this many kids.
+    Nested(usize),
+}
+
+/// Standard security handler settings. The user password is always empty, the owner password
+/// is `owner`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encryption {
+    /// Revision 2, RC4 with a 40-bit key.
+    Rc4_40,
+    /// Revision 3, RC4 with a 128-bit key.
+    Rc4_128,
+    /// Revision 4, the `AESV2` crypt filter.
+    Aes128,
+    /// Revision 6, the `AESV3` crypt filter.
+    Aes256,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Defect {
+    /// The first page's content is marked `/LZWDecode`, which the extractor rejects.
+    UnsupportedFilter,
+    /// The first page's content is marked `/FlateDecode` but is not zlib data.
+    CorruptFlate,
+    /// The first page selects a font that is not in its resources.
+    MissingFont,
+    /// The first page's `/Contents` points at an object that does not exist.
+    DanglingContents,
+    /// The last intermediate node lists the root as a kid.
+    CyclicPageTree,
+}
+
+#[derive(Debug, Clone)]
+pub struct Line {
+    pub text: String,
+    pub op: LineOp,
+}
+
+#[derive(Debug, Clone)]
+pub struct Doc {
+    pub pages: Vec<Vec<Line>>,
+    pub encoding: FontEncoding,
+    pub strings: StringSyntax,
+    pub filter: Filter,
+    /// Split each page's content into two streams at a line boundary.
+    pub split_contents: bool,
+    pub tree: PageTree,
+    /// Put the font resources and the media box on the root `/Pages` node instead of every
+    /// page, along with a `/Rotate 90` that only changes how pages are displayed.
+    pub inherit_attributes: bool,
+    /// Text of a FreeText annotation on the first page, drawn only by its appearance stream.
+    pub stamp: Option<String>,
+    pub encryption: Option<Encryption>,
+    pub defect: Option<Defect>,
+}
+
+fn word() -> impl Strategy<Value = String> {
+    "[A-Za-z0-9().\\\\-]{1,8}"
+}
+
+fn line() -> impl Strategy<Value = Line> {
+    let op = prop_oneof![
+        Just(LineOp::Td),
+        Just(LineOp::Quote),
+        Just(LineOp::NextLine),
+        Just(LineOp::Kerned),
+        Just(LineOp::Spaced),
+        Just(LineOp::Positioned),
+    ];
+    (prop::collection::vec(word(), 1..5), op).prop_map(|(words, op)| Line {
+        text: words.join(" "),
+        op,
+    })
+}
+
+fn encoding() -> impl Strategy<Value = FontEncoding> {
+    prop_oneof![
+        Just(FontEncoding::Default),
+        Just(FontEncoding::WinAnsi),
+        Just(FontEncoding::Standard),
+        Just(FontEncoding::MacRoman),
+        Just(FontEncoding::Differences),
+        Just(FontEncoding::Type0),
+        Just(FontEncoding::Type0Ucs2),
+        Just(FontEncoding::Type0Embedded),
+    ]
+}
+
+fn base_doc() -> impl Strategy<Value = Doc> {
+    (
+        prop::collection::vec(prop::collection::vec(line(), 1..5), 1..6),
+        encoding(),
+        prop_oneof![Just(StringSyntax::Literal), Just(StringSyntax::Hex)],
+        prop_oneof![
+            Just(Filter::None),
+            Just(Filter::Flate),
+            Just(Filter::FlateArray),
+            Just(Filter::Ascii85Flate),
+            Just(Filter::AsciiHex),
+            Just(Filter::FlatePng)
+        ],
+        any::<bool>(),
+        prop_oneof![Just(PageTree::Flat), (1..4usize).prop_map(PageTree::Nested)],
+        any::<bool>(),
+        prop::option::of(prop::collection::vec(word(), 1..4).prop_map(|words| words.join(" "))),
+    )
+        .prop_map(
+            |(
+                pages,
+                encoding,
+                strings,
+                filter,
+                split_contents,
+                tree,
+                inherit_attributes,
+                stamp,
+            )| {
+                Doc {
+                    pages,
+                    encoding,
+                    strings,
+                    filter,
+                    split_contents,
+                    tree,
+                    inherit_attributes,
+                    stamp,
+                    encryption: None,
+                    defect: None,
+                }
+            },
+        )
+}
+
+/// Well-formed documents.
+pub fn valid_doc() -> impl Strategy<Value = Doc> {
+    base_doc()
+}
+
+/// Well-formed documents encrypted with an empty user password.
+pub fn encrypted_doc() -> impl Strategy<Value = Doc> {
+    let encryption = prop_oneof![
+        Just(Encryption::Rc4_40),
+        Just(Encryption::Rc4_128),
+        Just(Encryption::Aes128),
+        Just(Encryption::Aes256),
+    ];
+    (base_doc(), encryption).prop_map(|(doc, encryption)| Doc {
+        encryption: Some(encryption),
+        ..doc
+    })
+}
+
+/// Documents with exactly one structural defect.
+pub fn defective_doc() -> impl Strategy<Value = Doc> {
+    let defect = prop_oneof![
+        Just(Defect::UnsupportedFilter),
+        Just(Defect::CorruptFlate),
+        Just(Defect::MissingFont),
+        Just(Defect::DanglingContents),
+        Just(Defect::CyclicPageTree),
+    ];
+    (base_doc(), defect).prop_map(|(doc, defect)| Doc {
+        defect: Some(defect),
+        ..doc
+    })
+}
+
+impl Doc {
+    /// Canonical text of each page.
+    pub fn expected_text(&self) -> Vec<String> {
+        self.pages
+            .iter()
+            .map(|lines| {
+                lines
+                    .iter()
+                    .map(|line| line.text.as_str())
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })
+            .collect()
+    }
+
+    /// Text of every page when annotation appearances are extracted too: the stamp follows
+    /// the first page's text.
+    pub fn expected_text_with_annotations(&self) -> Vec<String> {
+        let mut pages = self.expected_text();
+        if let Some(stamp) = &self.stamp {
+            pages[0] = format!("{}\n{}", pages[0], stamp);
+        }
+        pages
+    }
+
+    pub fn render(&self) -> Vec<u8> {
+        let mut objects = Objects::default();
+        let catalog = objects.reserve();
+        let root = objects.reserve();
+        objects.set(
+            catalog,
+            format!("<< /Type /Catalog /Pages {} 0 R >>", root).into_bytes(),
+        );
+
+        let font = self.font_objects(&mut objects);
+        let attributes = format!(
+            "/MediaBox [0 0 612 792] /Resources << /Font << /F1 {} 0 R >> >>",
+            font
+        );
+
+        // Page parents: the root, or one intermediate node per group of pages.
+        let groups: Vec<Vec<usize>> = match self.tree {
+            PageTree::Flat => vec![(0..self.pages.len()).collect()],
+            PageTree::Nested(size) => (0..self.pages.len())
+                .collect::<Vec<_>>()
+                .chunks(size)
+                .map(<[usize]>::to_vec)
+                .collect(),
+        };
+        let parents: Vec<usize> = match self.tree {
+            PageTree::Flat => vec![root],
+            PageTree::Nested(_) => groups.iter().map(|_| objects.reserve()).collect(),
+        };
+
+        let mut page_ids = vec![0; self.pages.len()];
+        for (group, &parent) in groups.iter().zip(&parents) {
+            for &index in group {
+                page_ids[index] = self.page_objects(
+                    &mut objects,
+                    index,
+                    parent,
+                    font,
+                    if self.inherit_attributes {
+                        ""
+                    } else {
+                        &attributes
+                    },
+                );
+            }
+        }
+
+        if let PageTree::Nested(_) = self.tree {
+            let last = parents.len() - 1;
+            for (i, (group, &parent)) in groups.iter().zip(&parents).enumerate() {
+                let mut kids = refs(group.iter().map(|&index| page_ids[index]));
+                if i == last && self.defect == Some(Defect::CyclicPageTree) {
+                    kids.push_str(&format!(" {} 0 R", root));
+                }
+                objects.set(
+                    parent,
+                    format!(
+                        "<< /Type /Pages /Parent {} 0 R /Kids [{}] /Count {} >>",
+                        root,
+                        kids,
+                        group.len()
+                    )
+                    .into_bytes(),
+                );
+            }
+        }
+
+        let mut root_kids = match self.tree {
+            PageTree::Flat => refs(page_ids.iter().copied()),
+            PageTree::Nested(_) => refs(parents.iter().copied()),
+        };
+        if self.tree == PageTree::Flat && self.defect == Some(Defect::CyclicPageTree) {
+            root_kids.push_str(&format!(" {} 0 R", root));
+        }
+        objects.set(
+            root,
+            format!(
+                "<< /Type /Pages /Kids [{}] /Count {}{} >>",
+                root_kids,
+                self.pages.len(),
+                if self.inherit_attributes {
+                    format!(" {} /Rotate 90", attributes)
+                } else {
+                    String::new()
+                }
+            )
+            .into_bytes(),
+        );
+
+        let info = objects.add(format!("<< /Title ({}) >>", TITLE).into_bytes());
+        objects.build(catalog, info, self.encryption)
+    }
+
+    /// Writes the font (plus descendant and CMap for Type0) and returns the font object number.
+    fn font_objects(&self, objects: &mut Objects) -> usize {
+        // Every glyph is 500 units wide but the space, which is 250
+        let widths = format!("250{}", " 500".repeat(223));
+        let simple = |encoding: &str| {
+            format!(
+                "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica{} \
+                 /FirstChar 32 /LastChar 255 /Widths [{}] >>",
+                encoding, widths
+            )
+            .into_bytes()
+        };
+        match self.encoding {
+            FontEncoding::Default => objects.add(simple("")),
+            FontEncoding::WinAnsi => objects.add(simple(" /Encoding /WinAnsiEncoding")),
+            FontEncoding::Standard => objects.add(simple(" /Encoding /StandardEncoding")),
+            FontEncoding::MacRoman => objects.add(simple(" /Encoding /MacRomanEncoding")),
+            FontEncoding::Differences => {
+                let lower: Vec<String> = ('a'..='z').map(|c| format!("/{}", c)).collect();
+                let digits = "/zero /one /two /three /four /five /six /seven /eight /nine";
+                let upper: Vec<String> = ('A'..='Z')
+                    .map(|c| format!("/uni{:04X}", c as u32))
+                    .collect();
+                objects.add(simple(&format!(
+                    " /Encoding << /Type /Encoding /BaseEncoding /WinAnsiEncoding \
+                     /Differences [192 {} {} 160 {}] >>",
+                    lower.join(" "),
+                    digits,
+                    upper.join(" ")
+                )))
+            }
+            FontEncoding::Type0 => {
+                let font = objects.reserve();
+                let descendant = objects.add(
+                    b"<< /Type /Font /Subtype /CIDFontType2 /BaseFont /Synthetic \
+                      /CIDSystemInfo << /Registry (Adobe) /Ordering (Identity) /Supplement 0 >> >>"
+                        .to_vec(),
+                );
+                let cmap = "/CIDInit /ProcSet findresource begin\n12 dict begin\nbegincmap\n\
+                     /CMapName /Adobe-Identity-UCS def\n/CMapType 2 def\n\
+                     1 begincodespacerange\n<0000> <FFFF>\nendcodespacerange\n\
+                     1 beginbfrange\n<1020> <107E> <0020>\nendbfrange\n\
+                     endcmap\nCMapName currentdict /CMap defineresource pop\nend\nend\n";
+                let to_unicode = objects.add(stream(cmap.as_bytes(), self.filter, ""));
+                objects.set(
+                    font,
+                    format!(
+                        "<< /Type /Font /Subtype /Type0 /BaseFont /Synthetic /Encoding /Identity-H \
+                         /DescendantFonts [{} 0 R] /ToUnicode {} 0 R >>",
+                        descendant, to_unicode
+                    )
+                    .into_bytes(),
+                );
+                font
+            }
+            FontEncoding::Type0Ucs2 => {
+                let descendant = objects.add(
+                    b"<< /Type /Font /Subtype /CIDFontType0 /BaseFont /Synthetic \
+                      /CIDSystemInfo << /Registry (Adobe) /Ordering (Japan1) /Supplement 6 >> >>"
+                        .to_vec(),
+                );
+                objects.add(
+                    format!(
+                        "<< /Type /Font /Subtype /Type0 /BaseFont /Synthetic \
+                         /Encoding /UniJIS-UCS2-H /DescendantFonts [{} 0 R] >>",
+                        descendant
+                    )
+                    .into_bytes(),
+                )
+            }
+            FontEncoding::Type0Embedded => {
+                // CID c is glyph c - 0x1F, and the font program maps U+0020..U+007E to glyphs
+                // 1..95, so CIDs equal the ASCII codes without the map being the identity.
+                let cid_to_gid: Vec<u8> = (0..0x7Fu16)
+                    .flat_map(|cid| cid.saturating_sub(0x1F).to_be_bytes())
+                    .collect();
+                let cid_to_gid = objects.add(stream(&cid_to_gid, self.filter, ""));
+                let program = objects.add(stream(&truetype_program(), self.filter, ""));
+                let descriptor = objects.add(
+                    format!(
+                        "<< /Type /FontDescriptor /FontName /Synthetic /Flags 32 \
+                         /FontFile2 {} 0 R >>",
+                        program
+                    )
+                    .into_bytes(),
+                );
+                let descendant = objects.add(
+                    format!(
+                        "<< /Type /Font /Subtype /CIDFontType2 /BaseFont /Synthetic \
+                         /CIDSystemInfo << /Registry (Adobe) /Ordering (Identity) /Supplement 0 >> \
+                         /FontDescriptor {} 0 R /CIDToGIDMap {} 0 R >>",
+                        descriptor, cid_to_gid
+                    )
+                    .into_bytes(),
+                );
+                objects.add(
+                    format!(
+                        "<< /Type /Font /Subtype /Type0 /BaseFont /Synthetic /Encoding /Identity-H \
+                         /DescendantFonts [{} 0 R] >>",
+                        descendant
+                    )
+                    .into_bytes(),
+                )
+            }
+        }
+    }
+
+    /// Writes a page and its content streams and returns the page object number.
+    fn page_objects(
+        &self,
+        objects: &mut Objects,
+        index: usize,
+        parent: usize,
+        font: usize,
+        attributes: &str,
+    ) -> usize {
+        let defect = if index == 0 { self.defect } else { None };
+        let content = self.content(&self.pages[index], defect);
+
+        let parts: Vec<&[u8]> = if self.split_contents {
+            // Split after the first line of text so both streams are non-empty.
+            let at = content
+                .iter()
+                .position(|&b| b == b'\n')
+                .map_or(content.len(), |p| p + 1);
+            vec![&content[..at], &content[at..]]
+        } else {
+            vec![&content[..]]
+        };
+
+        let page = objects.reserve();
+        let streams: Vec<usize> = parts
+            .into_iter()
+            .map(|part| {
+                let body = match defect {
+                    Some(Defect::UnsupportedFilter) => raw_stream(part, " /Filter /LZWDecode"),
+                    Some(Defect::CorruptFlate) => raw_stream(part, " /Filter /FlateDecode"),
+                    _ => stream(part, self.filter, ""),
+                };
+                objects.add(body)
+            })
+            .collect();
+
+        let contents = match defect {
+            Some(Defect::DanglingContents) => "9999 0 R".to_string(),
+            _ if streams.len() == 1 => format!("{} 0 R", streams[0]),
+            _ => format!("[{}]", refs(streams.iter().copied())),
+        };
+        // A 228x20 form fitted to a rectangle of the same size near the bottom of the page
+        let annots = match &self.stamp {
+            Some(stamp) if index == 0 => {
+                let mut appearance = b"BT /F1 12 Tf 2 5 Td ".to_vec();
+                appearance.extend(self.string(stamp));
+                appearance.extend_from_slice(b" Tj ET");
+                let appearance = objects.add(stream(
+                    &appearance,
+                    self.filter,
+                    &format!(
+                        " /Type /XObject /Subtype /Form /BBox [0 0 228 20] \
+                         /Resources << /Font << /F1 {} 0 R >> >>",
+                        font
+                    ),
+                ));
+                let annotation = objects.add(
+                    format!(
+                        "<< /Type /Annot /Subtype /FreeText /Rect [72 100 300 120] \
+                         /AP << /N {} 0 R >> >>",
+                        appearance
+                    )
+                    .into_bytes(),
+                );
+                format!(" /Annots [{} 0 R]", annotation)
+            }
+            _ => String::new(),
+        };
+        objects.set(
+            page,
+            format!(
+                "<< /Type /Page /Parent {} 0 R {} /Contents {}{} >>",
+                parent, attributes, contents, annots
+            )
+            .into_bytes(),
+        );
+        page
+    }
+
+    fn content(&self, lines: &[Line], defect: Option<Defect>) -> Vec<u8> {
+        let font = if defect == Some(Defect::MissingFont) {
+            "/F9"
+        } else {
+            "/F1"
+        };
+        let mut content = format!("BT {} 12 Tf 14 TL 72 720 Td\n", font).into_bytes();
+        for (i, line) in lines.iter().enumerate() {
+            let move_down: &[u8] = if i == 0 { b"" } else { b"0 -14 Td " };
+            match line.op {
+                LineOp::Td => {
+                    content.extend_from_slice(move_down);
+                    content.extend(self.string(&line.text));
+                    content.extend_from_slice(b" Tj");
+                }
+                LineOp::Quote => {
+                    content.extend(self.string(&line.text));
+                    content.extend_from_slice(b" '");
+                }
+                LineOp::NextLine => {
+                    content.extend_from_slice(b"T* ");
+                    content.extend(self.string(&line.text));
+                    content.extend_from_slice(b" Tj");
+                }
+                LineOp::Kerned => {
+                    let (head, tail) = line.text.split_at(line.text.len() / 2);
+                    content.extend_from_slice(move_down);
+                    content.push(b'[');
+                    content.extend(self.string(head));
+                    content.extend_from_slice(b" -50 ");
+                    content.extend(self.string(tail));
+                    content.extend_from_slice(b"] TJ");
+                }
+                LineOp::Spaced => {
+                    content.extend_from_slice(move_down);
+                    content.push(b'[');
+                    for (j, word) in line.text.split(' ').enumerate() {
+                        if j > 0 {
+                            content.extend_from_slice(b" -150 ");
+                        }
+                        content.extend(self.string(word));
+                    }
+                    content.extend_from_slice(b"] TJ");
+                }
+                LineOp::Positioned => {
+                    content.extend_from_slice(move_down);
+                    // Simple fonts' glyphs are half an em wide, Type0 ones the default em
+                    let glyph = match self.encoding {
+                        FontEncoding::Type0
+                        | FontEncoding::Type0Ucs2
+                        | FontEncoding::Type0Embedded => 12,
+                        _ => 6,
+                    };
+                    for (j, word) in line.text.split(' ').enumerate() {
+                        if j > 0 {
+                            let advance = glyph * line.text.split(' ').nth(j - 1).unwrap().len();
+                            content.extend(format!(" {} 0 Td ", advance + 3).into_bytes());
+                        }
+                        content.extend(self.string(word));
+                        content.extend_from_slice(b" Tj");
+                    }
+                }
+            }
+            content.push(b'\n');
+        }
+        content.extend_from_slice(b"ET\n");
+        content
+    }
+
+    /// `text` encoded for the font and written in the chosen string syntax.
+    fn string(&self, text: &str) -> Vec<u8> {
+        let codes: Vec<u8> = match self.encoding {
+            FontEncoding::Type0 => text.bytes().flat_map(|b| [0x10, b]).collect(),
+            FontEncoding::Type0Ucs2 | FontEncoding::Type0Embedded => {
+                text.bytes().flat_map(|b| [0, b]).collect()
+            }
+            FontEncoding::Differences => text
+                .bytes()
+                .map(|b| match b {
+                    b'a'..=b'z' => 192 + (b - b'a'),
+                    b'0'..=b'9' => 218 + (b - b'0'),
+                    b'A'..=b'Z' => 160 + (b - b'A'),
+                    _ => b,
+                })
+                .collect(),
+            _ => text.bytes().collect(),
+        };
+        match self.strings {
+            StringSyntax::Hex => {
+                let hex: String = codes.iter().map(|b| format!("{:02X}", b)).collect();
+                format!("<{}>", hex).into_bytes()
+            }
+            StringSyntax::Literal => {
+                let mut out = vec![b'('];
+                for b in codes {
+                    if matches!(b, b'(' | b')' | b'\\') {
+                        out.push(b'\\');
+                    }
+                    out.push(b);
+                }
+                out.push(b')');
+                out
+            }
+        }
+    }
+}
+
+fn refs(ids: impl Iterator<Item = usize>) -> String {
+    ids.map(|id| format!("{} 0 R", id))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn stream(data: &[u8], filter: Filter, extra: &str) -> Vec<u8> {
+    match filter {
+        Filter::None => raw_stream(data, extra),
+        Filter::Flate => raw_stream(
+            &compress_to_vec_zlib(data, 6),
+            &format!(" /Filter /FlateDecode{}", extra),
+        ),
+        Filter::FlateArray => raw_stream(
+            &compress_to_vec_zlib(data, 6),
+            &format!(" /Filter [/FlateDecode]{}", extra),
+        ),
+        Filter::Ascii85Flate => raw_stream(
+            &ascii85(&compress_to_vec_zlib(data, 6)),
+            &format!(" /Filter [/ASCII85Decode /FlateDecode]{}", extra),
+        ),
+        Filter::AsciiHex => raw_stream(
+            format!(
+                "{}>",
+                data.iter()
+                    .map(|b| format!("{:02X}", b))
+                    .collect::<String>()
+            )
+            .as_bytes(),
+            &format!(" /Filter /ASCIIHexDecode{}", extra),
+        ),
+        Filter::FlatePng => raw_stream(
+            &compress_to_vec_zlib(&png_up(data, 16), 6),
+            &format!(
+                " /Filter /FlateDecode /DecodeParms << /Predictor 12 /Columns 16 >>{}",
+                extra
+            ),
+        ),
+    }
+}
+
+/// Rows of `columns` bytes, each prefixed by the PNG Up filter type and stored as the
+/// difference from the row above. The last row is zero-padded, as the predictor requires.
+fn png_up(data: &[u8], columns: usize) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut prev = vec![0u8; columns];
+    for chunk in data.chunks(columns) {
+        let mut row = chunk.to_vec();
+        row.resize(columns, 0);
+        out.push(2);
+        out.extend(row.iter().zip(&prev).map(|(b, p)| b.wrapping_sub(*p)));
+        prev = row;
+    }
+    out
+}
+
+/// ASCII85 with `z` for zero groups, a line break every 16 groups, ending in `~>`.
+fn ascii85(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for (i, chunk) in data.chunks(4).enumerate() {
+        let mut group = [0u8; 4];
+        group[..chunk.len()].copy_from_slice(chunk);
+        let mut value = u32::from_be_bytes(group);
+        if value == 0 && chunk.len() == 4 {
+            out.push(b'z');
+        } else {
+            let mut digits = [0u8; 5];
+            for digit in digits.iter_mut().rev() {
+                *digit = b'!' + (value % 85) as u8;
+                value /= 85;
+            }
+            out.extend_from_slice(&digits[..chunk.len() + 1]);
+        }
+        if i % 16 == 15 {
+            out.push(b'\n');
+        }
+    }
+    out.extend_from_slice(b"~>");
+    out
+}
+
+/// A TrueType program reduced to a `cmap` table: one format 4 subtable (Windows Unicode BMP)
+/// mapping U+0020..U+007E to glyphs 1..95.
+fn truetype_program() -> Vec<u8> {
+    let words =
+        |values: &[u16]| -> Vec<u8> { values.iter().flat_map(|v| v.to_be_bytes()).collect() };
+    let mut subtable = words(&[4, 32, 0, 4, 4, 1, 0]);
+    subtable.extend(words(&[0x7E, 0xFFFF, 0, 0x20, 0xFFFF]));
+    subtable.extend(words(&[1u16.wrapping_sub(0x20), 1, 0, 0]));
+    let mut cmap = words(&[0, 1, 3, 1, 0, 12]);
+    cmap.extend(subtable);
+
+    let mut font = words(&[1, 0, 1, 16, 0, 0]);
+    font.extend_from_slice(b"cmap");
+    font.extend(words(&[0, 0, 0, 28, 0, cmap.len() as u16]));
+    font.extend(cmap);
+    font
+}
+
+fn raw_stream(data: &[u8], extra: &str) -> Vec<u8> {
+    let mut body = format!("<< /Length {}{} >>\nstream\n", data.len(), extra).into_bytes();
+    body.extend_from_slice(data);
+    body.extend_from_slice(b"\nendstream");
+    body
+}
+
+/// Object bodies numbered from 1, written with a classic cross-reference table.
+#[derive(Default)]
+struct Objects {
+    bodies: Vec<Vec<u8>>,
+}
+
+impl Objects {
+    fn reserve(&mut self) -> usize {
+        self.bodies.push(Vec::new());
+        self.bodies.len()
+    }
+
+    fn set(&mut self, id: usize, body: Vec<u8>) {
+        self.bodies[id - 1] = body;
+    }
+
+    fn add(&mut self, body: Vec<u8>) -> usize {
+        let id = self.reserve();
+        self.set(id, body);
+        id
+    }
+
+    fn build(mut self, catalog: usize, info: usize, encryption: Option<Encryption>) -> Vec<u8> {
+        // Every object is encrypted but the `/Encrypt` dictionary, which is added last
+        let security = encryption.map(Security::new);
+        let mut trailer = String::new();
+        if let Some(security) = &security {
+            for (index, body) in self.bodies.iter_mut().enumerate() {
+                *body = security.encrypt_body(index + 1, body);
+            }
+            let dictionary = self.add(security.dictionary.clone().into_bytes());
+            trailer = format!(
+                " /Encrypt {} 0 R /ID [<{}> <{}>]",
+                dictionary,
+                hex(&DOCUMENT_ID),
+                hex(&DOCUMENT_ID)
+            );
+        }
+
+        let mut pdf = b"%PDF-1.7\n%\xE2\xE3\xCF\xD3\n".to_vec();
+        let mut offsets = Vec::with_capacity(self.bodies.len());
+        for (index, body) in self.bodies.iter().enumerate() {
+            offsets.push(pdf.len());
+            pdf.extend_from_slice(format!("{} 0 obj\n", index + 1).as_bytes());
+            pdf.extend_from_slice(body);
+            pdf.extend_from_slice(b"\nendobj\n");
+        }
+
+        let xref = pdf.len();
+        pdf.extend_from_slice(
+            format!("xref\n0 {}\n0000000000 65535 f \n", self.bodies.len() + 1).as_bytes(),
+        );
+        for offset in offsets {
+            pdf.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+        }
+        pdf.extend_from_slice(
+            format!(
+                "trailer\n<< /Size {} /Root {} 0 R /Info {} 0 R{} >>\nstartxref\n{}\n%%EOF\n",
+                self.bodies.len() + 1,
+                catalog,
+                info,
+                trailer,
+                xref
+            )
+            .as_bytes(),
+        );
+        pdf
+    }
+}
+
+const DOCUMENT_ID: [u8; 16] = *b"synthetic pdf id";
+const PERMISSIONS: i32 = -3904;
+const PAD: [u8; 32] = [
+    0x28, 0xBF, 0x4E, 0x5E, 0x4E, 0x75, 0x8A, 0x41, 0x64, 0x00, 0x4E, 0x56, 0xFF, 0xFA, 0x01, 0x08,
+    0x2E, 0x2E, 0x00, 0xB6, 0xD0, 0x68, 0x3E, 0x80, 0x2F, 0x0C, 0xA9, 0xFE, 0x64, 0x53, 0x69, 0x7A,
+];
+
+/// The file key and `/Encrypt` dictionary of an encrypted document, written independently of
+/// the extractor from PDF 32000-2, 7.6.4.
+struct Security {
+    encryption: Encryption,
+    key: Vec<u8>,
+    dictionary: String,
+}
+
+impl Security {
+    fn new(encryption: Encryption) -> Self {
+        let (revision, length) = match encryption {
+            Encryption::Rc4_40 => (2, 5),
+            Encryption::Rc4_128 => (3, 16),
+            Encryption::Aes128 => (4, 16),
+            Encryption::Aes256 => return Self::aes256(),
+        };
+        // Algorithm 3: `/O` is the padding encrypted with a key from the owner password
+        let mut owner_password = b"owner".to_vec();
+        owner_password.extend_from_slice(&PAD[..27]);
+        let owner_key = md5_rounds(revision, length, &owner_password);
+        let owner = rc4_rounds(revision, &owner_key, &PAD);
+
+        // Algorithm 2 with the empty user password
+        let mut input = PAD.to_vec();
+        input.extend_from_slice(&owner);
+        input.extend_from_slice(&PERMISSIONS.to_le_bytes());
+        input.extend_from_slice(&DOCUMENT_ID);
+        let key = md5_rounds(revision, length, &input);
+
+        // Algorithms 4 and 5
+        let user = if revision == 2 {
+            rc4(&key, &PAD)
+        } else {
+            let mut user = rc4_rounds(
+                revision,
+                &key,
+                &Md5::digest([&PAD[..], &DOCUMENT_ID].concat()),
+            );
+            user.resize(32, 0);
+            user
+        };
+
+        let (version, filters) = match encryption {
+            Encryption::Rc4_40 => (1, ""),
+            Encryption::Rc4_128 => (2, ""),
+            _ => (
+                4,
+                " /CF << /StdCF << /CFM /AESV2 /Length 16 /AuthEvent /DocOpen >> >> \
+                 /StmF /StdCF /StrF /StdCF",
+            ),
+        };
+        let dictionary = format!(
+            "<< /Filter /Standard /V {} /R {} /Length {} /P {} /O <{}> /U <{}>{} >>",
+            version,
+            revision,
+            length * 8,
+            PERMISSIONS,
+            hex(&owner),
+            hex(&user),
+            filters
+        );
+        Security {
+            encryption,
+            key,
+            dictionary,
+        }
+    }
+
+    // Algorithms 8 to 10: the random file key is stored encrypted under hashes of each password
+    fn aes256() -> Self {
+        let key: Vec<u8> = (0..32).map(|i| i * 7 + 3).collect();
+        let (validation_salt, key_salt) = (b"validate", b"key salt");
+        let mut user = hash(b"", validation_salt, &[]);
+        user.extend_from_slice(validation_salt);
+        user.extend_from_slice(key_salt);
+        let user_key = aes256_block_encrypt(&hash(b"", key_salt, &[]), &key);
+
+        let mut owner = hash(b"owner", validation_salt, &user);
+        owner.extend_from_slice(validation_salt);
+        owner.extend_from_slice(key_salt);
+        let owner_key = aes256_block_encrypt(&hash(b"owner", key_salt, &user), &key);
+
+        let mut permissions = PERMISSIONS.to_le_bytes().to_vec();
+        permissions.extend_from_slice(b"\xFF\xFF\xFF\xFFTadb\0\0\0\0");
+        let permissions = aes256_block_encrypt(&key, &permissions);
+
+        let dictionary = format!(
+            "<< /Filter /Standard /V 5 /R 6 /Length 256 /P {} /O <{}> /U <{}> /OE <{}> \
+             /UE <{}> /Perms <{}> /CF << /StdCF << /CFM /AESV3 /Length 32 /AuthEvent /DocOpen >> >> \
+             /StmF /StdCF /StrF /StdCF >>",
+            PERMISSIONS,
+            hex(&owner),
+            hex(&user),
+            hex(&owner_key),
+            hex(&user_key),
+            hex(&permissions)
+        );
+        Security {
+            encryption: Encryption::Aes256,
+            key,
+            dictionary,
+        }
+    }
+
+    /// `body` with its literal strings and stream data encrypted for object `id`. The generator
+    /// writes dictionary strings without escapes or nested parentheses.
+    fn encrypt_body(&self, id: usize, body: &[u8]) -> Vec<u8> {
+        let split = body
+            .windows(8)
+            .position(|w| w == b"\nstream\n")
+            .unwrap_or(body.len());
+        let mut dictionary = String::from_utf8(body[..split].to_vec()).unwrap();
+        while let Some(start) = dictionary.find('(') {
+            let end = start + dictionary[start..].find(')').unwrap();
+            let encrypted = self.encrypt(id, &dictionary.as_bytes()[start + 1..end]);
+            dictionary.replace_range(start..=end, &format!("<{}>", hex(&encrypted)));
+        }
+        if split == body.len() {
+            return dictionary.into_bytes();
+        }
+
+        let data = &body[split + 8..body.len() - b"\nendstream".len()];
+        let encrypted = self.encrypt(id, data);
+        let dictionary = dictionary.replacen(
+            &format!("/Length {}", data.len()),
+            &format!("/Length {}", encrypted.len()),
+            1,
+        );
+        let mut body = dictionary.into_bytes();
+        body.extend_from_slice(b"\nstream\n");
+        body.extend_from_slice(&encrypted);
+        body.extend_from_slice(b"\nendstream");
+        body
+    }
+
+    fn encrypt(&self, id: usize, data: &[u8]) -> Vec<u8> {
+        // A fixed initialization vector per object keeps documents reproducible
+        let iv = [id as u8; 16];
+        let object_key = |salt: &[u8]| {
+            let mut input = self.key.clone();
+            input.extend_from_slice(&(id as u32).to_le_bytes()[..3]);
+            input.extend_from_slice(&[0, 0]);
+            input.extend_from_slice(salt);
+            Md5::digest(&input)[..(self.key.len() + 5).min(16)].to_vec()
+        };
+        let mut out = iv.to_vec();
+        match self.encryption {
+            Encryption::Rc4_40 | Encryption::Rc4_128 => return rc4(&object_key(b""), data),
+            Encryption::Aes128 => {
+                out.extend(aes_cbc_encrypt(&object_key(b"sAlT"), &iv, &pkcs5(data)))
+            }
+            Encryption::Aes256 => out.extend(aes_cbc_encrypt(&self.key, &iv, &pkcs5(data))),
+        }
+        out
+    }
+}
+
+// MD5, then 50 more rounds over the first `length` bytes from revision 3
+fn md5_rounds(revision: u32, length: usize, input: &[u8]) -> Vec<u8> {
+    let mut digest = Md5::digest(input).to_vec();
+    if revision >= 3 {
+        for _ in 0..50 {
+            digest = Md5::digest(&digest[..length]).to_vec();
+        }
+    }
+    digest.truncate(length);
+    digest
+}
+
+// RC4, then 19 more passes with the key XORed with the pass number from revision 3
+fn rc4_rounds(revision: u32, key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut out = rc4(key, data);
+    if revision >= 3 {
+        for round in 1..=19u8 {
+            let round_key: Vec<u8> = key.iter().map(|b| b ^ round).collect();
+            out = rc4(&round_key, &out);
+        }
+    }
+    out
+}
+
+fn rc4(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut state: Vec<u8> = (0..=255).collect();
+    let mut j = 0usize;
+    for i in 0..256 {
+        j = (j + state[i] as usize + key[i % key.len()] as usize) % 256;
+        state.swap(i, j);
+    }
+    let (mut i, mut j) = (0usize, 0usize);
+    data.iter()
+        .map(|b| {
+            i = (i + 1) % 256;
+            j = (j + state[i] as usize) % 256;
+            state.swap(i, j);
+            b ^ state[(state[i] as usize + state[j] as usize) % 256]
+        })
+        .collect()
+}
+
+// Algorithm 2.B
+fn hash(password: &[u8], salt: &[u8], user: &[u8]) -> Vec<u8> {
+    let mut k = Sha256::digest([password, salt, user].concat()).to_vec();
+    let mut round = 0;
+    loop {
+        let block = [password, &k, user].concat().repeat(64);
+        let e = aes_cbc_encrypt(&k[..16], &k[16..32], &block);
+        k = match e[..16].iter().map(|&b| b as usize).sum::<usize>() % 3 {
+            0 => Sha256::digest(&e).to_vec(),
+            1 => Sha384::digest(&e).to_vec(),
+            _ => Sha512::digest(&e).to_vec(),
+        };
+        round += 1;
+        if round >= 64 && (e[e.len() - 1] as usize) <= round - 32 {
+            break;
+        }
+    }
+    k.truncate(32);
+    k
+}
+
+fn aes256_block_encrypt(key: &[u8], data: &[u8]) -> Vec<u8> {
+    aes_cbc_encrypt(key, &[0; 16], data)
+}
+
+/// AES-CBC over whole blocks, AES-128 or AES-256 by the key length.
+fn aes_cbc_encrypt(key: &[u8], iv: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut out = data.to_vec();
+    match key.len() {
+        16 => cbc::Encryptor::<aes::Aes128>::new_from_slices(key, iv)
+            .unwrap()
+            .encrypt_padded_mut::<NoPadding>(&mut out, data.len())
+            .map(|_| ())
+            .unwrap(),
+        _ => cbc::Encryptor::<aes::Aes256>::new_from_slices(key, iv)
+            .unwrap()
+            .encrypt_padded_mut::<NoPadding>(&mut out, data.len())
+            .map(|_| ())
+            .unwrap(),
+    }
+    out
+}
+
+fn pkcs5(data: &[u8]) -> Vec<u8> {
+    let pad = 16 - data.len() % 16;
+    let mut out = data.to_vec();
+    out.resize(data.len() + pad, pad as u8);
+    out
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02X}", b)).collect()
+}
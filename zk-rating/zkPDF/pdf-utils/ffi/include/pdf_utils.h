@@ -0,0 +1,134 @@
+#ifndef PDF_UTILS_H
+#define PDF_UTILS_H
+
+/* Generated with cbindgen from ffi/src/lib.rs. Do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Outcome of an FFI call. On anything other than `Ok` the out-parameters are left untouched and
+ * a message is written to `error` when it is non-null.
+ */
+typedef enum ZkpdfStatus {
+  ZKPDF_STATUS_OK = 0,
+  /**
+   * A required pointer was null or a string was not valid UTF-8.
+   */
+  ZKPDF_STATUS_INVALID_ARGUMENT = 1,
+  /**
+   * Parsing, extraction or signature verification failed.
+   */
+  ZKPDF_STATUS_ERROR = 2,
+  /**
+   * The library panicked; this is a bug.
+   */
+  ZKPDF_STATUS_PANIC = 3,
+} ZkpdfStatus;
+
+/**
+ * Text of every page, as produced by `extract_text`. Free with `zkpdf_pages_free`.
+ */
+typedef struct ZkpdfPages {
+  /**
+   * `len` NUL-terminated UTF-8 strings. NUL characters in the text are replaced by U+FFFD.
+   */
+  char **pages;
+  size_t len;
+} ZkpdfPages;
+
+/**
+ * Signature metadata, as produced by `verify_pdf_signature`. Free with `zkpdf_signature_free`.
+ */
+typedef struct ZkpdfSignature {
+  bool is_valid;
+  /**
+   * Message digest committed to by the signer.
+   */
+  uint8_t *message_digest;
+  size_t message_digest_len;
+  /**
+   * PKCS#1 DER public key of the signer.
+   */
+  uint8_t *public_key;
+  size_t public_key_len;
+} ZkpdfSignature;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Extracts the text of every page of the PDF in `pdf[0..len]` into `out`.
+ *
+ * # Safety
+ * `pdf` must point to `len` readable bytes, `out` must be a valid pointer and `error` must be
+ * null or a valid pointer.
+ */
+enum ZkpdfStatus zkpdf_extract_text(const uint8_t *pdf,
+                                    size_t len,
+                                    struct ZkpdfPages *out,
+                                    char **error);
+
+/**
+ * Releases the strings held by `pages` and resets it to empty. Null is ignored.
+ *
+ * # Safety
+ * `pages` must be null or filled by `zkpdf_extract_text` and not freed before.
+ */
+void zkpdf_pages_free(struct ZkpdfPages *pages);
+
+/**
+ * Verifies the PDF's signature. A signature that parses but does not verify is reported with
+ * `is_valid == false` and status `Ok`; tampered signed bytes are reported as `Error`.
+ *
+ * # Safety
+ * `pdf` must point to `len` readable bytes, `out` must be a valid pointer and `error` must be
+ * null or a valid pointer.
+ */
+enum ZkpdfStatus zkpdf_verify_signature(const uint8_t *pdf,
+                                        size_t len,
+                                        struct ZkpdfSignature *out,
+                                        char **error);
+
+/**
+ * Releases the buffers held by `signature` and resets it. Null is ignored.
+ *
+ * # Safety
+ * `signature` must be null or filled by this library and not freed before.
+ */
+void zkpdf_signature_free(struct ZkpdfSignature *signature);
+
+/**
+ * Verifies the signature and checks that `substring` appears at byte `offset` of the text of
+ * `page`, like `verify_text`. Fails if the signature does not verify.
+ *
+ * # Safety
+ * `pdf` must point to `len` readable bytes, `substring` must be a NUL-terminated string,
+ * `matches` must be a valid pointer, and `signature` and `error` must be null or valid pointers.
+ */
+enum ZkpdfStatus zkpdf_verify_text(const uint8_t *pdf,
+                                   size_t len,
+                                   uint8_t page,
+                                   const char *substring,
+                                   size_t offset,
+                                   bool *matches,
+                                   struct ZkpdfSignature *signature,
+                                   char **error);
+
+/**
+ * Releases an error message. Null is ignored.
+ *
+ * # Safety
+ * `s` must be null or a string returned by this library and not freed before.
+ */
+void zkpdf_string_free(char *s);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* PDF_UTILS_H */
@@ -0,0 +1,324 @@
+//! C ABI over `pdf_core` for non-Rust backends. See `include/pdf_utils.h`.
+//!
+//! Ownership: every pointer written to an out-parameter is allocated here and must be released
+//! with the matching `*_free` function, never with the caller's `free`. Input buffers are only
+//! borrowed for the duration of the call.
+
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{catch_unwind, UnwindSafe};
+use std::{ptr, slice};
+
+use pdf_core::{extract_text, verify_pdf_signature, verify_text, PdfSignatureResult};
+
+/// Outcome of an FFI call. On anything other than `Ok` the out-parameters are left untouched and
+/// a message is written to `error` when it is non-null.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZkpdfStatus {
+    Ok = 0,
+    /// A required pointer was null or a string was not valid UTF-8.
+    InvalidArgument = 1,
+    /// Parsing, extraction or signature verification failed.
+    Error = 2,
+    /// The library panicked; this is a bug.
+    Panic = 3,
+}
+
+/// Text of every page, as produced by `extract_text`. Free with `zkpdf_pages_free`.
+#[repr(C)]
+pub struct ZkpdfPages {
+    /// `len` NUL-terminated UTF-8 strings. NUL characters in the text are replaced by U+FFFD.
+    pub pages: *mut *mut c_char,
+    pub len: usize,
+}
+
+/// Signature metadata, as produced by `verify_pdf_signature`. Free with `zkpdf_signature_free`.
+#[repr(C)]
+pub struct ZkpdfSignature {
+    pub is_valid: bool,
+    /// Message digest committed to by the signer.
+    pub message_digest: *mut u8,
+    pub message_digest_len: usize,
+    /// PKCS#1 DER public key of the signer.
+    pub public_key: *mut u8,
+    pub public_key_len: usize,
+}
+
+type FfiResult<T> = Result<T, (ZkpdfStatus, String)>;
+
+// Run `f`, converting errors and panics into a status and an optional error message
+fn guard(error: *mut *mut c_char, f: impl FnOnce() -> FfiResult<()> + UnwindSafe) -> ZkpdfStatus {
+    let (status, message) = match catch_unwind(f) {
+        Ok(Ok(())) => return ZkpdfStatus::Ok,
+        Ok(Err(failure)) => failure,
+        Err(_) => (ZkpdfStatus::Panic, "internal panic".to_string()),
+    };
+    if !error.is_null() {
+        // SAFETY: the caller passes either null or a valid `char **`
+        unsafe { *error = into_c_string(message) };
+    }
+    status
+}
+
+fn invalid(message: &str) -> (ZkpdfStatus, String) {
+    (ZkpdfStatus::InvalidArgument, message.to_string())
+}
+
+unsafe fn input<'a>(pdf: *const u8, len: usize) -> FfiResult<&'a [u8]> {
+    if pdf.is_null() {
+        return Err(invalid("pdf is null"));
+    }
+    Ok(slice::from_raw_parts(pdf, len))
+}
+
+fn into_c_string(s: String) -> *mut c_char {
+    let s = if s.contains('\0') {
+        s.replace('\0', "\u{FFFD}")
+    } else {
+        s
+    };
+    CString::new(s).expect("NUL bytes were replaced").into_raw()
+}
+
+fn into_raw_bytes(bytes: Vec<u8>) -> (*mut u8, usize) {
+    let len = bytes.len();
+    (Box::into_raw(bytes.into_boxed_slice()) as *mut u8, len)
+}
+
+unsafe fn free_bytes(ptr: *mut u8, len: usize) {
+    if !ptr.is_null() {
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(ptr, len)));
+    }
+}
+
+fn to_ffi_signature(signature: PdfSignatureResult) -> ZkpdfSignature {
+    let (message_digest, message_digest_len) = into_raw_bytes(signature.message_digest);
+    let (public_key, public_key_len) = into_raw_bytes(signature.public_key);
+    ZkpdfSignature {
+        is_valid: signature.is_valid,
+        message_digest,
+        message_digest_len,
+        public_key,
+        public_key_len,
+    }
+}
+
+/// Extracts the text of every page of the PDF in `pdf[0..len]` into `out`.
+///
+/// # Safety
+/// `pdf` must point to `len` readable bytes, `out` must be a valid pointer and `error` must be
+/// null or a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn zkpdf_extract_text(
+    pdf: *const u8,
+    len: usize,
+    out: *mut ZkpdfPages,
+    error: *mut *mut c_char,
+) -> ZkpdfStatus {
+    guard(error, || {
+        let pdf = input(pdf, len)?;
+        if out.is_null() {
+            return Err(invalid("out is null"));
+        }
+        let pages = extract_text(pdf.to_vec())
+            .map_err(|e| (ZkpdfStatus::Error, format!("text extraction error: {}", e)))?;
+
+        let pages: Box<[*mut c_char]> = pages.into_iter().map(into_c_string).collect();
+        let len = pages.len();
+        *out = ZkpdfPages {
+            pages: Box::into_raw(pages) as *mut *mut c_char,
+            len,
+        };
+        Ok(())
+    })
+}
+
+/// Releases the strings held by `pages` and resets it to empty. Null is ignored.
+///
+/// # Safety
+/// `pages` must be null or filled by `zkpdf_extract_text` and not freed before.
+#[no_mangle]
+pub unsafe extern "C" fn zkpdf_pages_free(pages: *mut ZkpdfPages) {
+    let Some(pages) = pages.as_mut() else {
+        return;
+    };
+    if !pages.pages.is_null() {
+        let strings = Box::from_raw(ptr::slice_from_raw_parts_mut(pages.pages, pages.len));
+        for &page in strings.iter() {
+            drop(CString::from_raw(page));
+        }
+    }
+    pages.pages = ptr::null_mut();
+    pages.len = 0;
+}
+
+/// Verifies the PDF's signature. A signature that parses but does not verify is reported with
+/// `is_valid == false` and status `Ok`; tampered signed bytes are reported as `Error`.
+///
+/// # Safety
+/// `pdf` must point to `len` readable bytes, `out` must be a valid pointer and `error` must be
+/// null or a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn zkpdf_verify_signature(
+    pdf: *const u8,
+    len: usize,
+    out: *mut ZkpdfSignature,
+    error: *mut *mut c_char,
+) -> ZkpdfStatus {
+    guard(error, || {
+        let pdf = input(pdf, len)?;
+        if out.is_null() {
+            return Err(invalid("out is null"));
+        }
+        let signature = verify_pdf_signature(pdf).map_err(|e| {
+            (
+                ZkpdfStatus::Error,
+                format!("signature verification error: {}", e),
+            )
+        })?;
+        *out = to_ffi_signature(signature);
+        Ok(())
+    })
+}
+
+/// Releases the buffers held by `signature` and resets it. Null is ignored.
+///
+/// # Safety
+/// `signature` must be null or filled by this library and not freed before.
+#[no_mangle]
+pub unsafe extern "C" fn zkpdf_signature_free(signature: *mut ZkpdfSignature) {
+    let Some(signature) = signature.as_mut() else {
+        return;
+    };
+    free_bytes(signature.message_digest, signature.message_digest_len);
+    free_bytes(signature.public_key, signature.public_key_len);
+    signature.message_digest = ptr::null_mut();
+    signature.message_digest_len = 0;
+    signature.public_key = ptr::null_mut();
+    signature.public_key_len = 0;
+}
+
+/// Verifies the signature and checks that `substring` appears at byte `offset` of the text of
+/// `page`, like `verify_text`. Fails if the signature does not verify.
+///
+/// # Safety
+/// `pdf` must point to `len` readable bytes, `substring` must be a NUL-terminated string,
+/// `matches` must be a valid pointer, and `signature` and `error` must be null or valid pointers.
+#[no_mangle]
+pub unsafe extern "C" fn zkpdf_verify_text(
+    pdf: *const u8,
+    len: usize,
+    page: u8,
+    substring: *const c_char,
+    offset: usize,
+    matches: *mut bool,
+    signature: *mut ZkpdfSignature,
+    error: *mut *mut c_char,
+) -> ZkpdfStatus {
+    guard(error, || {
+        let pdf = input(pdf, len)?;
+        if substring.is_null() || matches.is_null() {
+            return Err(invalid("substring or matches is null"));
+        }
+        let substring = CStr::from_ptr(substring)
+            .to_str()
+            .map_err(|_| invalid("substring is not valid UTF-8"))?;
+
+        let result = verify_text(pdf.to_vec(), page, substring, offset)
+            .map_err(|e| (ZkpdfStatus::Error, e.to_string()))?;
+        *matches = result.substring_matches;
+        if !signature.is_null() {
+            *signature = to_ffi_signature(result.signature);
+        }
+        Ok(())
+    })
+}
+
+/// Releases an error message. Null is ignored.
+///
+/// # Safety
+/// `s` must be null or a string returned by this library and not freed before.
+#[no_mangle]
+pub unsafe extern "C" fn zkpdf_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIGNED_PDF: &[u8] = include_bytes!("../../sample-pdfs/digitally_signed.pdf");
+
+    #[test]
+    fn extract_and_verify_round_trip() {
+        unsafe {
+            let mut pages = ZkpdfPages {
+                pages: ptr::null_mut(),
+                len: 0,
+            };
+            let status = zkpdf_extract_text(
+                SIGNED_PDF.as_ptr(),
+                SIGNED_PDF.len(),
+                &mut pages,
+                ptr::null_mut(),
+            );
+            assert_eq!(status, ZkpdfStatus::Ok);
+            assert_eq!(pages.len, 1);
+            let first = CStr::from_ptr(*pages.pages).to_str().unwrap();
+            assert_eq!(first, "Sample Signed PDF Document");
+            zkpdf_pages_free(&mut pages);
+            assert!(pages.pages.is_null());
+
+            let mut matches = false;
+            let mut signature = ZkpdfSignature {
+                is_valid: false,
+                message_digest: ptr::null_mut(),
+                message_digest_len: 0,
+                public_key: ptr::null_mut(),
+                public_key_len: 0,
+            };
+            let substring = CString::new("Signed PDF").unwrap();
+            let status = zkpdf_verify_text(
+                SIGNED_PDF.as_ptr(),
+                SIGNED_PDF.len(),
+                0,
+                substring.as_ptr(),
+                7,
+                &mut matches,
+                &mut signature,
+                ptr::null_mut(),
+            );
+            assert_eq!(status, ZkpdfStatus::Ok);
+            assert!(matches);
+            assert!(signature.is_valid);
+            assert!(signature.public_key_len > 0);
+            zkpdf_signature_free(&mut signature);
+        }
+    }
+
+    #[test]
+    fn errors_are_reported_through_the_error_pointer() {
+        unsafe {
+            let garbage = b"not a pdf";
+            let mut signature = ZkpdfSignature {
+                is_valid: false,
+                message_digest: ptr::null_mut(),
+                message_digest_len: 0,
+                public_key: ptr::null_mut(),
+                public_key_len: 0,
+            };
+            let mut error = ptr::null_mut();
+            let status =
+                zkpdf_verify_signature(garbage.as_ptr(), garbage.len(), &mut signature, &mut error);
+            assert_eq!(status, ZkpdfStatus::Error);
+            assert!(!error.is_null());
+            assert!(signature.message_digest.is_null());
+            zkpdf_string_free(error);
+
+            let status = zkpdf_extract_text(ptr::null(), 0, ptr::null_mut(), ptr::null_mut());
+            assert_eq!(status, ZkpdfStatus::InvalidArgument);
+        }
+    }
+}
@@ -0,0 +1,8 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use signature_validator::signed_bytes_extractor::get_signature_der;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = get_signature_der(data);
+});
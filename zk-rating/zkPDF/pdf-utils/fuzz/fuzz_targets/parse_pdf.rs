@@ -0,0 +1,8 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = extractor::parse_pdf(data);
+    let _ = extractor::extract_text(data.to_vec());
+});
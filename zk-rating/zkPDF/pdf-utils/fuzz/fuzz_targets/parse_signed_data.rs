@@ -0,0 +1,8 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use signature_validator::pkcs7_parser::parse_signed_data;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = parse_signed_data(data);
+});
@@ -0,0 +1,68 @@
+//! Fills `corpus/<target>/` from the sample PDFs: whole files for the document-level targets,
+//! decompressed content streams and CMaps for the stream parsers, and the embedded PKCS#7 blobs
+//! for `parse_signed_data`. Run from the `fuzz` directory: `cargo run --bin seed_corpus`.
+
+use std::fs;
+use std::path::Path;
+
+use extractor::types::PdfObj;
+use miniz_oxide::inflate::decompress_to_vec_zlib;
+use signature_validator::signed_bytes_extractor::get_signature_der;
+
+const SAMPLES: &str = "../sample-pdfs";
+const CORPUS: &str = "corpus";
+
+fn write_seed(target: &str, name: &str, data: &[u8]) {
+    let dir = Path::new(CORPUS).join(target);
+    fs::create_dir_all(&dir).expect("failed to create corpus directory");
+    fs::write(dir.join(name), data).expect("failed to write seed");
+}
+
+fn main() {
+    let mut entries: Vec<_> = fs::read_dir(SAMPLES)
+        .expect("sample-pdfs not found; run from the fuzz directory")
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "pdf"))
+        .collect();
+    entries.sort();
+
+    for path in entries {
+        let stem = path.file_stem().unwrap().to_string_lossy().into_owned();
+        let data = fs::read(&path).expect("failed to read sample");
+
+        write_seed("parse_pdf", &stem, &data);
+        write_seed("get_signature_der", &stem, &data);
+        if let Ok((der, _)) = get_signature_der(&data) {
+            write_seed("parse_signed_data", &stem, &der);
+        }
+
+        let Ok((_, objects)) = extractor::parse_pdf(&data) else {
+            continue;
+        };
+        let mut streams = 0;
+        for (&(id, generation), obj) in &objects {
+            let PdfObj::Stream(stream) = obj else {
+                continue;
+            };
+            let decoded = match stream.dict.get("Filter") {
+                Some(PdfObj::Name(filter)) if filter == "FlateDecode" => {
+                    match decompress_to_vec_zlib(&stream.data) {
+                        Ok(decoded) => decoded,
+                        Err(_) => continue,
+                    }
+                }
+                None => stream.data.to_vec(),
+                _ => continue,
+            };
+            let name = format!("{}-{}-{}", stem, id, generation);
+            if decoded.windows(9).any(|w| w == b"begincmap") {
+                write_seed("parse_cmap", &name, &decoded);
+                streams += 1;
+            } else if decoded.windows(2).any(|w| w == b"BT") && !stream.dict.contains_key("Type") {
+                write_seed("parse_content_tokens", &name, &decoded);
+                streams += 1;
+            }
+        }
+        println!("{}: {} stream seeds", stem, streams);
+    }
+}
@@ -0,0 +1,182 @@
+//! N-API bindings for Node.js. Functions take PDFs as `Buffer`s; the `*Async` variants run on
+//! the libuv thread pool so a server can verify several documents in parallel.
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+use pdf_core::{PdfSignatureResult, PdfVerifiedContent};
+
+#[napi(object)]
+pub struct SignatureInfo {
+    pub is_valid: bool,
+    /// Message digest committed to by the signer.
+    pub message_digest: Buffer,
+    /// PKCS#1 DER public key of the signer.
+    pub public_key: Buffer,
+}
+
+#[napi(object)]
+pub struct VerifiedContent {
+    pub pages: Vec<String>,
+    pub signature: SignatureInfo,
+}
+
+#[napi(object)]
+pub struct VerifyTextResult {
+    pub substring_matches: bool,
+    pub signature: SignatureInfo,
+}
+
+#[napi(object)]
+pub struct TextMatch {
+    pub page: u32,
+    /// Offset to pass to `verifyText`.
+    pub offset: u32,
+    pub length: u32,
+}
+
+impl From<PdfSignatureResult> for SignatureInfo {
+    fn from(signature: PdfSignatureResult) -> Self {
+        Self {
+            is_valid: signature.is_valid,
+            message_digest: signature.message_digest.into(),
+            public_key: signature.public_key.into(),
+        }
+    }
+}
+
+impl From<PdfVerifiedContent> for VerifiedContent {
+    fn from(content: PdfVerifiedContent) -> Self {
+        Self {
+            pages: content.pages,
+            signature: content.signature.into(),
+        }
+    }
+}
+
+fn to_napi_error(e: impl ToString) -> Error {
+    Error::from_reason(e.to_string())
+}
+
+/// Text of every page.
+#[napi]
+pub fn extract_text(pdf: Buffer) -> Result<Vec<String>> {
+    pdf_core::extract_text(pdf.to_vec()).map_err(to_napi_error)
+}
+
+/// Verifies the embedded signature without extracting text.
+#[napi]
+pub fn verify_signature(pdf: Buffer) -> Result<SignatureInfo> {
+    pdf_core::verify_pdf_signature(&pdf)
+        .map(SignatureInfo::from)
+        .map_err(to_napi_error)
+}
+
+/// Verifies the signature and checks that `substring` appears at `offset` on `page`.
+#[napi]
+pub fn verify_text(
+    pdf: Buffer,
+    page: u8,
+    substring: String,
+    offset: u32,
+) -> Result<VerifyTextResult> {
+    let result = pdf_core::verify_text(pdf.to_vec(), page, &substring, offset as usize)
+        .map_err(to_napi_error)?;
+    Ok(VerifyTextResult {
+        substring_matches: result.substring_matches,
+        signature: result.signature.into(),
+    })
+}
+
+/// Verifies the signature and returns the text of every page, also when the signature does
+/// not verify; check `signature.isValid`.
+#[napi]
+pub fn verify_and_extract(pdf: Buffer) -> Result<VerifiedContent> {
+    pdf_core::verify_and_extract(pdf.to_vec())
+        .map(VerifiedContent::from)
+        .map_err(to_napi_error)
+}
+
+/// Occurrences of `needle` in the extracted text, optionally restricted to one page.
+#[napi]
+pub fn find_text(pdf: Buffer, needle: String, page: Option<u32>) -> Result<Vec<TextMatch>> {
+    let pages = pdf_core::extract_text(pdf.to_vec()).map_err(to_napi_error)?;
+    Ok(
+        pdf_core::find_text(&pages, &needle, page.map(|page| page as usize))
+            .into_iter()
+            .map(|m| TextMatch {
+                page: m.page as u32,
+                offset: m.offset as u32,
+                length: m.len as u32,
+            })
+            .collect(),
+    )
+}
+
+pub struct VerifyAndExtractTask {
+    pdf: Vec<u8>,
+}
+
+impl Task for VerifyAndExtractTask {
+    type Output = PdfVerifiedContent;
+    type JsValue = VerifiedContent;
+
+    fn compute(&mut self) -> Result<Self::Output> {
+        pdf_core::verify_and_extract(std::mem::take(&mut self.pdf)).map_err(to_napi_error)
+    }
+
+    fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
+        Ok(output.into())
+    }
+}
+
+/// `verifyAndExtract` on the thread pool.
+#[napi]
+pub fn verify_and_extract_async(pdf: Buffer) -> AsyncTask<VerifyAndExtractTask> {
+    AsyncTask::new(VerifyAndExtractTask { pdf: pdf.to_vec() })
+}
+
+pub struct VerifyTextTask {
+    pdf: Vec<u8>,
+    page: u8,
+    substring: String,
+    offset: u32,
+}
+
+impl Task for VerifyTextTask {
+    type Output = pdf_core::PdfVerificationResult;
+    type JsValue = VerifyTextResult;
+
+    fn compute(&mut self) -> Result<Self::Output> {
+        pdf_core::verify_text(
+            std::mem::take(&mut self.pdf),
+            self.page,
+            &self.substring,
+            self.offset as usize,
+        )
+        .map_err(to_napi_error)
+    }
+
+    fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
+        Ok(VerifyTextResult {
+            substring_matches: output.substring_matches,
+            signature: output.signature.into(),
+        })
+    }
+}
+
+/// `verifyText` on the thread pool.
+#[napi]
+pub fn verify_text_async(
+    pdf: Buffer,
+    page: u8,
+    substring: String,
+    offset: u32,
+) -> AsyncTask<VerifyTextTask> {
+    AsyncTask::new(VerifyTextTask {
+        pdf: pdf.to_vec(),
+        page,
+        substring,
+        offset,
+    })
+}
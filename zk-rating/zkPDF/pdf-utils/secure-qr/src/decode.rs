@@ -0,0 +1,432 @@
+//! Reading a sampled symbol: format information, unmasking, block de-interleaving, error
+//! correction and segment decoding.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::gf;
+use crate::QrError;
+
+// Error correction codewords per block and number of blocks, by level then version (index 0
+// unused)
+const ECC_CODEWORDS_PER_BLOCK: [[u8; 41]; 4] = [
+    [
+        0, 7, 10, 15, 20, 26, 18, 20, 24, 30, 18, 20, 24, 26, 30, 22, 24, 28, 30, 28, 28, 28, 28,
+        30, 30, 26, 28, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30,
+    ],
+    [
+        0, 10, 16, 26, 18, 24, 16, 18, 22, 22, 26, 30, 22, 22, 24, 24, 28, 28, 26, 26, 26, 26, 28,
+        28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28,
+    ],
+    [
+        0, 13, 22, 18, 26, 18, 24, 18, 22, 20, 24, 28, 26, 24, 20, 30, 24, 28, 28, 26, 30, 28, 30,
+        30, 30, 30, 28, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30,
+    ],
+    [
+        0, 17, 28, 22, 16, 22, 28, 26, 26, 24, 28, 24, 28, 22, 24, 24, 30, 28, 28, 26, 28, 30, 24,
+        30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30,
+    ],
+];
+const ECC_BLOCKS: [[u8; 41]; 4] = [
+    [
+        0, 1, 1, 1, 1, 1, 2, 2, 2, 2, 4, 4, 4, 4, 4, 6, 6, 6, 6, 7, 8, 8, 9, 9, 10, 12, 12, 12, 13,
+        14, 15, 16, 17, 18, 19, 19, 20, 21, 22, 24, 25,
+    ],
+    [
+        0, 1, 1, 1, 2, 2, 4, 4, 4, 5, 5, 5, 8, 9, 9, 10, 10, 11, 13, 14, 16, 17, 17, 18, 20, 21,
+        23, 25, 26, 28, 29, 31, 33, 35, 37, 38, 40, 43, 45, 47, 49,
+    ],
+    [
+        0, 1, 1, 2, 2, 4, 4, 6, 6, 8, 8, 8, 10, 12, 16, 12, 17, 16, 18, 21, 20, 23, 23, 25, 27, 29,
+        34, 34, 35, 38, 40, 43, 45, 48, 51, 53, 56, 59, 62, 65, 68,
+    ],
+    [
+        0, 1, 1, 2, 4, 4, 4, 5, 6, 8, 8, 11, 11, 16, 16, 18, 16, 19, 21, 25, 25, 25, 34, 30, 32,
+        35, 37, 40, 42, 45, 48, 51, 54, 57, 60, 63, 66, 70, 74, 77, 81,
+    ],
+];
+
+const ALPHANUMERIC: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ $%*+-./:";
+
+/// The contents of a decoded symbol.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Decoded {
+    pub version: u8,
+    /// Concatenated segment payloads. Numeric and alphanumeric segments are ASCII; byte
+    /// segments are passed through untouched.
+    pub data: Vec<u8>,
+    /// Codewords repaired by error correction.
+    pub corrected: usize,
+}
+
+/// Decodes a `size` x `size` module grid, row by row, `true` for dark.
+pub fn decode_modules(modules: &[bool], size: usize) -> Result<Decoded, QrError> {
+    if size < 21 || !(size - 17).is_multiple_of(4) || size > 177 || modules.len() != size * size {
+        return Err(QrError::Sampling);
+    }
+    let version = ((size - 17) / 4) as u8;
+    let (level, mask) = read_format(modules, size)?;
+
+    let function = function_modules(version, size);
+    let mut codewords = vec![0u8; raw_data_modules(version) / 8];
+    let mut i = 0;
+    for (x, y) in zigzag(size) {
+        if function[y * size + x] {
+            continue;
+        }
+        if i == codewords.len() * 8 {
+            break;
+        }
+        if modules[y * size + x] != mask_bit(mask, x, y) {
+            codewords[i / 8] |= 0x80 >> (i % 8);
+        }
+        i += 1;
+    }
+
+    let (data, corrected) = correct_blocks(&codewords, version, level)?;
+    Ok(Decoded {
+        version,
+        data: read_segments(&data, version)?,
+        corrected,
+    })
+}
+
+/// Error correction level index (L, M, Q, H) and mask from either copy of the format
+/// information, whichever is closest to a valid code word.
+fn read_format(modules: &[bool], size: usize) -> Result<(usize, u8), QrError> {
+    let get = |x: usize, y: usize| modules[y * size + x] as u32;
+    let mut first = 0;
+    for i in 0..6 {
+        first |= get(8, i) << i;
+    }
+    first |= get(8, 7) << 6 | get(8, 8) << 7 | get(7, 8) << 8;
+    for i in 9..15 {
+        first |= get(14 - i, 8) << i;
+    }
+    let mut second = 0;
+    for i in 0..8 {
+        second |= get(size - 1 - i, 8) << i;
+    }
+    for i in 8..15 {
+        second |= get(8, size - 15 + i) << i;
+    }
+
+    let (distance, data) = (0..32u32)
+        .map(|data| {
+            let bits = format_bits(data);
+            let distance = (bits ^ first)
+                .count_ones()
+                .min((bits ^ second).count_ones());
+            (distance, data)
+        })
+        .min()
+        .expect("32 candidates");
+    if distance > 3 {
+        return Err(QrError::Format);
+    }
+    // The two level bits are L=01, M=00, Q=11, H=10
+    let level = [1, 0, 3, 2][(data >> 3) as usize];
+    Ok((level, (data & 7) as u8))
+}
+
+fn format_bits(data: u32) -> u32 {
+    let mut rem = data;
+    for _ in 0..10 {
+        rem = (rem << 1) ^ ((rem >> 9) * 0x537);
+    }
+    ((data << 10) | rem) ^ 0x5412
+}
+
+/// Finder, separator, timing, alignment, format and version modules.
+fn function_modules(version: u8, size: usize) -> Vec<bool> {
+    let mut function = vec![false; size * size];
+    let mut fill = |x0: usize, y0: usize, w: usize, h: usize| {
+        for y in y0..y0 + h {
+            for x in x0..x0 + w {
+                function[y * size + x] = true;
+            }
+        }
+    };
+    // Finders with separators and format areas
+    fill(0, 0, 9, 9);
+    fill(size - 8, 0, 8, 9);
+    fill(0, size - 8, 9, 8);
+    // Timing
+    fill(6, 0, 1, size);
+    fill(0, 6, size, 1);
+    let positions = alignment_positions(version);
+    let last = positions.len().saturating_sub(1);
+    for (i, &cx) in positions.iter().enumerate() {
+        for (j, &cy) in positions.iter().enumerate() {
+            if (i == 0 && (j == 0 || j == last)) || (i == last && j == 0) {
+                continue;
+            }
+            fill(cx - 2, cy - 2, 5, 5);
+        }
+    }
+    if version >= 7 {
+        fill(size - 11, 0, 3, 6);
+        fill(0, size - 11, 6, 3);
+    }
+    function
+}
+
+/// Module positions in placement order: two columns at a time from the right, alternating
+/// upwards and downwards, skipping the vertical timing column.
+fn zigzag(size: usize) -> impl Iterator<Item = (usize, usize)> {
+    let columns = (1..size as i32)
+        .rev()
+        .step_by(2)
+        .map(|right| if right <= 6 { right - 1 } else { right });
+    columns.flat_map(move |right| {
+        let upward = (right + 1) & 2 == 0;
+        (0..size).flat_map(move |vert| {
+            let y = if upward { size - 1 - vert } else { vert };
+            (0..2).map(move |j| ((right - j) as usize, y))
+        })
+    })
+}
+
+fn mask_bit(mask: u8, x: usize, y: usize) -> bool {
+    match mask {
+        0 => (x + y).is_multiple_of(2),
+        1 => y.is_multiple_of(2),
+        2 => x.is_multiple_of(3),
+        3 => (x + y).is_multiple_of(3),
+        4 => (x / 3 + y / 2).is_multiple_of(2),
+        5 => x * y % 2 + x * y % 3 == 0,
+        6 => (x * y % 2 + x * y % 3).is_multiple_of(2),
+        _ => ((x + y) % 2 + x * y % 3).is_multiple_of(2),
+    }
+}
+
+fn raw_data_modules(version: u8) -> usize {
+    let v = version as usize;
+    let mut result = (16 * v + 128) * v + 64;
+    if v >= 2 {
+        let align = v / 7 + 2;
+        result -= (25 * align - 10) * align - 55;
+        if v >= 7 {
+            result -= 36;
+        }
+    }
+    result
+}
+
+fn alignment_positions(version: u8) -> Vec<usize> {
+    if version == 1 {
+        return Vec::new();
+    }
+    let v = version as usize;
+    let count = v / 7 + 2;
+    let step = (v * 8 + count * 3 + 5) / (count * 4 - 4) * 2;
+    let mut positions: Vec<usize> = (0..count - 1).map(|i| v * 4 + 10 - i * step).collect();
+    positions.push(6);
+    positions.reverse();
+    positions
+}
+
+/// Splits the interleaved codewords into blocks, corrects each and joins the data parts.
+fn correct_blocks(
+    codewords: &[u8],
+    version: u8,
+    level: usize,
+) -> Result<(Vec<u8>, usize), QrError> {
+    let v = version as usize;
+    let blocks = ECC_BLOCKS[level][v] as usize;
+    let ecc_len = ECC_CODEWORDS_PER_BLOCK[level][v] as usize;
+    let raw = codewords.len();
+    let short_blocks = blocks - raw % blocks;
+    let short_data = raw / blocks - ecc_len;
+
+    let mut split: Vec<Vec<u8>> = (0..blocks)
+        .map(|j| Vec::with_capacity(short_data + 1 + ecc_len + usize::from(j >= short_blocks)))
+        .collect();
+    let mut next = codewords.iter();
+    for i in 0..=short_data {
+        for (j, block) in split.iter_mut().enumerate() {
+            // Short blocks have no codeword at the last data position
+            if i == short_data && j < short_blocks {
+                continue;
+            }
+            block.extend(next.next());
+        }
+    }
+    for _ in 0..ecc_len {
+        for block in split.iter_mut() {
+            block.extend(next.next());
+        }
+    }
+
+    let mut data = Vec::with_capacity(raw - blocks * ecc_len);
+    let mut corrected = 0;
+    for mut block in split {
+        corrected += gf::correct(&mut block, ecc_len).ok_or(QrError::TooManyErrors)?;
+        data.extend_from_slice(&block[..block.len() - ecc_len]);
+    }
+    Ok((data, corrected))
+}
+
+struct Bits<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl Bits<'_> {
+    fn remaining(&self) -> usize {
+        self.data.len() * 8 - self.pos
+    }
+
+    fn read(&mut self, len: usize) -> Result<u32, QrError> {
+        if len > self.remaining() {
+            return Err(QrError::Truncated);
+        }
+        let mut value = 0;
+        for _ in 0..len {
+            let bit = (self.data[self.pos / 8] >> (7 - self.pos % 8)) & 1;
+            value = (value << 1) | bit as u32;
+            self.pos += 1;
+        }
+        Ok(value)
+    }
+}
+
+fn read_segments(data: &[u8], version: u8) -> Result<Vec<u8>, QrError> {
+    let group = match version {
+        1..=9 => 0,
+        10..=26 => 1,
+        _ => 2,
+    };
+    let mut bits = Bits { data, pos: 0 };
+    let mut out = Vec::new();
+    while bits.remaining() >= 4 {
+        match bits.read(4)? {
+            0 => break,
+            1 => {
+                let mut count = bits.read([10, 12, 14][group])? as usize;
+                while count > 0 {
+                    let digits = count.min(3);
+                    let value = bits.read(digits * 3 + 1)?;
+                    if value >= 10u32.pow(digits as u32) {
+                        return Err(QrError::Segment);
+                    }
+                    for d in (0..digits).rev() {
+                        out.push(b'0' + (value / 10u32.pow(d as u32) % 10) as u8);
+                    }
+                    count -= digits;
+                }
+            }
+            2 => {
+                let mut count = bits.read([9, 11, 13][group])? as usize;
+                while count > 0 {
+                    let pair = count >= 2;
+                    let value = bits.read(if pair { 11 } else { 6 })? as usize;
+                    let chars: &[usize] = if pair {
+                        &[value / 45, value % 45]
+                    } else {
+                        &[value]
+                    };
+                    for &c in chars {
+                        out.push(*ALPHANUMERIC.get(c).ok_or(QrError::Segment)?);
+                    }
+                    count -= chars.len();
+                }
+            }
+            4 => {
+                let count = bits.read([8, 16, 16][group])?;
+                for _ in 0..count {
+                    out.push(bits.read(8)? as u8);
+                }
+            }
+            8 => {
+                // Shift JIS, thirteen bits per character
+                let count = bits.read([8, 10, 12][group])?;
+                for _ in 0..count {
+                    let value = bits.read(13)?;
+                    let mut c = ((value / 0xc0) << 8) | (value % 0xc0);
+                    c += if c + 0x8140 <= 0x9ffc { 0x8140 } else { 0xc140 };
+                    out.extend_from_slice(&[(c >> 8) as u8, c as u8]);
+                }
+            }
+            7 => {
+                // ECI designator: the payload bytes are passed through as they are
+                let first = bits.read(8)?;
+                if first & 0x80 != 0 {
+                    bits.read(if first & 0x40 == 0 { 8 } else { 16 })?;
+                }
+            }
+            // Structured append header
+            3 => {
+                bits.read(16)?;
+            }
+            // FNC1 markers
+            5 => {}
+            9 => {
+                bits.read(8)?;
+            }
+            _ => return Err(QrError::Segment),
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zigzag_visits_every_module_once() {
+        for size in [21, 25, 45, 177] {
+            let mut seen = vec![false; size * size];
+            for (x, y) in zigzag(size) {
+                assert!(!seen[y * size + x]);
+                seen[y * size + x] = true;
+            }
+            // Only the vertical timing column is skipped
+            for (i, s) in seen.iter().enumerate() {
+                assert_eq!(*s, i % size != 6, "module {}", i);
+            }
+        }
+    }
+
+    #[test]
+    fn function_modules_leave_the_raw_data_area() {
+        for version in 1..=40u8 {
+            let size = 17 + 4 * version as usize;
+            let free = function_modules(version, size)
+                .iter()
+                .filter(|f| !**f)
+                .count();
+            assert_eq!(free, raw_data_modules(version), "version {}", version);
+        }
+    }
+
+    #[test]
+    fn segments_decode() {
+        // 1-M "01234567" from the standard, then a byte segment "Hi"
+        let mut bits = Vec::new();
+        let mut push =
+            |value: u32, len: usize| bits.extend((0..len).rev().map(|i| (value >> i) & 1));
+        push(1, 4);
+        push(8, 10);
+        push(12, 10);
+        push(345, 10);
+        push(67, 7);
+        push(2, 4);
+        push(2, 9);
+        push(17 * 45 + 18, 11);
+        push(4, 4);
+        push(2, 8);
+        push(b'H' as u32, 8);
+        push(b'i' as u32, 8);
+        push(0, 4);
+        while bits.len() % 8 != 0 {
+            bits.push(0);
+        }
+        let bytes: Vec<u8> = bits
+            .chunks(8)
+            .map(|b| b.iter().fold(0, |acc, &bit| (acc << 1) | bit as u8))
+            .collect();
+        assert_eq!(read_segments(&bytes, 1).unwrap(), b"01234567HIHi");
+    }
+}
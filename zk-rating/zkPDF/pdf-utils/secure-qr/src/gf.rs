@@ -0,0 +1,198 @@
+//! GF(256) arithmetic over the QR polynomial 0x11d and Reed-Solomon error correction.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+const EXP: [u8; 256] = {
+    let mut table = [0u8; 256];
+    let mut x: u16 = 1;
+    let mut i = 0;
+    while i < 256 {
+        table[i] = x as u8;
+        x <<= 1;
+        if x & 0x100 != 0 {
+            x ^= 0x11d;
+        }
+        i += 1;
+    }
+    table
+};
+
+const LOG: [u8; 256] = {
+    let mut table = [0u8; 256];
+    let mut i = 0;
+    while i < 255 {
+        table[EXP[i] as usize] = i as u8;
+        i += 1;
+    }
+    table
+};
+
+fn exp(power: usize) -> u8 {
+    EXP[power % 255]
+}
+
+fn mul(a: u8, b: u8) -> u8 {
+    if a == 0 || b == 0 {
+        0
+    } else {
+        exp(LOG[a as usize] as usize + LOG[b as usize] as usize)
+    }
+}
+
+fn div(a: u8, b: u8) -> u8 {
+    if a == 0 {
+        0
+    } else {
+        exp(LOG[a as usize] as usize + 255 - LOG[b as usize] as usize)
+    }
+}
+
+/// Evaluates a polynomial stored lowest degree first.
+fn eval(poly: &[u8], x: u8) -> u8 {
+    poly.iter().rev().fold(0, |acc, &c| mul(acc, x) ^ c)
+}
+
+/// Syndromes `r(2^j)` of a block whose first codeword is the highest-degree coefficient.
+fn syndromes(block: &[u8], ecc_len: usize) -> Vec<u8> {
+    (0..ecc_len)
+        .map(|j| block.iter().fold(0, |acc, &c| mul(acc, exp(j)) ^ c))
+        .collect()
+}
+
+/// Corrects a block of data codewords followed by `ecc_len` check codewords in place.
+/// Returns the number of codewords corrected, or `None` when there are too many errors.
+pub fn correct(block: &mut [u8], ecc_len: usize) -> Option<usize> {
+    let n = block.len();
+    let synd = syndromes(block, ecc_len);
+    if synd.iter().all(|&s| s == 0) {
+        return Some(0);
+    }
+
+    // Berlekamp-Massey: the error locator, lowest degree first
+    let mut locator = vec![1u8];
+    let mut previous = vec![1u8];
+    let mut errors = 0;
+    let mut shift = 1;
+    let mut last_discrepancy = 1u8;
+    for k in 0..ecc_len {
+        let discrepancy = (1..=errors).fold(synd[k], |d, i| {
+            d ^ mul(locator.get(i).copied().unwrap_or(0), synd[k - i])
+        });
+        if discrepancy == 0 {
+            shift += 1;
+            continue;
+        }
+        let coef = div(discrepancy, last_discrepancy);
+        let before = locator.clone();
+        if locator.len() < previous.len() + shift {
+            locator.resize(previous.len() + shift, 0);
+        }
+        for (i, &p) in previous.iter().enumerate() {
+            locator[i + shift] ^= mul(coef, p);
+        }
+        if 2 * errors <= k {
+            errors = k + 1 - errors;
+            previous = before;
+            last_discrepancy = discrepancy;
+            shift = 1;
+        } else {
+            shift += 1;
+        }
+    }
+    while locator.last() == Some(&0) {
+        locator.pop();
+    }
+    if locator.len() != errors + 1 || 2 * errors > ecc_len {
+        return None;
+    }
+
+    // Chien search: codeword i sits at power n-1-i
+    let positions: Vec<usize> = (0..n)
+        .filter(|&i| eval(&locator, exp(255 - (n - 1 - i) % 255)) == 0)
+        .collect();
+    if positions.len() != errors {
+        return None;
+    }
+
+    // Forney, for generator roots starting at 2^0: e = X * omega(1/X) / locator'(1/X)
+    let mut omega = vec![0u8; ecc_len];
+    for (i, &s) in synd.iter().enumerate() {
+        for (j, &c) in locator.iter().enumerate().take(ecc_len - i) {
+            omega[i + j] ^= mul(s, c);
+        }
+    }
+    let derivative: Vec<u8> = locator
+        .iter()
+        .enumerate()
+        .skip(1)
+        .map(|(i, &c)| if i % 2 == 1 { c } else { 0 })
+        .collect();
+    for &i in &positions {
+        let x = exp(n - 1 - i);
+        let x_inv = div(1, x);
+        let denominator = eval(&derivative, x_inv);
+        if denominator == 0 {
+            return None;
+        }
+        block[i] ^= mul(x, div(eval(&omega, x_inv), denominator));
+    }
+
+    syndromes(block, ecc_len)
+        .iter()
+        .all(|&s| s == 0)
+        .then_some(errors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Check codewords for `data`, as an encoder computes them
+    fn encode(data: &[u8], ecc_len: usize) -> Vec<u8> {
+        let mut generator = vec![1u8];
+        for i in 0..ecc_len {
+            let mut next = vec![0u8; generator.len() + 1];
+            for (j, &g) in generator.iter().enumerate() {
+                next[j] ^= g;
+                next[j + 1] ^= mul(g, exp(i));
+            }
+            generator = next;
+        }
+        let mut remainder = data.to_vec();
+        remainder.resize(data.len() + ecc_len, 0);
+        for i in 0..data.len() {
+            let factor = remainder[i];
+            for (j, &g) in generator.iter().enumerate() {
+                remainder[i + j] ^= mul(g, factor);
+            }
+        }
+        [data, &remainder[data.len()..]].concat()
+    }
+
+    #[test]
+    fn corrects_up_to_half_the_check_codewords() {
+        // The 1-Q HELLO WORLD block from the standard
+        let data = [32, 91, 11, 120, 209, 114, 220, 77, 67, 64, 236, 17, 236];
+        let block = encode(&data, 13);
+        assert_eq!(
+            block[13..],
+            [168, 72, 22, 82, 217, 54, 156, 0, 46, 15, 180, 122, 16]
+        );
+
+        for errors in 0..=6 {
+            let mut damaged = block.clone();
+            for e in 0..errors {
+                damaged[(e * 5 + 3) % block.len()] ^= 0x5a + e as u8;
+            }
+            assert_eq!(correct(&mut damaged, 13), Some(errors));
+            assert_eq!(damaged, block);
+        }
+
+        let mut hopeless = block.clone();
+        for byte in hopeless.iter_mut().take(10) {
+            *byte ^= 0xff;
+        }
+        assert_ne!(correct(&mut hopeless, 13).map(|_| &hopeless), Some(&block));
+    }
+}
@@ -0,0 +1,88 @@
+//! Sampling the module grid of an upright symbol from a grayscale image.
+//!
+//! Only what a PDF producer emits is handled: one symbol per image, axis-aligned, with a
+//! light quiet zone. Photographed or rotated codes are out of scope.
+
+use alloc::vec::Vec;
+
+use crate::QrError;
+
+/// Samples the symbol in `luminance` (`width` x `height`, top row first) and returns its
+/// modules, row by row, with the symbol size.
+pub fn sample(
+    luminance: &[u8],
+    width: usize,
+    height: usize,
+) -> Result<(Vec<bool>, usize), QrError> {
+    if width == 0 || height == 0 || luminance.len() < width * height {
+        return Err(QrError::Sampling);
+    }
+    let pixels = &luminance[..width * height];
+    let (min, max) = pixels
+        .iter()
+        .fold((u8::MAX, 0), |(lo, hi), &p| (lo.min(p), hi.max(p)));
+    if max - min < 64 {
+        return Err(QrError::Sampling);
+    }
+    let threshold = (min as u16 + max as u16) / 2;
+    let dark = |x: usize, y: usize| (pixels[y * width + x] as u16) < threshold;
+
+    let (mut left, mut top, mut right, mut bottom) = (width, height, 0, 0);
+    for y in 0..height {
+        for x in 0..width {
+            if dark(x, y) {
+                left = left.min(x);
+                right = right.max(x);
+                top = top.min(y);
+                bottom = bottom.max(y);
+            }
+        }
+    }
+    // The top-left finder sits in the corner of the bounding box; its outer ring is seven
+    // modules wide in both directions.
+    if !dark(left, top) {
+        return Err(QrError::Sampling);
+    }
+    let run_x = (left..=right).take_while(|&x| dark(x, top)).count();
+    let run_y = (top..=bottom).take_while(|&y| dark(left, y)).count();
+    let module = (run_x + run_y) as f32 / 14.0;
+    let (box_width, box_height) = ((right - left + 1) as f32, (bottom - top + 1) as f32);
+
+    let version = ((box_width / module - 17.0) / 4.0 + 0.5) as usize;
+    if !(1..=40).contains(&version) {
+        return Err(QrError::Sampling);
+    }
+    let size = 17 + 4 * version;
+    if (box_height / module - size as f32).abs() > size as f32 * 0.1 {
+        return Err(QrError::Sampling);
+    }
+
+    let mut modules = Vec::with_capacity(size * size);
+    for row in 0..size {
+        let y = top + ((row as f32 + 0.5) * box_height / size as f32) as usize;
+        for col in 0..size {
+            let x = left + ((col as f32 + 0.5) * box_width / size as f32) as usize;
+            modules.push(dark(x.min(right), y.min(bottom)));
+        }
+    }
+    if !has_finders(&modules, size) {
+        return Err(QrError::Sampling);
+    }
+    Ok((modules, size))
+}
+
+/// Whether the three finder patterns are where they should be, allowing a few bad modules.
+fn has_finders(modules: &[bool], size: usize) -> bool {
+    let mut wrong = 0;
+    for (x0, y0) in [(0, 0), (size - 7, 0), (0, size - 7)] {
+        for dy in 0..7 {
+            for dx in 0..7 {
+                let ring = (dx as i32 - 3).abs().max((dy as i32 - 3).abs());
+                if modules[(y0 + dy) * size + x0 + dx] != (ring != 2) {
+                    wrong += 1;
+                }
+            }
+        }
+    }
+    wrong <= 15
+}
@@ -0,0 +1,176 @@
+//! Secure QR extraction and verification for zkPDF.
+//!
+//! Finds QR codes in the images of a PDF, decodes them, and verifies UIDAI secure QR payloads
+//! (Aadhaar letters and e-Aadhaar PDFs issued through DigiLocker): the RSA signature over the
+//! payload, and that the identifying fields also appear in the document text.
+//!
+//! ```no_run
+//! let pdf = std::fs::read("e-aadhaar.pdf").unwrap();
+//! let uidai_key = std::fs::read("uidai_auth_sign_prod.cer").unwrap();
+//! let result = zkpdf_secure_qr::verify_pdf_secure_qr(&pdf, &uidai_key).unwrap();
+//! assert!(result.is_verified());
+//! ```
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+mod decode;
+mod gf;
+mod grid;
+mod uidai;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use extractor::types::PdfError;
+
+pub use decode::Decoded;
+pub use uidai::{FieldCheck, SecureQr};
+
+/// Why an image could not be read as a QR code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QrError {
+    /// No upright symbol with finder patterns was found.
+    Sampling,
+    /// The format information is unreadable.
+    Format,
+    /// A block has more errors than its check codewords can correct.
+    TooManyErrors,
+    /// The data ends inside a segment.
+    Truncated,
+    /// An unknown mode or an out-of-range value.
+    Segment,
+}
+
+impl fmt::Display for QrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QrError::Sampling => write!(f, "No QR symbol found"),
+            QrError::Format => write!(f, "Unreadable QR format information"),
+            QrError::TooManyErrors => write!(f, "Too many errors to correct"),
+            QrError::Truncated => write!(f, "QR data is truncated"),
+            QrError::Segment => write!(f, "Invalid QR data segment"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for QrError {}
+
+#[derive(Debug)]
+pub enum SecureQrError {
+    Pdf(PdfError),
+    /// No QR code in the document holds a secure QR payload.
+    NotFound,
+    /// The QR text is not a decimal number.
+    NotNumeric,
+    /// The payload is not a valid gzip stream.
+    Decompression,
+    /// The decompressed payload lacks fields, hashes or the signature.
+    Malformed(&'static str),
+    /// The key is neither a PKCS#1 or SubjectPublicKeyInfo RSA key nor a certificate.
+    InvalidPublicKey,
+}
+
+impl fmt::Display for SecureQrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SecureQrError::Pdf(e) => write!(f, "{}", e),
+            SecureQrError::NotFound => write!(f, "No secure QR code found"),
+            SecureQrError::NotNumeric => write!(f, "Secure QR text is not numeric"),
+            SecureQrError::Decompression => write!(f, "Secure QR payload did not decompress"),
+            SecureQrError::Malformed(msg) => write!(f, "Malformed secure QR payload: {}", msg),
+            SecureQrError::InvalidPublicKey => write!(f, "Invalid UIDAI public key"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SecureQrError {}
+
+impl From<PdfError> for SecureQrError {
+    fn from(e: PdfError) -> Self {
+        SecureQrError::Pdf(e)
+    }
+}
+
+/// A QR code found in a document image.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QrCode {
+    /// Zero-based page index.
+    pub page: usize,
+    /// Resource name of the image, e.g. `Im1`, or `Fm1/Im1` inside a form XObject.
+    pub image: String,
+    pub version: u8,
+    pub data: Vec<u8>,
+}
+
+/// Decodes the QR code in a grayscale image (`width` x `height`, top row first).
+pub fn decode_qr(luminance: &[u8], width: usize, height: usize) -> Result<Decoded, QrError> {
+    let (modules, size) = grid::sample(luminance, width, height)?;
+    decode::decode_modules(&modules, size)
+}
+
+/// Every image in the document that decodes as a QR code. Images that are not QR codes,
+/// such as photos, are skipped.
+pub fn find_qr_codes(pdf_bytes: &[u8]) -> Result<Vec<QrCode>, SecureQrError> {
+    let images = extractor::extract_images(pdf_bytes)?;
+    Ok(images
+        .into_iter()
+        .filter_map(|image| {
+            let luminance = image.luminance()?;
+            let decoded = decode_qr(&luminance, image.width, image.height).ok()?;
+            Some(QrCode {
+                page: image.page,
+                image: image.name,
+                version: decoded.version,
+                data: decoded.data,
+            })
+        })
+        .collect())
+}
+
+/// The outcome of `verify_pdf_secure_qr`.
+#[derive(Debug, Clone)]
+pub struct SecureQrVerification {
+    /// Zero-based page index of the QR image.
+    pub page: usize,
+    pub image: String,
+    pub secure_qr: SecureQr,
+    pub signature_valid: bool,
+    /// The QR fields looked up in the document text.
+    pub fields: Vec<FieldCheck>,
+}
+
+impl SecureQrVerification {
+    /// The signature is valid and every checked field appears in the text.
+    pub fn is_verified(&self) -> bool {
+        self.signature_valid && self.fields.iter().all(|f| f.found)
+    }
+}
+
+/// Verifies the first secure QR code in the document against the UIDAI public key (PKCS#1,
+/// SubjectPublicKeyInfo or certificate, DER) and cross-checks it with the document text.
+pub fn verify_pdf_secure_qr(
+    pdf_bytes: &[u8],
+    uidai_public_key: &[u8],
+) -> Result<SecureQrVerification, SecureQrError> {
+    let (code, secure_qr) = find_qr_codes(pdf_bytes)?
+        .into_iter()
+        .find_map(|code| {
+            let secure_qr = SecureQr::parse(core::str::from_utf8(&code.data).ok()?).ok()?;
+            Some((code, secure_qr))
+        })
+        .ok_or(SecureQrError::NotFound)?;
+    let signature_valid = secure_qr.verify(uidai_public_key)?;
+    let pages = extractor::extract_text(pdf_bytes.to_vec())?;
+    Ok(SecureQrVerification {
+        page: code.page,
+        image: code.image,
+        fields: secure_qr.cross_check(&pages),
+        secure_qr,
+        signature_valid,
+    })
+}
@@ -0,0 +1,379 @@
+//! UIDAI secure QR payloads, as printed on Aadhaar letters and e-Aadhaar PDFs downloaded
+//! from DigiLocker.
+//!
+//! The QR text is a decimal number. Its big-endian bytes are a gzip stream holding
+//! ISO-8859-1 fields separated by `0xFF`, then a JPEG 2000 photo, optional SHA-256 hashes of
+//! the registered email and mobile, and a 256-byte RSA-SHA256 signature over everything
+//! before it.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use miniz_oxide::inflate::decompress_to_vec_with_limit;
+use rsa::pkcs1::DecodeRsaPublicKey;
+use rsa::pkcs8::DecodePublicKey;
+use rsa::{Pkcs1v15Sign, RsaPublicKey};
+use sha2::{Digest, Sha256};
+
+use crate::SecureQrError;
+
+const SIGNATURE_LEN: usize = 256;
+const HASH_LEN: usize = 32;
+/// Decompressed payloads are a few kilobytes; anything far larger is not a secure QR.
+const MAX_PAYLOAD: usize = 1 << 20;
+/// Text fields after the email/mobile indicator, in order.
+const FIELDS: usize = 15;
+
+/// The fields of a secure QR payload.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SecureQr {
+    /// `Some("V2")` and later; the original format has no version field.
+    pub version: Option<String>,
+    /// Last four digits of the Aadhaar number followed by the generation timestamp.
+    pub reference_id: String,
+    pub name: String,
+    /// `DD-MM-YYYY`.
+    pub date_of_birth: String,
+    /// `M`, `F` or `T`.
+    pub gender: String,
+    pub care_of: String,
+    pub district: String,
+    pub landmark: String,
+    pub house: String,
+    pub location: String,
+    pub pincode: String,
+    pub post_office: String,
+    pub state: String,
+    pub street: String,
+    pub sub_district: String,
+    pub vtc: String,
+    /// Last four digits of the registered mobile number, from V2 on.
+    pub mobile_last4: Option<String>,
+    /// The embedded photo, a JPEG 2000 image.
+    pub photo: Vec<u8>,
+    /// SHA-256 based hash of the registered email, when one is registered.
+    pub email_hash: Option<[u8; HASH_LEN]>,
+    /// SHA-256 based hash of the registered mobile number, when one is registered.
+    pub mobile_hash: Option<[u8; HASH_LEN]>,
+    signed_data: Vec<u8>,
+    signature: Vec<u8>,
+}
+
+/// Whether one field of the QR also appears in the document text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldCheck {
+    pub field: &'static str,
+    pub value: String,
+    pub found: bool,
+}
+
+impl SecureQr {
+    /// Parses the decimal text of a secure QR code.
+    pub fn parse(text: &str) -> Result<Self, SecureQrError> {
+        Self::from_payload(&gunzip(&decimal_to_bytes(text.trim())?)?)
+    }
+
+    /// Parses a decompressed payload.
+    pub fn from_payload(payload: &[u8]) -> Result<Self, SecureQrError> {
+        if payload.len() < SIGNATURE_LEN {
+            return Err(SecureQrError::Malformed(
+                "payload shorter than the signature",
+            ));
+        }
+        let (signed_data, signature) = payload.split_at(payload.len() - SIGNATURE_LEN);
+
+        // Only the text fields are delimited; the photo may contain 0xFF itself.
+        let mut rest = signed_data;
+        let mut next_field = || -> Result<String, SecureQrError> {
+            let end = rest
+                .iter()
+                .position(|&b| b == 0xff)
+                .ok_or(SecureQrError::Malformed("missing text field"))?;
+            let field = rest[..end].iter().map(|&b| b as char).collect();
+            rest = &rest[end + 1..];
+            Ok(field)
+        };
+
+        let mut first = next_field()?;
+        let version = if first.starts_with('V') {
+            Some(core::mem::replace(&mut first, next_field()?))
+        } else {
+            None
+        };
+        let indicator: u8 = match first.as_str() {
+            "0" => 0,
+            "1" => 1,
+            "2" => 2,
+            "3" => 3,
+            _ => return Err(SecureQrError::Malformed("bad email/mobile indicator")),
+        };
+        let mut fields = Vec::with_capacity(FIELDS);
+        for _ in 0..FIELDS {
+            fields.push(next_field()?);
+        }
+        let mobile_last4 = version.is_some().then(&mut next_field).transpose()?;
+
+        // Hashes trail the photo: email first, then mobile
+        // 1: email only, 2: mobile only, 3: both
+        let has_email = indicator & 1 != 0;
+        let has_mobile = indicator & 2 != 0;
+        let hashes = HASH_LEN * (has_email as usize + has_mobile as usize);
+        if rest.len() < hashes {
+            return Err(SecureQrError::Malformed("missing email/mobile hash"));
+        }
+        let (photo, mut hash_bytes) = rest.split_at(rest.len() - hashes);
+        let mut take_hash = |present: bool| {
+            present.then(|| {
+                let (hash, tail) = hash_bytes.split_at(HASH_LEN);
+                hash_bytes = tail;
+                hash.try_into().expect("32 bytes")
+            })
+        };
+        let email_hash = take_hash(has_email);
+        let mobile_hash = take_hash(has_mobile);
+
+        let mut fields = fields.into_iter();
+        let mut field = || fields.next().expect("FIELDS text fields");
+        Ok(Self {
+            version,
+            reference_id: field(),
+            name: field(),
+            date_of_birth: field(),
+            gender: field(),
+            care_of: field(),
+            district: field(),
+            landmark: field(),
+            house: field(),
+            location: field(),
+            pincode: field(),
+            post_office: field(),
+            state: field(),
+            street: field(),
+            sub_district: field(),
+            vtc: field(),
+            mobile_last4,
+            photo: photo.to_vec(),
+            email_hash,
+            mobile_hash,
+            signed_data: signed_data.to_vec(),
+            signature: signature.to_vec(),
+        })
+    }
+
+    /// Last four digits of the Aadhaar number.
+    pub fn aadhaar_last4(&self) -> &str {
+        self.reference_id.get(..4).unwrap_or("")
+    }
+
+    /// Checks the signature against the UIDAI public key, given as a PKCS#1 or
+    /// SubjectPublicKeyInfo DER key or as the DER certificate UIDAI publishes.
+    pub fn verify(&self, public_key_der: &[u8]) -> Result<bool, SecureQrError> {
+        let key = public_key(public_key_der)?;
+        Ok(key
+            .verify(
+                Pkcs1v15Sign::new::<Sha256>(),
+                &Sha256::digest(&self.signed_data),
+                &self.signature,
+            )
+            .is_ok())
+    }
+
+    /// Looks for the identifying fields in extracted page text. Matching ignores case and
+    /// runs of whitespace, and only counts whole words.
+    pub fn cross_check(&self, pages: &[String]) -> Vec<FieldCheck> {
+        let text = normalize(&pages.join(" "));
+        let gender = match self.gender.as_str() {
+            "M" => "Male",
+            "F" => "Female",
+            "T" => "Transgender",
+            other => other,
+        };
+        let slashed_dob = self.date_of_birth.replace('-', "/");
+        let candidates: [(&'static str, &str, &[&str]); 5] = [
+            ("aadhaar_last4", self.aadhaar_last4(), &[]),
+            ("name", &self.name, &[]),
+            ("date_of_birth", &self.date_of_birth, &[&slashed_dob]),
+            ("gender", gender, &[]),
+            ("pincode", &self.pincode, &[]),
+        ];
+        candidates
+            .iter()
+            .filter(|(_, value, _)| !value.trim().is_empty())
+            .map(|&(field, value, alternatives)| FieldCheck {
+                field,
+                value: value.into(),
+                found: core::iter::once(value)
+                    .chain(alternatives.iter().copied())
+                    .any(|v| contains_word(&text, &normalize(v))),
+            })
+            .collect()
+    }
+}
+
+fn normalize(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for word in text.split_whitespace() {
+        if !out.is_empty() {
+            out.push(' ');
+        }
+        out.extend(word.chars().flat_map(char::to_lowercase));
+    }
+    out
+}
+
+fn contains_word(haystack: &str, needle: &str) -> bool {
+    if needle.is_empty() {
+        return false;
+    }
+    haystack.match_indices(needle).any(|(at, _)| {
+        let before = haystack[..at].chars().next_back();
+        let after = haystack[at + needle.len()..].chars().next();
+        !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+    })
+}
+
+/// Big-endian bytes of a decimal number.
+fn decimal_to_bytes(text: &str) -> Result<Vec<u8>, SecureQrError> {
+    if text.is_empty() || !text.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(SecureQrError::NotNumeric);
+    }
+    // Little-endian accumulator, reversed at the end
+    let mut bytes: Vec<u8> = Vec::with_capacity(text.len() * 5 / 12 + 1);
+    for digit in text.bytes() {
+        let mut carry = (digit - b'0') as u32;
+        for byte in bytes.iter_mut() {
+            let value = *byte as u32 * 10 + carry;
+            *byte = value as u8;
+            carry = value >> 8;
+        }
+        while carry > 0 {
+            bytes.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+    bytes.reverse();
+    Ok(bytes)
+}
+
+fn gunzip(data: &[u8]) -> Result<Vec<u8>, SecureQrError> {
+    const FEXTRA: u8 = 4;
+    const FNAME: u8 = 8;
+    const FCOMMENT: u8 = 16;
+    const FHCRC: u8 = 2;
+
+    if data.len() < 18 || data[..3] != [0x1f, 0x8b, 8] {
+        return Err(SecureQrError::Decompression);
+    }
+    let flags = data[3];
+    let mut at = 10;
+    if flags & FEXTRA != 0 {
+        let len = *data.get(at).ok_or(SecureQrError::Decompression)? as usize
+            | (*data.get(at + 1).ok_or(SecureQrError::Decompression)? as usize) << 8;
+        at += 2 + len;
+    }
+    for flag in [FNAME, FCOMMENT] {
+        if flags & flag != 0 {
+            let end = data
+                .get(at..)
+                .and_then(|rest| rest.iter().position(|&b| b == 0))
+                .ok_or(SecureQrError::Decompression)?;
+            at += end + 1;
+        }
+    }
+    if flags & FHCRC != 0 {
+        at += 2;
+    }
+    if at + 8 > data.len() {
+        return Err(SecureQrError::Decompression);
+    }
+
+    let out = decompress_to_vec_with_limit(&data[at..data.len() - 8], MAX_PAYLOAD)
+        .map_err(|_| SecureQrError::Decompression)?;
+    let trailer = &data[data.len() - 8..];
+    let crc = u32::from_le_bytes(trailer[..4].try_into().expect("4 bytes"));
+    let size = u32::from_le_bytes(trailer[4..].try_into().expect("4 bytes"));
+    if crc != crc32(&out) || size != out.len() as u32 {
+        return Err(SecureQrError::Decompression);
+    }
+    Ok(out)
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0u32, |crc, &byte| {
+        (0..8).fold(crc ^ byte as u32, |c, _| {
+            (c >> 1) ^ (0xedb8_8320 & (c & 1).wrapping_neg())
+        })
+    })
+}
+
+fn public_key(der: &[u8]) -> Result<RsaPublicKey, SecureQrError> {
+    RsaPublicKey::from_pkcs1_der(der)
+        .or_else(|_| RsaPublicKey::from_public_key_der(der))
+        .ok()
+        .or_else(|| RsaPublicKey::from_public_key_der(certificate_key(der)?).ok())
+        .ok_or(SecureQrError::InvalidPublicKey)
+}
+
+/// The SubjectPublicKeyInfo of an X.509 certificate.
+fn certificate_key(der: &[u8]) -> Option<&[u8]> {
+    let (_, certificate, _) = der_element(der)?;
+    let (_, mut tbs, _) = der_element(certificate)?;
+    // Optional [0] version, then serial, signature algorithm, issuer, validity and subject
+    if tbs.first() == Some(&0xa0) {
+        tbs = der_element(tbs)?.2;
+    }
+    for _ in 0..5 {
+        tbs = der_element(tbs)?.2;
+    }
+    Some(der_element(tbs)?.0)
+}
+
+/// Splits off the first DER element: the whole element, its contents and the rest.
+fn der_element(data: &[u8]) -> Option<(&[u8], &[u8], &[u8])> {
+    let first = *data.get(1)? as usize;
+    let (header, len) = if first < 0x80 {
+        (2, first)
+    } else {
+        let count = first & 0x7f;
+        if count == 0 || count > 4 {
+            return None;
+        }
+        let len = data
+            .get(2..2 + count)?
+            .iter()
+            .fold(0usize, |acc, &b| acc << 8 | b as usize);
+        (2 + count, len)
+    };
+    let end = header.checked_add(len)?;
+    (end <= data.len()).then(|| (&data[..end], &data[header..end], &data[end..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decimal_conversion() {
+        assert_eq!(decimal_to_bytes("0").unwrap(), Vec::<u8>::new());
+        assert_eq!(decimal_to_bytes("255").unwrap(), [0xff]);
+        assert_eq!(decimal_to_bytes("65536").unwrap(), [1, 0, 0]);
+        assert!(matches!(
+            decimal_to_bytes("12a"),
+            Err(SecureQrError::NotNumeric)
+        ));
+    }
+
+    #[test]
+    fn crc32_matches_the_check_value() {
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+    }
+
+    #[test]
+    fn words_match_on_boundaries() {
+        let text = normalize("Gender:  FEMALE\nDOB: 01/02/1990");
+        assert!(contains_word(&text, "female"));
+        assert!(!contains_word(&text, "male"));
+        assert!(contains_word(&text, "01/02/1990"));
+        assert!(!contains_word(&text, "1/02/1990"));
+    }
+}
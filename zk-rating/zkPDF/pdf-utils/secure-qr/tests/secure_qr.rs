@@ -0,0 +1,276 @@
+use rsa::pkcs1::{DecodeRsaPrivateKey, EncodeRsaPublicKey};
+use rsa::{Pkcs1v15Sign, RsaPrivateKey};
+use sha2::{Digest, Sha256};
+use zkpdf_secure_qr::{decode_qr, find_qr_codes, verify_pdf_secure_qr, SecureQr, SecureQrError};
+use zkpdf_writer::qr::{encode, EcLevel, Segment};
+use zkpdf_writer::{Document, Page};
+
+/// The writer's bundled test key stands in for the UIDAI signing key.
+static KEY: &[u8] = include_bytes!("../../writer/keys/test-rsa-2048.der");
+
+fn key() -> RsaPrivateKey {
+    RsaPrivateKey::from_pkcs1_der(KEY).unwrap()
+}
+
+fn public_key_der() -> Vec<u8> {
+    key()
+        .to_public_key()
+        .to_pkcs1_der()
+        .unwrap()
+        .as_bytes()
+        .to_vec()
+}
+
+/// Scales a QR image up by `scale` pixels per module.
+fn luminance(code: &zkpdf_writer::qr::QrCode, scale: usize) -> (Vec<u8>, usize) {
+    let image = code.to_image(0.0, 0.0, 1.0);
+    let side = image.width * scale;
+    let mut out = vec![0u8; side * side];
+    for y in 0..side {
+        for x in 0..side {
+            out[y * side + x] = image.samples[(y / scale) * image.width + x / scale];
+        }
+    }
+    (out, side)
+}
+
+#[test]
+fn decodes_every_level_and_mask() {
+    let levels = [EcLevel::L, EcLevel::M, EcLevel::Q, EcLevel::H];
+    for (i, text) in ["HELLO WORLD", "zkPDF secure QR", "0123456789012345678901"]
+        .iter()
+        .enumerate()
+    {
+        for level in levels {
+            for mask in 0..8 {
+                let segment = match i {
+                    0 => Segment::Alphanumeric(text),
+                    1 => Segment::Bytes(text.as_bytes()),
+                    _ => Segment::Numeric(text),
+                };
+                let code = encode(&[segment], level, Some(mask)).unwrap();
+                let (pixels, side) = luminance(&code, 1 + mask as usize % 3);
+                let decoded = decode_qr(&pixels, side, side).unwrap();
+                assert_eq!(decoded.data, text.as_bytes(), "{:?} mask {}", level, mask);
+                assert_eq!(decoded.corrected, 0);
+            }
+        }
+    }
+}
+
+#[test]
+fn decodes_large_versions() {
+    for len in [100, 500, 1200, 2900] {
+        let data: Vec<u8> = (0..len).map(|i| (i * 7 % 256) as u8).collect();
+        let code = encode(&[Segment::Bytes(&data)], EcLevel::L, None).unwrap();
+        let (pixels, side) = luminance(&code, 2);
+        let decoded = decode_qr(&pixels, side, side).unwrap();
+        assert_eq!(decoded.version, code.version);
+        assert_eq!(decoded.data, data);
+    }
+}
+
+#[test]
+fn corrects_damaged_modules() {
+    let text = "A logo over the middle of a level H code";
+    let code = encode(&[Segment::Bytes(text.as_bytes())], EcLevel::H, None).unwrap();
+    let (mut pixels, side) = luminance(&code, 3);
+    // Blank a square in the centre, as a logo would
+    let (from, to) = (side * 9 / 20, side * 11 / 20);
+    for y in from..to {
+        for x in from..to {
+            pixels[y * side + x] = 255;
+        }
+    }
+    let decoded = decode_qr(&pixels, side, side).unwrap();
+    assert_eq!(decoded.data, text.as_bytes());
+    assert!(decoded.corrected > 0);
+}
+
+#[test]
+fn rejects_images_without_a_code() {
+    let gradient: Vec<u8> = (0..64 * 64).map(|i| (i % 64 * 4) as u8).collect();
+    assert!(decode_qr(&gradient, 64, 64).is_err());
+    assert!(decode_qr(&[255; 100], 10, 10).is_err());
+}
+
+/// Signed, gzipped payload in the UIDAI layout (V2, email and mobile hashes present).
+fn payload(name: &str) -> Vec<u8> {
+    let fields = [
+        "V2",
+        "3",
+        "123420240101120000123",
+        name,
+        "01-02-1990",
+        "F",
+        "D/O Ravi Kumar",
+        "Bengaluru",
+        "Near Park",
+        "12",
+        "Indiranagar",
+        "560038",
+        "Indiranagar",
+        "Karnataka",
+        "Main Road",
+        "Bengaluru North",
+        "Bengaluru",
+        "9876",
+    ];
+    let mut data = Vec::new();
+    for field in fields {
+        data.extend_from_slice(field.as_bytes());
+        data.push(0xff);
+    }
+    // A stand-in photo containing the delimiter byte
+    data.extend_from_slice(&[0x00, 0x00, 0x00, 0x0c, 0x6a, 0x50, 0xff, 0x20, 0xff]);
+    data.extend_from_slice(&Sha256::digest(b"email"));
+    data.extend_from_slice(&Sha256::digest(b"mobile"));
+    let signature = key()
+        .sign(Pkcs1v15Sign::new::<Sha256>(), &Sha256::digest(&data))
+        .unwrap();
+    data.extend_from_slice(&signature);
+    data
+}
+
+fn gzip(data: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x1f, 0x8b, 8, 0, 0, 0, 0, 0, 0, 0xff];
+    out.extend(miniz_oxide::deflate::compress_to_vec(data, 9));
+    let crc = !data.iter().fold(!0u32, |crc, &byte| {
+        (0..8).fold(crc ^ byte as u32, |c, _| {
+            (c >> 1) ^ (0xedb8_8320 & (c & 1).wrapping_neg())
+        })
+    });
+    out.extend(crc.to_le_bytes());
+    out.extend((data.len() as u32).to_le_bytes());
+    out
+}
+
+fn to_decimal(bytes: &[u8]) -> String {
+    let mut digits = Vec::new();
+    let mut number = bytes.to_vec();
+    while number.iter().any(|&b| b != 0) {
+        let mut remainder = 0u32;
+        for byte in number.iter_mut() {
+            let value = remainder << 8 | *byte as u32;
+            *byte = (value / 10) as u8;
+            remainder = value % 10;
+        }
+        digits.push(b'0' + remainder as u8);
+    }
+    digits.reverse();
+    String::from_utf8(digits).unwrap()
+}
+
+fn secure_qr_text(name: &str) -> String {
+    to_decimal(&gzip(&payload(name)))
+}
+
+#[test]
+fn parses_and_verifies_payloads() {
+    let qr = SecureQr::parse(&secure_qr_text("Asha Kumari")).unwrap();
+    assert_eq!(qr.version.as_deref(), Some("V2"));
+    assert_eq!(qr.aadhaar_last4(), "1234");
+    assert_eq!(qr.name, "Asha Kumari");
+    assert_eq!(qr.date_of_birth, "01-02-1990");
+    assert_eq!(qr.pincode, "560038");
+    assert_eq!(qr.mobile_last4.as_deref(), Some("9876"));
+    assert_eq!(
+        qr.photo,
+        [0x00, 0x00, 0x00, 0x0c, 0x6a, 0x50, 0xff, 0x20, 0xff]
+    );
+    assert_eq!(qr.email_hash.unwrap()[..], Sha256::digest(b"email")[..]);
+    assert_eq!(qr.mobile_hash.unwrap()[..], Sha256::digest(b"mobile")[..]);
+    assert!(qr.verify(&public_key_der()).unwrap());
+
+    // A changed field no longer matches the signature
+    let mut data = payload("Asha Kumari");
+    let at = data.windows(4).position(|w| w == b"Asha").unwrap();
+    data[at] = b'U';
+    let tampered = SecureQr::parse(&to_decimal(&gzip(&data))).unwrap();
+    assert!(!tampered.verify(&public_key_der()).unwrap());
+
+    assert!(matches!(
+        SecureQr::parse("1234"),
+        Err(SecureQrError::Decompression)
+    ));
+    assert!(matches!(
+        qr.verify(b"not a key"),
+        Err(SecureQrError::InvalidPublicKey)
+    ));
+}
+
+fn aadhaar_pdf(qr_name: &str, printed_name: &str) -> Vec<u8> {
+    let text = secure_qr_text(qr_name);
+    let code = encode(&[Segment::Numeric(&text)], EcLevel::M, None).unwrap();
+    let mut page = Page::from_lines(&[
+        "Unique Identification Authority of India",
+        printed_name,
+        "DOB: 01/02/1990",
+        "FEMALE",
+        "Bengaluru, Karnataka - 560038",
+        "XXXX XXXX 1234",
+    ]);
+    page.images.push(code.to_image(360.0, 72.0, 1.5));
+    // A photo-like image that is not a QR code
+    page.images.push(zkpdf_writer::Image {
+        width: 16,
+        height: 16,
+        samples: (0..256).map(|i| (i * 3 % 256) as u8).collect(),
+        x: 450.0,
+        y: 600.0,
+        display_width: 64.0,
+        display_height: 64.0,
+    });
+    Document {
+        pages: vec![page],
+        compress: true,
+        info: Vec::new(),
+    }
+    .to_bytes()
+    .unwrap()
+}
+
+#[test]
+fn verifies_secure_qr_in_a_pdf() {
+    let pdf = aadhaar_pdf("Asha Kumari", "Asha Kumari");
+    let codes = find_qr_codes(&pdf).unwrap();
+    assert_eq!(codes.len(), 1);
+
+    let result = verify_pdf_secure_qr(&pdf, &public_key_der()).unwrap();
+    assert_eq!(result.page, 0);
+    assert!(result.signature_valid);
+    let fields: Vec<_> = result.fields.iter().map(|f| (f.field, f.found)).collect();
+    assert_eq!(
+        fields,
+        [
+            ("aadhaar_last4", true),
+            ("name", true),
+            ("date_of_birth", true),
+            ("gender", true),
+            ("pincode", true),
+        ]
+    );
+    assert!(result.is_verified());
+}
+
+#[test]
+fn mismatched_text_fails_the_cross_check() {
+    let pdf = aadhaar_pdf("Asha Kumari", "Someone Else");
+    let result = verify_pdf_secure_qr(&pdf, &public_key_der()).unwrap();
+    assert!(result.signature_valid);
+    assert!(!result.is_verified());
+    let name = result.fields.iter().find(|f| f.field == "name").unwrap();
+    assert!(!name.found);
+}
+
+#[test]
+fn documents_without_secure_qr() {
+    let pdf = Document::from_pages(&[&["no images here"]])
+        .to_bytes()
+        .unwrap();
+    assert!(find_qr_codes(&pdf).unwrap().is_empty());
+    assert!(matches!(
+        verify_pdf_secure_qr(&pdf, &public_key_der()),
+        Err(SecureQrError::NotFound)
+    ));
+}
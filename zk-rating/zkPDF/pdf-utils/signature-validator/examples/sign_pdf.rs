@@ -0,0 +1,23 @@
+//! Signs the prepared signature field of a PDF:
+//! `cargo run -p signature-validator --features signing --example sign_pdf -- in.pdf key.der cert.der out.pdf`
+//!
+//! The key is PKCS#1 or PKCS#8 DER and the certificate X.509 DER. Any further arguments are
+//! chain certificates to embed.
+
+use signature_validator::signing::{sign_pdf, SigningKey};
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.len() < 4 {
+        eprintln!("usage: sign_pdf <in.pdf> <key.der> <cert.der> <out.pdf> [chain.der...]");
+        std::process::exit(2);
+    }
+    let read = |path: &String| std::fs::read(path).unwrap_or_else(|e| panic!("{}: {}", path, e));
+
+    let key = SigningKey::new(&read(&args[1]), &read(&args[2]))
+        .expect("invalid key or certificate")
+        .with_chain(args[4..].iter().map(read).collect());
+    let signed = sign_pdf(&read(&args[0]), &key).expect("signing failed");
+    std::fs::write(&args[3], signed).expect("failed to write output");
+    println!("wrote {}", args[3]);
+}
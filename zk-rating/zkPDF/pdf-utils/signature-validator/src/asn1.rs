@@ -0,0 +1,500 @@
+//! DER decoder for the PKCS#7 parser: the decoding half of `simple_asn1` 0.6 (ISC licence),
+//! trimmed to what we use and made alloc-only so the crate builds without std. Blocks have the
+//! same shape as `simple_asn1`'s, except that times are plain `DateTime` fields.
+
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::mem::size_of;
+use core::str::Utf8Error;
+
+pub use num_bigint::{BigInt, BigUint};
+use num_traits::{ToPrimitive, Zero};
+use thiserror::Error;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ASN1Class {
+    Universal,
+    Application,
+    ContextSpecific,
+    Private,
+}
+
+/// A decoded block. The first `usize` of every variant is the offset of the block's tag in the
+/// decoded buffer.
+#[derive(Clone, Debug)]
+pub enum ASN1Block {
+    Boolean(usize, bool),
+    Integer(usize, BigInt),
+    BitString(usize, usize, Vec<u8>),
+    OctetString(usize, Vec<u8>),
+    Null(usize),
+    ObjectIdentifier(usize, OID),
+    UTF8String(usize, String),
+    PrintableString(usize, String),
+    TeletexString(usize, String),
+    IA5String(usize, String),
+    UTCTime(usize, DateTime),
+    GeneralizedTime(usize, DateTime),
+    UniversalString(usize, String),
+    BMPString(usize, String),
+    Sequence(usize, Vec<ASN1Block>),
+    Set(usize, Vec<ASN1Block>),
+    Explicit(ASN1Class, usize, BigUint, Box<ASN1Block>),
+    Unknown(ASN1Class, bool, usize, BigUint, Vec<u8>),
+}
+
+/// A UTCTime or GeneralizedTime, without its fractional seconds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DateTime {
+    year: i32,
+    month: u8,
+    day: u8,
+    hour: u8,
+    minute: u8,
+    second: u8,
+}
+
+impl DateTime {
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+
+    pub fn hour(&self) -> u8 {
+        self.hour
+    }
+
+    pub fn minute(&self) -> u8 {
+        self.minute
+    }
+
+    pub fn second(&self) -> u8 {
+        self.second
+    }
+
+    /// Parses `YYYYMMDDHHMMSS`, rejecting out-of-range fields.
+    fn parse(digits: &str) -> Option<Self> {
+        if digits.len() != 14 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let field = |range: core::ops::Range<usize>| digits[range].parse::<u8>().ok();
+        let time = DateTime {
+            year: digits[0..4].parse().ok()?,
+            month: field(4..6)?,
+            day: field(6..8)?,
+            hour: field(8..10)?,
+            minute: field(10..12)?,
+            second: field(12..14)?,
+        };
+        let leap = time.year % 4 == 0 && (time.year % 100 != 0 || time.year % 400 == 0);
+        let days = match time.month {
+            1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+            4 | 6 | 9 | 11 => 30,
+            2 if leap => 29,
+            2 => 28,
+            _ => return None,
+        };
+        let valid = (1..=days).contains(&time.day)
+            && time.hour < 24
+            && time.minute < 60
+            && time.second < 60;
+        valid.then_some(time)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OID(Vec<BigUint>);
+
+impl OID {
+    pub fn new(x: Vec<BigUint>) -> OID {
+        OID(x)
+    }
+
+    pub fn as_vec<'a, T: TryFrom<&'a BigUint>>(&'a self) -> Result<Vec<T>, ASN1DecodeErr> {
+        self.0
+            .iter()
+            .map(|val| T::try_from(val).map_err(|_| ASN1DecodeErr::Overflow))
+            .collect()
+    }
+}
+
+impl fmt::Display for OID {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, arc) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(".")?;
+            }
+            write!(f, "{}", arc)?;
+        }
+        Ok(())
+    }
+}
+
+macro_rules! oid {
+    ( $( $e: expr ),* ) => {{
+        $crate::asn1::OID::new(alloc::vec![$($crate::asn1::BigUint::from($e as u64)),*])
+    }};
+}
+pub(crate) use oid;
+
+const PRINTABLE_CHARS: &str =
+    "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789'()+,-./:=? ";
+
+#[derive(Clone, Debug, Error, PartialEq)]
+pub enum ASN1DecodeErr {
+    #[error("Encountered an empty buffer decoding ASN1 block.")]
+    EmptyBuffer,
+    #[error("Bad length field in boolean block: {0}")]
+    BadBooleanLength(usize),
+    #[error("Length field too large for object type: {0}")]
+    LengthTooLarge(usize),
+    #[error("UTF8 string failed to properly decode: {0}")]
+    UTF8DecodeFailure(Utf8Error),
+    #[error("Printable string failed to properly decode.")]
+    PrintableStringDecodeFailure,
+    #[error("Invalid date value: {0}")]
+    InvalidDateValue(String),
+    #[error("Invalid length of bit string: {0}")]
+    InvalidBitStringLength(isize),
+    #[error("Incomplete data or invalid ASN1")]
+    Incomplete,
+    #[error("Value overflow")]
+    Overflow,
+}
+
+pub fn from_der(i: &[u8]) -> Result<Vec<ASN1Block>, ASN1DecodeErr> {
+    from_der_(i, 0)
+}
+
+/// Splits `i` into its top-level encoded elements, each with its tag and length, without
+/// decoding them. Used where the exact encoding matters, such as a signed tbsCertificate.
+pub fn split_der(i: &[u8]) -> Result<Vec<&[u8]>, ASN1DecodeErr> {
+    let mut elements = Vec::new();
+    let mut index = 0;
+    while index < i.len() {
+        let start = index;
+        decode_tag(i, &mut index)?;
+        let len = decode_length(i, &mut index)?;
+        let end = index
+            .checked_add(len)
+            .ok_or(ASN1DecodeErr::LengthTooLarge(len))?;
+        if end > i.len() {
+            return Err(ASN1DecodeErr::Incomplete);
+        }
+        elements.push(&i[start..end]);
+        index = end;
+    }
+    Ok(elements)
+}
+
+/// The encoded elements inside the constructed element `i`, as `split_der` returns them.
+pub fn der_children(i: &[u8]) -> Result<Vec<&[u8]>, ASN1DecodeErr> {
+    let mut index = 0;
+    decode_tag(i, &mut index)?;
+    let len = decode_length(i, &mut index)?;
+    if index.checked_add(len) != Some(i.len()) {
+        return Err(ASN1DecodeErr::Incomplete);
+    }
+    split_der(&i[index..])
+}
+
+fn from_der_(i: &[u8], start_offset: usize) -> Result<Vec<ASN1Block>, ASN1DecodeErr> {
+    let mut result: Vec<ASN1Block> = Vec::new();
+    let mut index: usize = 0;
+
+    while index < i.len() {
+        let soff = start_offset + index;
+        let (tag, constructed, class) = decode_tag(i, &mut index)?;
+        let len = decode_length(i, &mut index)?;
+        let checklen = index
+            .checked_add(len)
+            .ok_or(ASN1DecodeErr::LengthTooLarge(len))?;
+        if checklen > i.len() {
+            return Err(ASN1DecodeErr::Incomplete);
+        }
+        let body = &i[index..checklen];
+
+        if class != ASN1Class::Universal {
+            if constructed {
+                // A constructed block holding exactly one block is read as explicitly tagged
+                if let Ok(mut items) = from_der_(body, start_offset + index) {
+                    if items.len() == 1 {
+                        result.push(ASN1Block::Explicit(
+                            class,
+                            soff,
+                            tag,
+                            Box::new(items.remove(0)),
+                        ));
+                        index += len;
+                        continue;
+                    }
+                }
+            }
+            result.push(ASN1Block::Unknown(
+                class,
+                constructed,
+                soff,
+                tag,
+                body.to_vec(),
+            ));
+            index += len;
+            continue;
+        }
+
+        let block = match tag.to_u8() {
+            // BOOLEAN
+            Some(0x01) => {
+                if len != 1 {
+                    return Err(ASN1DecodeErr::BadBooleanLength(len));
+                }
+                ASN1Block::Boolean(soff, body[0] != 0)
+            }
+            // INTEGER
+            Some(0x02) => ASN1Block::Integer(soff, BigInt::from_signed_bytes_be(body)),
+            // BIT STRING
+            Some(0x03) if body.is_empty() => ASN1Block::BitString(soff, 0, Vec::new()),
+            Some(0x03) => {
+                let bits = body[1..].to_vec();
+                let bitcount = bits.len() * 8;
+                let rest = body[0] as usize;
+                if bitcount < rest {
+                    return Err(ASN1DecodeErr::InvalidBitStringLength(
+                        bitcount as isize - rest as isize,
+                    ));
+                }
+                ASN1Block::BitString(soff, bitcount - rest, bits)
+            }
+            // OCTET STRING
+            Some(0x04) => ASN1Block::OctetString(soff, body.to_vec()),
+            // NULL
+            Some(0x05) => ASN1Block::Null(soff),
+            // OBJECT IDENTIFIER
+            Some(0x06) => {
+                let first = *body.first().ok_or(ASN1DecodeErr::Incomplete)?;
+                let (value1, value2) = match first {
+                    0..=39 => (0, first),
+                    40..=79 => (1, first - 40),
+                    _ => (2, first - 80),
+                };
+                let mut arcs = Vec::from([BigUint::from(value1 as u8), BigUint::from(value2)]);
+                let mut bindex = 1;
+                while bindex < body.len() {
+                    arcs.push(decode_base127(body, &mut bindex)?);
+                }
+                ASN1Block::ObjectIdentifier(soff, OID(arcs))
+            }
+            // UTF8String
+            Some(0x0C) => ASN1Block::UTF8String(soff, utf8(body)?),
+            // SEQUENCE
+            Some(0x10) => ASN1Block::Sequence(soff, from_der_(body, start_offset + index)?),
+            // SET
+            Some(0x11) => ASN1Block::Set(soff, from_der_(body, start_offset + index)?),
+            // PrintableString
+            Some(0x13) => {
+                let value: String = body.iter().map(|&b| b as char).collect();
+                if !value.chars().all(|c| PRINTABLE_CHARS.contains(c)) {
+                    return Err(ASN1DecodeErr::PrintableStringDecodeFailure);
+                }
+                ASN1Block::PrintableString(soff, value)
+            }
+            // TeletexString
+            Some(0x14) => ASN1Block::TeletexString(soff, utf8(body)?),
+            // IA5String
+            Some(0x16) => ASN1Block::IA5String(soff, body.iter().map(|&b| b as char).collect()),
+            // UTCTime: YYMMDDHHMMSSZ, with years from 1950 to 2049
+            Some(0x17) => {
+                let value: String = body.iter().map(|&b| b as char).collect();
+                if body.len() != 13 {
+                    return Err(ASN1DecodeErr::InvalidDateValue(format!("{}", body.len())));
+                }
+                let century = match value.get(0..2).map(str::parse::<u8>) {
+                    Some(Ok(yy)) if yy >= 50 => "19",
+                    Some(Ok(_)) => "20",
+                    _ => return Err(ASN1DecodeErr::InvalidDateValue(value)),
+                };
+                let time = value
+                    .strip_suffix('Z')
+                    .and_then(|digits| DateTime::parse(&format!("{}{}", century, digits)));
+                match time {
+                    Some(time) => ASN1Block::UTCTime(soff, time),
+                    None => return Err(ASN1DecodeErr::InvalidDateValue(value)),
+                }
+            }
+            // GeneralizedTime: YYYYMMDDHHMMSS[.f{1,9}]Z
+            Some(0x18) => {
+                if body.len() < 15 {
+                    return Err(ASN1DecodeErr::InvalidDateValue(format!("{}", body.len())));
+                }
+                let value = utf8(body)?;
+                let time = value.strip_suffix('Z').and_then(|rest| {
+                    let (digits, fraction) = (rest.get(..14)?, rest.get(14..)?);
+                    let fraction_valid = match fraction.strip_prefix('.') {
+                        Some(f) => {
+                            (1..=9).contains(&f.len()) && f.bytes().all(|b| b.is_ascii_digit())
+                        }
+                        None => fraction.is_empty(),
+                    };
+                    if fraction_valid {
+                        DateTime::parse(digits)
+                    } else {
+                        None
+                    }
+                });
+                match time {
+                    Some(time) => ASN1Block::GeneralizedTime(soff, time),
+                    None => return Err(ASN1DecodeErr::InvalidDateValue(value)),
+                }
+            }
+            // UniversalString
+            Some(0x1C) => ASN1Block::UniversalString(soff, utf8(body)?),
+            // BMPString
+            Some(0x1E) => ASN1Block::BMPString(soff, utf8(body)?),
+            _ => ASN1Block::Unknown(class, constructed, soff, tag, body.to_vec()),
+        };
+        result.push(block);
+        index += len;
+    }
+
+    if result.is_empty() {
+        Err(ASN1DecodeErr::EmptyBuffer)
+    } else {
+        Ok(result)
+    }
+}
+
+fn utf8(body: &[u8]) -> Result<String, ASN1DecodeErr> {
+    String::from_utf8(body.to_vec()).map_err(|e| ASN1DecodeErr::UTF8DecodeFailure(e.utf8_error()))
+}
+
+fn decode_class(i: u8) -> ASN1Class {
+    match i >> 6 {
+        0b00 => ASN1Class::Universal,
+        0b01 => ASN1Class::Application,
+        0b10 => ASN1Class::ContextSpecific,
+        _ => ASN1Class::Private,
+    }
+}
+
+fn decode_tag(i: &[u8], index: &mut usize) -> Result<(BigUint, bool, ASN1Class), ASN1DecodeErr> {
+    let tagbyte = *i.get(*index).ok_or(ASN1DecodeErr::Incomplete)?;
+    let constructed = (tagbyte & 0b0010_0000) != 0;
+    let class = decode_class(tagbyte);
+    let basetag = tagbyte & 0b1_1111;
+
+    *index += 1;
+
+    if basetag == 0b1_1111 {
+        Ok((decode_base127(i, index)?, constructed, class))
+    } else {
+        Ok((BigUint::from(basetag), constructed, class))
+    }
+}
+
+fn decode_base127(i: &[u8], index: &mut usize) -> Result<BigUint, ASN1DecodeErr> {
+    let mut res = BigUint::zero();
+
+    loop {
+        let nextbyte = *i.get(*index).ok_or(ASN1DecodeErr::Incomplete)?;
+        *index += 1;
+        res = (res << 7) + BigUint::from(nextbyte & 0x7f);
+        if (nextbyte & 0x80) == 0 {
+            return Ok(res);
+        }
+    }
+}
+
+fn decode_length(i: &[u8], index: &mut usize) -> Result<usize, ASN1DecodeErr> {
+    let startbyte = *i.get(*index).ok_or(ASN1DecodeErr::Incomplete)?;
+    *index += 1;
+    if startbyte < 0x80 {
+        return Ok(startbyte as usize);
+    }
+
+    // Lengths that do not fit a usize are rejected rather than truncated
+    let lenlen = (startbyte & 0x7f) as usize;
+    if lenlen > size_of::<usize>() {
+        return Err(ASN1DecodeErr::LengthTooLarge(lenlen));
+    }
+    let mut res: usize = 0;
+    for _ in 0..lenlen {
+        let byte = *i.get(*index).ok_or(ASN1DecodeErr::Incomplete)?;
+        res = (res << 8) + byte as usize;
+        *index += 1;
+    }
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_signed_data_shapes() {
+        // SEQUENCE { OID 1.2.840.113549.1.7.2, [0] { INTEGER 1 }, UTCTime, GeneralizedTime }
+        let der = [
+            0x30, 0x34, 0x06, 0x09, 0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x07, 0x02, 0xA0,
+            0x03, 0x02, 0x01, 0x01, 0x17, 0x0D, b'2', b'4', b'0', b'2', b'2', b'9', b'1', b'2',
+            b'3', b'0', b'4', b'5', b'Z', 0x18, 0x13, b'2', b'0', b'5', b'1', b'1', b'2', b'3',
+            b'1', b'2', b'3', b'5', b'9', b'5', b'9', b'.', b'1', b'2', b'5', b'Z',
+        ];
+        assert_eq!(
+            from_der(&der[..der.len() - 1]).unwrap_err(),
+            ASN1DecodeErr::Incomplete
+        );
+
+        let blocks = from_der(&der).unwrap();
+        let [ASN1Block::Sequence(0, items)] = blocks.as_slice() else {
+            panic!("expected a SEQUENCE, got {:?}", blocks);
+        };
+        assert!(
+            matches!(&items[0], ASN1Block::ObjectIdentifier(_, o) if *o == oid!(1, 2, 840, 113549, 1, 7, 2))
+        );
+        assert!(matches!(
+            &items[1],
+            ASN1Block::Explicit(ASN1Class::ContextSpecific, 13, tag, inner)
+                if *tag == BigUint::from(0u8) && matches!(**inner, ASN1Block::Integer(_, ref n) if *n == BigInt::from(1))
+        ));
+        let ASN1Block::UTCTime(_, utc) = &items[2] else {
+            panic!("expected UTCTime");
+        };
+        assert_eq!((utc.year(), utc.month(), utc.day()), (2024, 2, 29));
+        assert_eq!((utc.hour(), utc.minute(), utc.second()), (12, 30, 45));
+        let ASN1Block::GeneralizedTime(_, generalized) = &items[3] else {
+            panic!("expected GeneralizedTime");
+        };
+        assert_eq!(
+            (generalized.year(), generalized.month(), generalized.day()),
+            (2051, 12, 31)
+        );
+
+        let elements = split_der(&der[2..]).unwrap();
+        assert_eq!(elements.len(), 4);
+        assert_eq!(elements[1], &[0xA0, 0x03, 0x02, 0x01, 0x01]);
+        assert_eq!(der_children(elements[1]).unwrap(), [&[0x02, 0x01, 0x01]]);
+        assert_eq!(der_children(&der).unwrap(), elements);
+        assert_eq!(
+            split_der(&der[2..der.len() - 1]).unwrap_err(),
+            ASN1DecodeErr::Incomplete
+        );
+
+        // February 30th
+        let invalid = [
+            0x17, 0x0D, b'2', b'4', b'0', b'2', b'3', b'0', b'0', b'0', b'0', b'0', b'0', b'0',
+            b'Z',
+        ];
+        assert!(matches!(
+            from_der(&invalid),
+            Err(ASN1DecodeErr::InvalidDateValue(_))
+        ));
+    }
+}
@@ -1,13 +1,45 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+#[macro_use]
+mod trace;
+
+pub mod asn1;
 pub mod pkcs7_parser;
+pub mod revocation;
 pub mod signed_bytes_extractor;
+#[cfg(feature = "signing")]
+pub mod signing;
+pub mod trust_store;
 pub mod types;
 
-use pkcs7_parser::{parse_signed_data, VerifierParams};
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use extractor::types::DocumentSecurityStore;
+use num_bigint::BigUint;
+use pkcs7_parser::{
+    parse_signed_data_at, parse_signer_details, parse_signer_details_at, parse_timestamp_token,
+    signer_certificate_der, signer_info_count, signer_spki_der, signing_certificates, SignerKey,
+    VerifierParams,
+};
+use revocation::revocation_status;
 use rsa::{errors::Error as RsaError, pkcs1::EncodeRsaPublicKey, Pkcs1v15Sign, RsaPublicKey};
 use sha1::Sha1;
 use sha2::{Digest, Sha256, Sha384, Sha512};
-use signed_bytes_extractor::get_signature_der;
-use types::{SignatureAlgorithm, SignatureResult, SignatureValidationError};
+use signed_bytes_extractor::{
+    get_approval_signature, get_approval_signatures, get_signature_der, timestamp_signatures,
+    ApprovalSignature,
+};
+pub use signed_bytes_extractor::{has_signature, signature_kinds};
+use trust_store::find_trust_anchor;
+use types::{
+    CheckOutcome, DetachedSignatureResult, DocumentTimestampResult, FailureReason, PolicyViolation,
+    ProfileViolation, RevocationStatus, SignatureAlgorithm, SignaturePolicy, SignatureReport,
+    SignatureResult, SignatureTimestamp, SignatureValidationError, SignerDetails, SubFilter,
+};
 
 use crate::types::PdfSignatureResult;
 
@@ -15,26 +47,25 @@ fn calculate_signed_data_hash(
     signed_data: &[u8],
     algorithm: &SignatureAlgorithm,
 ) -> SignatureResult<Vec<u8>> {
-    match algorithm {
-        SignatureAlgorithm::Sha1WithRsaEncryption => {
-            let mut hasher = Sha1::new();
-            hasher.update(signed_data);
-            Ok(hasher.finalize().to_vec())
-        }
-        SignatureAlgorithm::Sha256WithRsaEncryption => {
-            let mut hasher = Sha256::new();
-            hasher.update(signed_data);
-            Ok(hasher.finalize().to_vec())
-        }
-        SignatureAlgorithm::Sha384WithRsaEncryption => {
-            let mut hasher = Sha384::new();
-            hasher.update(signed_data);
-            Ok(hasher.finalize().to_vec())
+    calculate_digest(&[signed_data], algorithm)
+}
+
+/// The digest of `parts` one after the other, fed to the hasher in turn rather than copied
+/// into one buffer, such as the two halves of a PDF signature's ByteRange.
+fn calculate_digest(parts: &[&[u8]], algorithm: &SignatureAlgorithm) -> SignatureResult<Vec<u8>> {
+    fn digest<D: Digest>(parts: &[&[u8]]) -> Vec<u8> {
+        let mut hasher = D::new();
+        for part in parts {
+            hasher.update(part);
         }
-        SignatureAlgorithm::Sha512WithRsaEncryption => {
-            let mut hasher = Sha512::new();
-            hasher.update(signed_data);
-            Ok(hasher.finalize().to_vec())
+        hasher.finalize().to_vec()
+    }
+    match algorithm {
+        SignatureAlgorithm::Sha1WithRsaEncryption => Ok(digest::<Sha1>(parts)),
+        SignatureAlgorithm::Sha256WithRsaEncryption => Ok(digest::<Sha256>(parts)),
+        SignatureAlgorithm::Sha384WithRsaEncryption => Ok(digest::<Sha384>(parts)),
+        SignatureAlgorithm::Sha512WithRsaEncryption | SignatureAlgorithm::Ed25519 => {
+            Ok(digest::<Sha512>(parts))
         }
         other => Err(SignatureValidationError::UnsupportedAlgorithm(
             other.clone(),
@@ -42,10 +73,10 @@ fn calculate_signed_data_hash(
     }
 }
 
-fn create_rsa_public_key(verifier_params: &VerifierParams) -> SignatureResult<RsaPublicKey> {
+fn create_rsa_public_key(modulus: &[u8], exponent: &BigUint) -> SignatureResult<RsaPublicKey> {
     RsaPublicKey::new(
-        rsa::BigUint::from_bytes_be(&verifier_params.modulus),
-        rsa::BigUint::from_bytes_be(&verifier_params.exponent.to_bytes_be()),
+        rsa::BigUint::from_bytes_be(modulus),
+        rsa::BigUint::from_bytes_be(&exponent.to_bytes_be()),
     )
     .map_err(|e| SignatureValidationError::InvalidPublicKey(e.to_string()))
 }
@@ -77,55 +108,484 @@ fn verify_rsa_signature(
     }
 }
 
+fn verify_ed25519_signature(
+    public_key: &[u8; 32],
+    message: &[u8],
+    signature: &[u8],
+) -> SignatureResult<bool> {
+    let key = ed25519_dalek::VerifyingKey::from_bytes(public_key)
+        .map_err(|e| SignatureValidationError::InvalidPublicKey(e.to_string()))?;
+    let Ok(signature) = ed25519_dalek::Signature::from_slice(signature) else {
+        return Ok(false);
+    };
+    Ok(key.verify_strict(message, &signature).is_ok())
+}
+
 pub fn verify_pdf_signature(pdf_bytes: &[u8]) -> SignatureResult<PdfSignatureResult> {
-    let (signature_der, signed_data) = get_signature_der(pdf_bytes)?;
+    let _timed = timed!("verify_signature", bytes = pdf_bytes.len());
 
-    let verifier_params = parse_signed_data(&signature_der)?;
+    let signature = {
+        let _timed = timed!("extract_signed_bytes");
+        get_approval_signature(pdf_bytes)?
+    };
+    verify_approval(&signature, 0)
+}
 
-    // CHECK 1: Verify message digest
-    let calculated_signed_data_hash =
-        calculate_signed_data_hash(&signed_data, &verifier_params.algorithm)?;
+/// Checks the first approval signature like `verify_pdf_signature`, but reports the outcome
+/// of every check instead of folding them into `is_valid` and errors, so that callers can
+/// tell why a signature is not valid. A signature that cannot be read still fails with an
+/// error. The report also gives the signer certificate's revocation status at signing time,
+/// from the OCSP responses and CRLs of the document's `/DSS`, if it has one.
+pub fn verify_pdf_signature_report(pdf_bytes: &[u8]) -> SignatureResult<SignatureReport> {
+    let _timed = timed!("verify_signature_report", bytes = pdf_bytes.len());
 
-    if let Some(expected) = &verifier_params.signed_data_message_digest {
-        if expected != &calculated_signed_data_hash {
-            return Err(SignatureValidationError::MessageDigestMismatch {
-                expected: expected.clone(),
-                calculated: calculated_signed_data_hash,
+    let signature = {
+        let _timed = timed!("extract_signed_bytes");
+        get_approval_signature(pdf_bytes)?
+    };
+    // A document whose /DSS cannot be read is reported like one without
+    let dss = extractor::extract_dss(pdf_bytes).unwrap_or_default();
+    approval_report(&signature, 0, &dss)
+}
+
+/// Verifies every approval signature in the PDF, in file order, such as a certification
+/// signature followed by later approvals. Each result carries its field name and how much of
+/// the file its ByteRange covers; typically only the last one covers all of it. Document timestamps
+/// are left to `verify_pdf_timestamps`.
+pub fn verify_all_pdf_signatures(pdf_bytes: &[u8]) -> SignatureResult<Vec<PdfSignatureResult>> {
+    let _timed = timed!("verify_all_signatures", bytes = pdf_bytes.len());
+
+    let signatures = {
+        let _timed = timed!("extract_signed_bytes");
+        get_approval_signatures(pdf_bytes)?
+    };
+    signatures
+        .iter()
+        .map(|signature| verify_approval(signature, 0))
+        .collect()
+}
+
+/// Verifies every SignerInfo of the first approval signature, for CMS blobs signed by more
+/// than one party at once; `verify_pdf_signature` checks only the first. Each SignerInfo is
+/// checked against the certificate its issuer and serial number name.
+pub fn verify_pdf_signers(pdf_bytes: &[u8]) -> SignatureResult<Vec<PdfSignatureResult>> {
+    let _timed = timed!("verify_signers", bytes = pdf_bytes.len());
+
+    let signature = {
+        let _timed = timed!("extract_signed_bytes");
+        get_approval_signature(pdf_bytes)?
+    };
+    (0..signer_info_count(&signature.signature_der)?)
+        .map(|index| verify_approval(&signature, index))
+        .collect()
+}
+
+fn verify_approval(
+    signature: &ApprovalSignature,
+    index: usize,
+) -> SignatureResult<PdfSignatureResult> {
+    report_result(approval_report(
+        signature,
+        index,
+        &DocumentSecurityStore::default(),
+    )?)
+}
+
+fn approval_report(
+    signature: &ApprovalSignature,
+    index: usize,
+    dss: &DocumentSecurityStore,
+) -> SignatureResult<SignatureReport> {
+    let (verifier_params, report) = verify_signer_info(
+        &signature.signature_der,
+        &signature.signed_data.parts(),
+        index,
+    )?;
+    let profile = match signature.sub_filter {
+        Some(sub_filter) => match check_profile(sub_filter, &verifier_params) {
+            Ok(()) => CheckOutcome::Passed,
+            Err(violation) => CheckOutcome::Failed(FailureReason::Profile(violation)),
+        },
+        None => CheckOutcome::NotApplicable,
+    };
+    // A valid timestamp attests the signing time; the signingTime attribute only claims it
+    let signing_time = match report
+        .timestamp
+        .as_ref()
+        .and_then(SignatureTimestamp::verified)
+    {
+        Some(timestamp) => Some(timestamp.gen_time.as_str()),
+        None => report.signing_time.as_deref(),
+    };
+    let revocation = revocation_status(&signature.signature_der, index, dss, signing_time)?;
+    Ok(SignatureReport {
+        profile,
+        revocation,
+        field_name: signature.field_name.clone(),
+        coverage: Some(signature.coverage),
+        sub_filter: signature.sub_filter,
+        ..report
+    })
+}
+
+/// The `PdfSignatureResult` of a report. A bad signature value is reported through
+/// `is_valid`; the other failures are errors.
+fn report_result(report: SignatureReport) -> SignatureResult<PdfSignatureResult> {
+    for outcome in [&report.digest, &report.signing_certificate, &report.profile] {
+        match outcome.failure() {
+            Some(FailureReason::DigestMismatch {
+                expected,
+                calculated,
+            }) => {
+                return Err(SignatureValidationError::MessageDigestMismatch {
+                    expected: expected.clone(),
+                    calculated: calculated.clone(),
+                })
+            }
+            Some(FailureReason::SigningCertificateMismatch) => {
+                return Err(SignatureValidationError::SigningCertificateMismatch)
+            }
+            Some(FailureReason::Profile(violation)) => {
+                return Err(SignatureValidationError::Profile(violation.clone()))
+            }
+            Some(FailureReason::BadSignature) | None => {}
+        }
+    }
+    Ok(PdfSignatureResult {
+        is_valid: report.signature == CheckOutcome::Passed,
+        message_digest: report.message_digest,
+        public_key: report.public_key,
+        signer: Some(report.signer),
+        field_name: report.field_name,
+        coverage: report.coverage,
+        sub_filter: report.sub_filter,
+        signing_time: report.signing_time,
+        timestamp: report.timestamp,
+    })
+}
+
+/// Applies the rules of `sub_filter` beyond those of CMS itself. `ETSI.CAdES.detached`
+/// signatures must have signed attributes, among them a signingCertificateV2, which
+/// `verify_cms` has checked against the signer certificate.
+fn check_profile(
+    sub_filter: SubFilter,
+    verifier_params: &VerifierParams,
+) -> Result<(), ProfileViolation> {
+    if sub_filter != SubFilter::CadesDetached {
+        return Ok(());
+    }
+    let signed_attrs_der = verifier_params
+        .signed_attrs_der
+        .as_deref()
+        .ok_or(ProfileViolation::MissingSignedAttributes)?;
+    match signing_certificates(signed_attrs_der) {
+        Ok(bindings) if bindings.iter().any(|binding| binding.v2) => Ok(()),
+        _ => Err(ProfileViolation::MissingSigningCertificate),
+    }
+}
+
+/// Checks every ESS signingCertificate[V2] attribute among the signed attributes against the
+/// signer certificate, so that it cannot be swapped for another certificate with the same key
+/// inside the SignedData. Signatures without such attributes are not affected.
+fn check_signing_certificate(
+    signature_der: &[u8],
+    index: usize,
+    verifier_params: &VerifierParams,
+) -> SignatureResult<CheckOutcome> {
+    let Some(signed_attrs_der) = &verifier_params.signed_attrs_der else {
+        return Ok(CheckOutcome::NotApplicable);
+    };
+    let bindings = signing_certificates(signed_attrs_der)?;
+    if bindings.is_empty() {
+        return Ok(CheckOutcome::NotApplicable);
+    }
+    let certificate = signer_certificate_der(signature_der, index)?;
+    for binding in bindings {
+        if calculate_signed_data_hash(certificate, &binding.hash_algorithm)? != binding.cert_hash {
+            return Ok(CheckOutcome::Failed(
+                FailureReason::SigningCertificateMismatch,
+            ));
+        }
+    }
+    Ok(CheckOutcome::Passed)
+}
+
+/// Verifies every document timestamp (`/SubFilter /ETSI.RFC3161`) in the PDF, in file order.
+/// For each, the token's messageImprint must match the hash of the covered bytes and its
+/// messageDigest the TSTInfo; a bad TSA signature is reported through `is_valid`. A PDF without
+/// timestamps gives an empty list. Approval signatures are left to `verify_pdf_signature`.
+pub fn verify_pdf_timestamps(pdf_bytes: &[u8]) -> SignatureResult<Vec<DocumentTimestampResult>> {
+    let _timed = timed!("verify_timestamps", bytes = pdf_bytes.len());
+
+    let timestamps = {
+        let _timed = timed!("extract_signed_bytes");
+        timestamp_signatures(pdf_bytes)?
+    };
+    timestamps
+        .iter()
+        .map(|(token_der, covered)| verify_timestamp_token(token_der, &covered.parts()))
+        .collect()
+}
+
+/// Checks an RFC 3161 timestamp token over `imprinted`, the parts of the bytes its
+/// messageImprint must be the hash of: the token's messageDigest must match the TSTInfo, and
+/// a bad TSA signature is reported through `is_valid`.
+fn verify_timestamp_token(
+    token_der: &[u8],
+    imprinted: &[&[u8]],
+) -> SignatureResult<DocumentTimestampResult> {
+    let tst_info = parse_timestamp_token(token_der)?;
+    let imprint = calculate_digest(imprinted, &tst_info.hash_algorithm)?;
+    if imprint != tst_info.message_imprint {
+        return Err(SignatureValidationError::MessageDigestMismatch {
+            expected: tst_info.message_imprint,
+            calculated: imprint,
+        });
+    }
+    let (_, result) = verify_cms(token_der, &tst_info.tst_info_der)?;
+    Ok(DocumentTimestampResult {
+        is_valid: result.is_valid,
+        gen_time: tst_info.gen_time,
+        hash_algorithm: tst_info.hash_algorithm,
+        message_imprint: tst_info.message_imprint,
+        serial_number: tst_info.serial_number,
+        public_key: result.public_key,
+        tsa: parse_signer_details(token_der)?,
+    })
+}
+
+/// Verifies a detached CMS/PKCS#7 signature (e.g. a `.p7s` file) over `content_bytes`, with
+/// the same checks as `verify_pdf_signature`, then applies `policy` to a valid signature.
+/// An invalid signature is reported through `is_valid`; a valid one that breaks the policy
+/// fails with `SignatureValidationError::Policy`.
+pub fn verify_detached_signature(
+    content_bytes: &[u8],
+    p7s_der: &[u8],
+    policy: &SignaturePolicy,
+) -> SignatureResult<DetachedSignatureResult> {
+    let _timed = timed!("verify_detached_signature", bytes = content_bytes.len());
+
+    let (verifier_params, result) = verify_cms(p7s_der, content_bytes)?;
+    let signer = parse_signer_details(p7s_der)?;
+    if result.is_valid {
+        check_policy(policy, p7s_der, &verifier_params, &result, &signer)
+            .map_err(SignatureValidationError::Policy)?;
+    }
+    Ok(DetachedSignatureResult {
+        is_valid: result.is_valid,
+        message_digest: result.message_digest,
+        public_key: result.public_key,
+        signer,
+    })
+}
+
+/// Verifies a detached CMS/PKCS#7 signature over any payload, such as a DigiLocker XML
+/// document or an attachment, with no policy beyond what `verify_pdf_signature` accepts.
+/// Use `verify_detached_signature` to apply a `SignaturePolicy`.
+pub fn verify_pkcs7_detached(
+    signature_der: &[u8],
+    content_bytes: &[u8],
+) -> SignatureResult<DetachedSignatureResult> {
+    verify_detached_signature(content_bytes, signature_der, &SignaturePolicy::default())
+}
+
+fn check_policy(
+    policy: &SignaturePolicy,
+    signature_der: &[u8],
+    verifier_params: &VerifierParams,
+    result: &PdfSignatureResult,
+    signer: &SignerDetails,
+) -> Result<(), PolicyViolation> {
+    if policy.reject_sha1 && verifier_params.algorithm == SignatureAlgorithm::Sha1WithRsaEncryption
+    {
+        return Err(PolicyViolation::Sha1);
+    }
+    if verifier_params.algorithm != SignatureAlgorithm::Ed25519
+        && signer.key_size < policy.min_key_bits
+    {
+        return Err(PolicyViolation::KeyTooShort {
+            bits: signer.key_size,
+            min: policy.min_key_bits,
+        });
+    }
+    if policy.require_signed_attributes && verifier_params.signed_attr_digest.is_none() {
+        return Err(PolicyViolation::MissingSignedAttributes);
+    }
+    if !policy.trusted_keys.is_empty() && !policy.trusted_keys.contains(&result.public_key) {
+        return Err(PolicyViolation::UntrustedSigner);
+    }
+    if !policy.trust_anchors.is_empty()
+        && !matches!(
+            find_trust_anchor(signature_der, policy.trust_anchors),
+            Ok(Some(_))
+        )
+    {
+        return Err(PolicyViolation::UntrustedIssuer);
+    }
+    // Both sides are `YYYY-MM-DDTHH:MM:SSZ`, which orders lexicographically
+    if let Some(time) = &policy.validation_time {
+        if time.as_str() < signer.not_before.as_str() {
+            return Err(PolicyViolation::CertificateNotYetValid {
+                not_before: signer.not_before.clone(),
+            });
+        }
+        if time.as_str() > signer.not_after.as_str() {
+            return Err(PolicyViolation::CertificateExpired {
+                not_after: signer.not_after.clone(),
             });
         }
     }
+    Ok(())
+}
 
-    // CHECK 2: Verify RSA signature
-    let pub_key = create_rsa_public_key(&verifier_params)?;
-    let padding = get_pkcs1v15_padding(&verifier_params.algorithm)?;
-    let digest_for_signature = verifier_params
-        .signed_attr_digest
-        .clone()
-        .unwrap_or_else(|| calculated_signed_data_hash.clone());
-    let is_verified = verify_rsa_signature(
-        &pub_key,
-        padding,
-        &digest_for_signature,
-        &verifier_params.signature,
-    )?;
+/// Checks a CMS signature over `signed_data`: the message digest, then the RSA signature.
+fn verify_cms(
+    signature_der: &[u8],
+    signed_data: &[u8],
+) -> SignatureResult<(VerifierParams, PdfSignatureResult)> {
+    let (verifier_params, report) = verify_signer_info(signature_der, &[signed_data], 0)?;
+    Ok((verifier_params, report_result(report)?))
+}
 
-    Ok(PdfSignatureResult {
-        is_valid: is_verified,
+/// The checks of `verify_cms` for the SignerInfo at `index`, each with its outcome. The
+/// signed data comes in parts, hashed in turn, such as the two halves of a ByteRange.
+fn verify_signer_info(
+    signature_der: &[u8],
+    signed_data: &[&[u8]],
+    index: usize,
+) -> SignatureResult<(VerifierParams, SignatureReport)> {
+    let verifier_params = {
+        let _timed = timed!("parse_pkcs7", der_bytes = signature_der.len());
+        parse_signed_data_at(signature_der, index)?
+    };
+
+    // CHECK 1: Verify message digest
+    let calculated_signed_data_hash = {
+        let _timed = timed!(
+            "hash_signed_data",
+            signed_bytes = signed_data.iter().map(|part| part.len()).sum::<usize>(),
+            algorithm = %verifier_params.algorithm
+        );
+        calculate_digest(signed_data, &verifier_params.algorithm)?
+    };
+
+    let digest = match &verifier_params.signed_data_message_digest {
+        Some(expected) if expected != &calculated_signed_data_hash => {
+            CheckOutcome::Failed(FailureReason::DigestMismatch {
+                expected: expected.clone(),
+                calculated: calculated_signed_data_hash.clone(),
+            })
+        }
+        Some(_) => CheckOutcome::Passed,
+        // Ed25519 without signed attributes signs the content itself
+        None => CheckOutcome::NotApplicable,
+    };
+
+    // CHECK 2: Verify RSA or Ed25519 signature
+    let (is_verified, public_key) = match (&verifier_params.key, &verifier_params.algorithm) {
+        (SignerKey::Ed25519(key), SignatureAlgorithm::Ed25519) => {
+            // Ed25519 signs the signed attributes themselves, or else the content, which then
+            // has to be copied into one message
+            let message = match &verifier_params.signed_attrs_der {
+                Some(signed_attrs_der) => Cow::Borrowed(signed_attrs_der.as_slice()),
+                None => Cow::Owned(signed_data.concat()),
+            };
+            let is_verified = {
+                let _timed = timed!("ed25519_verify");
+                verify_ed25519_signature(key, &message, &verifier_params.signature)?
+            };
+            (is_verified, key.to_vec())
+        }
+        (SignerKey::Ed25519(_), other) => {
+            return Err(SignatureValidationError::UnsupportedAlgorithm(
+                other.clone(),
+            ))
+        }
+        (SignerKey::Rsa { modulus, exponent }, _) => {
+            let pub_key = create_rsa_public_key(modulus, exponent)?;
+            let padding = get_pkcs1v15_padding(&verifier_params.algorithm)?;
+            let digest_for_signature = verifier_params
+                .signed_attr_digest
+                .clone()
+                .unwrap_or_else(|| calculated_signed_data_hash.clone());
+            let is_verified = {
+                let _timed = timed!("rsa_verify");
+                verify_rsa_signature(
+                    &pub_key,
+                    padding,
+                    &digest_for_signature,
+                    &verifier_params.signature,
+                )?
+            };
+            let public_key = pub_key
+                .to_pkcs1_der()
+                .expect("Failed to encode public key")
+                .as_bytes()
+                .to_vec();
+            (is_verified, public_key)
+        }
+    };
+    trace_event!(is_valid = is_verified, "signature checked");
+
+    // CHECK 3: The signed attributes name the certificate the key was taken from
+    let signing_certificate = check_signing_certificate(signature_der, index, &verifier_params)?;
+
+    // A signature timestamp imprints the signature value itself
+    let timestamp = verifier_params.timestamp_token.as_deref().map(|token_der| {
+        match verify_timestamp_token(token_der, &[&verifier_params.signature]) {
+            Ok(timestamp) => SignatureTimestamp::Checked(Box::new(timestamp)),
+            Err(error) => SignatureTimestamp::Failed(error.to_string()),
+        }
+    });
+
+    let report = SignatureReport {
+        digest,
+        signature: if is_verified {
+            CheckOutcome::Passed
+        } else {
+            CheckOutcome::Failed(FailureReason::BadSignature)
+        },
+        signing_certificate,
+        profile: CheckOutcome::NotApplicable,
+        signed_attributes: verifier_params.signed_attrs_der.is_some(),
+        algorithm: verifier_params.algorithm.clone(),
+        revocation: RevocationStatus::Unknown,
+        coverage: None,
         message_digest: verifier_params
             .signed_data_message_digest
             .clone()
             .unwrap_or(calculated_signed_data_hash),
-        public_key: pub_key
-            .to_pkcs1_der()
-            .expect("Failed to encode public key")
-            .as_bytes()
-            .to_vec(),
-    })
+        public_key,
+        signer: parse_signer_details_at(signature_der, index)?,
+        field_name: None,
+        sub_filter: None,
+        signing_time: verifier_params.signing_time.clone(),
+        timestamp,
+    };
+    Ok((verifier_params, report))
+}
+
+/// Reads the signer's certificate details (subject, issuer, validity, key size) from a signed PDF.
+/// The signature is not verified; use `verify_pdf_signature` for that.
+pub fn get_signer_details(pdf_bytes: &[u8]) -> SignatureResult<SignerDetails> {
+    let (signature_der, _) = get_signature_der(pdf_bytes)?;
+    Ok(parse_signer_details(&signature_der)?)
+}
+
+/// SHA-256 of the signer certificate's subjectPublicKeyInfo, for RSA, ECDSA and Ed25519 keys
+/// alike. Unlike `get_signer_details`, this does not need a key type the crate can verify with.
+/// The circuit's `signerKeyHash` is still keccak-256 of `PdfSignatureResult::public_key`.
+pub fn signer_key_fingerprint_sha256(pdf_bytes: &[u8]) -> SignatureResult<[u8; 32]> {
+    let (signature_der, _) = get_signature_der(pdf_bytes)?;
+    Ok(Sha256::digest(signer_spki_der(&signature_der, 0)?).into())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::pkcs7_parser::{parse_certificate, parse_signed_data, signed_data_certificates};
+    use crate::trust_store::TrustAnchor;
+    use crate::types::{SignatureCoverage, SubFilter};
 
     // PUBLIC PDF
     static SAMPLE_PDF_BYTES: &[u8] = include_bytes!("../../sample-pdfs/digitally_signed.pdf");
@@ -135,6 +595,135 @@ mod tests {
         assert!(matches!(res, Ok(PdfSignatureResult { is_valid: true, .. })));
     }
 
+    #[test]
+    fn digests_of_parts_match_the_whole() {
+        let (_, signed_data) = get_signature_der(SAMPLE_PDF_BYTES).unwrap();
+        let (first, second) = signed_data.split_at(1000);
+        for algorithm in [
+            SignatureAlgorithm::Sha1WithRsaEncryption,
+            SignatureAlgorithm::Sha256WithRsaEncryption,
+            SignatureAlgorithm::Sha384WithRsaEncryption,
+            SignatureAlgorithm::Sha512WithRsaEncryption,
+        ] {
+            assert_eq!(
+                calculate_digest(&[first, second], &algorithm).unwrap(),
+                calculate_signed_data_hash(&signed_data, &algorithm).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_verified_signer_certificate() {
+        let res = verify_pdf_signature(SAMPLE_PDF_BYTES).expect("signature verification failed");
+        let signer = res
+            .signer
+            .as_ref()
+            .expect("verified signature without a signer certificate");
+
+        assert_eq!(
+            signer.issuer,
+            "C=US, O=GeoTrust Inc., CN=GeoTrust CA for Adobe"
+        );
+        assert!(signer
+            .subject
+            .ends_with("CN=John B Harris, emailAddress=jbharris@adobe.com"));
+        assert_eq!(signer.serial_number, vec![0x02, 0x02]);
+        assert_eq!(signer.not_before, "2006-08-22T18:58:23Z");
+        assert_eq!(signer.not_after, "2009-09-04T18:58:23Z");
+        assert_eq!(res.sub_filter, Some(SubFilter::Pkcs7Detached));
+    }
+
+    #[test]
+    fn test_signature_report() {
+        let report = verify_pdf_signature_report(SAMPLE_PDF_BYTES).expect("failed to check");
+        assert!(report.is_valid());
+        assert_eq!(report.digest, CheckOutcome::Passed);
+        assert_eq!(report.signature, CheckOutcome::Passed);
+        assert_eq!(report.signing_certificate, CheckOutcome::NotApplicable);
+        assert_eq!(report.profile, CheckOutcome::Passed);
+        assert!(report.signed_attributes);
+
+        // A changed byte in the binary comment after the header, which the signature covers
+        let mut modified = SAMPLE_PDF_BYTES.to_vec();
+        modified[11] ^= 1;
+        assert!(matches!(
+            verify_pdf_signature(&modified),
+            Err(SignatureValidationError::MessageDigestMismatch { .. })
+        ));
+        let report = verify_pdf_signature_report(&modified).expect("failed to check");
+        assert!(matches!(
+            report.failures()[..],
+            [FailureReason::DigestMismatch { .. }]
+        ));
+        // The signed attributes themselves are intact
+        assert_eq!(report.signature, CheckOutcome::Passed);
+
+        // The Ed25519 sample ends with its signature value
+        let pdf_bytes: &[u8] = include_bytes!("../../sample-pdfs/ed25519_signed.pdf");
+        let (mut signature_der, signed_data) = get_signature_der(pdf_bytes).unwrap();
+        let last = signature_der.len() - 1;
+        signature_der[last] ^= 1;
+        let (_, report) = verify_signer_info(&signature_der, &[&signed_data], 0).unwrap();
+        assert_eq!(report.failures(), [&FailureReason::BadSignature]);
+        assert_eq!(report.digest, CheckOutcome::Passed);
+    }
+
+    #[test]
+    fn test_signing_certificate_v1() {
+        // The TSA that timestamped the sample binds its certificate with a SHA-1 ESSCertID
+        let (signature_der, _) = get_signature_der(SAMPLE_PDF_BYTES).unwrap();
+        let token = parse_signed_data(&signature_der)
+            .unwrap()
+            .timestamp_token
+            .unwrap();
+        let params = parse_signed_data(&token).unwrap();
+        let bindings = signing_certificates(params.signed_attrs_der.as_deref().unwrap()).unwrap();
+
+        assert_eq!(bindings.len(), 1);
+        assert!(!bindings[0].v2);
+        let certificate = signer_certificate_der(&token, 0).unwrap();
+        assert_eq!(bindings[0].cert_hash, Sha1::digest(certificate).to_vec());
+    }
+
+    #[test]
+    fn test_signature_timestamp() {
+        let res = verify_pdf_signature(SAMPLE_PDF_BYTES).expect("signature verification failed");
+        let timestamp = res
+            .timestamp
+            .expect("signature without its timeStampToken attribute");
+        let timestamp = timestamp.verified().expect("timestamp does not verify");
+
+        assert_eq!(timestamp.gen_time, "2009-07-16T14:47:57Z");
+        // Within the signer certificate's validity
+        assert!(timestamp.gen_time.as_str() < "2009-09-04T18:58:23Z");
+    }
+
+    #[test]
+    fn test_ed25519_pdf() {
+        let pdf_bytes: &[u8] = include_bytes!("../../sample-pdfs/ed25519_signed.pdf");
+        let res = verify_pdf_signature(pdf_bytes).expect("Ed25519 signature verification failed");
+
+        assert!(res.is_valid, "Ed25519 signature reported invalid");
+        assert_eq!(res.public_key.len(), 32);
+        assert_eq!(res.message_digest.len(), 64);
+        let signer = res
+            .signer
+            .expect("verified signature without a signer certificate");
+        assert_eq!(signer.signature_algorithm, SignatureAlgorithm::Ed25519);
+        assert_eq!(signer.key_size, 256);
+        assert!(signer.subject.ends_with("CN=Ed25519 Test Signer"));
+        // SEQUENCE { SEQUENCE { id-Ed25519 }, BIT STRING { 00 || key } }
+        assert_eq!(signer.spki.len(), 44);
+        assert!(signer.spki.ends_with(&res.public_key));
+
+        // The signed bytes are intact, but the signature over the signed attributes is not
+        let (mut signature_der, signed_data) = get_signature_der(pdf_bytes).unwrap();
+        let last = signature_der.len() - 1;
+        signature_der[last] ^= 1;
+        let (_, tampered) = verify_cms(&signature_der, &signed_data).unwrap();
+        assert!(!tampered.is_valid);
+    }
+
     #[test]
     fn test_gst_template_pdf() {
         let pdf_bytes: &[u8] = include_bytes!("../../sample-pdfs/GST-certificate.pdf");
@@ -144,6 +733,169 @@ mod tests {
         assert!(res.is_valid, "GST certificate signature reported invalid");
     }
 
+    #[test]
+    fn test_signature_field_and_coverage() {
+        let pdf_bytes: &[u8] = include_bytes!("../../sample-pdfs/GST-certificate.pdf");
+        let all = verify_all_pdf_signatures(pdf_bytes).expect("failed to verify signatures");
+        assert_eq!(all.len(), 1);
+        assert!(all[0].is_valid);
+        assert_eq!(
+            all[0].field_name.as_deref(),
+            Some("N50Qaxhoo0JfhpSeePTjnXr9M1c=\r\n")
+        );
+        assert_eq!(all[0].coverage, Some(SignatureCoverage::FullDocument));
+        // adbe.pkcs7.sha1 has no profile of its own here
+        assert_eq!(all[0].sub_filter, None);
+
+        // An incremental update after signing leaves the signature valid but partial
+        let mut updated = pdf_bytes.to_vec();
+        let update = b"\n99 0 obj\n<< >>\nendobj\n%%EOF\n";
+        updated.extend_from_slice(update);
+        let res = verify_pdf_signature(&updated).expect("signature verification failed");
+        assert!(res.is_valid);
+        assert_eq!(
+            res.coverage,
+            Some(SignatureCoverage::PartialWithUpdates {
+                appended_bytes: update.len()
+            })
+        );
+    }
+
+    #[test]
+    fn test_signer_details() {
+        let details = get_signer_details(SAMPLE_PDF_BYTES).expect("failed to read signer details");
+
+        assert_eq!(
+            details.subject,
+            "C=US, ST=CA, L=San Jose, O=Adobe Systems Incorporated, CN=John B Harris, \
+             emailAddress=jbharris@adobe.com"
+        );
+        assert_eq!(
+            details.issuer,
+            "C=US, O=GeoTrust Inc., CN=GeoTrust CA for Adobe"
+        );
+        assert_eq!(details.serial_number, vec![0x02, 0x02]);
+        assert_eq!(details.not_before, "2006-08-22T18:58:23Z");
+        assert_eq!(details.not_after, "2009-09-04T18:58:23Z");
+        assert_eq!(details.key_size, 2048);
+        assert_eq!(
+            details.signature_algorithm.to_string(),
+            "sha1WithRSAEncryption"
+        );
+
+        // rsaEncryption SPKI around the PKCS#1 key the verifier reports
+        let spki = crate::asn1::der_children(&details.spki).unwrap();
+        assert_eq!(
+            spki[0],
+            &[
+                0x30, 0x0D, 0x06, 0x09, 0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x01, 0x01, 0x05,
+                0x00
+            ]
+        );
+        let public_key = verify_pdf_signature(SAMPLE_PDF_BYTES).unwrap().public_key;
+        assert_eq!(
+            &spki[1][spki[1].len() - public_key.len()..],
+            public_key.as_slice()
+        );
+        let fingerprint = details.key_fingerprint_sha256();
+        assert_eq!(fingerprint, <[u8; 32]>::from(Sha256::digest(&details.spki)));
+
+        // The whole certificate, which reads back to the same details
+        let header = [0x30, 0x82, 0x05, 0x67];
+        assert_eq!(details.certificate[..4], header);
+        assert_eq!(details.certificate.len(), 4 + 0x567);
+        let reparsed = crate::pkcs7_parser::certificate_details(
+            &details.certificate,
+            details.signature_algorithm,
+        )
+        .unwrap();
+        assert_eq!(reparsed.subject, details.subject);
+        assert_eq!(reparsed.spki, details.spki);
+        assert_eq!(
+            signer_key_fingerprint_sha256(SAMPLE_PDF_BYTES).unwrap(),
+            fingerprint
+        );
+    }
+
+    #[test]
+    fn sample_has_no_timestamps() {
+        assert!(verify_pdf_timestamps(SAMPLE_PDF_BYTES).unwrap().is_empty());
+    }
+
+    #[test]
+    fn detached_signature_from_pdf_sample() {
+        // The sample's CMS verified detached over its signed byte ranges
+        let (der, signed_data) = get_signature_der(SAMPLE_PDF_BYTES).unwrap();
+        let res = verify_detached_signature(&signed_data, &der, &SignaturePolicy::default());
+        assert!(res.unwrap().is_valid);
+        let res = verify_pkcs7_detached(&der, &signed_data).unwrap();
+        assert!(res.is_valid);
+        assert_eq!(res.signer.key_size, 2048);
+        assert!(matches!(
+            verify_pkcs7_detached(&der, &signed_data[1..]),
+            Err(SignatureValidationError::MessageDigestMismatch { .. })
+        ));
+
+        let sha1 = verify_detached_signature(&signed_data, &der, &SignaturePolicy::strict());
+        assert!(matches!(
+            sha1,
+            Err(SignatureValidationError::Policy(PolicyViolation::Sha1))
+        ));
+        let expired = verify_detached_signature(
+            &signed_data,
+            &der,
+            &SignaturePolicy {
+                validation_time: Some("2024-01-01T00:00:00Z".into()),
+                ..SignaturePolicy::default()
+            },
+        );
+        assert!(matches!(
+            expired,
+            Err(SignatureValidationError::Policy(
+                PolicyViolation::CertificateExpired { .. }
+            ))
+        ));
+
+        // Pinned to the sample's Adobe root, then to the certificate signing the GST sample
+        let pinned = |pdf: &[u8], self_signed: bool| {
+            let (der, _) = get_signature_der(pdf).unwrap();
+            signed_data_certificates(&der)
+                .unwrap()
+                .into_iter()
+                .map(|der| parse_certificate(der).unwrap())
+                .find(|cert| (cert.subject == cert.issuer) == self_signed)
+                .unwrap()
+                .der
+                .to_vec()
+        };
+        let verify_pinned = |certificate: &[u8]| {
+            let anchors = [TrustAnchor {
+                name: "pinned",
+                der: certificate,
+            }];
+            verify_detached_signature(
+                &signed_data,
+                &der,
+                &SignaturePolicy {
+                    trust_anchors: &anchors,
+                    ..SignaturePolicy::default()
+                },
+            )
+        };
+        let anchored = verify_pinned(&pinned(SAMPLE_PDF_BYTES, true));
+        assert!(anchored.unwrap().is_valid);
+        let unanchored = verify_pinned(&pinned(
+            include_bytes!("../../sample-pdfs/GST-certificate.pdf"),
+            false,
+        ));
+        assert!(matches!(
+            unanchored,
+            Err(SignatureValidationError::Policy(
+                PolicyViolation::UntrustedIssuer
+            ))
+        ));
+    }
+
     #[cfg(feature = "private_tests")]
     mod private {
         use super::*;
@@ -1,55 +1,811 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
 use num_bigint::BigUint;
-use num_traits::FromPrimitive;
 use sha1::Sha1;
 use sha2::{Digest, Sha256, Sha384, Sha512};
-use simple_asn1::{from_der, oid, ASN1Block, ASN1Class};
 
-use crate::types::{Pkcs7Error, Pkcs7Result, SignatureAlgorithm};
+use crate::asn1::{self, der_children, from_der, oid, split_der, ASN1Block, ASN1Class};
+use crate::types::{Pkcs7Error, Pkcs7Result, SignatureAlgorithm, SignerDetails};
+
+/// The public key of a signer certificate.
+pub enum SignerKey {
+    Rsa { modulus: Vec<u8>, exponent: BigUint },
+    Ed25519([u8; 32]),
+}
 
 pub struct VerifierParams {
-    pub modulus: Vec<u8>,
-    pub exponent: BigUint,
+    pub key: SignerKey,
     pub signature: Vec<u8>,
     pub signed_attr_digest: Option<Vec<u8>>,
+    /// The signed attributes as signed; Ed25519 signs them directly rather than their digest.
+    pub signed_attrs_der: Option<Vec<u8>>,
     pub algorithm: SignatureAlgorithm,
     pub signed_data_message_digest: Option<Vec<u8>>,
+    /// The signingTime signed attribute, as an ISO 8601 UTC timestamp; the signer's own claim.
+    pub signing_time: Option<String>,
+    /// The RFC 3161 token of the timeStampToken unsigned attribute, whose messageImprint is a
+    /// hash of `signature`.
+    pub timestamp_token: Option<Vec<u8>>,
+}
+
+/// How a SignerInfo names its certificate (the SignerIdentifier of RFC 5652).
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum SignerId {
+    /// The issuer Name as encoded, compared in full since serial numbers are only unique per
+    /// issuer, and the serial number.
+    IssuerAndSerialNumber { issuer: Vec<u8>, serial: BigUint },
+    /// `[0] subjectKeyIdentifier`, the keyIdentifier of the certificate's subjectKeyIdentifier
+    /// extension.
+    SubjectKeyIdentifier(Vec<u8>),
+}
+
+impl SignerId {
+    /// The sid of the SignerInfo at `index`.
+    fn at(der_bytes: &[u8], index: usize) -> Pkcs7Result<Self> {
+        let sid = SignerInfoFields::parse(signer_info_der(der_bytes, index)?)?.sid;
+        if sid.first() == Some(&0x30) {
+            let [issuer, serial] = der_children(sid)?[..] else {
+                return Err(Pkcs7Error::structure("Invalid issuerAndSerialNumber"));
+            };
+            return Ok(Self::IssuerAndSerialNumber {
+                issuer: issuer.to_vec(),
+                serial: integer(serial)?,
+            });
+        }
+        match from_der(sid)?.as_slice() {
+            [ASN1Block::Unknown(ASN1Class::ContextSpecific, false, _, tag, key_id)]
+                if *tag == BigUint::from(0u8) =>
+            {
+                Ok(Self::SubjectKeyIdentifier(key_id.clone()))
+            }
+            _ => Err(Pkcs7Error::structure(
+                "SignerInfo sid is neither issuerAndSerialNumber nor subjectKeyIdentifier",
+            )),
+        }
+    }
+
+    /// Whether `certificate` (DER) is the one this identifier names.
+    fn names(&self, certificate: &[u8]) -> Pkcs7Result<bool> {
+        let tbs_fields = tbs_fields_der(certificate)?;
+        match self {
+            Self::IssuerAndSerialNumber { issuer, serial } => {
+                // tbsCertificate: [0] version, serialNumber, signature, issuer, ...
+                let [cert_serial, _, cert_issuer, ..] = tbs_fields[..] else {
+                    return Err(Pkcs7Error::structure("tbsCertificate too short"));
+                };
+                Ok(cert_issuer == issuer.as_slice() && integer(cert_serial)? == *serial)
+            }
+            Self::SubjectKeyIdentifier(key_id) => {
+                Ok(subject_key_identifier(&tbs_fields)?.as_ref() == Some(key_id))
+            }
+        }
+    }
+}
+
+/// The tbsCertificate fields of a DER certificate as encoded, without the version:
+/// serialNumber, signature, issuer, validity, subject, subjectPublicKeyInfo, ...
+fn tbs_fields_der(certificate: &[u8]) -> Pkcs7Result<Vec<&[u8]>> {
+    let tbs = *der_children(certificate)?
+        .first()
+        .ok_or_else(|| Pkcs7Error::structure("tbsCertificate not found"))?;
+    let mut tbs_fields = der_children(tbs)?;
+    if tbs_fields.first().and_then(|field| field.first()) == Some(&0xA0) {
+        tbs_fields.remove(0);
+    }
+    Ok(tbs_fields)
+}
+
+/// The subjectPublicKeyInfo of a DER certificate as encoded: the key with its algorithm and
+/// parameters, whatever the algorithm (RSA, EC, Ed25519, ...).
+pub fn certificate_spki_der(certificate: &[u8]) -> Pkcs7Result<&[u8]> {
+    match tbs_fields_der(certificate)?.get(5) {
+        Some(spki) if spki.first() == Some(&0x30) => Ok(spki),
+        _ => Err(Pkcs7Error::structure("subjectPublicKeyInfo not found")),
+    }
+}
+
+/// `certificate_spki_der` of the certificate the SignerInfo at `index` names. Unlike
+/// `parse_signer_details`, this works for keys the crate cannot verify with, such as ECDSA.
+pub fn signer_spki_der(der_bytes: &[u8], index: usize) -> Pkcs7Result<&[u8]> {
+    certificate_spki_der(signer_certificate_der(der_bytes, index)?)
+}
+
+/// A DER INTEGER as the unsigned serial numbers it holds here.
+pub(crate) fn integer(der: &[u8]) -> Pkcs7Result<BigUint> {
+    match from_der(der)?.as_slice() {
+        [ASN1Block::Integer(_, value)] => Ok(BigUint::from_bytes_be(&value.to_signed_bytes_be())),
+        _ => Err(Pkcs7Error::structure("Expected serialNumber INTEGER")),
+    }
+}
+
+/// The extnValue contents of the extension `id` among the encoded tbsCertificate fields, if
+/// the certificate has one.
+fn extension_value(tbs_fields: &[&[u8]], id: &asn1::OID) -> Pkcs7Result<Option<Vec<u8>>> {
+    let Some(extensions) = tbs_fields.iter().find(|field| field.first() == Some(&0xA3)) else {
+        return Ok(None);
+    };
+    let [extensions] = der_children(extensions)?[..] else {
+        return Err(Pkcs7Error::structure("Invalid certificate extensions"));
+    };
+    for extension in der_children(extensions)? {
+        // Extension: extnID, critical DEFAULT FALSE, extnValue
+        let parts = der_children(extension)?;
+        let (Some(extn_id), Some(extn_value)) = (parts.first(), parts.last()) else {
+            return Err(Pkcs7Error::structure("Invalid certificate extension"));
+        };
+        if !matches!(from_der(extn_id)?.as_slice(),
+            [ASN1Block::ObjectIdentifier(_, oid)] if oid == id)
+        {
+            continue;
+        }
+        return match from_der(extn_value)?.as_slice() {
+            [ASN1Block::OctetString(_, value)] => Ok(Some(value.clone())),
+            _ => Err(Pkcs7Error::structure("Invalid certificate extension")),
+        };
+    }
+    Ok(None)
+}
+
+/// The keyIdentifier of the subjectKeyIdentifier extension (OID 2.5.29.14) among the encoded
+/// tbsCertificate fields, if the certificate has one.
+fn subject_key_identifier(tbs_fields: &[&[u8]]) -> Pkcs7Result<Option<Vec<u8>>> {
+    let Some(value) = extension_value(tbs_fields, &oid!(2, 5, 29, 14))? else {
+        return Ok(None);
+    };
+    match from_der(&value)?.as_slice() {
+        [ASN1Block::OctetString(_, key_id)] => Ok(Some(key_id.clone())),
+        _ => Err(Pkcs7Error::structure(
+            "Invalid subjectKeyIdentifier extension",
+        )),
+    }
+}
+
+/// The basicConstraints extension (OID 2.5.29.19): whether the subject is a CA, and the
+/// pathLenConstraint if it sets one. A certificate without the extension is not a CA.
+fn basic_constraints(tbs_fields: &[&[u8]]) -> Pkcs7Result<(bool, Option<usize>)> {
+    let Some(value) = extension_value(tbs_fields, &oid!(2, 5, 29, 19))? else {
+        return Ok((false, None));
+    };
+    let invalid = || Pkcs7Error::structure("Invalid basicConstraints extension");
+    // BasicConstraints: cA BOOLEAN DEFAULT FALSE, pathLenConstraint INTEGER OPTIONAL
+    let fields = match from_der(&value)?.as_slice() {
+        [ASN1Block::Sequence(_, fields)] => fields.clone(),
+        _ => return Err(invalid()),
+    };
+    let (ca, path_len) = match fields.as_slice() {
+        [] => (false, None),
+        [ASN1Block::Boolean(_, ca)] => (*ca, None),
+        [ASN1Block::Integer(_, path_len)] => (false, Some(path_len)),
+        [ASN1Block::Boolean(_, ca), ASN1Block::Integer(_, path_len)] => (*ca, Some(path_len)),
+        _ => return Err(invalid()),
+    };
+    let path_len = path_len
+        .map(|path_len| usize::try_from(path_len).map_err(|_| invalid()))
+        .transpose()?;
+    Ok((ca, path_len))
+}
+
+/// Whether the keyUsage extension (OID 2.5.29.15) asserts keyCertSign. A certificate without
+/// the extension does not.
+fn key_cert_sign(tbs_fields: &[&[u8]]) -> Pkcs7Result<bool> {
+    let Some(value) = extension_value(tbs_fields, &oid!(2, 5, 29, 15))? else {
+        return Ok(false);
+    };
+    match from_der(&value)?.as_slice() {
+        // keyCertSign is bit 5, counting from the most significant bit of the first byte
+        [ASN1Block::BitString(_, _, bits)] => Ok(bits.first().is_some_and(|bits| bits & 0x04 != 0)),
+        _ => Err(Pkcs7Error::structure("Invalid keyUsage extension")),
+    }
 }
 
+/// `parse_signed_data_at` for the first SignerInfo.
 pub fn parse_signed_data(der_bytes: &[u8]) -> Pkcs7Result<VerifierParams> {
+    parse_signed_data_at(der_bytes, 0)
+}
+
+/// The SignerInfo at `index` of a PKCS#7 SignedData blob, with the key of the certificate it
+/// names.
+pub fn parse_signed_data_at(der_bytes: &[u8], index: usize) -> Pkcs7Result<VerifierParams> {
     let blocks = from_der(der_bytes)?;
 
     let content_info = extract_content_info(&blocks)?;
     let signed_children = extract_signed_children(content_info)?;
-    let signature_data = get_signature_data(signed_children.clone())?;
+    let signature_data = get_signature_data(der_bytes, &signed_children, index)?;
 
-    let (modulus_bytes, exponent_big) =
-        extract_pubkey_components(&signed_children, &signature_data.signer_serial)?;
+    let key = extract_signer_key(&signer_tbs_fields(der_bytes, index)?)?;
 
     Ok(VerifierParams {
-        modulus: modulus_bytes,
-        exponent: exponent_big,
+        key,
         signature: signature_data.signature,
         signed_attr_digest: signature_data.digest_bytes,
+        signed_attrs_der: signature_data.signed_attrs_der,
         algorithm: signature_data.signed_algo,
         signed_data_message_digest: signature_data.expected_message_digest,
+        signing_time: signature_data.signing_time,
+        timestamp_token: signature_timestamp_token(der_bytes, index)?,
     })
 }
 
+/// Number of SignerInfos in a PKCS#7 SignedData blob.
+pub fn signer_info_count(der_bytes: &[u8]) -> Pkcs7Result<usize> {
+    Ok(signer_infos_der(der_bytes)?.len())
+}
+
+/// The SignerInfos of a PKCS#7 SignedData blob, as encoded.
+fn signer_infos_der(der_bytes: &[u8]) -> Pkcs7Result<Vec<&[u8]>> {
+    // SignedData ends with signerInfos
+    let signed_data = signed_data_elements(der_bytes)?;
+    let signer_infos = signed_data
+        .last()
+        .ok_or_else(|| Pkcs7Error::structure("SignerInfos not found"))?;
+    Ok(der_children(signer_infos)?)
+}
+
+/// The SignerInfo at `index`, as encoded.
+fn signer_info_der(der_bytes: &[u8], index: usize) -> Pkcs7Result<&[u8]> {
+    signer_infos_der(der_bytes)?
+        .get(index)
+        .copied()
+        .ok_or_else(|| Pkcs7Error::structure("No SignerInfo found"))
+}
+
+/// Parses the signer's certificate out of a PKCS#7 SignedData blob without verifying anything.
+pub fn parse_signer_details(der_bytes: &[u8]) -> Pkcs7Result<SignerDetails> {
+    parse_signer_details_at(der_bytes, 0)
+}
+
+/// `parse_signer_details` for the certificate of the SignerInfo at `index`.
+pub fn parse_signer_details_at(der_bytes: &[u8], index: usize) -> Pkcs7Result<SignerDetails> {
+    let blocks = from_der(der_bytes)?;
+
+    let content_info = extract_content_info(&blocks)?;
+    let signed_children = extract_signed_children(content_info)?;
+    let signature_data = get_signature_data(der_bytes, &signed_children, index)?;
+
+    certificate_details(
+        signer_certificate_der(der_bytes, index)?,
+        signature_data.signed_algo,
+    )
+}
+
+/// `SignerDetails` of a DER certificate on its own, such as one an XML signature's KeyInfo
+/// carries. The certificate does not say which algorithm its key signed with, so the caller
+/// passes `signature_algorithm`.
+pub fn certificate_details(
+    certificate: &[u8],
+    signature_algorithm: SignatureAlgorithm,
+) -> Pkcs7Result<SignerDetails> {
+    let tbs_fields = tbs_fields(certificate)?;
+    let key = extract_signer_key(&tbs_fields)?;
+
+    // tbsCertificate: version, serialNumber, signature, issuer, validity, subject, ...
+    let serial_number = match tbs_fields.get(1) {
+        Some(ASN1Block::Integer(_, serial)) => BigUint::from_bytes_be(&serial.to_signed_bytes_be()),
+        _ => return Err(Pkcs7Error::structure("Serial number not found")),
+    };
+    let issuer = tbs_fields
+        .get(3)
+        .ok_or_else(|| Pkcs7Error::structure("Certificate issuer not found"))?;
+    let subject = tbs_fields
+        .get(5)
+        .ok_or_else(|| Pkcs7Error::structure("Certificate subject not found"))?;
+    let (not_before, not_after) = match tbs_fields.get(4) {
+        Some(ASN1Block::Sequence(_, times)) if times.len() == 2 => {
+            (format_time(&times[0])?, format_time(&times[1])?)
+        }
+        _ => return Err(Pkcs7Error::structure("Certificate validity not found")),
+    };
+
+    Ok(SignerDetails {
+        subject: format_name(subject)?,
+        issuer: format_name(issuer)?,
+        serial_number: serial_number.to_bytes_be(),
+        not_before,
+        not_after,
+        signature_algorithm,
+        key_size: match key {
+            SignerKey::Rsa { modulus, .. } => BigUint::from_bytes_be(&modulus).bits(),
+            SignerKey::Ed25519(_) => 256,
+        },
+        spki: certificate_spki_der(certificate)?.to_vec(),
+        certificate: certificate.to_vec(),
+    })
+}
+
+/// The TSTInfo carried by an RFC 3161 timestamp token.
+pub struct TimestampInfo {
+    /// The DER TSTInfo, which the token's messageDigest attribute covers
+    pub tst_info_der: Vec<u8>,
+    pub hash_algorithm: SignatureAlgorithm,
+    pub message_imprint: Vec<u8>,
+    pub serial_number: Vec<u8>,
+    pub gen_time: String,
+}
+
+/// Reads the TSTInfo out of a timestamp token, a SignedData whose encapsulated content has
+/// type id-ct-TSTInfo. The token's signature is not verified.
+pub fn parse_timestamp_token(der_bytes: &[u8]) -> Pkcs7Result<TimestampInfo> {
+    let blocks = from_der(der_bytes)?;
+
+    let content_info = extract_content_info(&blocks)?;
+    let signed_children = extract_signed_children(content_info)?;
+
+    // SignedData: version, digestAlgorithms, encapContentInfo, ...
+    let encap_content_info = match signed_children.get(2) {
+        Some(ASN1Block::Sequence(_, items)) => items,
+        _ => return Err(Pkcs7Error::structure("encapContentInfo not found")),
+    };
+    match encap_content_info.first() {
+        Some(ASN1Block::ObjectIdentifier(_, content_type))
+            if *content_type == oid!(1, 2, 840, 113549, 1, 9, 16, 1, 4) => {}
+        _ => {
+            return Err(Pkcs7Error::structure(
+                "Not a timestamp token: eContentType is not id-ct-TSTInfo",
+            ))
+        }
+    }
+    let tst_info_der = match encap_content_info.get(1) {
+        Some(ASN1Block::Explicit(ASN1Class::ContextSpecific, _, _, inner)) => {
+            match inner.as_ref() {
+                ASN1Block::OctetString(_, bytes) => bytes.clone(),
+                _ => {
+                    return Err(Pkcs7Error::structure(
+                        "TSTInfo eContent not an OCTET STRING",
+                    ))
+                }
+            }
+        }
+        _ => return Err(Pkcs7Error::structure("TSTInfo eContent missing")),
+    };
+
+    // TSTInfo: version, policy, messageImprint, serialNumber, genTime, ...
+    let tst_info = from_der(&tst_info_der)?;
+    let fields = match tst_info.first() {
+        Some(ASN1Block::Sequence(_, fields)) if fields.len() >= 5 => fields,
+        _ => return Err(Pkcs7Error::structure("TSTInfo not a SEQUENCE")),
+    };
+    let (hash_algorithm, message_imprint) = match &fields[2] {
+        ASN1Block::Sequence(_, imprint) => match imprint.as_slice() {
+            [ASN1Block::Sequence(_, algorithm), ASN1Block::OctetString(_, hash)] => {
+                match algorithm.first() {
+                    Some(ASN1Block::ObjectIdentifier(_, hash_oid)) => {
+                        (digest_algorithm_from_oid(hash_oid)?, hash.clone())
+                    }
+                    _ => return Err(Pkcs7Error::structure("Invalid messageImprint algorithm")),
+                }
+            }
+            _ => return Err(Pkcs7Error::structure("Invalid messageImprint")),
+        },
+        _ => return Err(Pkcs7Error::structure("messageImprint not a SEQUENCE")),
+    };
+    let serial_number = match &fields[3] {
+        ASN1Block::Integer(_, serial) => {
+            BigUint::from_bytes_be(&serial.to_signed_bytes_be()).to_bytes_be()
+        }
+        _ => return Err(Pkcs7Error::structure("TSTInfo serialNumber not an INTEGER")),
+    };
+    let gen_time = match &fields[4] {
+        time @ ASN1Block::GeneralizedTime(..) => format_time(time)?,
+        _ => {
+            return Err(Pkcs7Error::structure(
+                "TSTInfo genTime not a GeneralizedTime",
+            ))
+        }
+    };
+
+    Ok(TimestampInfo {
+        tst_info_der,
+        hash_algorithm,
+        message_imprint,
+        serial_number,
+        gen_time,
+    })
+}
+
+/// A certificate with the parts needed to check the signature of its issuer over it.
+pub(crate) struct Certificate<'a> {
+    pub der: &'a [u8],
+    pub tbs_der: &'a [u8],
+    pub subject: String,
+    pub issuer: String,
+    /// The subject and issuer Names as encoded, which OCSP and CRLs identify issuers by.
+    pub subject_der: &'a [u8],
+    pub issuer_der: &'a [u8],
+    pub serial_number: Vec<u8>,
+    pub algorithm: SignatureAlgorithm,
+    pub signature: Vec<u8>,
+    /// The subjectPublicKey BIT STRING contents.
+    pub public_key: Vec<u8>,
+    pub modulus: Vec<u8>,
+    pub exponent: BigUint,
+    /// basicConstraints cA, and pathLenConstraint, the most CA certificates that may follow
+    /// it on the way down to an end entity.
+    pub ca: bool,
+    pub path_len: Option<usize>,
+    /// keyUsage keyCertSign.
+    pub key_cert_sign: bool,
+}
+
+/// Parses a DER certificate, keeping the tbsCertificate bytes its signature is over.
+pub(crate) fn parse_certificate(der: &[u8]) -> Pkcs7Result<Certificate<'_>> {
+    let [tbs_der, algorithm, signature] = der_children(der)?[..] else {
+        return Err(Pkcs7Error::structure(
+            "Certificate is not a 3-element SEQUENCE",
+        ));
+    };
+    let tbs_fields = match from_der(tbs_der)?.as_slice() {
+        [ASN1Block::Sequence(_, fields)] => fields.clone(),
+        _ => return Err(Pkcs7Error::structure("tbsCertificate not a SEQUENCE")),
+    };
+    let algorithm = match from_der(algorithm)?.as_slice() {
+        [ASN1Block::Sequence(_, fields)] => match fields.first() {
+            Some(ASN1Block::ObjectIdentifier(_, algorithm_oid)) => {
+                signature_algorithm_from_oid(algorithm_oid)
+            }
+            _ => {
+                return Err(Pkcs7Error::structure(
+                    "Certificate signature algorithm missing",
+                ))
+            }
+        },
+        _ => {
+            return Err(Pkcs7Error::structure(
+                "Certificate signature algorithm missing",
+            ))
+        }
+    };
+    let signature = match from_der(signature)?.as_slice() {
+        [ASN1Block::BitString(_, _, bits)] => bits.clone(),
+        _ => {
+            return Err(Pkcs7Error::structure(
+                "Certificate signature not a BIT STRING",
+            ))
+        }
+    };
+
+    // tbsCertificate: [0] version, serialNumber, signature, issuer, validity, subject, ...
+    let field = |index: usize, what: &str| {
+        tbs_fields
+            .get(index)
+            .ok_or_else(|| Pkcs7Error::structure(format!("Certificate {} not found", what)))
+    };
+    let serial_number = match field(1, "serial number")? {
+        ASN1Block::Integer(_, serial) => {
+            BigUint::from_bytes_be(&serial.to_signed_bytes_be()).to_bytes_be()
+        }
+        _ => return Err(Pkcs7Error::structure("Serial number not found")),
+    };
+    let spki_fields = find_subject_public_key_info(&tbs_fields)?;
+    let public_key = extract_public_key_bitstring(spki_fields)?;
+    let rsa_sequence = parse_rsa_public_key(&public_key)?;
+    let raw_fields = der_children(tbs_der)?;
+    let raw_field = |index: usize, what: &str| {
+        raw_fields
+            .get(index)
+            .copied()
+            .ok_or_else(|| Pkcs7Error::structure(format!("Certificate {} not found", what)))
+    };
+    // A certificate whose constraints cannot be read may still be a signer's, but not issue one
+    let (ca, path_len) = basic_constraints(&raw_fields).unwrap_or((false, None));
+
+    Ok(Certificate {
+        der,
+        tbs_der,
+        subject: format_name(field(5, "subject")?)?,
+        issuer: format_name(field(3, "issuer")?)?,
+        subject_der: raw_field(5, "subject")?,
+        issuer_der: raw_field(3, "issuer")?,
+        serial_number,
+        algorithm,
+        signature,
+        public_key,
+        modulus: extract_modulus(&rsa_sequence)?,
+        exponent: extract_exponent(&rsa_sequence)?,
+        ca,
+        path_len,
+        key_cert_sign: key_cert_sign(&raw_fields).unwrap_or(false),
+    })
+}
+
+/// The elements of the SignedData in a PKCS#7 ContentInfo, as encoded.
+fn signed_data_elements(der_bytes: &[u8]) -> Pkcs7Result<Vec<&[u8]>> {
+    let content_info = split_der(der_bytes)?
+        .first()
+        .copied()
+        .ok_or_else(|| Pkcs7Error::structure("Top-level not a SEQUENCE"))?;
+    let [_, content] = der_children(content_info)?[..] else {
+        return Err(Pkcs7Error::structure("Missing SignedData content"));
+    };
+    let [signed_data] = der_children(content)?[..] else {
+        return Err(Pkcs7Error::structure("Explicit SignedData not a SEQUENCE"));
+    };
+    Ok(der_children(signed_data)?)
+}
+
+/// The DER certificates carried in a PKCS#7 SignedData blob, in the order they appear.
+/// Attribute and other certificates, which are tagged rather than SEQUENCEs, are skipped.
+pub(crate) fn signed_data_certificates(der_bytes: &[u8]) -> Pkcs7Result<Vec<&[u8]>> {
+    // SignedData: version, digestAlgorithms, encapContentInfo, [0] certificates, ...
+    match signed_data_elements(der_bytes)?
+        .into_iter()
+        .find(|element| element.first() == Some(&0xA0))
+    {
+        Some(certificates) => Ok(der_children(certificates)?
+            .into_iter()
+            .filter(|certificate| certificate.first() == Some(&0x30))
+            .collect()),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// The DER of the certificate the SignerInfo at `index` names, by issuer and serial number or
+/// by subject key identifier.
+pub fn signer_certificate_der(der_bytes: &[u8], index: usize) -> Pkcs7Result<&[u8]> {
+    let signer_id = SignerId::at(der_bytes, index)?;
+    for certificate in signed_data_certificates(der_bytes)? {
+        if signer_id.names(certificate).unwrap_or(false) {
+            return Ok(certificate);
+        }
+    }
+    Err(Pkcs7Error::structure("No matching certificate found"))
+}
+
+/// The tbsCertificate fields of the certificate the SignerInfo at `index` names.
+fn signer_tbs_fields(der_bytes: &[u8], index: usize) -> Pkcs7Result<Vec<ASN1Block>> {
+    tbs_fields(signer_certificate_der(der_bytes, index)?)
+}
+
+fn tbs_fields(certificate: &[u8]) -> Pkcs7Result<Vec<ASN1Block>> {
+    match from_der(certificate)?.as_slice() {
+        [ASN1Block::Sequence(_, cert_fields)] => match cert_fields.first() {
+            Some(ASN1Block::Sequence(_, tbs_fields)) => Ok(tbs_fields.clone()),
+            _ => Err(Pkcs7Error::structure("tbsCertificate not found")),
+        },
+        _ => Err(Pkcs7Error::structure("Certificate not a SEQUENCE")),
+    }
+}
+
+/// The signer certificate as an ESS signingCertificate or signingCertificateV2 signed
+/// attribute identifies it (RFC 2634, RFC 5035): the hash of its DER, from the first
+/// ESSCertID of the attribute.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SigningCertificate {
+    /// signingCertificateV2 (OID 1.2.840.113549.1.9.16.2.47) rather than signingCertificate
+    /// (OID 1.2.840.113549.1.9.16.2.12), which always uses SHA-1.
+    pub v2: bool,
+    pub hash_algorithm: SignatureAlgorithm,
+    pub cert_hash: Vec<u8>,
+}
+
+/// Every signingCertificate and signingCertificateV2 attribute among `signed_attrs_der`.
+pub fn signing_certificates(signed_attrs_der: &[u8]) -> Pkcs7Result<Vec<SigningCertificate>> {
+    let mut found = Vec::new();
+    for attribute in der_children(signed_attrs_der)? {
+        let [attr_oid, values] = der_children(attribute)?[..] else {
+            return Err(Pkcs7Error::structure("Invalid signed attribute"));
+        };
+        let v2 = match from_der(attr_oid)?.as_slice() {
+            [ASN1Block::ObjectIdentifier(_, oid)]
+                if *oid == oid!(1, 2, 840, 113549, 1, 9, 16, 2, 12) =>
+            {
+                false
+            }
+            [ASN1Block::ObjectIdentifier(_, oid)]
+                if *oid == oid!(1, 2, 840, 113549, 1, 9, 16, 2, 47) =>
+            {
+                true
+            }
+            _ => continue,
+        };
+        let invalid = || Pkcs7Error::structure("Invalid signingCertificate attribute");
+        // SigningCertificate[V2] ::= SEQUENCE { certs SEQUENCE OF ESSCertID[v2], policies ... }
+        let signing_certificate = *der_children(values)?.first().ok_or_else(invalid)?;
+        let certs = *der_children(signing_certificate)?
+            .first()
+            .ok_or_else(invalid)?;
+        let cert_id = *der_children(certs)?.first().ok_or_else(invalid)?;
+        // ESSCertIDv2 starts with an optional hashAlgorithm, SHA-256 when left out
+        let (hash_algorithm, cert_hash) = match der_children(cert_id)?.as_slice() {
+            [algorithm, cert_hash, ..] if v2 && algorithm.first() == Some(&0x30) => {
+                let [algorithm_oid, ..] = der_children(algorithm)?[..] else {
+                    return Err(invalid());
+                };
+                match from_der(algorithm_oid)?.as_slice() {
+                    [ASN1Block::ObjectIdentifier(_, oid)] => {
+                        (digest_algorithm_from_oid(oid)?, *cert_hash)
+                    }
+                    _ => return Err(invalid()),
+                }
+            }
+            [cert_hash, ..] if v2 => (SignatureAlgorithm::Sha256WithRsaEncryption, *cert_hash),
+            [cert_hash, ..] => (SignatureAlgorithm::Sha1WithRsaEncryption, *cert_hash),
+            [] => return Err(invalid()),
+        };
+        match from_der(cert_hash)?.as_slice() {
+            [ASN1Block::OctetString(_, hash)] => found.push(SigningCertificate {
+                v2,
+                hash_algorithm,
+                cert_hash: hash.clone(),
+            }),
+            _ => return Err(invalid()),
+        }
+    }
+    Ok(found)
+}
+
+/// The token in the timeStampToken unsigned attribute (OID 1.2.840.113549.1.9.16.2.14) of the
+/// SignerInfo at `index`, as encoded, since its signature is checked over the exact bytes.
+fn signature_timestamp_token(der_bytes: &[u8], index: usize) -> Pkcs7Result<Option<Vec<u8>>> {
+    let Some(unsigned_attrs) =
+        SignerInfoFields::parse(signer_info_der(der_bytes, index)?)?.unsigned_attrs
+    else {
+        return Ok(None);
+    };
+    for attribute in der_children(unsigned_attrs)? {
+        let [attr_oid, values] = der_children(attribute)?[..] else {
+            return Err(Pkcs7Error::structure("Invalid unsigned attribute"));
+        };
+        if let [ASN1Block::ObjectIdentifier(_, oid)] = from_der(attr_oid)?.as_slice() {
+            if *oid == oid!(1, 2, 840, 113549, 1, 9, 16, 2, 14) {
+                return match der_children(values)?.first() {
+                    Some(token) => Ok(Some(token.to_vec())),
+                    None => Err(Pkcs7Error::structure("timeStampToken has no value")),
+                };
+            }
+        }
+    }
+    Ok(None)
+}
+
+pub(crate) fn signature_algorithm_from_oid(algorithm_oid: &asn1::OID) -> SignatureAlgorithm {
+    if algorithm_oid == &oid!(1, 2, 840, 113549, 1, 1, 5) {
+        SignatureAlgorithm::Sha1WithRsaEncryption
+    } else if algorithm_oid == &oid!(1, 2, 840, 113549, 1, 1, 11) {
+        SignatureAlgorithm::Sha256WithRsaEncryption
+    } else if algorithm_oid == &oid!(1, 2, 840, 113549, 1, 1, 12) {
+        SignatureAlgorithm::Sha384WithRsaEncryption
+    } else if algorithm_oid == &oid!(1, 2, 840, 113549, 1, 1, 13) {
+        SignatureAlgorithm::Sha512WithRsaEncryption
+    } else if algorithm_oid == &oid!(1, 3, 101, 112) {
+        SignatureAlgorithm::Ed25519
+    } else {
+        SignatureAlgorithm::Unknown(algorithm_oid.clone())
+    }
+}
+
+/// Formats an X.501 Name as comma separated `KEY=value` pairs in certificate order.
+fn format_name(name: &ASN1Block) -> Pkcs7Result<String> {
+    let rdns = match name {
+        ASN1Block::Sequence(_, rdns) => rdns,
+        other => {
+            return Err(Pkcs7Error::structure(format!(
+                "Expected Name SEQUENCE, got {:?}",
+                other
+            )))
+        }
+    };
+
+    let mut parts = Vec::new();
+    for rdn in rdns {
+        let ASN1Block::Set(_, attributes) = rdn else {
+            return Err(Pkcs7Error::structure(
+                "Expected RelativeDistinguishedName SET",
+            ));
+        };
+        for attribute in attributes {
+            if let ASN1Block::Sequence(_, items) = attribute {
+                if let [ASN1Block::ObjectIdentifier(_, attr_oid), value] = items.as_slice() {
+                    parts.push(format!(
+                        "{}={}",
+                        attribute_name(attr_oid),
+                        string_value(value)
+                    ));
+                }
+            }
+        }
+    }
+    Ok(parts.join(", "))
+}
+
+fn attribute_name(attr_oid: &asn1::OID) -> String {
+    let name = if *attr_oid == oid!(2, 5, 4, 3) {
+        "CN"
+    } else if *attr_oid == oid!(2, 5, 4, 5) {
+        "serialNumber"
+    } else if *attr_oid == oid!(2, 5, 4, 6) {
+        "C"
+    } else if *attr_oid == oid!(2, 5, 4, 7) {
+        "L"
+    } else if *attr_oid == oid!(2, 5, 4, 8) {
+        "ST"
+    } else if *attr_oid == oid!(2, 5, 4, 10) {
+        "O"
+    } else if *attr_oid == oid!(2, 5, 4, 11) {
+        "OU"
+    } else if *attr_oid == oid!(1, 2, 840, 113549, 1, 9, 1) {
+        "emailAddress"
+    } else {
+        return match attr_oid.as_vec::<u64>() {
+            Ok(arcs) => arcs
+                .iter()
+                .map(|arc| arc.to_string())
+                .collect::<Vec<_>>()
+                .join("."),
+            Err(_) => format!("{:?}", attr_oid),
+        };
+    };
+    name.to_string()
+}
+
+fn string_value(value: &ASN1Block) -> String {
+    match value {
+        ASN1Block::UTF8String(_, s)
+        | ASN1Block::PrintableString(_, s)
+        | ASN1Block::TeletexString(_, s)
+        | ASN1Block::IA5String(_, s)
+        | ASN1Block::UniversalString(_, s)
+        | ASN1Block::BMPString(_, s) => s.clone(),
+        ASN1Block::OctetString(_, bytes) => hex::encode(bytes),
+        other => format!("{:?}", other),
+    }
+}
+
+/// Formats a UTCTime or GeneralizedTime as an ISO 8601 UTC timestamp.
+pub(crate) fn format_time(time: &ASN1Block) -> Pkcs7Result<String> {
+    match time {
+        ASN1Block::UTCTime(_, t) | ASN1Block::GeneralizedTime(_, t) => Ok(format!(
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+            t.year(),
+            t.month(),
+            t.day(),
+            t.hour(),
+            t.minute(),
+            t.second()
+        )),
+        other => Err(Pkcs7Error::structure(format!(
+            "Expected validity time, got {:?}",
+            other
+        ))),
+    }
+}
+
 struct SignatureData {
     signature: Vec<u8>,
-    signer_serial: BigUint,
     digest_bytes: Option<Vec<u8>>,
+    signed_attrs_der: Option<Vec<u8>>,
     signed_algo: SignatureAlgorithm,
     expected_message_digest: Option<Vec<u8>>,
+    signing_time: Option<String>,
 }
 
-fn get_signature_data(signed_data_seq: Vec<ASN1Block>) -> Pkcs7Result<SignatureData> {
-    let signer_info_items = extract_signer_info(&signed_data_seq)?;
-    let (signer_serial, digest_oid) = extract_issuer_and_digest_algorithm(signer_info_items)?;
-    let signed_attrs_der = extract_signed_attributes_der(signer_info_items)?;
-    let has_signed_attrs = signed_attrs_der.is_some();
-    let embedded_digest = extract_signed_content_digest(&signed_data_seq)?;
+fn get_signature_data(
+    der_bytes: &[u8],
+    signed_data_seq: &[ASN1Block],
+    index: usize,
+) -> Pkcs7Result<SignatureData> {
+    let signer_info = SignerInfoFields::parse(signer_info_der(der_bytes, index)?)?;
+    let digest_oid = algorithm_oid(signer_info.digest_algorithm)?;
+    // The digest covers the attributes with their universal SET tag in place of [0]
+    let signed_attrs_der = signer_info.signed_attrs.map(|attrs| {
+        let mut der = attrs.to_vec();
+        der[0] = 0x31;
+        der
+    });
+    let embedded_digest = extract_signed_content_digest(signed_data_seq)?;
+    let ed25519 = algorithm_oid(signer_info.signature_algorithm)? == oid!(1, 3, 101, 112);
+    // RFC 8419: Ed25519 signers digest the content with SHA-512
+    if ed25519 && digest_oid != oid!(2, 16, 840, 1, 101, 3, 4, 2, 3) {
+        return Err(Pkcs7Error::UnsupportedDigestOid(digest_oid));
+    }
     let (digest_bytes, signed_algo, expected_message_digest) = match signed_attrs_der.as_ref() {
+        Some(der) if ed25519 => {
+            let signed_attrs = from_der(der)?;
+            let message_digest = extract_message_digest(&signed_attrs)?;
+            (None, SignatureAlgorithm::Ed25519, Some(message_digest))
+        }
+        // Without signed attributes, Ed25519 signs the content itself
+        None if ed25519 => (None, SignatureAlgorithm::Ed25519, None),
         Some(der) => {
             let (digest, algo) = compute_signed_attributes_digest(der, &digest_oid)?;
             let signed_attrs = from_der(der)?;
@@ -88,102 +844,100 @@ fn get_signature_data(signed_data_seq: Vec<ASN1Block>) -> Pkcs7Result<SignatureD
             (Some(signed_digest), algo, Some(digest))
         }
     };
-    let signature = extract_signature(signer_info_items, has_signed_attrs)?;
+    let signature = match from_der(signer_info.signature)?.as_slice() {
+        [ASN1Block::OctetString(_, signature)] => signature.clone(),
+        _ => {
+            return Err(Pkcs7Error::structure(
+                "EncryptedDigest (signature) not an OCTET STRING",
+            ))
+        }
+    };
+    let signing_time = match signed_attrs_der.as_ref() {
+        Some(der) => extract_signing_time(&from_der(der)?)?,
+        None => None,
+    };
 
     Ok(SignatureData {
         signature,
-        signer_serial,
         digest_bytes,
+        signed_attrs_der,
         signed_algo,
         expected_message_digest,
+        signing_time,
     })
 }
 
-fn extract_signer_info(signed_data_seq: &Vec<ASN1Block>) -> Pkcs7Result<&Vec<ASN1Block>> {
-    match signed_data_seq.last() {
-        Some(ASN1Block::Set(_, items)) => match items.first() {
-            Some(ASN1Block::Sequence(_, signer_info)) => Ok(signer_info),
-            _ => Err(Pkcs7Error::structure(
-                "Expected SignerInfo SEQUENCE in SignerInfo SET",
-            )),
-        },
-        _ => Err(Pkcs7Error::structure(
-            "Expected SignerInfo SET in SignedData",
-        )),
-    }
+/// The fields of a SignerInfo (RFC 5652 section 5.3), as encoded. They are told apart by tag
+/// rather than position, since the optional signedAttrs shift the fields after them.
+struct SignerInfoFields<'a> {
+    sid: &'a [u8],
+    digest_algorithm: &'a [u8],
+    /// `[0] IMPLICIT SET OF Attribute`
+    signed_attrs: Option<&'a [u8]>,
+    signature_algorithm: &'a [u8],
+    signature: &'a [u8],
+    /// `[1] IMPLICIT SET OF Attribute`
+    unsigned_attrs: Option<&'a [u8]>,
 }
 
-fn extract_issuer_and_digest_algorithm(
-    signer_info: &Vec<ASN1Block>,
-) -> Pkcs7Result<(BigUint, simple_asn1::OID)> {
-    let (_, signer_serial) = match &signer_info[1] {
-        ASN1Block::Sequence(_, parts) if parts.len() == 2 => {
-            let serial = match &parts[1] {
-                ASN1Block::Integer(_, big_int) => {
-                    BigUint::from_bytes_be(&big_int.to_signed_bytes_be())
-                }
-                other => {
-                    return Err(Pkcs7Error::structure(format!(
-                        "Expected serialNumber INTEGER, got {:?}",
-                        other
-                    )))
-                }
-            };
-            (parts[0].clone(), serial)
-        }
-        other => {
-            return Err(Pkcs7Error::structure(format!(
-                "Expected issuerAndSerialNumber SEQUENCE, got {:?}",
-                other
-            )))
-        }
-    };
+impl<'a> SignerInfoFields<'a> {
+    fn parse(signer_info: &'a [u8]) -> Pkcs7Result<Self> {
+        let children = der_children(signer_info)?;
+        let mut fields = children.as_slice();
+        let missing = |what: &str| Pkcs7Error::structure(format!("SignerInfo {} not found", what));
 
-    let digest_oid = if let ASN1Block::Sequence(_, items) = &signer_info[2] {
-        if let ASN1Block::ObjectIdentifier(_, oid) = &items[0] {
-            oid.clone()
-        } else {
-            return Err(Pkcs7Error::structure(
-                "Invalid digestAlgorithm in SignerInfo",
-            ));
+        take_field(&mut fields, &[0x02]).ok_or_else(|| missing("version"))?;
+        // issuerAndSerialNumber or [0] IMPLICIT subjectKeyIdentifier
+        let sid = take_field(&mut fields, &[0x30, 0x80]).ok_or_else(|| missing("sid"))?;
+        let digest_algorithm =
+            take_field(&mut fields, &[0x30]).ok_or_else(|| missing("digestAlgorithm"))?;
+        let signed_attrs = take_field(&mut fields, &[0xA0]);
+        let signature_algorithm =
+            take_field(&mut fields, &[0x30]).ok_or_else(|| missing("signatureAlgorithm"))?;
+        let signature = take_field(&mut fields, &[0x04]).ok_or_else(|| missing("signature"))?;
+        let unsigned_attrs = take_field(&mut fields, &[0xA1]);
+        if let Some(field) = fields.first() {
+            return Err(Pkcs7Error::structure(format!(
+                "Unexpected SignerInfo field with tag 0x{:02X}",
+                field.first().copied().unwrap_or_default()
+            )));
         }
-    } else {
-        return Err(Pkcs7Error::structure("Digest algorithm missing"));
-    };
 
-    Ok((signer_serial, digest_oid))
+        Ok(Self {
+            sid,
+            digest_algorithm,
+            signed_attrs,
+            signature_algorithm,
+            signature,
+            unsigned_attrs,
+        })
+    }
 }
 
-fn extract_signed_attributes_der(signer_info: &Vec<ASN1Block>) -> Pkcs7Result<Option<Vec<u8>>> {
-    for block in signer_info {
-        if let ASN1Block::Unknown(ASN1Class::ContextSpecific, true, _len, tag_no, content) = block {
-            if tag_no == &BigUint::from(0u8) {
-                let mut out = Vec::with_capacity(content.len() + 4);
-                out.push(0x31); // SET tag
-
-                let len = content.len();
-                if len < 128 {
-                    out.push(len as u8);
-                } else if len <= 0xFF {
-                    out.push(0x81);
-                    out.push(len as u8);
-                } else {
-                    out.push(0x82);
-                    out.push((len >> 8) as u8);
-                    out.push((len & 0xFF) as u8);
-                }
+/// Takes the next of `fields` if its tag is one of `tags`.
+fn take_field<'a>(fields: &mut &[&'a [u8]], tags: &[u8]) -> Option<&'a [u8]> {
+    let (field, rest) = fields.split_first()?;
+    if !tags.contains(field.first()?) {
+        return None;
+    }
+    *fields = rest;
+    Some(*field)
+}
 
-                out.extend_from_slice(content);
-                return Ok(Some(out));
-            }
-        }
+/// The OID of an encoded AlgorithmIdentifier.
+fn algorithm_oid(algorithm: &[u8]) -> Pkcs7Result<asn1::OID> {
+    match from_der(algorithm)?.as_slice() {
+        [ASN1Block::Sequence(_, items)] => match items.first() {
+            Some(ASN1Block::ObjectIdentifier(_, algorithm_oid)) => Ok(algorithm_oid.clone()),
+            _ => Err(Pkcs7Error::structure("AlgorithmIdentifier has no OID")),
+        },
+        _ => Err(Pkcs7Error::structure("AlgorithmIdentifier not a SEQUENCE")),
     }
-    Ok(None)
 }
 
 fn compute_signed_attributes_digest(
     signed_attrs_der: &[u8],
-    digest_oid: &simple_asn1::OID,
+    digest_oid: &asn1::OID,
 ) -> Pkcs7Result<(Vec<u8>, SignatureAlgorithm)> {
     let algorithm = digest_algorithm_from_oid(digest_oid)?;
     let digest = match algorithm {
@@ -213,18 +967,7 @@ fn compute_signed_attributes_digest(
     Ok((digest, algorithm))
 }
 
-fn extract_signature(signer_info: &Vec<ASN1Block>, has_signed_attrs: bool) -> Pkcs7Result<Vec<u8>> {
-    let sig_index = if has_signed_attrs { 5 } else { 4 };
-    if let Some(ASN1Block::OctetString(_, s)) = signer_info.get(sig_index) {
-        Ok(s.clone())
-    } else {
-        Err(Pkcs7Error::structure(
-            "EncryptedDigest (signature) not found",
-        ))
-    }
-}
-
-fn digest_algorithm_from_oid(digest_oid: &simple_asn1::OID) -> Pkcs7Result<SignatureAlgorithm> {
+pub(crate) fn digest_algorithm_from_oid(digest_oid: &asn1::OID) -> Pkcs7Result<SignatureAlgorithm> {
     if digest_oid == &oid!(1, 3, 14, 3, 2, 26) {
         Ok(SignatureAlgorithm::Sha1WithRsaEncryption)
     } else if digest_oid == &oid!(2, 16, 840, 1, 101, 3, 4, 2, 1) {
@@ -238,7 +981,7 @@ fn digest_algorithm_from_oid(digest_oid: &simple_asn1::OID) -> Pkcs7Result<Signa
     }
 }
 
-fn extract_signed_content_digest(signed_data_seq: &Vec<ASN1Block>) -> Pkcs7Result<Option<Vec<u8>>> {
+fn extract_signed_content_digest(signed_data_seq: &[ASN1Block]) -> Pkcs7Result<Option<Vec<u8>>> {
     for block in signed_data_seq {
         if let ASN1Block::Sequence(_, items) = block {
             if let Some(ASN1Block::ObjectIdentifier(_, oid_val)) = items.get(0) {
@@ -315,117 +1058,39 @@ pub fn extract_signed_children(children: &[ASN1Block]) -> Pkcs7Result<Vec<ASN1Bl
     }
 }
 
-pub fn extract_pubkey_components(
-    signed_data_seq: &Vec<ASN1Block>,
-    signed_serial_number: &BigUint,
-) -> Pkcs7Result<(Vec<u8>, BigUint)> {
-    let certificates = find_certificates(signed_data_seq)?;
-    let tbs_fields = get_correct_tbs(&certificates, signed_serial_number)?;
-    let spki_fields = find_subject_public_key_info(&tbs_fields)?;
-    let public_key_bitstring = extract_public_key_bitstring(spki_fields)?;
-    let rsa_sequence = parse_rsa_public_key(&public_key_bitstring)?;
-    let modulus = extract_modulus(&rsa_sequence)?;
-    let exponent = extract_exponent(&rsa_sequence)?;
-
-    Ok((modulus, exponent))
-}
-
-fn find_certificates(signed_data_seq: &Vec<ASN1Block>) -> Pkcs7Result<Vec<ASN1Block>> {
-    let certs_block = signed_data_seq.iter().find(|block| match block {
-        ASN1Block::Explicit(ASN1Class::ContextSpecific, _, tag, _) => {
-            tag == &simple_asn1::BigUint::from_usize(0).unwrap()
-        }
-        ASN1Block::Unknown(ASN1Class::ContextSpecific, _, _, tag, _) => {
-            tag == &simple_asn1::BigUint::from_usize(0).unwrap()
-        }
-        _ => false,
-    });
-
-    match certs_block {
-        Some(cert_block) => match cert_block {
-            ASN1Block::Unknown(ASN1Class::ContextSpecific, _, _, tag, data)
-                if tag == &BigUint::from(0u8) =>
-            {
-                let parsed_inner = from_der(data).map_err(Pkcs7Error::Der)?;
-                match parsed_inner.as_slice() {
-                    [ASN1Block::Set(_, items)] => Ok(items.clone()),
-                    [ASN1Block::Sequence(_, items)] => Ok(items.clone()),
-                    seqs if seqs.iter().all(|b| matches!(b, ASN1Block::Sequence(_, _))) => {
-                        Ok(seqs.to_vec())
-                    }
-                    other => Err(Pkcs7Error::structure(format!(
-                        "Unexpected structure inside implicit certificate block: {:?}",
-                        other
-                    ))),
-                }
-            }
-            ASN1Block::Explicit(ASN1Class::ContextSpecific, _, tag, inner)
-                if tag == &BigUint::from(0u8) =>
-            {
-                match inner.as_ref() {
-                    ASN1Block::Set(_, certs) => Ok(certs.clone()),
-                    ASN1Block::Sequence(tag, fields) => {
-                        Ok(vec![ASN1Block::Sequence(*tag, fields.clone())])
-                    }
-                    other => Err(Pkcs7Error::structure(format!(
-                        "Expected SET or SEQUENCE inside Explicit certificate block, got {:?}",
-                        other
-                    ))),
-                }
-            }
-            ASN1Block::Set(_, items)
-                if items.iter().all(|i| matches!(i, ASN1Block::Sequence(_, _))) =>
-            {
-                Ok(items.clone())
-            }
-            other => Err(Pkcs7Error::structure(format!(
-                "Unexpected certificates block type: {:?}",
-                other
-            ))),
-        },
-        None => Ok(Vec::new()),
+/// The signer certificate's RSA or Ed25519 public key.
+fn extract_signer_key(tbs_fields: &[ASN1Block]) -> Pkcs7Result<SignerKey> {
+    if let Ok(spki_fields) = find_spki(tbs_fields, &oid!(1, 3, 101, 112)) {
+        let public_key = extract_public_key_bitstring(spki_fields)?;
+        let key = public_key
+            .try_into()
+            .map_err(|_| Pkcs7Error::structure("Ed25519 public key is not 32 bytes"))?;
+        return Ok(SignerKey::Ed25519(key));
     }
+    let spki_fields = find_spki(tbs_fields, &oid!(1, 2, 840, 113549, 1, 1, 1))?;
+    let rsa_sequence = parse_rsa_public_key(&extract_public_key_bitstring(spki_fields)?)?;
+    Ok(SignerKey::Rsa {
+        modulus: extract_modulus(&rsa_sequence)?,
+        exponent: extract_exponent(&rsa_sequence)?,
+    })
 }
 
-fn get_correct_tbs(
-    certificates: &Vec<ASN1Block>,
-    signed_serial_number: &BigUint,
-) -> Pkcs7Result<Vec<ASN1Block>> {
-    for certificate in certificates {
-        let cert_fields = if let ASN1Block::Sequence(_, fields) = certificate {
-            fields
-        } else {
-            return Err(Pkcs7Error::structure("Certificate not a SEQUENCE"));
-        };
-
-        let tbs_fields = match &cert_fields[0] {
-            ASN1Block::Explicit(ASN1Class::ContextSpecific, _, _, _) => cert_fields.clone(),
-            ASN1Block::Sequence(_, seq) => seq.clone(),
-            _ => return Err(Pkcs7Error::structure("tbsCertificate not found")),
-        };
-
-        let serial_number = if let ASN1Block::Integer(_, big_int) = &tbs_fields[1] {
-            BigUint::from_bytes_be(&big_int.to_signed_bytes_be())
-        } else {
-            return Err(Pkcs7Error::structure("Serial number not found"));
-        };
-
-        // Check if the serial number matches the one we are looking for
-        if serial_number == *signed_serial_number {
-            return Ok(tbs_fields);
-        }
-    }
-    Err(Pkcs7Error::structure("No matching certificate found"))
+fn find_subject_public_key_info(tbs_fields: &Vec<ASN1Block>) -> Pkcs7Result<&Vec<ASN1Block>> {
+    find_spki(tbs_fields, &oid!(1, 2, 840, 113549, 1, 1, 1))
 }
 
-fn find_subject_public_key_info(tbs_fields: &Vec<ASN1Block>) -> Pkcs7Result<&Vec<ASN1Block>> {
+/// The subjectPublicKeyInfo fields of a key with algorithm `key_oid`.
+fn find_spki<'a>(
+    tbs_fields: &'a [ASN1Block],
+    key_oid: &asn1::OID,
+) -> Pkcs7Result<&'a Vec<ASN1Block>> {
     tbs_fields
         .iter()
         .find_map(|b| {
             if let ASN1Block::Sequence(_, sf) = b {
-                if let ASN1Block::Sequence(_, alg) = &sf[0] {
-                    if let Some(ASN1Block::ObjectIdentifier(_, o)) = alg.get(0) {
-                        if *o == oid!(1, 2, 840, 113549, 1, 1, 1) {
+                if let Some(ASN1Block::Sequence(_, alg)) = sf.first() {
+                    if let Some(ASN1Block::ObjectIdentifier(_, o)) = alg.first() {
+                        if o == key_oid {
                             return Some(sf);
                         }
                     }
@@ -469,6 +1134,29 @@ fn extract_modulus(rsa_sequence: &Vec<ASN1Block>) -> Pkcs7Result<Vec<u8>> {
     }
 }
 
+/// The signingTime attribute (OID 1.2.840.113549.1.9.5), if the signer included one.
+fn extract_signing_time(attrs: &[ASN1Block]) -> Pkcs7Result<Option<String>> {
+    let candidates = match attrs {
+        [ASN1Block::Set(_, inner)] => inner.as_slice(),
+        _ => attrs,
+    };
+    for attr in candidates {
+        if let ASN1Block::Sequence(_, items) = attr {
+            if let [ASN1Block::ObjectIdentifier(_, oid), ASN1Block::Set(_, values)] =
+                items.as_slice()
+            {
+                if *oid == oid!(1, 2, 840, 113549, 1, 9, 5) {
+                    let time = values
+                        .first()
+                        .ok_or_else(|| Pkcs7Error::structure("signingTime has no value"))?;
+                    return format_time(time).map(Some);
+                }
+            }
+        }
+    }
+    Ok(None)
+}
+
 /// find and return the messageDigest OCTET STRING bytes.
 fn extract_message_digest(attrs: &[ASN1Block]) -> Pkcs7Result<Vec<u8>> {
     let candidates: &[ASN1Block] = if attrs.len() == 1 {
@@ -0,0 +1,305 @@
+//! Revocation checking against the validation data a PAdES-LT document carries in its document
+//! security store (`/DSS`): OCSP responses (RFC 6960) and CRLs (RFC 5280). Nothing is fetched.
+//!
+//! A response or CRL only counts once it is found to be signed by the RSA key of the
+//! certificate that issued the signer certificate, taken from the SignedData or the `/DSS`
+//! certificates. Responses signed by a delegated OCSP responder are not followed, and are
+//! ignored like malformed data.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use extractor::types::DocumentSecurityStore;
+
+use crate::asn1::{der_children, from_der, oid, ASN1Block};
+use crate::calculate_signed_data_hash;
+use crate::pkcs7_parser::{
+    digest_algorithm_from_oid, format_time, integer, parse_certificate,
+    signature_algorithm_from_oid, signed_data_certificates, signer_certificate_der, Certificate,
+};
+use crate::trust_store::{is_signed_by, verifies_with};
+use crate::types::{
+    Pkcs7Error, Pkcs7Result, RevocationSource, RevocationStatus, SignatureAlgorithm,
+    SignatureResult,
+};
+
+/// What one verified OCSP response or CRL says about the signer certificate.
+struct Evidence {
+    source: RevocationSource,
+    /// thisUpdate: when the status was known to be correct.
+    this_update: String,
+    revoked_at: Option<String>,
+}
+
+/// The revocation status, at `signing_time`, of the certificate that made the SignerInfo at
+/// `index` of the PKCS#7 SignedData blob `signature_der`, from the OCSP responses and CRLs of
+/// `dss`. Times are `YYYY-MM-DDTHH:MM:SSZ`; without a signing time, any revocation counts.
+pub fn revocation_status(
+    signature_der: &[u8],
+    index: usize,
+    dss: &DocumentSecurityStore,
+    signing_time: Option<&str>,
+) -> SignatureResult<RevocationStatus> {
+    if dss.ocsps.is_empty() && dss.crls.is_empty() {
+        return Ok(RevocationStatus::Unknown);
+    }
+    // Certificates with keys other than RSA can neither be checked nor check others
+    let Ok(signer) = parse_certificate(signer_certificate_der(signature_der, index)?) else {
+        return Ok(RevocationStatus::Unknown);
+    };
+    let issuers: Vec<Certificate> = signed_data_certificates(signature_der)?
+        .into_iter()
+        .chain(dss.certs.iter().map(Vec::as_slice))
+        .filter_map(|der| parse_certificate(der).ok())
+        .filter(|cert| cert.subject_der == signer.issuer_der && is_signed_by(&signer, cert))
+        .collect();
+
+    let mut evidence = Vec::new();
+    for response in &dss.ocsps {
+        if let Ok(found) = ocsp_evidence(response, &signer, &issuers) {
+            evidence.extend(found);
+        }
+    }
+    for crl in &dss.crls {
+        if let Ok(Some(found)) = crl_evidence(crl, &signer, &issuers) {
+            evidence.push(found);
+        }
+    }
+    Ok(status_at(&evidence, signing_time))
+}
+
+/// A revocation at or before the signing time outweighs everything else. Otherwise the
+/// certificate was good if data from the signing time on says so; a status from before
+/// signing says nothing about the signing time itself.
+fn status_at(evidence: &[Evidence], signing_time: Option<&str>) -> RevocationStatus {
+    // `YYYY-MM-DDTHH:MM:SSZ` orders lexicographically
+    let revoked = evidence.iter().find_map(|found| {
+        let revoked_at = found.revoked_at.as_ref()?;
+        signing_time
+            .is_none_or(|time| revoked_at.as_str() <= time)
+            .then(|| (found.source, revoked_at.clone()))
+    });
+    if let Some((source, revoked_at)) = revoked {
+        return RevocationStatus::Revoked { source, revoked_at };
+    }
+    match evidence
+        .iter()
+        .filter(|found| signing_time.is_none_or(|time| found.this_update.as_str() >= time))
+        .min_by(|a, b| a.this_update.cmp(&b.this_update))
+    {
+        Some(found) => RevocationStatus::Good {
+            source: found.source,
+            checked_at: found.this_update.clone(),
+        },
+        None => RevocationStatus::Unknown,
+    }
+}
+
+/// The statuses an OCSPResponse gives for `signer`, if one of `issuers` signed it. Unknown
+/// statuses are left out.
+fn ocsp_evidence(
+    response: &[u8],
+    signer: &Certificate,
+    issuers: &[Certificate],
+) -> Pkcs7Result<Vec<Evidence>> {
+    // OCSPResponse: responseStatus, [0] EXPLICIT responseBytes
+    let [status, response_bytes] = der_children(response)?[..] else {
+        return Err(Pkcs7Error::structure(
+            "OCSP response carries no responseBytes",
+        ));
+    };
+    if status != [0x0a, 0x01, 0x00] {
+        return Err(Pkcs7Error::structure("OCSP response status not successful"));
+    }
+    // ResponseBytes: responseType, response OCTET STRING
+    let [response_bytes] = der_children(response_bytes)?[..] else {
+        return Err(Pkcs7Error::structure("Invalid OCSP responseBytes"));
+    };
+    let basic = match from_der(response_bytes)?.as_slice() {
+        [ASN1Block::Sequence(_, fields)] => match fields.as_slice() {
+            [ASN1Block::ObjectIdentifier(_, response_type), ASN1Block::OctetString(_, basic)]
+                if *response_type == oid!(1, 3, 6, 1, 5, 5, 7, 48, 1, 1) =>
+            {
+                basic.clone()
+            }
+            _ => {
+                return Err(Pkcs7Error::structure(
+                    "OCSP response is not id-pkix-ocsp-basic",
+                ))
+            }
+        },
+        _ => return Err(Pkcs7Error::structure("Invalid OCSP responseBytes")),
+    };
+
+    // BasicOCSPResponse: tbsResponseData, signatureAlgorithm, signature, [0] certs
+    let basic_fields = der_children(&basic)?;
+    let [tbs, algorithm, signature, ..] = basic_fields[..] else {
+        return Err(Pkcs7Error::structure("Invalid BasicOCSPResponse"));
+    };
+    let algorithm = signature_algorithm(algorithm)?;
+    let signature = bit_string(signature)?;
+    if !issuers
+        .iter()
+        .any(|issuer| verifies_with(tbs, &algorithm, &signature, issuer))
+    {
+        return Ok(Vec::new());
+    }
+
+    // ResponseData: [0] version, responderID, producedAt, responses, [1] extensions
+    let mut data_fields = der_children(tbs)?;
+    if data_fields.first().and_then(|field| field.first()) == Some(&0xA0) {
+        data_fields.remove(0);
+    }
+    let responses = data_fields
+        .get(2)
+        .ok_or_else(|| Pkcs7Error::structure("OCSP responses not found"))?;
+    let mut evidence = Vec::new();
+    for single in der_children(responses)? {
+        // SingleResponse: certID, certStatus, thisUpdate, [0] nextUpdate, [1] extensions
+        let single_fields = der_children(single)?;
+        let [cert_id, cert_status, this_update, ..] = single_fields[..] else {
+            return Err(Pkcs7Error::structure("Invalid OCSP SingleResponse"));
+        };
+        if !issuers
+            .iter()
+            .any(|issuer| names_certificate(cert_id, signer, issuer).unwrap_or(false))
+        {
+            continue;
+        }
+        let revoked_at = match cert_status.first() {
+            // good [0] IMPLICIT NULL
+            Some(0x80) => None,
+            // revoked [1] IMPLICIT RevokedInfo: revocationTime, [0] revocationReason
+            Some(0xA1) => {
+                let revocation_time = der_children(cert_status)?
+                    .first()
+                    .copied()
+                    .ok_or_else(|| Pkcs7Error::structure("OCSP revocationTime not found"))?;
+                Some(time(revocation_time)?)
+            }
+            // unknown [2] IMPLICIT NULL
+            _ => continue,
+        };
+        evidence.push(Evidence {
+            source: RevocationSource::Ocsp,
+            this_update: time(this_update)?,
+            revoked_at,
+        });
+    }
+    Ok(evidence)
+}
+
+/// Whether the OCSP CertID `cert_id` names `signer` as issued by `issuer`: the hashes of the
+/// issuer's Name and public key, and the serial number.
+fn names_certificate(
+    cert_id: &[u8],
+    signer: &Certificate,
+    issuer: &Certificate,
+) -> Pkcs7Result<bool> {
+    let [hash_algorithm, name_hash, key_hash, serial] = der_children(cert_id)?[..] else {
+        return Err(Pkcs7Error::structure("Invalid OCSP CertID"));
+    };
+    let hash_algorithm = match from_der(hash_algorithm)?.as_slice() {
+        [ASN1Block::Sequence(_, fields)] => match fields.first() {
+            Some(ASN1Block::ObjectIdentifier(_, digest_oid)) => {
+                digest_algorithm_from_oid(digest_oid)?
+            }
+            _ => return Err(Pkcs7Error::structure("OCSP CertID hashAlgorithm missing")),
+        },
+        _ => return Err(Pkcs7Error::structure("OCSP CertID hashAlgorithm missing")),
+    };
+    let (Ok(issuer_name_hash), Ok(issuer_key_hash)) = (
+        calculate_signed_data_hash(issuer.subject_der, &hash_algorithm),
+        calculate_signed_data_hash(&issuer.public_key, &hash_algorithm),
+    ) else {
+        return Ok(false);
+    };
+    Ok(octet_string(name_hash)? == issuer_name_hash
+        && octet_string(key_hash)? == issuer_key_hash
+        && integer(serial)?.to_bytes_be() == signer.serial_number)
+}
+
+/// What a CRL says about `signer`, if it is the CRL of the signer certificate's issuer and one
+/// of `issuers` signed it.
+fn crl_evidence(
+    crl: &[u8],
+    signer: &Certificate,
+    issuers: &[Certificate],
+) -> Pkcs7Result<Option<Evidence>> {
+    // CertificateList: tbsCertList, signatureAlgorithm, signatureValue
+    let [tbs, algorithm, signature] = der_children(crl)?[..] else {
+        return Err(Pkcs7Error::structure("CRL is not a 3-element SEQUENCE"));
+    };
+    // TBSCertList: version, signature, issuer, thisUpdate, nextUpdate, revokedCertificates, ...
+    let mut fields = der_children(tbs)?;
+    if fields.first().and_then(|field| field.first()) == Some(&0x02) {
+        fields.remove(0);
+    }
+    let [_, issuer, this_update, ref rest @ ..] = fields[..] else {
+        return Err(Pkcs7Error::structure("Invalid tbsCertList"));
+    };
+    if issuer != signer.issuer_der {
+        return Ok(None);
+    }
+    let algorithm = signature_algorithm(algorithm)?;
+    let signature = bit_string(signature)?;
+    if !issuers
+        .iter()
+        .any(|issuer| verifies_with(tbs, &algorithm, &signature, issuer))
+    {
+        return Ok(None);
+    }
+
+    let mut revoked_at = None;
+    // revokedCertificates is the only SEQUENCE after thisUpdate; nextUpdate is a time
+    if let Some(revoked) = rest.iter().find(|field| field.first() == Some(&0x30)) {
+        for entry in der_children(revoked)? {
+            // userCertificate, revocationDate, crlEntryExtensions
+            let entry_fields = der_children(entry)?;
+            let [serial, revocation_date, ..] = entry_fields[..] else {
+                return Err(Pkcs7Error::structure("Invalid CRL entry"));
+            };
+            if integer(serial)?.to_bytes_be() == signer.serial_number {
+                revoked_at = Some(time(revocation_date)?);
+                break;
+            }
+        }
+    }
+    Ok(Some(Evidence {
+        source: RevocationSource::Crl,
+        this_update: time(this_update)?,
+        revoked_at,
+    }))
+}
+
+fn signature_algorithm(algorithm: &[u8]) -> Pkcs7Result<SignatureAlgorithm> {
+    match from_der(algorithm)?.as_slice() {
+        [ASN1Block::Sequence(_, fields)] => match fields.first() {
+            Some(ASN1Block::ObjectIdentifier(_, algorithm_oid)) => {
+                Ok(signature_algorithm_from_oid(algorithm_oid))
+            }
+            _ => Err(Pkcs7Error::structure("Signature algorithm missing")),
+        },
+        _ => Err(Pkcs7Error::structure("Signature algorithm missing")),
+    }
+}
+
+fn bit_string(der: &[u8]) -> Pkcs7Result<Vec<u8>> {
+    match from_der(der)?.as_slice() {
+        [ASN1Block::BitString(_, _, bits)] => Ok(bits.clone()),
+        _ => Err(Pkcs7Error::structure("Signature not a BIT STRING")),
+    }
+}
+
+fn octet_string(der: &[u8]) -> Pkcs7Result<Vec<u8>> {
+    match from_der(der)?.as_slice() {
+        [ASN1Block::OctetString(_, bytes)] => Ok(bytes.clone()),
+        _ => Err(Pkcs7Error::structure("Expected OCTET STRING")),
+    }
+}
+
+fn time(der: &[u8]) -> Pkcs7Result<String> {
+    match from_der(der)?.as_slice() {
+        [time] => format_time(time),
+        _ => Err(Pkcs7Error::structure("Expected a time")),
+    }
+}
@@ -1,6 +1,14 @@
-use std::str;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::str;
 
-use crate::types::{SignedBytesError, SignedBytesResult};
+use extractor::parser_utils::{parse_hex_string, parse_literal_string};
+use extractor::types::SignatureField;
+use extractor::{decode_text_string, extract_signature_fields};
+
+use crate::types::{
+    SignatureCoverage, SignatureKind, SignedBytesError, SignedBytesResult, SubFilter,
+};
 
 struct ByteRange {
     offset1: usize,
@@ -9,11 +17,7 @@ struct ByteRange {
     len2: usize,
 }
 
-fn parse_byte_range(pdf_bytes: &[u8]) -> SignedBytesResult<ByteRange> {
-    let br_pos = pdf_bytes
-        .windows(b"/ByteRange".len())
-        .position(|w| w == b"/ByteRange")
-        .ok_or(SignedBytesError::ByteRangeNotFound)?;
+fn parse_byte_range(pdf_bytes: &[u8], br_pos: usize) -> SignedBytesResult<ByteRange> {
     let br_start = pdf_bytes[br_pos..]
         .iter()
         .position(|&b| b == b'[')
@@ -33,10 +37,13 @@ fn parse_byte_range(pdf_bytes: &[u8]) -> SignedBytesResult<ByteRange> {
         .filter_map(|s| s.parse().ok())
         .take(4)
         .collect();
-    if nums.len() != 4 {
+    byte_range_from(pdf_bytes, &nums)
+}
+
+fn byte_range_from(pdf_bytes: &[u8], nums: &[usize]) -> SignedBytesResult<ByteRange> {
+    let &[offset1, len1, offset2, len2] = nums else {
         return Err(SignedBytesError::InvalidByteRangeCount);
-    }
-    let [offset1, len1, offset2, len2] = [nums[0], nums[1], nums[2], nums[3]];
+    };
 
     if offset1 + len1 > pdf_bytes.len() || offset2 + len2 > pdf_bytes.len() {
         return Err(SignedBytesError::ByteRangeOutOfBounds);
@@ -50,104 +57,405 @@ fn parse_byte_range(pdf_bytes: &[u8]) -> SignedBytesResult<ByteRange> {
     })
 }
 
-fn extract_signed_data(pdf_bytes: &[u8], byte_range: &ByteRange) -> Vec<u8> {
-    let mut signed_data = Vec::with_capacity(byte_range.len1 + byte_range.len2);
-    signed_data
-        .extend_from_slice(&pdf_bytes[byte_range.offset1..byte_range.offset1 + byte_range.len1]);
-    signed_data
-        .extend_from_slice(&pdf_bytes[byte_range.offset2..byte_range.offset2 + byte_range.len2]);
-    signed_data
-}
-
-fn extract_signature_hex(pdf_bytes: &[u8], byte_range_pos: usize) -> SignedBytesResult<String> {
-    const KEY: &[u8] = b"/Contents";
-    let mut contents_pos = None;
-    let mut cursor_pos = 0;
-
-    let mut search_index = byte_range_pos;
-    while search_index < pdf_bytes.len() {
-        let slice = &pdf_bytes[search_index..];
-        if let Some(offset) = slice.windows(KEY.len()).position(|w| w == KEY) {
-            let pos = search_index + offset;
-            let mut cursor = pos + KEY.len();
-            while cursor < pdf_bytes.len() && pdf_bytes[cursor].is_ascii_whitespace() {
-                cursor += 1;
-            }
-            if cursor < pdf_bytes.len() && pdf_bytes[cursor] == b'<' {
-                contents_pos = Some(pos);
-                cursor_pos = cursor;
-                break;
+/// The two halves of a signature's `/ByteRange`, borrowed from the file: every byte the
+/// signature covers, in order, without copying them. Digests are computed over the halves in
+/// turn, so verifying a large document needs no second copy of it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SignedRanges<'a> {
+    parts: [&'a [u8]; 2],
+}
+
+impl<'a> SignedRanges<'a> {
+    fn new(pdf_bytes: &'a [u8], byte_range: &ByteRange) -> Self {
+        Self {
+            parts: [
+                &pdf_bytes[byte_range.offset1..byte_range.offset1 + byte_range.len1],
+                &pdf_bytes[byte_range.offset2..byte_range.offset2 + byte_range.len2],
+            ],
+        }
+    }
+
+    /// The halves, in file order.
+    pub fn parts(&self) -> [&'a [u8]; 2] {
+        self.parts
+    }
+
+    /// The signed bytes copied into one buffer.
+    pub fn to_vec(&self) -> Vec<u8> {
+        self.parts.concat()
+    }
+}
+
+/// The `/Contents` string in the gap between the two halves of the ByteRange, decoded but
+/// with its padding. The gap must be exactly that string, delimiters included, and the first
+/// half must start at the top of the file, so that no other byte of the signed revision is
+/// left out of the signature.
+fn extract_contents(pdf_bytes: &[u8], byte_range: &ByteRange) -> SignedBytesResult<Vec<u8>> {
+    if byte_range.offset1 != 0 || byte_range.len1 > byte_range.offset2 {
+        return Err(SignedBytesError::UncoveredBytes);
+    }
+    if !pdf_bytes[..byte_range.len1]
+        .trim_ascii_end()
+        .ends_with(b"/Contents")
+    {
+        return Err(SignedBytesError::ContentsNotFound);
+    }
+    let gap = &pdf_bytes[byte_range.len1..byte_range.offset2];
+    match gap.first() {
+        Some(b'<') => {
+            let hex_slice = gap[1..]
+                .strip_suffix(b">")
+                .ok_or(SignedBytesError::ContentsEndMissing)?;
+            let hex_str =
+                str::from_utf8(hex_slice).map_err(|_| SignedBytesError::InvalidContentsUtf8)?;
+            let cleaned: String = hex_str.split_whitespace().collect();
+            hex::decode(cleaned).map_err(SignedBytesError::ContentsHexDecode)
+        }
+        // Signers that write raw bytes use a literal string instead
+        Some(b'(') => match parse_literal_string(gap, 0) {
+            (contents, end) if end == gap.len() && gap.ends_with(b")") => Ok(contents),
+            _ => Err(SignedBytesError::ContentsEndMissing),
+        },
+        _ => Err(SignedBytesError::ContentsStartMissing),
+    }
+}
+
+/// Drops the zero padding after the CMS blob. A definite-length DER header says exactly where
+/// the blob ends, which matters when the signature itself ends in a zero byte; only
+/// indefinite-length (BER) blobs fall back to stripping trailing zeros.
+fn strip_padding(mut signature_der: Vec<u8>) -> Vec<u8> {
+    match der_length(&signature_der) {
+        Some(len) if len <= signature_der.len() => signature_der.truncate(len),
+        _ => {
+            while signature_der.last() == Some(&0) {
+                signature_der.pop();
             }
-            search_index = pos + 1;
-        } else {
-            break;
         }
     }
+    signature_der
+}
 
-    if contents_pos.is_none() {
-        let mut search_end = byte_range_pos;
-        while search_end > 0 {
-            let slice = &pdf_bytes[..search_end];
-            if let Some(pos) = slice.windows(KEY.len()).rposition(|w| w == KEY) {
-                let mut cursor = pos + KEY.len();
-                while cursor < pdf_bytes.len() && pdf_bytes[cursor].is_ascii_whitespace() {
-                    cursor += 1;
-                }
-                if cursor < pdf_bytes.len() && pdf_bytes[cursor] == b'<' {
-                    contents_pos = Some(pos);
-                    cursor_pos = cursor;
-                    break;
+/// Total length (header included) of the DER element at the start of `der`, if its length is
+/// definite.
+fn der_length(der: &[u8]) -> Option<usize> {
+    let first = *der.get(1)?;
+    if first < 0x80 {
+        return Some(2 + first as usize);
+    }
+    let n = (first & 0x7f) as usize;
+    if n == 0 || n > 4 {
+        return None;
+    }
+    let len = der
+        .get(2..2 + n)?
+        .iter()
+        .fold(0usize, |len, &b| (len << 8) | b as usize);
+    Some(2 + n + len)
+}
+
+/// Offsets of every `/ByteRange` key, in file order.
+fn byte_range_positions(pdf_bytes: &[u8]) -> impl Iterator<Item = usize> + '_ {
+    pdf_bytes
+        .windows(b"/ByteRange".len())
+        .enumerate()
+        .filter(|(_, w)| *w == b"/ByteRange")
+        .map(|(i, _)| i)
+}
+
+/// Where a signature dictionary was found.
+enum Source {
+    /// The `/V` of a signature field in the document's `/AcroForm`.
+    Field(SignatureField),
+    /// The `/ByteRange` key at this offset, for documents without signed form fields or that
+    /// do not parse.
+    Scan(usize),
+}
+
+/// The signature dictionaries of the PDF, in file order. They are looked up through the
+/// signature fields of the parsed document, so that `/ByteRange` or `/Contents` keys elsewhere
+/// cannot be mistaken for them; only if that finds none is the file scanned for `/ByteRange`.
+fn signature_sources(pdf_bytes: &[u8]) -> Vec<Source> {
+    match extract_signature_fields(pdf_bytes) {
+        Ok(mut fields) if !fields.is_empty() => {
+            fields.sort_by_key(|field| field.byte_range.get(1).copied());
+            fields.into_iter().map(Source::Field).collect()
+        }
+        _ => byte_range_positions(pdf_bytes).map(Source::Scan).collect(),
+    }
+}
+
+impl Source {
+    fn kind(&self, pdf_bytes: &[u8]) -> SignatureKind {
+        match self {
+            Source::Field(field) => {
+                if field.sig_type.as_deref() == Some("DocTimeStamp")
+                    || field.sub_filter.as_deref() == Some("ETSI.RFC3161")
+                {
+                    SignatureKind::DocumentTimestamp
+                } else {
+                    SignatureKind::Approval
                 }
-                search_end = pos;
-            } else {
-                break;
             }
+            Source::Scan(br_pos) => signature_kind(pdf_bytes, *br_pos),
         }
     }
 
-    if contents_pos.is_none() {
-        return Err(SignedBytesError::ContentsNotFound);
+    fn byte_range(&self, pdf_bytes: &[u8]) -> SignedBytesResult<ByteRange> {
+        match self {
+            Source::Field(field) => byte_range_from(pdf_bytes, &field.byte_range),
+            Source::Scan(br_pos) => parse_byte_range(pdf_bytes, *br_pos),
+        }
+    }
+
+    fn field_name(&self, pdf_bytes: &[u8]) -> Option<String> {
+        match self {
+            Source::Field(field) => Some(field.name.clone()),
+            Source::Scan(br_pos) => field_name(pdf_bytes, *br_pos),
+        }
     }
 
-    if cursor_pos >= pdf_bytes.len() || pdf_bytes[cursor_pos] != b'<' {
-        return Err(SignedBytesError::ContentsStartMissing);
+    fn sub_filter(&self, pdf_bytes: &[u8]) -> Option<SubFilter> {
+        match self {
+            Source::Field(field) => SubFilter::from_name(field.sub_filter.as_deref()?),
+            Source::Scan(br_pos) => {
+                let (start, end) = enclosing_object(pdf_bytes, *br_pos);
+                let dict = &pdf_bytes[start..end];
+                let value = dict[find_key(dict, b"/SubFilter")?..].trim_ascii_start();
+                let name = value.strip_prefix(b"/")?;
+                let len = name
+                    .iter()
+                    .position(|b| b.is_ascii_whitespace() || b"/<>[]()".contains(b))
+                    .unwrap_or(name.len());
+                SubFilter::from_name(str::from_utf8(&name[..len]).ok()?)
+            }
+        }
     }
+}
+
+/// Classifies the signature dictionary holding the `/ByteRange` at `br_pos`, by looking for
+/// `/Type /DocTimeStamp` or `/SubFilter /ETSI.RFC3161` between its `obj` and `endobj`.
+fn signature_kind(pdf_bytes: &[u8], br_pos: usize) -> SignatureKind {
+    let (start, end) = enclosing_object(pdf_bytes, br_pos);
+    let dict = &pdf_bytes[start..end];
+    let has = |name: &[u8]| dict.windows(name.len()).any(|w| w == name);
+    if has(b"/DocTimeStamp") || has(b"/ETSI.RFC3161") {
+        SignatureKind::DocumentTimestamp
+    } else {
+        SignatureKind::Approval
+    }
+}
 
-    let hex_start = cursor_pos + 1;
-    let hex_end = pdf_bytes[hex_start..]
+/// Returns true if the PDF has a well-formed `/ByteRange`, i.e. it appears to be signed. The
+/// signature itself is neither extracted nor verified.
+pub fn has_signature(pdf_bytes: &[u8]) -> bool {
+    byte_range_positions(pdf_bytes).any(|br_pos| parse_byte_range(pdf_bytes, br_pos).is_ok())
+}
+
+/// Kinds of all signatures in the PDF, in file order.
+pub fn signature_kinds(pdf_bytes: &[u8]) -> Vec<SignatureKind> {
+    signature_sources(pdf_bytes)
         .iter()
-        .position(|&b| b == b'>')
-        .ok_or(SignedBytesError::ContentsEndMissing)?
-        + hex_start;
+        .map(|source| source.kind(pdf_bytes))
+        .collect()
+}
 
-    let hex_slice = &pdf_bytes[hex_start..hex_end];
-    let hex_str = str::from_utf8(hex_slice).map_err(|_| SignedBytesError::InvalidContentsUtf8)?;
-    let cleaned: String = hex_str.split_whitespace().collect();
+/// Bounds of the indirect object around `pos`: from its `obj` keyword to its `endobj`.
+fn enclosing_object(pdf_bytes: &[u8], pos: usize) -> (usize, usize) {
+    let start = pdf_bytes[..pos]
+        .windows(4)
+        .rposition(|w| w == b" obj")
+        .unwrap_or(0);
+    let end = pdf_bytes[pos..]
+        .windows(6)
+        .position(|w| w == b"endobj")
+        .map_or(pdf_bytes.len(), |i| pos + i);
+    (start, end)
+}
 
-    Ok(cleaned)
+/// The `N G` before the `obj` keyword at `obj_pos`.
+fn object_id(pdf_bytes: &[u8], obj_pos: usize) -> Option<(u32, u32)> {
+    let head = str::from_utf8(&pdf_bytes[obj_pos.saturating_sub(24)..obj_pos]).ok()?;
+    let mut tokens = head.split_whitespace().rev();
+    let generation = tokens.next()?.parse().ok()?;
+    let number = tokens
+        .next()?
+        .trim_start_matches(|c: char| !c.is_ascii_digit());
+    Some((number.parse().ok()?, generation))
 }
 
-fn decode_signature_hex(hex_str: &str) -> SignedBytesResult<Vec<u8>> {
-    let mut signature_der = hex::decode(hex_str)?;
-    while signature_der.last() == Some(&0) {
-        signature_der.pop();
+/// The indirect reference `N G R` at the start of `bytes`, after optional whitespace.
+fn reference_at(bytes: &[u8]) -> Option<(u32, u32)> {
+    let mut rest = bytes;
+    let mut number = || {
+        let start = rest.iter().position(|b| !b.is_ascii_whitespace())?;
+        let len = rest[start..]
+            .iter()
+            .take_while(|b| b.is_ascii_digit())
+            .count();
+        let value = str::from_utf8(&rest[start..start + len])
+            .ok()?
+            .parse()
+            .ok()?;
+        rest = &rest[start + len..];
+        Some(value)
+    };
+    let (number, generation) = (number()?, number()?);
+    let rest = rest.trim_ascii_start();
+    (rest.first() == Some(&b'R') && !rest.get(1).is_some_and(|b| b.is_ascii_alphanumeric()))
+        .then_some((number, generation))
+}
+
+/// Position just past the `key` entry in `dict`, skipping keys that merely start with it
+/// (`/T` in `/Type`).
+fn find_key(dict: &[u8], key: &[u8]) -> Option<usize> {
+    dict.windows(key.len())
+        .enumerate()
+        .filter(|(_, w)| *w == key)
+        .map(|(i, _)| i + key.len())
+        .find(|&end| !dict.get(end).is_some_and(|b| b.is_ascii_alphanumeric()))
+}
+
+/// Decodes the literal `(...)` or hex `<...>` string at the start of `bytes`, after optional
+/// whitespace, as a text string: UTF-16BE with a byte order mark, or else PDFDocEncoding.
+fn pdf_string(bytes: &[u8]) -> Option<String> {
+    let start = bytes.iter().position(|b| !b.is_ascii_whitespace())?;
+    let (raw, _) = match bytes[start] {
+        b'(' => parse_literal_string(bytes, start),
+        b'<' => parse_hex_string(bytes, start),
+        _ => return None,
+    };
+    Some(decode_text_string(&raw))
+}
+
+/// The `/T` name of the signature field holding the signature dictionary at `br_pos`: either
+/// the object the dictionary sits in, or the last (most recently updated) field whose `/V`
+/// refers to it. `None` when the field is in an object stream or has no name.
+fn field_name(pdf_bytes: &[u8], br_pos: usize) -> Option<String> {
+    let (start, end) = enclosing_object(pdf_bytes, br_pos);
+    if let Some(t) = find_key(&pdf_bytes[start..end], b"/T") {
+        return pdf_string(&pdf_bytes[start + t..end]);
+    }
+
+    let (number, generation) = object_id(pdf_bytes, start)?;
+    let mut name = None;
+    let mut search = 0;
+    while let Some(v) = find_key(&pdf_bytes[search..], b"/V") {
+        let v = search + v;
+        search = v;
+        if reference_at(&pdf_bytes[v..]) != Some((number, generation)) {
+            continue;
+        }
+        let (field_start, field_end) = enclosing_object(pdf_bytes, v);
+        if let Some(t) = find_key(&pdf_bytes[field_start..field_end], b"/T") {
+            name = pdf_string(&pdf_bytes[field_start + t..field_end]).or(name);
+        }
+    }
+    name
+}
+
+fn signature_at<'a>(
+    pdf_bytes: &'a [u8],
+    source: &Source,
+) -> SignedBytesResult<(Vec<u8>, SignedRanges<'a>)> {
+    let byte_range = source.byte_range(pdf_bytes)?;
+    let signed_data = SignedRanges::new(pdf_bytes, &byte_range);
+
+    let contents = extract_contents(pdf_bytes, &byte_range)?;
+    // The gap must hold this dictionary's `/Contents`, not some other string
+    if matches!(source, Source::Field(field) if field.contents != contents) {
+        return Err(SignedBytesError::ContentsNotFound);
+    }
+
+    Ok((strip_padding(contents), signed_data))
+}
+
+/// Every approval signature dictionary, failing like `get_signature_der` when there is none.
+fn approval_sources(pdf_bytes: &[u8]) -> SignedBytesResult<Vec<Source>> {
+    let sources = signature_sources(pdf_bytes);
+    if sources.is_empty() {
+        return Err(SignedBytesError::ByteRangeNotFound);
+    }
+    let approvals: Vec<Source> = sources
+        .into_iter()
+        .filter(|source| source.kind(pdf_bytes) == SignatureKind::Approval)
+        .collect();
+    if approvals.is_empty() {
+        return Err(SignedBytesError::NoApprovalSignature);
     }
-    Ok(signature_der)
+    Ok(approvals)
 }
 
+/// The CMS blob and signed bytes of the first approval signature. Document timestamps are
+/// skipped; a PDF carrying nothing else fails with `NoApprovalSignature`.
+/// The signed bytes are copied; `get_approval_signature` borrows them from the file instead.
 pub fn get_signature_der(pdf_bytes: &[u8]) -> SignedBytesResult<(Vec<u8>, Vec<u8>)> {
-    let byte_range = parse_byte_range(pdf_bytes)?;
-    let signed_data = extract_signed_data(pdf_bytes, &byte_range);
+    let (signature_der, signed_data) = signature_at(pdf_bytes, &approval_sources(pdf_bytes)?[0])?;
+    Ok((signature_der, signed_data.to_vec()))
+}
 
-    let br_pos = pdf_bytes
-        .windows(b"/ByteRange".len())
-        .position(|w| w == b"/ByteRange")
-        .ok_or(SignedBytesError::ByteRangeNotFound)?;
+/// An approval signature together with the form field it belongs to.
+#[derive(Debug, Clone)]
+pub struct ApprovalSignature<'a> {
+    pub signature_der: Vec<u8>,
+    pub signed_data: SignedRanges<'a>,
+    /// Fully qualified name of the signature field, if it could be found.
+    pub field_name: Option<String>,
+    /// Whether anything follows the end of the `/ByteRange`.
+    pub coverage: SignatureCoverage,
+    /// The profile the dictionary's `/SubFilter` names, if it is a known one.
+    pub sub_filter: Option<SubFilter>,
+}
+
+fn approval_signature_at<'a>(
+    pdf_bytes: &'a [u8],
+    source: &Source,
+) -> SignedBytesResult<ApprovalSignature<'a>> {
+    let (signature_der, signed_data) = signature_at(pdf_bytes, source)?;
+    let byte_range = source.byte_range(pdf_bytes)?;
+    Ok(ApprovalSignature {
+        signature_der,
+        signed_data,
+        field_name: source.field_name(pdf_bytes),
+        coverage: match pdf_bytes.len() - (byte_range.offset2 + byte_range.len2) {
+            0 => SignatureCoverage::FullDocument,
+            appended_bytes => SignatureCoverage::PartialWithUpdates { appended_bytes },
+        },
+        sub_filter: source.sub_filter(pdf_bytes),
+    })
+}
+
+/// Like `get_signature_der`, with the field name and coverage of the signature.
+pub fn get_approval_signature(pdf_bytes: &[u8]) -> SignedBytesResult<ApprovalSignature<'_>> {
+    approval_signature_at(pdf_bytes, &approval_sources(pdf_bytes)?[0])
+}
+
+/// Every approval signature, in file order. Incremental updates append, so a certification
+/// signature comes before the approvals added after it, and only the last signature can have
+/// `SignatureCoverage::FullDocument`.
+pub fn get_approval_signatures(pdf_bytes: &[u8]) -> SignedBytesResult<Vec<ApprovalSignature<'_>>> {
+    approval_sources(pdf_bytes)?
+        .iter()
+        .map(|source| approval_signature_at(pdf_bytes, source))
+        .collect()
+}
 
-    let hex_str = extract_signature_hex(pdf_bytes, br_pos)?;
-    let signature_der = decode_signature_hex(&hex_str)?;
+/// The timestamp token and covered bytes of every document timestamp, in file order.
+pub fn get_timestamp_ders(pdf_bytes: &[u8]) -> SignedBytesResult<Vec<(Vec<u8>, Vec<u8>)>> {
+    Ok(timestamp_signatures(pdf_bytes)?
+        .into_iter()
+        .map(|(token_der, covered)| (token_der, covered.to_vec()))
+        .collect())
+}
 
-    Ok((signature_der, signed_data))
+/// `get_timestamp_ders` with the covered bytes borrowed from the file.
+pub(crate) fn timestamp_signatures(
+    pdf_bytes: &[u8],
+) -> SignedBytesResult<Vec<(Vec<u8>, SignedRanges<'_>)>> {
+    signature_sources(pdf_bytes)
+        .iter()
+        .filter(|source| source.kind(pdf_bytes) == SignatureKind::DocumentTimestamp)
+        .map(|source| signature_at(pdf_bytes, source))
+        .collect()
 }
 
 #[cfg(test)]
@@ -180,6 +488,112 @@ mod tests {
         assert_eq!(expected_signature, hex::encode(&signature_der));
     }
 
+    #[test]
+    fn approval_signatures_borrow_the_signed_bytes() {
+        let (_, copied) = get_signature_der(SAMPLE_PDF_BYTES).unwrap();
+        let signature = get_approval_signature(SAMPLE_PDF_BYTES).unwrap();
+        let [first, second] = signature.signed_data.parts();
+        // Both halves point into the file rather than into a copy of it
+        assert_eq!(first.as_ptr(), SAMPLE_PDF_BYTES.as_ptr());
+        assert!(SAMPLE_PDF_BYTES.as_ptr_range().contains(&second.as_ptr()));
+        assert_eq!(signature.signed_data.to_vec(), copied);
+    }
+
+    #[test]
+    fn detects_signature_presence() {
+        assert!(has_signature(SAMPLE_PDF_BYTES));
+        assert!(!has_signature(b"%PDF-1.7\n1 0 obj\n<< >>\nendobj\n%%EOF"));
+    }
+
+    #[test]
+    fn classifies_signature_dictionaries() {
+        let pdf = b"1 0 obj\n<< /Type /Sig /SubFilter /adbe.pkcs7.detached /ByteRange [0 1 2 3] >>\nendobj\n\
+                    2 0 obj\n<< /Type /DocTimeStamp /SubFilter /ETSI.RFC3161 /ByteRange [0 1 2 3] >>\nendobj\n";
+        assert_eq!(
+            signature_kinds(pdf),
+            [SignatureKind::Approval, SignatureKind::DocumentTimestamp]
+        );
+
+        let timestamp_only =
+            b"2 0 obj\n<< /SubFilter /ETSI.RFC3161 /ByteRange [0 1 2 3] >>\nendobj\n";
+        assert!(matches!(
+            get_signature_der(timestamp_only),
+            Err(SignedBytesError::NoApprovalSignature)
+        ));
+        assert!(matches!(
+            get_signature_der(b"%PDF-1.7\n%%EOF"),
+            Err(SignedBytesError::ByteRangeNotFound)
+        ));
+    }
+
+    #[test]
+    fn finds_signature_field_names() {
+        // Field referring to a separate signature dictionary, updated in a later revision
+        let pdf = b"4 0 obj\n<< /FT /Sig /T (Old) /V 5 0 R >>\nendobj\n\
+                    5 0 obj\n<< /Type /Sig /ByteRange [0 1 2 3] >>\nendobj\n\
+                    4 0 obj\n<< /FT /Sig /T (Approval \\(2\\)) /V 5 0 R/F 132 >>\nendobj\n\
+                    6 0 obj\n<< /FT /Sig /T (Other) /V 15 0 R >>\nendobj\n";
+        let br_pos = byte_range_positions(pdf).next().unwrap();
+        assert_eq!(field_name(pdf, br_pos).as_deref(), Some("Approval (2)"));
+
+        // Signature dictionary inline in the field, with a UTF-16BE hex name
+        let inline = b"7 0 obj\n<< /Type /Annot /T <FEFF00C9006C> /V << /ByteRange [0 1 2 3] >> >>\nendobj\n";
+        let br_pos = byte_range_positions(inline).next().unwrap();
+        assert_eq!(field_name(inline, br_pos).as_deref(), Some("\u{c9}l"));
+
+        let unnamed = b"5 0 obj\n<< /Type /Sig /ByteRange [0 1 2 3] >>\nendobj\n";
+        assert_eq!(field_name(unnamed, 16), None);
+    }
+
+    #[test]
+    fn byte_range_gap_must_be_the_contents() {
+        let pdf = |byte_range: &str| {
+            format!(
+                "1 0 obj\n<< /Contents <3000> /ByteRange [{:<12}] >>\nendobj\n",
+                byte_range
+            )
+            .into_bytes()
+        };
+        let (signature_der, _) = signature_at(&pdf("0 21 27 37"), &Source::Scan(28)).unwrap();
+        assert_eq!(signature_der, [0x30, 0]);
+
+        let err = |byte_range| signature_at(&pdf(byte_range), &Source::Scan(28)).unwrap_err();
+        assert!(matches!(
+            err("2 19 27 37"),
+            SignedBytesError::UncoveredBytes
+        ));
+        assert!(matches!(
+            err("0 27 21 37"),
+            SignedBytesError::UncoveredBytes
+        ));
+        assert!(matches!(
+            err("0 20 27 37"),
+            SignedBytesError::ContentsStartMissing
+        ));
+        assert!(matches!(
+            err("0 21 29 35"),
+            SignedBytesError::ContentsEndMissing
+        ));
+        assert!(matches!(
+            err("0 9 27 37"),
+            SignedBytesError::ContentsNotFound
+        ));
+    }
+
+    #[test]
+    fn keeps_trailing_zero_inside_der() {
+        // An OCTET STRING ending in 0x00, then zero padding up to the placeholder size.
+        assert_eq!(
+            strip_padding(hex::decode("0403aabb000000").unwrap()),
+            [4, 3, 0xaa, 0xbb, 0]
+        );
+        // Indefinite length: fall back to stripping the padding.
+        assert_eq!(
+            strip_padding(hex::decode("3080aa0000").unwrap()),
+            [0x30, 0x80, 0xaa]
+        );
+    }
+
     #[cfg(feature = "private_tests")]
     mod private {
         use super::*;
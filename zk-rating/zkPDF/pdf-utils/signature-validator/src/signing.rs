@@ -0,0 +1,1510 @@
+//! Creating signatures, with the `signing` feature: detached CMS blobs, and PAdES signatures
+//! written into a prepared PDF signature field.
+//!
+//! A prepared field is a signature dictionary whose `/ByteRange` holds a placeholder (e.g.
+//! `[0 0000000000 0000000000 0000000000]`) and whose `/Contents` is a hex string of zeros
+//! large enough for the CMS blob. `sign_pdf` fills in both without moving any other byte.
+
+mod der;
+
+use alloc::format;
+use alloc::string::ToString;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use rsa::pkcs1::DecodeRsaPrivateKey;
+use rsa::pkcs8::{DecodePrivateKey, DecodePublicKey};
+use rsa::traits::PublicKeyParts;
+use rsa::{Pkcs1v15Sign, RsaPrivateKey, RsaPublicKey};
+use sha2::{Digest, Sha256};
+
+use self::der::{SEQUENCE, SET};
+use crate::types::SigningError;
+pub use crate::types::SubFilter;
+
+const RSA_ENCRYPTION: &[u64] = &[1, 2, 840, 113549, 1, 1, 1];
+const SHA256_WITH_RSA: &[u64] = &[1, 2, 840, 113549, 1, 1, 11];
+const SHA256: &[u64] = &[2, 16, 840, 1, 101, 3, 4, 2, 1];
+const DATA: &[u64] = &[1, 2, 840, 113549, 1, 7, 1];
+const SIGNED_DATA: &[u64] = &[1, 2, 840, 113549, 1, 7, 2];
+const CONTENT_TYPE: &[u64] = &[1, 2, 840, 113549, 1, 9, 3];
+const MESSAGE_DIGEST: &[u64] = &[1, 2, 840, 113549, 1, 9, 4];
+const SIGNING_CERTIFICATE_V2: &[u64] = &[1, 2, 840, 113549, 1, 9, 16, 2, 47];
+
+/// An RSA private key with its certificate and any intermediate certificates to embed.
+/// Signatures use SHA-256 with PKCS#1 v1.5 and are deterministic.
+#[derive(Debug, Clone)]
+pub struct SigningKey {
+    key: RsaPrivateKey,
+    certificate: Vec<u8>,
+    /// DER issuer Name and serial magnitude, for the SignerInfo
+    issuer: Vec<u8>,
+    serial: Vec<u8>,
+    chain: Vec<Vec<u8>>,
+}
+
+impl SigningKey {
+    /// A key in PKCS#1 or PKCS#8 DER with the DER X.509 certificate of its public key.
+    pub fn new(key_der: &[u8], certificate_der: &[u8]) -> Result<Self, SigningError> {
+        let key = decode_key(key_der)?;
+        let fields = certificate_fields(certificate_der)?;
+        let public_key = RsaPublicKey::from_public_key_der(fields.subject_public_key_info)
+            .map_err(|_| SigningError::InvalidCertificate("not an RSA certificate"))?;
+        if public_key != key.to_public_key() {
+            return Err(SigningError::KeyMismatch);
+        }
+        Ok(Self {
+            key,
+            certificate: certificate_der.to_vec(),
+            issuer: fields.issuer.to_vec(),
+            serial: fields.serial.to_vec(),
+            chain: Vec::new(),
+        })
+    }
+
+    /// A key with a self-signed certificate valid from 2024 to 2044, for tests and demos.
+    /// `subject` lists the name attributes in order, by short name: `C`, `ST`, `L`, `O`,
+    /// `OU` or `CN`. The serial is derived from the key.
+    pub fn self_signed(key_der: &[u8], subject: &[(&str, &str)]) -> Result<Self, SigningError> {
+        let key = decode_key(key_der)?;
+        let mut rdns = Vec::with_capacity(subject.len());
+        for &(attribute, value) in subject {
+            let (arc, value) = match attribute {
+                "C" => (6, der::printable_string(value)),
+                "L" => (7, der::utf8_string(value)),
+                "ST" => (8, der::utf8_string(value)),
+                "O" => (10, der::utf8_string(value)),
+                "OU" => (11, der::utf8_string(value)),
+                "CN" => (3, der::utf8_string(value)),
+                _ => return Err(SigningError::InvalidCertificate("unknown name attribute")),
+            };
+            rdns.push(der::set(&[der::sequence(&[
+                der::oid(&[2, 5, 4, arc]),
+                value,
+            ])]));
+        }
+        let name = der::sequence(&rdns);
+        let serial = Sha256::digest(key.n().to_bytes_be())[..8].to_vec();
+
+        let public_key = der::sequence(&[
+            der::unsigned(&key.n().to_bytes_be()),
+            der::unsigned(&key.e().to_bytes_be()),
+        ]);
+        let tbs = der::sequence(&[
+            der::context(0, &[der::small(2)]),
+            der::unsigned(&serial),
+            der::algorithm(SHA256_WITH_RSA),
+            name.clone(),
+            der::sequence(&[
+                der::utc_time("240101000000Z"),
+                der::utc_time("440101000000Z"),
+            ]),
+            name.clone(),
+            der::sequence(&[der::algorithm(RSA_ENCRYPTION), der::bit_string(&public_key)]),
+        ]);
+        let signature = rsa_sign(&key, &tbs)?;
+        let certificate = der::sequence(&[
+            tbs,
+            der::algorithm(SHA256_WITH_RSA),
+            der::bit_string(&signature),
+        ]);
+
+        Ok(Self {
+            key,
+            certificate,
+            issuer: name,
+            serial,
+            chain: Vec::new(),
+        })
+    }
+
+    /// Adds certificates (DER) to embed after the signer's, e.g. the issuing CA chain.
+    pub fn with_chain(mut self, certificates: Vec<Vec<u8>>) -> Self {
+        self.chain = certificates;
+        self
+    }
+
+    /// The signer certificate, DER-encoded.
+    pub fn certificate_der(&self) -> &[u8] {
+        &self.certificate
+    }
+}
+
+fn decode_key(key_der: &[u8]) -> Result<RsaPrivateKey, SigningError> {
+    RsaPrivateKey::from_pkcs1_der(key_der)
+        .or_else(|_| RsaPrivateKey::from_pkcs8_der(key_der))
+        .map_err(|e| SigningError::InvalidKey(e.to_string()))
+}
+
+/// The parts of a certificate a SignerInfo refers to.
+struct CertificateFields<'a> {
+    /// The whole issuer Name element
+    issuer: &'a [u8],
+    /// Serial number magnitude
+    serial: &'a [u8],
+    subject_public_key_info: &'a [u8],
+}
+
+fn certificate_fields(certificate: &[u8]) -> Result<CertificateFields<'_>, SigningError> {
+    let malformed = || SigningError::InvalidCertificate("malformed certificate");
+    let (_, certificate, _) = der::split(certificate).ok_or_else(malformed)?;
+    let (_, mut tbs, _) = der::split(certificate).ok_or_else(malformed)?;
+    if tbs.first() == Some(&0xa0) {
+        tbs = der::split(tbs).ok_or_else(malformed)?.2;
+    }
+    // serialNumber, signature, issuer, validity, subject, subjectPublicKeyInfo
+    let mut fields = [&[][..]; 6];
+    for field in fields.iter_mut() {
+        let (element, _, rest) = der::split(tbs).ok_or_else(malformed)?;
+        *field = element;
+        tbs = rest;
+    }
+    let (_, serial, _) = der::split(fields[0]).ok_or_else(malformed)?;
+    Ok(CertificateFields {
+        issuer: fields[2],
+        serial,
+        subject_public_key_info: fields[5],
+    })
+}
+
+fn rsa_sign(key: &RsaPrivateKey, data: &[u8]) -> Result<Vec<u8>, SigningError> {
+    key.sign(Pkcs1v15Sign::new::<Sha256>(), &Sha256::digest(data))
+        .map_err(|e| SigningError::Rsa(e.to_string()))
+}
+
+/// A detached CMS SignedData (a `.p7s`) over `content`.
+pub fn sign_detached(
+    content: &[u8],
+    key: &SigningKey,
+    sub_filter: SubFilter,
+) -> Result<Vec<u8>, SigningError> {
+    signed_data(DATA, content, false, key, sub_filter)
+}
+
+/// A CMS SignedData over `content` of type `content_type`, which is embedded as the
+/// eContent if `encapsulate` is set.
+fn signed_data(
+    content_type: &[u64],
+    content: &[u8],
+    encapsulate: bool,
+    key: &SigningKey,
+    sub_filter: SubFilter,
+) -> Result<Vec<u8>, SigningError> {
+    let mut attributes = vec![
+        der::sequence(&[der::oid(CONTENT_TYPE), der::set(&[der::oid(content_type)])]),
+        der::sequence(&[
+            der::oid(MESSAGE_DIGEST),
+            der::set(&[der::octet_string(&Sha256::digest(content))]),
+        ]),
+    ];
+    if sub_filter == SubFilter::CadesDetached {
+        // ESSCertIDv2 with the default SHA-256 hash algorithm left out
+        let cert_id = der::sequence(&[der::octet_string(&Sha256::digest(&key.certificate))]);
+        attributes.push(der::sequence(&[
+            der::oid(SIGNING_CERTIFICATE_V2),
+            der::set(&[der::sequence(&[der::sequence(&[cert_id])])]),
+        ]));
+    }
+    attributes.sort();
+    // Signed as a SET OF, embedded as [0] IMPLICIT
+    let signature = rsa_sign(&key.key, &der::constructed(SET, &attributes))?;
+
+    let signer_info = der::sequence(&[
+        der::small(1),
+        der::sequence(&[key.issuer.clone(), der::unsigned(&key.serial)]),
+        der::algorithm(SHA256),
+        der::context(0, &attributes),
+        der::algorithm(RSA_ENCRYPTION),
+        der::octet_string(&signature),
+    ]);
+    let certificates: Vec<Vec<u8>> = core::iter::once(key.certificate.clone())
+        .chain(key.chain.iter().cloned())
+        .collect();
+    let encap_content_info = if encapsulate {
+        der::sequence(&[
+            der::oid(content_type),
+            der::context(0, &[der::octet_string(content)]),
+        ])
+    } else {
+        der::sequence(&[der::oid(content_type)])
+    };
+    let signed_data = der::sequence(&[
+        der::small(if encapsulate { 3 } else { 1 }),
+        der::set(&[der::algorithm(SHA256)]),
+        encap_content_info,
+        der::context(0, &certificates),
+        der::set_of(&[signer_info]),
+    ]);
+    Ok(der::constructed(
+        SEQUENCE,
+        &[der::oid(SIGNED_DATA), der::context(0, &[signed_data])],
+    ))
+}
+
+/// A prepared signature field located in a PDF.
+struct Placeholder {
+    /// Between the `/ByteRange` brackets
+    byte_range: Range<usize>,
+    /// From `<` to just past `>` of `/Contents`
+    contents: Range<usize>,
+    sub_filter: SubFilter,
+}
+
+/// Signs the last prepared signature field in `pdf`, choosing the CMS profile from its
+/// `/SubFilter`, and returns the signed document.
+pub fn sign_pdf(pdf: &[u8], key: &SigningKey) -> Result<Vec<u8>, SigningError> {
+    fill_placeholder(pdf, |signed_bytes, sub_filter| {
+        sign_detached(signed_bytes, key, sub_filter)
+    })
+}
+
+/// Writes the byte range into the last prepared field, then the blob `sign` makes over the
+/// covered bytes into its `/Contents`.
+fn fill_placeholder(
+    pdf: &[u8],
+    sign: impl FnOnce(&[u8], SubFilter) -> Result<Vec<u8>, SigningError>,
+) -> Result<Vec<u8>, SigningError> {
+    let Placeholder {
+        byte_range,
+        contents,
+        sub_filter,
+    } = find_placeholder(pdf)?;
+
+    let range = format!(
+        "0 {} {} {}",
+        contents.start,
+        contents.end,
+        pdf.len() - contents.end
+    );
+    if range.len() > byte_range.len() {
+        return Err(SigningError::ByteRangeTooShort(range));
+    }
+    let mut signed = pdf.to_vec();
+    signed[byte_range.clone()]
+        .copy_from_slice(format!("{:<width$}", range, width = byte_range.len()).as_bytes());
+
+    let signed_bytes = [&signed[..contents.start], &signed[contents.end..]].concat();
+    let cms = sign(&signed_bytes, sub_filter)?;
+    let capacity = (contents.len() - 2) / 2;
+    if cms.len() > capacity {
+        return Err(SigningError::SignatureTooLarge {
+            size: cms.len(),
+            capacity,
+        });
+    }
+    let hex = hex::encode(&cms);
+    signed[contents.start + 1..contents.start + 1 + hex.len()].copy_from_slice(hex.as_bytes());
+    Ok(signed)
+}
+
+fn find_placeholder(pdf: &[u8]) -> Result<Placeholder, SigningError> {
+    // The last /Contents holding only zeros
+    let contents = occurrences(pdf, b"/Contents")
+        .rev()
+        .find_map(|at| {
+            let start = at + b"/Contents".len();
+            let open = start
+                + pdf[start..]
+                    .iter()
+                    .take_while(|b| b.is_ascii_whitespace())
+                    .count();
+            if pdf.get(open) != Some(&b'<') {
+                return None;
+            }
+            let zeros = pdf[open + 1..].iter().take_while(|&&b| b == b'0').count();
+            (zeros > 0 && pdf.get(open + 1 + zeros) == Some(&b'>'))
+                .then_some(open..open + zeros + 2)
+        })
+        .ok_or(SigningError::NoPlaceholder("no /Contents of zeros"))?;
+
+    // The enclosing object bounds where the /ByteRange and /SubFilter must be
+    let object = pdf[..contents.start]
+        .windows(4)
+        .rposition(|w| w == b" obj")
+        .unwrap_or(0)
+        ..occurrences(&pdf[contents.end..], b"endobj")
+            .next()
+            .map_or(pdf.len(), |at| contents.end + at);
+    let dict = &pdf[object.clone()];
+
+    let range_at = occurrences(dict, b"/ByteRange")
+        .next()
+        .ok_or(SigningError::NoPlaceholder(
+            "no /ByteRange next to /Contents",
+        ))?
+        + object.start;
+    let open = range_at
+        + pdf[range_at..]
+            .iter()
+            .position(|&b| b == b'[')
+            .ok_or(SigningError::NoPlaceholder("/ByteRange is not an array"))?;
+    let close = open
+        + pdf[open..]
+            .iter()
+            .position(|&b| b == b']')
+            .ok_or(SigningError::NoPlaceholder("/ByteRange is not an array"))?;
+
+    let sub_filter = if occurrences(dict, b"/ETSI.CAdES.detached").next().is_some() {
+        SubFilter::CadesDetached
+    } else {
+        SubFilter::Pkcs7Detached
+    };
+    Ok(Placeholder {
+        byte_range: open + 1..close,
+        contents,
+        sub_filter,
+    })
+}
+
+fn occurrences<'a>(
+    haystack: &'a [u8],
+    needle: &'a [u8],
+) -> impl DoubleEndedIterator<Item = usize> + 'a {
+    haystack
+        .windows(needle.len())
+        .enumerate()
+        .filter(move |(_, w)| *w == needle)
+        .map(|(i, _)| i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{
+        PolicyViolation, ProfileViolation, RevocationSource, RevocationStatus, SignatureCoverage,
+        SignatureKind, SignaturePolicy, SignatureTimestamp, SignatureValidationError,
+        SignedBytesError,
+    };
+
+    static TEST_KEY: &[u8] = include_bytes!("../../writer/keys/test-rsa-2048.der");
+
+    fn test_key() -> SigningKey {
+        SigningKey::self_signed(
+            TEST_KEY,
+            &[
+                ("C", "IN"),
+                ("O", "zkPDF Test"),
+                ("CN", "zkPDF Test Signer"),
+            ],
+        )
+        .unwrap()
+    }
+
+    fn prepared(sub_filter: &str, byte_range: &str, zeros: usize) -> Vec<u8> {
+        format!(
+            "%PDF-1.7\n1 0 obj\n<< /Type /Catalog >>\nendobj\n2 0 obj\n<< /Type /Sig \
+             /Filter /Adobe.PPKLite /SubFilter /{} /ByteRange [{}] /Contents <{}> >>\nendobj\n\
+             trailer\n<< /Root 1 0 R >>\n%%EOF\n",
+            sub_filter,
+            byte_range,
+            "0".repeat(zeros)
+        )
+        .into_bytes()
+    }
+
+    const PLACEHOLDER: &str = "0 0000000000 0000000000 0000000000";
+
+    #[test]
+    fn signs_a_prepared_field() {
+        let pdf = prepared("adbe.pkcs7.detached", PLACEHOLDER, 8192);
+        let signed = sign_pdf(&pdf, &test_key()).unwrap();
+        assert_eq!(signed.len(), pdf.len());
+        assert!(crate::verify_pdf_signature(&signed).unwrap().is_valid);
+
+        // Only the placeholders changed
+        let start = pdf.windows(10).position(|w| w == b"/ByteRange").unwrap();
+        assert_eq!(signed[..start], pdf[..start]);
+        let tail = pdf.len() - b" >>\nendobj\ntrailer\n<< /Root 1 0 R >>\n%%EOF\n".len();
+        assert_eq!(signed[tail..], pdf[tail..]);
+    }
+
+    #[test]
+    fn signs_a_second_field_in_an_incremental_update() {
+        let key = test_key();
+        let signature = |n: u32| {
+            format!(
+                "{} 0 obj\n<< /Type /Sig /SubFilter /adbe.pkcs7.detached /ByteRange [{}] \
+                 /Contents <{}> >>\nendobj\n",
+                n,
+                PLACEHOLDER,
+                "0".repeat(8192)
+            )
+        };
+        let first = format!(
+            "%PDF-1.7\n1 0 obj\n<< /Type /Catalog >>\nendobj\n{}\
+             3 0 obj\n<< /FT /Sig /T (Certification) /V 2 0 R >>\nendobj\n%%EOF\n",
+            signature(2)
+        );
+        let mut pdf = sign_pdf(first.as_bytes(), &key).unwrap();
+        let first_len = pdf.len();
+        pdf.extend_from_slice(
+            format!(
+                "{}5 0 obj\n<< /FT /Sig /T (Approval) /V 4 0 R >>\nendobj\n%%EOF\n",
+                signature(4)
+            )
+            .as_bytes(),
+        );
+        let signed = sign_pdf(&pdf, &key).unwrap();
+
+        let all = crate::verify_all_pdf_signatures(&signed).unwrap();
+        let fields: Vec<_> = all
+            .iter()
+            .map(|res| (res.is_valid, res.field_name.as_deref(), res.coverage))
+            .collect();
+        assert_eq!(
+            fields,
+            [
+                (
+                    true,
+                    Some("Certification"),
+                    Some(SignatureCoverage::PartialWithUpdates {
+                        appended_bytes: signed.len() - first_len
+                    })
+                ),
+                (
+                    true,
+                    Some("Approval"),
+                    Some(SignatureCoverage::FullDocument)
+                )
+            ]
+        );
+        assert_eq!(
+            crate::verify_pdf_signature(&signed)
+                .unwrap()
+                .field_name
+                .as_deref(),
+            Some("Certification")
+        );
+    }
+
+    #[test]
+    fn reads_a_literal_contents_string_through_the_form() {
+        const CAPACITY: usize = 4096;
+        // An unreferenced dictionary with the same keys comes before the real one
+        let head = |byte_range: &str| {
+            format!(
+                "%PDF-1.7\n1 0 obj\n<< /Type /Catalog /AcroForm << /Fields [3 0 R] >> >>\nendobj\n\
+                 4 0 obj\n<< /ByteRange [0 1 2 3] /Contents <00> >>\nendobj\n\
+                 2 0 obj\n<< /Type /Sig /SubFilter /adbe.pkcs7.detached /ByteRange [{:<30}] \
+                 /Contents ",
+                byte_range
+            )
+        };
+        let tail = " >>\nendobj\n3 0 obj\n<< /FT /Sig /T (Literal) /V 2 0 R >>\nendobj\n\
+                    trailer\n<< /Root 1 0 R >>\n%%EOF\n";
+        let len1 = head("").len();
+        let offset2 = len1 + 2 + 4 * CAPACITY;
+        let head = head(&format!("0 {} {} {}", len1, offset2, tail.len()));
+        let signed_bytes = [head.as_bytes(), tail.as_bytes()].concat();
+        let mut p7s = sign_detached(&signed_bytes, &test_key(), SubFilter::Pkcs7Detached).unwrap();
+        p7s.resize(CAPACITY, 0);
+        let literal: String = p7s.iter().map(|b| format!("\\{:03o}", b)).collect();
+        let pdf = format!("{}({}){}", head, literal, tail);
+
+        let res = crate::verify_pdf_signature(pdf.as_bytes()).unwrap();
+        assert!(res.is_valid);
+        assert_eq!(res.field_name.as_deref(), Some("Literal"));
+        assert_eq!(res.coverage, Some(SignatureCoverage::FullDocument));
+    }
+
+    #[test]
+    fn pades_fields_get_a_signing_certificate_attribute() {
+        let key = test_key();
+        let pdf = prepared("ETSI.CAdES.detached", PLACEHOLDER, 8192);
+        let signed = sign_pdf(&pdf, &key).unwrap();
+        assert!(crate::verify_pdf_signature(&signed).unwrap().is_valid);
+
+        let (cms, content) = crate::get_signature_der(&signed).unwrap();
+        let cert_hash = Sha256::digest(key.certificate_der());
+        assert!(cms.windows(32).any(|w| w == cert_hash.as_slice()));
+        let res =
+            crate::verify_detached_signature(&content, &cms, &SignaturePolicy::strict()).unwrap();
+        assert!(res.is_valid);
+    }
+
+    /// `cms` with its certificates replaced by `certificates`, which may include other
+    /// CertificateChoices.
+    fn with_certificates(cms: &[u8], certificates: &[Vec<u8>]) -> Vec<u8> {
+        let content_info = elements(der::split(cms).unwrap().1);
+        let mut signed_data = elements(
+            der::split(der::split(&content_info[1]).unwrap().1)
+                .unwrap()
+                .1,
+        );
+        signed_data[3] = der::context(0, certificates);
+        der::sequence(&[
+            content_info[0].clone(),
+            der::context(0, &[der::sequence(&signed_data)]),
+        ])
+    }
+
+    #[test]
+    fn cades_profile_is_enforced() {
+        let key = test_key();
+        let pdf = prepared("ETSI.CAdES.detached", PLACEHOLDER, 8192);
+        let signed = sign_pdf(&pdf, &key).unwrap();
+        let res = crate::verify_pdf_signature(&signed).unwrap();
+        assert_eq!(res.sub_filter, Some(SubFilter::CadesDetached));
+        let plain = sign_pdf(&prepared("adbe.pkcs7.detached", PLACEHOLDER, 8192), &key).unwrap();
+        let res = crate::verify_pdf_signature(&plain).unwrap();
+        assert_eq!(res.sub_filter, Some(SubFilter::Pkcs7Detached));
+
+        // A PKCS#7 blob lacks the signingCertificateV2 attribute CAdES requires
+        let pkcs7_in_cades = fill_placeholder(&pdf, |covered, _| {
+            sign_detached(covered, &key, SubFilter::Pkcs7Detached)
+        })
+        .unwrap();
+        assert!(matches!(
+            crate::verify_pdf_signature(&pkcs7_in_cades),
+            Err(SignatureValidationError::Profile(
+                ProfileViolation::MissingSigningCertificate
+            ))
+        ));
+    }
+
+    #[test]
+    fn signing_certificate_binds_the_signer_certificate() {
+        let key = test_key();
+        // Another certificate with the same issuer, serial and key still verifies the
+        // signature, but is not the one the signer bound
+        let mut other = key.certificate_der().to_vec();
+        *other.last_mut().unwrap() ^= 1;
+        let swap = |cms: Vec<u8>| with_certificates(&cms, std::slice::from_ref(&other));
+
+        // Whatever the field's profile, a CAdES blob carries the binding
+        for sub_filter in ["ETSI.CAdES.detached", "adbe.pkcs7.detached"] {
+            let pdf = prepared(sub_filter, PLACEHOLDER, 8192);
+            let swapped = fill_placeholder(&pdf, |covered, _| {
+                Ok(swap(sign_detached(
+                    covered,
+                    &key,
+                    SubFilter::CadesDetached,
+                )?))
+            })
+            .unwrap();
+            assert!(matches!(
+                crate::verify_pdf_signature(&swapped),
+                Err(SignatureValidationError::SigningCertificateMismatch)
+            ));
+        }
+
+        let content = b"attachment";
+        let cms = sign_detached(content, &key, SubFilter::CadesDetached).unwrap();
+        assert!(matches!(
+            crate::verify_detached_signature(content, &swap(cms), &SignaturePolicy::default()),
+            Err(SignatureValidationError::SigningCertificateMismatch)
+        ));
+
+        // Without the attribute nothing binds the certificate
+        let cms = sign_detached(content, &key, SubFilter::Pkcs7Detached).unwrap();
+        let res =
+            crate::verify_detached_signature(content, &swap(cms), &SignaturePolicy::default())
+                .unwrap();
+        assert!(res.is_valid);
+    }
+
+    /// `first` with the certificates and SignerInfos of `second` appended: one blob signed by
+    /// both signers.
+    fn cosigned(first: &[u8], second: &[u8]) -> Vec<u8> {
+        let signed_data = |cms: &[u8]| {
+            let content_info = elements(der::split(cms).unwrap().1);
+            elements(
+                der::split(der::split(&content_info[1]).unwrap().1)
+                    .unwrap()
+                    .1,
+            )
+        };
+        let (mut merged, other) = (signed_data(first), signed_data(second));
+        for (i, set) in [(3, &other[3]), (4, &other[4])] {
+            let mut items = elements(der::split(&merged[i]).unwrap().1);
+            items.extend(elements(der::split(set).unwrap().1));
+            merged[i] = der::constructed(merged[i][0], &items);
+        }
+        der::sequence(&[
+            der::oid(SIGNED_DATA),
+            der::context(0, &[der::sequence(&merged)]),
+        ])
+    }
+
+    #[test]
+    fn every_signer_info_is_verified_against_its_certificate() {
+        let key = test_key();
+        // The same key gives the same serial number, so only the issuer tells the two
+        // certificates apart
+        let other = SigningKey::self_signed(TEST_KEY, &[("CN", "Someone Else")]).unwrap();
+        let pdf = prepared("ETSI.CAdES.detached", PLACEHOLDER, 16384);
+        let signed = fill_placeholder(&pdf, |covered, sub_filter| {
+            Ok(cosigned(
+                &sign_detached(covered, &key, sub_filter)?,
+                &sign_detached(covered, &other, sub_filter)?,
+            ))
+        })
+        .unwrap();
+
+        let results = crate::verify_pdf_signers(&signed).unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|result| result.is_valid));
+        let subjects: Vec<String> = results
+            .iter()
+            .map(|result| result.signer.as_ref().unwrap().subject.clone())
+            .collect();
+        assert_eq!(
+            subjects,
+            [
+                "C=IN, O=zkPDF Test, CN=zkPDF Test Signer",
+                "CN=Someone Else"
+            ]
+        );
+        // The first SignerInfo is still what `verify_pdf_signature` reports
+        let first = crate::verify_pdf_signature(&signed).unwrap();
+        assert_eq!(first.signer.unwrap().subject, subjects[0]);
+
+        // A broken second signature does not hide behind a good first one
+        let tampered = fill_placeholder(&pdf, |covered, sub_filter| {
+            let mut second = sign_detached(covered, &other, sub_filter)?;
+            let last = second.len() - 1;
+            second[last] ^= 1;
+            Ok(cosigned(
+                &sign_detached(covered, &key, sub_filter)?,
+                &second,
+            ))
+        })
+        .unwrap();
+        let results = crate::verify_pdf_signers(&tampered).unwrap();
+        assert!(results[0].is_valid);
+        assert!(!results[1].is_valid);
+    }
+
+    /// `certificate` with tbsCertificate field `index` replaced, or added when past the end.
+    /// The certificate's own signature is left as it was.
+    fn with_tbs_field(certificate: &[u8], index: usize, field: Vec<u8>) -> Vec<u8> {
+        let mut parts = elements(der::split(certificate).unwrap().1);
+        let mut tbs = elements(der::split(&parts[0]).unwrap().1);
+        if index < tbs.len() {
+            tbs[index] = field;
+        } else {
+            tbs.push(field);
+        }
+        parts[0] = der::sequence(&tbs);
+        der::sequence(&parts)
+    }
+
+    /// `cms` with the fields of its SignerInfo changed by `edit`.
+    fn with_signer_info(cms: &[u8], edit: impl FnOnce(&mut Vec<Vec<u8>>)) -> Vec<u8> {
+        let content_info = elements(der::split(cms).unwrap().1);
+        let mut signed_data = elements(
+            der::split(der::split(&content_info[1]).unwrap().1)
+                .unwrap()
+                .1,
+        );
+        let signer_infos = elements(der::split(&signed_data[4]).unwrap().1);
+        let mut signer_info = elements(der::split(&signer_infos[0]).unwrap().1);
+        edit(&mut signer_info);
+        signed_data[4] = der::set(&[der::sequence(&signer_info)]);
+        der::sequence(&[
+            content_info[0].clone(),
+            der::context(0, &[der::sequence(&signed_data)]),
+        ])
+    }
+
+    /// `cms` with its SignerInfo naming the signer certificate by `sid`, as a version 3
+    /// SignerInfo.
+    fn with_signer_identifier(cms: &[u8], sid: Vec<u8>) -> Vec<u8> {
+        with_signer_info(cms, |signer_info| {
+            signer_info[0] = der::small(3);
+            signer_info[1] = sid;
+        })
+    }
+
+    /// `cms` with `attributes` as its signed attributes, signed again with `key`.
+    fn with_signed_attributes(cms: &[u8], key: &SigningKey, attributes: &[Vec<u8>]) -> Vec<u8> {
+        let signature = rsa_sign(&key.key, &der::constructed(SET, attributes)).unwrap();
+        with_signer_info(cms, |signer_info| {
+            signer_info[3] = der::context(0, attributes);
+            signer_info[5] = der::octet_string(&signature);
+        })
+    }
+
+    #[test]
+    fn signer_info_fields_are_found_by_tag() {
+        let key = test_key();
+        let content = b"attachment";
+        let policy = SignaturePolicy::default();
+        let cms = sign_detached(content, &key, SubFilter::Pkcs7Detached).unwrap();
+        let message_digest = der::sequence(&[
+            der::oid(MESSAGE_DIGEST),
+            der::set(&[der::octet_string(&Sha256::digest(content))]),
+        ]);
+
+        // A lone signed attribute reads like an explicitly tagged value
+        let lone = with_signed_attributes(&cms, &key, std::slice::from_ref(&message_digest));
+        assert!(
+            crate::verify_detached_signature(content, &lone, &policy)
+                .unwrap()
+                .is_valid
+        );
+
+        // Signed attributes longer than 64 KiB, as embedded revocation data can make them
+        let revocation_info = der::sequence(&[
+            der::oid(&[1, 2, 840, 113583, 1, 1, 8]),
+            der::set(&[der::sequence(&[der::octet_string(&[0x5a; 70_000])])]),
+        ]);
+        let long = with_signed_attributes(&cms, &key, &[message_digest, revocation_info]);
+        assert!(
+            crate::verify_detached_signature(content, &long, &policy)
+                .unwrap()
+                .is_valid
+        );
+
+        // Unsigned attributes follow the signature without moving it
+        let unsigned = with_signer_info(&cms, |signer_info| {
+            signer_info.push(der::context(
+                1,
+                &[
+                    der::sequence(&[der::oid(&[1, 2, 3, 4]), der::set(&[der::null()])]),
+                    der::sequence(&[der::oid(&[1, 2, 3, 5]), der::set(&[der::null()])]),
+                ],
+            ))
+        });
+        assert!(
+            crate::verify_detached_signature(content, &unsigned, &policy)
+                .unwrap()
+                .is_valid
+        );
+
+        // Fields out of order are rejected rather than read from the wrong place
+        for edit in [
+            |signer_info: &mut Vec<Vec<u8>>| signer_info.swap(4, 5),
+            |signer_info: &mut Vec<Vec<u8>>| signer_info.push(der::null()),
+            |signer_info: &mut Vec<Vec<u8>>| {
+                signer_info.remove(4);
+            },
+        ] {
+            let broken = with_signer_info(&cms, edit);
+            assert!(matches!(
+                crate::verify_detached_signature(content, &broken, &policy),
+                Err(SignatureValidationError::Pkcs7(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn signer_identifiers_name_the_whole_issuer_or_the_key_identifier() {
+        let key = test_key();
+        let pdf = prepared("adbe.pkcs7.detached", PLACEHOLDER, 8192);
+
+        // The same attributes in a single RDN read the same once formatted, but name another
+        // issuer
+        let one_rdn = der::sequence(&[der::set(&[
+            der::sequence(&[der::oid(&[2, 5, 4, 6]), der::printable_string("IN")]),
+            der::sequence(&[der::oid(&[2, 5, 4, 10]), der::utf8_string("zkPDF Test")]),
+            der::sequence(&[
+                der::oid(&[2, 5, 4, 3]),
+                der::utf8_string("zkPDF Test Signer"),
+            ]),
+        ])]);
+        let reissued = with_tbs_field(key.certificate_der(), 3, one_rdn);
+        let signed = fill_placeholder(&pdf, |covered, sub_filter| {
+            Ok(with_certificates(
+                &sign_detached(covered, &key, sub_filter)?,
+                std::slice::from_ref(&reissued),
+            ))
+        })
+        .unwrap();
+        assert!(crate::verify_pdf_signature(&signed).is_err());
+
+        // A version 3 SignerInfo names the certificate by its subjectKeyIdentifier extension
+        let key_id = [0x5a; 20];
+        let certificate = with_tbs_field(
+            key.certificate_der(),
+            7,
+            der::context(
+                3,
+                &[der::sequence(&[der::sequence(&[
+                    der::oid(&[2, 5, 29, 14]),
+                    der::octet_string(&der::octet_string(&key_id)),
+                ])])],
+            ),
+        );
+        let key = SigningKey::new(TEST_KEY, &certificate).unwrap();
+        let by_key_id = |key_id: &[u8]| {
+            fill_placeholder(&pdf, |covered, sub_filter| {
+                Ok(with_signer_identifier(
+                    &sign_detached(covered, &key, sub_filter)?,
+                    der::tlv(0x80, key_id),
+                ))
+            })
+            .unwrap()
+        };
+        let res = crate::verify_pdf_signature(&by_key_id(&key_id)).unwrap();
+        assert!(res.is_valid);
+        assert_eq!(
+            res.signer.unwrap().subject,
+            "C=IN, O=zkPDF Test, CN=zkPDF Test Signer"
+        );
+        assert!(crate::verify_pdf_signature(&by_key_id(&[0xa5; 20])).is_err());
+    }
+
+    #[test]
+    fn spki_of_a_key_the_verifier_cannot_use() {
+        // id-ecPublicKey on prime256v1, with an uncompressed point
+        let mut point = vec![0x04];
+        point.extend([0x11; 64]);
+        let ec_spki = der::sequence(&[
+            der::sequence(&[
+                der::oid(&[1, 2, 840, 10045, 2, 1]),
+                der::oid(&[1, 2, 840, 10045, 3, 1, 7]),
+            ]),
+            der::bit_string(&point),
+        ]);
+        let certificate = with_tbs_field(test_key().certificate_der(), 6, ec_spki.clone());
+        assert_eq!(
+            crate::pkcs7_parser::certificate_spki_der(&certificate).unwrap(),
+            ec_spki.as_slice()
+        );
+
+        let pdf = prepared("adbe.pkcs7.detached", PLACEHOLDER, 8192);
+        let signed = fill_placeholder(&pdf, |covered, sub_filter| {
+            Ok(with_certificates(
+                &sign_detached(covered, &test_key(), sub_filter)?,
+                std::slice::from_ref(&certificate),
+            ))
+        })
+        .unwrap();
+        assert!(crate::get_signer_details(&signed).is_err());
+        assert_eq!(
+            crate::signer_key_fingerprint_sha256(&signed).unwrap(),
+            <[u8; 32]>::from(Sha256::digest(&ec_spki))
+        );
+    }
+
+    #[test]
+    fn attribute_certificates_are_skipped() {
+        let key = test_key();
+        let pdf = prepared("ETSI.CAdES.detached", PLACEHOLDER, 8192);
+        let signed = fill_placeholder(&pdf, |covered, sub_filter| {
+            let cms = sign_detached(covered, &key, sub_filter)?;
+            // v2AttrCert [2] IMPLICIT AttributeCertificate, with a placeholder body
+            let attribute_certificate = der::context(2, &[der::sequence(&[der::small(1)])]);
+            Ok(with_certificates(
+                &cms,
+                &[attribute_certificate, key.certificate_der().to_vec()],
+            ))
+        })
+        .unwrap();
+
+        let res = crate::verify_pdf_signature(&signed).unwrap();
+        assert!(res.is_valid);
+        assert_eq!(
+            res.signer.unwrap().subject,
+            "C=IN, O=zkPDF Test, CN=zkPDF Test Signer"
+        );
+    }
+
+    #[test]
+    fn provided_certificate_and_chain() {
+        let self_signed = test_key();
+        let key = SigningKey::new(TEST_KEY, self_signed.certificate_der())
+            .unwrap()
+            .with_chain(vec![self_signed.certificate_der().to_vec()]);
+        let p7s = sign_detached(b"content", &key, SubFilter::CadesDetached).unwrap();
+        let res = crate::verify_detached_signature(b"content", &p7s, &SignaturePolicy::default())
+            .unwrap();
+        assert!(res.is_valid);
+        assert_eq!(
+            res.signer.subject,
+            "C=IN, O=zkPDF Test, CN=zkPDF Test Signer"
+        );
+
+        assert!(matches!(
+            SigningKey::new(TEST_KEY, b"not a certificate"),
+            Err(SigningError::InvalidCertificate(_))
+        ));
+        assert!(matches!(
+            SigningKey::new(b"not a key", self_signed.certificate_der()),
+            Err(SigningError::InvalidKey(_))
+        ));
+    }
+
+    #[test]
+    fn placeholder_errors() {
+        let key = test_key();
+        assert!(matches!(
+            sign_pdf(b"%PDF-1.7\n%%EOF", &key),
+            Err(SigningError::NoPlaceholder(_))
+        ));
+        assert!(matches!(
+            sign_pdf(&prepared("adbe.pkcs7.detached", "0 0 0 0", 8192), &key),
+            Err(SigningError::ByteRangeTooShort(_))
+        ));
+        assert!(matches!(
+            sign_pdf(&prepared("adbe.pkcs7.detached", PLACEHOLDER, 200), &key),
+            Err(SigningError::SignatureTooLarge { capacity: 100, .. })
+        ));
+    }
+
+    #[test]
+    fn contents_may_come_before_the_byte_range() {
+        let pdf = b"1 0 obj << /Contents <0000> /SubFilter /ETSI.CAdES.detached /ByteRange [ 0 0 ] >> endobj";
+        let placeholder = find_placeholder(pdf).unwrap();
+        assert_eq!(&pdf[placeholder.contents], b"<0000>");
+        assert_eq!(&pdf[placeholder.byte_range], b" 0 0 ");
+        assert_eq!(placeholder.sub_filter, SubFilter::CadesDetached);
+    }
+
+    #[test]
+    fn detached_signature_verifies() {
+        let content = b"<Invoice><Total>120.00</Total></Invoice>";
+        let p7s = sign_detached(content, &test_key(), SubFilter::Pkcs7Detached).unwrap();
+
+        let res =
+            crate::verify_detached_signature(content, &p7s, &SignaturePolicy::strict()).unwrap();
+        assert!(res.is_valid);
+        assert_eq!(
+            res.signer.subject,
+            "C=IN, O=zkPDF Test, CN=zkPDF Test Signer"
+        );
+
+        let tampered =
+            crate::verify_detached_signature(b"<Invoice/>", &p7s, &SignaturePolicy::default());
+        assert!(matches!(
+            tampered,
+            Err(SignatureValidationError::MessageDigestMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn detached_signature_policy() {
+        let content = b"attachment";
+        let p7s = sign_detached(content, &test_key(), SubFilter::Pkcs7Detached).unwrap();
+        let check = |policy: SignaturePolicy| {
+            crate::verify_detached_signature(content, &p7s, &policy).map(|res| res.is_valid)
+        };
+        let violation = |policy: SignaturePolicy| match check(policy) {
+            Err(SignatureValidationError::Policy(v)) => v,
+            other => panic!("expected a policy violation, got {:?}", other),
+        };
+
+        let key = crate::verify_detached_signature(content, &p7s, &SignaturePolicy::default())
+            .unwrap()
+            .public_key;
+        assert!(check(SignaturePolicy {
+            trusted_keys: vec![key],
+            validation_time: Some("2030-01-01T00:00:00Z".into()),
+            ..SignaturePolicy::strict()
+        })
+        .unwrap());
+        assert_eq!(
+            violation(SignaturePolicy {
+                trusted_keys: vec![vec![0x30, 0x00]],
+                ..SignaturePolicy::default()
+            }),
+            PolicyViolation::UntrustedSigner
+        );
+        assert_eq!(
+            violation(SignaturePolicy {
+                min_key_bits: 3072,
+                ..SignaturePolicy::default()
+            }),
+            PolicyViolation::KeyTooShort {
+                bits: 2048,
+                min: 3072
+            }
+        );
+        assert!(matches!(
+            violation(SignaturePolicy {
+                validation_time: Some("2050-01-01T00:00:00Z".into()),
+                ..SignaturePolicy::default()
+            }),
+            PolicyViolation::CertificateExpired { .. }
+        ));
+    }
+
+    /// An RFC 3161 token over `content`, as a TSA would issue it at `gen_time`.
+    fn timestamp_token(content: &[u8], key: &SigningKey, gen_time: &str) -> Vec<u8> {
+        let tst_info = der::sequence(&[
+            der::small(1),
+            der::oid(&[1, 2, 3, 4, 1]),
+            der::sequence(&[
+                der::algorithm(SHA256),
+                der::octet_string(&Sha256::digest(content)),
+            ]),
+            der::small(42),
+            der::tlv(0x18, gen_time.as_bytes()),
+        ]);
+        signed_data(
+            &[1, 2, 840, 113549, 1, 9, 16, 1, 4],
+            &tst_info,
+            true,
+            key,
+            SubFilter::Pkcs7Detached,
+        )
+        .unwrap()
+    }
+
+    /// Appends a document timestamp field as an incremental update and fills it.
+    fn add_timestamp(pdf: &[u8], key: &SigningKey) -> Vec<u8> {
+        let mut pdf = pdf.to_vec();
+        pdf.extend_from_slice(
+            format!(
+                "3 0 obj\n<< /Type /DocTimeStamp /Filter /Adobe.PPKLite /SubFilter /ETSI.RFC3161 \
+                 /ByteRange [{}] /Contents <{}> >>\nendobj\n%%EOF\n",
+                PLACEHOLDER,
+                "0".repeat(8192)
+            )
+            .as_bytes(),
+        );
+        fill_placeholder(&pdf, |covered, _| {
+            Ok(timestamp_token(covered, key, "20250301120000.5Z"))
+        })
+        .unwrap()
+    }
+
+    /// The elements of a DER SEQUENCE or SET's contents.
+    fn elements(mut contents: &[u8]) -> Vec<Vec<u8>> {
+        let mut out = Vec::new();
+        while let Some((element, _, rest)) = der::split(contents) {
+            out.push(element.to_vec());
+            contents = rest;
+        }
+        out
+    }
+
+    /// `cms` with a timeStampToken unsigned attribute added, holding the token `stamp` makes
+    /// from the signature value.
+    fn with_signature_timestamp(cms: &[u8], stamp: impl FnOnce(&[u8]) -> Vec<u8>) -> Vec<u8> {
+        let content_info = elements(der::split(cms).unwrap().1);
+        let mut signed_data = elements(
+            der::split(der::split(&content_info[1]).unwrap().1)
+                .unwrap()
+                .1,
+        );
+        let signer_infos = elements(der::split(&signed_data[4]).unwrap().1);
+        let mut signer_info = elements(der::split(&signer_infos[0]).unwrap().1);
+        let token = stamp(der::split(&signer_info[5]).unwrap().1);
+        signer_info.push(der::context(
+            1,
+            &[der::sequence(&[
+                der::oid(&[1, 2, 840, 113549, 1, 9, 16, 2, 14]),
+                der::set(&[token]),
+            ])],
+        ));
+        signed_data[4] = der::set(&[der::sequence(&signer_info)]);
+        der::sequence(&[
+            content_info[0].clone(),
+            der::context(0, &[der::sequence(&signed_data)]),
+        ])
+    }
+
+    #[test]
+    fn signature_timestamps_attest_the_signing_time() {
+        let key = test_key();
+        let pdf = prepared("adbe.pkcs7.detached", PLACEHOLDER, 16384);
+        let signed = fill_placeholder(&pdf, |covered, sub_filter| {
+            let cms = sign_detached(covered, &key, sub_filter)?;
+            Ok(with_signature_timestamp(&cms, |signature| {
+                timestamp_token(signature, &key, "20250301120000Z")
+            }))
+        })
+        .unwrap();
+
+        let result = crate::verify_pdf_signature(&signed).unwrap();
+        assert!(result.is_valid);
+        assert_eq!(result.signing_time, None);
+        let timestamp = result.timestamp.unwrap();
+        let timestamp = timestamp.verified().unwrap();
+        assert_eq!(timestamp.gen_time, "2025-03-01T12:00:00Z");
+        assert_eq!(
+            timestamp.tsa.subject,
+            "C=IN, O=zkPDF Test, CN=zkPDF Test Signer"
+        );
+
+        // Without the attribute nothing is attested
+        let plain = sign_pdf(&pdf, &key).unwrap();
+        assert!(crate::verify_pdf_signature(&plain)
+            .unwrap()
+            .timestamp
+            .is_none());
+
+        // A token must imprint the signature value, not the document
+        let misplaced = fill_placeholder(&pdf, |covered, sub_filter| {
+            let cms = sign_detached(covered, &key, sub_filter)?;
+            Ok(with_signature_timestamp(&cms, |_| {
+                timestamp_token(covered, &key, "20250301120000Z")
+            }))
+        })
+        .unwrap();
+        let result = crate::verify_pdf_signature(&misplaced).unwrap();
+        assert!(result.is_valid);
+        assert!(matches!(
+            result.timestamp,
+            Some(SignatureTimestamp::Failed(_))
+        ));
+
+        // A corrupted token is reported with the signature, which still verifies
+        let corrupted = fill_placeholder(&pdf, |covered, sub_filter| {
+            let cms = sign_detached(covered, &key, sub_filter)?;
+            Ok(with_signature_timestamp(&cms, |signature| {
+                let mut token = timestamp_token(signature, &key, "20250301120000Z");
+                let half = token.len() / 2;
+                token[half..].fill(0);
+                token
+            }))
+        })
+        .unwrap();
+        let result = crate::verify_pdf_signature(&corrupted).unwrap();
+        assert!(result.is_valid);
+        assert_eq!(result.signing_time, None);
+        assert!(matches!(
+            result.timestamp,
+            Some(SignatureTimestamp::Failed(_))
+        ));
+    }
+
+    #[test]
+    fn document_timestamps_are_verified_apart_from_approvals() {
+        let key = test_key();
+        let signed = sign_pdf(&prepared("adbe.pkcs7.detached", PLACEHOLDER, 8192), &key).unwrap();
+        let stamped = add_timestamp(&signed, &key);
+        assert_eq!(
+            crate::signature_kinds(&stamped),
+            [SignatureKind::Approval, SignatureKind::DocumentTimestamp]
+        );
+
+        assert!(crate::verify_pdf_signature(&stamped).unwrap().is_valid);
+        let timestamps = crate::verify_pdf_timestamps(&stamped).unwrap();
+        assert_eq!(timestamps.len(), 1);
+        assert!(timestamps[0].is_valid);
+        assert_eq!(timestamps[0].gen_time, "2025-03-01T12:00:00Z");
+        assert_eq!(timestamps[0].serial_number, [42]);
+        assert_eq!(
+            timestamps[0].tsa.subject,
+            "C=IN, O=zkPDF Test, CN=zkPDF Test Signer"
+        );
+
+        // A timestamp alone is not an approval signature
+        let timestamp_only = add_timestamp(b"%PDF-1.7\n", &key);
+        assert!(crate::verify_pdf_timestamps(&timestamp_only).unwrap()[0].is_valid);
+        assert!(matches!(
+            crate::verify_pdf_signature(&timestamp_only),
+            Err(SignatureValidationError::SignedBytes(
+                SignedBytesError::NoApprovalSignature
+            ))
+        ));
+
+        // Changing a covered byte breaks the imprint
+        let mut tampered = stamped.clone();
+        tampered[signed.len() + 2] = b'4';
+        assert!(matches!(
+            crate::verify_pdf_timestamps(&tampered),
+            Err(SignatureValidationError::MessageDigestMismatch { .. })
+        ));
+    }
+
+    /// An OCSP response signed by `key` as the issuer of its own certificate, giving `status`
+    /// for the certificate with `serial` as of `this_update`.
+    fn ocsp_response(
+        key: &SigningKey,
+        serial: &[u8],
+        status: Vec<u8>,
+        this_update: &str,
+    ) -> Vec<u8> {
+        let public_key = der::sequence(&[
+            der::unsigned(&key.key.n().to_bytes_be()),
+            der::unsigned(&key.key.e().to_bytes_be()),
+        ]);
+        let cert_id = der::sequence(&[
+            der::algorithm(SHA256),
+            der::octet_string(&Sha256::digest(&key.issuer)),
+            der::octet_string(&Sha256::digest(&public_key)),
+            der::unsigned(serial),
+        ]);
+        let tbs = der::sequence(&[
+            der::context(1, std::slice::from_ref(&key.issuer)),
+            der::tlv(0x18, this_update.as_bytes()),
+            der::sequence(&[der::sequence(&[
+                cert_id,
+                status,
+                der::tlv(0x18, this_update.as_bytes()),
+            ])]),
+        ]);
+        let basic = der::sequence(&[
+            tbs.clone(),
+            der::algorithm(SHA256_WITH_RSA),
+            der::bit_string(&rsa_sign(&key.key, &tbs).unwrap()),
+        ]);
+        der::sequence(&[
+            der::tlv(0x0a, &[0]),
+            der::context(
+                0,
+                &[der::sequence(&[
+                    der::oid(&[1, 3, 6, 1, 5, 5, 7, 48, 1, 1]),
+                    der::octet_string(&basic),
+                ])],
+            ),
+        ])
+    }
+
+    /// A CRL signed by `key` as the issuer of its own certificate, listing `revoked` serial
+    /// numbers with their revocation dates.
+    fn crl(key: &SigningKey, this_update: &str, revoked: &[(&[u8], &str)]) -> Vec<u8> {
+        let mut tbs = vec![
+            der::small(1),
+            der::algorithm(SHA256_WITH_RSA),
+            key.issuer.clone(),
+            der::utc_time(this_update),
+        ];
+        if !revoked.is_empty() {
+            let entries: Vec<Vec<u8>> = revoked
+                .iter()
+                .map(|(serial, date)| der::sequence(&[der::unsigned(serial), der::utc_time(date)]))
+                .collect();
+            tbs.push(der::sequence(&entries));
+        }
+        let tbs = der::sequence(&tbs);
+        der::sequence(&[
+            tbs.clone(),
+            der::algorithm(SHA256_WITH_RSA),
+            der::bit_string(&rsa_sign(&key.key, &tbs).unwrap()),
+        ])
+    }
+
+    /// Appends an incremental update giving the catalog a `/DSS` with `ocsps` and `crls`.
+    fn with_dss(pdf: &[u8], ocsps: &[Vec<u8>], crls: &[Vec<u8>]) -> Vec<u8> {
+        let mut pdf = pdf.to_vec();
+        let mut streams = Vec::new();
+        let mut refs = |items: &[Vec<u8>]| {
+            let mut refs = Vec::new();
+            for item in items {
+                let id = 11 + streams.len();
+                refs.push(format!("{} 0 R", id));
+                streams.push((id, item.clone()));
+            }
+            refs.join(" ")
+        };
+        let dss = format!("<< /OCSPs [{}] /CRLs [{}] >>", refs(ocsps), refs(crls));
+        pdf.extend_from_slice(
+            format!(
+                "1 0 obj\n<< /Type /Catalog /DSS 10 0 R >>\nendobj\n10 0 obj\n{}\nendobj\n",
+                dss
+            )
+            .as_bytes(),
+        );
+        for (id, data) in streams {
+            pdf.extend_from_slice(
+                format!("{} 0 obj\n<< /Length {} >>\nstream\n", id, data.len()).as_bytes(),
+            );
+            pdf.extend_from_slice(&data);
+            pdf.extend_from_slice(b"\nendstream\nendobj\n");
+        }
+        pdf.extend_from_slice(b"%%EOF\n");
+        pdf
+    }
+
+    #[test]
+    fn revocation_status_at_signing_time() {
+        let key = test_key();
+        // Signed at 2025-03-01T12:00:00Z, as a timestamp attests
+        let signed = fill_placeholder(
+            &prepared("adbe.pkcs7.detached", PLACEHOLDER, 16384),
+            |covered, sub_filter| {
+                let cms = sign_detached(covered, &key, sub_filter)?;
+                Ok(with_signature_timestamp(&cms, |signature| {
+                    timestamp_token(signature, &key, "20250301120000Z")
+                }))
+            },
+        )
+        .unwrap();
+        let status = |ocsps: &[Vec<u8>], crls: &[Vec<u8>]| {
+            let report =
+                crate::verify_pdf_signature_report(&with_dss(&signed, ocsps, crls)).unwrap();
+            assert!(report.is_valid());
+            report.revocation
+        };
+        let good = || der::tlv(0x80, &[]);
+        let revoked = |at: &str| der::context(1, &[der::tlv(0x18, at.as_bytes())]);
+
+        assert_eq!(
+            crate::verify_pdf_signature_report(&signed)
+                .unwrap()
+                .revocation,
+            RevocationStatus::Unknown
+        );
+        assert_eq!(
+            status(
+                &[ocsp_response(&key, &key.serial, good(), "20250302000000Z")],
+                &[]
+            ),
+            RevocationStatus::Good {
+                source: RevocationSource::Ocsp,
+                checked_at: "2025-03-02T00:00:00Z".into()
+            }
+        );
+        // A good status from before signing says nothing about the signing time
+        assert_eq!(
+            status(
+                &[ocsp_response(&key, &key.serial, good(), "20250201000000Z")],
+                &[]
+            ),
+            RevocationStatus::Unknown
+        );
+        // Nor does a status for another certificate, or one the issuer did not sign
+        assert_eq!(
+            status(&[ocsp_response(&key, &[7], good(), "20250302000000Z")], &[]),
+            RevocationStatus::Unknown
+        );
+        let mut forged = ocsp_response(&key, &key.serial, good(), "20250302000000Z");
+        *forged.last_mut().unwrap() ^= 1;
+        assert_eq!(status(&[forged], &[]), RevocationStatus::Unknown);
+
+        assert_eq!(
+            status(
+                &[ocsp_response(
+                    &key,
+                    &key.serial,
+                    revoked("20250201000000Z"),
+                    "20250302000000Z"
+                )],
+                &[]
+            ),
+            RevocationStatus::Revoked {
+                source: RevocationSource::Ocsp,
+                revoked_at: "2025-02-01T00:00:00Z".into()
+            }
+        );
+        // Revoked after signing: the certificate was still good when it signed
+        assert_eq!(
+            status(
+                &[ocsp_response(
+                    &key,
+                    &key.serial,
+                    revoked("20250305000000Z"),
+                    "20250306000000Z"
+                )],
+                &[]
+            ),
+            RevocationStatus::Good {
+                source: RevocationSource::Ocsp,
+                checked_at: "2025-03-06T00:00:00Z".into()
+            }
+        );
+
+        assert_eq!(
+            status(
+                &[],
+                &[crl(
+                    &key,
+                    "250302000000Z",
+                    &[(&key.serial, "250201000000Z")]
+                )]
+            ),
+            RevocationStatus::Revoked {
+                source: RevocationSource::Crl,
+                revoked_at: "2025-02-01T00:00:00Z".into()
+            }
+        );
+        assert_eq!(
+            status(
+                &[],
+                &[crl(&key, "250302000000Z", &[(&[7], "250201000000Z")])]
+            ),
+            RevocationStatus::Good {
+                source: RevocationSource::Crl,
+                checked_at: "2025-03-02T00:00:00Z".into()
+            }
+        );
+    }
+
+    /// A certificate for the test key named `subject`, issued by `issuer` and signed with the
+    /// test key, with `extensions`.
+    fn issued(subject: &str, issuer: &str, extensions: &[Vec<u8>]) -> Vec<u8> {
+        let name = |cn: &str| {
+            der::sequence(&[der::set(&[der::sequence(&[
+                der::oid(&[2, 5, 4, 3]),
+                der::utf8_string(cn),
+            ])])])
+        };
+        let certificate = test_key().certificate_der().to_vec();
+        let certificate = with_tbs_field(&certificate, 3, name(issuer));
+        let certificate = with_tbs_field(&certificate, 5, name(subject));
+        let certificate = with_tbs_field(
+            &certificate,
+            7,
+            der::context(3, &[der::sequence(extensions)]),
+        );
+        let mut parts = elements(der::split(&certificate).unwrap().1);
+        parts[2] = der::bit_string(&rsa_sign(&test_key().key, &parts[0]).unwrap());
+        der::sequence(&parts)
+    }
+
+    #[test]
+    fn trust_anchors_are_reached_through_ca_certificates_only() {
+        use crate::trust_store::{find_trust_anchor, TrustAnchor};
+
+        let ca = |path_len: Option<u8>| {
+            let mut constraints = vec![der::tlv(0x01, &[0xff])];
+            constraints.extend(path_len.map(der::small));
+            [
+                der::sequence(&[
+                    der::oid(&[2, 5, 29, 19]),
+                    der::octet_string(&der::sequence(&constraints)),
+                ]),
+                // keyCertSign and cRLSign
+                der::sequence(&[
+                    der::oid(&[2, 5, 29, 15]),
+                    der::octet_string(&der::tlv(0x03, &[0x01, 0x06])),
+                ]),
+            ]
+        };
+        let root = issued("Root CA", "Root CA", &ca(None));
+        let anchors = [TrustAnchor {
+            name: "Root CA",
+            der: &root,
+        }];
+        let pdf = prepared("adbe.pkcs7.detached", PLACEHOLDER, 16384);
+        let anchor_of = |chain: Vec<Vec<u8>>| {
+            let signer = issued("Signer", "Sub CA", &[]);
+            let key = SigningKey::new(TEST_KEY, &signer)
+                .unwrap()
+                .with_chain(chain);
+            let signed = sign_pdf(&pdf, &key).unwrap();
+            let (signature_der, _) = crate::get_signature_der(&signed).unwrap();
+            find_trust_anchor(&signature_der, &anchors)
+                .unwrap()
+                .map(|anchor| anchor.name)
+        };
+
+        assert_eq!(
+            anchor_of(vec![issued("Sub CA", "Root CA", &ca(None))]),
+            Some("Root CA")
+        );
+        assert_eq!(
+            anchor_of(vec![issued("Sub CA", "Root CA", &ca(Some(0)))]),
+            Some("Root CA")
+        );
+        // An end-entity certificate under the root cannot issue the signer's
+        assert_eq!(anchor_of(vec![issued("Sub CA", "Root CA", &[])]), None);
+        // Nor can a CA whose key is not for signing certificates
+        let [basic_constraints, _] = ca(None);
+        assert_eq!(
+            anchor_of(vec![issued("Sub CA", "Root CA", &[basic_constraints])]),
+            None
+        );
+        // Nor one below a CA whose path length forbids further CAs
+        assert_eq!(
+            anchor_of(vec![
+                issued("Sub CA", "Mid CA", &ca(None)),
+                issued("Mid CA", "Root CA", &ca(Some(0))),
+            ]),
+            None
+        );
+        assert_eq!(
+            anchor_of(vec![
+                issued("Sub CA", "Mid CA", &ca(None)),
+                issued("Mid CA", "Root CA", &ca(Some(1))),
+            ]),
+            Some("Root CA")
+        );
+    }
+}
@@ -0,0 +1,130 @@
+//! Just enough DER encoding for a self-signed certificate and a CMS SignedData.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+pub const SEQUENCE: u8 = 0x30;
+pub const SET: u8 = 0x31;
+
+pub fn tlv(tag: u8, content: &[u8]) -> Vec<u8> {
+    let mut out = vec![tag];
+    let len = content.len();
+    if len < 0x80 {
+        out.push(len as u8);
+    } else {
+        let bytes: Vec<u8> = len
+            .to_be_bytes()
+            .into_iter()
+            .skip_while(|&b| b == 0)
+            .collect();
+        out.push(0x80 | bytes.len() as u8);
+        out.extend(bytes);
+    }
+    out.extend_from_slice(content);
+    out
+}
+
+pub fn constructed(tag: u8, parts: &[Vec<u8>]) -> Vec<u8> {
+    tlv(tag, &parts.concat())
+}
+
+pub fn sequence(parts: &[Vec<u8>]) -> Vec<u8> {
+    constructed(SEQUENCE, parts)
+}
+
+pub fn set(parts: &[Vec<u8>]) -> Vec<u8> {
+    constructed(SET, parts)
+}
+
+/// A DER `SET OF`, with the elements sorted by their encoding.
+pub fn set_of(parts: &[Vec<u8>]) -> Vec<u8> {
+    let mut sorted = parts.to_vec();
+    sorted.sort();
+    set(&sorted)
+}
+
+/// `[n]`, constructed.
+pub fn context(n: u8, parts: &[Vec<u8>]) -> Vec<u8> {
+    constructed(0xa0 | n, parts)
+}
+
+/// A non-negative INTEGER from big-endian magnitude bytes.
+pub fn unsigned(magnitude: &[u8]) -> Vec<u8> {
+    let trimmed: Vec<u8> = magnitude.iter().copied().skip_while(|&b| b == 0).collect();
+    let mut content = Vec::with_capacity(trimmed.len() + 1);
+    if trimmed.first().is_none_or(|&b| b & 0x80 != 0) {
+        content.push(0);
+    }
+    content.extend(trimmed);
+    tlv(0x02, &content)
+}
+
+pub fn small(n: u8) -> Vec<u8> {
+    unsigned(&[n])
+}
+
+pub fn null() -> Vec<u8> {
+    vec![0x05, 0x00]
+}
+
+pub fn octet_string(bytes: &[u8]) -> Vec<u8> {
+    tlv(0x04, bytes)
+}
+
+pub fn bit_string(bytes: &[u8]) -> Vec<u8> {
+    tlv(0x03, &[&[0][..], bytes].concat())
+}
+
+pub fn utf8_string(s: &str) -> Vec<u8> {
+    tlv(0x0c, s.as_bytes())
+}
+
+pub fn printable_string(s: &str) -> Vec<u8> {
+    tlv(0x13, s.as_bytes())
+}
+
+/// `YYMMDDHHMMSSZ`
+pub fn utc_time(s: &str) -> Vec<u8> {
+    tlv(0x17, s.as_bytes())
+}
+
+/// An OBJECT IDENTIFIER from its dotted arcs.
+pub fn oid(arcs: &[u64]) -> Vec<u8> {
+    let mut content = vec![(arcs[0] * 40 + arcs[1]) as u8];
+    for &arc in &arcs[2..] {
+        let mut groups = vec![(arc & 0x7f) as u8];
+        let mut rest = arc >> 7;
+        while rest > 0 {
+            groups.push(0x80 | (rest & 0x7f) as u8);
+            rest >>= 7;
+        }
+        content.extend(groups.into_iter().rev());
+    }
+    tlv(0x06, &content)
+}
+
+/// AlgorithmIdentifier with NULL parameters.
+pub fn algorithm(arcs: &[u64]) -> Vec<u8> {
+    sequence(&[oid(arcs), null()])
+}
+
+/// Splits the first element off `data`: the whole element, its contents and the bytes after
+/// it. Definite lengths only.
+pub fn split(data: &[u8]) -> Option<(&[u8], &[u8], &[u8])> {
+    let first = *data.get(1)? as usize;
+    let (header, len) = if first < 0x80 {
+        (2, first)
+    } else {
+        let count = first & 0x7f;
+        if count == 0 || count > 4 {
+            return None;
+        }
+        let len = data
+            .get(2..2 + count)?
+            .iter()
+            .fold(0usize, |acc, &b| acc << 8 | b as usize);
+        (2 + count, len)
+    };
+    let end = header.checked_add(len)?;
+    (end <= data.len()).then(|| (&data[..end], &data[header..end], &data[end..]))
+}
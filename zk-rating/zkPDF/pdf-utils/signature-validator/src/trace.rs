@@ -0,0 +1,48 @@
+//! Optional `tracing` instrumentation, enabled with the `tracing` feature. `timed!` opens a
+//! debug span that emits an `elapsed_us` event when dropped; `trace_event!` emits a debug event.
+//! Without the feature both compile to nothing.
+
+#[cfg(feature = "tracing")]
+pub(crate) struct Timed {
+    _span: tracing::span::EnteredSpan,
+    start: std::time::Instant,
+}
+
+#[cfg(feature = "tracing")]
+impl Timed {
+    pub(crate) fn new(span: tracing::Span) -> Self {
+        Self {
+            _span: span.entered(),
+            start: std::time::Instant::now(),
+        }
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl Drop for Timed {
+    fn drop(&mut self) {
+        // Still inside the span: `_span` is exited after this returns
+        let elapsed_us = self.start.elapsed().as_micros() as u64;
+        tracing::debug!(elapsed_us, "done");
+    }
+}
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) struct Timed;
+
+macro_rules! timed {
+    ($name:literal $(, $($fields:tt)*)?) => {{
+        #[cfg(feature = "tracing")]
+        let timed = $crate::trace::Timed::new(tracing::debug_span!($name $(, $($fields)*)?));
+        #[cfg(not(feature = "tracing"))]
+        let timed = $crate::trace::Timed;
+        timed
+    }};
+}
+
+macro_rules! trace_event {
+    ($($args:tt)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::debug!($($args)*);
+    };
+}
@@ -0,0 +1,160 @@
+//! Issuer pinning: whether the certificate that made a signature chains up to one of a fixed
+//! set of CA certificates. The chain is built from the certificates the SignedData carries,
+//! and each link must be signed by the key of the next. Every carried certificate the chain
+//! passes through must be a CA (basicConstraints `cA`) allowed to sign certificates (keyUsage
+//! `keyCertSign`) within its pathLenConstraint, so an end-entity certificate under a pinned
+//! CA cannot issue one of its own. Validity periods are not checked.
+//! The caller supplies the CA certificates, e.g. through `SignaturePolicy::trust_anchors`.
+
+use alloc::vec::Vec;
+use rsa::RsaPublicKey;
+
+use crate::pkcs7_parser::{
+    parse_certificate, parse_signer_details_at, signed_data_certificates, Certificate,
+};
+use crate::types::{SignatureAlgorithm, SignatureResult};
+use crate::{calculate_signed_data_hash, get_pkcs1v15_padding, verify_rsa_signature};
+
+/// A pinned CA certificate, borrowed from a `static` (e.g. through `include_bytes!`) or from
+/// certificates loaded at run time.
+#[derive(Debug, PartialEq, Eq)]
+pub struct TrustAnchor<'a> {
+    /// Name the certificate is pinned under, such as `CCA India 2022`.
+    pub name: &'a str,
+    pub der: &'a [u8],
+}
+
+/// Longest chain followed from the signer certificate to an anchor.
+const MAX_CHAIN_LEN: usize = 8;
+
+/// The anchor in `anchors` that the signer certificate of the PKCS#7 SignedData blob
+/// `signature_der` chains up to, if any. The signer certificate itself may be pinned.
+pub fn find_trust_anchor<'a>(
+    signature_der: &[u8],
+    anchors: &'a [TrustAnchor<'a>],
+) -> SignatureResult<Option<&'a TrustAnchor<'a>>> {
+    find_trust_anchor_at(signature_der, 0, anchors)
+}
+
+/// `find_trust_anchor` for the certificate of the SignerInfo at `index`.
+pub fn find_trust_anchor_at<'a>(
+    signature_der: &[u8],
+    index: usize,
+    anchors: &'a [TrustAnchor<'a>],
+) -> SignatureResult<Option<&'a TrustAnchor<'a>>> {
+    if anchors.is_empty() {
+        return Ok(None);
+    }
+    let signer = parse_signer_details_at(signature_der, index)?;
+    // Certificates with keys other than RSA can neither be checked nor check others
+    let carried: Vec<Certificate> = signed_data_certificates(signature_der)?
+        .into_iter()
+        .filter_map(|der| parse_certificate(der).ok())
+        .collect();
+    let pinned: Vec<(&TrustAnchor, Certificate)> = anchors
+        .iter()
+        .filter_map(|anchor| Some((anchor, parse_certificate(anchor.der).ok()?)))
+        .collect();
+
+    let Some(mut current) = carried
+        .iter()
+        .find(|cert| cert.serial_number == signer.serial_number && cert.issuer == signer.issuer)
+    else {
+        return Ok(None);
+    };
+    // CA certificates passed between the signer certificate and `current`
+    for below in 0..MAX_CHAIN_LEN {
+        let anchor = pinned.iter().find(|(_, cert)| {
+            cert.der == current.der || cert.subject == current.issuer && is_signed_by(current, cert)
+        });
+        if let Some((anchor, _)) = anchor {
+            return Ok(Some(*anchor));
+        }
+        // A self-signed certificate that is not pinned ends the chain
+        if current.subject == current.issuer {
+            return Ok(None);
+        }
+        match carried.iter().find(|cert| {
+            cert.subject == current.issuer && is_signed_by(current, cert) && may_issue(cert, below)
+        }) {
+            Some(issuer) => current = issuer,
+            None => return Ok(None),
+        }
+    }
+    Ok(None)
+}
+
+/// Whether `certificate` may issue a certificate with `below` CA certificates under it.
+fn may_issue(certificate: &Certificate, below: usize) -> bool {
+    certificate.ca
+        && certificate.key_cert_sign
+        && certificate
+            .path_len
+            .is_none_or(|path_len| below <= path_len)
+}
+
+pub(crate) fn is_signed_by(certificate: &Certificate, issuer: &Certificate) -> bool {
+    verifies_with(
+        certificate.tbs_der,
+        &certificate.algorithm,
+        &certificate.signature,
+        issuer,
+    )
+}
+
+/// Whether `signature` over `signed` verifies with the RSA key of `signer`.
+pub(crate) fn verifies_with(
+    signed: &[u8],
+    algorithm: &SignatureAlgorithm,
+    signature: &[u8],
+    signer: &Certificate,
+) -> bool {
+    let Ok(key) = RsaPublicKey::new(
+        rsa::BigUint::from_bytes_be(&signer.modulus),
+        rsa::BigUint::from_bytes_be(&signer.exponent.to_bytes_be()),
+    ) else {
+        return false;
+    };
+    let (Ok(padding), Ok(digest)) = (
+        get_pkcs1v15_padding(algorithm),
+        calculate_signed_data_hash(signed, algorithm),
+    ) else {
+        return false;
+    };
+    verify_rsa_signature(&key, padding, &digest, signature).unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signed_bytes_extractor::get_signature_der;
+
+    static SAMPLE_PDF_BYTES: &[u8] = include_bytes!("../../sample-pdfs/digitally_signed.pdf");
+    static GST_PDF_BYTES: &[u8] = include_bytes!("../../sample-pdfs/GST-certificate.pdf");
+
+    #[test]
+    fn test_signer_chains_to_pinned_root() {
+        let (signature_der, _) = get_signature_der(SAMPLE_PDF_BYTES).unwrap();
+        // The sample carries its whole chain: signer, GeoTrust CA for Adobe and Adobe Root CA
+        let certificates = signed_data_certificates(&signature_der).unwrap();
+        let root = certificates
+            .iter()
+            .map(|der| parse_certificate(der).unwrap())
+            .find(|cert| cert.subject == cert.issuer)
+            .expect("sample carries no root");
+        let anchors = [TrustAnchor {
+            name: "Adobe Root CA",
+            der: root.der,
+        }];
+
+        let anchor = find_trust_anchor(&signature_der, &anchors).unwrap();
+        assert_eq!(anchor.map(|anchor| anchor.name), Some("Adobe Root CA"));
+
+        let (gst_signature_der, _) = get_signature_der(GST_PDF_BYTES).unwrap();
+        assert_eq!(
+            find_trust_anchor(&gst_signature_der, &anchors).unwrap(),
+            None
+        );
+        assert_eq!(find_trust_anchor(&signature_der, &[]).unwrap(), None);
+    }
+}
@@ -1,18 +1,43 @@
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
 use hex::FromHexError;
-use simple_asn1::{ASN1DecodeErr, OID};
+use sha2::{Digest, Sha256};
 use thiserror::Error;
 
+use crate::asn1::{ASN1DecodeErr, OID};
+use crate::trust_store::TrustAnchor;
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum SignatureAlgorithm {
     Sha1WithRsaEncryption,
     Sha256WithRsaEncryption,
     Sha384WithRsaEncryption,
     Sha512WithRsaEncryption,
+    /// Ed25519 over SHA-512 signed attributes (RFC 8419).
+    Ed25519,
     RsaEncryption,
     RsaEncryptionWithUnknownHash(OID),
     Unknown(OID),
 }
 
+impl fmt::Display for SignatureAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SignatureAlgorithm::Sha1WithRsaEncryption => write!(f, "sha1WithRSAEncryption"),
+            SignatureAlgorithm::Sha256WithRsaEncryption => write!(f, "sha256WithRSAEncryption"),
+            SignatureAlgorithm::Sha384WithRsaEncryption => write!(f, "sha384WithRSAEncryption"),
+            SignatureAlgorithm::Sha512WithRsaEncryption => write!(f, "sha512WithRSAEncryption"),
+            SignatureAlgorithm::Ed25519 => write!(f, "Ed25519"),
+            SignatureAlgorithm::RsaEncryption => write!(f, "rsaEncryption"),
+            SignatureAlgorithm::RsaEncryptionWithUnknownHash(oid)
+            | SignatureAlgorithm::Unknown(oid) => write!(f, "{:?}", oid),
+        }
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum SignedBytesError {
     #[error("PDF is not digitally signed: /ByteRange not found")]
@@ -27,7 +52,9 @@ pub enum SignedBytesError {
     InvalidByteRangeCount,
     #[error("ByteRange values out of bounds")]
     ByteRangeOutOfBounds,
-    #[error("Contents not found after ByteRange")]
+    #[error("ByteRange leaves bytes other than /Contents unsigned")]
+    UncoveredBytes,
+    #[error("ByteRange gap is not preceded by /Contents")]
     ContentsNotFound,
     #[error("Start '<' not found after Contents")]
     ContentsStartMissing,
@@ -36,7 +63,9 @@ pub enum SignedBytesError {
     #[error("Invalid hex in Contents")]
     InvalidContentsUtf8,
     #[error("Contents hex parse error: {0}")]
-    ContentsHexDecode(#[from] FromHexError),
+    ContentsHexDecode(FromHexError),
+    #[error("PDF has document timestamps but no approval signature")]
+    NoApprovalSignature,
 }
 
 pub type SignedBytesResult<T> = Result<T, SignedBytesError>;
@@ -78,19 +107,365 @@ pub enum SignatureValidationError {
     InvalidPublicKey(String),
     #[error("RSA signature verification error: {0}")]
     SignatureVerification(String),
+    #[error("Signature rejected by policy: {0}")]
+    Policy(PolicyViolation),
+    #[error("signingCertificate attribute does not match the signer certificate")]
+    SigningCertificateMismatch,
+    #[error("Signature does not follow its /SubFilter profile: {0}")]
+    Profile(ProfileViolation),
 }
 
 pub type SignatureResult<T> = Result<T, SignatureValidationError>;
 
+/// Errors from the `signing` module.
+#[cfg(feature = "signing")]
+#[derive(Debug, Error)]
+pub enum SigningError {
+    #[error("Invalid signing key: {0}")]
+    InvalidKey(String),
+    #[error("Invalid certificate: {0}")]
+    InvalidCertificate(&'static str),
+    #[error("Certificate does not match the signing key")]
+    KeyMismatch,
+    #[error("RSA signing failed: {0}")]
+    Rsa(String),
+    #[error("No prepared signature field: {0}")]
+    NoPlaceholder(&'static str),
+    #[error("ByteRange placeholder is too short for [{0}]")]
+    ByteRangeTooShort(String),
+    #[error("Signature of {size} bytes exceeds the {capacity} reserved")]
+    SignatureTooLarge { size: usize, capacity: usize },
+}
+
 /// Metadata returned after verifying a PDF signature.
 ///
 /// `is_valid` indicates whether the signature check succeeded.
 /// `message_digest` is the hash that the signer committed to in the PDF (length determined by the
 /// signature algorithm).
-/// `public_key` of pdf signer's certificate in DER format.
+/// `public_key` of pdf signer's certificate in DER format (PKCS#1), or the raw 32 bytes of an
+/// Ed25519 key.
+/// `signer` is the certificate `public_key` was taken from; `None` when the signature could not
+/// be checked.
+/// `field_name` is the `/T` of the signature field, and `coverage` tells whether anything was
+/// appended to the file after signing. Both are left unset when the signature does not come
+/// from a PDF.
+/// `sub_filter` is the CMS profile the signature dictionary declares, when it is one this crate
+/// knows; its rules have been enforced.
+/// `signing_time` is the time the signer claims, from its signingTime signed attribute, and
+/// `timestamp` the RFC 3161 token a TSA issued over the signature value, whose `gen_time`
+/// attests that the signature existed by then if it verified; a token that fails to verify is
+/// reported there without affecting `is_valid`.
 #[derive(Debug, Clone)]
 pub struct PdfSignatureResult {
     pub is_valid: bool,
     pub message_digest: Vec<u8>,
     pub public_key: Vec<u8>,
+    pub signer: Option<SignerDetails>,
+    pub field_name: Option<String>,
+    pub coverage: Option<SignatureCoverage>,
+    pub sub_filter: Option<SubFilter>,
+    pub signing_time: Option<String>,
+    pub timestamp: Option<SignatureTimestamp>,
+}
+
+/// Every check made on a PDF signature and its outcome, from `verify_pdf_signature_report`,
+/// so that a signature that is not valid can be told apart by reason.
+///
+/// `digest` compares the hash of the signed bytes with the signed messageDigest attribute, or
+/// with the digest embedded in the SignedData, and `signature` checks the RSA or Ed25519
+/// signature with the signer certificate's key. `signing_certificate` checks any ESS
+/// signingCertificate[V2] attribute against the signer certificate and `profile` the rules of
+/// `sub_filter`. `signed_attributes` and `algorithm` tell what the signer signed and how, and
+/// `revocation` whether the signer certificate was revoked at the signing time. The other
+/// fields are those of `PdfSignatureResult`.
+#[derive(Debug, Clone)]
+pub struct SignatureReport {
+    pub digest: CheckOutcome,
+    pub signature: CheckOutcome,
+    pub signing_certificate: CheckOutcome,
+    pub profile: CheckOutcome,
+    pub signed_attributes: bool,
+    pub algorithm: SignatureAlgorithm,
+    pub revocation: RevocationStatus,
+    pub coverage: Option<SignatureCoverage>,
+    pub message_digest: Vec<u8>,
+    pub public_key: Vec<u8>,
+    pub signer: SignerDetails,
+    pub field_name: Option<String>,
+    pub sub_filter: Option<SubFilter>,
+    pub signing_time: Option<String>,
+    pub timestamp: Option<SignatureTimestamp>,
+}
+
+impl SignatureReport {
+    /// Whether every check passed or did not apply.
+    pub fn is_valid(&self) -> bool {
+        self.failures().is_empty()
+    }
+
+    /// Why the failed checks failed, in the order the checks are made.
+    pub fn failures(&self) -> Vec<&FailureReason> {
+        [
+            &self.digest,
+            &self.signature,
+            &self.signing_certificate,
+            &self.profile,
+        ]
+        .into_iter()
+        .filter_map(CheckOutcome::failure)
+        .collect()
+    }
+}
+
+/// The outcome of one check of a `SignatureReport`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CheckOutcome {
+    Passed,
+    Failed(FailureReason),
+    /// There was nothing to check, such as the signingCertificate attribute of a signature
+    /// without one.
+    NotApplicable,
+}
+
+impl CheckOutcome {
+    /// Why the check failed, if it did.
+    pub fn failure(&self) -> Option<&FailureReason> {
+        match self {
+            CheckOutcome::Failed(reason) => Some(reason),
+            _ => None,
+        }
+    }
+}
+
+/// Why a check of a `SignatureReport` failed.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum FailureReason {
+    #[error("signed bytes do not hash to the signed message digest")]
+    DigestMismatch {
+        expected: Vec<u8>,
+        calculated: Vec<u8>,
+    },
+    #[error("signature does not verify with the signer certificate's key")]
+    BadSignature,
+    #[error("signingCertificate attribute does not match the signer certificate")]
+    SigningCertificateMismatch,
+    #[error("signature does not follow its /SubFilter profile: {0}")]
+    Profile(ProfileViolation),
+}
+
+/// Whether a signer certificate was revoked when it signed, from the OCSP responses and CRLs
+/// of the document's `/DSS` that its issuer signed (see `revocation`). The signing time is
+/// the `gen_time` of a valid signature timestamp, or else the signer's claimed signingTime.
+/// This is reported rather than counted as a failure.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RevocationStatus {
+    /// Data issued at `checked_at`, no earlier than the signing time, says the certificate was
+    /// not revoked then. Without a signing time, any such data counts.
+    Good {
+        source: RevocationSource,
+        checked_at: String,
+    },
+    /// The certificate was revoked at `revoked_at`, no later than the signing time.
+    Revoked {
+        source: RevocationSource,
+        revoked_at: String,
+    },
+    /// No verified OCSP response or CRL covers the certificate at the signing time.
+    Unknown,
+}
+
+/// Where a `RevocationStatus` comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RevocationSource {
+    Ocsp,
+    Crl,
+}
+
+/// How much of the file a PDF signature's `/ByteRange` covers. Every byte before the end of
+/// the range is signed, except the `/Contents` hex string itself; a range leaving anything
+/// else out is rejected with `SignedBytesError::UncoveredBytes`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignatureCoverage {
+    /// The range runs to the end of the file.
+    FullDocument,
+    /// `appended_bytes` follow the range: incremental updates made after signing, such as
+    /// later signatures, annotations or form fills, which the signature does not vouch for.
+    PartialWithUpdates { appended_bytes: usize },
+}
+
+/// The CMS profile of a signature, as named by the field's `/SubFilter`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SubFilter {
+    /// `adbe.pkcs7.detached`: contentType and messageDigest signed attributes.
+    #[default]
+    Pkcs7Detached,
+    /// `ETSI.CAdES.detached`, the PAdES baseline profile: adds a signingCertificateV2
+    /// attribute binding the signer certificate.
+    CadesDetached,
+}
+
+impl SubFilter {
+    /// The `/SubFilter` name, without the slash.
+    pub fn name(self) -> &'static str {
+        match self {
+            SubFilter::Pkcs7Detached => "adbe.pkcs7.detached",
+            SubFilter::CadesDetached => "ETSI.CAdES.detached",
+        }
+    }
+
+    /// The profile a `/SubFilter` name, without the slash, stands for.
+    pub fn from_name(name: &str) -> Option<Self> {
+        [SubFilter::Pkcs7Detached, SubFilter::CadesDetached]
+            .into_iter()
+            .find(|sub_filter| sub_filter.name() == name)
+    }
+}
+
+/// What a PDF signature dictionary attests to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignatureKind {
+    /// A signer approving the document (`adbe.pkcs7.detached`, `ETSI.CAdES.detached`, ...).
+    Approval,
+    /// A `/DocTimeStamp` with `/SubFilter /ETSI.RFC3161`: an RFC 3161 timestamp token from a
+    /// TSA, proving the covered bytes existed at `genTime`.
+    DocumentTimestamp,
+}
+
+/// Details of the certificate that produced a PDF signature, for display purposes.
+///
+/// `subject` and `issuer` are distinguished names such as `C=US, O=Example, CN=Jane Doe`.
+/// `not_before` and `not_after` are ISO 8601 UTC timestamps. `key_size` is the RSA modulus
+/// length in bits, or 256 for Ed25519. `spki` is the certificate's subjectPublicKeyInfo as
+/// encoded, which identifies the key the same way whatever its algorithm, and `certificate`
+/// the whole certificate as encoded, which certificates sharing a key do not share.
+#[derive(Debug, Clone)]
+pub struct SignerDetails {
+    pub subject: String,
+    pub issuer: String,
+    pub serial_number: Vec<u8>,
+    pub not_before: String,
+    pub not_after: String,
+    pub signature_algorithm: SignatureAlgorithm,
+    pub key_size: u64,
+    pub spki: Vec<u8>,
+    pub certificate: Vec<u8>,
+}
+
+impl SignerDetails {
+    /// SHA-256 of `spki`: the key fingerprint `openssl x509 -pubkey | openssl pkey -pubin
+    /// -outform DER | sha256sum` prints, and HPKP-style pins are the base64 of.
+    pub fn key_fingerprint_sha256(&self) -> [u8; 32] {
+        Sha256::digest(&self.spki).into()
+    }
+}
+
+/// Requirements a detached signature must meet beyond verifying cryptographically. The
+/// default accepts everything `verify_pdf_signature` accepts.
+#[derive(Debug, Clone, Default)]
+pub struct SignaturePolicy<'a> {
+    /// Reject signatures made with SHA-1.
+    pub reject_sha1: bool,
+    /// Minimum RSA modulus length in bits; `0` accepts any size. Ed25519 keys always pass.
+    pub min_key_bits: u64,
+    /// Require signed attributes, so the signature commits to a `messageDigest` attribute
+    /// rather than directly to the content hash.
+    pub require_signed_attributes: bool,
+    /// Signer public keys (PKCS#1 DER or raw Ed25519, as in `PdfSignatureResult::public_key`) that are
+    /// accepted. Empty accepts any signer.
+    pub trusted_keys: Vec<Vec<u8>>,
+    /// A time, as `YYYY-MM-DDTHH:MM:SSZ`, at which the signer certificate must be valid.
+    pub validation_time: Option<String>,
+    /// CA certificates the signer certificate must chain up to, as `trust_store::find_trust_anchor`
+    /// checks it. Empty accepts any issuer.
+    pub trust_anchors: &'a [TrustAnchor<'a>],
+}
+
+impl SignaturePolicy<'_> {
+    /// No SHA-1, at least 2048-bit keys and signed attributes required.
+    pub fn strict() -> Self {
+        Self {
+            reject_sha1: true,
+            min_key_bits: 2048,
+            require_signed_attributes: true,
+            ..Self::default()
+        }
+    }
+}
+
+/// Why a valid signature was rejected by a `SignaturePolicy`.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum PolicyViolation {
+    #[error("SHA-1 signatures are not accepted")]
+    Sha1,
+    #[error("{bits}-bit key is shorter than the required {min} bits")]
+    KeyTooShort { bits: u64, min: u64 },
+    #[error("signature has no signed attributes")]
+    MissingSignedAttributes,
+    #[error("signer key is not trusted")]
+    UntrustedSigner,
+    #[error("signer certificate does not chain up to a pinned certificate")]
+    UntrustedIssuer,
+    #[error("certificate is not valid until {not_before}")]
+    CertificateNotYetValid { not_before: String },
+    #[error("certificate expired at {not_after}")]
+    CertificateExpired { not_after: String },
+}
+
+/// Why a signature that verifies was rejected under the rules of its `SubFilter`.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ProfileViolation {
+    #[error("ETSI.CAdES.detached signatures must have signed attributes")]
+    MissingSignedAttributes,
+    #[error("ETSI.CAdES.detached signatures must have a signingCertificateV2 attribute")]
+    MissingSigningCertificate,
+}
+
+/// Result of `verify_detached_signature`: the same checks as for a PDF, plus the signer
+/// certificate the policy was applied to.
+#[derive(Debug, Clone)]
+pub struct DetachedSignatureResult {
+    pub is_valid: bool,
+    pub message_digest: Vec<u8>,
+    pub public_key: Vec<u8>,
+    pub signer: SignerDetails,
+}
+
+/// The timeStampToken of a signature, as `PdfSignatureResult::timestamp` reports it. A token
+/// is evidence about the signature, not part of it, so one that fails to verify is reported
+/// here rather than failing the signature.
+#[derive(Debug, Clone)]
+pub enum SignatureTimestamp {
+    /// The token imprints the signature value and its SignedData was checked; `gen_time` is
+    /// only proven if `is_valid`.
+    Checked(Box<DocumentTimestampResult>),
+    /// Why the token could not be checked: it is malformed, or imprints other bytes than the
+    /// signature value.
+    Failed(String),
+}
+
+impl SignatureTimestamp {
+    /// The checked token, if its TSA signature is valid.
+    pub fn verified(&self) -> Option<&DocumentTimestampResult> {
+        match self {
+            SignatureTimestamp::Checked(timestamp) if timestamp.is_valid => Some(timestamp),
+            _ => None,
+        }
+    }
+}
+
+/// Result of `verify_pdf_timestamps` for one document timestamp, or of checking the timestamp
+/// token of a signature (`SignatureTimestamp::Checked`).
+///
+/// `gen_time` is the time the TSA asserts, as an ISO 8601 UTC timestamp; it is only proven if
+/// `is_valid`. `message_imprint` is the hash of the covered bytes under `hash_algorithm`, and
+/// `tsa` the certificate that signed the token.
+#[derive(Debug, Clone)]
+pub struct DocumentTimestampResult {
+    pub is_valid: bool,
+    pub gen_time: String,
+    pub hash_algorithm: SignatureAlgorithm,
+    pub message_imprint: Vec<u8>,
+    pub serial_number: Vec<u8>,
+    pub public_key: Vec<u8>,
+    pub tsa: SignerDetails,
 }
@@ -1,7 +1,15 @@
 #!/bin/bash
 
 # Generate WASM module for PDF verification
-echo "🔨 Building WASM module..."
+# Pass --extract-only to build the text extraction bundle (no signature verification) into pkg-extract
+OUT_DIR="pkg"
+CARGO_ARGS=()
+if [ "$1" == "--extract-only" ]; then
+    OUT_DIR="pkg-extract"
+    CARGO_ARGS=(--no-default-features)
+fi
+
+echo "🔨 Building WASM module into $OUT_DIR..."
 
 # Install wasm-pack if not available
 if ! command -v wasm-pack &> /dev/null; then
@@ -10,17 +18,17 @@ if ! command -v wasm-pack &> /dev/null; then
 fi
 
 # Build WASM module
-wasm-pack build --target web --out-dir pkg
+wasm-pack build --target web --out-dir "$OUT_DIR" -- "${CARGO_ARGS[@]}"
 
 # Copy to app public directory
-if [ -d "../../app/public/pkg" ]; then
-    echo "📁 Copying WASM files to app/public/pkg..."
-    cp -r pkg/* ../../app/public/pkg/
+if [ -d "../../app/public/$OUT_DIR" ]; then
+    echo "📁 Copying WASM files to app/public/$OUT_DIR..."
+    cp -r "$OUT_DIR"/* "../../app/public/$OUT_DIR/"
     echo "✅ WASM files copied successfully!"
 else
-    echo "⚠️  app/public/pkg directory not found. Creating it..."
-    mkdir -p ../../app/public/pkg
-    cp -r pkg/* ../../app/public/pkg/
+    echo "⚠️  app/public/$OUT_DIR directory not found. Creating it..."
+    mkdir -p "../../app/public/$OUT_DIR"
+    cp -r "$OUT_DIR"/* "../../app/public/$OUT_DIR/"
     echo "✅ WASM files copied successfully!"
 fi
 
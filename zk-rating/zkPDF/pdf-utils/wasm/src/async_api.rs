@@ -0,0 +1,147 @@
+use extractor::{PdfDocument, types::PdfError};
+use js_sys::{Function, Promise};
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+
+use crate::{JsPdfOptions, options::PdfOptions};
+#[cfg(feature = "verify")]
+use crate::{VerifyAndExtractResponse, verify_and_extract_response};
+#[cfg(feature = "verify")]
+use pdf_core::{PdfVerifiedContent, verify_signature_with_policy};
+
+#[wasm_bindgen(typescript_custom_section)]
+const TS_PROGRESS: &'static str = r#"
+export interface ProgressEvent {
+  /** `parsed` once objects are read, `signature` after verification, `page` after each page. */
+  stage: "parsed" | "signature" | "page";
+  pages_done: number;
+  page_count: number;
+}
+
+export type ProgressCallback = (event: ProgressEvent) => void;
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "ProgressCallback")]
+    pub type JsProgressCallback;
+
+    // Available on both `window` and worker global scopes
+    #[wasm_bindgen(js_name = setTimeout)]
+    fn set_timeout(handler: &Function, timeout: i32) -> JsValue;
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Stage {
+    Parsed,
+    #[cfg_attr(not(feature = "verify"), allow(dead_code))]
+    Signature,
+    Page,
+}
+
+#[derive(Serialize)]
+struct ProgressEvent {
+    stage: Stage,
+    pages_done: usize,
+    page_count: usize,
+}
+
+struct Progress {
+    callback: Option<Function>,
+}
+
+impl Progress {
+    fn new(callback: Option<JsProgressCallback>) -> Self {
+        Self {
+            callback: callback.map(JsCast::unchecked_into),
+        }
+    }
+
+    /// Invokes the callback, if any. An exception thrown by the callback aborts the operation.
+    fn report(&self, stage: Stage, pages_done: usize, page_count: usize) -> Result<(), JsValue> {
+        if let Some(callback) = &self.callback {
+            let event = serde_wasm_bindgen::to_value(&ProgressEvent {
+                stage,
+                pages_done,
+                page_count,
+            })?;
+            callback.call1(&JsValue::NULL, &event)?;
+        }
+        Ok(())
+    }
+}
+
+// Resolves on the next macrotask so the browser can render and handle input between pages
+async fn yield_now() -> Result<(), JsValue> {
+    let promise = Promise::new(&mut |resolve, _reject| {
+        set_timeout(&resolve, 0);
+    });
+    JsFuture::from(promise).await.map(|_| ())
+}
+
+// The outer error comes from JS (a throwing callback); the inner one from the PDF itself
+async fn extract_pages(
+    pdf_bytes: &[u8],
+    options: &PdfOptions,
+    progress: &Progress,
+) -> Result<Result<Vec<String>, PdfError>, JsValue> {
+    let document = match PdfDocument::parse(pdf_bytes, &options.extract_options()) {
+        Ok(document) => document,
+        Err(e) => return Ok(Err(e)),
+    };
+    let page_count = document.page_count();
+    progress.report(Stage::Parsed, 0, page_count)?;
+
+    let mut pages = Vec::with_capacity(page_count);
+    for page in 0..page_count {
+        yield_now().await?;
+        match document.page_text(page) {
+            Ok(text) => pages.push(text),
+            Err(e) => return Ok(Err(e)),
+        }
+        progress.report(Stage::Page, page + 1, page_count)?;
+    }
+    Ok(Ok(pages))
+}
+
+/// WebAssembly export: `wasm_extract_text` that yields to the event loop between pages and
+/// reports progress through `on_progress`. Resolves to an empty list if the PDF cannot be parsed
+#[wasm_bindgen]
+pub async fn wasm_extract_text_async(
+    pdf_bytes: Vec<u8>,
+    options: Option<JsPdfOptions>,
+    on_progress: Option<JsProgressCallback>,
+) -> Result<Vec<String>, JsValue> {
+    let options = PdfOptions::from_js(options)?;
+    let progress = Progress::new(on_progress);
+    Ok(extract_pages(&pdf_bytes, &options, &progress)
+        .await?
+        .unwrap_or_default())
+}
+
+/// WebAssembly export: `wasm_verify_and_extract` that yields to the event loop between pages and
+/// reports progress through `on_progress`
+#[cfg(feature = "verify")]
+#[wasm_bindgen]
+pub async fn wasm_verify_and_extract_async(
+    pdf_bytes: Vec<u8>,
+    options: Option<JsPdfOptions>,
+    on_progress: Option<JsProgressCallback>,
+) -> Result<VerifyAndExtractResponse, JsValue> {
+    let options = PdfOptions::from_js(options)?;
+    let progress = Progress::new(on_progress);
+
+    let content = match verify_signature_with_policy(&pdf_bytes, &options.policy()) {
+        Ok(signature) => {
+            progress.report(Stage::Signature, 0, 0)?;
+            extract_pages(&pdf_bytes, &options, &progress)
+                .await?
+                .map(|pages| PdfVerifiedContent { pages, signature })
+                .map_err(pdf_core::Error::Extraction)
+        }
+        Err(e) => Err(e),
+    };
+    Ok(verify_and_extract_response(content)?)
+}
@@ -0,0 +1,212 @@
+//! `_b64` variants of the exports that take PDF bytes. Each accepts a base64 string or a
+//! `data:` URL (as produced by `FileReader.readAsDataURL`) and decodes it straight into WASM
+//! memory, so callers holding base64 don't need an intermediate `Uint8Array`.
+
+use base64::{Engine as _, engine::general_purpose};
+use wasm_bindgen::prelude::*;
+
+#[cfg(feature = "verify")]
+use crate::{
+    DocumentInfoResponse, SignatureDetailsResponse, VerifyAndExtractResponse,
+    VerifySignatureResponse, VerifyTextResponse,
+    circuit::{
+        CircuitInputResponse, JsClaimDescription, JsNullifierScope, NullifierResponse,
+        wasm_compute_nullifier, wasm_prepare_circuit_input,
+    },
+    claims::{VerifyClaimsResponse, verify_claims_owned},
+    template::{JsDocumentTemplate, TemplateResponse, wasm_apply_template},
+    verify_and_extract_owned, verify_text_owned, wasm_get_document_info,
+    wasm_get_signature_details, wasm_verify_and_extract_async, wasm_verify_gst_certificate,
+    wasm_verify_pdf_signature,
+};
+use crate::{
+    FindTextResponse, FormFieldsResponse, JsPdfOptions, JsProgressCallback, PageTextResponse,
+    extract_text_owned, wasm_extract_form_fields, wasm_extract_page, wasm_extract_text_async,
+    wasm_find_text,
+};
+
+/// Decodes standard base64, optionally wrapped in a `data:<mime>;base64,` URL.
+fn decode_pdf(pdf_b64: &str) -> Result<Vec<u8>, String> {
+    let encoded = match pdf_b64.strip_prefix("data:") {
+        Some(url) => url
+            .split_once(";base64,")
+            .map(|(_, data)| data)
+            .ok_or("Data URL is not base64 encoded")?,
+        None => pdf_b64,
+    };
+    general_purpose::STANDARD
+        .decode(encoded.trim())
+        .map_err(|e| format!("Invalid base64 input: {}", e))
+}
+
+/// `wasm_extract_text` for base64 input
+#[wasm_bindgen]
+pub fn wasm_extract_text_b64(
+    pdf_b64: &str,
+    options: Option<JsPdfOptions>,
+) -> Result<Vec<String>, String> {
+    extract_text_owned(decode_pdf(pdf_b64)?, options)
+}
+
+/// `wasm_extract_page` for base64 input
+#[wasm_bindgen]
+pub fn wasm_extract_page_b64(
+    pdf_b64: &str,
+    page: usize,
+    options: Option<JsPdfOptions>,
+) -> Result<PageTextResponse, String> {
+    wasm_extract_page(&decode_pdf(pdf_b64)?, page, options)
+}
+
+/// `wasm_extract_form_fields` for base64 input
+#[wasm_bindgen]
+pub fn wasm_extract_form_fields_b64(pdf_b64: &str) -> Result<FormFieldsResponse, String> {
+    wasm_extract_form_fields(&decode_pdf(pdf_b64)?)
+}
+
+/// `wasm_find_text` for base64 input
+#[wasm_bindgen]
+pub fn wasm_find_text_b64(
+    pdf_b64: &str,
+    needle: &str,
+    page: Option<u8>,
+    options: Option<JsPdfOptions>,
+) -> Result<FindTextResponse, String> {
+    wasm_find_text(&decode_pdf(pdf_b64)?, needle, page, options)
+}
+
+/// `wasm_extract_text_async` for base64 input
+#[wasm_bindgen]
+pub async fn wasm_extract_text_async_b64(
+    pdf_b64: String,
+    options: Option<JsPdfOptions>,
+    on_progress: Option<JsProgressCallback>,
+) -> Result<Vec<String>, JsValue> {
+    wasm_extract_text_async(decode_pdf(&pdf_b64)?, options, on_progress).await
+}
+
+/// `wasm_verify_and_extract` for base64 input
+#[cfg(feature = "verify")]
+#[wasm_bindgen]
+pub fn wasm_verify_and_extract_b64(
+    pdf_b64: &str,
+    options: Option<JsPdfOptions>,
+) -> Result<VerifyAndExtractResponse, String> {
+    verify_and_extract_owned(decode_pdf(pdf_b64)?, options)
+}
+
+/// `wasm_verify_claims` for base64 input
+#[cfg(feature = "verify")]
+#[wasm_bindgen]
+pub fn wasm_verify_claims_b64(
+    pdf_b64: &str,
+    claims_json: &str,
+    options: Option<JsPdfOptions>,
+) -> Result<VerifyClaimsResponse, String> {
+    verify_claims_owned(decode_pdf(pdf_b64)?, claims_json, options)
+}
+
+/// `wasm_verify_and_extract_async` for base64 input
+#[cfg(feature = "verify")]
+#[wasm_bindgen]
+pub async fn wasm_verify_and_extract_async_b64(
+    pdf_b64: String,
+    options: Option<JsPdfOptions>,
+    on_progress: Option<JsProgressCallback>,
+) -> Result<VerifyAndExtractResponse, JsValue> {
+    wasm_verify_and_extract_async(decode_pdf(&pdf_b64)?, options, on_progress).await
+}
+
+/// `wasm_verify_text` for base64 input
+#[cfg(feature = "verify")]
+#[wasm_bindgen]
+pub fn wasm_verify_text_b64(
+    pdf_b64: &str,
+    page_number: u8,
+    sub_string: &str,
+    offset: usize,
+    options: Option<JsPdfOptions>,
+) -> Result<VerifyTextResponse, String> {
+    verify_text_owned(
+        decode_pdf(pdf_b64)?,
+        page_number,
+        sub_string,
+        offset,
+        options,
+    )
+}
+
+/// `wasm_verify_pdf_signature` for base64 input
+#[cfg(feature = "verify")]
+#[wasm_bindgen]
+pub fn wasm_verify_pdf_signature_b64(pdf_b64: &str) -> Result<VerifySignatureResponse, String> {
+    wasm_verify_pdf_signature(&decode_pdf(pdf_b64)?)
+}
+
+/// `wasm_get_document_info` for base64 input
+#[cfg(feature = "verify")]
+#[wasm_bindgen]
+pub fn wasm_get_document_info_b64(pdf_b64: &str) -> Result<DocumentInfoResponse, String> {
+    wasm_get_document_info(&decode_pdf(pdf_b64)?)
+}
+
+/// `wasm_get_signature_details` for base64 input
+#[cfg(feature = "verify")]
+#[wasm_bindgen]
+pub fn wasm_get_signature_details_b64(pdf_b64: &str) -> Result<SignatureDetailsResponse, String> {
+    wasm_get_signature_details(&decode_pdf(pdf_b64)?)
+}
+
+/// `wasm_verify_gst_certificate` for base64 input
+#[cfg(feature = "verify")]
+#[wasm_bindgen]
+pub fn wasm_verify_gst_certificate_b64(
+    pdf_b64: &str,
+    options: Option<JsPdfOptions>,
+) -> Result<TemplateResponse, String> {
+    wasm_verify_gst_certificate(&decode_pdf(pdf_b64)?, options)
+}
+
+/// `wasm_apply_template` for base64 input
+#[cfg(feature = "verify")]
+#[wasm_bindgen]
+pub fn wasm_apply_template_b64(
+    pdf_b64: &str,
+    template: JsDocumentTemplate,
+    options: Option<JsPdfOptions>,
+) -> Result<TemplateResponse, String> {
+    wasm_apply_template(&decode_pdf(pdf_b64)?, template, options)
+}
+
+/// `wasm_prepare_circuit_input` for base64 input
+#[cfg(feature = "verify")]
+#[wasm_bindgen]
+pub fn wasm_prepare_circuit_input_b64(
+    pdf_b64: &str,
+    claim: JsClaimDescription,
+) -> Result<CircuitInputResponse, String> {
+    wasm_prepare_circuit_input(&decode_pdf(pdf_b64)?, claim)
+}
+
+/// `wasm_compute_nullifier` for base64 input
+#[cfg(feature = "verify")]
+#[wasm_bindgen]
+pub fn wasm_compute_nullifier_b64(
+    pdf_b64: &str,
+    page_number: u8,
+    substring: &str,
+    offset: u32,
+    scope: Option<JsNullifierScope>,
+    external_nullifier: Option<String>,
+    user_secret: Option<String>,
+) -> Result<NullifierResponse, String> {
+    wasm_compute_nullifier(
+        &decode_pdf(pdf_b64)?,
+        page_number,
+        substring,
+        offset,
+        scope,
+        external_nullifier,
+        user_secret,
+    )
+}
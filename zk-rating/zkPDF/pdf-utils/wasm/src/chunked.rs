@@ -0,0 +1,126 @@
+use wasm_bindgen::prelude::*;
+
+#[cfg(feature = "verify")]
+use crate::{
+    DocumentInfoResponse, SignatureDetailsResponse, VerifyAndExtractResponse,
+    VerifySignatureResponse, VerifyTextResponse, verify_and_extract_owned, verify_text_owned,
+    wasm_get_document_info, wasm_get_signature_details, wasm_verify_pdf_signature,
+};
+use crate::{
+    FindTextResponse, JsPdfOptions, PageTextResponse, extract_text_owned, wasm_extract_page,
+    wasm_find_text,
+};
+
+/// Accumulates a PDF that arrives in chunks, e.g. from a `ReadableStream` reader, so the caller
+/// never has to build one contiguous copy on the JS side before handing it to WASM.
+///
+/// ```javascript
+/// const reader = new PdfChunkReader(file.size);
+/// for await (const chunk of file.stream()) reader.push(chunk);
+/// const result = reader.verify_and_extract(); // consumes the reader
+/// ```
+///
+/// Methods that verify or extract text consume the reader and pass the buffer on without
+/// cloning it. Read-only queries borrow it and can be called any number of times first.
+#[wasm_bindgen]
+pub struct PdfChunkReader {
+    buffer: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl PdfChunkReader {
+    /// Creates an empty reader. Passing the total size up front avoids reallocating as chunks
+    /// are appended.
+    #[wasm_bindgen(constructor)]
+    pub fn new(expected_len: Option<usize>) -> PdfChunkReader {
+        PdfChunkReader {
+            buffer: Vec::with_capacity(expected_len.unwrap_or(0)),
+        }
+    }
+
+    /// Appends the next chunk. Fails as soon as the first bytes show the input is not a PDF.
+    pub fn push(&mut self, chunk: &[u8]) -> Result<(), String> {
+        const HEADER: &[u8] = b"%PDF-";
+
+        let checked = self.buffer.len().min(HEADER.len());
+        self.buffer.extend_from_slice(chunk);
+        let available = self.buffer.len().min(HEADER.len());
+        if available > checked && self.buffer[..available] != HEADER[..available] {
+            self.buffer = Vec::new();
+            return Err("Input is not a PDF: missing %PDF- header".to_string());
+        }
+        Ok(())
+    }
+
+    /// Number of bytes received so far.
+    #[wasm_bindgen(getter)]
+    pub fn len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buffer.is_empty()
+    }
+
+    /// Same as `wasm_extract_text` on the accumulated bytes.
+    pub fn extract_text(self, options: Option<JsPdfOptions>) -> Result<Vec<String>, String> {
+        extract_text_owned(self.buffer, options)
+    }
+
+    /// Same as `wasm_extract_page` on the accumulated bytes.
+    pub fn extract_page(
+        &self,
+        page: usize,
+        options: Option<JsPdfOptions>,
+    ) -> Result<PageTextResponse, String> {
+        wasm_extract_page(&self.buffer, page, options)
+    }
+
+    /// Same as `wasm_find_text` on the accumulated bytes.
+    pub fn find_text(
+        &self,
+        needle: &str,
+        page: Option<u8>,
+        options: Option<JsPdfOptions>,
+    ) -> Result<FindTextResponse, String> {
+        wasm_find_text(&self.buffer, needle, page, options)
+    }
+}
+
+#[cfg(feature = "verify")]
+#[wasm_bindgen]
+impl PdfChunkReader {
+    /// Same as `wasm_verify_and_extract` on the accumulated bytes.
+    pub fn verify_and_extract(
+        self,
+        options: Option<JsPdfOptions>,
+    ) -> Result<VerifyAndExtractResponse, String> {
+        verify_and_extract_owned(self.buffer, options)
+    }
+
+    /// Same as `wasm_verify_text` on the accumulated bytes.
+    pub fn verify_text(
+        self,
+        page_number: u8,
+        sub_string: &str,
+        offset: usize,
+        options: Option<JsPdfOptions>,
+    ) -> Result<VerifyTextResponse, String> {
+        verify_text_owned(self.buffer, page_number, sub_string, offset, options)
+    }
+
+    /// Same as `wasm_verify_pdf_signature` on the accumulated bytes.
+    pub fn verify_signature(&self) -> Result<VerifySignatureResponse, String> {
+        wasm_verify_pdf_signature(&self.buffer)
+    }
+
+    /// Same as `wasm_get_document_info` on the accumulated bytes.
+    pub fn document_info(&self) -> Result<DocumentInfoResponse, String> {
+        wasm_get_document_info(&self.buffer)
+    }
+
+    /// Same as `wasm_get_signature_details` on the accumulated bytes.
+    pub fn signature_details(&self) -> Result<SignatureDetailsResponse, String> {
+        wasm_get_signature_details(&self.buffer)
+    }
+}
@@ -0,0 +1,695 @@
+use pdf_core::{
+    find_text,
+    nullifier::{
+        self, ClaimHashes, HashSuite, blinded_hash, compute_nullifier, keccak256, scoped_nullifier,
+        secret_bound_nullifier, secret_commitment,
+    },
+    verify_and_extract_strict, verify_text,
+};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::{JsCast, prelude::*};
+use zkpdf_errors::{ErrorCode, ZkpdfError};
+
+use crate::ErrorResult;
+
+#[wasm_bindgen(typescript_custom_section)]
+const TS_CIRCUIT: &'static str = r#"
+export interface ClaimDescription {
+  page_number: number;
+  substring: string;
+  /** Offset of `substring` on the page; the first occurrence is used when omitted. */
+  offset?: number;
+  /** Disclose `substring` in the proof's public values instead of only its hash. */
+  reveal?: boolean;
+  /** What the proof's nullifier is bound to; `per_claim` when omitted. */
+  nullifier_scope?: NullifierScope;
+  /** 0x-prefixed bytes32 domain separator of the application the nullifier is for. */
+  external_nullifier?: string;
+  /** 0x-prefixed bytes32 secret of the holder to bind the nullifier to. */
+  user_secret?: string;
+}
+
+export type CircuitInputResult =
+  | {
+      success: true;
+      page_number: number;
+      offset: number;
+      substring: string;
+      /** JSON body for the prover server's `/prove` and `/fixture` endpoints. */
+      request_json: string;
+    }
+  | ErrorResult;
+
+/** Nullifier binding: the claim itself (substring, page and offset), the document, or the
+ * document and its signer. */
+export type NullifierScope = "per_claim" | "per_document" | "per_signer_and_doc";
+
+export type NullifierResult =
+  | {
+      success: true;
+      substring_matches: boolean;
+      /** 0x-prefixed bytes32 values, as committed in the proof's public values. */
+      message_digest_hash: string;
+      signer_key_hash: string;
+      substring_hash: string;
+      nullifier: string;
+    }
+  | ErrorResult;
+
+export type PublicValuesCheckResult =
+  | {
+      success: true;
+      /** True when `mismatches` is empty. */
+      consistent: boolean;
+      /** Names of the public values that disagree with the expected claim. */
+      mismatches: (
+        | "claim_type"
+        | "negated"
+        | "date_claim"
+        | "substring_matches"
+        | "substring_hash"
+        | "nullifier"
+        | "nullifier_scope"
+        | "external_nullifier"
+        | "secret_commitment"
+        | "hash_suite"
+        | "revealed"
+      )[];
+      /** Kind of claim proven, zkpdf_lib's `ClaimType`; 1 for a substring claim. */
+      claim_type: number;
+      substring_matches: boolean;
+      message_digest_hash: string;
+      signer_key_hash: string;
+      substring_hash: string;
+      nullifier: string;
+      /** Pages of the document, and the Merkle root of their text hashes. */
+      page_count: number;
+      pages_root: string;
+      /** keccak256 of the signer certificate's subject DN, and its validity window in Unix
+       * seconds; zero when the certificate is unknown. */
+      signer_subject_hash: string;
+      not_before: number;
+      not_after: number;
+      /** Unix time a TSA attests the signature existed at; zero without a valid
+       * timestamp. */
+      timestamped_at: number;
+      /** Commitment to the template id and field name of a template claim; zero
+       * otherwise. */
+      template_claim: string;
+      /** keccak256 of the holder's name, such as a GST legal name; zero otherwise. */
+      holder_name_hash: string;
+      /** Scope the nullifier was computed under (0 per claim, 1 per document, 2 per signer
+       * and document), and the application's external nullifier; zero if none. */
+      nullifier_scope: number;
+      external_nullifier: string;
+      /** Commitment to the holder's secret, for a nullifier bound to one; zero otherwise. */
+      secret_commitment: string;
+      /** 0 when the hashes are keccak256, 1 for Poseidon, which is not recomputed here. */
+      hash_suite: number;
+      /** keccak256 of the signer certificate's DER; zero when the certificate is unknown. */
+      signer_cert_hash: string;
+      /** 0x-prefixed revealed value; `0x` when the claim did not reveal it. */
+      revealed: string;
+    }
+  | ErrorResult;
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "ClaimDescription")]
+    pub type JsClaimDescription;
+
+    #[wasm_bindgen(typescript_type = "CircuitInputResult")]
+    pub type CircuitInputResponse;
+
+    #[wasm_bindgen(typescript_type = "NullifierScope")]
+    pub type JsNullifierScope;
+
+    #[wasm_bindgen(typescript_type = "NullifierResult")]
+    pub type NullifierResponse;
+
+    #[wasm_bindgen(typescript_type = "PublicValuesCheckResult")]
+    pub type PublicValuesCheckResponse;
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ClaimDescription {
+    page_number: u8,
+    substring: String,
+    offset: Option<usize>,
+    #[serde(default)]
+    reveal: bool,
+    #[serde(default)]
+    nullifier_scope: NullifierScope,
+    external_nullifier: Option<String>,
+    user_secret: Option<String>,
+}
+
+/// Request body accepted by the prover server.
+#[derive(Serialize)]
+struct ProofRequestBody<'a> {
+    pdf_bytes: &'a [u8],
+    page_number: u8,
+    sub_string: &'a str,
+    offset: u32,
+    reveal: bool,
+    nullifier_scope: NullifierScope,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    external_nullifier: Option<[u8; 32]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    user_secret: Option<[u8; 32]>,
+}
+
+#[derive(Serialize)]
+struct CircuitInputResult {
+    success: bool,
+    page_number: u8,
+    offset: u32,
+    substring: String,
+    request_json: String,
+}
+
+#[derive(Serialize)]
+struct NullifierResult {
+    success: bool,
+    substring_matches: bool,
+    message_digest_hash: String,
+    signer_key_hash: String,
+    substring_hash: String,
+    nullifier: String,
+}
+
+#[derive(Serialize)]
+struct PublicValuesCheckResult {
+    success: bool,
+    consistent: bool,
+    mismatches: Vec<&'static str>,
+    claim_type: u8,
+    substring_matches: bool,
+    message_digest_hash: String,
+    signer_key_hash: String,
+    substring_hash: String,
+    nullifier: String,
+    page_count: u32,
+    pages_root: String,
+    signer_subject_hash: String,
+    not_before: u64,
+    not_after: u64,
+    timestamped_at: u64,
+    template_claim: String,
+    holder_name_hash: String,
+    nullifier_scope: u8,
+    external_nullifier: String,
+    secret_commitment: String,
+    hash_suite: u8,
+    signer_cert_hash: String,
+    revealed: String,
+}
+
+/// Nullifier scopes the guest program can prove, `pdf_core::nullifier::NullifierScope`.
+#[allow(clippy::enum_variant_names)] // Named as in pdf_core
+#[derive(Serialize, Deserialize, Default, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+enum NullifierScope {
+    /// Bound to the document, signer, substring, page and offset.
+    #[default]
+    PerClaim,
+    /// Bound to the document only.
+    PerDocument,
+    /// Bound to the document and signer.
+    PerSignerAndDoc,
+}
+
+impl NullifierScope {
+    fn from_js(scope: Option<JsNullifierScope>) -> Result<Self, String> {
+        match scope {
+            Some(scope) => serde_wasm_bindgen::from_value(scope.into())
+                .map_err(|e| format!("Invalid nullifier scope: {}", e)),
+            None => Ok(Self::default()),
+        }
+    }
+}
+
+impl From<NullifierScope> for nullifier::NullifierScope {
+    fn from(scope: NullifierScope) -> Self {
+        match scope {
+            NullifierScope::PerClaim => Self::PerClaim,
+            NullifierScope::PerDocument => Self::PerDocument,
+            NullifierScope::PerSignerAndDoc => Self::PerSignerAndDoc,
+        }
+    }
+}
+
+/// Parses an optional 0x-prefixed bytes32 argument such as an external nullifier.
+fn parse_bytes32(value: Option<&str>, name: &str) -> Result<Option<[u8; 32]>, String> {
+    value
+        .map(|value| {
+            hex::decode(value.strip_prefix("0x").unwrap_or(value))
+                .ok()
+                .and_then(|bytes| bytes.try_into().ok())
+                .ok_or_else(|| format!("Invalid {}: expected 32 hex-encoded bytes", name))
+        })
+        .transpose()
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    format!("0x{}", hex::encode(bytes))
+}
+
+/// `PUBLIC_VALUES_VERSION` of zkpdf_lib: the `PublicValuesStruct` layout decoded here.
+const PUBLIC_VALUES_VERSION: u16 = 1;
+
+/// `ClaimType::Substring` of zkpdf_lib, the only claim checked here.
+const SUBSTRING_CLAIM_TYPE: u8 = 1;
+
+/// `PublicValuesStruct` as the guest program commits it, less its version.
+struct PublicValues {
+    claim_type: u8,
+    substring_matches: bool,
+    message_digest_hash: [u8; 32],
+    signer_key_hash: [u8; 32],
+    substring_hash: [u8; 32],
+    nullifier: [u8; 32],
+    negated: bool,
+    date_claim: [u8; 32],
+    page_count: u32,
+    pages_root: [u8; 32],
+    signer_subject_hash: [u8; 32],
+    not_before: u64,
+    not_after: u64,
+    timestamped_at: u64,
+    template_claim: [u8; 32],
+    holder_name_hash: [u8; 32],
+    nullifier_scope: u8,
+    external_nullifier: [u8; 32],
+    secret_commitment: [u8; 32],
+    hash_suite: u8,
+    signer_cert_hash: [u8; 32],
+    revealed: Vec<u8>,
+}
+
+/// Words in the head of the encoded struct: twenty-two fields and the offset of `revealed`.
+const HEAD_WORDS: usize = 23;
+
+/// Decodes the ABI encoding of `PublicValuesStruct`. The struct has a dynamic member, so it is
+/// encoded as an offset to its head, the head (one word per static field and the offset of
+/// `revealed`), and the length and padded bytes of `revealed`. The version, the first field, is
+/// checked before the rest, which another layout would lay out differently.
+fn decode_public_values(public_values_hex: &str) -> Result<PublicValues, ZkpdfError> {
+    let invalid = |message: String| ZkpdfError::new(ErrorCode::InvalidInput, message);
+    let hex_str = public_values_hex
+        .strip_prefix("0x")
+        .unwrap_or(public_values_hex);
+    let bytes =
+        hex::decode(hex_str).map_err(|e| invalid(format!("Invalid public values hex: {}", e)))?;
+
+    let word = |index: usize| -> Result<[u8; 32], ZkpdfError> {
+        bytes
+            .get(index * 32..(index + 1) * 32)
+            .and_then(|chunk| chunk.try_into().ok())
+            .ok_or_else(|| invalid(format!("Public values end before word {}", index)))
+    };
+    let decode_u64 = |word: [u8; 32]| {
+        word[..24]
+            .iter()
+            .all(|&b| b == 0)
+            .then(|| u64::from_be_bytes(word[24..].try_into().unwrap()))
+            .ok_or_else(|| invalid("Public values are not a PublicValuesStruct".to_string()))
+    };
+    let decode_usize = |word: [u8; 32], expected: Option<usize>| {
+        let value = decode_u64(word)
+            .ok()
+            .and_then(|value| usize::try_from(value).ok());
+        match (value, expected) {
+            (Some(value), None) => Ok(value),
+            (Some(value), Some(expected)) if value == expected => Ok(value),
+            _ => Err(invalid(
+                "Public values are not a PublicValuesStruct".to_string(),
+            )),
+        }
+    };
+    let decode_bool = |word: [u8; 32]| match (word[..31].iter().all(|&b| b == 0), word[31]) {
+        (true, 0) => Ok(false),
+        (true, 1) => Ok(true),
+        _ => Err(invalid("Public values contain an invalid bool".to_string())),
+    };
+
+    decode_usize(word(0)?, Some(32))?;
+    let version = decode_u64(word(1)?)?;
+    if version != u64::from(PUBLIC_VALUES_VERSION) {
+        return Err(invalid(format!(
+            "Public values version {} is not supported, expected {}",
+            version, PUBLIC_VALUES_VERSION
+        )));
+    }
+    decode_usize(word(HEAD_WORDS)?, Some(32 * HEAD_WORDS))?;
+    let length = decode_usize(word(HEAD_WORDS + 1)?, None)?;
+    let start = 32 * (HEAD_WORDS + 2);
+    let padded = length.div_ceil(32) * 32;
+    if bytes.len() != start + padded {
+        return Err(invalid(format!(
+            "Public values must be {} bytes for {} revealed bytes, got {}",
+            start + padded,
+            length,
+            bytes.len()
+        )));
+    }
+
+    Ok(PublicValues {
+        claim_type: u8::try_from(decode_u64(word(2)?)?)
+            .map_err(|_| invalid("Public values contain an invalid claim type".to_string()))?,
+        substring_matches: decode_bool(word(3)?)?,
+        message_digest_hash: word(4)?,
+        signer_key_hash: word(5)?,
+        substring_hash: word(6)?,
+        nullifier: word(7)?,
+        negated: decode_bool(word(8)?)?,
+        date_claim: word(9)?,
+        page_count: u32::try_from(decode_usize(word(10)?, None)?)
+            .map_err(|_| invalid("Public values contain an invalid page count".to_string()))?,
+        pages_root: word(11)?,
+        signer_subject_hash: word(12)?,
+        not_before: decode_u64(word(13)?)?,
+        not_after: decode_u64(word(14)?)?,
+        timestamped_at: decode_u64(word(15)?)?,
+        template_claim: word(16)?,
+        holder_name_hash: word(17)?,
+        nullifier_scope: u8::try_from(decode_u64(word(18)?)?)
+            .map_err(|_| invalid("Public values contain an invalid nullifier scope".to_string()))?,
+        external_nullifier: word(19)?,
+        secret_commitment: word(20)?,
+        hash_suite: u8::try_from(decode_u64(word(21)?)?)
+            .map_err(|_| invalid("Public values contain an invalid hash suite".to_string()))?,
+        signer_cert_hash: word(22)?,
+        revealed: bytes[start..start + length].to_vec(),
+    })
+}
+
+/// Checks `claim` against the document exactly as the guest program will: signature must be
+/// valid and the substring must sit at the offset in the default (canonical) text.
+fn prepare_circuit_input(pdf_bytes: &[u8], claim: ClaimDescription) -> Result<u32, ZkpdfError> {
+    let content = verify_and_extract_strict(pdf_bytes.to_vec())?;
+
+    let index = claim.page_number as usize;
+    let page_text = content
+        .pages
+        .get(index)
+        .ok_or(pdf_core::Error::PageOutOfBounds {
+            page: index,
+            total: content.pages.len(),
+        })?;
+
+    let offset = match claim.offset {
+        Some(offset) => {
+            let matches = page_text
+                .get(offset..)
+                .is_some_and(|slice| slice.starts_with(&claim.substring));
+            if !matches {
+                return Err(ZkpdfError::new(
+                    ErrorCode::ClaimMismatch,
+                    format!(
+                        "substring not found at offset {} on page {}",
+                        offset, claim.page_number
+                    ),
+                ));
+            }
+            offset
+        }
+        None => find_text(&content.pages, &claim.substring, Some(index))
+            .first()
+            .map(|m| m.offset)
+            .ok_or_else(|| {
+                ZkpdfError::new(
+                    ErrorCode::ClaimMismatch,
+                    format!("substring not found on page {}", claim.page_number),
+                )
+            })?,
+    };
+
+    u32::try_from(offset)
+        .map_err(|_| ZkpdfError::new(ErrorCode::InvalidInput, "offset does not fit in u32"))
+}
+
+/// WebAssembly export: validate a claim and build the prover server request for it. The
+/// signature and offset are checked with the same extraction the guest program uses, so a
+/// successful result will not fail in the prover for a mismatched offset
+#[wasm_bindgen]
+pub fn wasm_prepare_circuit_input(
+    pdf_bytes: &[u8],
+    claim: JsClaimDescription,
+) -> Result<CircuitInputResponse, String> {
+    let claim: ClaimDescription = serde_wasm_bindgen::from_value(claim.into())
+        .map_err(|e| format!("Invalid claim: {}", e))?;
+    let page_number = claim.page_number;
+    let substring = claim.substring.clone();
+    let reveal = claim.reveal;
+    let nullifier_scope = claim.nullifier_scope;
+    let external_nullifier =
+        parse_bytes32(claim.external_nullifier.as_deref(), "external nullifier")?;
+    let user_secret = parse_bytes32(claim.user_secret.as_deref(), "user secret")?;
+
+    match prepare_circuit_input(pdf_bytes, claim) {
+        Ok(offset) => {
+            let request_json = serde_json::to_string(&ProofRequestBody {
+                pdf_bytes,
+                page_number,
+                sub_string: &substring,
+                offset,
+                reveal,
+                nullifier_scope,
+                external_nullifier,
+                user_secret,
+            })
+            .map_err(|e| format!("Failed to serialize request: {}", e))?;
+            let response = CircuitInputResult {
+                success: true,
+                page_number,
+                offset,
+                substring,
+                request_json,
+            };
+            serde_wasm_bindgen::to_value(&response)
+                .map_err(|e| format!("Failed to serialize result: {}", e))
+        }
+        Err(e) => {
+            let error_result = ErrorResult {
+                substring_matches: Some(false),
+                ..ErrorResult::new(e)
+            };
+            serde_wasm_bindgen::to_value(&error_result)
+                .map_err(|e| format!("Failed to serialize error: {}", e))
+        }
+    }
+    .map(JsCast::unchecked_into)
+}
+
+/// WebAssembly export: compute the nullifier (and the other committed hashes) a proof of
+/// `substring` at `offset` on `page_number` would publish, so it can be checked against a
+/// registry before proving. `external_nullifier` is the application's 0x-prefixed bytes32
+/// domain separator, if it has one, and `user_secret` the holder's secret the nullifier is
+/// bound to, if any. With a user secret the signer key and substring hashes are blinded as
+/// the proof blinds them, while the message digest, which the proof leaves out, is reported as
+/// computed
+#[wasm_bindgen]
+pub fn wasm_compute_nullifier(
+    pdf_bytes: &[u8],
+    page_number: u8,
+    substring: &str,
+    offset: u32,
+    scope: Option<JsNullifierScope>,
+    external_nullifier: Option<String>,
+    user_secret: Option<String>,
+) -> Result<NullifierResponse, String> {
+    let scope = NullifierScope::from_js(scope)?;
+    let external_nullifier =
+        parse_bytes32(external_nullifier.as_deref(), "external nullifier")?.unwrap_or_default();
+    let user_secret = parse_bytes32(user_secret.as_deref(), "user secret")?;
+
+    match verify_text(pdf_bytes.to_vec(), page_number, substring, offset as usize) {
+        Ok(result) => {
+            let hashes = ClaimHashes::new(&result.signature, substring, page_number, offset);
+            let nullifier = scoped_nullifier(
+                scope.into(),
+                &external_nullifier,
+                &hashes.message_digest_hash,
+                &hashes.signer_key_hash,
+                &hashes.nullifier,
+            );
+            let (nullifier, signer_key_hash, substring_hash) = match &user_secret {
+                Some(user_secret) => {
+                    let nullifier = secret_bound_nullifier(user_secret, &nullifier);
+                    (
+                        nullifier,
+                        blinded_hash(&hashes.signer_key_hash, &nullifier),
+                        blinded_hash(&hashes.substring_hash, &nullifier),
+                    )
+                }
+                None => (nullifier, hashes.signer_key_hash, hashes.substring_hash),
+            };
+            let response = NullifierResult {
+                success: true,
+                substring_matches: result.substring_matches,
+                message_digest_hash: to_hex(&hashes.message_digest_hash),
+                signer_key_hash: to_hex(&signer_key_hash),
+                substring_hash: to_hex(&substring_hash),
+                nullifier: to_hex(&nullifier),
+            };
+            serde_wasm_bindgen::to_value(&response)
+                .map_err(|e| format!("Failed to serialize result: {}", e))
+        }
+        Err(e) => {
+            let error_result = ErrorResult {
+                substring_matches: Some(false),
+                ..ErrorResult::new(e.into())
+            };
+            serde_wasm_bindgen::to_value(&error_result)
+                .map_err(|e| format!("Failed to serialize error: {}", e))
+        }
+    }
+    .map(JsCast::unchecked_into)
+}
+
+/// WebAssembly export: sanity check the public values returned with a proof against the claim
+/// that was requested. The substring hash and nullifier are recomputed locally; the document
+/// digest and signer key hashes are taken from the public values and only reported. With
+/// `user_secret` the digest is not committed, so the secret commitment is checked instead of
+/// the nullifier, which `wasm_compute_nullifier` recomputes from the document, and the
+/// substring hash is expected blinded with the committed nullifier
+#[wasm_bindgen]
+pub fn wasm_check_public_values(
+    public_values_hex: &str,
+    expected_substring: &str,
+    page_number: u8,
+    offset: u32,
+    scope: Option<JsNullifierScope>,
+    external_nullifier: Option<String>,
+    user_secret: Option<String>,
+) -> Result<PublicValuesCheckResponse, String> {
+    let scope = NullifierScope::from_js(scope)?;
+    let expected_external_nullifier =
+        parse_bytes32(external_nullifier.as_deref(), "external nullifier")?.unwrap_or_default();
+    let user_secret = parse_bytes32(user_secret.as_deref(), "user secret")?;
+
+    match decode_public_values(public_values_hex) {
+        Ok(PublicValues {
+            claim_type,
+            substring_matches,
+            message_digest_hash,
+            signer_key_hash,
+            substring_hash,
+            nullifier,
+            negated,
+            date_claim,
+            page_count,
+            pages_root,
+            signer_subject_hash,
+            not_before,
+            not_after,
+            timestamped_at,
+            template_claim,
+            holder_name_hash,
+            nullifier_scope,
+            external_nullifier,
+            secret_commitment: committed_secret,
+            hash_suite,
+            signer_cert_hash,
+            revealed,
+        }) => {
+            let expected_substring_hash = keccak256(expected_substring.as_bytes());
+            let expected_nullifier = scoped_nullifier(
+                scope.into(),
+                &expected_external_nullifier,
+                &message_digest_hash,
+                &signer_key_hash,
+                &compute_nullifier(
+                    &message_digest_hash,
+                    &signer_key_hash,
+                    &expected_substring_hash,
+                    page_number,
+                    offset,
+                ),
+            );
+
+            let mut mismatches = Vec::new();
+            if claim_type != SUBSTRING_CLAIM_TYPE {
+                mismatches.push("claim_type");
+            }
+            // A proof that the substring is absent says nothing about it being at the offset
+            if negated {
+                mismatches.push("negated");
+            }
+            // Nor does a proof of a date comparison
+            if date_claim != [0; 32] {
+                mismatches.push("date_claim");
+            }
+            if !substring_matches {
+                mismatches.push("substring_matches");
+            }
+            // A proof bound to a user secret blinds it with its own nullifier
+            let expected_substring_hash = match user_secret {
+                Some(_) => blinded_hash(&expected_substring_hash, &nullifier),
+                None => expected_substring_hash,
+            };
+            if substring_hash != expected_substring_hash {
+                mismatches.push("substring_hash");
+            }
+            let expected_secret_commitment = user_secret
+                .map(|user_secret| secret_commitment(&user_secret, &expected_external_nullifier))
+                .unwrap_or_default();
+            if user_secret.is_none() && nullifier != expected_nullifier {
+                mismatches.push("nullifier");
+            }
+            if nullifier_scope != nullifier::NullifierScope::from(scope) as u8 {
+                mismatches.push("nullifier_scope");
+            }
+            if external_nullifier != expected_external_nullifier {
+                mismatches.push("external_nullifier");
+            }
+            if committed_secret != expected_secret_commitment {
+                mismatches.push("secret_commitment");
+            }
+            // The hashes above are keccak256
+            if hash_suite != HashSuite::Keccak256 as u8 {
+                mismatches.push("hash_suite");
+            }
+            if !revealed.is_empty() && revealed != expected_substring.as_bytes() {
+                mismatches.push("revealed");
+            }
+
+            let response = PublicValuesCheckResult {
+                success: true,
+                consistent: mismatches.is_empty(),
+                mismatches,
+                claim_type,
+                substring_matches,
+                message_digest_hash: to_hex(&message_digest_hash),
+                signer_key_hash: to_hex(&signer_key_hash),
+                substring_hash: to_hex(&substring_hash),
+                nullifier: to_hex(&nullifier),
+                page_count,
+                pages_root: to_hex(&pages_root),
+                signer_subject_hash: to_hex(&signer_subject_hash),
+                not_before,
+                not_after,
+                timestamped_at,
+                template_claim: to_hex(&template_claim),
+                holder_name_hash: to_hex(&holder_name_hash),
+                nullifier_scope,
+                external_nullifier: to_hex(&external_nullifier),
+                secret_commitment: to_hex(&committed_secret),
+                hash_suite,
+                signer_cert_hash: to_hex(&signer_cert_hash),
+                revealed: to_hex(&revealed),
+            };
+            serde_wasm_bindgen::to_value(&response)
+                .map_err(|e| format!("Failed to serialize result: {}", e))
+        }
+        Err(e) => {
+            let error_result = ErrorResult::new(e);
+            serde_wasm_bindgen::to_value(&error_result)
+                .map_err(|e| format!("Failed to serialize error: {}", e))
+        }
+    }
+    .map(JsCast::unchecked_into)
+}
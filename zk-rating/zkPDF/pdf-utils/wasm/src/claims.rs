@@ -0,0 +1,211 @@
+use base64::{Engine as _, engine::general_purpose};
+use pdf_core::{Claim, Date, verify_claims};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::{JsCast, prelude::*};
+
+use crate::{ErrorResult, JsPdfOptions, SignatureInfo, options::PdfOptions};
+
+#[wasm_bindgen(typescript_custom_section)]
+const TS_CLAIMS: &'static str = r#"
+export type ClaimSpec =
+  /** `substring` at `offset` on `page`, as checked by `wasm_verify_text`. */
+  | { type: "substring"; page: number; substring: string; offset: number }
+  /** `substring` anywhere on `page`, or on any page when omitted. */
+  | { type: "substring_anywhere"; substring: string; page?: number }
+  /** The form field with this fully qualified name has `value`. */
+  | { type: "field_equals"; field: string; value: string }
+  /** Regular expression (Rust `regex` syntax) on `page`, or on any page when omitted. */
+  | { type: "regex"; pattern: string; page?: number }
+  /** The number `pattern` captures (its first group) lies within `min..=max`. */
+  | { type: "numeric_range"; pattern: string; page?: number; min?: number; max?: number }
+  /** The date `pattern` captures is before `before`, `dd/mm/yyyy` or a PDF date. */
+  | { type: "date_before"; pattern: string; page?: number; before: string };
+
+export interface ClaimResult {
+  matches: boolean;
+  /** Location of the match, omitted for form field claims; for regex claims the first
+   * match in page order. */
+  page?: number;
+  offset?: number;
+  value?: string;
+  /** Set when the claim could not be evaluated, e.g. an invalid pattern. */
+  error?: string;
+}
+
+export type VerifyClaimsResult =
+  | { success: true; results: ClaimResult[]; signature: SignatureInfo }
+  | ErrorResult;
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "VerifyClaimsResult")]
+    pub type VerifyClaimsResponse;
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+enum ClaimSpec {
+    Substring {
+        page: usize,
+        substring: String,
+        offset: usize,
+    },
+    SubstringAnywhere {
+        substring: String,
+        page: Option<usize>,
+    },
+    FieldEquals {
+        field: String,
+        value: String,
+    },
+    Regex {
+        pattern: String,
+        page: Option<usize>,
+    },
+    NumericRange {
+        pattern: String,
+        page: Option<usize>,
+        min: Option<f64>,
+        max: Option<f64>,
+    },
+    DateBefore {
+        pattern: String,
+        page: Option<usize>,
+        before: String,
+    },
+}
+
+impl TryFrom<ClaimSpec> for Claim {
+    type Error = String;
+
+    fn try_from(spec: ClaimSpec) -> Result<Self, String> {
+        Ok(match spec {
+            ClaimSpec::Substring {
+                page,
+                substring,
+                offset,
+            } => Claim::Substring {
+                page,
+                substring,
+                offset,
+            },
+            ClaimSpec::SubstringAnywhere { substring, page } => {
+                Claim::SubstringAnywhere { substring, page }
+            }
+            ClaimSpec::FieldEquals { field, value } => Claim::FieldEquals { field, value },
+            ClaimSpec::Regex { pattern, page } => Claim::Regex { pattern, page },
+            ClaimSpec::NumericRange {
+                pattern,
+                page,
+                min,
+                max,
+            } => Claim::NumericRange {
+                pattern,
+                page,
+                min,
+                max,
+            },
+            ClaimSpec::DateBefore {
+                pattern,
+                page,
+                before,
+            } => Claim::DateBefore {
+                pattern,
+                page,
+                before: Date::parse(&before)
+                    .ok_or_else(|| format!("Invalid claims: `{}` is not a date", before))?,
+            },
+        })
+    }
+}
+
+#[derive(Serialize, Default)]
+struct ClaimResult {
+    matches: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    page: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    offset: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Serialize)]
+struct VerifyClaimsResult {
+    success: bool,
+    results: Vec<ClaimResult>,
+    signature: SignatureInfo,
+}
+
+/// WebAssembly export: verify the signature and evaluate every claim in `claims_json` (a JSON
+/// array of `ClaimSpec`) against a single extraction of the document
+#[wasm_bindgen]
+pub fn wasm_verify_claims(
+    pdf_bytes: &[u8],
+    claims_json: &str,
+    options: Option<JsPdfOptions>,
+) -> Result<VerifyClaimsResponse, String> {
+    verify_claims_owned(pdf_bytes.to_vec(), claims_json, options)
+}
+
+pub(crate) fn verify_claims_owned(
+    pdf_bytes: Vec<u8>,
+    claims_json: &str,
+    options: Option<JsPdfOptions>,
+) -> Result<VerifyClaimsResponse, String> {
+    let claims: Vec<ClaimSpec> =
+        serde_json::from_str(claims_json).map_err(|e| format!("Invalid claims: {}", e))?;
+    let claims = claims
+        .into_iter()
+        .map(Claim::try_from)
+        .collect::<Result<Vec<Claim>, String>>()?;
+    let options = PdfOptions::from_js(options)?;
+
+    match verify_claims(
+        pdf_bytes,
+        &claims,
+        &options.extract_options(),
+        &options.policy(),
+    ) {
+        Ok(result) => {
+            let response = VerifyClaimsResult {
+                success: true,
+                results: result
+                    .results
+                    .into_iter()
+                    .map(|outcome| match outcome {
+                        Ok(Some(m)) => ClaimResult {
+                            matches: true,
+                            page: m.page,
+                            offset: m.offset,
+                            value: Some(m.value),
+                            error: None,
+                        },
+                        Ok(None) => ClaimResult::default(),
+                        Err(e) => ClaimResult {
+                            error: Some(e.to_string()),
+                            ..ClaimResult::default()
+                        },
+                    })
+                    .collect(),
+                signature: SignatureInfo {
+                    is_valid: result.signature.is_valid,
+                    message_digest: general_purpose::STANDARD
+                        .encode(&result.signature.message_digest),
+                    public_key: general_purpose::STANDARD.encode(&result.signature.public_key),
+                },
+            };
+            serde_wasm_bindgen::to_value(&response)
+                .map_err(|e| format!("Failed to serialize result: {}", e))
+        }
+        Err(e) => {
+            let error_result = ErrorResult::new(e.into());
+            serde_wasm_bindgen::to_value(&error_result)
+                .map_err(|e| format!("Failed to serialize error: {}", e))
+        }
+    }
+    .map(JsCast::unchecked_into)
+}
@@ -1,10 +1,54 @@
+#[cfg(feature = "verify")]
 use base64::{Engine as _, engine::general_purpose};
-use core::{verify_and_extract, verify_pdf_signature, verify_text};
-use extractor::extract_text;
+use extractor::{extract_form_fields, extract_page_text, extract_text_with_options, find_text};
+#[cfg(feature = "verify")]
+use pdf_core::{
+    PdfVerifiedContent, document_info, get_signer_details, has_signature,
+    verify_and_extract_with_options, verify_pdf_signature, verify_text_with_options,
+};
 use serde::Serialize;
 use serde_wasm_bindgen;
-use wasm_bindgen::prelude::*;
+#[cfg(feature = "verify")]
+use std::collections::BTreeMap;
+use wasm_bindgen::{JsCast, prelude::*};
+use zkpdf_errors::ZkpdfError;
 
+mod async_api;
+mod b64;
+mod chunked;
+#[cfg(feature = "verify")]
+mod circuit;
+#[cfg(feature = "verify")]
+mod claims;
+mod options;
+#[cfg(feature = "verify")]
+mod template;
+
+#[cfg(feature = "verify")]
+pub use async_api::wasm_verify_and_extract_async;
+pub use async_api::{JsProgressCallback, wasm_extract_text_async};
+#[cfg(feature = "verify")]
+pub use b64::{
+    wasm_apply_template_b64, wasm_compute_nullifier_b64, wasm_get_document_info_b64,
+    wasm_get_signature_details_b64, wasm_prepare_circuit_input_b64,
+    wasm_verify_and_extract_async_b64, wasm_verify_and_extract_b64, wasm_verify_claims_b64,
+    wasm_verify_gst_certificate_b64, wasm_verify_pdf_signature_b64, wasm_verify_text_b64,
+};
+pub use b64::{
+    wasm_extract_form_fields_b64, wasm_extract_page_b64, wasm_extract_text_async_b64,
+    wasm_extract_text_b64, wasm_find_text_b64,
+};
+pub use chunked::PdfChunkReader;
+#[cfg(feature = "verify")]
+pub use circuit::{wasm_check_public_values, wasm_compute_nullifier, wasm_prepare_circuit_input};
+#[cfg(feature = "verify")]
+pub use claims::wasm_verify_claims;
+pub use options::JsPdfOptions;
+use options::PdfOptions;
+#[cfg(feature = "verify")]
+pub use template::{wasm_apply_template, wasm_verify_gst_certificate};
+
+#[cfg(feature = "verify")]
 #[derive(Serialize)]
 struct SignatureInfo {
     is_valid: bool,
@@ -12,6 +56,7 @@ struct SignatureInfo {
     public_key: String,
 }
 
+#[cfg(feature = "verify")]
 #[derive(Serialize)]
 struct VerifyAndExtractResult {
     success: bool,
@@ -19,6 +64,7 @@ struct VerifyAndExtractResult {
     signature: SignatureInfo,
 }
 
+#[cfg(feature = "verify")]
 #[derive(Serialize)]
 struct VerifySignatureResult {
     success: bool,
@@ -27,6 +73,7 @@ struct VerifySignatureResult {
     public_key: String,
 }
 
+#[cfg(feature = "verify")]
 #[derive(Serialize)]
 struct VerifyTextResult {
     success: bool,
@@ -34,9 +81,69 @@ struct VerifyTextResult {
     signature: SignatureInfo,
 }
 
+#[cfg(feature = "verify")]
+#[derive(Serialize)]
+struct DocumentInfoResult {
+    success: bool,
+    page_count: usize,
+    page_char_counts: Vec<usize>,
+    metadata: BTreeMap<String, String>,
+    appears_signed: bool,
+}
+
+#[derive(Serialize)]
+struct PageTextResult {
+    success: bool,
+    page: usize,
+    page_count: usize,
+    text: String,
+}
+
+#[derive(Serialize)]
+struct TextMatchInfo {
+    page: usize,
+    offset: usize,
+    length: usize,
+}
+
+#[derive(Serialize)]
+struct FindTextResult {
+    success: bool,
+    matches: Vec<TextMatchInfo>,
+}
+
+#[derive(Serialize)]
+struct FormFieldInfo {
+    name: String,
+    #[serde(rename = "type")]
+    field_type: String,
+    value: Option<String>,
+}
+
+#[derive(Serialize)]
+struct FormFieldsResult {
+    success: bool,
+    fields: Vec<FormFieldInfo>,
+}
+
+#[cfg(feature = "verify")]
+#[derive(Serialize)]
+struct SignatureDetailsResult {
+    success: bool,
+    subject: String,
+    issuer: String,
+    serial_number: String,
+    not_before: String,
+    not_after: String,
+    algorithm: String,
+    key_size: u64,
+    key_fingerprint_sha256: String,
+}
+
 #[derive(Serialize)]
 struct ErrorResult {
     success: bool,
+    code: u16,
     error: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     is_valid: Option<bool>,
@@ -44,10 +151,162 @@ struct ErrorResult {
     substring_matches: Option<bool>,
 }
 
+impl ErrorResult {
+    fn new(error: ZkpdfError) -> Self {
+        ErrorResult {
+            success: false,
+            code: error.code.code(),
+            error: error.message,
+            is_valid: None,
+            substring_matches: None,
+        }
+    }
+}
+
+// TypeScript definitions for the objects returned by the exports below. wasm-bindgen copies
+// this section into the generated `.d.ts`, so keep it in sync with the `*Result` structs.
+#[wasm_bindgen(typescript_custom_section)]
+const TS_RESULT_TYPES: &'static str = r#"
+export interface ErrorResult {
+  success: false;
+  /** Stable `zkpdf-errors` code, e.g. 3003 for a digest mismatch. */
+  code: number;
+  error: string;
+  is_valid?: boolean;
+  substring_matches?: boolean;
+}
+
+export interface SignatureInfo {
+  is_valid: boolean;
+  /** Base64 encoded message digest committed to by the signer. */
+  message_digest: string;
+  /** Base64 encoded PKCS#1 DER public key of the signer. */
+  public_key: string;
+}
+
+export type VerifyAndExtractResult =
+  | { success: true; pages: string[]; signature: SignatureInfo }
+  | ErrorResult;
+
+export type VerifyTextResult =
+  | { success: true; substring_matches: boolean; signature: SignatureInfo }
+  | ErrorResult;
+
+export type VerifySignatureResult =
+  | ({ success: true } & SignatureInfo)
+  | ErrorResult;
+
+export type DocumentInfoResult =
+  | {
+      success: true;
+      page_count: number;
+      page_char_counts: number[];
+      metadata: Record<string, string>;
+      appears_signed: boolean;
+    }
+  | ErrorResult;
+
+export type PageTextResult =
+  | { success: true; page: number; page_count: number; text: string }
+  | ErrorResult;
+
+export interface TextMatch {
+  page: number;
+  /** Offset to pass to `wasm_verify_text`. */
+  offset: number;
+  length: number;
+}
+
+export type FindTextResult = { success: true; matches: TextMatch[] } | ErrorResult;
+
+export interface FormField {
+  /** Fully qualified field name, with parent names joined by `.`. */
+  name: string;
+  type: "text" | "button" | "choice" | "signature" | "unknown";
+  /** Text value, button state or selected options; `null` when unset and for signatures. */
+  value: string | null;
+}
+
+export type FormFieldsResult = { success: true; fields: FormField[] } | ErrorResult;
+
+export type SignatureDetailsResult =
+  | {
+      success: true;
+      subject: string;
+      issuer: string;
+      /** Base64 encoded big-endian serial number. */
+      serial_number: string;
+      /** ISO 8601 UTC timestamp. */
+      not_before: string;
+      /** ISO 8601 UTC timestamp. */
+      not_after: string;
+      algorithm: string;
+      key_size: number;
+      /** Hex SHA-256 of the signer certificate's subjectPublicKeyInfo. */
+      key_fingerprint_sha256: string;
+    }
+  | ErrorResult;
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "PageTextResult")]
+    pub type PageTextResponse;
+
+    #[wasm_bindgen(typescript_type = "FindTextResult")]
+    pub type FindTextResponse;
+
+    #[wasm_bindgen(typescript_type = "FormFieldsResult")]
+    pub type FormFieldsResponse;
+}
+
+#[cfg(feature = "verify")]
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "VerifyAndExtractResult")]
+    pub type VerifyAndExtractResponse;
+
+    #[wasm_bindgen(typescript_type = "VerifyTextResult")]
+    pub type VerifyTextResponse;
+
+    #[wasm_bindgen(typescript_type = "VerifySignatureResult")]
+    pub type VerifySignatureResponse;
+
+    #[wasm_bindgen(typescript_type = "DocumentInfoResult")]
+    pub type DocumentInfoResponse;
+
+    #[wasm_bindgen(typescript_type = "SignatureDetailsResult")]
+    pub type SignatureDetailsResponse;
+}
+
 /// WebAssembly export: verify and extract content from PDF (signature verification + text extraction)
+#[cfg(feature = "verify")]
 #[wasm_bindgen]
-pub fn wasm_verify_and_extract(pdf_bytes: &[u8]) -> Result<JsValue, String> {
-    match verify_and_extract(pdf_bytes.to_vec()) {
+pub fn wasm_verify_and_extract(
+    pdf_bytes: &[u8],
+    options: Option<JsPdfOptions>,
+) -> Result<VerifyAndExtractResponse, String> {
+    verify_and_extract_owned(pdf_bytes.to_vec(), options)
+}
+
+#[cfg(feature = "verify")]
+pub(crate) fn verify_and_extract_owned(
+    pdf_bytes: Vec<u8>,
+    options: Option<JsPdfOptions>,
+) -> Result<VerifyAndExtractResponse, String> {
+    let options = PdfOptions::from_js(options)?;
+    verify_and_extract_response(verify_and_extract_with_options(
+        pdf_bytes,
+        &options.extract_options(),
+        &options.policy(),
+    ))
+}
+
+#[cfg(feature = "verify")]
+pub(crate) fn verify_and_extract_response(
+    result: Result<PdfVerifiedContent, pdf_core::Error>,
+) -> Result<VerifyAndExtractResponse, String> {
+    match result {
         Ok(content) => {
             let result = VerifyAndExtractResult {
                 success: true,
@@ -63,28 +322,45 @@ pub fn wasm_verify_and_extract(pdf_bytes: &[u8]) -> Result<JsValue, String> {
                 .map_err(|e| format!("Failed to serialize result: {}", e))
         }
         Err(e) => {
-            let error_result = ErrorResult {
-                success: false,
-                error: e,
-                is_valid: None,
-                substring_matches: None,
-            };
+            let error_result = ErrorResult::new(e.into());
             serde_wasm_bindgen::to_value(&error_result)
                 .map_err(|e| format!("Failed to serialize error: {}", e))
         }
     }
+    .map(JsCast::unchecked_into)
 }
 
 /// WebAssembly export: verify text and signature in a PDF at a specific offset
 /// Returns a JSON object with success status and error message (if any)
+#[cfg(feature = "verify")]
 #[wasm_bindgen]
 pub fn wasm_verify_text(
     pdf_bytes: &[u8],
     page_number: u8,
     sub_string: &str,
     offset: usize,
-) -> Result<JsValue, String> {
-    match verify_text(pdf_bytes.to_vec(), page_number, sub_string, offset) {
+    options: Option<JsPdfOptions>,
+) -> Result<VerifyTextResponse, String> {
+    verify_text_owned(pdf_bytes.to_vec(), page_number, sub_string, offset, options)
+}
+
+#[cfg(feature = "verify")]
+pub(crate) fn verify_text_owned(
+    pdf_bytes: Vec<u8>,
+    page_number: u8,
+    sub_string: &str,
+    offset: usize,
+    options: Option<JsPdfOptions>,
+) -> Result<VerifyTextResponse, String> {
+    let options = PdfOptions::from_js(options)?;
+    match verify_text_with_options(
+        pdf_bytes,
+        page_number,
+        sub_string,
+        offset,
+        &options.extract_options(),
+        &options.policy(),
+    ) {
         Ok(result) => {
             let response = VerifyTextResult {
                 success: true,
@@ -101,21 +377,21 @@ pub fn wasm_verify_text(
         }
         Err(e) => {
             let error_result = ErrorResult {
-                success: false,
-                error: e,
-                is_valid: None,
                 substring_matches: Some(false),
+                ..ErrorResult::new(e.into())
             };
             serde_wasm_bindgen::to_value(&error_result)
                 .map_err(|e| format!("Failed to serialize error: {}", e))
         }
     }
+    .map(JsCast::unchecked_into)
 }
 
 /// WebAssembly export: verify PDF signature only (no text extraction)
 /// Returns a JSON object with signature verification results
+#[cfg(feature = "verify")]
 #[wasm_bindgen]
-pub fn wasm_verify_pdf_signature(pdf_bytes: &[u8]) -> Result<JsValue, String> {
+pub fn wasm_verify_pdf_signature(pdf_bytes: &[u8]) -> Result<VerifySignatureResponse, String> {
     match verify_pdf_signature(pdf_bytes) {
         Ok(signature_result) => {
             let response = VerifySignatureResult {
@@ -129,22 +405,202 @@ pub fn wasm_verify_pdf_signature(pdf_bytes: &[u8]) -> Result<JsValue, String> {
         }
         Err(e) => {
             let error_result = ErrorResult {
-                success: false,
-                error: format!("Signature verification failed: {}", e),
                 is_valid: Some(false),
-                substring_matches: None,
+                ..ErrorResult::new(ZkpdfError::new(
+                    (&e).into(),
+                    format!("Signature verification failed: {}", e),
+                ))
             };
             serde_wasm_bindgen::to_value(&error_result)
                 .map_err(|e| format!("Failed to serialize error: {}", e))
         }
     }
+    .map(JsCast::unchecked_into)
 }
 
 /// WebAssembly export: extract raw text content per page
+/// Returns an empty list if the PDF cannot be parsed; only invalid options raise an error
+#[wasm_bindgen]
+pub fn wasm_extract_text(
+    pdf_bytes: &[u8],
+    options: Option<JsPdfOptions>,
+) -> Result<Vec<String>, String> {
+    extract_text_owned(pdf_bytes.to_vec(), options)
+}
+
+pub(crate) fn extract_text_owned(
+    pdf_bytes: Vec<u8>,
+    options: Option<JsPdfOptions>,
+) -> Result<Vec<String>, String> {
+    let options = PdfOptions::from_js(options)?;
+    Ok(extract_text_with_options(pdf_bytes, &options.extract_options()).unwrap_or_default())
+}
+
+/// WebAssembly export: extract the text of a single page without decoding the content streams
+/// and fonts of the other pages. Also reports the total page count
+#[wasm_bindgen]
+pub fn wasm_extract_page(
+    pdf_bytes: &[u8],
+    page: usize,
+    options: Option<JsPdfOptions>,
+) -> Result<PageTextResponse, String> {
+    let options = PdfOptions::from_js(options)?;
+    match extract_page_text(pdf_bytes, page, &options.extract_options()) {
+        Ok(page_text) => {
+            let response = PageTextResult {
+                success: true,
+                page,
+                page_count: page_text.page_count,
+                text: page_text.text,
+            };
+            serde_wasm_bindgen::to_value(&response)
+                .map_err(|e| format!("Failed to serialize result: {}", e))
+        }
+        Err(e) => {
+            let error_result = ErrorResult::new(ZkpdfError::new(
+                (&e).into(),
+                format!("Text extraction failed: {}", e),
+            ));
+            serde_wasm_bindgen::to_value(&error_result)
+                .map_err(|e| format!("Failed to serialize error: {}", e))
+        }
+    }
+    .map(JsCast::unchecked_into)
+}
+
+/// WebAssembly export: page count, per-page character counts, Info metadata and whether the
+/// document appears signed, without verifying the signature
+#[cfg(feature = "verify")]
+#[wasm_bindgen]
+pub fn wasm_get_document_info(pdf_bytes: &[u8]) -> Result<DocumentInfoResponse, String> {
+    match document_info(pdf_bytes) {
+        Ok(info) => {
+            let response = DocumentInfoResult {
+                success: true,
+                page_count: info.page_count(),
+                page_char_counts: info.page_char_counts,
+                metadata: info.metadata,
+                appears_signed: has_signature(pdf_bytes),
+            };
+            // Serialize `metadata` as a plain object rather than a JS `Map`
+            response
+                .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+                .map_err(|e| format!("Failed to serialize result: {}", e))
+        }
+        Err(e) => {
+            let error_result = ErrorResult::new(ZkpdfError::new(
+                (&e).into(),
+                format!("Failed to read document: {}", e),
+            ));
+            serde_wasm_bindgen::to_value(&error_result)
+                .map_err(|e| format!("Failed to serialize error: {}", e))
+        }
+    }
+    .map(JsCast::unchecked_into)
+}
+
+/// WebAssembly export: find occurrences of `needle` in the extracted text, optionally on a single
+/// page. Each match carries the page index and the offset to pass to `wasm_verify_text`
 #[wasm_bindgen]
-pub fn wasm_extract_text(pdf_bytes: &[u8]) -> Vec<JsValue> {
-    match extract_text(pdf_bytes.to_vec()) {
-        Ok(pages) => pages.into_iter().map(JsValue::from).collect(),
-        Err(_) => Vec::new(),
+pub fn wasm_find_text(
+    pdf_bytes: &[u8],
+    needle: &str,
+    page: Option<u8>,
+    options: Option<JsPdfOptions>,
+) -> Result<FindTextResponse, String> {
+    let options = PdfOptions::from_js(options)?;
+    match extract_text_with_options(pdf_bytes.to_vec(), &options.extract_options()) {
+        Ok(pages) => {
+            let matches = find_text(&pages, needle, page.map(usize::from))
+                .into_iter()
+                .map(|m| TextMatchInfo {
+                    page: m.page,
+                    offset: m.offset,
+                    length: m.len,
+                })
+                .collect();
+            let response = FindTextResult {
+                success: true,
+                matches,
+            };
+            serde_wasm_bindgen::to_value(&response)
+                .map_err(|e| format!("Failed to serialize result: {}", e))
+        }
+        Err(e) => {
+            let error_result = ErrorResult::new(ZkpdfError::new(
+                (&e).into(),
+                format!("Text extraction failed: {}", e),
+            ));
+            serde_wasm_bindgen::to_value(&error_result)
+                .map_err(|e| format!("Failed to serialize error: {}", e))
+        }
+    }
+    .map(JsCast::unchecked_into)
+}
+
+/// WebAssembly export: list the AcroForm fields of the document with their types and values, as
+/// of the latest revision. Field values are not covered by the text proofs
+#[wasm_bindgen]
+pub fn wasm_extract_form_fields(pdf_bytes: &[u8]) -> Result<FormFieldsResponse, String> {
+    match extract_form_fields(pdf_bytes) {
+        Ok(fields) => {
+            let response = FormFieldsResult {
+                success: true,
+                fields: fields
+                    .into_iter()
+                    .map(|field| FormFieldInfo {
+                        name: field.name,
+                        field_type: field.field_type.to_string(),
+                        value: field.value,
+                    })
+                    .collect(),
+            };
+            serde_wasm_bindgen::to_value(&response)
+                .map_err(|e| format!("Failed to serialize result: {}", e))
+        }
+        Err(e) => {
+            let error_result = ErrorResult::new(ZkpdfError::new(
+                (&e).into(),
+                format!("Failed to read form fields: {}", e),
+            ));
+            serde_wasm_bindgen::to_value(&error_result)
+                .map_err(|e| format!("Failed to serialize error: {}", e))
+        }
+    }
+    .map(JsCast::unchecked_into)
+}
+
+/// WebAssembly export: signer certificate details for display (subject, issuer, base64 serial
+/// number, ISO 8601 validity dates, algorithm, key size and key fingerprint). Does not verify the
+/// signature
+#[cfg(feature = "verify")]
+#[wasm_bindgen]
+pub fn wasm_get_signature_details(pdf_bytes: &[u8]) -> Result<SignatureDetailsResponse, String> {
+    match get_signer_details(pdf_bytes) {
+        Ok(details) => {
+            let key_fingerprint_sha256 = hex::encode(details.key_fingerprint_sha256());
+            let response = SignatureDetailsResult {
+                success: true,
+                subject: details.subject,
+                issuer: details.issuer,
+                serial_number: general_purpose::STANDARD.encode(&details.serial_number),
+                not_before: details.not_before,
+                not_after: details.not_after,
+                algorithm: details.signature_algorithm.to_string(),
+                key_size: details.key_size,
+                key_fingerprint_sha256,
+            };
+            serde_wasm_bindgen::to_value(&response)
+                .map_err(|e| format!("Failed to serialize result: {}", e))
+        }
+        Err(e) => {
+            let error_result = ErrorResult::new(ZkpdfError::new(
+                (&e).into(),
+                format!("Failed to read signer certificate: {}", e),
+            ));
+            serde_wasm_bindgen::to_value(&error_result)
+                .map_err(|e| format!("Failed to serialize error: {}", e))
+        }
     }
+    .map(JsCast::unchecked_into)
 }
@@ -0,0 +1,116 @@
+use extractor::types::{ExtractOptions, Normalization};
+#[cfg(feature = "verify")]
+use pdf_core::{MatchMode, VerificationPolicy};
+use serde::Deserialize;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(typescript_custom_section)]
+const TS_OPTIONS: &'static str = r#"
+export interface PdfOptions {
+  /** `canonical` (default) collapses whitespace; offsets refer to this text. */
+  normalization?: "canonical" | "raw" | "layout";
+  /** Return pages that fail to load as empty strings and skip objects that fail to parse, instead of failing. */
+  lenient?: boolean;
+  /** Only extract the first `max_pages` pages. */
+  max_pages?: number;
+  /** Read the document as of this revision (0 is the original) instead of the latest. */
+  revision?: number;
+  /** Also extract text drawn by annotation appearance streams (stamps, form widgets). */
+  annotations?: boolean;
+  /** NFC with Arabic and Hebrew presentation forms folded to letters. */
+  unicode_normalization?: boolean;
+  /** Reorder right-to-left runs from drawing order to logical order. */
+  bidi?: boolean;
+  /** Return results for documents whose signature does not verify. */
+  allow_invalid_signature?: boolean;
+  /** Return results for documents with incremental updates after the signed range, whose text may not be signed. */
+  allow_unsigned_updates?: boolean;
+  /** How claimed substrings are compared; outside `exact`, offsets index the canonicalized page text. */
+  match_mode?: "exact" | "normalized_whitespace" | "case_insensitive";
+}
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "PdfOptions")]
+    pub type JsPdfOptions;
+}
+
+#[derive(Deserialize, Default, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+enum NormalizationOption {
+    #[default]
+    Canonical,
+    Raw,
+    Layout,
+}
+
+#[derive(Deserialize, Default, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(not(feature = "verify"), allow(dead_code))]
+enum MatchModeOption {
+    #[default]
+    Exact,
+    NormalizedWhitespace,
+    CaseInsensitive,
+}
+
+/// Options object accepted by the extraction and verification exports. Missing fields keep
+/// the same defaults as the plain Rust API.
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct PdfOptions {
+    normalization: NormalizationOption,
+    lenient: bool,
+    max_pages: Option<usize>,
+    revision: Option<usize>,
+    annotations: bool,
+    unicode_normalization: bool,
+    bidi: bool,
+    // Accepted without `verify` so the same options object works with either bundle
+    #[cfg_attr(not(feature = "verify"), allow(dead_code))]
+    allow_invalid_signature: bool,
+    #[cfg_attr(not(feature = "verify"), allow(dead_code))]
+    allow_unsigned_updates: bool,
+    #[cfg_attr(not(feature = "verify"), allow(dead_code))]
+    match_mode: MatchModeOption,
+}
+
+impl PdfOptions {
+    pub(crate) fn from_js(options: Option<JsPdfOptions>) -> Result<Self, String> {
+        match options {
+            Some(options) => serde_wasm_bindgen::from_value(options.into())
+                .map_err(|e| format!("Invalid options: {}", e)),
+            None => Ok(Self::default()),
+        }
+    }
+
+    pub(crate) fn extract_options(&self) -> ExtractOptions {
+        ExtractOptions {
+            normalization: match self.normalization {
+                NormalizationOption::Canonical => Normalization::Canonical,
+                NormalizationOption::Raw => Normalization::Raw,
+                NormalizationOption::Layout => Normalization::Layout,
+            },
+            lenient: self.lenient,
+            max_pages: self.max_pages,
+            revision: self.revision,
+            annotations: self.annotations,
+            unicode_normalization: self.unicode_normalization,
+            bidi: self.bidi,
+        }
+    }
+
+    #[cfg(feature = "verify")]
+    pub(crate) fn policy(&self) -> VerificationPolicy {
+        VerificationPolicy {
+            allow_invalid_signature: self.allow_invalid_signature,
+            allow_unsigned_updates: self.allow_unsigned_updates,
+            match_mode: match self.match_mode {
+                MatchModeOption::Exact => MatchMode::Exact,
+                MatchModeOption::NormalizedWhitespace => MatchMode::NormalizedWhitespace,
+                MatchModeOption::CaseInsensitive => MatchMode::CaseInsensitive,
+            },
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use base64::{Engine as _, engine::general_purpose};
+use pdf_core::{FieldPattern, Template, apply_template};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::{JsCast, prelude::*};
+
+use crate::{ErrorResult, JsPdfOptions, SignatureInfo, options::PdfOptions};
+
+#[wasm_bindgen(typescript_custom_section)]
+const TS_TEMPLATE: &'static str = r#"
+export interface FieldTemplate {
+  name: string;
+  /** Regular expression (Rust `regex` syntax) matched against each page in order. */
+  pattern: string;
+  /** Capture group holding the value; defaults to the whole match. */
+  group?: number;
+}
+
+export interface DocumentTemplate {
+  fields: FieldTemplate[];
+}
+
+export interface ExtractedField {
+  name: string;
+  /** Exact substring that will be proven. */
+  value: string;
+  page: number;
+  /** Offset to pass to `wasm_verify_text`. */
+  offset: number;
+}
+
+export type TemplateResult =
+  | { success: true; fields: ExtractedField[]; signature: SignatureInfo }
+  | ErrorResult;
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "DocumentTemplate")]
+    pub type JsDocumentTemplate;
+
+    #[wasm_bindgen(typescript_type = "TemplateResult")]
+    pub type TemplateResponse;
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FieldTemplate {
+    name: String,
+    pattern: String,
+    #[serde(default)]
+    group: usize,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DocumentTemplate {
+    fields: Vec<FieldTemplate>,
+}
+
+#[derive(Serialize)]
+struct ExtractedFieldInfo {
+    name: String,
+    value: String,
+    page: usize,
+    offset: usize,
+}
+
+#[derive(Serialize)]
+struct TemplateResult {
+    success: bool,
+    fields: Vec<ExtractedFieldInfo>,
+    signature: SignatureInfo,
+}
+
+/// WebAssembly export: verify a GST registration certificate and return its GST number and
+/// legal name with the page and offset each one would be proven at
+#[wasm_bindgen]
+pub fn wasm_verify_gst_certificate(
+    pdf_bytes: &[u8],
+    options: Option<JsPdfOptions>,
+) -> Result<TemplateResponse, String> {
+    apply_template_owned(pdf_bytes.to_vec(), &Template::gst_certificate(), options)
+}
+
+/// WebAssembly export: verify the signature and locate each field of `template` in the
+/// extracted text. Fails if any field is missing
+#[wasm_bindgen]
+pub fn wasm_apply_template(
+    pdf_bytes: &[u8],
+    template: JsDocumentTemplate,
+    options: Option<JsPdfOptions>,
+) -> Result<TemplateResponse, String> {
+    let template: DocumentTemplate = serde_wasm_bindgen::from_value(template.into())
+        .map_err(|e| format!("Invalid template: {}", e))?;
+    let template = Template {
+        fields: template
+            .fields
+            .into_iter()
+            .map(|field| FieldPattern {
+                name: field.name,
+                pattern: field.pattern,
+                group: field.group,
+            })
+            .collect(),
+    };
+
+    apply_template_owned(pdf_bytes.to_vec(), &template, options)
+}
+
+fn apply_template_owned(
+    pdf_bytes: Vec<u8>,
+    template: &Template,
+    options: Option<JsPdfOptions>,
+) -> Result<TemplateResponse, String> {
+    let options = PdfOptions::from_js(options)?;
+    match apply_template(
+        pdf_bytes,
+        template,
+        &options.extract_options(),
+        &options.policy(),
+    ) {
+        Ok(result) => {
+            let response = TemplateResult {
+                success: true,
+                fields: result
+                    .fields
+                    .into_iter()
+                    .map(|field| ExtractedFieldInfo {
+                        name: field.name,
+                        value: field.value,
+                        page: field.page,
+                        offset: field.offset,
+                    })
+                    .collect(),
+                signature: SignatureInfo {
+                    is_valid: result.signature.is_valid,
+                    message_digest: general_purpose::STANDARD
+                        .encode(&result.signature.message_digest),
+                    public_key: general_purpose::STANDARD.encode(&result.signature.public_key),
+                },
+            };
+            serde_wasm_bindgen::to_value(&response)
+                .map_err(|e| format!("Failed to serialize result: {}", e))
+        }
+        Err(e) => {
+            let error_result = ErrorResult::new(e.into());
+            serde_wasm_bindgen::to_value(&error_result)
+                .map_err(|e| format!("Failed to serialize error: {}", e))
+        }
+    }
+    .map(JsCast::unchecked_into)
+}
@@ -0,0 +1,27 @@
+//! Writes a signed sample certificate: `cargo run -p zkpdf-writer --example write_signed -- out.pdf`
+
+use zkpdf_writer::{Document, Font, Page, Signer};
+
+fn main() {
+    let path = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "signed.pdf".to_string());
+
+    let mut doc = Document {
+        compress: true,
+        info: vec![("Title".to_string(), "Sample certificate".to_string())],
+        ..Document::from_pages(&[&[
+            "Certificate of Registration",
+            "Registration Number: 07AAATC0869P1ZB",
+            "Legal Name: Example Traders",
+        ]])
+    };
+    doc.pages
+        .push(Page::from_lines_in(Font::UNICODE, &["प्रमाणपत्र", "証明書"]));
+
+    let pdf = doc
+        .to_signed_bytes(&Signer::test())
+        .expect("sample document is writable");
+    std::fs::write(&path, pdf).expect("failed to write output");
+    println!("wrote {}", path);
+}
@@ -0,0 +1,691 @@
+//! Minimal PDF writer for tests and examples.
+//!
+//! Produces small, deterministic documents: pages of positioned text runs in the standard 14
+//! fonts or a Type0 font with a ToUnicode CMap, optionally Flate-compressed, and with the
+//! `sign` feature a detached PKCS#7 signature from a bundled test key. The same [`Document`]
+//! always renders to the same bytes, so fixtures can be generated at test time instead of
+//! checked in.
+//!
+//! ```
+//! use zkpdf_writer::Document;
+//!
+//! let doc = Document::from_pages(&[&["Certificate No. 42", "Issued to: Alice"]]);
+//! let pdf = doc.to_bytes().unwrap();
+//! assert!(pdf.starts_with(b"%PDF-1.7"));
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use miniz_oxide::deflate::compress_to_vec_zlib;
+
+pub mod qr;
+#[cfg(feature = "sign")]
+mod sign;
+
+#[cfg(feature = "sign")]
+pub use sign::Signer;
+#[cfg(feature = "sign")]
+pub use signature_validator::signing::SubFilter;
+
+/// How a font maps text to character codes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Encoding {
+    /// No `/Encoding` entry, so the font's built-in encoding applies. Limited to printable
+    /// ASCII without `'` and `` ` ``, which StandardEncoding maps to curly quotes.
+    Builtin,
+    /// `/WinAnsiEncoding`: printable ASCII and Latin-1.
+    WinAnsi,
+    /// `/MacRomanEncoding`: printable ASCII.
+    MacRoman,
+    /// Type0 font with `/Identity-H` and a ToUnicode CMap: any text.
+    Identity,
+}
+
+/// A font selected by a text run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Font {
+    pub base_font: &'static str,
+    pub encoding: Encoding,
+}
+
+impl Font {
+    pub const HELVETICA: Font = Font {
+        base_font: "Helvetica",
+        encoding: Encoding::WinAnsi,
+    };
+    pub const TIMES: Font = Font {
+        base_font: "Times-Roman",
+        encoding: Encoding::WinAnsi,
+    };
+    pub const COURIER: Font = Font {
+        base_font: "Courier",
+        encoding: Encoding::WinAnsi,
+    };
+    /// A composite font that can write any Unicode text.
+    pub const UNICODE: Font = Font {
+        base_font: "ZkpdfUnicode",
+        encoding: Encoding::Identity,
+    };
+}
+
+/// A string drawn at a position. Every run extracts as its own line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextRun {
+    pub text: String,
+    pub font: Font,
+    pub size: f32,
+    pub x: f32,
+    pub y: f32,
+}
+
+/// An 8-bit grayscale image, drawn `display_width` x `display_height` points with its
+/// lower-left corner at `x, y`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    /// `width * height` samples, row by row from the top; 0 is black.
+    pub samples: Vec<u8>,
+    pub x: f32,
+    pub y: f32,
+    pub display_width: f32,
+    pub display_height: f32,
+}
+
+/// A page; the default is US Letter with no content.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Page {
+    pub width: f32,
+    pub height: f32,
+    pub runs: Vec<TextRun>,
+    /// Drawn before the text, as image XObjects `/Im1`, `/Im2`, ...
+    pub images: Vec<Image>,
+}
+
+impl Default for Page {
+    fn default() -> Self {
+        Self {
+            width: 612.0,
+            height: 792.0,
+            runs: Vec::new(),
+            images: Vec::new(),
+        }
+    }
+}
+
+impl Page {
+    /// One line of 12pt Helvetica per entry, top to bottom.
+    pub fn from_lines(lines: &[&str]) -> Self {
+        Self::from_lines_in(Font::HELVETICA, lines)
+    }
+
+    /// One line of 12pt text in `font` per entry, top to bottom.
+    pub fn from_lines_in(font: Font, lines: &[&str]) -> Self {
+        let mut page = Page::default();
+        for (i, line) in lines.iter().enumerate() {
+            page.runs.push(TextRun {
+                text: line.to_string(),
+                font,
+                size: 12.0,
+                x: 72.0,
+                y: 720.0 - 14.0 * i as f32,
+            });
+        }
+        page
+    }
+}
+
+/// A document to write. Render with [`Document::to_bytes`], or
+/// [`Document::to_signed_bytes`] with the `sign` feature.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Document {
+    pub pages: Vec<Page>,
+    /// Flate-compress content streams, CMaps and images.
+    pub compress: bool,
+    /// Entries of the `/Info` dictionary, e.g. `("Title", "Certificate")`.
+    pub info: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WriteError {
+    /// The character has no code in the run's font encoding.
+    Unencodable { ch: char, encoding: Encoding },
+    /// The signing key could not be decoded.
+    InvalidKey(String),
+    /// The RSA signing operation failed.
+    Signing(String),
+    /// The CMS blob does not fit the space reserved for `/Contents`.
+    SignatureTooLarge { size: usize, capacity: usize },
+    /// A signed document needs a page to hold its signature field.
+    NoPages,
+    /// The data does not fit in a version 40 QR code.
+    QrCapacity,
+}
+
+impl fmt::Display for WriteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WriteError::Unencodable { ch, encoding } => {
+                write!(f, "{:?} cannot be written with {:?} encoding", ch, encoding)
+            }
+            WriteError::InvalidKey(msg) => write!(f, "invalid signing key: {}", msg),
+            WriteError::Signing(msg) => write!(f, "signing failed: {}", msg),
+            WriteError::SignatureTooLarge { size, capacity } => write!(
+                f,
+                "signature of {} bytes exceeds the {} reserved",
+                size, capacity
+            ),
+            WriteError::NoPages => write!(f, "cannot sign a document without pages"),
+            WriteError::QrCapacity => write!(f, "data too long for a QR code"),
+        }
+    }
+}
+
+impl std::error::Error for WriteError {}
+
+impl Document {
+    /// One page per entry, each holding one line of Helvetica per string.
+    pub fn from_pages(pages: &[&[&str]]) -> Self {
+        Self {
+            pages: pages.iter().map(|lines| Page::from_lines(lines)).collect(),
+            ..Self::default()
+        }
+    }
+
+    /// What canonical extraction returns for each page.
+    pub fn expected_text(&self) -> Vec<String> {
+        self.pages
+            .iter()
+            .map(|page| {
+                page.runs
+                    .iter()
+                    .map(|run| run.text.as_str())
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })
+            .collect()
+    }
+
+    /// Renders the document without a signature.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, WriteError> {
+        self.render(None)
+    }
+
+    /// Renders the document. With `signature`, the first page gets a signature widget whose
+    /// value is that dictionary, wired into the catalog's `/AcroForm`.
+    fn render(&self, signature: Option<&[u8]>) -> Result<Vec<u8>, WriteError> {
+        let mut objects = Objects::default();
+        let catalog = objects.reserve();
+        let root = objects.reserve();
+        let fonts = FontTable::new(self, &mut objects)?;
+
+        let page_ids: Vec<usize> = self.pages.iter().map(|_| objects.reserve()).collect();
+        let widget = match (signature, page_ids.first()) {
+            (Some(dict), Some(&first)) => {
+                let value = objects.add(dict.to_vec());
+                Some(
+                    objects.add(
+                        format!(
+                            "<< /Type /Annot /Subtype /Widget /FT /Sig /T (Signature1) /F 132 \
+                         /Rect [0 0 0 0] /P {} 0 R /V {} 0 R >>",
+                            first, value
+                        )
+                        .into_bytes(),
+                    ),
+                )
+            }
+            _ => None,
+        };
+
+        for (index, (page, &id)) in self.pages.iter().zip(&page_ids).enumerate() {
+            let mut content = Vec::new();
+            let mut xobjects = Vec::new();
+            for (n, image) in page.images.iter().enumerate() {
+                let image_id = objects.add(image_stream(image, self.compress));
+                xobjects.push(format!("/Im{} {} 0 R", n + 1, image_id));
+                content.extend_from_slice(
+                    format!(
+                        "q {} 0 0 {} {} {} cm /Im{} Do Q\n",
+                        image.display_width,
+                        image.display_height,
+                        image.x,
+                        image.y,
+                        n + 1
+                    )
+                    .as_bytes(),
+                );
+            }
+            content.extend(fonts.content(page)?);
+            let content = objects.add(stream(&content, self.compress));
+            let xobjects = if xobjects.is_empty() {
+                String::new()
+            } else {
+                format!(" /XObject << {} >>", xobjects.join(" "))
+            };
+            let annots = match widget {
+                Some(widget) if index == 0 => format!(" /Annots [{} 0 R]", widget),
+                _ => String::new(),
+            };
+            objects.set(
+                id,
+                format!(
+                    "<< /Type /Page /Parent {} 0 R /MediaBox [0 0 {} {}] \
+                     /Resources << /Font << {} >>{} >> /Contents {} 0 R{} >>",
+                    root,
+                    page.width,
+                    page.height,
+                    fonts.resources(page),
+                    xobjects,
+                    content,
+                    annots
+                )
+                .into_bytes(),
+            );
+        }
+
+        objects.set(
+            root,
+            format!(
+                "<< /Type /Pages /Kids [{}] /Count {} >>",
+                refs(&page_ids),
+                page_ids.len()
+            )
+            .into_bytes(),
+        );
+        let acro_form = match widget {
+            Some(widget) => format!(" /AcroForm << /Fields [{} 0 R] /SigFlags 3 >>", widget),
+            None => String::new(),
+        };
+        objects.set(
+            catalog,
+            format!("<< /Type /Catalog /Pages {} 0 R{} >>", root, acro_form).into_bytes(),
+        );
+
+        let info = (!self.info.is_empty()).then(|| {
+            let entries: Vec<String> = self
+                .info
+                .iter()
+                .map(|(key, value)| format!("/{} {}", key, text_string(value)))
+                .collect();
+            objects.add(format!("<< {} >>", entries.join(" ")).into_bytes())
+        });
+
+        Ok(objects.build(catalog, info))
+    }
+}
+
+/// Font resources shared by every page: `/F1`, `/F2`, ... in order of first use, plus the
+/// code assigned to each character of the Identity fonts.
+struct FontTable {
+    fonts: BTreeMap<Font, (usize, usize)>,
+    cids: BTreeMap<Font, BTreeMap<char, u16>>,
+}
+
+impl FontTable {
+    fn new(doc: &Document, objects: &mut Objects) -> Result<Self, WriteError> {
+        let mut order: Vec<Font> = Vec::new();
+        let mut chars: BTreeMap<Font, BTreeSet<char>> = BTreeMap::new();
+        for run in doc.pages.iter().flat_map(|page| &page.runs) {
+            if !order.contains(&run.font) {
+                order.push(run.font);
+            }
+            if run.font.encoding == Encoding::Identity {
+                chars.entry(run.font).or_default().extend(run.text.chars());
+            }
+        }
+
+        let mut table = FontTable {
+            fonts: BTreeMap::new(),
+            cids: BTreeMap::new(),
+        };
+        for (index, font) in order.into_iter().enumerate() {
+            let id = match font.encoding {
+                Encoding::Identity => {
+                    // Codes start at 1 in character order, so output does not depend on the
+                    // order text appears in.
+                    let cids: BTreeMap<char, u16> = chars
+                        .remove(&font)
+                        .unwrap_or_default()
+                        .into_iter()
+                        .zip(1..)
+                        .collect();
+                    let id = identity_font(objects, font, &cids, doc.compress);
+                    table.cids.insert(font, cids);
+                    id
+                }
+                encoding => {
+                    let entry = match encoding {
+                        Encoding::WinAnsi => " /Encoding /WinAnsiEncoding",
+                        Encoding::MacRoman => " /Encoding /MacRomanEncoding",
+                        _ => "",
+                    };
+                    objects.add(
+                        format!(
+                            "<< /Type /Font /Subtype /Type1 /BaseFont /{}{} >>",
+                            font.base_font, entry
+                        )
+                        .into_bytes(),
+                    )
+                }
+            };
+            table.fonts.insert(font, (index + 1, id));
+        }
+        Ok(table)
+    }
+
+    fn resources(&self, page: &Page) -> String {
+        let used: BTreeSet<(usize, usize)> =
+            page.runs.iter().map(|run| self.fonts[&run.font]).collect();
+        used.into_iter()
+            .map(|(n, id)| format!("/F{} {} 0 R", n, id))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn content(&self, page: &Page) -> Result<Vec<u8>, WriteError> {
+        let mut content = Vec::new();
+        for run in &page.runs {
+            let (n, _) = self.fonts[&run.font];
+            content.extend_from_slice(
+                format!("BT /F{} {} Tf 1 0 0 1 {} {} Tm ", n, run.size, run.x, run.y).as_bytes(),
+            );
+            content.extend(self.string(run)?);
+            content.extend_from_slice(b" Tj ET\n");
+        }
+        Ok(content)
+    }
+
+    /// The run's text as a PDF string in its font's encoding.
+    fn string(&self, run: &TextRun) -> Result<Vec<u8>, WriteError> {
+        let encoding = run.font.encoding;
+        if encoding == Encoding::Identity {
+            let cids = &self.cids[&run.font];
+            let hex: String = run
+                .text
+                .chars()
+                .map(|ch| format!("{:04X}", cids[&ch]))
+                .collect();
+            return Ok(format!("<{}>", hex).into_bytes());
+        }
+
+        let codes = run
+            .text
+            .chars()
+            .map(|ch| {
+                let code = ch as u32;
+                let printable = (0x20..0x7f).contains(&code);
+                let ok = match encoding {
+                    Encoding::Builtin => printable && ch != '\'' && ch != '`',
+                    Encoding::WinAnsi => printable || (0xa0..=0xff).contains(&code),
+                    _ => printable,
+                };
+                if ok {
+                    Ok(code as u8)
+                } else {
+                    Err(WriteError::Unencodable { ch, encoding })
+                }
+            })
+            .collect::<Result<Vec<u8>, _>>()?;
+        Ok(literal(&codes))
+    }
+}
+
+/// Writes a Type0 font with its descendant and ToUnicode CMap; returns the font's number.
+fn identity_font(
+    objects: &mut Objects,
+    font: Font,
+    cids: &BTreeMap<char, u16>,
+    compress: bool,
+) -> usize {
+    let descendant = objects.add(
+        format!(
+            "<< /Type /Font /Subtype /CIDFontType2 /BaseFont /{} \
+             /CIDSystemInfo << /Registry (Adobe) /Ordering (Identity) /Supplement 0 >> >>",
+            font.base_font
+        )
+        .into_bytes(),
+    );
+
+    let mut cmap = String::from(
+        "/CIDInit /ProcSet findresource begin\n12 dict begin\nbegincmap\n\
+         /CMapName /Adobe-Identity-UCS def\n/CMapType 2 def\n\
+         1 begincodespacerange\n<0000> <FFFF>\nendcodespacerange\n",
+    );
+    let entries: Vec<(&char, &u16)> = cids.iter().collect();
+    // At most 100 entries per block.
+    for chunk in entries.chunks(100) {
+        cmap.push_str(&format!("{} beginbfchar\n", chunk.len()));
+        for (ch, cid) in chunk {
+            let mut units = [0u16; 2];
+            let dst: String = ch
+                .encode_utf16(&mut units)
+                .iter()
+                .map(|unit| format!("{:04X}", unit))
+                .collect();
+            cmap.push_str(&format!("<{:04X}> <{}>\n", cid, dst));
+        }
+        cmap.push_str("endbfchar\n");
+    }
+    cmap.push_str("endcmap\nCMapName currentdict /CMap defineresource pop\nend\nend\n");
+    let to_unicode = objects.add(stream(cmap.as_bytes(), compress));
+
+    objects.add(
+        format!(
+            "<< /Type /Font /Subtype /Type0 /BaseFont /{} /Encoding /Identity-H \
+             /DescendantFonts [{} 0 R] /ToUnicode {} 0 R >>",
+            font.base_font, descendant, to_unicode
+        )
+        .into_bytes(),
+    )
+}
+
+/// A literal string, escaping delimiters and non-printable bytes.
+fn literal(bytes: &[u8]) -> Vec<u8> {
+    let mut out = vec![b'('];
+    for &b in bytes {
+        match b {
+            b'(' | b')' | b'\\' => out.extend_from_slice(&[b'\\', b]),
+            0x20..=0x7e => out.push(b),
+            _ => out.extend_from_slice(format!("\\{:03o}", b).as_bytes()),
+        }
+    }
+    out.push(b')');
+    out
+}
+
+/// A text string for metadata: literal ASCII, or UTF-16BE with a byte order mark.
+fn text_string(text: &str) -> String {
+    if text.bytes().all(|b| (0x20..0x7f).contains(&b)) {
+        String::from_utf8(literal(text.as_bytes())).expect("escaped ASCII is UTF-8")
+    } else {
+        let hex: String = text
+            .encode_utf16()
+            .map(|unit| format!("{:04X}", unit))
+            .collect();
+        format!("<FEFF{}>", hex)
+    }
+}
+
+fn image_stream(image: &Image, compress: bool) -> Vec<u8> {
+    let dict = format!(
+        " /Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace /DeviceGray \
+         /BitsPerComponent 8",
+        image.width, image.height
+    );
+    stream_with(&image.samples, compress, &dict)
+}
+
+fn stream(data: &[u8], compress: bool) -> Vec<u8> {
+    stream_with(data, compress, "")
+}
+
+fn stream_with(data: &[u8], compress: bool, entries: &str) -> Vec<u8> {
+    let (data, filter) = if compress {
+        (compress_to_vec_zlib(data, 6), " /Filter /FlateDecode")
+    } else {
+        (data.to_vec(), "")
+    };
+    let mut out = format!(
+        "<<{} /Length {}{} >>\nstream\n",
+        entries,
+        data.len(),
+        filter
+    )
+    .into_bytes();
+    out.extend_from_slice(&data);
+    out.extend_from_slice(b"\nendstream");
+    out
+}
+
+fn refs(ids: &[usize]) -> String {
+    ids.iter()
+        .map(|id| format!("{} 0 R", id))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Numbered objects, written out with a classic cross-reference table.
+#[derive(Default)]
+struct Objects {
+    bodies: Vec<Vec<u8>>,
+}
+
+impl Objects {
+    fn reserve(&mut self) -> usize {
+        self.bodies.push(Vec::new());
+        self.bodies.len()
+    }
+
+    fn set(&mut self, id: usize, body: Vec<u8>) {
+        self.bodies[id - 1] = body;
+    }
+
+    fn add(&mut self, body: Vec<u8>) -> usize {
+        let id = self.reserve();
+        self.set(id, body);
+        id
+    }
+
+    fn build(&self, catalog: usize, info: Option<usize>) -> Vec<u8> {
+        let mut out = b"%PDF-1.7\n%\xe2\xe3\xcf\xd3\n".to_vec();
+        let mut offsets = Vec::with_capacity(self.bodies.len());
+        for (i, body) in self.bodies.iter().enumerate() {
+            offsets.push(out.len());
+            out.extend_from_slice(format!("{} 0 obj\n", i + 1).as_bytes());
+            out.extend_from_slice(body);
+            out.extend_from_slice(b"\nendobj\n");
+        }
+        let xref = out.len();
+        out.extend_from_slice(
+            format!("xref\n0 {}\n0000000000 65535 f \n", self.bodies.len() + 1).as_bytes(),
+        );
+        for offset in offsets {
+            out.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+        }
+        let info = info.map_or(String::new(), |id| format!(" /Info {} 0 R", id));
+        out.extend_from_slice(
+            format!(
+                "trailer\n<< /Size {} /Root {} 0 R{} >>\nstartxref\n{}\n%%EOF\n",
+                self.bodies.len() + 1,
+                catalog,
+                info,
+                xref
+            )
+            .as_bytes(),
+        );
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use extractor::types::ExtractOptions;
+
+    fn extract(pdf: &[u8]) -> Vec<String> {
+        extractor::extract_text(pdf.to_vec()).expect("generated PDF must extract")
+    }
+
+    #[test]
+    fn text_round_trips_through_extractor() {
+        let doc = Document::from_pages(&[
+            &["Certificate No. 42", "Issued to: (Alice) \\ Bob"],
+            &["Second page"],
+        ]);
+        let pdf = doc.to_bytes().unwrap();
+        assert_eq!(extract(&pdf), doc.expected_text());
+
+        let page = extractor::extract_page_text(&pdf, 1, &ExtractOptions::default()).unwrap();
+        assert_eq!(page.text, "Second page");
+        assert_eq!(page.page_count, 2);
+    }
+
+    #[test]
+    fn every_encoding_round_trips_compressed_or_not() {
+        let fonts = [
+            Font {
+                base_font: "Helvetica",
+                encoding: Encoding::Builtin,
+            },
+            Font::TIMES,
+            Font {
+                base_font: "Courier",
+                encoding: Encoding::MacRoman,
+            },
+            Font::UNICODE,
+        ];
+        for compress in [false, true] {
+            let mut page = Page::default();
+            for (i, font) in fonts.into_iter().enumerate() {
+                page.runs.push(TextRun {
+                    text: format!("Line {} of 4", i + 1),
+                    font,
+                    size: 10.0,
+                    x: 72.0,
+                    y: 700.0 - 20.0 * i as f32,
+                });
+            }
+            page.runs.push(TextRun {
+                text: "Müller — 東京 ✓".to_string(),
+                ..page.runs[3].clone()
+            });
+            page.runs.push(TextRun {
+                text: "Café Zürich".to_string(),
+                ..page.runs[1].clone()
+            });
+            let doc = Document {
+                pages: vec![page],
+                compress,
+                info: vec![("Title".to_string(), "Führerschein".to_string())],
+            };
+            let pdf = doc.to_bytes().unwrap();
+            assert_eq!(extract(&pdf), doc.expected_text());
+            assert_eq!(
+                extractor::document_info(&pdf).unwrap().metadata["Title"],
+                "Führerschein"
+            );
+        }
+    }
+
+    #[test]
+    fn rendering_is_deterministic() {
+        let doc = Document {
+            compress: true,
+            ..Document::from_pages(&[&["same", "bytes"]])
+        };
+        assert_eq!(doc.to_bytes().unwrap(), doc.to_bytes().unwrap());
+    }
+
+    #[test]
+    fn rejects_unencodable_text() {
+        let doc = Document::from_pages(&[&["price: 5 €"]]);
+        assert_eq!(
+            doc.to_bytes().unwrap_err(),
+            WriteError::Unencodable {
+                ch: '€',
+                encoding: Encoding::WinAnsi
+            }
+        );
+    }
+}
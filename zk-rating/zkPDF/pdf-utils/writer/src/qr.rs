@@ -0,0 +1,598 @@
+//! QR code encoder (ISO/IEC 18004, model 2), for fixtures that carry machine-readable data.
+//!
+//! ```
+//! use zkpdf_writer::qr::{encode, EcLevel, Segment};
+//!
+//! let code = encode(&[Segment::Bytes(b"hello")], EcLevel::M, None).unwrap();
+//! assert_eq!(code.version, 1);
+//! let image = code.to_image(72.0, 72.0, 2.0);
+//! ```
+
+use crate::{Image, WriteError};
+
+/// Error correction level, in increasing order of redundancy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EcLevel {
+    L,
+    M,
+    Q,
+    H,
+}
+
+impl EcLevel {
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    /// The two format-information bits.
+    fn format_bits(self) -> u32 {
+        match self {
+            EcLevel::L => 1,
+            EcLevel::M => 0,
+            EcLevel::Q => 3,
+            EcLevel::H => 2,
+        }
+    }
+}
+
+/// A run of data in one encoding mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Segment<'a> {
+    /// ASCII digits only.
+    Numeric(&'a str),
+    /// `0-9`, `A-Z`, space and `$%*+-./:`.
+    Alphanumeric(&'a str),
+    Bytes(&'a [u8]),
+}
+
+const ALPHANUMERIC: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ $%*+-./:";
+
+// Error correction codewords per block and number of blocks, by level then version (index 0
+// unused)
+const ECC_CODEWORDS_PER_BLOCK: [[u8; 41]; 4] = [
+    [
+        0, 7, 10, 15, 20, 26, 18, 20, 24, 30, 18, 20, 24, 26, 30, 22, 24, 28, 30, 28, 28, 28, 28,
+        30, 30, 26, 28, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30,
+    ],
+    [
+        0, 10, 16, 26, 18, 24, 16, 18, 22, 22, 26, 30, 22, 22, 24, 24, 28, 28, 26, 26, 26, 26, 28,
+        28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28,
+    ],
+    [
+        0, 13, 22, 18, 26, 18, 24, 18, 22, 20, 24, 28, 26, 24, 20, 30, 24, 28, 28, 26, 30, 28, 30,
+        30, 30, 30, 28, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30,
+    ],
+    [
+        0, 17, 28, 22, 16, 22, 28, 26, 26, 24, 28, 24, 28, 22, 24, 24, 30, 28, 28, 26, 28, 30, 24,
+        30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30,
+    ],
+];
+const ECC_BLOCKS: [[u8; 41]; 4] = [
+    [
+        0, 1, 1, 1, 1, 1, 2, 2, 2, 2, 4, 4, 4, 4, 4, 6, 6, 6, 6, 7, 8, 8, 9, 9, 10, 12, 12, 12, 13,
+        14, 15, 16, 17, 18, 19, 19, 20, 21, 22, 24, 25,
+    ],
+    [
+        0, 1, 1, 1, 2, 2, 4, 4, 4, 5, 5, 5, 8, 9, 9, 10, 10, 11, 13, 14, 16, 17, 17, 18, 20, 21,
+        23, 25, 26, 28, 29, 31, 33, 35, 37, 38, 40, 43, 45, 47, 49,
+    ],
+    [
+        0, 1, 1, 2, 2, 4, 4, 6, 6, 8, 8, 8, 10, 12, 16, 12, 17, 16, 18, 21, 20, 23, 23, 25, 27, 29,
+        34, 34, 35, 38, 40, 43, 45, 48, 51, 53, 56, 59, 62, 65, 68,
+    ],
+    [
+        0, 1, 1, 2, 4, 4, 4, 5, 6, 8, 8, 11, 11, 16, 16, 18, 16, 19, 21, 25, 25, 25, 34, 30, 32,
+        35, 37, 40, 42, 45, 48, 51, 54, 57, 60, 63, 66, 70, 74, 77, 81,
+    ],
+];
+
+/// An encoded symbol: `size` x `size` modules, `true` for dark.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QrCode {
+    pub version: u8,
+    pub ec_level: EcLevel,
+    pub mask: u8,
+    pub size: usize,
+    modules: Vec<bool>,
+}
+
+/// Encodes `segments` in the smallest version that fits. With `mask: None` the mask with the
+/// lowest penalty score is chosen, as a real encoder would.
+pub fn encode(
+    segments: &[Segment],
+    ec_level: EcLevel,
+    mask: Option<u8>,
+) -> Result<QrCode, WriteError> {
+    let (version, codewords) = data_codewords_for(segments, ec_level)?;
+    let codewords = add_ecc_and_interleave(&codewords, version, ec_level);
+    let mut code = QrCode {
+        version,
+        ec_level,
+        mask: 0,
+        size: version as usize * 4 + 17,
+        modules: Vec::new(),
+    };
+    let (base, function) = code.function_patterns();
+    let mut data_matrix = base;
+    place_codewords(&mut data_matrix, &function, code.size, &codewords);
+
+    let masks: Vec<u8> = match mask {
+        Some(mask) => vec![mask & 7],
+        None => (0..8).collect(),
+    };
+    let (mask, modules) = masks
+        .into_iter()
+        .map(|mask| {
+            let mut modules = data_matrix.clone();
+            apply_mask(&mut modules, &function, code.size, mask);
+            draw_format(&mut modules, code.size, ec_level, mask);
+            (mask, modules)
+        })
+        .min_by_key(|(_, modules)| penalty(modules, code.size))
+        .expect("at least one mask");
+    code.mask = mask;
+    code.modules = modules;
+    Ok(code)
+}
+
+impl QrCode {
+    pub fn is_dark(&self, x: usize, y: usize) -> bool {
+        self.modules[y * self.size + x]
+    }
+
+    /// A grayscale image of the symbol with a four-module quiet zone, one pixel per module,
+    /// drawn with its lower-left corner at `x, y` and each module `module_size` points wide.
+    pub fn to_image(&self, x: f32, y: f32, module_size: f32) -> Image {
+        let quiet = 4;
+        let side = self.size + 2 * quiet;
+        let mut samples = vec![255u8; side * side];
+        for row in 0..self.size {
+            for col in 0..self.size {
+                if self.is_dark(col, row) {
+                    samples[(row + quiet) * side + col + quiet] = 0;
+                }
+            }
+        }
+        Image {
+            width: side,
+            height: side,
+            samples,
+            x,
+            y,
+            display_width: side as f32 * module_size,
+            display_height: side as f32 * module_size,
+        }
+    }
+
+    /// Light modules with the finder, timing and alignment patterns, the dark module and the
+    /// version information drawn, plus the mask of every function module (format areas
+    /// included).
+    fn function_patterns(&self) -> (Vec<bool>, Vec<bool>) {
+        let size = self.size;
+        let mut modules = vec![false; size * size];
+        let mut function = vec![false; size * size];
+        let mut set = |x: usize, y: usize, dark: bool| {
+            modules[y * size + x] = dark;
+            function[y * size + x] = true;
+        };
+
+        for i in 0..size {
+            set(6, i, i % 2 == 0);
+            set(i, 6, i % 2 == 0);
+        }
+        for (cx, cy) in [(3, 3), (size - 4, 3), (3, size - 4)] {
+            for dy in -4i32..=4 {
+                for dx in -4i32..=4 {
+                    let (x, y) = (cx as i32 + dx, cy as i32 + dy);
+                    if (0..size as i32).contains(&x) && (0..size as i32).contains(&y) {
+                        let dist = dx.abs().max(dy.abs());
+                        set(x as usize, y as usize, dist != 2 && dist != 4);
+                    }
+                }
+            }
+        }
+        let positions = alignment_positions(self.version);
+        let last = positions.len().saturating_sub(1);
+        for (i, &cx) in positions.iter().enumerate() {
+            for (j, &cy) in positions.iter().enumerate() {
+                // Skip the three corners occupied by finder patterns
+                if (i == 0 && (j == 0 || j == last)) || (i == last && j == 0) {
+                    continue;
+                }
+                for dy in -2i32..=2 {
+                    for dx in -2i32..=2 {
+                        let dist = dx.abs().max(dy.abs());
+                        set(
+                            (cx as i32 + dx) as usize,
+                            (cy as i32 + dy) as usize,
+                            dist != 1,
+                        );
+                    }
+                }
+            }
+        }
+        // Format areas (drawn per mask later) and the dark module
+        for i in 0..9 {
+            set(8, i, false);
+            set(i, 8, false);
+        }
+        for i in 0..8 {
+            set(size - 1 - i, 8, false);
+            set(8, size - 1 - i, false);
+        }
+        set(8, size - 8, true);
+        if self.version >= 7 {
+            let bits = version_bits(self.version);
+            for i in 0..18 {
+                let dark = (bits >> i) & 1 != 0;
+                let (a, b) = (size - 11 + i % 3, i / 3);
+                set(a, b, dark);
+                set(b, a, dark);
+            }
+        }
+        (modules, function)
+    }
+}
+
+/// Picks the smallest version that fits and returns it with the padded data codewords.
+fn data_codewords_for(
+    segments: &[Segment],
+    ec_level: EcLevel,
+) -> Result<(u8, Vec<u8>), WriteError> {
+    let (version, mut bits) = (1..=40)
+        .find_map(|version| {
+            let capacity = data_codewords(version, ec_level) * 8;
+            let bits = segment_bits(segments, version)?;
+            (bits.len() <= capacity).then_some((version, bits))
+        })
+        .ok_or(WriteError::QrCapacity)?;
+
+    let capacity = data_codewords(version, ec_level) * 8;
+    // Terminator, then pad to a byte boundary and fill with alternating pad bytes
+    let terminator = (capacity - bits.len()).min(4);
+    bits.extend(std::iter::repeat_n(false, terminator));
+    while bits.len() % 8 != 0 {
+        bits.push(false);
+    }
+    let mut codewords: Vec<u8> = bits
+        .chunks(8)
+        .map(|byte| byte.iter().fold(0, |acc, &bit| (acc << 1) | bit as u8))
+        .collect();
+    for pad in [0xec, 0x11].into_iter().cycle() {
+        if codewords.len() == capacity / 8 {
+            break;
+        }
+        codewords.push(pad);
+    }
+    Ok((version, codewords))
+}
+
+fn segment_bits(segments: &[Segment], version: u8) -> Option<Vec<bool>> {
+    let group = match version {
+        1..=9 => 0,
+        10..=26 => 1,
+        _ => 2,
+    };
+    let mut bits = Vec::new();
+    let push = |bits: &mut Vec<bool>, value: u32, len: usize| {
+        bits.extend((0..len).rev().map(|i| (value >> i) & 1 != 0));
+    };
+    for segment in segments {
+        let (mode, count_bits, count) = match segment {
+            Segment::Numeric(s) => (1, [10, 12, 14][group], s.len()),
+            Segment::Alphanumeric(s) => (2, [9, 11, 13][group], s.len()),
+            Segment::Bytes(b) => (4, [8, 16, 16][group], b.len()),
+        };
+        if count >= 1 << count_bits {
+            return None;
+        }
+        push(&mut bits, mode, 4);
+        push(&mut bits, count as u32, count_bits);
+        match segment {
+            Segment::Numeric(s) => {
+                for chunk in s.as_bytes().chunks(3) {
+                    let value = chunk.iter().fold(0, |acc, &d| acc * 10 + (d - b'0') as u32);
+                    push(&mut bits, value, chunk.len() * 3 + 1);
+                }
+            }
+            Segment::Alphanumeric(s) => {
+                let index = |c: &u8| ALPHANUMERIC.iter().position(|a| a == c).unwrap_or(0) as u32;
+                for chunk in s.as_bytes().chunks(2) {
+                    match chunk {
+                        [a, b] => push(&mut bits, index(a) * 45 + index(b), 11),
+                        [a] => push(&mut bits, index(a), 6),
+                        _ => unreachable!(),
+                    }
+                }
+            }
+            Segment::Bytes(bytes) => {
+                for &b in *bytes {
+                    push(&mut bits, b as u32, 8);
+                }
+            }
+        }
+    }
+    Some(bits)
+}
+
+fn raw_data_modules(version: u8) -> usize {
+    let v = version as usize;
+    let mut result = (16 * v + 128) * v + 64;
+    if v >= 2 {
+        let align = v / 7 + 2;
+        result -= (25 * align - 10) * align - 55;
+        if v >= 7 {
+            result -= 36;
+        }
+    }
+    result
+}
+
+fn data_codewords(version: u8, ec_level: EcLevel) -> usize {
+    let (v, l) = (version as usize, ec_level.index());
+    raw_data_modules(version) / 8
+        - ECC_CODEWORDS_PER_BLOCK[l][v] as usize * ECC_BLOCKS[l][v] as usize
+}
+
+fn alignment_positions(version: u8) -> Vec<usize> {
+    if version == 1 {
+        return Vec::new();
+    }
+    let v = version as usize;
+    let count = v / 7 + 2;
+    let step = (v * 8 + count * 3 + 5) / (count * 4 - 4) * 2;
+    let mut positions: Vec<usize> = (0..count - 1).map(|i| v * 4 + 10 - i * step).collect();
+    positions.push(6);
+    positions.reverse();
+    positions
+}
+
+fn version_bits(version: u8) -> u32 {
+    let mut rem = version as u32;
+    for _ in 0..12 {
+        rem = (rem << 1) ^ ((rem >> 11) * 0x1f25);
+    }
+    ((version as u32) << 12) | rem
+}
+
+fn format_bits(ec_level: EcLevel, mask: u8) -> u32 {
+    let data = (ec_level.format_bits() << 3) | mask as u32;
+    let mut rem = data;
+    for _ in 0..10 {
+        rem = (rem << 1) ^ ((rem >> 9) * 0x537);
+    }
+    ((data << 10) | rem) ^ 0x5412
+}
+
+fn draw_format(modules: &mut [bool], size: usize, ec_level: EcLevel, mask: u8) {
+    let bits = format_bits(ec_level, mask);
+    let bit = |i: usize| (bits >> i) & 1 != 0;
+    let mut set = |x: usize, y: usize, dark: bool| modules[y * size + x] = dark;
+    for i in 0..6 {
+        set(8, i, bit(i));
+    }
+    set(8, 7, bit(6));
+    set(8, 8, bit(7));
+    set(7, 8, bit(8));
+    for i in 9..15 {
+        set(14 - i, 8, bit(i));
+    }
+    for i in 0..8 {
+        set(size - 1 - i, 8, bit(i));
+    }
+    for i in 8..15 {
+        set(8, size - 15 + i, bit(i));
+    }
+}
+
+/// Fills the non-function modules in the zigzag order, two columns at a time from the right.
+fn place_codewords(modules: &mut [bool], function: &[bool], size: usize, codewords: &[u8]) {
+    let mut i = 0;
+    let mut right = size as i32 - 1;
+    while right >= 1 {
+        if right == 6 {
+            right = 5;
+        }
+        for vert in 0..size {
+            for j in 0..2 {
+                let x = (right - j) as usize;
+                let upward = (right + 1) & 2 == 0;
+                let y = if upward { size - 1 - vert } else { vert };
+                if !function[y * size + x] && i < codewords.len() * 8 {
+                    modules[y * size + x] = (codewords[i / 8] >> (7 - i % 8)) & 1 != 0;
+                    i += 1;
+                }
+            }
+        }
+        right -= 2;
+    }
+}
+
+fn mask_bit(mask: u8, x: usize, y: usize) -> bool {
+    match mask {
+        0 => (x + y).is_multiple_of(2),
+        1 => y.is_multiple_of(2),
+        2 => x.is_multiple_of(3),
+        3 => (x + y).is_multiple_of(3),
+        4 => (x / 3 + y / 2).is_multiple_of(2),
+        5 => x * y % 2 + x * y % 3 == 0,
+        6 => (x * y % 2 + x * y % 3).is_multiple_of(2),
+        _ => ((x + y) % 2 + x * y % 3).is_multiple_of(2),
+    }
+}
+
+fn apply_mask(modules: &mut [bool], function: &[bool], size: usize, mask: u8) {
+    for y in 0..size {
+        for x in 0..size {
+            if !function[y * size + x] && mask_bit(mask, x, y) {
+                modules[y * size + x] ^= true;
+            }
+        }
+    }
+}
+
+/// The standard mask penalty: long runs, 2x2 blocks, finder-like patterns and dark balance.
+fn penalty(modules: &[bool], size: usize) -> usize {
+    let at = |x: usize, y: usize| modules[y * size + x];
+    let mut score = 0;
+    for transpose in [false, true] {
+        for a in 0..size {
+            let line: Vec<bool> = (0..size)
+                .map(|b| if transpose { at(a, b) } else { at(b, a) })
+                .collect();
+            let mut run = 1;
+            for b in 1..=size {
+                if b < size && line[b] == line[b - 1] {
+                    run += 1;
+                } else {
+                    if run >= 5 {
+                        score += run - 2;
+                    }
+                    run = 1;
+                }
+            }
+            let finder = [true, false, true, true, true, false, true];
+            for b in 0..size.saturating_sub(6) {
+                if line[b..b + 7] != finder {
+                    continue;
+                }
+                let light = |range: std::ops::Range<usize>| {
+                    range.clone().all(|i| !line[i]) && range.end <= size
+                };
+                if (b >= 4 && light(b - 4..b)) || (b + 11 <= size && light(b + 7..b + 11)) {
+                    score += 40;
+                }
+            }
+        }
+    }
+    for y in 0..size - 1 {
+        for x in 0..size - 1 {
+            let c = at(x, y);
+            if c == at(x + 1, y) && c == at(x, y + 1) && c == at(x + 1, y + 1) {
+                score += 3;
+            }
+        }
+    }
+    let dark = modules.iter().filter(|&&m| m).count();
+    let total = size * size;
+    let k = (dark * 20)
+        .abs_diff(total * 10)
+        .div_ceil(total)
+        .saturating_sub(1);
+    score + k * 10
+}
+
+fn add_ecc_and_interleave(data: &[u8], version: u8, ec_level: EcLevel) -> Vec<u8> {
+    let (v, l) = (version as usize, ec_level.index());
+    let blocks = ECC_BLOCKS[l][v] as usize;
+    let ecc_len = ECC_CODEWORDS_PER_BLOCK[l][v] as usize;
+    let raw = raw_data_modules(version) / 8;
+    let short_blocks = blocks - raw % blocks;
+    let short_len = raw / blocks;
+
+    let divisor = rs_divisor(ecc_len);
+    let mut split: Vec<(Vec<u8>, Vec<u8>)> = Vec::with_capacity(blocks);
+    let mut start = 0;
+    for i in 0..blocks {
+        let len = short_len - ecc_len + usize::from(i >= short_blocks);
+        let block = data[start..start + len].to_vec();
+        start += len;
+        let ecc = rs_remainder(&block, &divisor);
+        split.push((block, ecc));
+    }
+
+    let mut out = Vec::with_capacity(raw);
+    for i in 0..=short_len - ecc_len {
+        for (block, _) in &split {
+            if let Some(&b) = block.get(i) {
+                out.push(b);
+            }
+        }
+    }
+    for i in 0..ecc_len {
+        for (_, ecc) in &split {
+            out.push(ecc[i]);
+        }
+    }
+    out
+}
+
+fn gf_mul(x: u8, y: u8) -> u8 {
+    let mut z: u16 = 0;
+    for i in (0..8).rev() {
+        z = (z << 1) ^ ((z >> 7) * 0x11d);
+        z ^= ((y as u16 >> i) & 1) * x as u16;
+    }
+    z as u8
+}
+
+/// Generator polynomial with roots 2^0 .. 2^(degree-1), highest coefficient (1) omitted.
+fn rs_divisor(degree: usize) -> Vec<u8> {
+    let mut result = vec![0u8; degree];
+    result[degree - 1] = 1;
+    let mut root = 1u8;
+    for _ in 0..degree {
+        for j in 0..degree {
+            result[j] = gf_mul(result[j], root);
+            if j + 1 < degree {
+                result[j] ^= result[j + 1];
+            }
+        }
+        root = gf_mul(root, 2);
+    }
+    result
+}
+
+fn rs_remainder(data: &[u8], divisor: &[u8]) -> Vec<u8> {
+    let mut result = vec![0u8; divisor.len()];
+    for &b in data {
+        let factor = b ^ result.remove(0);
+        result.push(0);
+        for (r, &d) in result.iter_mut().zip(divisor) {
+            *r ^= gf_mul(d, factor);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn capacities_match_the_standard() {
+        assert_eq!(data_codewords(1, EcLevel::L), 19);
+        assert_eq!(data_codewords(1, EcLevel::H), 9);
+        assert_eq!(data_codewords(5, EcLevel::Q), 62);
+        assert_eq!(data_codewords(10, EcLevel::M), 216);
+        assert_eq!(data_codewords(40, EcLevel::L), 2956);
+        assert_eq!(data_codewords(40, EcLevel::H), 1276);
+        assert_eq!(alignment_positions(7), [6, 22, 38]);
+        assert_eq!(alignment_positions(32), [6, 34, 60, 86, 112, 138]);
+    }
+
+    #[test]
+    fn hello_world_codewords() {
+        // The worked 1-Q example from the standard's annex.
+        let (version, data) =
+            data_codewords_for(&[Segment::Alphanumeric("HELLO WORLD")], EcLevel::Q).unwrap();
+        assert_eq!(version, 1);
+        assert_eq!(
+            data,
+            [32, 91, 11, 120, 209, 114, 220, 77, 67, 64, 236, 17, 236]
+        );
+        assert_eq!(
+            rs_remainder(&data, &rs_divisor(13)),
+            [168, 72, 22, 82, 217, 54, 156, 0, 46, 15, 180, 122, 16]
+        );
+        assert_eq!(add_ecc_and_interleave(&data, 1, EcLevel::Q)[..13], data);
+    }
+
+    #[test]
+    fn format_and_version_bits() {
+        assert_eq!(format_bits(EcLevel::M, 0), 0b101010000010010);
+        assert_eq!(format_bits(EcLevel::L, 0), 0b111011111000100);
+        assert_eq!(format_bits(EcLevel::H, 7), 0b000100000111011);
+        assert_eq!(version_bits(7), 0b000111110010010100);
+    }
+}
@@ -0,0 +1,221 @@
+//! Detached PKCS#7 (`adbe.pkcs7.detached`) and PAdES (`ETSI.CAdES.detached`) signing with a
+//! self-signed certificate, through `signature_validator::signing`.
+
+use signature_validator::signing::{self, SigningKey, SubFilter};
+use signature_validator::types::SigningError;
+
+use crate::{Document, WriteError};
+
+/// 2048-bit RSA key in PKCS#1 DER, generated for this crate. It is public: never trust a
+/// document signed with it.
+static TEST_KEY: &[u8] = include_bytes!("../keys/test-rsa-2048.der");
+
+/// Bytes reserved for the CMS blob; `/Contents` holds twice as many hex digits.
+const SIGNATURE_CAPACITY: usize = 8192;
+/// Wide enough for four offsets of up to ten digits.
+const BYTE_RANGE_PLACEHOLDER: &str = "0 0000000000 0000000000 0000000000";
+
+/// An RSA key with a self-signed certificate. Signatures use SHA-256 and PKCS#1 v1.5, and
+/// are deterministic: the same document and key always give the same bytes.
+#[derive(Debug, Clone)]
+pub struct Signer {
+    key: SigningKey,
+}
+
+impl Signer {
+    /// The bundled test key, certified as `C=IN, O=zkPDF Test, CN=zkPDF Test Signer`.
+    pub fn test() -> Self {
+        Self::from_pkcs1_der(TEST_KEY, "zkPDF Test Signer").expect("bundled test key is valid")
+    }
+
+    /// A signer for a PKCS#1 DER private key, self-certified under `common_name` and valid
+    /// from 2024 to 2044.
+    pub fn from_pkcs1_der(key_der: &[u8], common_name: &str) -> Result<Self, WriteError> {
+        let key = SigningKey::self_signed(
+            key_der,
+            &[("C", "IN"), ("O", "zkPDF Test"), ("CN", common_name)],
+        )?;
+        Ok(Self { key })
+    }
+
+    /// The self-signed X.509 certificate, DER-encoded.
+    pub fn certificate_der(&self) -> &[u8] {
+        self.key.certificate_der()
+    }
+
+    /// The underlying key, for `signature_validator::signing` directly.
+    pub fn signing_key(&self) -> &SigningKey {
+        &self.key
+    }
+
+    /// A detached CMS signature over `content`, as in a `.p7s` file.
+    pub fn sign_detached(&self, content: &[u8]) -> Result<Vec<u8>, WriteError> {
+        Ok(signing::sign_detached(
+            content,
+            &self.key,
+            SubFilter::Pkcs7Detached,
+        )?)
+    }
+}
+
+impl From<SigningError> for WriteError {
+    fn from(e: SigningError) -> Self {
+        match e {
+            SigningError::InvalidKey(msg) => WriteError::InvalidKey(msg),
+            SigningError::SignatureTooLarge { size, capacity } => {
+                WriteError::SignatureTooLarge { size, capacity }
+            }
+            other => WriteError::Signing(other.to_string()),
+        }
+    }
+}
+
+impl Document {
+    /// Renders the document with a detached PKCS#7 signature covering every byte outside
+    /// `/Contents`. Needs at least one page to hold the signature field.
+    pub fn to_signed_bytes(&self, signer: &Signer) -> Result<Vec<u8>, WriteError> {
+        self.to_signed_bytes_as(signer, SubFilter::Pkcs7Detached)
+    }
+
+    /// [`Document::to_signed_bytes`] with a chosen `/SubFilter`; `CadesDetached` gives a
+    /// PAdES baseline signature.
+    pub fn to_signed_bytes_as(
+        &self,
+        signer: &Signer,
+        sub_filter: SubFilter,
+    ) -> Result<Vec<u8>, WriteError> {
+        Ok(signing::sign_pdf(
+            &self.to_prepared_bytes(sub_filter)?,
+            &signer.key,
+        )?)
+    }
+
+    /// Renders the document with an empty signature field, ready for
+    /// `signature_validator::signing::sign_pdf`: a `/ByteRange` placeholder and `/Contents`
+    /// of zeros with room for an 8 KiB CMS blob.
+    pub fn to_prepared_bytes(&self, sub_filter: SubFilter) -> Result<Vec<u8>, WriteError> {
+        if self.pages.is_empty() {
+            return Err(WriteError::NoPages);
+        }
+        let dict = format!(
+            "<< /Type /Sig /Filter /Adobe.PPKLite /SubFilter /{} \
+             /ByteRange [{}] /Contents <{}> >>",
+            sub_filter.name(),
+            BYTE_RANGE_PLACEHOLDER,
+            "0".repeat(2 * SIGNATURE_CAPACITY)
+        );
+        self.render(Some(dict.as_bytes()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use signature_validator::types::SignatureValidationError;
+
+    fn find(haystack: &[u8], needle: &[u8], from: usize) -> usize {
+        haystack[from..]
+            .windows(needle.len())
+            .position(|w| w == needle)
+            .unwrap()
+            + from
+    }
+
+    fn signed(lines: &[&str]) -> (Document, Vec<u8>) {
+        let doc = Document::from_pages(&[lines]);
+        let pdf = doc.to_signed_bytes(&Signer::test()).unwrap();
+        (doc, pdf)
+    }
+
+    #[test]
+    fn signature_verifies_and_text_extracts() {
+        let (doc, pdf) = signed(&["Certificate No. 42", "Issued to: Alice"]);
+
+        let result = signature_validator::verify_pdf_signature(&pdf).unwrap();
+        assert!(result.is_valid);
+        assert_eq!(
+            extractor::extract_text(pdf.clone()).unwrap(),
+            doc.expected_text()
+        );
+        let offset = doc.expected_text()[0].find("Alice").unwrap();
+        let verified = pdf_core::verify_text(pdf.clone(), 0, "Alice", offset).unwrap();
+        assert!(verified.substring_matches && verified.signature.is_valid);
+
+        let fields = extractor::extract_form_fields(&pdf).unwrap();
+        assert_eq!(fields[0].name, "Signature1");
+    }
+
+    #[test]
+    fn signer_details_come_from_the_certificate() {
+        let (_, pdf) = signed(&["details"]);
+        let details = signature_validator::get_signer_details(&pdf).unwrap();
+        assert_eq!(details.subject, "C=IN, O=zkPDF Test, CN=zkPDF Test Signer");
+        assert_eq!(details.subject, details.issuer);
+        assert_eq!(details.key_size, 2048);
+    }
+
+    #[test]
+    fn tampering_breaks_the_digest() {
+        let (_, mut pdf) = signed(&["Amount: 100"]);
+        let at = find(&pdf, b"(Amount: 100)", 0);
+        pdf[at + 9] = b'9';
+        assert!(matches!(
+            signature_validator::verify_pdf_signature(&pdf),
+            Err(SignatureValidationError::MessageDigestMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn pades_signature_binds_the_certificate() {
+        let signer = Signer::test();
+        let pdf = Document::from_pages(&[&["PAdES"]])
+            .to_signed_bytes_as(&signer, SubFilter::CadesDetached)
+            .unwrap();
+        assert!(find(&pdf, b"/SubFilter /ETSI.CAdES.detached", 0) > 0);
+        assert!(
+            signature_validator::verify_pdf_signature(&pdf)
+                .unwrap()
+                .is_valid
+        );
+
+        // The signingCertificateV2 attribute carries the certificate hash
+        let (cms, _) =
+            signature_validator::signed_bytes_extractor::get_signature_der(&pdf).unwrap();
+        let cert_hash = {
+            use sha2::{Digest, Sha256};
+            Sha256::digest(signer.certificate_der())
+        };
+        assert!(cms.windows(32).any(|w| w == cert_hash.as_slice()));
+    }
+
+    #[test]
+    fn signing_is_deterministic() {
+        assert_eq!(signed(&["same"]).1, signed(&["same"]).1);
+    }
+
+    #[test]
+    fn signatures_ending_in_zero_still_verify() {
+        // The CMS ends with the RSA signature. Find a document whose signature ends in 0x00,
+        // which the zero padding of `/Contents` must not swallow.
+        let signer = Signer::test();
+        let pdf = (0..4000)
+            .map(|i| {
+                Document::from_pages(&[&[&format!("document {}", i)]])
+                    .to_signed_bytes(&signer)
+                    .unwrap()
+            })
+            .find(|pdf| {
+                let start = find(pdf, b"/Contents <", 0) + b"/Contents <".len();
+                let hex = std::str::from_utf8(&pdf[start..start + 8]).unwrap();
+                // 30 82 <len>: the blob is 4 + len bytes.
+                let len = 4 + usize::from_str_radix(&hex[4..], 16).unwrap();
+                &pdf[start + 2 * len - 2..start + 2 * len] == b"00"
+            })
+            .expect("about one in 256 signatures ends in a zero byte");
+        assert!(
+            signature_validator::verify_pdf_signature(&pdf)
+                .unwrap()
+                .is_valid
+        );
+    }
+}
@@ -0,0 +1,313 @@
+//! Canonical XML 1.0 and Exclusive XML Canonicalization 1.0, both without comments, of a
+//! whole document or of the subtree of one element.
+
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::parse::{local_name, prefix, Document, Element, Node, ProcessingInstruction};
+
+pub const C14N: &str = "http://www.w3.org/TR/2001/REC-xml-c14n-20010315";
+pub const EXC_C14N: &str = "http://www.w3.org/2001/10/xml-exc-c14n#";
+
+/// A canonicalization algorithm.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Canonicalization {
+    /// Canonical XML 1.0: every namespace in scope is rendered on the apex element.
+    Inclusive,
+    /// Exclusive XML Canonicalization 1.0: only namespaces an element visibly uses are
+    /// rendered, plus the prefixes of an InclusiveNamespaces PrefixList (`#default` for the
+    /// default namespace).
+    Exclusive { inclusive_prefixes: Vec<String> },
+}
+
+impl Canonicalization {
+    /// The algorithm an identifier names, if it is one of the two without comments.
+    pub fn from_uri(uri: &str) -> Option<Self> {
+        match uri {
+            C14N => Some(Canonicalization::Inclusive),
+            EXC_C14N => Some(Canonicalization::Exclusive {
+                inclusive_prefixes: Vec::new(),
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl Document {
+    /// The canonical form of the whole document.
+    pub fn canonicalize(&self, method: &Canonicalization) -> Vec<u8> {
+        canonicalize(self, None, None, method)
+    }
+}
+
+/// Namespaces in scope or rendered, by prefix (empty for the default namespace).
+type Namespaces = BTreeMap<String, String>;
+
+/// The canonical form of the subtree at `apex` (child indices from the document element), or
+/// of the whole document with its processing instructions if `apex` is `None`. The subtree at
+/// `exclude`, such as an enveloped signature, is left out.
+pub(crate) fn canonicalize(
+    document: &Document,
+    apex: Option<&[usize]>,
+    exclude: Option<&[usize]>,
+    method: &Canonicalization,
+) -> Vec<u8> {
+    let mut canonicalizer = Canonicalizer {
+        out: String::new(),
+        exclude,
+        method,
+    };
+    match apex {
+        None => {
+            for instruction in &document.prolog {
+                canonicalizer.processing_instruction(instruction);
+                canonicalizer.out.push('\n');
+            }
+            canonicalizer.element(
+                &document.root,
+                &Namespaces::new(),
+                &Namespaces::new(),
+                &mut Vec::new(),
+            );
+            for instruction in &document.epilog {
+                canonicalizer.out.push('\n');
+                canonicalizer.processing_instruction(instruction);
+            }
+        }
+        Some(apex) => {
+            // The apex inherits the namespaces its ancestors declare, none of which are rendered
+            let mut in_scope = Namespaces::new();
+            for depth in 0..apex.len() {
+                if let Some(ancestor) = document.root.at(&apex[..depth]) {
+                    declare(&mut in_scope, ancestor);
+                }
+            }
+            if let Some(element) = document.root.at(apex) {
+                canonicalizer.element(element, &in_scope, &Namespaces::new(), &mut apex.to_vec());
+            }
+        }
+    }
+    canonicalizer.out.into_bytes()
+}
+
+fn declare(in_scope: &mut Namespaces, element: &Element) {
+    for (prefix, namespace) in element.namespace_declarations() {
+        in_scope.insert(prefix.to_string(), namespace.to_string());
+    }
+}
+
+struct Canonicalizer<'a> {
+    out: String,
+    exclude: Option<&'a [usize]>,
+    method: &'a Canonicalization,
+}
+
+impl Canonicalizer<'_> {
+    /// Renders `element` at `path`, given the namespaces in scope at its parent and those
+    /// rendered on its output ancestors.
+    fn element(
+        &mut self,
+        element: &Element,
+        parent_scope: &Namespaces,
+        rendered: &Namespaces,
+        path: &mut Vec<usize>,
+    ) {
+        if self.exclude == Some(path.as_slice()) {
+            return;
+        }
+        let mut in_scope = parent_scope.clone();
+        declare(&mut in_scope, element);
+
+        let mut candidates: Vec<&str> = Vec::new();
+        match self.method {
+            Canonicalization::Inclusive => {
+                candidates.push("");
+                candidates.extend(in_scope.keys().map(String::as_str));
+            }
+            Canonicalization::Exclusive { inclusive_prefixes } => {
+                candidates.push(prefix(&element.name));
+                candidates.extend(
+                    element
+                        .attributes
+                        .iter()
+                        .map(|attribute| prefix(&attribute.name))
+                        .filter(|prefix| !prefix.is_empty() && *prefix != "xmlns"),
+                );
+                candidates.extend(
+                    inclusive_prefixes
+                        .iter()
+                        .map(|prefix| match prefix.as_str() {
+                            "#default" => "",
+                            prefix => prefix,
+                        }),
+                );
+            }
+        }
+        // A namespace is rendered unless the nearest output ancestor already rendered it
+        // with the same value; an empty default namespace counts as rendered at the apex
+        let mut declarations: BTreeMap<&str, &str> = BTreeMap::new();
+        for prefix in candidates {
+            if prefix == "xml" {
+                continue;
+            }
+            let namespace = in_scope.get(prefix).map_or("", String::as_str);
+            if rendered.get(prefix).map_or("", String::as_str) != namespace {
+                declarations.insert(prefix, namespace);
+            }
+        }
+
+        self.out.push('<');
+        self.out.push_str(&element.name);
+        for (prefix, namespace) in &declarations {
+            self.out.push_str(" xmlns");
+            if !prefix.is_empty() {
+                self.out.push(':');
+                self.out.push_str(prefix);
+            }
+            self.attribute_value(namespace);
+        }
+        let mut attributes: Vec<_> = element
+            .attributes
+            .iter()
+            .filter(|attribute| attribute.name != "xmlns" && !attribute.name.starts_with("xmlns:"))
+            .collect();
+        attributes.sort_by(|a, b| {
+            (a.namespace.as_str(), local_name(&a.name))
+                .cmp(&(b.namespace.as_str(), local_name(&b.name)))
+        });
+        for attribute in attributes {
+            self.out.push(' ');
+            self.out.push_str(&attribute.name);
+            self.attribute_value(&attribute.value);
+        }
+        self.out.push('>');
+
+        let mut rendered = rendered.clone();
+        for (prefix, namespace) in declarations {
+            rendered.insert(prefix.to_string(), namespace.to_string());
+        }
+        for (index, child) in element.children.iter().enumerate() {
+            match child {
+                Node::Element(child) => {
+                    path.push(index);
+                    self.element(child, &in_scope, &rendered, path);
+                    path.pop();
+                }
+                Node::Text(text) => self.text(text),
+                Node::ProcessingInstruction(instruction) => {
+                    self.processing_instruction(instruction)
+                }
+            }
+        }
+
+        self.out.push_str("</");
+        self.out.push_str(&element.name);
+        self.out.push('>');
+    }
+
+    fn text(&mut self, text: &str) {
+        for c in text.chars() {
+            match c {
+                '&' => self.out.push_str("&amp;"),
+                '<' => self.out.push_str("&lt;"),
+                '>' => self.out.push_str("&gt;"),
+                '\r' => self.out.push_str("&#xD;"),
+                c => self.out.push(c),
+            }
+        }
+    }
+
+    /// `="value"`, escaped.
+    fn attribute_value(&mut self, value: &str) {
+        self.out.push_str("=\"");
+        for c in value.chars() {
+            match c {
+                '&' => self.out.push_str("&amp;"),
+                '<' => self.out.push_str("&lt;"),
+                '"' => self.out.push_str("&quot;"),
+                '\t' => self.out.push_str("&#x9;"),
+                '\n' => self.out.push_str("&#xA;"),
+                '\r' => self.out.push_str("&#xD;"),
+                c => self.out.push(c),
+            }
+        }
+        self.out.push('"');
+    }
+
+    fn processing_instruction(&mut self, instruction: &ProcessingInstruction) {
+        self.out.push_str("<?");
+        self.out.push_str(&instruction.target);
+        if !instruction.data.is_empty() {
+            self.out.push(' ');
+            self.out.push_str(&instruction.data);
+        }
+        self.out.push_str("?>");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::parse;
+
+    // Expected output from libxml2 (`xmllint --exc-c14n` and `xmllint --c14n`)
+    const NAMESPACES: &str = "<a:root xmlns:a=\"urn:a\" xmlns:b=\"urn:b\" xmlns=\"urn:d\">\
+        <b:child z=\"1\" b:y=\"2\" a=\"3\" xml:lang=\"hi\"><inner xmlns=\"\" attr=\"&#9;x&#10;y\nz\"/>\
+        <d>t&#13;</d></b:child></a:root>";
+
+    #[test]
+    fn exclusive_renders_namespaces_where_used() {
+        let document = parse(NAMESPACES.as_bytes()).unwrap();
+        let canonical = document.canonicalize(&Canonicalization::Exclusive {
+            inclusive_prefixes: Vec::new(),
+        });
+        assert_eq!(
+            String::from_utf8(canonical).unwrap(),
+            "<a:root xmlns:a=\"urn:a\"><b:child xmlns:b=\"urn:b\" a=\"3\" z=\"1\" xml:lang=\"hi\" \
+             b:y=\"2\"><inner attr=\"&#x9;x&#xA;y z\"></inner><d xmlns=\"urn:d\">t&#xD;</d>\
+             </b:child></a:root>"
+        );
+
+        // A PrefixList prefix is rendered where it is first in scope, used or not
+        let canonical = document.canonicalize(&Canonicalization::Exclusive {
+            inclusive_prefixes: vec!["b".into(), "#default".into()],
+        });
+        assert!(String::from_utf8(canonical).unwrap().starts_with(
+            "<a:root xmlns=\"urn:d\" xmlns:a=\"urn:a\" xmlns:b=\"urn:b\"><b:child a="
+        ));
+    }
+
+    #[test]
+    fn inclusive_renders_every_namespace_in_scope() {
+        let document = parse(NAMESPACES.as_bytes()).unwrap();
+        assert_eq!(
+            String::from_utf8(document.canonicalize(&Canonicalization::Inclusive)).unwrap(),
+            "<a:root xmlns=\"urn:d\" xmlns:a=\"urn:a\" xmlns:b=\"urn:b\"><b:child a=\"3\" z=\"1\" \
+             xml:lang=\"hi\" b:y=\"2\"><inner xmlns=\"\" attr=\"&#x9;x&#xA;y z\"></inner>\
+             <d>t&#xD;</d></b:child></a:root>"
+        );
+    }
+
+    #[test]
+    fn subtree_inherits_ancestor_namespaces() {
+        let document = parse(NAMESPACES.as_bytes()).unwrap();
+        let child = canonicalize(&document, Some(&[0]), None, &Canonicalization::Inclusive);
+        assert!(String::from_utf8(child)
+            .unwrap()
+            .starts_with("<b:child xmlns=\"urn:d\" xmlns:a=\"urn:a\" xmlns:b=\"urn:b\" a=\"3\""));
+        let without_inner = canonicalize(
+            &document,
+            Some(&[0]),
+            Some(&[0, 0]),
+            &Canonicalization::Exclusive {
+                inclusive_prefixes: Vec::new(),
+            },
+        );
+        assert_eq!(
+            String::from_utf8(without_inner).unwrap(),
+            "<b:child xmlns:b=\"urn:b\" a=\"3\" z=\"1\" xml:lang=\"hi\" b:y=\"2\">\
+             <d xmlns=\"urn:d\">t&#xD;</d></b:child>"
+        );
+    }
+}
@@ -0,0 +1,329 @@
+//! Enveloped XML-DSig signatures (XML Signature Syntax and Processing): each Reference's
+//! digest over its canonicalized content, then the RSA signature over the canonical SignedInfo.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use rsa::pkcs1::EncodeRsaPublicKey;
+use rsa::pkcs8::DecodePublicKey;
+use rsa::{BigUint, Pkcs1v15Sign, RsaPublicKey};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use signature_validator::pkcs7_parser::{certificate_details, certificate_spki_der};
+use signature_validator::types::SignatureAlgorithm;
+
+use crate::c14n::{canonicalize, Canonicalization, EXC_C14N};
+use crate::parse::{Document, Element, Node};
+use crate::{XmlError, XmlSignatureResult};
+
+/// The XML-DSig namespace.
+pub const DSIG: &str = "http://www.w3.org/2000/09/xmldsig#";
+const ENVELOPED_SIGNATURE: &str = "http://www.w3.org/2000/09/xmldsig#enveloped-signature";
+const RSA_SHA1: &str = "http://www.w3.org/2000/09/xmldsig#rsa-sha1";
+const RSA_SHA256: &str = "http://www.w3.org/2001/04/xmldsig-more#rsa-sha256";
+const SHA1: &str = "http://www.w3.org/2000/09/xmldsig#sha1";
+const SHA256: &str = "http://www.w3.org/2001/04/xmlenc#sha256";
+
+#[derive(Debug, Clone, Copy)]
+enum DigestMethod {
+    Sha1,
+    Sha256,
+}
+
+impl DigestMethod {
+    fn digest(self, data: &[u8]) -> Vec<u8> {
+        match self {
+            DigestMethod::Sha1 => Sha1::digest(data).to_vec(),
+            DigestMethod::Sha256 => Sha256::digest(data).to_vec(),
+        }
+    }
+}
+
+/// What a verified signature covers: the subtrees its References point at (`None` for the
+/// whole document) and the Signature element itself, which is never signed content.
+pub(crate) struct Coverage {
+    pub references: Vec<Option<Vec<usize>>>,
+    pub signature: Vec<usize>,
+}
+
+/// Verifies the first Signature element of `document`, with the key of `certificate` if given
+/// and otherwise the one KeyInfo carries.
+pub(crate) fn verify(
+    document: &Document,
+    certificate: Option<&[u8]>,
+) -> Result<(XmlSignatureResult, Coverage), XmlError> {
+    let signature_path = find(&document.root, &mut Vec::new(), &|element| {
+        element.is(DSIG, "Signature")
+    })
+    .ok_or(XmlError::NotSigned)?;
+    let signature = document
+        .root
+        .at(&signature_path)
+        .ok_or(XmlError::NotSigned)?;
+    let (signed_info_index, signed_info) = signature
+        .children
+        .iter()
+        .enumerate()
+        .find_map(|(index, node)| match node {
+            Node::Element(element) if element.is(DSIG, "SignedInfo") => Some((index, element)),
+            _ => None,
+        })
+        .ok_or(XmlError::Malformed("SignedInfo not found"))?;
+
+    // Step 1: every Reference's digest over the content it covers
+    let mut references = Vec::new();
+    let mut covered = Vec::new();
+    for reference in signed_info
+        .elements()
+        .filter(|element| element.is(DSIG, "Reference"))
+    {
+        let uri = reference
+            .attribute("URI")
+            .ok_or(XmlError::Malformed("Reference without a URI"))?;
+        let apex = resolve(document, uri)?;
+        let mut method = None;
+        let mut enveloped = false;
+        if let Some(transforms) = reference.child(DSIG, "Transforms") {
+            for transform in transforms
+                .elements()
+                .filter(|element| element.is(DSIG, "Transform"))
+            {
+                match algorithm(transform)? {
+                    ENVELOPED_SIGNATURE => enveloped = true,
+                    _ => method = Some(canonicalization(transform)?),
+                }
+            }
+        }
+        // Without a canonicalization transform the node-set becomes octets by Canonical XML 1.0
+        let content = canonicalize(
+            document,
+            apex.as_deref(),
+            enveloped.then_some(signature_path.as_slice()),
+            &method.unwrap_or(Canonicalization::Inclusive),
+        );
+        let digest_method = digest_method(
+            reference
+                .child(DSIG, "DigestMethod")
+                .ok_or(XmlError::Malformed("DigestMethod not found"))?,
+        )?;
+        let expected = base64(
+            reference
+                .child(DSIG, "DigestValue")
+                .ok_or(XmlError::Malformed("DigestValue not found"))?,
+        )?;
+        if digest_method.digest(&content) != expected {
+            return Err(XmlError::DigestMismatch {
+                uri: uri.to_string(),
+            });
+        }
+        references.push(uri.to_string());
+        covered.push(apex);
+    }
+    if references.is_empty() {
+        return Err(XmlError::Malformed("SignedInfo has no Reference"));
+    }
+
+    // Step 2: the signature over the canonical SignedInfo
+    let method = canonicalization(
+        signed_info
+            .child(DSIG, "CanonicalizationMethod")
+            .ok_or(XmlError::Malformed("CanonicalizationMethod not found"))?,
+    )?;
+    let mut signed_info_path = signature_path.clone();
+    signed_info_path.push(signed_info_index);
+    let canonical_signed_info = canonicalize(document, Some(&signed_info_path), None, &method);
+    let signature_method = signed_info
+        .child(DSIG, "SignatureMethod")
+        .ok_or(XmlError::Malformed("SignatureMethod not found"))?;
+    let (signature_algorithm, digest_method) = match algorithm(signature_method)? {
+        RSA_SHA256 => (
+            SignatureAlgorithm::Sha256WithRsaEncryption,
+            DigestMethod::Sha256,
+        ),
+        RSA_SHA1 => (
+            SignatureAlgorithm::Sha1WithRsaEncryption,
+            DigestMethod::Sha1,
+        ),
+        other => return Err(XmlError::UnsupportedAlgorithm(other.to_string())),
+    };
+    let message_digest = digest_method.digest(&canonical_signed_info);
+    let signature_value = base64(
+        signature
+            .child(DSIG, "SignatureValue")
+            .ok_or(XmlError::Malformed("SignatureValue not found"))?,
+    )?;
+
+    let (key, certificate) = match certificate {
+        Some(certificate) => (certificate_key(certificate)?, Some(certificate.to_vec())),
+        None => signer_key(signature)?,
+    };
+    let padding = match digest_method {
+        DigestMethod::Sha1 => Pkcs1v15Sign::new::<Sha1>(),
+        DigestMethod::Sha256 => Pkcs1v15Sign::new::<Sha256>(),
+    };
+    let is_valid = key
+        .verify(padding, &message_digest, &signature_value)
+        .is_ok();
+    let signer = match certificate {
+        Some(certificate) => Some(
+            certificate_details(&certificate, signature_algorithm.clone())
+                .map_err(XmlError::Certificate)?,
+        ),
+        None => None,
+    };
+
+    Ok((
+        XmlSignatureResult {
+            is_valid,
+            signature_algorithm,
+            message_digest,
+            public_key: key
+                .to_pkcs1_der()
+                .map_err(|_| XmlError::InvalidPublicKey)?
+                .as_bytes()
+                .to_vec(),
+            signer,
+            references,
+        },
+        Coverage {
+            references: covered,
+            signature: signature_path,
+        },
+    ))
+}
+
+/// The path of the first element, in document order, that `matches`.
+pub(crate) fn find(
+    element: &Element,
+    path: &mut Vec<usize>,
+    matches: &dyn Fn(&Element) -> bool,
+) -> Option<Vec<usize>> {
+    if matches(element) {
+        return Some(path.clone());
+    }
+    for (index, child) in element.children.iter().enumerate() {
+        if let Node::Element(child) = child {
+            path.push(index);
+            let found = find(child, path, matches);
+            path.pop();
+            if found.is_some() {
+                return found;
+            }
+        }
+    }
+    None
+}
+
+/// The subtree a Reference URI points at: `None` for the whole document (`""`), or the one
+/// element whose `Id`, `ID` or `id` attribute is the fragment of `#fragment`.
+fn resolve(document: &Document, uri: &str) -> Result<Option<Vec<usize>>, XmlError> {
+    if uri.is_empty() {
+        return Ok(None);
+    }
+    let not_found = || XmlError::ReferenceNotFound(uri.to_string());
+    let id = uri.strip_prefix('#').ok_or_else(not_found)?;
+    let has_id = |element: &Element| {
+        ["Id", "ID", "id"]
+            .iter()
+            .any(|name| element.attribute(name) == Some(id))
+    };
+    let path = find(&document.root, &mut Vec::new(), &has_id).ok_or_else(not_found)?;
+    // A second element with the same ID could be swapped in for the signed one
+    let mut count = 0;
+    count_matching(&document.root, &has_id, &mut count);
+    if count > 1 {
+        return Err(XmlError::Malformed("duplicate ID"));
+    }
+    Ok(Some(path))
+}
+
+fn count_matching(element: &Element, matches: &dyn Fn(&Element) -> bool, count: &mut usize) {
+    if matches(element) {
+        *count += 1;
+    }
+    for child in element.elements() {
+        count_matching(child, matches, count);
+    }
+}
+
+fn algorithm(element: &Element) -> Result<&str, XmlError> {
+    element
+        .attribute("Algorithm")
+        .ok_or(XmlError::Malformed("Algorithm attribute not found"))
+}
+
+/// The canonicalization a CanonicalizationMethod or Transform element names, with the
+/// InclusiveNamespaces PrefixList of an exclusive one.
+fn canonicalization(element: &Element) -> Result<Canonicalization, XmlError> {
+    let uri = algorithm(element)?;
+    match Canonicalization::from_uri(uri) {
+        Some(Canonicalization::Exclusive { .. }) => Ok(Canonicalization::Exclusive {
+            inclusive_prefixes: element
+                .child(EXC_C14N, "InclusiveNamespaces")
+                .and_then(|inclusive| inclusive.attribute("PrefixList"))
+                .map(|list| list.split_whitespace().map(String::from).collect())
+                .unwrap_or_default(),
+        }),
+        Some(method) => Ok(method),
+        None => Err(XmlError::UnsupportedAlgorithm(uri.to_string())),
+    }
+}
+
+fn digest_method(element: &Element) -> Result<DigestMethod, XmlError> {
+    match algorithm(element)? {
+        SHA256 => Ok(DigestMethod::Sha256),
+        SHA1 => Ok(DigestMethod::Sha1),
+        other => Err(XmlError::UnsupportedAlgorithm(other.to_string())),
+    }
+}
+
+/// The base64 text of an element, which may be wrapped over several lines.
+fn base64(element: &Element) -> Result<Vec<u8>, XmlError> {
+    let text: String = element
+        .text()
+        .chars()
+        .filter(|c| !c.is_ascii_whitespace())
+        .collect();
+    STANDARD
+        .decode(text)
+        .map_err(|_| XmlError::Malformed("invalid base64"))
+}
+
+/// The RSA key of a DER X.509 certificate.
+fn certificate_key(certificate: &[u8]) -> Result<RsaPublicKey, XmlError> {
+    let spki = certificate_spki_der(certificate).map_err(XmlError::Certificate)?;
+    RsaPublicKey::from_public_key_der(spki).map_err(|_| XmlError::InvalidPublicKey)
+}
+
+/// The RSA key that signed: from the first X509Certificate of KeyInfo, which is returned too,
+/// or else from an RSAKeyValue.
+fn signer_key(signature: &Element) -> Result<(RsaPublicKey, Option<Vec<u8>>), XmlError> {
+    let key_info = signature
+        .child(DSIG, "KeyInfo")
+        .ok_or(XmlError::Malformed("KeyInfo not found"))?;
+    let certificate = key_info
+        .elements()
+        .filter(|element| element.is(DSIG, "X509Data"))
+        .find_map(|data| data.child(DSIG, "X509Certificate"));
+    if let Some(certificate) = certificate {
+        let certificate = base64(certificate)?;
+        return Ok((certificate_key(&certificate)?, Some(certificate)));
+    }
+    let rsa_key = key_info
+        .child(DSIG, "KeyValue")
+        .and_then(|value| value.child(DSIG, "RSAKeyValue"))
+        .ok_or(XmlError::Malformed(
+            "KeyInfo has neither an X509Certificate nor an RSAKeyValue",
+        ))?;
+    let component = |name: &'static str| {
+        rsa_key
+            .child(DSIG, name)
+            .ok_or(XmlError::Malformed("RSAKeyValue is incomplete"))
+            .and_then(base64)
+            .map(|bytes| BigUint::from_bytes_be(&bytes))
+    };
+    let key = RsaPublicKey::new(component("Modulus")?, component("Exponent")?)
+        .map_err(|_| XmlError::InvalidPublicKey)?;
+    Ok((key, None))
+}
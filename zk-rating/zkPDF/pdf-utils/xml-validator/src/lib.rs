@@ -0,0 +1,197 @@
+//! XML-DSig verification for zkPDF.
+//!
+//! Many Indian credentials are issued as signed XML rather than PDF, such as the certificates
+//! DigiLocker issuers push. This crate parses such a document, checks its enveloped
+//! signature (Canonical XML 1.0 or Exclusive XML Canonicalization, RSA with SHA-256 or
+//! SHA-1), and reads attribute values out of the signed content.
+//!
+//! ```no_run
+//! let xml = std::fs::read("certificate.xml").unwrap();
+//! let verified = zkpdf_xml_validator::verify_xml(&xml).unwrap();
+//! assert!(verified.signature.is_valid);
+//! let name = verified.attribute("Certificate/IssuedTo/Person@name");
+//! ```
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+mod c14n;
+mod dsig;
+mod parse;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use signature_validator::types::{Pkcs7Error, SignatureAlgorithm, SignerDetails};
+
+pub use c14n::Canonicalization;
+pub use dsig::DSIG;
+pub use parse::{parse, Attribute, Document, Element, Node, ProcessingInstruction};
+
+#[derive(Debug)]
+pub enum XmlError {
+    /// The document is not well-formed, or uses a feature the parser does not support. The
+    /// offset is into the document with its line endings normalized.
+    Parse {
+        offset: usize,
+        message: &'static str,
+    },
+    /// The document has no XML-DSig Signature element.
+    NotSigned,
+    /// The Signature element lacks a part, or a part is invalid.
+    Malformed(&'static str),
+    /// A canonicalization, transform, digest or signature algorithm this crate does not
+    /// implement, by its identifier.
+    UnsupportedAlgorithm(String),
+    /// A Reference URI that names no element in the document.
+    ReferenceNotFound(String),
+    /// The content a Reference covers does not hash to its DigestValue: the document was
+    /// modified after signing.
+    DigestMismatch { uri: String },
+    /// The KeyInfo certificate, or the one given to `verify_xml_with_certificate`, could not be
+    /// parsed.
+    Certificate(Pkcs7Error),
+    /// The signer's key is not a valid RSA key.
+    InvalidPublicKey,
+}
+
+impl fmt::Display for XmlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            XmlError::Parse { offset, message } => {
+                write!(f, "XML parse error at byte {}: {}", offset, message)
+            }
+            XmlError::NotSigned => write!(f, "No XML signature found"),
+            XmlError::Malformed(msg) => write!(f, "Malformed XML signature: {}", msg),
+            XmlError::UnsupportedAlgorithm(uri) => write!(f, "Unsupported algorithm: {}", uri),
+            XmlError::ReferenceNotFound(uri) => write!(f, "Reference `{}` not found", uri),
+            XmlError::DigestMismatch { uri } => {
+                write!(f, "Digest of reference `{}` does not match", uri)
+            }
+            XmlError::Certificate(e) => write!(f, "Invalid signer certificate: {}", e),
+            XmlError::InvalidPublicKey => write!(f, "Invalid signer public key"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for XmlError {}
+
+/// The outcome of checking an XML signature. Every Reference digest matched; `is_valid` says
+/// whether the RSA signature over them verifies too.
+#[derive(Debug, Clone)]
+pub struct XmlSignatureResult {
+    pub is_valid: bool,
+    pub signature_algorithm: SignatureAlgorithm,
+    /// The digest of the canonical SignedInfo, which the signature value signs and which
+    /// commits to every Reference digest. The counterpart of
+    /// `PdfSignatureResult::message_digest`.
+    pub message_digest: Vec<u8>,
+    /// The signer's RSA key as PKCS#1 DER, like `PdfSignatureResult::public_key`.
+    pub public_key: Vec<u8>,
+    /// The certificate the key came from, given or from KeyInfo; `None` for an RSAKeyValue.
+    pub signer: Option<SignerDetails>,
+    /// The URI of each Reference, in order; `""` is the whole document.
+    pub references: Vec<String>,
+}
+
+/// A document together with the outcome of checking its signature, for reading values out of
+/// the signed content.
+#[derive(Debug, Clone)]
+pub struct VerifiedXml {
+    pub document: Document,
+    pub signature: XmlSignatureResult,
+    /// The subtrees the References cover, `None` for the whole document.
+    covered: Vec<Option<Vec<usize>>>,
+    /// The Signature element, which is never signed content.
+    signature_path: Vec<usize>,
+}
+
+impl VerifiedXml {
+    /// The value of the attribute `selector` names, looked up in the signed content only.
+    /// A selector is a `/`-separated path of element local names from the document element,
+    /// then `@` and the attribute name, e.g. `Certificate/IssuedTo/Person@name`. The first
+    /// matching element in document order that has the attribute is used.
+    pub fn attribute(&self, selector: &str) -> Option<&str> {
+        let (path, attribute) = selector.rsplit_once('@')?;
+        let mut names = path.split('/');
+        if names.next()? != self.document.root.local_name() {
+            return None;
+        }
+        let names: Vec<&str> = names.collect();
+        self.lookup(&self.document.root, &names, attribute, &mut Vec::new())
+    }
+
+    fn lookup<'a>(
+        &self,
+        element: &'a Element,
+        names: &[&str],
+        attribute: &str,
+        path: &mut Vec<usize>,
+    ) -> Option<&'a str> {
+        let Some((name, rest)) = names.split_first() else {
+            return element
+                .attribute(attribute)
+                .filter(|_| self.is_signed(path));
+        };
+        for (index, child) in element.children.iter().enumerate() {
+            let Node::Element(child) = child else {
+                continue;
+            };
+            if child.local_name() != *name {
+                continue;
+            }
+            path.push(index);
+            let found = self.lookup(child, rest, attribute, path);
+            path.pop();
+            if found.is_some() {
+                return found;
+            }
+        }
+        None
+    }
+
+    /// Whether the element at `path` is part of what a Reference covers.
+    fn is_signed(&self, path: &[usize]) -> bool {
+        !path.starts_with(&self.signature_path)
+            && self
+                .covered
+                .iter()
+                .any(|apex| apex.as_ref().is_none_or(|apex| path.starts_with(apex)))
+    }
+}
+
+/// Parses a document and checks its first XML-DSig Signature element. A signature that
+/// does not verify is reported through `signature.is_valid`; a modified reference fails with
+/// `XmlError::DigestMismatch`.
+pub fn verify_xml(xml_bytes: &[u8]) -> Result<VerifiedXml, XmlError> {
+    verify(xml_bytes, None)
+}
+
+/// `verify_xml` for signatures without KeyInfo, such as UIDAI's offline e-KYC, which are
+/// checked against the key of the issuer's published certificate (DER). KeyInfo, if present,
+/// is ignored, and `signature.signer` describes `certificate`.
+pub fn verify_xml_with_certificate(
+    xml_bytes: &[u8],
+    certificate: &[u8],
+) -> Result<VerifiedXml, XmlError> {
+    verify(xml_bytes, Some(certificate))
+}
+
+fn verify(xml_bytes: &[u8], certificate: Option<&[u8]>) -> Result<VerifiedXml, XmlError> {
+    let document = parse(xml_bytes)?;
+    let (signature, coverage) = dsig::verify(&document, certificate)?;
+    Ok(VerifiedXml {
+        document,
+        signature,
+        covered: coverage.references,
+        signature_path: coverage.signature,
+    })
+}
+
+/// `verify_xml` without the document.
+pub fn verify_xml_signature(xml_bytes: &[u8]) -> Result<XmlSignatureResult, XmlError> {
+    verify_xml(xml_bytes).map(|verified| verified.signature)
+}
@@ -0,0 +1,462 @@
+//! A small non-validating XML parser: elements, attributes, text and processing instructions,
+//! with namespace prefixes resolved. Comments are dropped. Documents with a DOCTYPE are
+//! rejected, since a DTD can default attributes and define entities that the signer saw and
+//! this parser would not.
+
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::XmlError;
+
+/// The namespace the `xml` prefix is bound to.
+pub const XML_NAMESPACE: &str = "http://www.w3.org/XML/1998/namespace";
+
+/// A parsed document: the document element, and the processing instructions around it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Document {
+    pub root: Element,
+    pub prolog: Vec<ProcessingInstruction>,
+    pub epilog: Vec<ProcessingInstruction>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Element {
+    /// The qualified name as written, e.g. `ds:Signature`.
+    pub name: String,
+    /// The namespace the name resolves to; empty for none.
+    pub namespace: String,
+    /// In document order, namespace declarations included.
+    pub attributes: Vec<Attribute>,
+    pub children: Vec<Node>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attribute {
+    /// The qualified name as written, e.g. `xml:lang` or `xmlns:ds`.
+    pub name: String,
+    /// The namespace of a prefixed name; empty for unprefixed names and declarations.
+    pub namespace: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcessingInstruction {
+    pub target: String,
+    pub data: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Node {
+    Element(Element),
+    /// Character data, with references replaced and CDATA sections merged in.
+    Text(String),
+    ProcessingInstruction(ProcessingInstruction),
+}
+
+impl Element {
+    /// The name without its prefix.
+    pub fn local_name(&self) -> &str {
+        local_name(&self.name)
+    }
+
+    /// Whether the element is `local_name` in `namespace`.
+    pub fn is(&self, namespace: &str, local_name: &str) -> bool {
+        self.namespace == namespace && self.local_name() == local_name
+    }
+
+    /// The value of the attribute written as `name`.
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|attribute| attribute.name == name)
+            .map(|attribute| attribute.value.as_str())
+    }
+
+    /// The child elements, in document order.
+    pub fn elements(&self) -> impl Iterator<Item = &Element> {
+        self.children.iter().filter_map(|node| match node {
+            Node::Element(element) => Some(element),
+            _ => None,
+        })
+    }
+
+    /// The first child element that is `local_name` in `namespace`.
+    pub fn child(&self, namespace: &str, local_name: &str) -> Option<&Element> {
+        self.elements()
+            .find(|element| element.is(namespace, local_name))
+    }
+
+    /// The text of the element's own text children, concatenated.
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+        for node in &self.children {
+            if let Node::Text(data) = node {
+                text.push_str(data);
+            }
+        }
+        text
+    }
+
+    /// The namespace declarations on this element as `(prefix, namespace)` pairs, with an
+    /// empty prefix for the default namespace.
+    pub fn namespace_declarations(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.attributes.iter().filter_map(|attribute| {
+            let prefix = match attribute.name.strip_prefix("xmlns") {
+                Some("") => "",
+                Some(rest) => rest.strip_prefix(':')?,
+                None => return None,
+            };
+            Some((prefix, attribute.value.as_str()))
+        })
+    }
+
+    /// The element at `path`, a list of indices into `children` from this element.
+    pub(crate) fn at(&self, path: &[usize]) -> Option<&Element> {
+        let mut element = self;
+        for &index in path {
+            element = match element.children.get(index)? {
+                Node::Element(child) => child,
+                _ => return None,
+            };
+        }
+        Some(element)
+    }
+}
+
+pub(crate) fn local_name(name: &str) -> &str {
+    name.split_once(':').map_or(name, |(_, local)| local)
+}
+
+/// The prefix of a qualified name; empty for none.
+pub(crate) fn prefix(name: &str) -> &str {
+    name.split_once(':').map_or("", |(prefix, _)| prefix)
+}
+
+/// Parses a UTF-8 document. A byte order mark and an XML declaration are accepted; the
+/// declaration may only name UTF-8 as the encoding.
+pub fn parse(xml: &[u8]) -> Result<Document, XmlError> {
+    let text = core::str::from_utf8(xml).map_err(|e| XmlError::Parse {
+        offset: e.valid_up_to(),
+        message: "document is not UTF-8",
+    })?;
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    // End-of-line handling (XML 1.0, section 2.11); offsets below are into this text
+    let text = text.replace("\r\n", "\n").replace('\r', "\n");
+    Parser {
+        src: &text,
+        pos: 0,
+        namespaces: vec![("xml".to_string(), XML_NAMESPACE.to_string())],
+    }
+    .document()
+}
+
+struct Parser<'a> {
+    src: &'a str,
+    pos: usize,
+    /// Declarations in scope, innermost last.
+    namespaces: Vec<(String, String)>,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self, message: &'static str) -> XmlError {
+        XmlError::Parse {
+            offset: self.pos,
+            message,
+        }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.src[self.pos..]
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        let found = self.rest().starts_with(token);
+        if found {
+            self.pos += token.len();
+        }
+        found
+    }
+
+    fn expect(&mut self, token: &str, message: &'static str) -> Result<(), XmlError> {
+        if self.eat(token) {
+            Ok(())
+        } else {
+            Err(self.error(message))
+        }
+    }
+
+    fn skip_whitespace(&mut self) -> bool {
+        let rest = self.rest();
+        let trimmed = rest.trim_start_matches([' ', '\t', '\n']);
+        self.pos += rest.len() - trimmed.len();
+        trimmed.len() < rest.len()
+    }
+
+    /// The text up to `end`, moving past `end`.
+    fn until(&mut self, end: &str, message: &'static str) -> Result<&'a str, XmlError> {
+        let Some(len) = self.rest().find(end) else {
+            return Err(self.error(message));
+        };
+        let text = &self.rest()[..len];
+        self.pos += len + end.len();
+        Ok(text)
+    }
+
+    fn name(&mut self) -> Result<&'a str, XmlError> {
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| c.is_ascii_whitespace() || "/>=?<\"'".contains(c))
+            .unwrap_or(rest.len());
+        if len == 0 {
+            return Err(self.error("expected a name"));
+        }
+        self.pos += len;
+        Ok(&rest[..len])
+    }
+
+    fn resolve(&self, prefix: &str) -> Option<&str> {
+        self.namespaces
+            .iter()
+            .rev()
+            .find(|(declared, _)| declared == prefix)
+            .map(|(_, namespace)| namespace.as_str())
+    }
+
+    fn document(mut self) -> Result<Document, XmlError> {
+        if self.rest().starts_with("<?xml")
+            && self.rest()[5..].starts_with(|c: char| c.is_ascii_whitespace())
+        {
+            let declaration = self.until("?>", "unterminated XML declaration")?;
+            if let Some((_, encoding)) = declaration.split_once("encoding") {
+                let encoding = encoding
+                    .trim_start_matches([' ', '\t', '\n', '='])
+                    .trim_start_matches(['"', '\''])
+                    .split(['"', '\''])
+                    .next()
+                    .unwrap_or("");
+                if !encoding.eq_ignore_ascii_case("utf-8") {
+                    return Err(self.error("only UTF-8 documents are supported"));
+                }
+            }
+        }
+        let prolog = self.misc()?;
+        if self.rest().starts_with("<!DOCTYPE") {
+            return Err(self.error("DOCTYPE declarations are not supported"));
+        }
+        if !self.rest().starts_with('<') {
+            return Err(self.error("expected the document element"));
+        }
+        let root = self.element()?;
+        let epilog = self.misc()?;
+        if !self.rest().is_empty() {
+            return Err(self.error("content after the document element"));
+        }
+        Ok(Document {
+            root,
+            prolog,
+            epilog,
+        })
+    }
+
+    /// Whitespace, comments and processing instructions outside the document element.
+    fn misc(&mut self) -> Result<Vec<ProcessingInstruction>, XmlError> {
+        let mut instructions = Vec::new();
+        loop {
+            self.skip_whitespace();
+            if self.eat("<!--") {
+                self.until("-->", "unterminated comment")?;
+            } else if self.rest().starts_with("<?") {
+                instructions.push(self.processing_instruction()?);
+            } else {
+                return Ok(instructions);
+            }
+        }
+    }
+
+    fn processing_instruction(&mut self) -> Result<ProcessingInstruction, XmlError> {
+        self.expect("<?", "expected a processing instruction")?;
+        let target = self.name()?;
+        if target.eq_ignore_ascii_case("xml") {
+            return Err(self.error("misplaced XML declaration"));
+        }
+        self.skip_whitespace();
+        let data = self.until("?>", "unterminated processing instruction")?;
+        Ok(ProcessingInstruction {
+            target: target.to_string(),
+            data: data.to_string(),
+        })
+    }
+
+    fn element(&mut self) -> Result<Element, XmlError> {
+        self.expect("<", "expected an element")?;
+        let name = self.name()?;
+        let mut attributes: Vec<Attribute> = Vec::new();
+        loop {
+            let spaced = self.skip_whitespace();
+            if self.rest().starts_with("/>") || self.rest().starts_with('>') {
+                break;
+            }
+            if !spaced {
+                return Err(self.error("expected whitespace before an attribute"));
+            }
+            let attribute = self.name()?;
+            self.skip_whitespace();
+            self.expect("=", "expected `=` after an attribute name")?;
+            self.skip_whitespace();
+            let quote = if self.eat("\"") {
+                "\""
+            } else if self.eat("'") {
+                "'"
+            } else {
+                return Err(self.error("expected a quoted attribute value"));
+            };
+            let start = self.pos;
+            let raw = self.until(quote, "unterminated attribute value")?;
+            if raw.contains('<') {
+                return Err(XmlError::Parse {
+                    offset: start,
+                    message: "`<` in an attribute value",
+                });
+            }
+            if attributes.iter().any(|a| a.name == attribute) {
+                return Err(self.error("duplicate attribute"));
+            }
+            // Attribute-value normalization (XML 1.0, section 3.3.3), before references are
+            // replaced so that `&#xA;` survives
+            let value = decode_references(&raw.replace(['\t', '\n'], " "), start)?;
+            attributes.push(Attribute {
+                name: attribute.to_string(),
+                namespace: String::new(),
+                value,
+            });
+        }
+
+        let scope = self.namespaces.len();
+        for attribute in &attributes {
+            let declared = match attribute.name.strip_prefix("xmlns") {
+                Some("") => "",
+                Some(rest) => match rest.strip_prefix(':') {
+                    Some(declared) if !attribute.value.is_empty() => declared,
+                    Some(_) => return Err(self.error("a prefix cannot be undeclared")),
+                    None => continue,
+                },
+                None => continue,
+            };
+            self.namespaces
+                .push((declared.to_string(), attribute.value.clone()));
+        }
+        let namespace = match prefix(name) {
+            "" => self.resolve("").unwrap_or(""),
+            prefix => self
+                .resolve(prefix)
+                .ok_or_else(|| self.error("undeclared namespace prefix"))?,
+        }
+        .to_string();
+        for attribute in attributes.iter_mut() {
+            let prefix = prefix(&attribute.name);
+            if prefix.is_empty() || prefix == "xmlns" {
+                continue;
+            }
+            attribute.namespace = self
+                .resolve(prefix)
+                .ok_or_else(|| self.error("undeclared namespace prefix"))?
+                .to_string();
+        }
+
+        let mut children = Vec::new();
+        if !self.eat("/>") {
+            self.expect(">", "expected `>`")?;
+            self.content(name, &mut children)?;
+        }
+        self.namespaces.truncate(scope);
+        Ok(Element {
+            name: name.to_string(),
+            namespace,
+            attributes,
+            children,
+        })
+    }
+
+    /// Element content up to and including the end tag of `name`.
+    fn content(&mut self, name: &str, children: &mut Vec<Node>) -> Result<(), XmlError> {
+        loop {
+            if self.eat("</") {
+                if self.name()? != name {
+                    return Err(self.error("mismatched end tag"));
+                }
+                self.skip_whitespace();
+                return self.expect(">", "expected `>`");
+            } else if self.eat("<!--") {
+                self.until("-->", "unterminated comment")?;
+            } else if self.eat("<![CDATA[") {
+                let data = self.until("]]>", "unterminated CDATA section")?;
+                push_text(children, data);
+            } else if self.rest().starts_with("<?") {
+                let instruction = self.processing_instruction()?;
+                children.push(Node::ProcessingInstruction(instruction));
+            } else if self.rest().starts_with("<!") {
+                return Err(self.error("unexpected markup declaration"));
+            } else if self.rest().starts_with('<') {
+                children.push(Node::Element(self.element()?));
+            } else if self.rest().is_empty() {
+                return Err(self.error("unclosed element"));
+            } else {
+                let start = self.pos;
+                let len = self.rest().find('<').unwrap_or(self.rest().len());
+                self.pos += len;
+                let data = decode_references(&self.src[start..start + len], start)?;
+                push_text(children, &data);
+            }
+        }
+    }
+}
+
+/// Appends text, merging it into a preceding text node.
+fn push_text(children: &mut Vec<Node>, data: &str) {
+    match children.last_mut() {
+        Some(Node::Text(text)) => text.push_str(data),
+        _ => children.push(Node::Text(data.to_string())),
+    }
+}
+
+/// Replaces the predefined entity and character references. `offset` locates `raw` in the
+/// document, for errors.
+fn decode_references(raw: &str, offset: usize) -> Result<String, XmlError> {
+    let mut decoded = String::with_capacity(raw.len());
+    let mut rest = raw;
+    while let Some(amp) = rest.find('&') {
+        decoded.push_str(&rest[..amp]);
+        let error = |message| XmlError::Parse {
+            offset: offset + raw.len() - rest.len() + amp,
+            message,
+        };
+        let Some(end) = rest[amp..].find(';') else {
+            return Err(error("unterminated reference"));
+        };
+        let reference = &rest[amp + 1..amp + end];
+        let c = match reference {
+            "lt" => '<',
+            "gt" => '>',
+            "amp" => '&',
+            "quot" => '"',
+            "apos" => '\'',
+            _ => {
+                let code = if let Some(hex) = reference.strip_prefix("#x") {
+                    u32::from_str_radix(hex, 16).ok()
+                } else if let Some(decimal) = reference.strip_prefix('#') {
+                    decimal.parse().ok()
+                } else {
+                    return Err(error("unknown entity reference"));
+                };
+                code.and_then(char::from_u32)
+                    .filter(|&c| c != '\0')
+                    .ok_or_else(|| error("invalid character reference"))?
+            }
+        };
+        decoded.push(c);
+        rest = &rest[amp + end + 1..];
+    }
+    decoded.push_str(rest);
+    Ok(decoded)
+}
@@ -0,0 +1,290 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use rsa::pkcs1::{DecodeRsaPrivateKey, EncodeRsaPublicKey};
+use rsa::traits::PublicKeyParts;
+use rsa::{Pkcs1v15Sign, RsaPrivateKey};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use signature_validator::types::SignatureAlgorithm;
+use zkpdf_xml_validator::{
+    parse, verify_xml, verify_xml_signature, verify_xml_with_certificate, XmlError,
+};
+
+/// A DigiLocker-style certificate signed outside this crate: canonicalized with `xmllint
+/// --exc-c14n`, signed with `openssl dgst -sha256 -sign` using the writer's test key, with a
+/// self-signed certificate for that key in KeyInfo.
+static SIGNED: &str = include_str!("data/signed_certificate.xml");
+
+/// A UIDAI offline e-KYC document with the same key, signed over Canonical XML 1.0 without
+/// KeyInfo, as UIDAI signs; its key is in a separate certificate.
+static OFFLINE_EKYC: &str = include_str!("data/offline_ekyc.xml");
+static CERTIFICATE: &[u8] = include_bytes!("data/uidai_test_certificate.der");
+
+static KEY: &[u8] = include_bytes!("../../writer/keys/test-rsa-2048.der");
+
+fn key() -> RsaPrivateKey {
+    RsaPrivateKey::from_pkcs1_der(KEY).unwrap()
+}
+
+#[test]
+fn verifies_an_externally_signed_certificate() {
+    let verified = verify_xml(SIGNED.as_bytes()).unwrap();
+    let signature = &verified.signature;
+    assert!(signature.is_valid);
+    assert_eq!(
+        signature.signature_algorithm,
+        SignatureAlgorithm::Sha256WithRsaEncryption
+    );
+    assert_eq!(signature.references, [""]);
+    assert_eq!(signature.message_digest.len(), 32);
+    assert_eq!(
+        signature.public_key,
+        key().to_public_key().to_pkcs1_der().unwrap().as_bytes()
+    );
+    let signer = signature.signer.as_ref().unwrap();
+    assert_eq!(
+        signer.subject,
+        "C=IN, O=Example Board of Secondary Education, CN=Example Board Document Signer"
+    );
+    assert_eq!(signer.serial_number, [0x5a, 0x17]);
+    assert_eq!(signer.not_before, "2025-01-01T00:00:00Z");
+    assert_eq!(signer.key_size, 2048);
+
+    assert_eq!(
+        verified.attribute("Certificate/IssuedTo/Person@name"),
+        Some("Asha Kumari")
+    );
+    assert_eq!(
+        verified.attribute("Certificate/IssuedTo/Person@motherName"),
+        Some("Rekha & Devi")
+    );
+    assert_eq!(
+        verified.attribute("Certificate/IssuedTo/Person@dl:uid"),
+        Some("XXXXXXXX1234")
+    );
+    assert_eq!(
+        verified.attribute("Certificate@number"),
+        Some("EBSE/2024/0042")
+    );
+    assert_eq!(
+        verified.attribute("Certificate/IssuedTo/Person@email"),
+        None
+    );
+    assert_eq!(verified.attribute("Diploma/IssuedTo/Person@name"), None);
+    // The signature itself is not signed content
+    assert_eq!(
+        verified.attribute("Certificate/Signature/SignedInfo/Reference@URI"),
+        None
+    );
+}
+
+#[test]
+fn modified_content_fails_its_digest() {
+    for (from, to) in [
+        ("Asha Kumari", "Asha Kumary"),
+        ("percentage=\"91.2\"", "percentage=\"99.2\""),
+        ("<Note>", "<Note>Not "),
+    ] {
+        let tampered = SIGNED.replacen(from, to, 1);
+        assert!(
+            matches!(
+                verify_xml_signature(tampered.as_bytes()),
+                Err(XmlError::DigestMismatch { ref uri }) if uri.is_empty()
+            ),
+            "{} -> {}",
+            from,
+            to
+        );
+    }
+}
+
+#[test]
+fn canonically_equivalent_documents_still_verify() {
+    let reformatted = SIGNED
+        .replacen(
+            "dob='02-03-2008' gender=\"F\"",
+            "gender='F' dob=\"02-03-2008\"",
+            1,
+        )
+        .replacen(
+            "year=\"2024\" month=\"March\"/>",
+            "year=\"2024\" month=\"March\" ></Examination>",
+            1,
+        )
+        .replacen("<IssuedTo>", "<IssuedTo><!-- holder -->", 1)
+        .replace('\n', "\r\n");
+    assert!(
+        verify_xml_signature(reformatted.as_bytes())
+            .unwrap()
+            .is_valid
+    );
+}
+
+#[test]
+fn a_broken_signature_value_is_invalid() {
+    let start = SIGNED.find("<SignatureValue>").unwrap() + "<SignatureValue>\n".len();
+    let mut tampered = SIGNED.to_string();
+    let flipped = if &SIGNED[start..start + 1] == "A" {
+        "B"
+    } else {
+        "A"
+    };
+    tampered.replace_range(start..start + 1, flipped);
+    let signature = verify_xml_signature(tampered.as_bytes()).unwrap();
+    assert!(!signature.is_valid);
+}
+
+#[test]
+fn verifies_with_a_published_certificate() {
+    assert!(matches!(
+        verify_xml(OFFLINE_EKYC.as_bytes()),
+        Err(XmlError::Malformed("KeyInfo not found"))
+    ));
+
+    let verified = verify_xml_with_certificate(OFFLINE_EKYC.as_bytes(), CERTIFICATE).unwrap();
+    assert!(verified.signature.is_valid);
+    assert_eq!(
+        verified.signature.public_key,
+        key().to_public_key().to_pkcs1_der().unwrap().as_bytes()
+    );
+    assert_eq!(
+        verified.signature.signer.as_ref().unwrap().serial_number,
+        [0x5a, 0x17]
+    );
+    assert_eq!(
+        verified.attribute("OfflinePaperlessKyc/UidData/Poa@pc"),
+        Some("411005")
+    );
+    assert!(matches!(
+        verify_xml_with_certificate(OFFLINE_EKYC.as_bytes(), &CERTIFICATE[..100]),
+        Err(XmlError::Certificate(_))
+    ));
+}
+
+#[test]
+fn unsigned_and_unsupported_documents_are_rejected() {
+    let unsigned = "<Certificate number=\"1\"><IssuedTo/></Certificate>";
+    assert!(matches!(
+        verify_xml(unsigned.as_bytes()),
+        Err(XmlError::NotSigned)
+    ));
+
+    let doctype = format!(
+        "<?xml version=\"1.0\"?>\n<!DOCTYPE Certificate [<!ENTITY name \"Asha\">]>\n{}",
+        unsigned
+    );
+    assert!(matches!(
+        parse(doctype.as_bytes()),
+        Err(XmlError::Parse { .. })
+    ));
+    for malformed in [
+        "<a><b></a>",
+        "<a x=\"1\" x=\"2\"/>",
+        "<p:a/>",
+        "<a>&nbsp;</a>",
+        "<a/><b/>",
+    ] {
+        assert!(
+            matches!(parse(malformed.as_bytes()), Err(XmlError::Parse { .. })),
+            "{}",
+            malformed
+        );
+    }
+
+    let sha512 = SIGNED.replacen(
+        "http://www.w3.org/2001/04/xmlenc#sha256",
+        "http://www.w3.org/2001/04/xmlenc#sha512",
+        1,
+    );
+    assert!(matches!(
+        verify_xml(sha512.as_bytes()),
+        Err(XmlError::UnsupportedAlgorithm(uri)) if uri.ends_with("#sha512")
+    ));
+}
+
+const DSIG: &str = "http://www.w3.org/2000/09/xmldsig#";
+const C14N: &str = "http://www.w3.org/TR/2001/REC-xml-c14n-20010315";
+const RSA_SHA256: &str = "http://www.w3.org/2001/04/xmldsig-more#rsa-sha256";
+const SHA1: &str = "http://www.w3.org/2000/09/xmldsig#sha1";
+
+/// A document whose Reference points at `#payload` with Canonical XML 1.0 and SHA-1, and
+/// whose key is an RSAKeyValue. The canonical forms are written out by hand.
+fn id_referenced(extra: &str) -> String {
+    let payload = "<app:Payload xmlns=\"urn:example:default\" xmlns:app=\"urn:example:app\" \
+                   Id=\"payload\" a=\"1\" b=\"2\"><Value>42</Value></app:Payload>";
+    let digest = STANDARD.encode(Sha1::digest(payload));
+    let signed_info = |namespaces: &str, empty: &dyn Fn(&str, &str) -> String| {
+        format!(
+            "<SignedInfo{namespaces}>{}{}<Reference URI=\"#payload\">{}\
+             <DigestValue>{digest}</DigestValue></Reference></SignedInfo>",
+            empty("CanonicalizationMethod", C14N),
+            empty("SignatureMethod", RSA_SHA256),
+            empty("DigestMethod", SHA1),
+        )
+    };
+    // In context, SignedInfo inherits the document's app prefix and the dsig default namespace
+    let canonical = signed_info(
+        &format!(" xmlns=\"{DSIG}\" xmlns:app=\"urn:example:app\""),
+        &|name, algorithm| format!("<{name} Algorithm=\"{algorithm}\"></{name}>"),
+    );
+    let key = key();
+    let signature = key
+        .sign(
+            Pkcs1v15Sign::new::<Sha256>(),
+            &Sha256::digest(canonical.as_bytes()),
+        )
+        .unwrap();
+    let public_key = key.to_public_key();
+    format!(
+        "<app:Envelope xmlns:app=\"urn:example:app\" xmlns=\"urn:example:default\" version=\"1\">\n\
+         <app:Payload b=\"2\" a='1' Id=\"payload\"><Value>42</Value></app:Payload>{extra}\n\
+         <Signature xmlns=\"{DSIG}\">{}<SignatureValue>{}</SignatureValue><KeyInfo><KeyValue>\
+         <RSAKeyValue><Modulus>{}</Modulus><Exponent>{}</Exponent></RSAKeyValue></KeyValue>\
+         </KeyInfo></Signature>\n</app:Envelope>",
+        signed_info("", &|name, algorithm| format!(
+            "<{name} Algorithm=\"{algorithm}\"/>"
+        )),
+        STANDARD.encode(signature),
+        STANDARD.encode(public_key.n().to_bytes_be()),
+        STANDARD.encode(public_key.e().to_bytes_be()),
+    )
+}
+
+#[test]
+fn id_references_cover_only_their_element() {
+    let verified = verify_xml(id_referenced("").as_bytes()).unwrap();
+    assert!(verified.signature.is_valid);
+    assert_eq!(
+        verified.signature.signature_algorithm,
+        SignatureAlgorithm::Sha256WithRsaEncryption
+    );
+    assert_eq!(verified.signature.references, ["#payload"]);
+    assert!(verified.signature.signer.is_none());
+    assert_eq!(verified.attribute("Envelope/Payload@a"), Some("1"));
+    // The document element is outside the referenced subtree
+    assert_eq!(verified.attribute("Envelope@version"), None);
+
+    // Unsigned siblings are not signed content either, even if they match first
+    let verified = verify_xml(
+        id_referenced("<app:Payload a=\"forged\"/>")
+            .replacen(
+                "<app:Payload b=",
+                "<app:Payload a=\"forged\"/><app:Payload b=",
+                1,
+            )
+            .as_bytes(),
+    )
+    .unwrap();
+    assert!(verified.signature.is_valid);
+    assert_eq!(verified.attribute("Envelope/Payload@a"), Some("1"));
+
+    let duplicated = id_referenced("<app:Payload Id=\"payload\" a=\"9\"/>");
+    assert!(matches!(
+        verify_xml(duplicated.as_bytes()),
+        Err(XmlError::Malformed("duplicate ID"))
+    ));
+    let missing = id_referenced("").replacen("Id=\"payload\"", "Id=\"other\"", 1);
+    assert!(matches!(
+        verify_xml(missing.as_bytes()),
+        Err(XmlError::ReferenceNotFound(uri)) if uri == "#payload"
+    ));
+}